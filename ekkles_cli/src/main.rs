@@ -1,19 +1,31 @@
 use anyhow::{Context, Result, bail};
+use chrono::NaiveDate;
 use clap::{Parser, ValueEnum};
-use ekkles_data::{Song, bible::parse_bible_from_xml};
-use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
-use std::path::PathBuf;
-use tokio::fs::read_to_string;
+use ekkles_data::{
+    Song,
+    bible::parse_bible_from_xml,
+    database::open_database,
+    reports::{Report, ReportPeriod},
+    song_openlyrics::hymn_number_from_openlyrics,
+    song_xml::hymn_number_from_xml,
+    songbook::SongbookNumber,
+};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use tokio::{fs::read_to_string, task::spawn_blocking};
+
+mod seed;
 
-/// Malá utilitka k programu Ekkles, která slouží k importu písní (ve formátu Opensongu)
-/// a biblí (ve formátu z github repozitáře) do databáze Ekklesu.
+/// Malá utilitka k programu Ekkles, která slouží k importu písní (ve formátu Opensongu, OpenLyrics nebo ChordPro)
+/// a biblí (ve formátu z github repozitáře) do databáze Ekklesu, k exportu přehledů
+/// o používání písní do CSV a ke generování ukázkových dat.
 #[derive(Parser, Debug)]
 struct Cli {
-    /// Co se bude parsovat
+    /// Co se bude parsovat (nebo exportovat)
     parse_kind: ParseKind,
     /// Soubor obsahující SQLite3 databázi.
     db_file: PathBuf,
-    /// Vstupní XML soubory bible nebo písní
+    /// Vstupní soubory bible nebo písní
     input_files: Vec<PathBuf>,
     /// Určuje, jak nakládat s biblemi/písněmi, které již v databázi existují.
     /// Ve výchozím nastavení jsou takové vstupy ignorovány (v databázi jsou zachována
@@ -21,14 +33,102 @@ struct Cli {
     /// existující záznamy přepsány.
     #[arg(long, short)]
     overwrite_records: bool,
+    /// Zpěvník, do kterého patří čísla písní (element `hymn_number`), u kterých
+    /// není uveden název zpěvníku (např. pouze "452" místo "EZ 452").
+    /// Bez této volby jsou taková čísla ignorována.
+    #[arg(long, short)]
+    songbook: Option<String>,
+    /// Přehled, který se při `report` vypíše ve formátu CSV na standardní výstup
+    #[arg(long, short, value_enum, default_value_t = ReportKind::SongLibrary)]
+    report: ReportKind,
+    /// První den období hlášení CCLI (RRRR-MM-DD)
+    #[arg(long, required_if_eq("report", "ccli"))]
+    from: Option<NaiveDate>,
+    /// Poslední den období hlášení CCLI (RRRR-MM-DD)
+    #[arg(long, required_if_eq("report", "ccli"))]
+    to: Option<NaiveDate>,
+    /// Počet písní, které se při `seed` vygenerují
+    #[arg(long, default_value_t = 200)]
+    songs: usize,
+    /// Počet playlistů, které se při `seed` vygenerují
+    #[arg(long, default_value_t = 20)]
+    playlists: usize,
+    /// Kolik souborů písní se parsuje najednou (do databáze se písně ukládají postupně).
+    /// Překlady Bible se importují vždy postupně.
+    #[arg(long, short, default_value_t = 1)]
+    jobs: usize,
 }
 
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 enum ParseKind {
     /// Budou se parsovat Bible
     Bible,
-    /// Budou se parsovat písně
+    /// Budou se parsovat písně (XML Opensongu)
     Song,
+    /// Budou se parsovat písně ve formátu ChordPro (`.cho`, `.crd`)
+    Chordpro,
+    /// Budou se parsovat písně ve formátu OpenLyrics (XML OpenLP)
+    Openlyrics,
+    /// Nic se nebude parsovat, na standardní výstup se vypíše přehled `--report`
+    Report,
+    /// Nic se nebude parsovat, do databáze se vygenerují ukázkové písně (`--songs`)
+    /// a playlisty (`--playlists`), viz [`seed`]
+    Seed,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ReportKind {
+    /// Všechny písně (název, autor, CCLI, naposledy použita, počet použití)
+    SongLibrary,
+    /// Pouze použité písně seřazené podle počtu použití
+    SongUsage,
+    /// Jednotlivé záznamy o promítání položek playlistů
+    PresentationLog,
+    /// Hlášení pro CCLI za období `--from` až `--to`
+    Ccli,
+}
+
+impl Cli {
+    /// Sestaví přehled, který se má vypsat, podle `--report` (a případně období)
+    fn report(&self) -> Result<Report> {
+        Ok(match self.report {
+            ReportKind::SongLibrary => Report::SongLibrary,
+            ReportKind::SongUsage => Report::SongUsage,
+            ReportKind::PresentationLog => Report::PresentationLog,
+            ReportKind::Ccli => {
+                let (Some(from), Some(to)) = (self.from, self.to) else {
+                    bail!("Hlášení CCLI vyžaduje období (--from a --to)");
+                };
+                Report::Ccli(ReportPeriod::new(from, to)?)
+            }
+        })
+    }
+}
+
+/// Zparsuje píseň ze souboru `input_file` ve formátu podle `parse_kind` a vytáhne z něj
+/// číslo ve zpěvníku, pokud jej obsahuje. Je blokující, volá se ve vlastním vlákně
+/// (viz `--jobs`).
+fn parse_song_file(input_file: &Path, parse_kind: ParseKind) -> Result<(Song, Option<String>)> {
+    let song = match parse_kind {
+        ParseKind::Chordpro => Song::parse_from_chordpro_file(input_file),
+        ParseKind::Openlyrics => Song::parse_from_openlyrics_file(input_file),
+        _ => Song::parse_from_xml_file(input_file),
+    }?;
+
+    // Soubor už se jednou podařilo přečíst a zparsovat, čísla ve zpěvníku jsou nepovinná
+    // a umíme je vytáhnout pouze z XML (Opensong a OpenLyrics)
+    let xml = if parse_kind != ParseKind::Chordpro {
+        std::fs::read_to_string(input_file).ok()
+    } else {
+        None
+    };
+    let hymn_number = match (xml.as_deref(), parse_kind) {
+        (Some(xml), ParseKind::Openlyrics) => hymn_number_from_openlyrics(xml).ok().flatten(),
+        (Some(xml), _) => hymn_number_from_xml(xml).ok().flatten(),
+        (None, _) => None,
+    };
+
+    Ok((song, hymn_number))
 }
 
 /// Hlavní funkce programu, cyklus postupně načítá všechny soubory specifikované
@@ -37,69 +137,119 @@ enum ParseKind {
 /// ### Přepis existujícího záznamu
 /// Jestli se přepisuje záleží na konfiguraci (viz [`Cli`]).
 async fn run(config: Cli) -> Result<()> {
-    let db_options = SqliteConnectOptions::new()
-        .filename(config.db_file)
-        .optimize_on_close(true, None);
+    // Stejná nastavení připojení (cizí klíče, WAL) jako v Ekklesu, viz `open_database`
+    let db_pool = open_database(&config.db_file).await?;
 
-    let db_pool = SqlitePool::connect_with(db_options)
-        .await
-        .context("Nelze se připojit k databázi")?;
+    if config.parse_kind == ParseKind::Report {
+        let mut conn = db_pool
+            .acquire()
+            .await
+            .context("Nelze získat připojení k databázi z poolu")?;
+        let csv = config.report()?.to_csv(&mut conn).await?;
+        print!("{csv}");
+        return Ok(());
+    }
+
+    if config.parse_kind == ParseKind::Seed {
+        let (songs, playlists) = seed::seed(&db_pool, config.songs, config.playlists).await?;
+        println!("Vygenerováno písní: {songs}, playlistů: {playlists}");
+        return Ok(());
+    }
 
     let total = config.input_files.len();
     let mut successes = 0;
     let mut fails = 0;
     println!("Úspěch + Selhání / Celkem");
-    for input_file in config.input_files {
-        match config.parse_kind {
-            ParseKind::Bible => {
-                let xml = read_to_string(&input_file)
-                    .await
-                    .with_context(|| format!("Nelze přečíst soubor {}", input_file.display()))?;
-                match parse_bible_from_xml(&xml, &db_pool).await {
-                    Ok(_) => successes += 1,
-                    Err(err) => {
-                        eprintln!(
-                            "Nelze zpracovat a uložit soubor {}: {}",
-                            input_file.display(),
-                            err
-                        );
-                        fails += 1;
-                    }
+
+    if config.parse_kind == ParseKind::Bible {
+        // Bible se ukládá do databáze už během parsování (v jediné transakci),
+        // překlady se tedy importují postupně
+        for input_file in &config.input_files {
+            let xml = read_to_string(input_file)
+                .await
+                .with_context(|| format!("Nelze přečíst soubor {}", input_file.display()))?;
+            match parse_bible_from_xml(&xml, &db_pool).await {
+                Ok(_) => successes += 1,
+                Err(err) => {
+                    eprintln!(
+                        "Nelze zpracovat a uložit soubor {}: {}",
+                        input_file.display(),
+                        err
+                    );
+                    fails += 1;
                 }
             }
-            ParseKind::Song => {
-                let res = Song::parse_from_xml_file(&input_file);
-                match res {
-                    Ok(song) => {
-                        if config.overwrite_records
-                            && let Ok(id) = Song::exists_in_db(&song.title, &db_pool).await
-                        {
-                            // Pokud píseň existuje, nejdříve ji vymažeme a uložíme novou
-                            Song::delete_from_db(id, &db_pool).await?;
+            println!("{:04}   + {:04}    / {:04}", successes, fails, total);
+        }
+    } else {
+        // Písně se parsují paralelně (nejvýše `--jobs` souborů najednou), do databáze
+        // se ale zapisují postupně v pořadí vstupních souborů
+        let jobs = config.jobs.max(1);
+        let parse_kind = config.parse_kind;
+        let mut input_files = config.input_files.iter().cloned();
+        let mut pending = VecDeque::with_capacity(jobs);
+
+        loop {
+            while pending.len() < jobs
+                && let Some(input_file) = input_files.next()
+            {
+                let path = input_file.clone();
+                pending.push_back((
+                    input_file,
+                    spawn_blocking(move || parse_song_file(&path, parse_kind)),
+                ));
+            }
+            let Some((input_file, parsing)) = pending.pop_front() else {
+                break;
+            };
+
+            match parsing.await.context("Vlákno parsující píseň selhalo")? {
+                Ok((song, hymn_number)) => {
+                    // Existující píseň se přepíše na místě, zachová si id a zůstane tak
+                    // i v playlistech, které ji obsahují
+                    let saved = if config.overwrite_records {
+                        if Song::exists_in_db(&song.title, &db_pool).await.is_ok() {
                             println!("[INFO]: Přepisuju píseň '{}'", &song.title);
                         }
+                        song.upsert_to_db(&db_pool).await
+                    } else {
+                        song.save_to_db(&db_pool).await
+                    };
 
-                        match song.save_to_db(&db_pool).await {
-                            Ok(_) => successes += 1,
-                            Err(err) => {
-                                eprintln!("[ERROR]: {:?}", err);
-                                fails += 1;
+                    match saved {
+                        Ok(id) => {
+                            successes += 1;
+                            if let Some(hymn_number) = hymn_number {
+                                match SongbookNumber::parse(
+                                    &hymn_number,
+                                    config.songbook.as_deref(),
+                                ) {
+                                    Some(number) => number.save_to_db(id, &db_pool).await?,
+                                    None => eprintln!(
+                                        "[WARN]: Číslo '{}' písně '{}' nelze přiřadit ke zpěvníku, ignoruji jej",
+                                        hymn_number, &song.title
+                                    ),
+                                }
                             }
-                        };
-                    }
-                    Err(err) => {
-                        eprintln!(
-                            "Nelze zparsovat píseň ze souboru {}: {}",
-                            input_file.display(),
-                            err
-                        );
-                        fails += 1;
-                    }
+                        }
+                        Err(err) => {
+                            eprintln!("[ERROR]: {:?}", err);
+                            fails += 1;
+                        }
+                    };
+                }
+                Err(err) => {
+                    eprintln!(
+                        "Nelze zparsovat píseň ze souboru {}: {}",
+                        input_file.display(),
+                        err
+                    );
+                    fails += 1;
                 }
             }
-        }
 
-        println!("{:04}   + {:04}    / {:04}", successes, fails, total);
+            println!("{:04}   + {:04}    / {:04}", successes, fails, total);
+        }
     }
 
     println!("=== HOTOVO ===");
@@ -113,7 +263,11 @@ async fn run(config: Cli) -> Result<()> {
 async fn main() -> Result<()> {
     let config = Cli::parse();
 
-    if config.input_files.is_empty() {
+    if matches!(config.parse_kind, ParseKind::Report | ParseKind::Seed) {
+        if !config.input_files.is_empty() {
+            eprintln!("[WARN]: Při exportu přehledu a generování dat se vstupní soubory ignorují");
+        }
+    } else if config.input_files.is_empty() {
         bail!("Nebyly zadány žádné vstupní soubory k parsování, končím");
     } else if config.overwrite_records && config.parse_kind == ParseKind::Bible {
         eprintln!("[WARN]: Překlady Bible se nemění, volba overwrite, nebude mít žádný efekt");
@@ -0,0 +1,237 @@
+//! Generátor ukázkových dat (`seed`): vymyšlené písně s českým textem, pasáže a playlisty,
+//! aby šlo Ekkles zkoušet (výkon, snímky obrazovky) bez importu skutečného zpěvníku,
+//! na jehož písně se vztahují autorská práva.
+//!
+//! Generátor je deterministický (pevné semínko), opakované spuštění nad prázdnou
+//! databází vytvoří stejná data. Písně a playlisty, jejichž název již v databázi je,
+//! se přeskočí, takže lze seed spustit i opakovaně.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use ekkles_data::{
+    Song,
+    bible::{
+        get_available_translations,
+        indexing::{Book, VerseIndex},
+    },
+    playlist::{self, PlaylistMetadata},
+};
+use sqlx::SqlitePool;
+
+/// Slova, ze kterých se skládají texty písní
+const WORDS: &[&str] = &[
+    "světlo",
+    "milost",
+    "cesta",
+    "srdce",
+    "píseň",
+    "naděje",
+    "pokoj",
+    "radost",
+    "slovo",
+    "nebe",
+    "země",
+    "ruce",
+    "dech",
+    "den",
+    "noc",
+    "chvála",
+    "láska",
+    "věrnost",
+    "skála",
+    "voda",
+    "oheň",
+    "hora",
+    "údolí",
+    "brána",
+    "domov",
+    "král",
+    "pastýř",
+    "jméno",
+    "život",
+    "pravda",
+    "zpívám",
+    "volám",
+    "hledám",
+    "čekám",
+    "věřím",
+    "stojím",
+    "kráčím",
+    "děkuji",
+    "slyším",
+    "dáváš",
+    "vedeš",
+    "neseš",
+    "svatý",
+    "věrný",
+    "dobrý",
+    "mocný",
+    "tichý",
+    "nový",
+    "jasný",
+    "věčný",
+    "každý",
+    "celý",
+    "ráno",
+    "dnes",
+    "znovu",
+    "vždy",
+    "blízko",
+    "daleko",
+    "spolu",
+    "navždy",
+];
+
+/// Vymyšlení autoři písní, u některých písní autor chybí
+const AUTHORS: &[Option<&str>] = &[
+    Some("Jan Ukázkový"),
+    Some("Marie Vzorová"),
+    Some("Petr Zkušební"),
+    Some("Chválová skupina Příklad"),
+    None,
+];
+
+/// Často čtené pasáže (kniha, kapitola, první a poslední verš)
+const PASSAGES: &[(Book, u8, u8, u8)] = &[
+    (Book::John, 3, 16, 18),
+    (Book::Psalms, 23, 1, 6),
+    (Book::Matthew, 5, 3, 12),
+    (Book::Romans, 8, 28, 39),
+    (Book::Corinthians1, 13, 1, 13),
+    (Book::Isaiah, 40, 28, 31),
+];
+
+/// Semínko generátoru, aby byla data při každém spuštění stejná
+const SEED: u64 = 0x454b_4b4c_4553;
+
+/// Jednoduchý pseudonáhodný generátor (xorshift64*), na ukázková data stačí
+struct Generator(u64);
+
+impl Generator {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Náhodné číslo z intervalu `from..=to`
+    fn range(&mut self, from: usize, to: usize) -> usize {
+        from + (self.next() % (to - from + 1) as u64) as usize
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0, items.len() - 1)]
+    }
+
+    /// Řádek o `from` až `to` slovech, začínající velkým písmenem
+    fn line(&mut self, from: usize, to: usize) -> String {
+        let words: Vec<&str> = (0..self.range(from, to))
+            .map(|_| *self.pick(WORDS))
+            .collect();
+        capitalize(&words.join(" "))
+    }
+
+    /// Sloka nebo refrén o 4 řádcích
+    fn stanza(&mut self) -> String {
+        (0..4)
+            .map(|_| self.line(4, 7))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Píseň s `number` v názvu (kvůli jedinečnosti), 2 až 4 slokami a refrénem
+    /// za každou slokou
+    fn song(&mut self, number: usize) -> Song {
+        let mut parts = HashMap::from([(String::from("C"), self.stanza())]);
+        let mut order = Vec::new();
+        for verse in 1..=self.range(2, 4) {
+            let tag = format!("V{verse}");
+            parts.insert(tag.clone(), self.stanza());
+            order.push(tag);
+            order.push(String::from("C"));
+        }
+
+        Song {
+            title: format!("{} (ukázka {number})", self.line(2, 4)),
+            author: self.pick(AUTHORS).map(str::to_string),
+            parts,
+            order,
+            metadata: Default::default(),
+        }
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Vygeneruje do databáze `pool` `songs` písní a `playlists` playlistů. Playlisty obsahují
+/// vygenerované písně, oznámení a pokud je v databázi nějaký překlad Bible, i pasáže z něj.
+/// Vrací počet skutečně vytvořených písní a playlistů (již existující se přeskočí).
+pub async fn seed(pool: &SqlitePool, songs: usize, playlists: usize) -> Result<(usize, usize)> {
+    let mut generator = Generator(SEED);
+
+    let mut created_songs = 0;
+    let mut song_ids = Vec::with_capacity(songs);
+    for number in 1..=songs {
+        let song = generator.song(number);
+        match Song::exists_in_db(&song.title, pool).await {
+            Ok(id) => song_ids.push(id),
+            Err(e) if e.is_not_found() => {
+                song_ids.push(song.save_to_db(pool).await?);
+                created_songs += 1;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let mut conn = pool
+        .acquire()
+        .await
+        .context("Nelze získat připojení k databázi z poolu")?;
+    let translation_id = get_available_translations(&mut conn)
+        .await?
+        .first()
+        .map(|(id, _)| *id);
+
+    let mut created_playlists = 0;
+    for number in 1..=playlists {
+        let name = format!("Ukázková bohoslužba {number}");
+        let name_conn = pool
+            .acquire()
+            .await
+            .context("Nelze získat připojení k databázi z poolu")?;
+        if !playlist::is_name_available(name_conn, &name).await? {
+            continue;
+        }
+
+        let mut playlist = PlaylistMetadata::new(&name);
+        playlist.push_custom_text("Oznámení", &generator.stanza());
+        for _ in 0..generator.range(4, 7) {
+            if !song_ids.is_empty() {
+                playlist.push_song(*generator.pick(&song_ids));
+            }
+        }
+        if let Some(translation_id) = translation_id {
+            let (book, chapter, from, to) = *generator.pick(PASSAGES);
+            if let (Some(from), Some(to)) = (
+                VerseIndex::try_new(book, chapter, from),
+                VerseIndex::try_new(book, chapter, to),
+            ) {
+                let position = generator.range(1, playlist.get_items().len());
+                playlist.add_bible_passage(translation_id, from, to, position);
+            }
+        }
+
+        playlist.save(&mut conn).await?;
+        created_playlists += 1;
+    }
+
+    Ok((created_songs, created_playlists))
+}
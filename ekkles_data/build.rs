@@ -0,0 +1,68 @@
+//! Generuje tabulku verzifikace Bible (počty veršů v kapitolách knih) ze souboru
+//! `data/versification.txt`, viz `src/bible/indexing.rs`.
+
+use std::{env, fs, path::Path};
+
+const VERSIFICATION_PATH: &str = "data/versification.txt";
+const GENERATED_NAME: &str = "versification.rs";
+const NUM_BOOKS: usize = 66;
+
+fn main() {
+    println!("cargo:rerun-if-changed={VERSIFICATION_PATH}");
+    // Makro `sqlx::migrate!` musí vidět nové migrace
+    println!("cargo:rerun-if-changed=migrations");
+
+    let input = fs::read_to_string(VERSIFICATION_PATH)
+        .unwrap_or_else(|e| panic!("Nelze přečíst {VERSIFICATION_PATH}: {e}"));
+
+    let mut books = Vec::with_capacity(NUM_BOOKS);
+    for (line_number, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, counts) = line
+            .split_once(':')
+            .unwrap_or_else(|| panic!("{VERSIFICATION_PATH}:{}: chybí ':'", line_number + 1));
+        let counts: Vec<u8> = counts
+            .split_whitespace()
+            .map(|count| match count.parse() {
+                Ok(count) if count > 0 => count,
+                _ => panic!(
+                    "{VERSIFICATION_PATH}:{}: nevalidní počet veršů '{count}'",
+                    line_number + 1
+                ),
+            })
+            .collect();
+        if counts.is_empty() || counts.len() > usize::from(u8::MAX) {
+            panic!(
+                "{VERSIFICATION_PATH}:{}: kniha {name} má nevalidní počet kapitol",
+                line_number + 1
+            );
+        }
+
+        books.push((name.trim().to_string(), counts));
+    }
+    if books.len() != NUM_BOOKS {
+        panic!(
+            "{VERSIFICATION_PATH} obsahuje {} knih místo {NUM_BOOKS}",
+            books.len()
+        );
+    }
+
+    let mut generated = format!(
+        "/// Počty veršů v kapitolách knih, index je `Book as usize`.\n\
+         /// Vygenerováno z `{VERSIFICATION_PATH}` pomocí `build.rs`, neupravovat ručně.\n\
+         const VERSE_COUNTS: [&[u8]; {NUM_BOOKS}] = [\n"
+    );
+    for (name, counts) in &books {
+        let counts: Vec<String> = counts.iter().map(u8::to_string).collect();
+        generated.push_str(&format!("    // {name}\n    &[{}],\n", counts.join(", ")));
+    }
+    generated.push_str("];\n");
+
+    let out_dir = env::var("OUT_DIR").expect("Cargo nastavuje OUT_DIR");
+    fs::write(Path::new(&out_dir).join(GENERATED_NAME), generated)
+        .unwrap_or_else(|e| panic!("Nelze zapsat {GENERATED_NAME}: {e}"));
+}
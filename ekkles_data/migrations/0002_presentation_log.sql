@@ -0,0 +1,10 @@
+-- Záznam průběhu prezentací, kdy byla která položka playlistu promítána
+
+CREATE TABLE presentation_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    playlist_id INTEGER NOT NULL,
+    part_order INTEGER NOT NULL,
+    started TEXT NOT NULL,
+    ended TEXT NOT NULL,
+    FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+-- Očekávaná délka položky playlistu v sekundách (naměřená při minulé prezentaci),
+-- NULL = neznámá
+
+ALTER TABLE playlist_parts ADD COLUMN expected_duration INTEGER;
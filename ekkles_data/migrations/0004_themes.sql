@@ -0,0 +1,12 @@
+-- Pojmenované motivy slajdů (barva textu a pozadí ve tvaru '#rrggbb'). Motiv playlistu,
+-- pokud je nastaven, přebije preferované motivy jednotlivých písní.
+
+CREATE TABLE themes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    text_color TEXT NOT NULL,
+    background_color TEXT NOT NULL
+);
+
+ALTER TABLE songs ADD COLUMN theme_id INTEGER REFERENCES themes (id) ON DELETE SET NULL;
+ALTER TABLE playlists ADD COLUMN theme_id INTEGER REFERENCES themes (id) ON DELETE SET NULL;
@@ -0,0 +1,13 @@
+-- Úpravy jednotlivých slajdů playlistu (velikost textu, zarovnání, pozadí), NULL = bez úpravy.
+-- Jsou vázané na položku playlistu, při jejím smazání se smažou s ní.
+
+CREATE TABLE slide_overrides (
+    playlist_id INTEGER NOT NULL,
+    part_order INTEGER NOT NULL,
+    slide_index INTEGER NOT NULL, -- Pořadí slajdu v rámci položky
+    text_scale INTEGER CHECK (text_scale BETWEEN 0 AND 255),
+    alignment TEXT CHECK (alignment IN ('left', 'center', 'right')),
+    background_color TEXT,
+    PRIMARY KEY (playlist_id, part_order, slide_index),
+    FOREIGN KEY (playlist_id, part_order) REFERENCES playlist_parts (playlist_id, part_order) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+-- Úložiště médií (obrázky na pozadí slajdů). Ukládá se originál i verze zmenšená
+-- na rozlišení výstupu, která se používá při prezentaci.
+
+CREATE TABLE media (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    original BLOB NOT NULL, -- Importovaný soubor beze změny
+    optimized BLOB NOT NULL, -- Zmenšená verze ve formátu JPEG
+    width INTEGER NOT NULL, -- Rozměry zmenšené verze v pixelech
+    height INTEGER NOT NULL
+);
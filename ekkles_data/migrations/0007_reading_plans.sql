@@ -0,0 +1,24 @@
+-- Plány čtení Bible (např. M'Cheyne, roční plán sboru). Den plánu se počítá od `start_date`,
+-- po posledním dni začíná plán znovu od začátku.
+
+CREATE TABLE reading_plans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    start_date TEXT NOT NULL -- Datum prvního dne plánu ve tvaru 'YYYY-MM-DD'
+);
+
+CREATE TABLE reading_plan_entries (
+    plan_id INTEGER NOT NULL,
+    day INTEGER NOT NULL CHECK (day >= 1),
+    entry_order INTEGER NOT NULL, -- Pořadí čtení v rámci dne
+    start_book_id INTEGER NOT NULL,
+    start_chapter INTEGER NOT NULL,
+    start_number INTEGER NOT NULL,
+    end_book_id INTEGER NOT NULL,
+    end_chapter INTEGER NOT NULL,
+    end_number INTEGER NOT NULL,
+    PRIMARY KEY (plan_id, day, entry_order),
+    FOREIGN KEY (plan_id) REFERENCES reading_plans (id) ON DELETE CASCADE,
+    FOREIGN KEY (start_book_id) REFERENCES books (id),
+    FOREIGN KEY (end_book_id) REFERENCES books (id)
+);
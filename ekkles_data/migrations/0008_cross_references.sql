@@ -0,0 +1,20 @@
+-- Křížové odkazy mezi verši (importované např. z datové sady TSK/OpenBible.info).
+-- Odkaz vede z jednoho verše na pasáž, `votes` určuje relevanci odkazu.
+
+CREATE TABLE cross_references (
+    from_book_id INTEGER NOT NULL,
+    from_chapter INTEGER NOT NULL,
+    from_number INTEGER NOT NULL,
+    start_book_id INTEGER NOT NULL,
+    start_chapter INTEGER NOT NULL,
+    start_number INTEGER NOT NULL,
+    end_book_id INTEGER NOT NULL,
+    end_chapter INTEGER NOT NULL,
+    end_number INTEGER NOT NULL,
+    votes INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (from_book_id) REFERENCES books (id),
+    FOREIGN KEY (start_book_id) REFERENCES books (id),
+    FOREIGN KEY (end_book_id) REFERENCES books (id)
+);
+
+CREATE INDEX cross_references_from ON cross_references (from_book_id, from_chapter, from_number);
@@ -0,0 +1,14 @@
+-- Slova veršů označená Strongovými čísly (pouze u překladů, které je obsahují).
+-- Slouží jen k interlineárnímu zobrazení při přípravě, nikdy se nepromítají.
+
+CREATE TABLE verse_strongs (
+    translation_id INTEGER NOT NULL,
+    book_id INTEGER NOT NULL,
+    chapter INTEGER NOT NULL,
+    number INTEGER NOT NULL,
+    word_order INTEGER NOT NULL, -- Pořadí označeného slova ve verši
+    word TEXT NOT NULL,
+    strongs TEXT NOT NULL, -- Strongova čísla oddělená mezerou, např. 'H7225' nebo 'G2316 G3588'
+    PRIMARY KEY (translation_id, book_id, chapter, number, word_order),
+    FOREIGN KEY (translation_id, book_id, chapter, number) REFERENCES verses (translation_id, book_id, chapter, number)
+);
@@ -0,0 +1,4 @@
+-- Ruční rozdělení pasáže na slajdy. Indexy veršů (od 0), kterými začíná nový slajd,
+-- oddělené čárkou; NULL = automatické rozdělení.
+
+ALTER TABLE playlist_passages ADD COLUMN slide_breaks TEXT;
@@ -0,0 +1,3 @@
+-- Tónina, ve které se píseň na této bohoslužbě hraje, NULL = neurčena
+
+ALTER TABLE playlist_songs ADD COLUMN song_key TEXT;
@@ -0,0 +1,3 @@
+-- Píseň, která je překladem této písně (části se párují podle tagů), NULL = bez překladu
+
+ALTER TABLE songs ADD COLUMN translated_song_id INTEGER REFERENCES songs (id) ON DELETE SET NULL;
@@ -0,0 +1,15 @@
+-- Zpěvníky (např. "EZ" nebo "Hosana"), písně v nich mají svá čísla
+
+CREATE TABLE songbooks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE
+);
+
+CREATE TABLE songbook_entries (
+    songbook_id INTEGER NOT NULL,
+    song_id INTEGER NOT NULL,
+    number TEXT NOT NULL, -- Text, protože čísla mohou mít písmenný suffix (např. "452a")
+    PRIMARY KEY (songbook_id, song_id),
+    FOREIGN KEY (songbook_id) REFERENCES songbooks (id) ON DELETE CASCADE,
+    FOREIGN KEY (song_id) REFERENCES songs (id) ON DELETE CASCADE
+);
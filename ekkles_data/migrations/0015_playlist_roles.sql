@@ -0,0 +1,9 @@
+-- Lidé v rolích (kazatel, vedoucí chval, ...) na bohoslužbě daného playlistu
+
+CREATE TABLE playlist_roles (
+    playlist_id INTEGER NOT NULL,
+    role TEXT NOT NULL,
+    person TEXT NOT NULL,
+    PRIMARY KEY (playlist_id, role),
+    FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Číslo písně v licenční databázi CCLI, NULL = neznámé
+
+ALTER TABLE songs ADD COLUMN ccli_number TEXT;
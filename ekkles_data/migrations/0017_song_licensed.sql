@@ -0,0 +1,4 @@
+-- Zda má sbor k písni licenci, 0 = píseň je ručně označena jako nelicencovaná.
+-- Dosavadní písně se považují za licencované.
+
+ALTER TABLE songs ADD COLUMN licensed INTEGER NOT NULL DEFAULT 1 CHECK (licensed IN (0, 1));
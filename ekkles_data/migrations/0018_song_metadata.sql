@@ -0,0 +1,16 @@
+-- Rozšířená metadata písní (copyright, tónina, capo) a témata pro filtrování písní
+
+-- Copyright písně (např. 1982 Thankyou Music), NULL = neznámý
+ALTER TABLE songs ADD COLUMN copyright TEXT;
+-- Původní tónina písně (např. "G"), NULL = neznámá
+ALTER TABLE songs ADD COLUMN song_key TEXT;
+-- Doporučený capo (pražec) pro kytaru, NULL = bez capa
+ALTER TABLE songs ADD COLUMN capo INTEGER CHECK (capo >= 0);
+
+-- Témata písně (např. "Chvála", "Vánoce")
+CREATE TABLE song_topics (
+    song_id INTEGER NOT NULL,
+    topic TEXT NOT NULL,
+    PRIMARY KEY (song_id, topic),
+    FOREIGN KEY (song_id) REFERENCES songs (id) ON DELETE CASCADE
+);
@@ -0,0 +1,35 @@
+-- Vlastní textové slajdy (oznámení, uvítání, ...) jako položky playlistu
+--
+-- Sloupec `playlist_parts.kind` má integritní omezení s výčtem druhů položek, které SQLite
+-- neumí změnit, tabulku je tedy potřeba přestavět. Její smazání kaskádou smaže i úpravy
+-- slajdů, ty se proto nejdříve odloží stranou a po přestavbě vrátí.
+
+CREATE TEMPORARY TABLE slide_overrides_backup AS SELECT * FROM slide_overrides;
+
+CREATE TABLE playlist_parts_new (
+    playlist_id INTEGER NOT NULL,
+    part_order INTEGER NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('song', 'bible', 'text')),
+    -- Očekávaná délka položky v sekundách (naměřená při minulé prezentaci), NULL = neznámá
+    expected_duration INTEGER,
+    PRIMARY KEY (playlist_id, part_order),
+    FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE CASCADE
+);
+
+INSERT INTO playlist_parts_new (playlist_id, part_order, kind, expected_duration)
+SELECT playlist_id, part_order, kind, expected_duration FROM playlist_parts;
+
+DROP TABLE playlist_parts;
+ALTER TABLE playlist_parts_new RENAME TO playlist_parts;
+
+INSERT INTO slide_overrides SELECT * FROM slide_overrides_backup;
+DROP TABLE slide_overrides_backup;
+
+CREATE TABLE playlist_texts (
+    playlist_id INTEGER NOT NULL,
+    part_order INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    PRIMARY KEY (playlist_id, part_order),
+    FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE CASCADE
+);
@@ -0,0 +1,34 @@
+-- Obrázky (titulní grafika kázání, plakáty oznámení) z úložiště médií jako položky playlistu
+--
+-- Druh položky `image` vyžaduje přestavbu tabulky `playlist_parts` stejně jako
+-- v migraci 0019 (i s odložením úprav slajdů stranou).
+
+CREATE TEMPORARY TABLE slide_overrides_backup AS SELECT * FROM slide_overrides;
+
+CREATE TABLE playlist_parts_new (
+    playlist_id INTEGER NOT NULL,
+    part_order INTEGER NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('song', 'bible', 'text', 'image')),
+    -- Očekávaná délka položky v sekundách (naměřená při minulé prezentaci), NULL = neznámá
+    expected_duration INTEGER,
+    PRIMARY KEY (playlist_id, part_order),
+    FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE CASCADE
+);
+
+INSERT INTO playlist_parts_new (playlist_id, part_order, kind, expected_duration)
+SELECT playlist_id, part_order, kind, expected_duration FROM playlist_parts;
+
+DROP TABLE playlist_parts;
+ALTER TABLE playlist_parts_new RENAME TO playlist_parts;
+
+INSERT INTO slide_overrides SELECT * FROM slide_overrides_backup;
+DROP TABLE slide_overrides_backup;
+
+CREATE TABLE playlist_images (
+    playlist_id INTEGER NOT NULL,
+    part_order INTEGER NOT NULL,
+    media_id INTEGER NOT NULL,
+    PRIMARY KEY (playlist_id, part_order),
+    FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE CASCADE,
+    FOREIGN KEY (media_id) REFERENCES media (id)
+);
@@ -0,0 +1,5 @@
+-- Obrázek na pozadí motivu z úložiště médií (NULL = pouze barva pozadí) a jeho ztmavení
+-- v procentech, aby byl text čitelný
+
+ALTER TABLE themes ADD COLUMN background_media_id INTEGER REFERENCES media (id) ON DELETE SET NULL;
+ALTER TABLE themes ADD COLUMN background_dimming INTEGER NOT NULL DEFAULT 0 CHECK (background_dimming BETWEEN 0 AND 100);
@@ -0,0 +1,3 @@
+-- Číslo světelného cue odeslaného při promítnutí položky (Art-Net), NULL = bez cue
+
+ALTER TABLE playlist_parts ADD COLUMN lighting_cue INTEGER CHECK (lighting_cue BETWEEN 1 AND 255);
@@ -0,0 +1,74 @@
+-- Knihy Bible se místo českého názvu identifikují zkratkou podle standardu OSIS
+-- (Gen, Exod, ... Rev), názvy knih se lokalizují v aplikaci (viz `Book::osis`).
+
+ALTER TABLE books RENAME COLUMN title TO osis_id;
+
+UPDATE books SET osis_id = CASE id
+    WHEN 0 THEN 'Gen'
+    WHEN 1 THEN 'Exod'
+    WHEN 2 THEN 'Lev'
+    WHEN 3 THEN 'Num'
+    WHEN 4 THEN 'Deut'
+    WHEN 5 THEN 'Josh'
+    WHEN 6 THEN 'Judg'
+    WHEN 7 THEN 'Ruth'
+    WHEN 8 THEN '1Sam'
+    WHEN 9 THEN '2Sam'
+    WHEN 10 THEN '1Kgs'
+    WHEN 11 THEN '2Kgs'
+    WHEN 12 THEN '1Chr'
+    WHEN 13 THEN '2Chr'
+    WHEN 14 THEN 'Ezra'
+    WHEN 15 THEN 'Neh'
+    WHEN 16 THEN 'Esth'
+    WHEN 17 THEN 'Job'
+    WHEN 18 THEN 'Ps'
+    WHEN 19 THEN 'Prov'
+    WHEN 20 THEN 'Eccl'
+    WHEN 21 THEN 'Song'
+    WHEN 22 THEN 'Isa'
+    WHEN 23 THEN 'Jer'
+    WHEN 24 THEN 'Lam'
+    WHEN 25 THEN 'Ezek'
+    WHEN 26 THEN 'Dan'
+    WHEN 27 THEN 'Hos'
+    WHEN 28 THEN 'Joel'
+    WHEN 29 THEN 'Amos'
+    WHEN 30 THEN 'Obad'
+    WHEN 31 THEN 'Jonah'
+    WHEN 32 THEN 'Mic'
+    WHEN 33 THEN 'Nah'
+    WHEN 34 THEN 'Hab'
+    WHEN 35 THEN 'Zeph'
+    WHEN 36 THEN 'Hag'
+    WHEN 37 THEN 'Zech'
+    WHEN 38 THEN 'Mal'
+    WHEN 39 THEN 'Matt'
+    WHEN 40 THEN 'Mark'
+    WHEN 41 THEN 'Luke'
+    WHEN 42 THEN 'John'
+    WHEN 43 THEN 'Acts'
+    WHEN 44 THEN 'Rom'
+    WHEN 45 THEN '1Cor'
+    WHEN 46 THEN '2Cor'
+    WHEN 47 THEN 'Gal'
+    WHEN 48 THEN 'Eph'
+    WHEN 49 THEN 'Phil'
+    WHEN 50 THEN 'Col'
+    WHEN 51 THEN '1Thess'
+    WHEN 52 THEN '2Thess'
+    WHEN 53 THEN '1Tim'
+    WHEN 54 THEN '2Tim'
+    WHEN 55 THEN 'Titus'
+    WHEN 56 THEN 'Phlm'
+    WHEN 57 THEN 'Heb'
+    WHEN 58 THEN 'Jas'
+    WHEN 59 THEN '1Pet'
+    WHEN 60 THEN '2Pet'
+    WHEN 61 THEN '1John'
+    WHEN 62 THEN '2John'
+    WHEN 63 THEN '3John'
+    WHEN 64 THEN 'Jude'
+    WHEN 65 THEN 'Rev'
+    ELSE osis_id
+END;
@@ -0,0 +1,12 @@
+-- Nadpisy veršů (např. nadpisy žalmů), u překladů, které je obsahují. Nadpis patří
+-- k verši, před kterým v překladu stojí, do textu verše se nezapočítává.
+
+CREATE TABLE verse_headings (
+    translation_id INTEGER NOT NULL,
+    book_id INTEGER NOT NULL,
+    chapter INTEGER NOT NULL,
+    number INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    PRIMARY KEY (translation_id, book_id, chapter, number),
+    FOREIGN KEY (translation_id, book_id, chapter, number) REFERENCES verses (translation_id, book_id, chapter, number)
+);
@@ -0,0 +1,3 @@
+-- Rozsahy pasáží se načítají podle pořadí veršů (WHERE verse_order BETWEEN ...)
+
+CREATE UNIQUE INDEX verses_order ON verses (translation_id, verse_order);
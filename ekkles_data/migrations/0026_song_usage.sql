@@ -0,0 +1,28 @@
+-- Záznam použití písní pro přehledy a hlášení CCLI, zapisuje se při promítnutí písně
+-- v prezentaci (viz `Song::record_usage`).
+--
+-- Na rozdíl od záznamu průběhu prezentací (`presentation_log`) se odkazuje přímo na píseň,
+-- použití tedy zůstanou zachována i po úpravě nebo smazání playlistu. Položka playlistu
+-- se za den zaznamená nejvýše jednou.
+
+CREATE TABLE song_usage (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    song_id INTEGER NOT NULL,
+    -- Playlist a pořadí položky, ze které byla píseň promítnuta, `NULL` po smazání playlistu
+    playlist_id INTEGER,
+    part_order INTEGER NOT NULL,
+    presented TEXT NOT NULL,
+    FOREIGN KEY (song_id) REFERENCES songs (id) ON DELETE CASCADE,
+    FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE SET NULL
+);
+
+CREATE INDEX song_usage_presented ON song_usage (presented);
+
+-- Dosavadní použití se převezmou ze záznamu průběhu prezentací
+INSERT INTO song_usage (song_id, playlist_id, part_order, presented)
+SELECT playlist_songs.song_id, presentation_log.playlist_id, presentation_log.part_order,
+    MIN(presentation_log.started)
+FROM presentation_log JOIN playlist_songs
+    ON playlist_songs.playlist_id = presentation_log.playlist_id
+    AND playlist_songs.part_order = presentation_log.part_order
+GROUP BY presentation_log.playlist_id, presentation_log.part_order, date(presentation_log.started);
@@ -0,0 +1,5 @@
+-- Vlastní pořadí částí písně pro jednu položku playlistu (např. refrén navíc na konci),
+-- viz `PlaylistMetadata::set_arrangement`. Tagy jsou oddělené mezerou stejně jako
+-- ve sloupci `songs.part_order`, NULL = pořadí písně.
+
+ALTER TABLE playlist_songs ADD COLUMN arrangement TEXT;
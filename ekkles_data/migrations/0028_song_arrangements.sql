@@ -0,0 +1,11 @@
+-- Pojmenovaná pořadí částí písně (např. "Celá", "Krátká", "Akustická"), viz
+-- `ekkles_data::song_arrangement`. Výchozí pořadí zůstává ve sloupci `songs.part_order`,
+-- tagy jsou oddělené mezerou stejně jako tam.
+
+CREATE TABLE song_arrangements (
+    song_id INTEGER NOT NULL,
+    name TEXT NOT NULL COLLATE NOCASE,
+    part_order TEXT NOT NULL,
+    PRIMARY KEY (song_id, name),
+    FOREIGN KEY (song_id) REFERENCES songs (id) ON DELETE CASCADE
+);
@@ -0,0 +1,39 @@
+-- Bloky, tedy playlisty vložené jako položka do jiného playlistu (např. liturgie Večeře Páně),
+-- viz `PlaylistItemMetadata::Block`. Při prezentaci se blok rozbalí na své položky.
+--
+-- Sloupec `playlist_parts.kind` má integritní omezení s výčtem druhů položek, které SQLite
+-- neumí změnit, tabulku je tedy potřeba přestavět. Její smazání kaskádou smaže i úpravy
+-- slajdů, ty se proto nejdříve odloží stranou a po přestavbě vrátí.
+
+CREATE TEMPORARY TABLE slide_overrides_backup AS SELECT * FROM slide_overrides;
+
+CREATE TABLE playlist_parts_new (
+    playlist_id INTEGER NOT NULL,
+    part_order INTEGER NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('song', 'bible', 'text', 'image', 'block')),
+    -- Očekávaná délka položky v sekundách (naměřená při minulé prezentaci), NULL = neznámá
+    expected_duration INTEGER,
+    -- Číslo světelného cue odeslaného při promítnutí položky (Art-Net), NULL = bez cue
+    lighting_cue INTEGER CHECK (lighting_cue BETWEEN 1 AND 255),
+    PRIMARY KEY (playlist_id, part_order),
+    FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE CASCADE
+);
+
+INSERT INTO playlist_parts_new (playlist_id, part_order, kind, expected_duration, lighting_cue)
+SELECT playlist_id, part_order, kind, expected_duration, lighting_cue FROM playlist_parts;
+
+DROP TABLE playlist_parts;
+ALTER TABLE playlist_parts_new RENAME TO playlist_parts;
+
+INSERT INTO slide_overrides SELECT * FROM slide_overrides_backup;
+DROP TABLE slide_overrides_backup;
+
+-- Playlist vložený jako blok nelze smazat, dokud je v nějakém playlistu
+CREATE TABLE playlist_blocks (
+    playlist_id INTEGER NOT NULL,
+    part_order INTEGER NOT NULL,
+    block_id INTEGER NOT NULL,
+    PRIMARY KEY (playlist_id, part_order),
+    FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE CASCADE,
+    FOREIGN KEY (block_id) REFERENCES playlists (id)
+);
@@ -0,0 +1,4 @@
+-- Vypnuté položky playlistu (např. nepovinná píseň) zůstávají v plánu, ale nepromítají se,
+-- viz `PlaylistMetadata::set_item_enabled`. Nové položky jsou zapnuté.
+
+ALTER TABLE playlist_parts ADD COLUMN enabled INTEGER NOT NULL DEFAULT 1 CHECK (enabled IN (0, 1));
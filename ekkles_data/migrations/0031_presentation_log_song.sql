@@ -0,0 +1,12 @@
+-- Píseň promítnuté položky, aby záznam průběhu prezentací zůstal správný i po úpravě
+-- playlistu (změna pořadí, smazání nebo nahrazení položky), `NULL` u ostatních položek
+-- a po smazání písně.
+
+ALTER TABLE presentation_log ADD COLUMN song_id INTEGER REFERENCES songs (id) ON DELETE SET NULL;
+
+-- U dosavadních záznamů lze píseň dohledat pouze podle aktuálního obsahu playlistu
+UPDATE presentation_log SET song_id = (
+    SELECT playlist_songs.song_id FROM playlist_songs
+    WHERE playlist_songs.playlist_id = presentation_log.playlist_id
+        AND playlist_songs.part_order = presentation_log.part_order
+);
@@ -0,0 +1,203 @@
+//! Export celé databáze do složky s čitelnými soubory (archiv). Slouží jako pojistka, aby
+//! data šla obnovit (nebo alespoň přečíst) i bez Ekklesu a bez nástrojů pro práci se SQLite.
+//!
+//! ### Struktura archivu
+//! - `songs/` - písně v XML formátu Opensongu (viz [`Song::to_xml()`]), lze je znovu
+//!   naimportovat pomocí `ekkles_cli song`
+//! - `playlists/` - playlisty jako přenosné balíčky (JSON, viz [`crate::playlist::bundle`])
+//! - `bible/translations.txt` - názvy překladů v databázi, text Bible se neexportuje
+//!   (překlady lze znovu naimportovat ze zdrojových XML)
+//! - `bible/passages.txt` - odkazy na pasáže Bible použité v playlistech
+//! - `README.txt` - popis archivu
+//!
+//! Existující soubory se stejnými názvy jsou přepsány.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use sqlx::SqlitePool;
+
+use crate::{
+    Song,
+    bible::get_available_translations,
+    playlist::{self, PlaylistItemMetadata, PlaylistMetadata},
+};
+
+const SONGS_DIR_NAME: &str = "songs";
+const PLAYLISTS_DIR_NAME: &str = "playlists";
+const BIBLE_DIR_NAME: &str = "bible";
+const TRANSLATIONS_FILE_NAME: &str = "translations.txt";
+const PASSAGES_FILE_NAME: &str = "passages.txt";
+const README_FILE_NAME: &str = "README.txt";
+/// Název souboru pro položku bez (použitelného) názvu
+const UNNAMED_FILE_NAME: &str = "bez_nazvu";
+
+const README: &str = "Archiv dat programu Ekkles
+
+songs/                  písně ve formátu XML programu Opensong
+playlists/              playlisty (JSON), obsahují celé písně, odkazy na pasáže a vlastní texty
+bible/translations.txt  názvy překladů Bible v databázi (text Bible archiv neobsahuje)
+bible/passages.txt      pasáže Bible použité v playlistech
+";
+
+/// Počty exportovaných položek, viz [`export_archive()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveSummary {
+    pub songs: usize,
+    pub playlists: usize,
+    pub translations: usize,
+}
+
+/// Exportuje celou databázi `pool` do archivu ve složce `dir` (pokud neexistuje, bude
+/// vytvořena), viz [dokumentace modulu](crate::archive). Pokud nelze některou položku
+/// načíst nebo zapsat, vrací Error, archiv pak může být neúplný.
+pub async fn export_archive(dir: &Path, pool: &SqlitePool) -> Result<ArchiveSummary> {
+    let songs_dir = dir.join(SONGS_DIR_NAME);
+    let playlists_dir = dir.join(PLAYLISTS_DIR_NAME);
+    let bible_dir = dir.join(BIBLE_DIR_NAME);
+    for dir in [&songs_dir, &playlists_dir, &bible_dir] {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Nelze vytvořit složku archivu {}", dir.display()))?;
+    }
+    write(&dir.join(README_FILE_NAME), README)?;
+
+    let mut conn = pool
+        .acquire()
+        .await
+        .context("Nelze získat připojení k databázi z poolu")?;
+
+    let songs = Song::get_available_from_db(&mut conn).await?;
+    let mut used_names = HashSet::new();
+    for (id, title) in &songs {
+        let song = Song::load_from_db(*id, &mut conn).await?;
+        let file_name = unique_file_name(title, "xml", &mut used_names);
+        write(&songs_dir.join(file_name), &song.to_xml())?;
+    }
+
+    let translations: HashMap<i64, String> = get_available_translations(&mut conn)
+        .await?
+        .into_iter()
+        .collect();
+    let mut translation_names: Vec<&str> = translations.values().map(String::as_str).collect();
+    translation_names.sort();
+    write(
+        &bible_dir.join(TRANSLATIONS_FILE_NAME),
+        &lines(translation_names),
+    )?;
+
+    let playlists = playlist::get_available(
+        pool.acquire()
+            .await
+            .context("Nelze získat připojení k databázi z poolu")?,
+    )
+    .await?;
+    let mut used_names = HashSet::new();
+    let mut passages = Vec::new();
+    for info in &playlists {
+        let playlist = PlaylistMetadata::load(
+            info.id,
+            pool.acquire()
+                .await
+                .context("Nelze získat připojení k databázi z poolu")?,
+        )
+        .await?;
+        let file_name = unique_file_name(&info.name, "json", &mut used_names);
+        playlist
+            .export_bundle(&playlists_dir.join(file_name), &mut conn)
+            .await
+            .with_context(|| format!("Nelze exportovat playlist {}", info.name))?;
+
+        for item in playlist.get_items() {
+            if let PlaylistItemMetadata::BiblePassage {
+                translation_id,
+                from,
+                to,
+                ..
+            } = item
+            {
+                let translation = translations
+                    .get(translation_id)
+                    .map_or("neznámý překlad", String::as_str);
+                passages.push(format!("{}: {from} - {to} ({translation})", info.name));
+            }
+        }
+    }
+    write(&bible_dir.join(PASSAGES_FILE_NAME), &lines(passages))?;
+
+    Ok(ArchiveSummary {
+        songs: songs.len(),
+        playlists: playlists.len(),
+        translations: translations.len(),
+    })
+}
+
+fn write(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content).with_context(|| format!("Nelze zapsat soubor {}", path.display()))
+}
+
+/// Spojí řádky `lines`, každý ukončí znakem nového řádku
+fn lines(lines: impl IntoIterator<Item = impl AsRef<str>>) -> String {
+    lines
+        .into_iter()
+        .map(|line| format!("{}\n", line.as_ref()))
+        .collect()
+}
+
+/// Vytvoří z názvu položky `name` název souboru s příponou `extension`, který je platný
+/// na všech běžných systémech souborů a zatím není v `used` (porovnává se bez ohledu
+/// na velikost písmen). Použitý název přidá do `used`.
+fn unique_file_name(name: &str, extension: &str, used: &mut HashSet<String>) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|ch| match ch {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            ch if ch.is_control() => '_',
+            ch => ch,
+        })
+        .collect();
+    let sanitized = sanitized.trim().trim_end_matches('.');
+    let base = if sanitized.is_empty() {
+        UNNAMED_FILE_NAME
+    } else {
+        sanitized
+    };
+
+    let mut file_name = format!("{base}.{extension}");
+    let mut counter = 2;
+    while !used.insert(file_name.to_lowercase()) {
+        file_name = format!("{base} ({counter}).{extension}");
+        counter += 1;
+    }
+
+    file_name
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn file_names_are_sanitized_and_unique() {
+        let mut used = HashSet::new();
+
+        assert_eq!(
+            unique_file_name("AC/DC: Hvězdy?", "xml", &mut used),
+            "AC_DC_ Hvězdy_.xml"
+        );
+        assert_eq!(unique_file_name("Neděle", "json", &mut used), "Neděle.json");
+        assert_eq!(
+            unique_file_name("neděle", "json", &mut used),
+            "neděle (2).json"
+        );
+        assert_eq!(
+            unique_file_name(" ... ", "json", &mut used),
+            "bez_nazvu.json"
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Modul pro registr autorů písní. Autor písně je v Opensongu (a tedy i v [`crate::Song`])
+//! volný text (např. "Robert Lowry, 1874"), aby šlo písně procházet a filtrovat podle
+//! autorů, při uložení písně se z něj vytáhnou normalizovaná jména (viz [`normalize_authors()`])
+//! a píseň se s nimi propojí v tabulce `song_authors`.
+
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use sqlx::{Sqlite, SqliteConnection, pool::PoolConnection, query};
+
+lazy_static! {
+    /// Matchne oddělovač více autorů (`,`, `;`, `&`, `/`, `+`, spojky "a", "and")
+    static ref AUTHOR_SPLIT_REGEX: Regex =
+        Regex::new(r"(?i)\s*[,;&/+]\s*|\s+(?:a|and|und)\s+").unwrap();
+    /// Matchne roli autora na začátku jména (např. "Text:" nebo "Hudba:")
+    static ref AUTHOR_ROLE_REGEX: Regex = Regex::new(
+        r"(?i)^(?:text|slova|hudba|překlad|words|music|lyrics|translation|arr\.?)\s*:\s*"
+    )
+    .unwrap();
+    /// Matchne poznámku v závorce nebo letopočet (případně rozsah let), nejsou součástí jména
+    static ref AUTHOR_NOISE_REGEX: Regex =
+        Regex::new(r"\([^)]*\)|[*†]?\s*\b\d{4}\b(?:\s*[-–]\s*\d{4}\b)?").unwrap();
+}
+
+/// Vytáhne z volného textu `author` normalizovaná jména autorů v pořadí, v jakém se v textu
+/// vyskytují. Text se rozdělí na jednotlivé autory, z každého se odstraní role, letopočty a
+/// poznámky v závorkách a přebytečné mezery. Úseky bez písmen se zahodí, stejná jména
+/// (bez ohledu na velikost písmen) se vrátí pouze jednou.
+pub fn normalize_authors(author: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+
+    for part in AUTHOR_SPLIT_REGEX.split(author) {
+        let part = AUTHOR_ROLE_REGEX.replace(part.trim(), "");
+        let part = AUTHOR_NOISE_REGEX.replace_all(&part, " ");
+        let name = part
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .trim_matches(|c: char| c.is_ascii_punctuation() || c == '–')
+            .trim()
+            .to_string();
+
+        let is_duplicate = names
+            .iter()
+            .any(|existing| existing.to_lowercase() == name.to_lowercase());
+        if name.chars().any(char::is_alphabetic) && !is_duplicate {
+            names.push(name);
+        }
+    }
+
+    names
+}
+
+/// Propojí píseň `song_id` s autory vytaženými z volného textu `author`, autory, kteří
+/// v registru ještě nejsou, do něj přidá. Typicky se volá v rámci transakce ukládání písně.
+pub(crate) async fn link_song_authors(
+    song_id: i64,
+    author: &str,
+    conn: &mut SqliteConnection,
+) -> Result<()> {
+    for name in normalize_authors(author) {
+        query!(
+            "INSERT INTO authors (name) VALUES ($1) ON CONFLICT (name) DO NOTHING",
+            name
+        )
+        .execute(&mut *conn)
+        .await
+        .with_context(|| format!("Nelze uložit autora {name}"))?;
+
+        let author_id = query!("SELECT id FROM authors WHERE name = $1", name)
+            .fetch_one(&mut *conn)
+            .await
+            .with_context(|| format!("Nelze načíst id autora {name}"))?
+            .id
+            .expect("Id je primární klíč, musí být přítomen");
+
+        query!(
+            "INSERT OR IGNORE INTO song_authors (song_id, author_id) VALUES ($1, $2)",
+            song_id,
+            author_id
+        )
+        .execute(&mut *conn)
+        .await
+        .with_context(|| format!("Nelze propojit píseň s id {song_id} s autorem {name}"))?;
+    }
+
+    Ok(())
+}
+
+/// Získá vektor dvojic (id, jméno) všech autorů v registru seřazených podle jména,
+/// pokud se vyskytne při čtení chyba, vrací `Error`.
+pub async fn get_authors_from_db(conn: &mut PoolConnection<Sqlite>) -> Result<Vec<(i64, String)>> {
+    query!("SELECT id, name FROM authors ORDER BY name")
+        .map(|record| {
+            (
+                record.id.expect("Id je primární klíč, musí být přítomen"),
+                record.name,
+            )
+        })
+        .fetch_all(conn.as_mut())
+        .await
+        .context("Nelze načíst seznam autorů z databáze")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn strips_years_and_splits() {
+        assert_eq!(
+            normalize_authors("Robert Lowry, 1874"),
+            vec!["Robert Lowry"]
+        );
+        assert_eq!(
+            normalize_authors("Text: John Newton (1725–1807) & hudba: William  Walker"),
+            vec!["John Newton", "William Walker"]
+        );
+        assert_eq!(
+            normalize_authors("Petr Nekola a Jan Novák / *1950 petr nekola"),
+            vec!["Petr Nekola", "Jan Novák"]
+        );
+    }
+
+    #[test]
+    fn no_names() {
+        assert!(normalize_authors("").is_empty());
+        assert!(normalize_authors("1874, (neznámý)").is_empty());
+    }
+}
@@ -2,11 +2,21 @@
 //! z [tohoto repa](https://github.com/Beblia/Holy-Bible-XML-Format/tree/master)
 //! a ukládání do lokální SQLite databáze.
 
-use anyhow::{Context, Result, bail};
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
 use roxmltree::{Document, Node, TextPos};
-use sqlx::{Sqlite, SqlitePool, pool::PoolConnection, query};
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, SqlitePool, pool::PoolConnection, query};
+
+use crate::error::EkklesDataError;
+use indexing::Book;
 
+pub mod cross_reference;
+pub mod heading;
 pub mod indexing;
+pub mod reading_plan;
+pub mod strongs;
+pub mod verse_cache;
 
 const XML_TRANSLATION_NAME_ATTRIBUTE: &str = "translation";
 const XML_TRANSLATION_NAME_ATTRIBUTE_SECONDARY: &str = "name";
@@ -19,18 +29,126 @@ const XML_CHAPTER_TAG_NAME: &str = "chapter";
 const XML_VERSE_TAG_NAME: &str = "verse";
 /// Je to opravdu konstanta 😎
 const NUM_BOOKS_IN_THE_BIBLE: usize = 66;
+/// Kolik řádků se při importu vkládá jedním příkazem `INSERT`, 128 řádků po nejvýše
+/// 7 sloupcích se vejde do limitu 999 parametrů starších verzí SQLite
+const INSERT_BATCH_ROWS: usize = 128;
+
+/// Řádky importovaného překladu čekající na hromadné vložení do databáze. Vkládat
+/// ~31 000 veršů po jednom trvá řádově minuty, po dávkách [`INSERT_BATCH_ROWS`] sekundy.
+struct BatchInserter {
+    translation_id: i64,
+    /// (kniha, kapitola, číslo, text, pořadí verše)
+    verses: Vec<(i64, u32, u32, String, i64)>,
+    /// (kniha, kapitola, číslo, nadpis)
+    headings: Vec<(i64, u32, u32, String)>,
+    /// (kniha, kapitola, číslo, pořadí slova, slovo, Strongova čísla)
+    strongs: Vec<(i64, u32, u32, i64, String, String)>,
+}
+
+impl BatchInserter {
+    fn new(translation_id: i64) -> Self {
+        Self {
+            translation_id,
+            verses: Vec::with_capacity(INSERT_BATCH_ROWS),
+            headings: Vec::new(),
+            strongs: Vec::new(),
+        }
+    }
+
+    /// Vloží nasbírané řádky, pokud jich je alespoň na jednu dávku
+    async fn flush_if_full(&mut self, conn: &mut SqliteConnection) -> Result<()> {
+        let full = [self.verses.len(), self.headings.len(), self.strongs.len()]
+            .into_iter()
+            .any(|len| len >= INSERT_BATCH_ROWS);
+
+        if full { self.flush(conn).await } else { Ok(()) }
+    }
+
+    /// Vloží všechny nasbírané řádky, verše jako první, protože na ně nadpisy
+    /// i Strongova čísla odkazují cizím klíčem
+    async fn flush(&mut self, conn: &mut SqliteConnection) -> Result<()> {
+        let translation_id = self.translation_id;
+
+        for verses in self.verses.chunks(INSERT_BATCH_ROWS) {
+            QueryBuilder::<Sqlite>::new(
+                "INSERT INTO verses (translation_id, book_id, chapter, number, content, verse_order) ",
+            )
+            .push_values(verses, |mut row, (book_id, chapter, number, content, verse_order)| {
+                row.push_bind(translation_id)
+                    .push_bind(*book_id)
+                    .push_bind(*chapter)
+                    .push_bind(*number)
+                    .push_bind(content.as_str())
+                    .push_bind(*verse_order);
+            })
+            .build()
+            .execute(&mut *conn)
+            .await
+            .context("Nelze uložit verše")?;
+        }
+        self.verses.clear();
+
+        for headings in self.headings.chunks(INSERT_BATCH_ROWS) {
+            QueryBuilder::<Sqlite>::new(
+                "INSERT INTO verse_headings (translation_id, book_id, chapter, number, content) ",
+            )
+            .push_values(headings, |mut row, (book_id, chapter, number, content)| {
+                row.push_bind(translation_id)
+                    .push_bind(*book_id)
+                    .push_bind(*chapter)
+                    .push_bind(*number)
+                    .push_bind(content.as_str());
+            })
+            .build()
+            .execute(&mut *conn)
+            .await
+            .context("Nelze uložit nadpisy veršů")?;
+        }
+        self.headings.clear();
+
+        for strongs in self.strongs.chunks(INSERT_BATCH_ROWS) {
+            QueryBuilder::<Sqlite>::new(
+                "INSERT INTO verse_strongs (translation_id, book_id, chapter, number, word_order, word, strongs) ",
+            )
+            .push_values(
+                strongs,
+                |mut row, (book_id, chapter, number, word_order, word, strongs)| {
+                    row.push_bind(translation_id)
+                        .push_bind(*book_id)
+                        .push_bind(*chapter)
+                        .push_bind(*number)
+                        .push_bind(*word_order)
+                        .push_bind(word.as_str())
+                        .push_bind(strongs.as_str());
+                },
+            )
+            .build()
+            .execute(&mut *conn)
+            .await
+            .context("Nelze uložit Strongova čísla veršů")?;
+        }
+        self.strongs.clear();
+
+        Ok(())
+    }
+}
 
 /// Zparsuje XML bible a uloží ji do databáze pomocí dodaného poolu,
-/// v případě chyby vrátí Error.
+/// v případě chyby vrátí Error. Pokud XML není validní Bible, vrací
+/// [`EkklesDataError::InvalidData`], pokud překlad se stejným názvem už v databázi je,
+/// [`EkklesDataError::Conflict`].
 ///
 /// ### Transakce
 /// Používá mechanismus transakcí, tedy buď kompletně celá kniha bude uložena
-/// do databáze nebo ani část z ní (v případě chyby).
+/// do databáze nebo ani část z ní (v případě chyby). Verše se v rámci transakce
+/// vkládají po dávkách, viz [`BatchInserter`].
 ///
 /// ### Implementace
 /// Parsuje formát z [tohoto repa](https://github.com/Beblia/Holy-Bible-XML-Format/tree/master).
 /// Nejdřív uloží nový název překladu do databáze a poté začne ukládat jednotlivé verše.
-pub async fn parse_bible_from_xml(xml: &str, pool: &SqlitePool) -> Result<()> {
+/// Pokud verše obsahují Strongova čísla, uloží se také, viz [`strongs`]. Nadpisy (např. žalmů)
+/// se uloží zvlášť k verši, před kterým stojí, viz [`heading`].
+pub async fn parse_bible_from_xml(xml: &str, pool: &SqlitePool) -> crate::error::Result<()> {
     let document = Document::parse(xml).context("Nelze zparsovat XML")?;
 
     // Používáme transakci, abychom mohli na konci po úspěšném zparsování spustit `commit()`,
@@ -58,7 +176,7 @@ pub async fn parse_bible_from_xml(xml: &str, pool: &SqlitePool) -> Result<()> {
     )
     .execute(&mut *transaction)
     .await
-    .context("Nelze uložit název překladu do databáze")?
+    .with_context(|| format!("Nelze uložit překlad {translation_name} do databáze"))?
     .last_insert_rowid();
 
     // Pozor, tady se musí provést filtrování, protože mezi jednotlivými
@@ -75,7 +193,9 @@ pub async fn parse_bible_from_xml(xml: &str, pool: &SqlitePool) -> Result<()> {
 
     let count = books.clone().count();
     if count != NUM_BOOKS_IN_THE_BIBLE {
-        bail!("Nesprávný počet knih ({count})");
+        return Err(EkklesDataError::InvalidData(format!(
+            "Nesprávný počet knih ({count})"
+        )));
     }
 
     // Closure pro spočítání řádku a sloupce XML uzlu v případě chyby
@@ -84,7 +204,8 @@ pub async fn parse_bible_from_xml(xml: &str, pool: &SqlitePool) -> Result<()> {
         document.text_pos_at(start_byte)
     };
 
-    let mut verse_order = 0;
+    let mut verse_order: i64 = 0;
+    let mut inserter = BatchInserter::new(translation_id);
 
     for book in books {
         let book_number = book
@@ -132,10 +253,19 @@ pub async fn parse_bible_from_xml(xml: &str, pool: &SqlitePool) -> Result<()> {
                     )
                 })?;
 
-            for verse in chapter
-                .children()
-                .filter(|node| node.is_element() && node.tag_name().name() == XML_VERSE_TAG_NAME)
-            {
+            // Nadpisy, které v kapitole stojí před dalším veršem
+            let mut pending_headings: Vec<String> = Vec::new();
+
+            for node in chapter.children().filter(|node| node.is_element()) {
+                if heading::is_heading(node) {
+                    pending_headings.extend(heading::heading_text(node));
+                    continue;
+                }
+                if node.tag_name().name() != XML_VERSE_TAG_NAME {
+                    continue;
+                }
+
+                let verse = node;
                 let verse_number = verse
                     .attribute(XML_VERSE_NUMBER_ATTRIBUTE)
                     .with_context(|| {
@@ -152,30 +282,53 @@ pub async fn parse_bible_from_xml(xml: &str, pool: &SqlitePool) -> Result<()> {
                         )
                     })?;
 
-                let verse_content = verse.text().with_context(|| {
-                    format!("Verš neobsahuje text na pozici {}", get_pos(verse))
-                })?;
+                if verse_number == heading::HEADING_VERSE_NUMBER {
+                    pending_headings.extend(heading::heading_text(verse));
+                    continue;
+                }
+
+                // Text verše bez případných značek se Strongovými čísly
+                let (verse_content, tagged_words) = strongs::parse_verse(verse, order)
+                    .with_context(|| {
+                        format!("Verš neobsahuje text na pozici {}", get_pos(verse))
+                    })?;
+
+                inserter.verses.push((
+                    book_id,
+                    chapter_number,
+                    verse_number,
+                    verse_content,
+                    verse_order,
+                ));
+
+                pending_headings.extend(heading::headings_in_verse(verse));
+                if !pending_headings.is_empty() {
+                    let verse_heading = pending_headings.join(" ");
+                    pending_headings.clear();
+                    inserter
+                        .headings
+                        .push((book_id, chapter_number, verse_number, verse_heading));
+                }
 
-                query!(
-                        "
-                        INSERT INTO verses (translation_id, book_id, chapter, number, content, verse_order) VALUES ($1, $2, $3, $4, $5, $6);
-                        ",
-                        translation_id,
+                for (word_order, tagged_word) in tagged_words.into_iter().enumerate() {
+                    inserter.strongs.push((
                         book_id,
                         chapter_number,
                         verse_number,
-                        verse_content,
-                        verse_order,
-                    )
-                    .execute(&mut *transaction)
-                    .await
-                    .context("Nelze uložit verš")?;
+                        word_order as i64,
+                        tagged_word.word,
+                        tagged_word.strongs.join(" "),
+                    ));
+                }
 
+                inserter.flush_if_full(&mut transaction).await?;
                 verse_order += 1;
             }
         }
     }
 
+    inserter.flush(&mut transaction).await?;
+
     // Pokud jsme se dostali až sem, znamená to, že nenastala chyba, můžeme commitnout transakci
     transaction
         .commit()
@@ -194,10 +347,38 @@ fn book_number_to_order(number: u32) -> u32 {
 /// Vrátí vektor dvojic (id, název) všech dostupných překladů v databázi, pokud nelze načíst seznam z databáze, vrátí Error.
 pub async fn get_available_translations(
     conn: &mut PoolConnection<Sqlite>,
-) -> Result<Vec<(i64, String)>> {
-    query!("SELECT id, name FROM translations")
+) -> crate::error::Result<Vec<(i64, String)>> {
+    Ok(query!("SELECT id, name FROM translations")
         .map(|record| (record.id, record.name))
         .fetch_all(conn.as_mut())
         .await
-        .context("Nelze načíst seznam překladů z databáze")
+        .context("Nelze načíst seznam překladů z databáze")?)
+}
+
+/// Vrátí číslo posledního verše každé kapitoly překladu `translation_id` jako mapu
+/// (kniha, kapitola) -> poslední verš. Kapitoly, které překlad nemá, v mapě nejsou.
+///
+/// Překlady se ve verzifikaci mohou lišit od tabulky v [`indexing`] (např. Malachiáš
+/// 3:19-24 je v anglických překladech 4:1-6), výběr pasáže tak lze zkontrolovat přímo
+/// vůči vybranému překladu.
+pub async fn get_verse_counts(
+    translation_id: i64,
+    conn: &mut PoolConnection<Sqlite>,
+) -> crate::error::Result<HashMap<(Book, u8), u8>> {
+    let records = query!(
+        r#"SELECT book_id AS "book_id!", chapter AS "chapter!", MAX(number) AS "last_verse!: i64"
+        FROM verses WHERE translation_id = $1 GROUP BY book_id, chapter"#,
+        translation_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .with_context(|| format!("Nelze načíst počty veršů překladu s id {translation_id}"))?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            let book = Book::try_from(record.book_id as u8)?;
+            Ok(((book, record.chapter as u8), record.last_verse as u8))
+        })
+        .collect()
 }
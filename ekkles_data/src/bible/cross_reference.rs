@@ -0,0 +1,256 @@
+//! Modul pro křížové odkazy mezi verši.
+//!
+//! Odkazy se importují z datové sady ve formátu [OpenBible.info](https://www.openbible.info/labs/cross-references/)
+//! (obsahuje i odkazy z Treasury of Scripture Knowledge). Jde o textový soubor, kde každý
+//! řádek obsahuje tabulátorem oddělený verš, odkazovanou pasáž a počet hlasů (relevanci):
+//! ```text
+//! From Verse    To Verse    Votes
+//! Gen.1.1    John.1.1-John.1.3    388
+//! Gen.1.1    Heb.11.3    338
+//! ```
+//! Knihy jsou zapsané zkratkami OSIS, viz [`Book::from_osis()`].
+
+use anyhow::{Context, Result, anyhow, bail};
+use log::{debug, trace};
+use sqlx::{Sqlite, SqlitePool, pool::PoolConnection, query};
+
+use super::indexing::{Book, VerseIndex};
+
+/// Začátek řádku s hlavičkou v datové sadě OpenBible.info
+const OPENBIBLE_HEADER_PREFIX: &str = "From Verse";
+
+/// Křížový odkaz z verše `from` na pasáž `to` (od-do, včetně)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrossReference {
+    pub from: VerseIndex,
+    pub to: (VerseIndex, VerseIndex),
+    /// Relevance odkazu, čím vyšší, tím relevantnější
+    pub votes: i64,
+}
+
+/// Zparsuje křížové odkazy z datové sady ve formátu OpenBible.info (viz dokumentace
+/// modulu). Odkazy na verše, které v našem číslování neexistují (datová sada používá
+/// jinou versifikaci), se přeskočí. Pokud je řádek ve špatném formátu, vrací Error.
+pub fn parse_openbible(input: &str) -> Result<Vec<CrossReference>> {
+    let mut cross_references = Vec::new();
+    let mut skipped = 0;
+
+    for (line_number, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(OPENBIBLE_HEADER_PREFIX) {
+            continue;
+        }
+
+        match parse_openbible_line(line)
+            .with_context(|| format!("Chyba na řádku {}", line_number + 1))?
+        {
+            Some(cross_reference) => cross_references.push(cross_reference),
+            None => {
+                trace!("Přeskakuji křížový odkaz na neexistující verš: {line}");
+                skipped += 1;
+            }
+        }
+    }
+
+    debug!(
+        "Zparsováno {} křížových odkazů, přeskočeno {}",
+        cross_references.len(),
+        skipped
+    );
+    Ok(cross_references)
+}
+
+/// Zparsuje jeden řádek datové sady, pokud odkazuje na neexistující verš, vrací `None`.
+fn parse_openbible_line(line: &str) -> Result<Option<CrossReference>> {
+    let mut columns = line.split('\t');
+    let (Some(from), Some(to), Some(votes)) = (columns.next(), columns.next(), columns.next())
+    else {
+        bail!("Očekávány tři sloupce oddělené tabulátorem");
+    };
+
+    let votes = votes
+        .trim()
+        .parse()
+        .with_context(|| format!("'{votes}' není počet hlasů"))?;
+
+    let (start, end) = to.split_once('-').unwrap_or((to, to));
+    let (Some(from), Some(start), Some(end)) = (
+        parse_osis_verse(from)?,
+        parse_osis_verse(start)?,
+        parse_osis_verse(end)?,
+    ) else {
+        return Ok(None);
+    };
+
+    if start > end {
+        return Ok(None);
+    }
+
+    Ok(Some(CrossReference {
+        from,
+        to: (start, end),
+        votes,
+    }))
+}
+
+/// Zparsuje verš ve tvaru `Kniha.kapitola.verš` (`Gen.1.1`). Pokud verš v našem číslování
+/// neexistuje, vrací `None`, pokud je ve špatném formátu, vrací Error.
+fn parse_osis_verse(input: &str) -> Result<Option<VerseIndex>> {
+    let mut parts = input.trim().split('.');
+    let (Some(book), Some(chapter), Some(verse), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        bail!("'{input}' není verš ve tvaru Kniha.kapitola.verš");
+    };
+
+    let book = Book::from_osis(book).ok_or(anyhow!("Neznámá kniha '{book}'"))?;
+    let chapter = chapter
+        .parse()
+        .with_context(|| format!("'{chapter}' není číslo kapitoly"))?;
+    let verse = verse
+        .parse()
+        .with_context(|| format!("'{verse}' není číslo verše"))?;
+
+    Ok(VerseIndex::try_new(book, chapter, verse))
+}
+
+/// Nahradí všechny křížové odkazy v databázi odkazy `cross_references`.
+///
+/// ### Integrita databáze
+/// Probíhá v transakci, pokud se import nepovede, zůstanou v databázi původní odkazy.
+pub async fn replace_all(cross_references: &[CrossReference], pool: &SqlitePool) -> Result<()> {
+    let mut transaction = pool.begin().await.context("Nelze zahájit transakci")?;
+
+    query!("DELETE FROM cross_references")
+        .execute(&mut *transaction)
+        .await
+        .context("Nelze smazat původní křížové odkazy")?;
+
+    for cross_reference in cross_references {
+        let (from_book, from_chapter, from_number) = cross_reference.from.destructure_numeric();
+        let (start_book, start_chapter, start_number) = cross_reference.to.0.destructure_numeric();
+        let (end_book, end_chapter, end_number) = cross_reference.to.1.destructure_numeric();
+
+        query!(
+            "INSERT INTO cross_references (from_book_id, from_chapter, from_number, start_book_id, start_chapter, start_number, end_book_id, end_chapter, end_number, votes) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            from_book,
+            from_chapter,
+            from_number,
+            start_book,
+            start_chapter,
+            start_number,
+            end_book,
+            end_chapter,
+            end_number,
+            cross_reference.votes
+        )
+        .execute(&mut *transaction)
+        .await
+        .with_context(|| format!("Nelze uložit křížový odkaz z {}", cross_reference.from))?;
+    }
+
+    transaction
+        .commit()
+        .await
+        .context("Nelze provést commit transakce")?;
+
+    Ok(())
+}
+
+/// Vrátí počet křížových odkazů v databázi.
+pub async fn count(conn: &mut PoolConnection<Sqlite>) -> Result<i64> {
+    query!(r#"SELECT COUNT(*) AS "count: i64" FROM cross_references"#)
+        .fetch_one(conn.as_mut())
+        .await
+        .context("Nelze zjistit počet křížových odkazů")
+        .map(|record| record.count)
+}
+
+/// Načte křížové odkazy z verše `verse` seřazené od nejrelevantnějšího.
+pub async fn get_for_verse(
+    verse: VerseIndex,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<Vec<CrossReference>> {
+    let (book, chapter, number) = verse.destructure_numeric();
+
+    let records = query!(
+        "SELECT start_book_id, start_chapter, start_number, end_book_id, end_chapter, end_number, votes FROM cross_references WHERE from_book_id = $1 AND from_chapter = $2 AND from_number = $3 ORDER BY votes DESC",
+        book,
+        chapter,
+        number
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .with_context(|| format!("Nelze načíst křížové odkazy verše {verse}"))?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            let start = VerseIndex::try_new(
+                Book::try_from(record.start_book_id as u8)?,
+                record.start_chapter as u8,
+                record.start_number as u8,
+            )
+            .ok_or(anyhow!("Nevalidní index verše v databázi"))?;
+
+            let end = VerseIndex::try_new(
+                Book::try_from(record.end_book_id as u8)?,
+                record.end_chapter as u8,
+                record.end_number as u8,
+            )
+            .ok_or(anyhow!("Nevalidní index verše v databázi"))?;
+
+            Ok(CrossReference {
+                from: verse,
+                to: (start, end),
+                votes: record.votes,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn index(book: Book, chapter: u8, verse: u8) -> VerseIndex {
+        VerseIndex::try_new(book, chapter, verse).unwrap()
+    }
+
+    #[test]
+    fn parse_openbible_dataset() {
+        let input = "From Verse\tTo Verse\tVotes\t#www.openbible.info CC-BY\n\
+                     Gen.1.1\tJohn.1.1-John.1.3\t388\n\
+                     Gen.1.1\tHeb.11.3\t338\n\
+                     Gen.1.1\tJoel.3.21\t-2\n";
+
+        let cross_references = parse_openbible(input).unwrap();
+
+        assert_eq!(
+            cross_references,
+            vec![
+                CrossReference {
+                    from: index(Book::Genesis, 1, 1),
+                    to: (index(Book::John, 1, 1), index(Book::John, 1, 3)),
+                    votes: 388,
+                },
+                CrossReference {
+                    from: index(Book::Genesis, 1, 1),
+                    to: (index(Book::Hebrews, 11, 3), index(Book::Hebrews, 11, 3)),
+                    votes: 338,
+                },
+            ],
+            "Jóel 3:21 v našem číslování neexistuje, má se přeskočit"
+        );
+    }
+
+    #[test]
+    fn parse_malformed_dataset() {
+        assert!(parse_openbible("Gen.1.1\tJohn.1.1").is_err());
+        assert!(parse_openbible("Gen.1.1\tFoo.1.1\t3").is_err());
+        assert!(parse_openbible("Gen.1\tJohn.1.1\t3").is_err());
+        assert!(parse_openbible("Gen.1.1\tJohn.1.1\tmnoho").is_err());
+    }
+}
@@ -0,0 +1,108 @@
+//! Modul pro nadpisy veršů (nadpisy žalmů, nadpisy oddílů) u překladů, které je obsahují.
+//!
+//! V XML překladu může být nadpis zapsán několika způsoby:
+//! - element `<title>` nebo `<heading>` v kapitole před veršem, ke kterému patří
+//! - stejný element uvnitř verše (`<verse number="1"><title>Žalm Davidův</title>...</verse>`)
+//! - verš s číslem 0 (`<verse number="0">`), takto některé překlady zapisují nadpisy žalmů
+//!
+//! Nadpis se uloží k verši, před kterým stojí, a do textu verše se nezapočítává
+//! (dříve se buď přilepil k textu verše, nebo verš 0 rozbil import). Značky `Sela`
+//! jsou součástí textu verše a zůstávají v něm.
+
+use anyhow::{Context, Result};
+use roxmltree::Node;
+use sqlx::{Sqlite, pool::PoolConnection, query};
+
+use super::indexing::VerseIndex;
+
+/// Elementy, ve kterých je nadpis
+const XML_HEADING_TAG_NAMES: [&str; 2] = ["title", "heading"];
+/// Číslo "verše", pod kterým některé překlady zapisují nadpis kapitoly (žalmu)
+pub(crate) const HEADING_VERSE_NUMBER: u32 = 0;
+
+/// Zjistí, zda je `node` element s nadpisem
+pub(crate) fn is_heading(node: Node) -> bool {
+    node.is_element() && XML_HEADING_TAG_NAMES.contains(&node.tag_name().name())
+}
+
+/// Zjistí, zda je `node` nadpis nebo je uvnitř nadpisu, takový text nepatří do textu verše
+pub(crate) fn in_heading(node: Node) -> bool {
+    node.ancestors().any(is_heading)
+}
+
+/// Vrátí text uzlu `node` (včetně potomků) bez okolních bílých znaků,
+/// pokud je prázdný, vrací `None`
+pub(crate) fn heading_text(node: Node) -> Option<String> {
+    let text: String = node
+        .descendants()
+        .filter(|node| node.is_text())
+        .filter_map(|node| node.text())
+        .collect();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    (!text.is_empty()).then_some(text)
+}
+
+/// Vrátí nadpisy uvnitř verše `verse` v pořadí, v jakém ve verši jsou
+pub(crate) fn headings_in_verse(verse: Node) -> impl Iterator<Item = String> {
+    verse
+        .descendants()
+        .filter(move |node| *node != verse && is_heading(*node))
+        .filter_map(heading_text)
+}
+
+/// Načte nadpis verše `index` v překladu `translation_id`, pokud verš nadpis nemá,
+/// vrací `None`. Pokud nastane chyba s databází, vrací Error.
+pub async fn load(
+    index: VerseIndex,
+    translation_id: i64,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<Option<String>> {
+    let (book, chapter, number) = index.destructure_numeric();
+
+    let heading = query!(
+        "SELECT content FROM verse_headings WHERE translation_id = $1 AND book_id = $2 AND chapter = $3 AND number = $4",
+        translation_id,
+        book,
+        chapter,
+        number
+    )
+    .fetch_optional(conn.as_mut())
+    .await
+    .with_context(|| format!("Nelze načíst nadpis verše {index}"))?
+    .map(|record| record.content);
+
+    Ok(heading)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use roxmltree::Document;
+
+    use super::*;
+    use crate::bible::strongs::parse_verse;
+
+    #[test]
+    fn heading_is_not_part_of_verse() {
+        let xml = r#"<verse number="1"><title>Žalm Davidův.</title> Hospodin je můj pastýř, nic nepostrádám.</verse>"#;
+        let document = Document::parse(xml).unwrap();
+        let verse = document.root_element();
+
+        let (content, _) = parse_verse(verse, 18).unwrap();
+
+        assert_eq!(content.trim(), "Hospodin je můj pastýř, nic nepostrádám.");
+        assert_eq!(
+            headings_in_verse(verse).collect::<Vec<_>>(),
+            vec![String::from("Žalm Davidův.")]
+        );
+    }
+
+    #[test]
+    fn empty_heading_is_ignored() {
+        let document = Document::parse("<heading>  \n </heading>").unwrap();
+
+        assert!(is_heading(document.root_element()));
+        assert_eq!(heading_text(document.root_element()), None);
+    }
+}
@@ -0,0 +1,410 @@
+//! Modul pro plány čtení Bible (např. M'Cheyne, roční plán sboru).
+//!
+//! Plán je posloupnost dnů, každý den obsahuje jedno či více čtení (pasáží). Plán
+//! začíná v den `start_date` a po posledním dni začíná znovu od začátku, roční plány
+//! tak lze používat i v dalších letech.
+//!
+//! ### Formát souboru s plánem
+//! Jeden řádek odpovídá jednomu dni, jednotlivá čtení jsou oddělena středníkem.
+//! Prázdné řádky a řádky začínající `#` se přeskakují. Čtení se zapisují stejně jako
+//! v rychlém výběru pasáže, navíc lze vynechat verše a číst celé kapitoly:
+//! ```text
+//! # M'Cheyne
+//! Genesis 1; Matouš 1; Ezdráš 1; Skutky 1
+//! Genesis 2; Matouš 2; Ezdráš 2; Skutky 2
+//! Žalmy 119:1-24; Jan 3:16-4:2; Rút 1-2
+//! ```
+
+use std::fmt::Display;
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{Local, NaiveDate};
+use lazy_static::lazy_static;
+use regex::Regex;
+use sqlx::{Sqlite, SqlitePool, pool::PoolConnection, query};
+
+use super::indexing::{Book, VerseIndex, verses_in_chapter};
+
+/// Formát data v databázi
+const DB_DATE_FORMAT: &str = "%F";
+/// Oddělovač čtení v rámci jednoho dne v souboru s plánem
+const READINGS_SEPARATOR: char = ';';
+/// Začátek komentáře v souboru s plánem
+const COMMENT_PREFIX: char = '#';
+
+lazy_static! {
+    /// Matchne odkaz na pasáž ve tvaru `KNIHA KAPITOLA[:VERŠ][-[KNIHA] ČÍSLO[:ČÍSLO]]`
+    static ref REFERENCE_REGEX: Regex = Regex::new(
+        r"^(?P<book>(\d\. *)?\p{Letter}+( +\p{Letter}+)*?) *(?P<chapter>\d+)( *: *(?P<verse>\d+))?( *- *((?P<to_book>(\d\. *)?\p{Letter}+( +\p{Letter}+)*?) *)?(?P<to_first>\d+)( *: *(?P<to_second>\d+))?)?$"
+    )
+    .unwrap();
+}
+
+/// Jedno čtení plánu, rozsah pasáže od-do (včetně)
+pub type Reading = (VerseIndex, VerseIndex);
+
+/// Plán čtení připravený k uložení do databáze
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadingPlan {
+    pub name: String,
+    /// Čtení pro jednotlivé dny, index 0 je první den plánu
+    pub days: Vec<Vec<Reading>>,
+}
+
+/// Informace o plánu čtení v databázi (bez samotných čtení)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadingPlanInfo {
+    pub id: i64,
+    pub name: String,
+    /// Datum prvního dne plánu
+    pub start_date: NaiveDate,
+    /// Počet dní plánu
+    pub num_days: u32,
+}
+
+impl Display for ReadingPlanInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl ReadingPlanInfo {
+    /// Vrátí den plánu (od 1), který připadá na datum `date`. Pokud plán v daný den
+    /// ještě nezačal nebo nemá žádné dny, vrací `None`.
+    pub fn day_for(&self, date: NaiveDate) -> Option<u32> {
+        if self.num_days == 0 || date < self.start_date {
+            return None;
+        }
+
+        let days_since_start = (date - self.start_date).num_days() as u32;
+        Some(days_since_start % self.num_days + 1)
+    }
+
+    /// Vrátí den plánu (od 1), který připadá na dnešek, viz [`ReadingPlanInfo::day_for`].
+    pub fn day_for_today(&self) -> Option<u32> {
+        self.day_for(Local::now().date_naive())
+    }
+}
+
+impl ReadingPlan {
+    /// Zparsuje plán čtení s názvem `name` z textu ve formátu popsaném v dokumentaci
+    /// modulu. Pokud nějaké čtení nelze zparsovat, vrací Error s číslem řádku.
+    pub fn parse(name: &str, input: &str) -> Result<Self> {
+        let days = input
+            .lines()
+            .enumerate()
+            .map(|(line_number, line)| (line_number + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with(COMMENT_PREFIX))
+            .map(|(line_number, line)| {
+                line.split(READINGS_SEPARATOR)
+                    .map(str::trim)
+                    .filter(|reading| !reading.is_empty())
+                    .map(parse_reading)
+                    .collect::<Result<Vec<Reading>>>()
+                    .with_context(|| format!("Chyba na řádku {line_number}"))
+            })
+            .collect::<Result<Vec<Vec<Reading>>>>()?;
+
+        if days.is_empty() {
+            bail!("Plán čtení neobsahuje žádný den");
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            days,
+        })
+    }
+
+    /// Uloží plán do databáze s prvním dnem `start_date` a vrátí jeho id. Pokud plán
+    /// se stejným názvem již existuje, vrací Error.
+    ///
+    /// ### Integrita databáze
+    /// Plán se ukládá v transakci, buď se uloží celý, nebo vůbec.
+    pub async fn save_to_db(&self, start_date: NaiveDate, pool: &SqlitePool) -> Result<i64> {
+        let mut transaction = pool.begin().await.context("Nelze zahájit transakci")?;
+
+        let start_date = start_date.format(DB_DATE_FORMAT).to_string();
+        let plan_id = query!(
+            "INSERT INTO reading_plans (name, start_date) VALUES ($1, $2)",
+            self.name,
+            start_date
+        )
+        .execute(&mut *transaction)
+        .await
+        .with_context(|| format!("Nelze uložit plán čtení '{}' do databáze", self.name))?
+        .last_insert_rowid();
+
+        for (day, readings) in self.days.iter().enumerate() {
+            let day = day as i64 + 1;
+            for (entry_order, (from, to)) in readings.iter().enumerate() {
+                let entry_order = entry_order as i64;
+                let (start_book, start_chapter, start_number) = from.destructure_numeric();
+                let (end_book, end_chapter, end_number) = to.destructure_numeric();
+
+                query!(
+                    "INSERT INTO reading_plan_entries (plan_id, day, entry_order, start_book_id, start_chapter, start_number, end_book_id, end_chapter, end_number) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+                    plan_id,
+                    day,
+                    entry_order,
+                    start_book,
+                    start_chapter,
+                    start_number,
+                    end_book,
+                    end_chapter,
+                    end_number
+                )
+                .execute(&mut *transaction)
+                .await
+                .with_context(|| format!("Nelze uložit čtení {day}. dne plánu do databáze"))?;
+            }
+        }
+
+        transaction
+            .commit()
+            .await
+            .context("Nelze provést commit transakce")?;
+
+        Ok(plan_id)
+    }
+}
+
+/// Zparsuje jedno čtení (odkaz na pasáž). Pokud nejsou uvedeny verše, jde o celé
+/// kapitoly (`Rút 1-2`), jinak se chybějící kniha/kapitola konce doplní ze začátku
+/// (`Jan 3:16-21`).
+pub fn parse_reading(input: &str) -> Result<Reading> {
+    let caps = REFERENCE_REGEX
+        .captures(input.trim())
+        .ok_or(anyhow!("'{input}' není odkaz na pasáž"))?;
+
+    // Všechna čísla v regexu jsou `\d+`, selhat může pouze převod na u8 (příliš velké číslo)
+    let number = |name: &str| -> Result<Option<u8>> {
+        caps.name(name)
+            .map(|m| m.as_str().parse::<u8>())
+            .transpose()
+            .with_context(|| format!("Nevalidní číslo v odkazu '{input}'"))
+    };
+
+    let book: Book = caps["book"].parse()?;
+    let chapter = number("chapter")?.expect("Kapitola je v regexu povinná");
+    let verse = number("verse")?;
+
+    let to_book: Option<Book> = caps
+        .name("to_book")
+        .map(|m| m.as_str().parse())
+        .transpose()?;
+    let to_first = number("to_first")?;
+    let to_second = number("to_second")?;
+
+    // Konec pasáže jako (kniha, kapitola, verš), `None` verš znamená konec kapitoly
+    let (end_book, end_chapter, end_verse) = match (to_book, to_first, to_second) {
+        (None, None, _) => (book, chapter, verse),
+        (Some(to_book), Some(to_chapter), to_verse) => (to_book, to_chapter, to_verse),
+        (None, Some(to_chapter), Some(to_verse)) => (book, to_chapter, Some(to_verse)),
+        (None, Some(to_verse), None) if verse.is_some() => (book, chapter, Some(to_verse)),
+        (None, Some(to_chapter), None) => (book, to_chapter, None),
+        (Some(_), None, _) => unreachable!("Za knihou konce musí v regexu následovat číslo"),
+    };
+
+    let first_verse = *verses_in_chapter(book, chapter)
+        .ok_or(anyhow!("{book} nemá kapitolu {chapter}"))?
+        .start();
+    let last_verse = *verses_in_chapter(end_book, end_chapter)
+        .ok_or(anyhow!("{end_book} nemá kapitolu {end_chapter}"))?
+        .end();
+
+    let from = VerseIndex::try_new(book, chapter, verse.unwrap_or(first_verse))
+        .ok_or(anyhow!("Verš na začátku '{input}' neexistuje"))?;
+    let to = VerseIndex::try_new(end_book, end_chapter, end_verse.unwrap_or(last_verse))
+        .ok_or(anyhow!("Verš na konci '{input}' neexistuje"))?;
+
+    if from > to {
+        bail!("Začátek pasáže '{input}' je až po jejím konci");
+    }
+
+    Ok((from, to))
+}
+
+/// Vrátí informace o všech plánech čtení v databázi seřazené podle názvu.
+pub async fn get_available(conn: &mut PoolConnection<Sqlite>) -> Result<Vec<ReadingPlanInfo>> {
+    let records = query!(
+        r#"SELECT id AS "id!", name, start_date, (SELECT MAX(day) FROM reading_plan_entries WHERE plan_id = reading_plans.id) AS "num_days?: i64" FROM reading_plans ORDER BY name ASC"#
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .context("Nelze načíst plány čtení z databáze")?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            Ok(ReadingPlanInfo {
+                start_date: NaiveDate::parse_from_str(&record.start_date, DB_DATE_FORMAT)
+                    .context("Nevalidní datum začátku plánu v databázi")?,
+                id: record.id,
+                name: record.name,
+                num_days: record.num_days.unwrap_or(0) as u32,
+            })
+        })
+        .collect()
+}
+
+/// Načte čtení dne `day` (od 1) plánu `plan_id` v pořadí, v jakém jsou v plánu.
+pub async fn load_readings(
+    plan_id: i64,
+    day: u32,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<Vec<Reading>> {
+    let records = query!(
+        "SELECT start_book_id, start_chapter, start_number, end_book_id, end_chapter, end_number FROM reading_plan_entries WHERE plan_id = $1 AND day = $2 ORDER BY entry_order ASC",
+        plan_id,
+        day
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .with_context(|| format!("Nelze načíst čtení {day}. dne plánu s id {plan_id}"))?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            let from = VerseIndex::try_new(
+                Book::try_from(record.start_book_id as u8)?,
+                record.start_chapter as u8,
+                record.start_number as u8,
+            )
+            .ok_or(anyhow!("Nevalidní index verše v databázi"))?;
+
+            let to = VerseIndex::try_new(
+                Book::try_from(record.end_book_id as u8)?,
+                record.end_chapter as u8,
+                record.end_number as u8,
+            )
+            .ok_or(anyhow!("Nevalidní index verše v databázi"))?;
+
+            Ok((from, to))
+        })
+        .collect()
+}
+
+/// Nastaví plánu `plan_id` datum prvního dne na `start_date`.
+pub async fn set_start_date(plan_id: i64, start_date: NaiveDate, pool: &SqlitePool) -> Result<()> {
+    let start_date = start_date.format(DB_DATE_FORMAT).to_string();
+    query!(
+        "UPDATE reading_plans SET start_date = $1 WHERE id = $2",
+        start_date,
+        plan_id
+    )
+    .execute(pool)
+    .await
+    .with_context(|| format!("Nelze nastavit začátek plánu čtení s id {plan_id}"))?;
+
+    Ok(())
+}
+
+/// Smaže plán čtení `plan_id` i se všemi jeho čteními.
+pub async fn delete(plan_id: i64, pool: &SqlitePool) -> Result<()> {
+    query!("DELETE FROM reading_plans WHERE id = $1", plan_id)
+        .execute(pool)
+        .await
+        .with_context(|| format!("Nelze smazat plán čtení s id {plan_id} z databáze"))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn index(book: Book, chapter: u8, verse: u8) -> VerseIndex {
+        VerseIndex::try_new(book, chapter, verse).unwrap()
+    }
+
+    #[test]
+    fn parse_whole_chapters() {
+        assert_eq!(
+            parse_reading("Genesis 1").unwrap(),
+            (index(Book::Genesis, 1, 1), index(Book::Genesis, 1, 31))
+        );
+        assert_eq!(
+            parse_reading("Rút 1-2").unwrap(),
+            (index(Book::Ruth, 1, 1), index(Book::Ruth, 2, 23))
+        );
+        assert_eq!(
+            parse_reading("Píseň písní 2").unwrap(),
+            (
+                index(Book::SongOfSolomon, 2, 1),
+                index(Book::SongOfSolomon, 2, 17)
+            )
+        );
+    }
+
+    #[test]
+    fn parse_verse_ranges() {
+        assert_eq!(
+            parse_reading("Jan 3:16-21").unwrap(),
+            (index(Book::John, 3, 16), index(Book::John, 3, 21))
+        );
+        assert_eq!(
+            parse_reading("Jan 3:16 - 4:2").unwrap(),
+            (index(Book::John, 3, 16), index(Book::John, 4, 2))
+        );
+        assert_eq!(
+            parse_reading("1. Sam 3:1").unwrap(),
+            (index(Book::Samuel1, 3, 1), index(Book::Samuel1, 3, 1))
+        );
+        assert_eq!(
+            parse_reading("Jan 21:24 - Skutky 1:2").unwrap(),
+            (index(Book::John, 21, 24), index(Book::Acts, 1, 2))
+        );
+    }
+
+    #[test]
+    fn parse_invalid_readings() {
+        assert!(parse_reading("Jan").is_err());
+        assert!(parse_reading("Jan 22").is_err());
+        assert!(parse_reading("Jan 3:40").is_err());
+        assert!(parse_reading("Jan 3:16-10").is_err());
+        assert!(parse_reading("Ma 1").is_err());
+    }
+
+    #[test]
+    fn parse_plan() {
+        let input = "# Komentář\nGenesis 1; Matouš 1\n\nGenesis 2;Matouš 2;\n";
+        let plan = ReadingPlan::parse("Test", input).unwrap();
+
+        assert_eq!(plan.days.len(), 2);
+        assert_eq!(plan.days[0].len(), 2);
+        assert_eq!(plan.days[1][1].0, index(Book::Matthew, 2, 1));
+
+        let err = ReadingPlan::parse("Test", "Genesis 1\nXyz 1").unwrap_err();
+        assert!(format!("{err:#}").contains("řádku 2"));
+        assert!(ReadingPlan::parse("Test", "# Jen komentář").is_err());
+    }
+
+    #[test]
+    fn plan_day_wraps_around() {
+        let info = ReadingPlanInfo {
+            id: 1,
+            name: String::from("Test"),
+            start_date: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            num_days: 365,
+        };
+
+        assert_eq!(
+            info.day_for(NaiveDate::from_ymd_opt(2024, 12, 31).unwrap()),
+            None
+        );
+        assert_eq!(
+            info.day_for(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()),
+            Some(1)
+        );
+        assert_eq!(
+            info.day_for(NaiveDate::from_ymd_opt(2025, 12, 31).unwrap()),
+            Some(365)
+        );
+        assert_eq!(
+            info.day_for(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()),
+            Some(1)
+        );
+    }
+}
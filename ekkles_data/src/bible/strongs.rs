@@ -0,0 +1,229 @@
+//! Modul pro Strongova čísla (interlineární data) u překladů, které je obsahují.
+//!
+//! V XML překladu jsou označená slova podelementy verše s atributem `strong`
+//! (`<w strong="H7225">počátku</w>`), `str` (formát Zefania, `<gr str="7225">`) nebo
+//! `lemma` ve tvaru OSIS (`<w lemma="strong:H7225">`). Při importu se text verše uloží
+//! bez značek (promítá se tedy stejně jako u ostatních překladů) a označená slova se
+//! uloží zvlášť, slouží pouze k interlineárnímu zobrazení při přípravě kázání.
+
+use anyhow::{Context, Result};
+use roxmltree::Node;
+use sqlx::{Sqlite, pool::PoolConnection, query};
+
+use super::{heading::in_heading, indexing::VerseIndex};
+
+/// Atributy, ve kterých jsou Strongova čísla přímo
+const XML_STRONG_ATTRIBUTES: [&str; 2] = ["strong", "str"];
+/// Atribut `lemma` ve tvaru OSIS, Strongova čísla jsou v hodnotách s prefixem [`OSIS_STRONG_PREFIX`]
+const XML_LEMMA_ATTRIBUTE: &str = "lemma";
+const OSIS_STRONG_PREFIX: &str = "strong:";
+/// Počet knih Starého zákona, čísla bez prefixu se podle knihy doplní o `H` nebo `G`
+const NUM_BOOKS_IN_THE_OLD_TESTAMENT: u32 = 39;
+
+/// Slovo verše označené Strongovými čísly
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaggedWord {
+    pub word: String,
+    /// Strongova čísla s prefixem `H` (hebrejština) nebo `G` (řečtina), např. `H7225`
+    pub strongs: Vec<String>,
+}
+
+/// Vrátí text verše `verse` bez značek a slova označená Strongovými čísly v pořadí,
+/// v jakém jsou ve verši. `book_order` (od 0) určuje, zda jde o Starý zákon, podle toho
+/// se čísla bez prefixu doplní o `H` nebo `G`. Nadpisy uvnitř verše (viz [`super::heading`])
+/// se do textu ani označených slov nezapočítávají. Pokud verš neobsahuje žádný text,
+/// vrací `None`.
+pub(crate) fn parse_verse(verse: Node, book_order: u32) -> Option<(String, Vec<TaggedWord>)> {
+    let content: String = verse
+        .descendants()
+        .filter(|node| node.is_text() && !in_heading(*node))
+        .filter_map(|node| node.text())
+        .collect();
+
+    if content.is_empty() {
+        return None;
+    }
+
+    let prefix = if book_order < NUM_BOOKS_IN_THE_OLD_TESTAMENT {
+        'H'
+    } else {
+        'G'
+    };
+
+    let tagged_words = verse
+        .descendants()
+        .filter(|node| node.is_element() && *node != verse && !in_heading(*node))
+        .filter_map(|node| {
+            let strongs: Vec<String> = node_strongs(node)
+                .map(|strong| normalize_strong(strong, prefix))
+                .collect();
+
+            if strongs.is_empty() {
+                return None;
+            }
+
+            let word: String = node
+                .descendants()
+                .filter(|node| node.is_text())
+                .filter_map(|node| node.text())
+                .collect();
+
+            Some(TaggedWord {
+                word: word.trim().to_string(),
+                strongs,
+            })
+        })
+        .collect();
+
+    Some((content, tagged_words))
+}
+
+/// Vrátí surová Strongova čísla z atributů uzlu `node`
+fn node_strongs<'a>(node: Node<'a, '_>) -> impl Iterator<Item = &'a str> {
+    let direct = XML_STRONG_ATTRIBUTES
+        .iter()
+        .filter_map(move |attribute| node.attribute(*attribute))
+        .flat_map(str::split_whitespace);
+
+    let lemma = node
+        .attribute(XML_LEMMA_ATTRIBUTE)
+        .into_iter()
+        .flat_map(str::split_whitespace)
+        .filter_map(|value| value.strip_prefix(OSIS_STRONG_PREFIX));
+
+    direct.chain(lemma)
+}
+
+/// Sjednotí zápis Strongova čísla, číslo bez prefixu doplní o `prefix` a odstraní
+/// úvodní nuly (`07225` -> `H7225`)
+fn normalize_strong(raw: &str, prefix: char) -> String {
+    let (prefix, number) = match raw.chars().next() {
+        Some(first) if first.is_ascii_alphabetic() => (first.to_ascii_uppercase(), &raw[1..]),
+        _ => (prefix, raw),
+    };
+
+    let number = number.trim_start_matches('0');
+    format!("{prefix}{number}")
+}
+
+/// Načte označená slova veršů pasáže `from` až `to` v překladu `translation_id`.
+/// Vrací dvojice (číslo verše, označená slova) v pořadí veršů, verše bez označených
+/// slov vynechá.
+pub async fn load_for_passage(
+    from: VerseIndex,
+    to: VerseIndex,
+    translation_id: i64,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<Vec<(u8, Vec<TaggedWord>)>> {
+    let (from_book, from_chapter, from_number) = from.destructure_numeric();
+    let (to_book, to_chapter, to_number) = to.destructure_numeric();
+
+    let records = query!(
+        "SELECT v.number, s.word, s.strongs FROM verse_strongs s JOIN verses v ON v.translation_id = s.translation_id AND v.book_id = s.book_id AND v.chapter = s.chapter AND v.number = s.number
+        WHERE v.translation_id = $1
+        AND v.verse_order >= (SELECT verse_order FROM verses WHERE translation_id = $1 AND book_id = $2 AND chapter = $3 AND number = $4)
+        AND v.verse_order <= (SELECT verse_order FROM verses WHERE translation_id = $1 AND book_id = $5 AND chapter = $6 AND number = $7)
+        ORDER BY v.verse_order ASC, s.word_order ASC",
+        translation_id,
+        from_book,
+        from_chapter,
+        from_number,
+        to_book,
+        to_chapter,
+        to_number
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .with_context(|| format!("Nelze načíst Strongova čísla pasáže {from} - {to}"))?;
+
+    let mut verses: Vec<(u8, Vec<TaggedWord>)> = Vec::new();
+    for record in records {
+        let number = record.number as u8;
+        let word = TaggedWord {
+            word: record.word,
+            strongs: record
+                .strongs
+                .split_whitespace()
+                .map(String::from)
+                .collect(),
+        };
+
+        match verses.last_mut() {
+            Some((last_number, words)) if *last_number == number => words.push(word),
+            _ => verses.push((number, vec![word])),
+        }
+    }
+
+    Ok(verses)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use roxmltree::Document;
+
+    use super::*;
+
+    fn parse(xml: &str, book_order: u32) -> Option<(String, Vec<TaggedWord>)> {
+        let document = Document::parse(xml).unwrap();
+        parse_verse(document.root_element(), book_order)
+    }
+
+    fn tagged(word: &str, strongs: &[&str]) -> TaggedWord {
+        TaggedWord {
+            word: word.to_string(),
+            strongs: strongs.iter().map(|strong| strong.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn plain_verse_has_no_tags() {
+        assert_eq!(
+            parse(r#"<verse number="1">Na počátku bylo Slovo</verse>"#, 42),
+            Some((String::from("Na počátku bylo Slovo"), vec![]))
+        );
+        assert_eq!(parse(r#"<verse number="1"/>"#, 42), None);
+    }
+
+    #[test]
+    fn tagged_verse_keeps_plain_text() {
+        let (content, words) = parse(
+            r#"<verse number="1"><w strong="H7225">Na počátku</w> <w strong="H1254">stvořil</w> Bůh</verse>"#,
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(content, "Na počátku stvořil Bůh");
+        assert_eq!(
+            words,
+            vec![
+                tagged("Na počátku", &["H7225"]),
+                tagged("stvořil", &["H1254"])
+            ]
+        );
+    }
+
+    #[test]
+    fn strongs_are_normalized() {
+        let (_, words) = parse(
+            r#"<verse number="1"><gr str="3056">Slovo</gr> <w lemma="strong:G2316 strong:g3588 lemma.TR:theos">Bůh</w></verse>"#,
+            42,
+        )
+        .unwrap();
+
+        assert_eq!(
+            words,
+            vec![
+                tagged("Slovo", &["G3056"]),
+                tagged("Bůh", &["G2316", "G3588"])
+            ]
+        );
+
+        let (_, words) = parse(
+            r#"<verse number="1"><gr str="07225">počátku</gr></verse>"#,
+            0,
+        )
+        .unwrap();
+        assert_eq!(words, vec![tagged("počátku", &["H7225"])]);
+    }
+}
@@ -0,0 +1,289 @@
+//! Cache veršů načtených pasáží, viz [`super::indexing::Passage::load()`]. Náhled pasáže
+//! se při jejím vybírání načítá s každou změnou vstupu znovu a tytéž verše se tak z databáze
+//! četly opakovaně.
+//!
+//! Velikost cache je omezená (viz [`set_capacity()`]), při jejím překročení se zahazují
+//! nejdéle nepoužité pasáže. Text veršů je internovaný, překrývající se pasáže (Jan 3:16-18
+//! a Jan 3:16-21) tak drží každý verš v paměti pouze jednou. Velikost se počítá jako součet
+//! délek textů veršů (bez režie datových struktur), jde tedy o přibližnou hodnotu.
+//!
+//! Pasáže se rozlišují podle názvu překladu, ne podle jeho id. Id se v různých databázích
+//! (profilech) opakují, název překladu je v databázi jedinečný.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use lazy_static::lazy_static;
+
+use super::indexing::VerseIndex;
+
+/// Výchozí velikost cache v bajtech (celá Bible má kolem 4 MB textu)
+pub const DEFAULT_CAPACITY_BYTES: usize = 16 * 1024 * 1024;
+
+lazy_static! {
+    static ref VERSE_CACHE: Mutex<VerseCache> = Mutex::new(VerseCache::new(DEFAULT_CAPACITY_BYTES));
+}
+
+/// Statistiky cache (pro diagnostiku)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerseCacheStats {
+    /// Kolikrát byla pasáž nalezena v cache
+    pub hits: u64,
+    /// Kolikrát pasáž v cache nebyla a načítala se z databáze
+    pub misses: u64,
+    /// Kolik pasáží bylo zahozeno kvůli velikosti cache
+    pub evictions: u64,
+    /// Počet pasáží v cache
+    pub passages: usize,
+    /// Počet různých (internovaných) textů veršů v cache
+    pub verses: usize,
+    /// Přibližná velikost cache v bajtech
+    pub bytes: usize,
+    /// Nejvyšší velikost cache v bajtech, 0 = cache je vypnutá
+    pub capacity: usize,
+}
+
+/// Klíč pasáže v cache (název překladu, první verš, poslední verš)
+type PassageKey = (String, VerseIndex, VerseIndex);
+
+struct CachedPassage {
+    verses: Vec<(u8, Arc<str>)>,
+    /// Hodnota [`VerseCache::clock`] při posledním použití
+    last_used: u64,
+}
+
+struct VerseCache {
+    capacity: usize,
+    passages: BTreeMap<PassageKey, CachedPassage>,
+    /// Internované texty veršů, sdílí je všechny pasáže v cache
+    interned: HashSet<Arc<str>>,
+    /// Součet délek internovaných textů
+    bytes: usize,
+    /// Počítadlo přístupů, podle něj se určuje nejdéle nepoužitá pasáž
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl VerseCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            passages: BTreeMap::new(),
+            interned: HashSet::new(),
+            bytes: 0,
+            clock: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    fn get(&mut self, key: PassageKey) -> Option<Vec<(u8, String)>> {
+        self.clock += 1;
+        match self.passages.get_mut(&key) {
+            Some(passage) => {
+                passage.last_used = self.clock;
+                self.hits += 1;
+                Some(
+                    passage
+                        .verses
+                        .iter()
+                        .map(|(number, content)| (*number, content.to_string()))
+                        .collect(),
+                )
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&mut self, key: PassageKey, verses: &[(u8, String)]) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.clock += 1;
+        let verses = verses
+            .iter()
+            .map(|(number, content)| (*number, self.intern(content)))
+            .collect();
+        let replaced = self.passages.insert(
+            key,
+            CachedPassage {
+                verses,
+                last_used: self.clock,
+            },
+        );
+        if replaced.is_some() {
+            self.release_unused();
+        }
+
+        self.shrink();
+    }
+
+    /// Vrátí internovaný text `content`, pokud v cache ještě není, přidá jej
+    fn intern(&mut self, content: &str) -> Arc<str> {
+        if let Some(interned) = self.interned.get(content) {
+            return interned.clone();
+        }
+
+        let interned: Arc<str> = Arc::from(content);
+        self.bytes += content.len();
+        self.interned.insert(interned.clone());
+        interned
+    }
+
+    /// Zahodí internované texty, které už nepoužívá žádná pasáž
+    fn release_unused(&mut self) {
+        let bytes = &mut self.bytes;
+        self.interned.retain(|content| {
+            // Jedna reference je v `interned`, ostatní v pasážích
+            let used = Arc::strong_count(content) > 1;
+            if !used {
+                *bytes -= content.len();
+            }
+            used
+        });
+    }
+
+    /// Zahazuje nejdéle nepoužité pasáže, dokud cache nemá nejvýše `capacity` bajtů
+    fn shrink(&mut self) {
+        while self.bytes > self.capacity {
+            let Some(oldest) = self
+                .passages
+                .iter()
+                .min_by_key(|(_, passage)| passage.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+
+            self.passages.remove(&oldest);
+            self.evictions += 1;
+            self.release_unused();
+        }
+    }
+
+    fn stats(&self) -> VerseCacheStats {
+        VerseCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+            passages: self.passages.len(),
+            verses: self.interned.len(),
+            bytes: self.bytes,
+            capacity: self.capacity,
+        }
+    }
+}
+
+fn with_cache<T>(f: impl FnOnce(&mut VerseCache) -> T) -> T {
+    f(&mut VERSE_CACHE.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Nastaví nejvyšší velikost cache v bajtech (0 = cache je vypnutá), pokud je cache
+/// větší, zahodí nejdéle nepoužité pasáže.
+pub fn set_capacity(capacity: usize) {
+    with_cache(|cache| {
+        cache.capacity = capacity;
+        cache.shrink();
+    });
+}
+
+/// Vrátí aktuální statistiky cache
+pub fn stats() -> VerseCacheStats {
+    with_cache(|cache| cache.stats())
+}
+
+/// Vrátí verše pasáže od `from` do `to` v překladu `translation_name`, pokud je v cache
+pub(crate) fn get(
+    translation_name: &str,
+    from: VerseIndex,
+    to: VerseIndex,
+) -> Option<Vec<(u8, String)>> {
+    with_cache(|cache| cache.get((translation_name.to_string(), from, to)))
+}
+
+/// Uloží verše načtené pasáže od `from` do `to` v překladu `translation_name` do cache
+pub(crate) fn insert(
+    translation_name: &str,
+    from: VerseIndex,
+    to: VerseIndex,
+    verses: &[(u8, String)],
+) {
+    with_cache(|cache| cache.insert((translation_name.to_string(), from, to), verses));
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::bible::indexing::Book;
+
+    fn john_3(from: u8, to: u8) -> PassageKey {
+        (
+            String::from("ČEP"),
+            VerseIndex::try_new(Book::John, 3, from).unwrap(),
+            VerseIndex::try_new(Book::John, 3, to).unwrap(),
+        )
+    }
+
+    fn verses(from: u8, to: u8) -> Vec<(u8, String)> {
+        (from..=to)
+            .map(|number| (number, format!("Verš {number:02}")))
+            .collect()
+    }
+
+    #[test]
+    fn overlapping_passages_share_verses() {
+        let mut cache = VerseCache::new(DEFAULT_CAPACITY_BYTES);
+
+        cache.insert(john_3(16, 18), &verses(16, 18));
+        cache.insert(john_3(16, 21), &verses(16, 21));
+
+        assert_eq!(cache.get(john_3(16, 21)), Some(verses(16, 21)));
+        assert_eq!(
+            cache.get((String::from("B21"), john_3(16, 21).1, john_3(16, 21).2)),
+            None
+        );
+        assert_eq!(cache.get(john_3(1, 2)), None);
+        let stats = cache.stats();
+        assert_eq!((stats.passages, stats.verses), (2, 6));
+        assert_eq!(stats.bytes, 6 * "Verš 16".len());
+        assert_eq!((stats.hits, stats.misses), (1, 2));
+    }
+
+    #[test]
+    fn least_recently_used_passage_is_evicted() {
+        let verse_len = "Verš 01".len();
+        let mut cache = VerseCache::new(5 * verse_len);
+
+        cache.insert(john_3(1, 2), &verses(1, 2));
+        cache.insert(john_3(3, 4), &verses(3, 4));
+        cache.get(john_3(1, 2));
+        cache.insert(john_3(5, 6), &verses(5, 6));
+
+        assert!(cache.get(john_3(3, 4)).is_none());
+        assert!(cache.get(john_3(1, 2)).is_some());
+        let stats = cache.stats();
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.bytes, 4 * verse_len);
+    }
+
+    #[test]
+    fn zero_capacity_disables_cache() {
+        let mut cache = VerseCache::new(0);
+
+        cache.insert(john_3(16, 18), &verses(16, 18));
+
+        assert_eq!(cache.get(john_3(16, 18)), None);
+        assert_eq!(cache.stats().bytes, 0);
+    }
+}
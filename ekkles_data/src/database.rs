@@ -2,14 +2,28 @@
 
 use std::path::Path;
 
-use anyhow::{Context, Result};
-use sqlx::{SqlitePool, query, sqlite::SqliteConnectOptions};
+use anyhow::{Context, Result, bail};
+use sqlx::{
+    SqlitePool,
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode},
+};
 use tokio::fs::{DirBuilder, OpenOptions};
 
+/// Migrace schématu databáze ze složky `migrations`, aplikují se postupně podle verze.
+/// Změna schématu se zapisuje jako nová migrace `<verze>_<popis>.sql`, již vydané
+/// migrace se nemění.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
 /// Připojí se k SQLite databázi na cestě `db_path`, pokud se připojení nezdaří, vrátí Error.
+///
+/// Všechna připojení mají zapnuté cizí klíče (bez nich by se neprováděla kaskádová mazání
+/// `ON DELETE CASCADE`) a databáze je v režimu WAL, čtení tak neblokuje zápis a naopak.
 pub async fn open_database(db_path: impl AsRef<Path>) -> Result<SqlitePool> {
     let db_options = SqliteConnectOptions::new()
         .filename(db_path)
+        .foreign_keys(true)
+        .journal_mode(SqliteJournalMode::Wal)
         .optimize_on_close(true, None);
 
     let db_pool = SqlitePool::connect_with(db_options)
@@ -19,7 +33,16 @@ pub async fn open_database(db_path: impl AsRef<Path>) -> Result<SqlitePool> {
     Ok(db_pool)
 }
 
-/// Vytvoří novou databázi na cestě `path` a nalije do ní prázdnou databázi Ekklesu.
+/// Aplikuje na databázi `db` všechny migrace, které na ní ještě neproběhly
+/// (databáze vytvořená starší verzí Ekklesu se tím aktualizuje na aktuální schéma).
+pub async fn migrate(db: &SqlitePool) -> Result<()> {
+    MIGRATOR
+        .run(db)
+        .await
+        .context("Nelze aktualizovat schéma databáze")
+}
+
+/// Vytvoří novou databázi na cestě `path` a aplikuje na ni všechny migrace, viz [`MIGRATOR`].
 ///
 /// - Pokud na cestě `path` existuje nějaký soubor bude přepsán!
 pub async fn create_new_database(path: impl AsRef<Path>) -> Result<SqlitePool> {
@@ -56,180 +79,34 @@ pub async fn create_new_database(path: impl AsRef<Path>) -> Result<SqlitePool> {
 
     let db = open_database(path.as_ref()).await?;
 
-    query!("
-        DROP TABLE IF EXISTS songs;
-        DROP TABLE IF EXISTS song_parts;
-        DROP TABLE IF EXISTS translations;
-        DROP TABLE IF EXISTS books;
-        DROP TABLE IF EXISTS verses;
-        DROP TABLE IF EXISTS playlists;
-        DROP TABLE IF EXISTS playlist_parts;
-        DROP TABLE IF EXISTS playlist_songs;
-        DROP TABLE IF EXISTS playlist_passages;
-
-        CREATE TABLE IF NOT EXISTS songs (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            title TEXT NOT NULL UNIQUE,
-            author TEXT,
-            part_order TEXT NOT NULL -- Vektor uložený jako text, trochu hack
-        );
-
-        CREATE TABLE IF NOT EXISTS song_parts (
-            song_id INTEGER NOT NULL,
-            tag TEXT NOT NULL,
-            lyrics TEXT NOT NULL,
-            PRIMARY KEY (song_id, tag),
-            FOREIGN KEY (song_id) REFERENCES songs (id) ON DELETE CASCADE -- Při smazání písně budou automaticky smazány všechny její části
-        );
-
-        CREATE TABLE IF NOT EXISTS translations (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL UNIQUE
-        );
-
-        CREATE TABLE IF NOT EXISTS books (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            book_order INTEGER NOT NULL UNIQUE, -- Pořadí knih v Bible (Genesis, Exodus, ... Zjevení)
-            title TEXT NOT NULL UNIQUE
-        );
-
-        CREATE TABLE IF NOT EXISTS verses (
-            translation_id INTEGER NOT NULL,
-            book_id INTEGER NOT NULL,
-            chapter INTEGER NOT NULL,
-            number INTEGER NOT NULL,
-            content TEXT NOT NULL,
-            -- Pořadí veršů v daném překladu, abychom se mohli jednoduše dotazovat na rozsahy
-            verse_order INTEGER NOT NULL,
-            PRIMARY KEY (translation_id, book_id, chapter, number),
-            FOREIGN KEY (book_id) REFERENCES books (id),
-            FOREIGN KEY (translation_id) REFERENCES translations (id)
-        );
-
-        CREATE TABLE IF NOT EXISTS playlists (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL UNIQUE,
-            -- Kdy byl playlist vytvořen, může být použito pro řazení playlistů
-            created TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
-        );
-
-        -- playlist_part může být buď pasáž z Bible nebo píseň (v budoucnu možná další),
-        -- vytvoříme tedy pro každou možnost separátní tabulku, ze které se budeme odkazovat
-        -- na PK tabulky `playlist_parts`
-        CREATE TABLE IF NOT EXISTS playlist_parts (
-            playlist_id INTEGER NOT NULL,
-            part_order INTEGER NOT NULL,
-            kind TEXT NOT NULL CHECK (kind IN ('song', 'bible')),
-            PRIMARY KEY (playlist_id, part_order),
-            FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE CASCADE
-        );
-
-        CREATE TABLE IF NOT EXISTS playlist_songs (
-            playlist_id INTEGER NOT NULL,
-            part_order INTEGER NOT NULL,
-            song_id INTEGER NOT NULL,
-            PRIMARY KEY (playlist_id, part_order),
-            FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE CASCADE,
-            FOREIGN KEY (song_id) REFERENCES songs (id)
-        );
-
-        CREATE TABLE IF NOT EXISTS playlist_passages (
-            playlist_id INTEGER NOT NULL,
-            part_order INTEGER NOT NULL,
-            translation_id INTEGER NOT NULL,
-            start_book_id INTEGER NOT NULL,
-            start_chapter INTEGER NOT NULL,
-            start_number INTEGER NOT NULL,
-            end_book_id INTEGER NOT NULL,
-            end_chapter INTEGER NOT NULL,
-            end_number INTEGER NOT NULL,
-            PRIMARY KEY (playlist_id, part_order),
-            FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE CASCADE,
-            FOREIGN KEY (translation_id, start_book_id, start_chapter, start_number) REFERENCES verses (translation_id, book_id, chapter, number),
-            FOREIGN KEY (translation_id, end_book_id, end_chapter, end_number) REFERENCES verses (translation_id, book_id, chapter, number)
-        );
-
-        INSERT INTO books (id, book_order, title) VALUES
-            (0, 0, 'Genesis'),
-            (1, 1, 'Exodus'),
-            (2, 2, 'Leviticus'),
-            (3, 3, 'Numeri'),
-            (4, 4, 'Deuteronomium'),
-            (5, 5, 'Jozue'),
-            (6, 6, 'Soudců'),
-            (7, 7, 'Rút'),
-            (8, 8, '1. Samuelova'),
-            (9, 9, '2. Samuelova'),
-            (10, 10, '1. Královská'),
-            (11, 11, '2. Královská'),
-            (12, 12, '1. Paralipomenon'),
-            (13, 13, '2. Paralipomenon'),
-            (14, 14, 'Ezdráš'),
-            (15, 15, 'Nehemjáš'),
-            (16, 16, 'Ester'),
-            (17, 17, 'Jób'),
-            (18, 18, 'Žalmy'),
-            (19, 19, 'Přísloví'),
-            (20, 20, 'Kazatel'),
-            (21, 21, 'Píseň písní'),
-            (22, 22, 'Izajáš'),
-            (23, 23, 'Jeremjáš'),
-            (24, 24, 'Pláč'),
-            (25, 25, 'Ezechiel'),
-            (26, 26, 'Daniel'),
-            (27, 27, 'Ozeáš'),
-            (28, 28, 'Jóel'),
-            (29, 29, 'Ámos'),
-            (30, 30, 'Abdijáš'),
-            (31, 31, 'Jonáš'),
-            (32, 32, 'Micheáš'),
-            (33, 33, 'Nahum'),
-            (34, 34, 'Abakuk'),
-            (35, 35, 'Sofonjáš'),
-            (36, 36, 'Ageus'),
-            (37, 37, 'Zacharjáš'),
-            (38, 38, 'Malachiáš'),
-            (39, 39, 'Matouš'),
-            (40, 40, 'Marek'),
-            (41, 41, 'Lukáš'),
-            (42, 42, 'Jan'),
-            (43, 43, 'Skutky'),
-            (44, 44, 'Římanům'),
-            (45, 45, '1. Korintským'),
-            (46, 46, '2. Korintským'),
-            (47, 47, 'Galatským'),
-            (48, 48, 'Efezským'),
-            (49, 49, 'Filipským'),
-            (50, 50, 'Koloským'),
-            (51, 51, '1. Tesalonickým'),
-            (52, 52, '2. Tesalonickým'),
-            (53, 53, '1. Timoteovi'),
-            (54, 54, '2. Timoteovi'),
-            (55, 55, 'Titovi'),
-            (56, 56, 'Filemonovi'),
-            (57, 57, 'Židům'),
-            (58, 58, 'Jakub'),
-            (59, 59, '1. Petrova'),
-            (60, 60, '2. Petrova'),
-            (61, 61, '1. Janova'),
-            (62, 62, '2. Janova'),
-            (63, 63, '3. Janova'),
-            (64, 64, 'Juda'),
-            (65, 65, 'Zjevení');
-        ")
+    migrate(&db).await?;
+
+    Ok(db)
+}
+
+/// Provede checkpoint databáze (zapíše případný WAL do hlavního souboru) a uzavře všechna
+/// připojení v poolu. Volá se při ukončování programu, po zavolání už nelze pool používat.
+pub async fn checkpoint_and_close(db: SqlitePool) -> Result<()> {
+    // Pokud databáze není v režimu WAL, je checkpoint no-op
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
         .execute(&db)
         .await
-        .context("Nelze inicializovat databázi")?;
+        .context("Nelze provést checkpoint databáze")?;
 
-    Ok(db)
-    // todo!()
+    db.close().await;
+
+    Ok(())
 }
 
-/// Otvře databázi na cestě `path`, pokud neexistuje, bude vytvořena a inicializována.
-/// Pokud se na této cestě předtím vyskytoval jiný soubor, bude přepsán.
+/// Otvře databázi na cestě `path` a aplikuje na ni čekající migrace, pokud neexistuje,
+/// bude vytvořena a inicializována. Pokud se na této cestě předtím vyskytoval jiný soubor,
+/// bude přepsán.
 pub async fn open_or_create_database(path: impl AsRef<Path>) -> Result<SqlitePool> {
     match open_database(path.as_ref()).await {
-        Ok(db) => Ok(db),
+        Ok(db) => {
+            migrate(&db).await?;
+            Ok(db)
+        }
         Err(_) => create_new_database(path.as_ref()).await.with_context(|| {
             format!(
                 "Nelze vytvořit nový soubor pro databázi na {}",
@@ -238,3 +115,70 @@ pub async fn open_or_create_database(path: impl AsRef<Path>) -> Result<SqlitePoo
         }),
     }
 }
+
+/// Přehled databáze pro diagnostiku (verze schématu a počty dat)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseOverview {
+    /// Verze poslední úspěšně aplikované migrace, `None` u databáze bez migrací
+    pub schema_version: Option<i64>,
+    /// Verze poslední migrace, kterou zná tato verze Ekklesu, viz [`MIGRATOR`]
+    pub latest_schema_version: Option<i64>,
+    pub songs: i64,
+    pub playlists: i64,
+    pub translations: i64,
+    pub media: i64,
+}
+
+/// Načte přehled databáze `db` (verze schématu a počty písní, playlistů, ...), viz
+/// [`DatabaseOverview`]. Pokud se čtení nepovede, vrací Error.
+pub async fn overview(db: &SqlitePool) -> Result<DatabaseOverview> {
+    let schema_version: Option<i64> =
+        sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1")
+            .fetch_one(db)
+            .await
+            .context("Nelze přečíst verzi schématu databáze")?;
+
+    let count = |table: &'static str| {
+        let sql = format!("SELECT COUNT(*) FROM {table}");
+        async move {
+            sqlx::query_scalar::<_, i64>(&sql)
+                .fetch_one(db)
+                .await
+                .with_context(|| format!("Nelze spočítat řádky tabulky {table}"))
+        }
+    };
+
+    Ok(DatabaseOverview {
+        schema_version,
+        latest_schema_version: MIGRATOR.iter().map(|migration| migration.version).max(),
+        songs: count("songs").await?,
+        playlists: count("playlists").await?,
+        translations: count("translations").await?,
+        media: count("media").await?,
+    })
+}
+
+/// Otevře existující databázi na cestě `path` a ověří, že jde o databázi Ekklesu
+/// (obsahuje tabulky s písněmi a playlisty), poté na ni aplikuje čekající migrace.
+/// Pokud soubor neexistuje nebo jde o jinou SQLite databázi, vrací Error a pool je uzavřen.
+pub async fn open_ekkles_database(path: impl AsRef<Path>) -> Result<SqlitePool> {
+    let db = open_database(path.as_ref())
+        .await
+        .with_context(|| format!("Nelze otevřít databázi na {}", path.as_ref().display()))?;
+
+    let tables: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN ('songs', 'playlists')",
+    )
+    .fetch_one(&db)
+    .await
+    .context("Nelze přečíst schéma databáze")?;
+
+    if tables != 2 {
+        db.close().await;
+        bail!("Soubor {} není databáze Ekklesu", path.as_ref().display());
+    }
+
+    migrate(&db).await?;
+
+    Ok(db)
+}
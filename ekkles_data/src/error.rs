@@ -0,0 +1,142 @@
+//! Typované chyby vracené moduly pracujícími s databází ([`crate::song_db`],
+//! [`crate::playlist`], [`crate::bible`]).
+//!
+//! Volající tak může rozlišit např. obsazený název playlistu ([`EkklesDataError::Conflict`])
+//! od nedostupné databáze ([`EkklesDataError::Db`]) a podle toho reagovat, místo aby chybu
+//! pouze zobrazil. Uvnitř modulů se chyby dál skládají pomocí `anyhow` (kontext), na hranici
+//! modulu se převedou implementací `From<anyhow::Error>`. `EkklesDataError` je běžná chyba,
+//! lze na ni tedy volat `.context()` i `?` do `anyhow`.
+
+use std::{error::Error, fmt};
+
+/// Výsledek operace nad databází, viz [`EkklesDataError`]
+pub type Result<T, E = EkklesDataError> = std::result::Result<T, E>;
+
+/// Chyba při práci s daty v databázi
+#[derive(thiserror::Error)]
+pub enum EkklesDataError {
+    /// Hledaný záznam (píseň, playlist, pasáž, ...) v databázi neexistuje
+    #[error("{0}")]
+    NotFound(String),
+    /// Operace koliduje s existujícími daty (obsazený název, píseň použitá v playlistu, ...)
+    #[error("{0}")]
+    Conflict(String),
+    /// Data nejsou validní (porušený invariant písně, nevalidní XML, neplatný index, ...)
+    #[error("{0}")]
+    InvalidData(String),
+    /// Chyba databáze samotné (nedostupná, poškozená, zamčená, ...)
+    #[error("{context}")]
+    Db {
+        context: String,
+        #[source]
+        source: sqlx::Error,
+    },
+}
+
+impl EkklesDataError {
+    /// Zatřídí chybu databáze `source`, ke které došlo při `context`. Nenalezený řádek
+    /// je [`EkklesDataError::NotFound`], porušení jedinečnosti (např. názvu písně)
+    /// je [`EkklesDataError::Conflict`], ostatní jsou [`EkklesDataError::Db`].
+    pub(crate) fn from_sqlx(context: String, source: sqlx::Error) -> Self {
+        if matches!(source, sqlx::Error::RowNotFound) {
+            return Self::NotFound(context);
+        }
+
+        let unique_violation = source
+            .as_database_error()
+            .is_some_and(|e| e.is_unique_violation());
+        if unique_violation {
+            Self::Conflict(format!("{context}: {source}"))
+        } else {
+            Self::Db { context, source }
+        }
+    }
+
+    /// Jestli hledaný záznam neexistuje, viz [`EkklesDataError::NotFound`]
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::NotFound(_))
+    }
+
+    /// Jestli operace koliduje s existujícími daty, viz [`EkklesDataError::Conflict`]
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, Self::Conflict(_))
+    }
+}
+
+/// Převede chybu z `anyhow` (s kontextem) na typovanou chybu:
+/// - pokud je jejím původcem `EkklesDataError`, vrátí jej (kontext navíc se zahodí)
+/// - pokud je jejím původcem chyba sqlx, zatřídí ji (viz [`EkklesDataError::from_sqlx`])
+///   s vnějším kontextem jako popisem
+/// - jinak jde o chybu v datech, [`EkklesDataError::InvalidData`] s celým řetězcem kontextů
+impl From<anyhow::Error> for EkklesDataError {
+    fn from(err: anyhow::Error) -> Self {
+        let context = err.to_string();
+        let err = match err.downcast::<EkklesDataError>() {
+            Ok(err) => return err,
+            Err(err) => err,
+        };
+
+        match err.downcast::<sqlx::Error>() {
+            // Bez kontextu by byl popis stejný jako u zdroje
+            Ok(source) if context == source.to_string() => {
+                Self::from_sqlx(String::from("Chyba při přístupu do databáze"), source)
+            }
+            Ok(source) => Self::from_sqlx(context, source),
+            Err(err) => Self::InvalidData(format!("{err:#}")),
+        }
+    }
+}
+
+impl From<sqlx::Error> for EkklesDataError {
+    fn from(source: sqlx::Error) -> Self {
+        Self::from_sqlx(String::from("Chyba při přístupu do databáze"), source)
+    }
+}
+
+/// Vypisuje chybu i s její příčinou, stejně jako `anyhow::Error`, aby zůstaly čitelné
+/// chybové hlášky v GUI (`format!("{:?}", e)`)
+impl fmt::Debug for EkklesDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self}")?;
+        if let Some(source) = self.source() {
+            write!(f, "\n\nCaused by:\n    {source}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{Context, anyhow};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn anyhow_errors_are_classified() {
+        let not_found: anyhow::Result<()> =
+            Err(sqlx::Error::RowNotFound).context("Píseň s id 42 nebyla nalezena");
+        let err = EkklesDataError::from(not_found.unwrap_err());
+        assert!(err.is_not_found());
+        assert_eq!(err.to_string(), "Píseň s id 42 nebyla nalezena");
+
+        let db: anyhow::Result<()> =
+            Err(sqlx::Error::PoolTimedOut).context("Nelze načíst playlisty z databáze");
+        let err = EkklesDataError::from(db.unwrap_err());
+        assert!(matches!(err, EkklesDataError::Db { .. }));
+        assert_eq!(err.to_string(), "Nelze načíst playlisty z databáze");
+
+        let invalid = anyhow!("Píseň obsahuje tag s mezerou").context("Nelze uložit píseň");
+        let err = EkklesDataError::from(invalid);
+        assert!(matches!(err, EkklesDataError::InvalidData(_)));
+        assert_eq!(
+            err.to_string(),
+            "Nelze uložit píseň: Píseň obsahuje tag s mezerou"
+        );
+
+        let typed = anyhow::Error::from(EkklesDataError::Conflict(String::from("Obsazeno")))
+            .context("Nelze uložit playlist");
+        assert!(EkklesDataError::from(typed).is_conflict());
+    }
+}
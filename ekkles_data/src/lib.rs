@@ -8,11 +8,32 @@
 use anyhow::{Result, bail};
 use std::collections::{HashMap, HashSet};
 
+pub mod archive;
+pub mod author;
 pub mod bible;
 pub mod database;
+pub mod error;
+pub mod license;
+pub mod media;
 pub mod playlist;
+pub mod presentation_log;
+pub mod reports;
+pub mod slide_override;
+pub mod song_arrangement;
+pub mod song_chordpro;
 pub mod song_db;
+pub mod song_diff;
+pub mod song_ocr;
+pub mod song_openlyrics;
+pub mod song_text;
 pub mod song_xml;
+pub mod songbook;
+pub mod span;
+pub mod spellcheck;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+pub mod theme;
+pub mod verify;
 
 /// Tag označující část písně, typicky něco jako "V1", "V2", "C" (sloka1, sloka2, refrén)
 pub type PartTag = String;
@@ -32,6 +53,42 @@ pub struct Song {
     pub parts: HashMap<PartTag, String>,
     /// Pořadí jednotlivých částí písně, umožňuje opakování jedné části
     pub order: Vec<PartTag>,
+    /// Doplňující údaje o písni (CCLI, copyright, tónina, ...)
+    pub metadata: SongMetadata,
+}
+
+/// Doplňující údaje o písni, všechny jsou nepovinné
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SongMetadata {
+    /// Číslo písně v licenční databázi CCLI
+    pub ccli_number: Option<String>,
+    /// Copyright písně (např. "1982 Thankyou Music")
+    pub copyright: Option<String>,
+    /// Původní tónina písně (např. "G")
+    pub key: Option<String>,
+    /// Doporučený capo (pražec) pro kytaru
+    pub capo: Option<u8>,
+    /// Témata písně (např. "Chvála", "Vánoce"), bez duplicit
+    pub topics: Vec<String>,
+}
+
+impl SongMetadata {
+    /// Oříznutý neprázdný text, jinak `None`, pro nepovinné textové údaje z importovaných souborů
+    pub(crate) fn non_empty(text: Option<&str>) -> Option<String> {
+        text.map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string)
+    }
+
+    /// Přidá témata z `topics`, oddělená `;` nebo `,` (jak je zapisuje např. Opensong),
+    /// prázdná a již přítomná témata vynechá.
+    pub(crate) fn add_topics(&mut self, topics: &str) {
+        for topic in topics.split([';', ',']).map(str::trim) {
+            if !topic.is_empty() && !self.topics.iter().any(|existing| existing == topic) {
+                self.topics.push(topic.to_string());
+            }
+        }
+    }
 }
 
 impl Song {
@@ -58,6 +115,27 @@ impl Song {
 
         Ok(())
     }
+
+    /// Vrátí sekvenci slajdů, kterou píseň vytvoří při promítání, tedy dvojice
+    /// (tag, obsah části) v pořadí `order`. Pokud tag z pořadí nemá žádná slova,
+    /// je obsah `None`.
+    pub fn slide_sequence(&self) -> Vec<(&PartTag, Option<&str>)> {
+        self.order
+            .iter()
+            .map(|tag| (tag, self.parts.get(tag).map(String::as_str)))
+            .collect()
+    }
+
+    /// Vrátí seřazené tagy částí, které nejsou v pořadí `order`, a tedy se nepromítnou
+    pub fn unused_parts(&self) -> Vec<&PartTag> {
+        let mut unused: Vec<_> = self
+            .parts
+            .keys()
+            .filter(|tag| !self.order.contains(tag))
+            .collect();
+        unused.sort();
+        unused
+    }
 }
 
 #[cfg(test)]
@@ -111,6 +189,7 @@ mod tests {
                 String::from("V2b"),
                 String::from("TAG S MEZERAMI"),
             ],
+            metadata: Default::default(),
         };
 
         assert!(
@@ -119,6 +198,38 @@ mod tests {
         )
     }
 
+    #[test]
+    fn slide_sequence_test() {
+        let song = Song {
+            title: String::from("Testovací píseň"),
+            author: None,
+            parts: HashMap::from([
+                (String::from("V1"), String::from("Sloka")),
+                (String::from("C"), String::from("Refrén")),
+                (String::from("B"), String::from("Bridge")),
+            ]),
+            order: vec![
+                String::from("V1"),
+                String::from("C"),
+                String::from("V2"),
+                String::from("C"),
+            ],
+            metadata: Default::default(),
+        };
+
+        let tag = |tag: &str| tag.to_string();
+        assert_eq!(
+            song.slide_sequence(),
+            vec![
+                (&tag("V1"), Some("Sloka")),
+                (&tag("C"), Some("Refrén")),
+                (&tag("V2"), None),
+                (&tag("C"), Some("Refrén")),
+            ]
+        );
+        assert_eq!(song.unused_parts(), vec![&tag("B")]);
+    }
+
     #[test]
     fn check_invariants_test_matching_tags() {
         let song = Song {
@@ -161,6 +272,7 @@ mod tests {
                 String::from("V2a"),
                 // String::from("V2b"), Chybí
             ],
+            metadata: Default::default(),
         };
 
         assert!(
@@ -0,0 +1,87 @@
+//! Modul pro kontrolu licencí písní. V licenčním režimu (volitelný, zapíná se v nastavení)
+//! se u písní, které nemají CCLI číslo nebo jsou ručně označené jako nelicencované,
+//! zobrazuje varování a jejich promítnutí je potřeba potvrdit.
+
+use std::{collections::HashMap, fmt::Display};
+
+use anyhow::{Context, Result};
+use sqlx::{Sqlite, SqlitePool, pool::PoolConnection, query};
+
+/// Značka, která se připojí k názvu písně s varováním
+pub const LICENSE_WARNING_BADGE: &str = "⚠";
+
+/// Proč píseň nemusí být licencovaná
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LicenseWarning {
+    /// Píseň nemá CCLI číslo, nelze ji nahlásit
+    MissingCcliNumber,
+    /// Píseň je ručně označena jako nelicencovaná
+    NotLicensed,
+}
+
+impl Display for LicenseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LicenseWarning::MissingCcliNumber => "bez CCLI čísla",
+            LicenseWarning::NotLicensed => "bez licence",
+        })
+    }
+}
+
+/// Načte varování ke všem písním, které jej mají, jako mapu id písně -> varování.
+/// Pokud je píseň označena jako nelicencovaná, má toto varování přednost před chybějícím
+/// CCLI číslem.
+pub async fn get_license_warnings(
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<HashMap<i64, LicenseWarning>> {
+    let records = query!(
+        r#"SELECT id AS "id!", licensed, ccli_number FROM songs
+        WHERE licensed = 0 OR ccli_number IS NULL"#
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .context("Nelze načíst licence písní z databáze")?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| {
+            let warning = if record.licensed == 0 {
+                LicenseWarning::NotLicensed
+            } else {
+                LicenseWarning::MissingCcliNumber
+            };
+            (record.id, warning)
+        })
+        .collect())
+}
+
+/// Označí píseň `song_id` jako licencovanou/nelicencovanou.
+pub async fn set_licensed(song_id: i64, licensed: bool, pool: &SqlitePool) -> Result<()> {
+    query!(
+        "UPDATE songs SET licensed = $1 WHERE id = $2",
+        licensed,
+        song_id
+    )
+    .execute(pool)
+    .await
+    .with_context(|| format!("Nelze nastavit licenci písni s id {song_id}"))?;
+
+    Ok(())
+}
+
+/// Doplní k názvům písní `songs` (dvojice id, název), které mají varování, značku
+/// [`LICENSE_WARNING_BADGE`] s popisem varování, např. "Christ Arose ⚠ bez CCLI čísla".
+pub async fn append_license_badges(
+    songs: Vec<(i64, String)>,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<Vec<(i64, String)>> {
+    let warnings = get_license_warnings(conn).await?;
+
+    Ok(songs
+        .into_iter()
+        .map(|(id, title)| match warnings.get(&id) {
+            Some(warning) => (id, format!("{title} {LICENSE_WARNING_BADGE} {warning}")),
+            None => (id, title),
+        })
+        .collect())
+}
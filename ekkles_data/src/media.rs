@@ -0,0 +1,226 @@
+//! Modul pro úložiště médií (obrázků na pozadí slajdů).
+//!
+//! Fotky z mobilu mají klidně 48 Mpx, jejich dekódování při přechodu mezi slajdy by
+//! prezentaci zasekávalo. Proto se obrázek při importu zmenší na rozlišení výstupu
+//! (aby jej stále celý pokryl) a uloží se jako JPEG vedle originálu. Při prezentaci
+//! se používá pouze zmenšená verze, originál zůstává pro případ, že by se změnilo
+//! rozlišení výstupu.
+//!
+//! Podporované formáty jsou JPEG, PNG a WebP.
+
+use std::{fs, io::Cursor, path::Path};
+
+use anyhow::{Context, Result, bail};
+use image::{
+    DynamicImage, ImageDecoder, ImageFormat, ImageReader, codecs::jpeg::JpegEncoder,
+    imageops::FilterType,
+};
+use sqlx::{Sqlite, SqlitePool, pool::PoolConnection, query};
+
+/// Kvalita JPEG komprese zmenšené verze obrázku
+const OPTIMIZED_JPEG_QUALITY: u8 = 90;
+/// Formáty, které lze importovat
+const SUPPORTED_FORMATS: [ImageFormat; 3] =
+    [ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::WebP];
+/// Přípony souborů podporovaných formátů (pro dialogy výběru souboru)
+pub const SUPPORTED_EXTENSIONS: [&str; 4] = ["jpg", "jpeg", "png", "webp"];
+
+/// Informace o médiu v databázi (bez samotných dat)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaInfo {
+    pub id: i64,
+    pub name: String,
+    /// Šířka zmenšené verze v pixelech
+    pub width: u32,
+    /// Výška zmenšené verze v pixelech
+    pub height: u32,
+}
+
+/// Obrázek připravený k uložení do databáze
+#[derive(Debug, Clone)]
+pub struct NewMedia {
+    pub name: String,
+    original: Vec<u8>,
+    optimized: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+impl NewMedia {
+    /// Načte obrázek ze souboru `path` a připraví jeho zmenšenou verzi pro výstup
+    /// o rozměrech `output_width`×`output_height`, viz [`optimize()`].
+    ///
+    /// Dekódování a zmenšení velkého obrázku trvá i sekundy, volající by tedy tuto
+    /// funkci neměl volat z vlákna GUI.
+    pub fn from_file(path: &Path, output_width: u32, output_height: u32) -> Result<Self> {
+        let original =
+            fs::read(path).with_context(|| format!("Nelze přečíst obrázek {}", path.display()))?;
+
+        let (optimized, width, height) = optimize(&original, output_width, output_height)
+            .with_context(|| format!("Nelze zpracovat obrázek {}", path.display()))?;
+
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("Obrázek"));
+
+        Ok(Self {
+            name,
+            original,
+            optimized,
+            width,
+            height,
+        })
+    }
+
+    /// Připraví k uložení obrázek, který již byl zmenšen (např. z balíčku playlistu,
+    /// viz [`crate::playlist::bundle`]). Originál není k dispozici, uloží se místo něj
+    /// zmenšená verze.
+    pub(crate) fn from_optimized(
+        name: String,
+        optimized: Vec<u8>,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        Self {
+            name,
+            original: optimized.clone(),
+            optimized,
+            width,
+            height,
+        }
+    }
+
+    /// Uloží médium do databáze a vrátí jeho id.
+    pub async fn save_to_db(&self, pool: &SqlitePool) -> Result<i64> {
+        query!(
+            "INSERT INTO media (name, original, optimized, width, height) VALUES ($1, $2, $3, $4, $5)",
+            self.name,
+            self.original,
+            self.optimized,
+            self.width,
+            self.height
+        )
+        .execute(pool)
+        .await
+        .with_context(|| format!("Nelze uložit obrázek '{}' do databáze", self.name))
+        .map(|result| result.last_insert_rowid())
+    }
+}
+
+/// Dekóduje obrázek `original` (JPEG/PNG/WebP), otočí jej podle EXIF orientace a zmenší
+/// jej tak, aby stále celý pokryl výstup o rozměrech `output_width`×`output_height`
+/// (poměr stran zůstane zachován, menší obrázky se nezvětšují). Vrátí zmenšenou verzi
+/// zakódovanou jako JPEG a její rozměry.
+pub fn optimize(
+    original: &[u8],
+    output_width: u32,
+    output_height: u32,
+) -> Result<(Vec<u8>, u32, u32)> {
+    let reader = ImageReader::new(Cursor::new(original))
+        .with_guessed_format()
+        .context("Nelze zjistit formát obrázku")?;
+
+    match reader.format() {
+        Some(format) if SUPPORTED_FORMATS.contains(&format) => {}
+        Some(format) => bail!("Nepodporovaný formát obrázku {:?}", format),
+        None => bail!("Neznámý formát obrázku"),
+    }
+
+    let mut decoder = reader.into_decoder().context("Nelze dekódovat obrázek")?;
+    let orientation = decoder
+        .orientation()
+        .context("Nelze zjistit orientaci obrázku")?;
+    let mut image = DynamicImage::from_decoder(decoder).context("Nelze dekódovat obrázek")?;
+    image.apply_orientation(orientation);
+
+    let (width, height) = (image.width(), image.height());
+    let scale = f64::max(
+        output_width as f64 / width as f64,
+        output_height as f64 / height as f64,
+    );
+
+    let image = if scale < 1.0 {
+        let new_width = ((width as f64 * scale).round() as u32).max(1);
+        let new_height = ((height as f64 * scale).round() as u32).max(1);
+        image.resize_exact(new_width, new_height, FilterType::Triangle)
+    } else {
+        image
+    };
+
+    let mut optimized = Vec::new();
+    JpegEncoder::new_with_quality(&mut optimized, OPTIMIZED_JPEG_QUALITY)
+        .encode_image(&image.to_rgb8())
+        .context("Nelze zakódovat zmenšený obrázek")?;
+
+    Ok((optimized, image.width(), image.height()))
+}
+
+/// Vrátí informace o všech médiích v databázi seřazené podle názvu.
+pub async fn get_available(conn: &mut PoolConnection<Sqlite>) -> Result<Vec<MediaInfo>> {
+    query!("SELECT id, name, width, height FROM media ORDER BY name ASC")
+        .map(|record| MediaInfo {
+            id: record.id,
+            name: record.name,
+            width: record.width as u32,
+            height: record.height as u32,
+        })
+        .fetch_all(conn.as_mut())
+        .await
+        .context("Nelze načíst média z databáze")
+}
+
+/// Načte zmenšenou verzi (JPEG) média s daným `id`.
+pub async fn load_optimized(id: i64, conn: &mut PoolConnection<Sqlite>) -> Result<Vec<u8>> {
+    query!("SELECT optimized FROM media WHERE id = $1", id)
+        .fetch_one(conn.as_mut())
+        .await
+        .with_context(|| format!("Médium s id {id} nebylo nalezeno"))
+        .map(|record| record.optimized)
+}
+
+/// Smaže médium s daným `id` z databáze.
+pub async fn delete(id: i64, pool: &SqlitePool) -> Result<()> {
+    query!("DELETE FROM media WHERE id = $1", id)
+        .execute(pool)
+        .await
+        .with_context(|| format!("Nelze smazat médium s id {id} z databáze"))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use image::RgbImage;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn png_of_size(width: u32, height: u32) -> Vec<u8> {
+        let mut png = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(width, height))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        png
+    }
+
+    #[test]
+    fn large_image_is_downscaled_to_cover_output() {
+        let (jpeg, width, height) = optimize(&png_of_size(4000, 3000), 1920, 1080).unwrap();
+
+        assert_eq!((width, height), (1920, 1440));
+        assert_eq!(image::guess_format(&jpeg).unwrap(), ImageFormat::Jpeg);
+    }
+
+    #[test]
+    fn small_image_is_not_upscaled() {
+        let (_, width, height) = optimize(&png_of_size(800, 600), 1920, 1080).unwrap();
+
+        assert_eq!((width, height), (800, 600));
+    }
+
+    #[test]
+    fn unsupported_format_is_rejected() {
+        assert!(optimize(b"tohle neni obrazek", 1920, 1080).is_err());
+    }
+}
@@ -31,23 +31,68 @@
 //! let utc = Utc::now();
 //! let local: DateTime<Local> = DateTime::from(utc);
 //! ```
+//!
+//! ### Bloky
+//! Položkou playlistu může být i jiný playlist, tzv. blok ([`PlaylistItemMetadata::Block`]),
+//! např. opakovaně používaná liturgie Večeře Páně. Blok se rozbalí na své položky až při
+//! prezentaci ([`Playlist::load()`]), úprava bloku se tedy projeví ve všech playlistech,
+//! které jej obsahují. Bloky mohou obsahovat další bloky, playlist ale nesmí (ani nepřímo)
+//! obsahovat sám sebe, takový playlist nelze uložit ani načíst. Playlist vložený jako blok
+//! nelze smazat, dokud jej nějaký playlist obsahuje.
+//!
+//! ### Chyby
+//! Funkce pracující s databází vrací typované chyby [`EkklesDataError`] (např. obsazený
+//! název playlistu je [`EkklesDataError::Conflict`]), úpravy playlistu v paměti vrací
+//! `anyhow::Error`.
 
 use crate::{
-    Song,
+    PartTag, Song,
     bible::indexing::{Book, Passage, VerseIndex},
+    error::EkklesDataError,
+    media, slide_override, span,
+    theme::SlideTheme,
+    verify::{self, Checksum},
 };
 use anyhow::{Context, Result, anyhow, bail};
 use chrono::{DateTime, NaiveDateTime, SubsecRound, Utc};
-use sqlx::{Acquire, Sqlite, Transaction, pool::PoolConnection, query};
+use futures::future::{BoxFuture, FutureExt};
+use sqlx::{Acquire, Sqlite, SqliteConnection, Transaction, pool::PoolConnection, query};
+use std::collections::{HashMap, HashSet, hash_map::Entry};
+
+pub mod bundle;
+pub mod preflight;
+pub mod reminder;
 
 /// Hodnota sloupce 'kind' v tabulce 'playlist_parts' pro píseň
 const DB_PLAYLIST_KIND_SONG: &str = "song";
 /// Hodnota sloupce 'kind' v tabulce 'playlist_parts' pro pasáž z Bible
 const DB_PLAYLIST_KIND_BIBLE_PASSAGE: &str = "bible";
+/// Hodnota sloupce 'kind' v tabulce 'playlist_parts' pro vlastní text
+const DB_PLAYLIST_KIND_CUSTOM_TEXT: &str = "text";
+/// Hodnota sloupce 'kind' v tabulce 'playlist_parts' pro obrázek
+const DB_PLAYLIST_KIND_IMAGE: &str = "image";
+/// Hodnota sloupce 'kind' v tabulce 'playlist_parts' pro blok (vložený playlist)
+const DB_PLAYLIST_KIND_BLOCK: &str = "block";
+/// Oddělovač zlomů slajdů pasáže ve sloupci `slide_breaks` tabulky `playlist_passages`
+const DB_SLIDE_BREAKS_SEPARATOR: &str = ",";
+/// Oddělovač tagů vlastního pořadí písně ve sloupci `arrangement` tabulky `playlist_songs`
+/// (stejný jako v pořadí písně, tagy mezeru obsahovat nesmí)
+const DB_ARRANGEMENT_SEPARATOR: &str = " ";
 /// Formátovací řetězec pro [`NaiveDateTime::parse_from_str`] a jí podobné funkce při
 /// parsování řetězců z/do databáze.
 const DB_DATETIME_FORMAT: &str = "%F %T";
 
+/// Role lidí podílejících se na bohoslužbě, které lze přiřadit playlistu,
+/// viz [`PlaylistMetadata::set_role()`].
+pub const SERVICE_ROLES: [&str; 4] = ["Kazatel", "Vedoucí chval", "Projekce", "Zvuk"];
+
+/// Tóniny, které lze nastavit písni v playlistu, viz [`PlaylistMetadata::set_song_key()`].
+/// Durové tóniny jsou psané bez přípony, mollové s příponou `m`.
+pub const SONG_KEYS: [&str; 24] = [
+    "C", "Db", "D", "Eb", "E", "F", "F#", "G", "Ab", "A", "Bb", "B", "Cm", "C#m", "Dm", "Ebm",
+    "Em", "Fm", "F#m", "Gm", "G#m", "Am", "Bbm", "Bm",
+];
+
 /// Status playlistu ohledně databáze, viz [dokumentace modulu](`crate::playlist`)
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum PlaylistMetadataStatus {
@@ -60,30 +105,79 @@ pub enum PlaylistMetadataStatus {
 }
 
 /// Playlist se skládá z vícero druhů položek, tento enum je rozlišuje.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum PlaylistItemMetadata {
     BiblePassage {
         translation_id: i64,
         from: VerseIndex,
         to: VerseIndex,
+        /// Indexy veršů pasáže (od 0), kterými začíná nový slajd, seřazené vzestupně.
+        /// `None` znamená automatické rozdělení po pevném počtu veršů.
+        slide_breaks: Option<Vec<usize>>,
     },
     Song(i64),
+    /// Vlastní textový slajd (oznámení, uvítání, ...), `body` se promítá, `title` slouží
+    /// k jeho rozpoznání v playlistu
+    CustomText {
+        title: String,
+        body: String,
+    },
+    /// Obrázek (titulní grafika kázání, plakát oznámení) z úložiště médií, ID média,
+    /// viz [`crate::media`]
+    Image(i64),
+    /// Jiný playlist vložený jako blok, ID playlistu, viz [dokumentace modulu](crate::playlist)
+    Block(i64),
 }
 
-/// Vrátí seznam všech playlistů v databázi. Vrátí dvojice (ID, název) seřazené podle
-/// času vytvoření. Pokud se vyskytne chyba v databázi, vrátí Error
-pub async fn get_available(mut conn: PoolConnection<Sqlite>) -> Result<Vec<(i64, String)>> {
-    query!("SELECT id, name FROM playlists ORDER BY created ASC")
-        .map(|record| (record.id, record.name))
-        .fetch_all(&mut *conn)
-        .await
-        .context("Nelze načíst playlisty z databáze")
+/// Základní informace o playlistu pro jeho výběr, viz [`get_available()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistInfo {
+    pub id: i64,
+    pub name: String,
+    /// Čas vytvoření playlistu (UTC)
+    pub created: DateTime<Utc>,
+    /// Počet položek playlistu
+    pub num_items: usize,
+}
+
+/// Vrátí seznam všech playlistů v databázi s časem vytvoření a počtem položek, seřazený
+/// podle času vytvoření. Pokud se vyskytne chyba v databázi, vrátí Error
+pub async fn get_available(
+    mut conn: PoolConnection<Sqlite>,
+) -> crate::error::Result<Vec<PlaylistInfo>> {
+    let records = query!(
+        r#"SELECT id, name, created,
+            (SELECT COUNT(*) FROM playlist_parts WHERE playlist_id = playlists.id) AS "num_items!: i64"
+        FROM playlists ORDER BY created ASC, id ASC"#
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .context("Nelze načíst playlisty z databáze")?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            let created = NaiveDateTime::parse_from_str(&record.created, DB_DATETIME_FORMAT)
+                .with_context(|| format!("Nelze zparsovat datum z databáze {}", record.created))?
+                .and_utc();
+
+            Ok(PlaylistInfo {
+                id: record.id,
+                name: record.name,
+                created,
+                num_items: record.num_items as usize,
+            })
+        })
+        .collect()
 }
 
 /// Pokud je název playlistu `name` k dispozici (zatím v databázi neexistuje
 /// takto pojmenovaný playlist), vrátí `true`, jinak `false`. Pokud nastane
 /// chyba s připojením k databázi, vrátí Error.
-pub async fn is_name_available(mut conn: PoolConnection<Sqlite>, name: &str) -> Result<bool> {
+pub async fn is_name_available(
+    mut conn: PoolConnection<Sqlite>,
+    name: &str,
+) -> crate::error::Result<bool> {
     Ok(query!("SELECT id FROM playlists WHERE name == $1", name)
         .fetch_optional(&mut *conn)
         .await
@@ -91,6 +185,142 @@ pub async fn is_name_available(mut conn: PoolConnection<Sqlite>, name: &str) ->
         .is_none())
 }
 
+/// Smaže z databáze playlist s ID `id` (i s jeho položkami), aniž by jej bylo nutné
+/// načítat. Pokud takový playlist neexistuje, vrátí [`EkklesDataError::NotFound`],
+/// pokud nastane chyba v databázi, vrátí Error.
+///
+/// Playlist vložený jako blok do jiných playlistů se nesmaže a vrací se
+/// [`EkklesDataError::Conflict`] s názvy těchto playlistů.
+pub async fn delete_by_id(id: i64, conn: &mut PoolConnection<Sqlite>) -> crate::error::Result<()> {
+    check_not_used_as_block(id, conn).await?;
+
+    let deleted = query!("DELETE FROM playlists WHERE id = $1", id)
+        .execute(conn.as_mut())
+        .await
+        .context("Nelze smazat playlist z databáze")?
+        .rows_affected();
+
+    if deleted == 0 {
+        return Err(EkklesDataError::NotFound(format!(
+            "Playlist s ID {id} v databázi neexistuje"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Vrátí dvojice (id, název) playlistů seřazené podle názvu, které lze vložit jako blok
+/// do playlistu s ID `id` (`None` pro dosud neuložený playlist). Vynechá sám playlist
+/// a playlisty, které jej (i nepřímo) obsahují, vložení by vytvořilo cyklus.
+/// Pokud nastane chyba v databázi, vrátí Error.
+pub async fn get_block_options(
+    id: Option<i64>,
+    conn: &mut PoolConnection<Sqlite>,
+) -> crate::error::Result<Vec<(i64, String)>> {
+    let including = match id {
+        Some(id) => including_playlists(id, conn).await?,
+        None => HashSet::new(),
+    };
+
+    Ok(
+        query!(r#"SELECT id AS "id!", name FROM playlists ORDER BY name ASC"#)
+            .fetch_all(conn.as_mut())
+            .await
+            .context("Nelze načíst playlisty z databáze")?
+            .into_iter()
+            .filter(|record| !including.contains(&record.id))
+            .map(|record| (record.id, record.name))
+            .collect(),
+    )
+}
+
+/// Vrátí ID playlistu `id` a všech playlistů, které jej (i nepřímo) obsahují jako blok
+async fn including_playlists(id: i64, conn: &mut SqliteConnection) -> Result<HashSet<i64>> {
+    Ok(query!(
+        r#"WITH RECURSIVE including(id) AS (
+            SELECT $1
+            UNION
+            SELECT playlist_blocks.playlist_id FROM playlist_blocks
+            JOIN including ON playlist_blocks.block_id = including.id
+        )
+        SELECT id AS "id!: i64" FROM including"#,
+        id
+    )
+    .fetch_all(conn)
+    .await
+    .with_context(|| format!("Nelze zjistit playlisty obsahující playlist s id {id}"))?
+    .into_iter()
+    .map(|record| record.id)
+    .collect())
+}
+
+/// Pokud je playlist `id` vložený jako blok do jiných playlistů, vrací
+/// [`EkklesDataError::Conflict`] s jejich názvy
+async fn check_not_used_as_block(
+    id: i64,
+    conn: &mut PoolConnection<Sqlite>,
+) -> crate::error::Result<()> {
+    let names: Vec<String> = query!(
+        "SELECT name FROM playlists
+        WHERE id IN (SELECT playlist_id FROM playlist_blocks WHERE block_id = $1)
+        ORDER BY name ASC",
+        id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .context("Nelze zjistit, kde je playlist vložený jako blok")?
+    .into_iter()
+    .map(|record| record.name)
+    .collect();
+
+    if names.is_empty() {
+        Ok(())
+    } else {
+        Err(EkklesDataError::Conflict(format!(
+            "Playlist nelze smazat, je vložený jako blok v playlistech: {}",
+            names.join(", ")
+        )))
+    }
+}
+
+/// Vrátí role všech playlistů jako mapu ID playlistu -> dvojice (role, jméno) seřazené podle
+/// [`SERVICE_ROLES`]. Playlisty bez rolí v mapě nejsou. Pokud nastane chyba v databázi, vrátí Error.
+pub async fn get_all_roles(
+    conn: &mut PoolConnection<Sqlite>,
+) -> crate::error::Result<HashMap<i64, Vec<(String, String)>>> {
+    let records = query!("SELECT playlist_id, role, person FROM playlist_roles")
+        .fetch_all(conn.as_mut())
+        .await
+        .context("Nelze načíst role playlistů z databáze")?;
+
+    let mut roles: HashMap<i64, Vec<(String, String)>> = HashMap::new();
+    for record in records {
+        roles
+            .entry(record.playlist_id)
+            .or_default()
+            .push((record.role, record.person));
+    }
+    for playlist_roles in roles.values_mut() {
+        playlist_roles.sort_by_key(|(role, _)| SERVICE_ROLES.iter().position(|r| r == role));
+    }
+
+    Ok(roles)
+}
+
+/// Vrátí abecedně seřazená jména všech lidí, kteří už měli v nějakém playlistu roli,
+/// slouží pro doplňování jmen. Pokud nastane chyba v databázi, vrátí Error.
+pub async fn get_known_people(
+    conn: &mut PoolConnection<Sqlite>,
+) -> crate::error::Result<Vec<String>> {
+    Ok(
+        query!("SELECT DISTINCT person FROM playlist_roles ORDER BY person")
+            .map(|record| record.person)
+            .fetch_all(conn.as_mut())
+            .await
+            .context("Nelze načíst jména lidí z databáze")?,
+    )
+}
+
 impl PlaylistItemMetadata {
     /// Uloží danou položku playlistu `playlist_id` s pořadovým číslem `order` do databáze za pomocí dané transakce, pokud nastane chyba
     /// při ukládání, vrací Error.
@@ -107,6 +337,9 @@ impl PlaylistItemMetadata {
         let kind = match self {
             PlaylistItemMetadata::BiblePassage { .. } => DB_PLAYLIST_KIND_BIBLE_PASSAGE,
             PlaylistItemMetadata::Song(_) => DB_PLAYLIST_KIND_SONG,
+            PlaylistItemMetadata::CustomText { .. } => DB_PLAYLIST_KIND_CUSTOM_TEXT,
+            PlaylistItemMetadata::Image(_) => DB_PLAYLIST_KIND_IMAGE,
+            PlaylistItemMetadata::Block(_) => DB_PLAYLIST_KIND_BLOCK,
         };
 
         query!(
@@ -124,11 +357,13 @@ impl PlaylistItemMetadata {
                 translation_id,
                 from,
                 to,
+                slide_breaks,
             } => {
                 let (from_book, from_chapter, from_verse_number) = from.destructure_numeric();
                 let (to_book, to_chapter, to_verse_number) = to.destructure_numeric();
+                let slide_breaks = slide_breaks_to_db(slide_breaks.as_deref());
                 query!(
-                        "INSERT INTO playlist_passages ( playlist_id, part_order, translation_id , start_book_id , start_chapter , start_number , end_book_id , end_chapter , end_number) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+                        "INSERT INTO playlist_passages ( playlist_id, part_order, translation_id , start_book_id , start_chapter , start_number , end_book_id , end_chapter , end_number, slide_breaks) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
                         playlist_id,
                         order,
                         translation_id,
@@ -137,7 +372,8 @@ impl PlaylistItemMetadata {
                         from_verse_number,
                         to_book,
                         to_chapter,
-                        to_verse_number
+                        to_verse_number,
+                        slide_breaks
                     )
                     .execute(&mut **transaction)
                     .await
@@ -154,6 +390,40 @@ impl PlaylistItemMetadata {
                     .await
                     .with_context(|| format!("Nelze uložit píseň s ID {} do databáze", song_id))?;
             }
+            PlaylistItemMetadata::CustomText { title, body } => {
+                query!(
+                    "INSERT INTO playlist_texts (playlist_id, part_order, title, body) VALUES ($1, $2, $3, $4)",
+                    playlist_id,
+                    order,
+                    title,
+                    body
+                )
+                .execute(&mut **transaction)
+                .await
+                .with_context(|| format!("Nelze uložit text '{}' do databáze", title))?;
+            }
+            PlaylistItemMetadata::Image(media_id) => {
+                query!(
+                    "INSERT INTO playlist_images (playlist_id, part_order, media_id) VALUES ($1, $2, $3)",
+                    playlist_id,
+                    order,
+                    media_id
+                )
+                .execute(&mut **transaction)
+                .await
+                .with_context(|| format!("Nelze uložit obrázek s ID {} do databáze", media_id))?;
+            }
+            PlaylistItemMetadata::Block(block_id) => {
+                query!(
+                    "INSERT INTO playlist_blocks (playlist_id, part_order, block_id) VALUES ($1, $2, $3)",
+                    playlist_id,
+                    order,
+                    block_id
+                )
+                .execute(&mut **transaction)
+                .await
+                .with_context(|| format!("Nelze uložit blok s ID {} do databáze", block_id))?;
+            }
         }
 
         Ok(())
@@ -168,11 +438,11 @@ impl PlaylistItemMetadata {
     /// Playlist by v db měl být prázdný, není to upsert, ale čistý insert, pokud nebude prázdný,
     /// shoří to na konfliktu při vkládání.
     async fn insert_many(
-        items: &[Self],
+        items: impl IntoIterator<Item = &Self>,
         transaction: &mut Transaction<'_, Sqlite>,
         playlist_id: i64,
     ) -> Result<()> {
-        for (order, item) in items.iter().enumerate() {
+        for (order, item) in items.into_iter().enumerate() {
             let order: u32 = order.try_into().with_context(|| {
                 format!(
                     "Playlist obsahuje více než {} položek (proč???), nelze uložit",
@@ -236,6 +506,33 @@ impl PlaylistItemMetadata {
             .await
             .context("Nelze smazat píseň z playlistu")?
             .rows_affected(),
+            PlaylistItemMetadata::CustomText { .. } => query!(
+                "DELETE FROM playlist_texts WHERE playlist_id = $1 AND part_order = $2",
+                playlist_id,
+                order,
+            )
+            .execute(&mut **transaction)
+            .await
+            .context("Nelze smazat text z playlistu")?
+            .rows_affected(),
+            PlaylistItemMetadata::Image(_) => query!(
+                "DELETE FROM playlist_images WHERE playlist_id = $1 AND part_order = $2",
+                playlist_id,
+                order,
+            )
+            .execute(&mut **transaction)
+            .await
+            .context("Nelze smazat obrázek z playlistu")?
+            .rows_affected(),
+            PlaylistItemMetadata::Block(_) => query!(
+                "DELETE FROM playlist_blocks WHERE playlist_id = $1 AND part_order = $2",
+                playlist_id,
+                order,
+            )
+            .execute(&mut **transaction)
+            .await
+            .context("Nelze smazat blok z playlistu")?
+            .rows_affected(),
         };
 
         if rows_affected == 0 {
@@ -276,6 +573,30 @@ impl PlaylistItemMetadata {
         .await
         .context("Nelze smazat pasáže playlistu")?;
 
+        query!(
+            "DELETE FROM playlist_texts WHERE playlist_id = $1",
+            playlist_id
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Nelze smazat texty playlistu")?;
+
+        query!(
+            "DELETE FROM playlist_images WHERE playlist_id = $1",
+            playlist_id
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Nelze smazat obrázky playlistu")?;
+
+        query!(
+            "DELETE FROM playlist_blocks WHERE playlist_id = $1",
+            playlist_id
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Nelze smazat bloky playlistu")?;
+
         Ok(())
     }
 
@@ -317,7 +638,7 @@ impl PlaylistItemMetadata {
             }
             DB_PLAYLIST_KIND_BIBLE_PASSAGE => {
                 let record = query!(
-                        "SELECT translation_id, start_book_id, start_chapter, start_number, end_book_id, end_chapter, end_number FROM playlist_passages WHERE playlist_id = $1 AND part_order = $2",
+                        "SELECT translation_id, start_book_id, start_chapter, start_number, end_book_id, end_chapter, end_number, slide_breaks FROM playlist_passages WHERE playlist_id = $1 AND part_order = $2",
                         playlist_id,
                         order
                     )
@@ -348,22 +669,83 @@ impl PlaylistItemMetadata {
                     translation_id: record.translation_id,
                     from,
                     to,
+                    slide_breaks: slide_breaks_from_db(record.slide_breaks.as_deref())?,
+                })
+            }
+            DB_PLAYLIST_KIND_CUSTOM_TEXT => {
+                let record = query!(
+                    "SELECT title, body FROM playlist_texts WHERE playlist_id = $1 AND part_order = $2",
+                    playlist_id,
+                    order
+                )
+                .fetch_one(&mut *conn)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Nelze načíst část {} playlistu s id {} z databáze",
+                        order, playlist_id
+                    )
+                })?;
+
+                Ok(PlaylistItemMetadata::CustomText {
+                    title: record.title,
+                    body: record.body,
                 })
             }
+            DB_PLAYLIST_KIND_IMAGE => {
+                let media_id = query!(
+                    "SELECT media_id FROM playlist_images WHERE playlist_id = $1 AND part_order = $2",
+                    playlist_id,
+                    order
+                )
+                .fetch_one(&mut *conn)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Nelze načíst část {} playlistu s id {} z databáze",
+                        order, playlist_id
+                    )
+                })?
+                .media_id;
+
+                Ok(PlaylistItemMetadata::Image(media_id))
+            }
+            DB_PLAYLIST_KIND_BLOCK => {
+                let block_id = query!(
+                    "SELECT block_id FROM playlist_blocks WHERE playlist_id = $1 AND part_order = $2",
+                    playlist_id,
+                    order
+                )
+                .fetch_one(&mut *conn)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Nelze načíst část {} playlistu s id {} z databáze",
+                        order, playlist_id
+                    )
+                })?
+                .block_id;
+
+                Ok(PlaylistItemMetadata::Block(block_id))
+            }
             _ => panic!(
-                "Sloupec playlist_parts.kind by měl být integritně omezen na '{}' nebo '{}', došlo ke korupci dat v databázi?",
-                DB_PLAYLIST_KIND_SONG, DB_PLAYLIST_KIND_BIBLE_PASSAGE
+                "Sloupec playlist_parts.kind by měl být integritně omezen na '{}', '{}', '{}', '{}' nebo '{}', došlo ke korupci dat v databázi?",
+                DB_PLAYLIST_KIND_SONG,
+                DB_PLAYLIST_KIND_BIBLE_PASSAGE,
+                DB_PLAYLIST_KIND_CUSTOM_TEXT,
+                DB_PLAYLIST_KIND_IMAGE,
+                DB_PLAYLIST_KIND_BLOCK
             ),
         }
     }
 
     /// Načte všechny položky playlistu a vrátí je jako vektor, pokud se načítání z databáze nepovede, vrací Error.
-    async fn load_many(mut conn: PoolConnection<Sqlite>, playlist_id: i64) -> Result<Vec<Self>> {
+    async fn load_many(conn: &mut PoolConnection<Sqlite>, playlist_id: i64) -> Result<Vec<Self>> {
         let parts = query!(
             "SELECT part_order, kind FROM playlist_parts WHERE playlist_id = $1 ORDER BY part_order ASC",
             playlist_id
         )
-        .fetch_all(&mut *conn)
+        .fetch_all(conn.as_mut())
         .await
         .context("Nelze načíst část playlistu z databáze")?;
 
@@ -377,7 +759,7 @@ impl PlaylistItemMetadata {
                     playlist_id,
                     record.part_order
                     )
-                    .fetch_one(&mut *conn)
+                    .fetch_one(conn.as_mut())
                     .await
                     .with_context(|| {
                         format!(
@@ -390,11 +772,11 @@ impl PlaylistItemMetadata {
                 }
                 DB_PLAYLIST_KIND_BIBLE_PASSAGE => {
                     let record = query!(
-                        "SELECT translation_id, start_book_id, start_chapter, start_number, end_book_id, end_chapter, end_number FROM playlist_passages WHERE playlist_id = $1 AND part_order = $2",
+                        "SELECT translation_id, start_book_id, start_chapter, start_number, end_book_id, end_chapter, end_number, slide_breaks FROM playlist_passages WHERE playlist_id = $1 AND part_order = $2",
                         playlist_id,
                         record.part_order
                     )
-                    .fetch_one(&mut *conn)
+                    .fetch_one(conn.as_mut())
                     .await
                     .with_context(|| {
                         format!(
@@ -421,13 +803,74 @@ impl PlaylistItemMetadata {
                         translation_id: record.translation_id,
                         from,
                         to,
+                        slide_breaks: slide_breaks_from_db(record.slide_breaks.as_deref())?,
                     };
 
                     items.push(new_item);
                 }
+                DB_PLAYLIST_KIND_CUSTOM_TEXT => {
+                    let record = query!(
+                        "SELECT title, body FROM playlist_texts WHERE playlist_id = $1 AND part_order = $2",
+                        playlist_id,
+                        record.part_order
+                    )
+                    .fetch_one(conn.as_mut())
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Nelze načíst část {} playlistu s id {} z databáze",
+                            record.part_order, playlist_id
+                        )
+                    })?;
+
+                    items.push(PlaylistItemMetadata::CustomText {
+                        title: record.title,
+                        body: record.body,
+                    });
+                }
+                DB_PLAYLIST_KIND_IMAGE => {
+                    let media_id = query!(
+                        "SELECT media_id FROM playlist_images WHERE playlist_id = $1 AND part_order = $2",
+                        playlist_id,
+                        record.part_order
+                    )
+                    .fetch_one(conn.as_mut())
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Nelze načíst část {} playlistu s id {} z databáze",
+                            record.part_order, playlist_id
+                        )
+                    })?
+                    .media_id;
+
+                    items.push(PlaylistItemMetadata::Image(media_id));
+                }
+                DB_PLAYLIST_KIND_BLOCK => {
+                    let block_id = query!(
+                        "SELECT block_id FROM playlist_blocks WHERE playlist_id = $1 AND part_order = $2",
+                        playlist_id,
+                        record.part_order
+                    )
+                    .fetch_one(conn.as_mut())
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Nelze načíst část {} playlistu s id {} z databáze",
+                            record.part_order, playlist_id
+                        )
+                    })?
+                    .block_id;
+
+                    items.push(PlaylistItemMetadata::Block(block_id));
+                }
                 _ => panic!(
-                    "Sloupec playlist_parts.kind by měl být integritně omezen na '{}' nebo '{}', došlo ke korupci dat v databázi?",
-                    DB_PLAYLIST_KIND_SONG, DB_PLAYLIST_KIND_BIBLE_PASSAGE
+                    "Sloupec playlist_parts.kind by měl být integritně omezen na '{}', '{}', '{}', '{}' nebo '{}', došlo ke korupci dat v databázi?",
+                    DB_PLAYLIST_KIND_SONG,
+                    DB_PLAYLIST_KIND_BIBLE_PASSAGE,
+                    DB_PLAYLIST_KIND_CUSTOM_TEXT,
+                    DB_PLAYLIST_KIND_IMAGE,
+                    DB_PLAYLIST_KIND_BLOCK
                 ),
             }
         }
@@ -436,6 +879,41 @@ impl PlaylistItemMetadata {
     }
 }
 
+/// Položka [`PlaylistMetadata`] spolu s daty, která k ní patří pouze v tomto playlistu
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct PlaylistEntry {
+    item: PlaylistItemMetadata,
+    /// Očekávaná délka položky v sekundách
+    expected_duration: Option<u32>,
+    /// Tónina písně pro tuto bohoslužbu (u ostatních položek vždy `None`)
+    song_key: Option<String>,
+    /// Vlastní pořadí částí písně pro tuto bohoslužbu (`None` = pořadí písně, u ostatních
+    /// položek vždy `None`)
+    arrangement: Option<Vec<PartTag>>,
+    /// Číslo světelného cue odeslaného při promítnutí položky
+    lighting_cue: Option<u8>,
+    /// Zda se položka promítá (vypnutá zůstává v plánu)
+    enabled: bool,
+    /// Pořadí položky v uloženém playlistu, podle kterého se při uložení přenesou úpravy
+    /// jejích slajdů (viz [`crate::slide_override`]), `None` u nové nebo nahrazené položky
+    saved_order: Option<usize>,
+}
+
+impl PlaylistEntry {
+    /// Nová zapnutá položka bez dalších dat, která ještě nebyla uložena
+    fn new(item: PlaylistItemMetadata) -> Self {
+        Self {
+            item,
+            expected_duration: None,
+            song_key: None,
+            arrangement: None,
+            lighting_cue: None,
+            enabled: true,
+            saved_order: None,
+        }
+    }
+}
+
 /// Struktura obsahující pouze metadata playlistu určená pro editaci
 /// (nemusí načítat obsahy jednotlivých položek, postačí identifikátory).
 ///
@@ -448,7 +926,11 @@ pub struct PlaylistMetadata {
     name: String,
     /// Čas vytvoření playlistu zaokrouhlený k nejbližší sekundě
     created: DateTime<Utc>,
-    items: Vec<PlaylistItemMetadata>,
+    items: Vec<PlaylistEntry>,
+    /// ID motivu, který přebije motivy jednotlivých písní
+    theme_id: Option<i64>,
+    /// Lidé v rolích na této bohoslužbě (role z [`SERVICE_ROLES`] -> jméno)
+    roles: HashMap<String, String>,
 }
 
 impl PlaylistMetadata {
@@ -459,6 +941,8 @@ impl PlaylistMetadata {
             name: name.to_string(),
             created: Utc::now().round_subsecs(0),
             items: Vec::new(),
+            theme_id: None,
+            roles: HashMap::new(),
         }
     }
 
@@ -474,46 +958,75 @@ impl PlaylistMetadata {
     pub fn from_other(name: &str, other: &mut PlaylistMetadata) -> Self {
         let mut new = Self::new(name);
         std::mem::swap(&mut new.items, &mut other.items);
+        // Nový playlist zatím nemá žádné úpravy slajdů, které by šlo přenést
+        for entry in &mut new.items {
+            entry.saved_order = None;
+        }
+        new.theme_id = other.theme_id;
+        new.roles = other.roles.clone();
         new
     }
 
     /// Načte existující playlist s daným ID z databáze, status bude mít nastaven na
-    /// [`PlaylistMetadataStatus::Clean`]. Pokud takový playlist neexistuje
-    /// nebo se něco v pokazí při načítání, vrátí Error.
-    pub async fn load(id: i64, mut conn: PoolConnection<Sqlite>) -> Result<Self> {
-        let metadata = query!("SELECT name, created FROM playlists WHERE id = $1", id)
-            .fetch_one(&mut *conn)
-            .await
-            .with_context(|| format!("Nelze načíst playlist s id {id} z databáze"))?;
+    /// [`PlaylistMetadataStatus::Clean`]. Pokud takový playlist neexistuje, vrátí
+    /// [`EkklesDataError::NotFound`], pokud se něco pokazí při načítání, vrátí Error.
+    pub async fn load(id: i64, mut conn: PoolConnection<Sqlite>) -> crate::error::Result<Self> {
+        span::in_span("playlist.load", format!("playlist={id}"), async move {
+            Self::load_with(id, &mut conn).await
+        })
+        .await
+    }
+
+    /// Stejné jako [`PlaylistMetadata::load()`], ale pouze si půjčí připojení `conn`
+    async fn load_with(id: i64, conn: &mut PoolConnection<Sqlite>) -> crate::error::Result<Self> {
+        let metadata = query!(
+            "SELECT name, created, theme_id FROM playlists WHERE id = $1",
+            id
+        )
+        .fetch_one(conn.as_mut())
+        .await
+        .with_context(|| format!("Nelze načíst playlist s id {id} z databáze"))?;
 
         let name = metadata.name;
         let created = NaiveDateTime::parse_from_str(&metadata.created, DB_DATETIME_FORMAT)
             .with_context(|| format!("Nelze zparsovat datum z databáze {}", metadata.created))?
             .and_utc();
 
+        let roles = load_roles(conn, id)
+            .await
+            .context("Nepodařilo se načíst role playlistu")?;
+
         let items = PlaylistItemMetadata::load_many(conn, id)
             .await
             .context("Nepodařilo se načíst položky playlistu")?;
 
+        let items = load_entries(conn, id, items)
+            .await
+            .context("Nepodařilo se načíst data položek playlistu")?;
+
         Ok(Self {
             status: PlaylistMetadataStatus::Clean(id),
             name,
             created,
             items,
+            theme_id: metadata.theme_id,
+            roles,
         })
     }
 
     /// Smaže playlist z databáze a nastaví jeho stav
-    /// na [`PlaylistMetadataStatus::Transient`].
-    pub async fn delete(&mut self, conn: &mut PoolConnection<Sqlite>) -> Result<()> {
+    /// na [`PlaylistMetadataStatus::Transient`]. Playlist vložený jako blok do jiných
+    /// playlistů se nesmaže a vrací se [`EkklesDataError::Conflict`].
+    pub async fn delete(&mut self, conn: &mut PoolConnection<Sqlite>) -> crate::error::Result<()> {
         match self.status {
             PlaylistMetadataStatus::Transient => Ok(()),
             PlaylistMetadataStatus::Clean(id) | PlaylistMetadataStatus::Dirty(id) => {
+                check_not_used_as_block(id, conn).await?;
                 query!("DELETE FROM playlists WHERE id = $1", id)
                     .execute(conn.as_mut())
                     .await
-                    .context("Nelze smazat playlist z databáze")
-                    .map(|_| ())
+                    .context("Nelze smazat playlist z databáze")?;
+                Ok(())
             }
         }
     }
@@ -527,67 +1040,550 @@ impl PlaylistMetadata {
         &self.name
     }
 
-    pub fn get_items(&self) -> &[PlaylistItemMetadata] {
-        &self.items
+    /// Vrátí iterátor přes položky playlistu v jejich pořadí
+    pub fn get_items(&self) -> impl ExactSizeIterator<Item = &PlaylistItemMetadata> {
+        self.items.iter().map(|entry| &entry.item)
     }
 
-    /// Convenience funkce pro vkládání písní na konec playlistu. Má stejné chování jako [`PlaylistMetadata::add_song`].
-    pub fn push_song(&mut self, song_id: i64) {
-        self.add_song(song_id, self.items.len());
+    /// Vrátí položku na indexu `position`, pokud neexistuje, vrací `None`.
+    pub fn get_item(&self, position: usize) -> Option<&PlaylistItemMetadata> {
+        self.items.get(position).map(|entry| &entry.item)
     }
 
-    /// Přidá píseň s ID `song_id` do playlistu na pozici `position`. Pokud byl status `clean`, shodí jej na `dirty`.
-    pub fn add_song(&mut self, song_id: i64, position: usize) {
+    /// Vrátí očekávanou délku položky na indexu `position` v sekundách, pokud není známa
+    /// (nebo položka neexistuje), vrací `None`.
+    pub fn get_expected_duration(&self, position: usize) -> Option<u32> {
         self.items
-            .insert(position, PlaylistItemMetadata::Song(song_id));
+            .get(position)
+            .and_then(|entry| entry.expected_duration)
+    }
 
-        if let PlaylistMetadataStatus::Clean(id) = self.status {
-            self.status = PlaylistMetadataStatus::Dirty(id);
+    /// Vrátí součet očekávaných délek všech promítaných položek v sekundách a počet
+    /// promítaných položek, jejichž délka není známa. Vypnuté položky se nezapočítávají.
+    pub fn get_total_expected_duration(&self) -> (u32, usize) {
+        let enabled_durations = || {
+            self.items
+                .iter()
+                .filter(|entry| entry.enabled)
+                .map(|entry| entry.expected_duration)
+        };
+        let total = enabled_durations().flatten().sum();
+        let unknown = enabled_durations().filter(|d| d.is_none()).count();
+        (total, unknown)
+    }
+
+    /// Nastaví očekávanou délku položky na indexu `position` v sekundách. Pokud na tomto indexu
+    /// neexistuje položka, vrací Error. Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn set_expected_duration(&mut self, position: usize, duration: Option<u32>) -> Result<()> {
+        match self.items.get_mut(position) {
+            Some(entry) => {
+                entry.expected_duration = duration;
+
+                if let PlaylistMetadataStatus::Clean(id) = self.status {
+                    self.status = PlaylistMetadataStatus::Dirty(id);
+                }
+
+                Ok(())
+            }
+            None => bail!("Položka na indexu {position} neexistuje"),
         }
     }
 
-    /// Convenience funkce pro vkládání pasáží na konec playlistu. Má stejné chování jako [`PlaylistMetadata::add_bible_passage`].
-    pub fn push_bible_passage(&mut self, translation_id: i64, from: VerseIndex, to: VerseIndex) {
-        self.add_bible_passage(translation_id, from, to, self.items.len());
+    /// Vrátí tóninu písně na indexu `position`, pokud není určena (nebo na indexu není píseň),
+    /// vrací `None`.
+    pub fn get_song_key(&self, position: usize) -> Option<&str> {
+        self.items
+            .get(position)
+            .and_then(|entry| entry.song_key.as_deref())
     }
 
-    /// Přidá pasáž do playlistu na pozici `position`. Pasáž bude z překladu s ID `translation_id` a bude od `from` do `to`. Pokud byl status `clean`, shodí jej na `dirty`.
-    pub fn add_bible_passage(
-        &mut self,
-        translation_id: i64,
-        from: VerseIndex,
-        to: VerseIndex,
-        position: usize,
-    ) {
-        self.items.insert(
-            position,
-            PlaylistItemMetadata::BiblePassage {
-                translation_id,
-                from,
-                to,
-            },
-        );
+    /// Nastaví tóninu písně na indexu `position` (`None` = neurčena). Tónina musí být jedna
+    /// ze [`SONG_KEYS`], jinak (nebo pokud na indexu není píseň) vrací Error.
+    /// Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn set_song_key(&mut self, position: usize, key: Option<&str>) -> Result<()> {
+        if let Some(key) = key
+            && !SONG_KEYS.contains(&key)
+        {
+            bail!("Neznámá tónina '{key}'");
+        }
 
-        if let PlaylistMetadataStatus::Clean(id) = self.status {
-            self.status = PlaylistMetadataStatus::Dirty(id);
+        match self.items.get_mut(position) {
+            Some(PlaylistEntry {
+                item: PlaylistItemMetadata::Song(_),
+                song_key,
+                ..
+            }) => {
+                *song_key = key.map(str::to_string);
+
+                if let PlaylistMetadataStatus::Clean(id) = self.status {
+                    self.status = PlaylistMetadataStatus::Dirty(id);
+                }
+
+                Ok(())
+            }
+            Some(_) => bail!("Položka na indexu {position} není píseň"),
+            None => bail!("Položka na indexu {position} neexistuje"),
         }
     }
 
-    /// Odstraní položku na indexu `position` z playlistu, pokud na tomto indexu neexistje
-    /// položka, vrací Error. Pokud byl status `clean`, shodí jej na `dirty`.
-    pub fn delete_item(&mut self, position: usize) -> Result<()> {
-        if self.items.len() <= position {
-            bail!("Položka na indexu {position} neexistuje");
-        } else {
-            self.items.remove(position);
+    /// Vrátí vlastní pořadí částí písně na indexu `position`, pokud píseň žádné nemá
+    /// (promítá se v pořadí písně) nebo na indexu není píseň, vrací `None`.
+    pub fn get_arrangement(&self, position: usize) -> Option<&[PartTag]> {
+        self.items
+            .get(position)
+            .and_then(|entry| entry.arrangement.as_deref())
+    }
 
-            if let PlaylistMetadataStatus::Clean(id) = self.status {
-                self.status = PlaylistMetadataStatus::Dirty(id);
+    /// Nastaví vlastní pořadí částí písně na indexu `position` pro tuto bohoslužbu
+    /// (`None` = pořadí písně). Pořadí nesmí být prázdné a tagy nesmí být prázdné ani
+    /// obsahovat mezeru (viz [`Song`]), jinak (nebo pokud na indexu není píseň) vrací Error.
+    /// Zda píseň části s danými tagy obsahuje, se kontroluje až před prezentací
+    /// (viz [`preflight`]), protože se píseň může mezitím změnit.
+    /// Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn set_arrangement(
+        &mut self,
+        position: usize,
+        arrangement: Option<Vec<PartTag>>,
+    ) -> Result<()> {
+        if let Some(arrangement) = &arrangement {
+            if arrangement.is_empty() {
+                bail!("Pořadí částí písně nesmí být prázdné");
+            }
+            if let Some(tag) = arrangement
+                .iter()
+                .find(|tag| tag.is_empty() || tag.contains(DB_ARRANGEMENT_SEPARATOR))
+            {
+                bail!("Neplatný tag části písně '{tag}'");
             }
-
-            Ok(())
         }
-    }
+
+        match self.items.get_mut(position) {
+            Some(PlaylistEntry {
+                item: PlaylistItemMetadata::Song(_),
+                arrangement: song_arrangement,
+                ..
+            }) => {
+                *song_arrangement = arrangement;
+
+                if let PlaylistMetadataStatus::Clean(id) = self.status {
+                    self.status = PlaylistMetadataStatus::Dirty(id);
+                }
+
+                Ok(())
+            }
+            Some(_) => bail!("Položka na indexu {position} není píseň"),
+            None => bail!("Položka na indexu {position} neexistuje"),
+        }
+    }
+
+    /// Vrátí číslo světelného cue položky na indexu `position`, pokud žádné nemá
+    /// (nebo položka neexistuje), vrací `None`.
+    pub fn get_lighting_cue(&self, position: usize) -> Option<u8> {
+        self.items
+            .get(position)
+            .and_then(|entry| entry.lighting_cue)
+    }
+
+    /// Nastaví číslo světelného cue (1 až 255, `None` = žádné), které se odešle osvětlovacímu
+    /// pultu, když se položka na indexu `position` začne promítat. Pokud je číslo 0 nebo
+    /// na indexu neexistuje položka, vrací Error. Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn set_lighting_cue(&mut self, position: usize, cue: Option<u8>) -> Result<()> {
+        if cue == Some(0) {
+            bail!("Číslo cue musí být 1 až {}", u8::MAX);
+        }
+
+        match self.items.get_mut(position) {
+            Some(entry) => {
+                entry.lighting_cue = cue;
+
+                if let PlaylistMetadataStatus::Clean(id) = self.status {
+                    self.status = PlaylistMetadataStatus::Dirty(id);
+                }
+
+                Ok(())
+            }
+            None => bail!("Položka na indexu {position} neexistuje"),
+        }
+    }
+
+    /// Zda se položka na indexu `position` promítá, neexistující položka se nepromítá
+    pub fn is_item_enabled(&self, position: usize) -> bool {
+        self.items.get(position).is_some_and(|entry| entry.enabled)
+    }
+
+    /// Zapne nebo vypne položku na indexu `position`. Vypnutá položka (např. nepovinná píseň)
+    /// zůstává v playlistu, ale nepromítá se. Pokud na indexu neexistuje položka, vrací Error.
+    /// Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn set_item_enabled(&mut self, position: usize, enabled: bool) -> Result<()> {
+        match self.items.get_mut(position) {
+            Some(entry) => {
+                entry.enabled = enabled;
+
+                if let PlaylistMetadataStatus::Clean(id) = self.status {
+                    self.status = PlaylistMetadataStatus::Dirty(id);
+                }
+
+                Ok(())
+            }
+            None => bail!("Položka na indexu {position} neexistuje"),
+        }
+    }
+
+    /// Vrátí ID motivu playlistu, pokud žádný nemá, použijí se motivy písní
+    pub fn get_theme_id(&self) -> Option<i64> {
+        self.theme_id
+    }
+
+    /// Nastaví motiv playlistu (`None` = motivy písní). Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn set_theme_id(&mut self, theme_id: Option<i64>) {
+        self.theme_id = theme_id;
+
+        if let PlaylistMetadataStatus::Clean(id) = self.status {
+            self.status = PlaylistMetadataStatus::Dirty(id);
+        }
+    }
+
+    /// Vrátí jméno člověka v roli `role`, pokud ji nikdo nemá, vrací `None`
+    pub fn get_role(&self, role: &str) -> Option<&str> {
+        self.roles.get(role).map(String::as_str)
+    }
+
+    /// Vrátí obsazené role jako dvojice (role, jméno) seřazené podle [`SERVICE_ROLES`]
+    pub fn get_roles(&self) -> Vec<(&'static str, &str)> {
+        SERVICE_ROLES
+            .iter()
+            .filter_map(|role| self.get_role(role).map(|person| (*role, person)))
+            .collect()
+    }
+
+    /// Přiřadí roli `role` člověku `person`, prázdné jméno nebo `None` roli zruší.
+    /// Pokud role není v [`SERVICE_ROLES`], vrací Error. Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn set_role(&mut self, role: &str, person: Option<&str>) -> Result<()> {
+        if !SERVICE_ROLES.contains(&role) {
+            bail!("Neznámá role '{role}'");
+        }
+
+        match person.map(str::trim).filter(|person| !person.is_empty()) {
+            Some(person) => self.roles.insert(role.to_string(), person.to_string()),
+            None => self.roles.remove(role),
+        };
+
+        if let PlaylistMetadataStatus::Clean(id) = self.status {
+            self.status = PlaylistMetadataStatus::Dirty(id);
+        }
+
+        Ok(())
+    }
+
+    /// Convenience funkce pro vkládání písní na konec playlistu. Má stejné chování jako [`PlaylistMetadata::add_song`].
+    pub fn push_song(&mut self, song_id: i64) {
+        self.add_song(song_id, self.items.len());
+    }
+
+    /// Přidá píseň s ID `song_id` do playlistu na pozici `position`. Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn add_song(&mut self, song_id: i64, position: usize) {
+        self.items.insert(
+            position,
+            PlaylistEntry::new(PlaylistItemMetadata::Song(song_id)),
+        );
+
+        if let PlaylistMetadataStatus::Clean(id) = self.status {
+            self.status = PlaylistMetadataStatus::Dirty(id);
+        }
+    }
+
+    /// Convenience funkce pro vkládání pasáží na konec playlistu. Má stejné chování jako [`PlaylistMetadata::add_bible_passage`].
+    pub fn push_bible_passage(&mut self, translation_id: i64, from: VerseIndex, to: VerseIndex) {
+        self.add_bible_passage(translation_id, from, to, self.items.len());
+    }
+
+    /// Přidá pasáž do playlistu na pozici `position`. Pasáž bude z překladu s ID `translation_id` a bude od `from` do `to`. Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn add_bible_passage(
+        &mut self,
+        translation_id: i64,
+        from: VerseIndex,
+        to: VerseIndex,
+        position: usize,
+    ) {
+        self.items.insert(
+            position,
+            PlaylistEntry::new(PlaylistItemMetadata::BiblePassage {
+                translation_id,
+                from,
+                to,
+                slide_breaks: None,
+            }),
+        );
+
+        if let PlaylistMetadataStatus::Clean(id) = self.status {
+            self.status = PlaylistMetadataStatus::Dirty(id);
+        }
+    }
+
+    /// Nahradí píseň na indexu `position` písní s ID `song_id`, jejíž obsah je `song`. Pozice
+    /// i ostatní data položky (očekávaná délka, tónina, úpravy slajdů) zůstanou zachovány,
+    /// z vlastního pořadí částí se vypustí části, které nová píseň nemá (pokud nezbude žádná,
+    /// vlastní pořadí se zruší).
+    /// Pokud na tomto indexu není píseň, vrací Error. Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn replace_song(&mut self, position: usize, song_id: i64, song: &Song) -> Result<()> {
+        match self.items.get_mut(position) {
+            Some(PlaylistEntry {
+                item: PlaylistItemMetadata::Song(old_song_id),
+                arrangement,
+                ..
+            }) => {
+                *old_song_id = song_id;
+                *arrangement = arrangement
+                    .take()
+                    .map(|mut tags| {
+                        tags.retain(|tag| song.parts.contains_key(tag));
+                        tags
+                    })
+                    .filter(|tags| !tags.is_empty());
+
+                if let PlaylistMetadataStatus::Clean(id) = self.status {
+                    self.status = PlaylistMetadataStatus::Dirty(id);
+                }
+
+                Ok(())
+            }
+            Some(_) => bail!("Položka na indexu {position} není píseň"),
+            None => bail!("Položka na indexu {position} neexistuje"),
+        }
+    }
+
+    /// Nahradí pasáž na indexu `position` pasáží z překladu s ID `translation_id` od `from`
+    /// do `to`, pozice i očekávaná délka položky zůstanou zachovány. Zlomy slajdů se zachovají
+    /// pouze pokud se rozsah pasáže nezměnil. Pokud na tomto indexu není pasáž, vrací Error.
+    /// Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn replace_bible_passage(
+        &mut self,
+        position: usize,
+        translation_id: i64,
+        from: VerseIndex,
+        to: VerseIndex,
+    ) -> Result<()> {
+        match self.items.get_mut(position) {
+            Some(PlaylistEntry {
+                item:
+                    PlaylistItemMetadata::BiblePassage {
+                        translation_id: old_translation_id,
+                        from: old_from,
+                        to: old_to,
+                        slide_breaks,
+                    },
+                saved_order,
+                ..
+            }) => {
+                if (*old_from, *old_to) != (from, to) {
+                    *slide_breaks = None;
+                    *saved_order = None;
+                }
+                *old_translation_id = translation_id;
+                *old_from = from;
+                *old_to = to;
+
+                if let PlaylistMetadataStatus::Clean(id) = self.status {
+                    self.status = PlaylistMetadataStatus::Dirty(id);
+                }
+
+                Ok(())
+            }
+            Some(_) => bail!("Položka na indexu {position} není pasáž"),
+            None => bail!("Položka na indexu {position} neexistuje"),
+        }
+    }
+
+    /// Nastaví zlomy slajdů pasáže na indexu `position` (`None` = automatické rozdělení),
+    /// viz [`PlaylistItemMetadata::BiblePassage`]. Pokud na tomto indexu není pasáž, vrací
+    /// Error. Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn set_slide_breaks(
+        &mut self,
+        position: usize,
+        slide_breaks: Option<Vec<usize>>,
+    ) -> Result<()> {
+        match self.items.get_mut(position) {
+            Some(PlaylistEntry {
+                item:
+                    PlaylistItemMetadata::BiblePassage {
+                        slide_breaks: breaks,
+                        ..
+                    },
+                ..
+            }) => {
+                *breaks = slide_breaks;
+
+                if let PlaylistMetadataStatus::Clean(id) = self.status {
+                    self.status = PlaylistMetadataStatus::Dirty(id);
+                }
+
+                Ok(())
+            }
+            Some(_) => bail!("Položka na indexu {position} není pasáž"),
+            None => bail!("Položka na indexu {position} neexistuje"),
+        }
+    }
+
+    /// Convenience funkce pro vkládání textů na konec playlistu. Má stejné chování jako [`PlaylistMetadata::add_custom_text`].
+    pub fn push_custom_text(&mut self, title: &str, body: &str) {
+        self.add_custom_text(title, body, self.items.len());
+    }
+
+    /// Přidá vlastní text s názvem `title` a obsahem `body` do playlistu na pozici `position`.
+    /// Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn add_custom_text(&mut self, title: &str, body: &str, position: usize) {
+        self.items.insert(
+            position,
+            PlaylistEntry::new(PlaylistItemMetadata::CustomText {
+                title: title.to_string(),
+                body: body.to_string(),
+            }),
+        );
+
+        if let PlaylistMetadataStatus::Clean(id) = self.status {
+            self.status = PlaylistMetadataStatus::Dirty(id);
+        }
+    }
+
+    /// Nahradí název a obsah vlastního textu na indexu `position`, pozice i očekávaná délka
+    /// položky zůstanou zachovány. Pokud na tomto indexu není vlastní text, vrací Error.
+    /// Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn replace_custom_text(&mut self, position: usize, title: &str, body: &str) -> Result<()> {
+        match self.items.get_mut(position) {
+            Some(PlaylistEntry {
+                item:
+                    PlaylistItemMetadata::CustomText {
+                        title: old_title,
+                        body: old_body,
+                    },
+                ..
+            }) => {
+                *old_title = title.to_string();
+                *old_body = body.to_string();
+
+                if let PlaylistMetadataStatus::Clean(id) = self.status {
+                    self.status = PlaylistMetadataStatus::Dirty(id);
+                }
+
+                Ok(())
+            }
+            Some(_) => bail!("Položka na indexu {position} není text"),
+            None => bail!("Položka na indexu {position} neexistuje"),
+        }
+    }
+
+    /// Convenience funkce pro vkládání obrázků na konec playlistu. Má stejné chování jako [`PlaylistMetadata::add_image`].
+    pub fn push_image(&mut self, media_id: i64) {
+        self.add_image(media_id, self.items.len());
+    }
+
+    /// Přidá obrázek s ID média `media_id` do playlistu na pozici `position`.
+    /// Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn add_image(&mut self, media_id: i64, position: usize) {
+        self.items.insert(
+            position,
+            PlaylistEntry::new(PlaylistItemMetadata::Image(media_id)),
+        );
+
+        if let PlaylistMetadataStatus::Clean(id) = self.status {
+            self.status = PlaylistMetadataStatus::Dirty(id);
+        }
+    }
+
+    /// Convenience funkce pro vkládání bloků na konec playlistu. Má stejné chování jako [`PlaylistMetadata::add_block`].
+    pub fn push_block(&mut self, block_id: i64) {
+        self.add_block(block_id, self.items.len());
+    }
+
+    /// Přidá playlist s ID `block_id` jako blok do playlistu na pozici `position`, zda
+    /// nevznikne cyklus, se kontroluje až při uložení. Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn add_block(&mut self, block_id: i64, position: usize) {
+        self.items.insert(
+            position,
+            PlaylistEntry::new(PlaylistItemMetadata::Block(block_id)),
+        );
+
+        if let PlaylistMetadataStatus::Clean(id) = self.status {
+            self.status = PlaylistMetadataStatus::Dirty(id);
+        }
+    }
+
+    /// Vrátí kopii playlistu, ve které jsou bloky (i vnořené) nahrazeny svými položkami
+    /// (včetně jejich tónin, pořadí, ...), světelné cue bloku přebije cue jeho první
+    /// položky. Slouží ke čtení celého obsahu (export, kontrola), kopie se nemá ukládat.
+    ///
+    /// Pokud playlist (i nepřímo) obsahuje sám sebe, vrací [`EkklesDataError::InvalidData`],
+    /// pokud nastane chyba při načítání bloků, vrací Error.
+    pub async fn expand_blocks(
+        &self,
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> crate::error::Result<Self> {
+        let mut visiting = match self.status {
+            PlaylistMetadataStatus::Transient => Vec::new(),
+            PlaylistMetadataStatus::Clean(id) | PlaylistMetadataStatus::Dirty(id) => vec![id],
+        };
+        self.expand_blocks_nested(conn, &mut visiting).await
+    }
+
+    /// Rekurzivní část [`PlaylistMetadata::expand_blocks()`], `visiting` jsou ID playlistů,
+    /// které se právě rozbalují (kvůli detekci cyklu)
+    fn expand_blocks_nested<'a>(
+        &'a self,
+        conn: &'a mut PoolConnection<Sqlite>,
+        visiting: &'a mut Vec<i64>,
+    ) -> BoxFuture<'a, crate::error::Result<Self>> {
+        async move {
+            let mut expanded = self.clone();
+
+            // Odzadu, aby rozbalení bloku neposunulo pozice dosud nerozbalených
+            for (position, entry) in self.items.iter().enumerate().rev() {
+                let PlaylistItemMetadata::Block(block_id) = entry.item else {
+                    continue;
+                };
+                if visiting.contains(&block_id) {
+                    return Err(EkklesDataError::InvalidData(format!(
+                        "Playlist s id {block_id} obsahuje (i nepřímo) sám sebe jako blok"
+                    )));
+                }
+
+                visiting.push(block_id);
+                let block = Self::load_with(block_id, conn).await?;
+                let block = block.expand_blocks_nested(conn, visiting).await?;
+                visiting.pop();
+
+                let mut block_items = block.items;
+                if let (Some(cue), Some(first)) = (entry.lighting_cue, block_items.first_mut()) {
+                    first.lighting_cue = Some(cue);
+                }
+                for block_entry in &mut block_items {
+                    // Vypnutý blok vypne všechny své položky
+                    block_entry.enabled &= entry.enabled;
+                    // Úpravy slajdů položek bloku patří k bloku, ne k tomuto playlistu
+                    block_entry.saved_order = None;
+                }
+                expanded.items.splice(position..position + 1, block_items);
+            }
+
+            Ok(expanded)
+        }
+        .boxed()
+    }
+
+    /// Odstraní položku na indexu `position` z playlistu, pokud na tomto indexu neexistje
+    /// položka, vrací Error. Pokud byl status `clean`, shodí jej na `dirty`.
+    pub fn delete_item(&mut self, position: usize) -> Result<()> {
+        if self.items.len() <= position {
+            bail!("Položka na indexu {position} neexistuje");
+        } else {
+            self.items.remove(position);
+
+            if let PlaylistMetadataStatus::Clean(id) = self.status {
+                self.status = PlaylistMetadataStatus::Dirty(id);
+            }
+
+            Ok(())
+        }
+    }
 
     /// Prohodí položky na pozicích `a` a `b` v playlistu, pokud je jeden index mimo vektor, vrací error. Pokud byl status `clean`, shodí jej na `dirty`.
     pub fn swap_items(&mut self, a: usize, b: usize) -> Result<()> {
@@ -612,18 +1608,92 @@ impl PlaylistMetadata {
         }
     }
 
+    /// Přeuspořádá položky tak, že na pozici `i` bude položka, která byla na pozici
+    /// `order[i]` (např. pořadí změněné během prezentace). S položkami se přesouvají
+    /// i jejich tóniny, vlastní pořadí částí, očekávané délky a světelná cue (viz [`PlaylistMetadata::swap_items`]).
+    /// Pokud `order` není permutací indexů položek, vrací Error a playlist nemění.
+    pub fn reorder_items(&mut self, order: &[usize]) -> Result<()> {
+        let mut sorted = order.to_vec();
+        sorted.sort_unstable();
+        if !sorted.into_iter().eq(0..self.items.len()) {
+            bail!("Nové pořadí {order:?} není pořadím všech položek playlistu");
+        }
+
+        // positions[původní index] = aktuální pozice, current[pozice] = původní index
+        let mut positions: Vec<usize> = (0..order.len()).collect();
+        let mut current: Vec<usize> = (0..order.len()).collect();
+        for (target, &original) in order.iter().enumerate() {
+            let from = positions[original];
+            if from != target {
+                self.swap_items(from, target)?;
+                let displaced = current[target];
+                current.swap(from, target);
+                positions[original] = target;
+                positions[displaced] = from;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Zapamatuje si aktuální pořadí položek jako jejich pořadí v uloženém playlistu
+    fn mark_saved_orders(&mut self) {
+        for (order, entry) in self.items.iter_mut().enumerate() {
+            entry.saved_order = Some(order);
+        }
+    }
+
     /// Uloží daný playlist do databáze a nastaví jeho status na [`PlaylistMetadataStatus::Clean`].
     /// Pokud je již status playlistu [`PlaylistMetadataStatus::Clean`], je tato metoda no-op.
-    pub async fn save(&mut self, conn: &mut PoolConnection<Sqlite>) -> Result<()> {
-        match self.status {
+    ///
+    /// Pokud je název playlistu obsazený jiným playlistem, vrací [`EkklesDataError::Conflict`].
+    /// Při zapnuté kontrole zápisu (viz [`crate::verify`]) se uložený playlist načte zpět
+    /// a pokud se liší, vrací [`EkklesDataError::InvalidData`].
+    pub async fn save(&mut self, conn: &mut PoolConnection<Sqlite>) -> crate::error::Result<()> {
+        let subject = match self.status {
+            PlaylistMetadataStatus::Transient => format!("playlist=nový {:?}", self.name),
+            PlaylistMetadataStatus::Clean(id) | PlaylistMetadataStatus::Dirty(id) => {
+                format!("playlist={id}")
+            }
+        };
+        span::in_span("playlist.save", subject, async move {
+            match self.status {
+                PlaylistMetadataStatus::Transient => {
+                    let new_id = self.save_transient(conn).await?;
+                    self.status = PlaylistMetadataStatus::Clean(new_id);
+                    self.mark_saved_orders();
+                }
+                PlaylistMetadataStatus::Clean(_) => return Ok(()),
+                PlaylistMetadataStatus::Dirty(_) => self.save_dirty(conn).await?,
+            }
+
+            if verify::is_enabled() {
+                self.verify_saved(conn).await?;
+            }
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Načte playlist z databáze a porovná jej s tímto, viz [`crate::verify`]. Pokud se liší
+    /// nebo playlist ještě nebyl uložen, vrací [`EkklesDataError::InvalidData`].
+    pub async fn verify_saved(
+        &self,
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> crate::error::Result<()> {
+        let id = match self.status {
             PlaylistMetadataStatus::Transient => {
-                let new_id = self.save_transient(conn).await?;
-                self.status = PlaylistMetadataStatus::Clean(new_id);
-                Ok(())
+                return Err(EkklesDataError::InvalidData(format!(
+                    "Playlist {} ještě nebyl uložen",
+                    self.name
+                )));
             }
-            PlaylistMetadataStatus::Clean(_) => Ok(()),
-            PlaylistMetadataStatus::Dirty(_) => self.save_dirty(conn).await,
-        }
+            PlaylistMetadataStatus::Clean(id) | PlaylistMetadataStatus::Dirty(id) => id,
+        };
+
+        let saved = Self::load_with(id, conn).await?;
+        verify::compare(&format!("Playlist {}", self.name), self, &saved)
     }
 
     /// Uloží "špinavý" playlist do databáze a označí jej jako čistý, pokud se nepovede, vrací Error.
@@ -649,32 +1719,67 @@ impl PlaylistMetadata {
             .await
             .context("Nelze získat transakci na poolu databáze")?;
 
-        // Update jména
+        // Blok nesmí (ani nepřímo) obsahovat tento playlist
+        let including = including_playlists(id, &mut transaction).await?;
+        if let Some(block_id) = self.get_items().find_map(|item| match item {
+            PlaylistItemMetadata::Block(block_id) if including.contains(block_id) => {
+                Some(*block_id)
+            }
+            _ => None,
+        }) {
+            bail!(EkklesDataError::InvalidData(format!(
+                "Blok s id {block_id} obsahuje (i nepřímo) playlist {}, vznikl by cyklus",
+                self.name
+            )));
+        }
+
+        // Update jména a motivu
         query!(
-            "UPDATE playlists SET name = $1 WHERE id = $2",
+            "UPDATE playlists SET name = $1, theme_id = $2 WHERE id = $3",
             self.name,
+            self.theme_id,
             id
         )
         .execute(&mut *transaction)
         .await
         .context("Nelze updatovat jméno playlistu")?;
 
+        // Úpravy slajdů se smažou kaskádou se starými položkami, odložíme je tedy stranou
+        // a po vložení nových položek je vrátíme k položkám na jejich nových pozicích
+        let overrides = slide_override::load_stored(id, &mut transaction)
+            .await
+            .context("Nelze odložit úpravy slajdů playlistu")?;
+
         // Odstranění všech starých položek
         PlaylistItemMetadata::delete_all(&mut transaction, id)
             .await
             .context("Nelze smazat staré položky playlistu")?;
 
         // Vložení nových položek
-        PlaylistItemMetadata::insert_many(&self.items, &mut transaction, id)
+        PlaylistItemMetadata::insert_many(self.get_items(), &mut transaction, id)
             .await
             .context("Nelze vložit nové položky playlistu")?;
 
+        let saved_orders: Vec<_> = self.items.iter().map(|entry| entry.saved_order).collect();
+        slide_override::restore_moved(id, overrides, &saved_orders, &mut transaction)
+            .await
+            .context("Nelze přenést úpravy slajdů playlistu")?;
+
+        save_entries(&self.items, &mut transaction, id)
+            .await
+            .context("Nelze uložit data položek playlistu")?;
+
+        save_roles(&self.roles, &mut transaction, id)
+            .await
+            .context("Nelze uložit role playlistu")?;
+
         transaction
             .commit()
             .await
             .with_context(|| format!("Commit transakce uložení playlistu {} selhal", self.name))?;
 
         self.status = PlaylistMetadataStatus::Clean(id);
+        self.mark_saved_orders();
 
         Ok(())
     }
@@ -703,75 +1808,29 @@ impl PlaylistMetadata {
         let formatted_datetime = self.created.format(DB_DATETIME_FORMAT).to_string();
 
         let playlist_id = query!(
-            "INSERT INTO playlists (name, created) VALUES ($1, datetime($2))",
+            "INSERT INTO playlists (name, created, theme_id) VALUES ($1, datetime($2), $3)",
             self.name,
-            formatted_datetime
+            formatted_datetime,
+            self.theme_id
         )
         .execute(&mut *transaction)
         .await
         .with_context(|| format!("Nelze uložit playlist '{}' do databáze", self.name))?
         .last_insert_rowid();
 
-        for (order, item) in self.items.iter().enumerate() {
-            let order: u32 = order.try_into().with_context(|| {
-                format!(
-                    "Playlist obsahuje více než {} položek (proč???), nelze uložit",
-                    u32::MAX
-                )
+        PlaylistItemMetadata::insert_many(self.get_items(), &mut transaction, playlist_id)
+            .await
+            .with_context(|| {
+                format!("Nelze uložit položky playlistu '{}' do databáze", self.name)
             })?;
 
-            let item_kind = match item {
-                PlaylistItemMetadata::BiblePassage { .. } => DB_PLAYLIST_KIND_BIBLE_PASSAGE,
-                PlaylistItemMetadata::Song(_) => DB_PLAYLIST_KIND_SONG,
-            };
-
-            query!(
-                "INSERT INTO playlist_parts (playlist_id, part_order, kind) VALUES ($1, $2, $3)",
-                playlist_id,
-                order,
-                item_kind
-            )
-            .execute(&mut *transaction)
+        save_entries(&self.items, &mut transaction, playlist_id)
             .await
-            .with_context(|| format!("Nelze uložit část playlistu '{}' do databáze", self.name))?;
+            .with_context(|| format!("Nelze uložit data položek playlistu '{}'", self.name))?;
 
-            match item {
-                PlaylistItemMetadata::BiblePassage {
-                    translation_id,
-                    from,
-                    to,
-                } => {
-                    let (from_book, from_chapter, from_verse_number) = from.destructure_numeric();
-                    let (to_book, to_chapter, to_verse_number) = to.destructure_numeric();
-                    query!(
-                        "INSERT INTO playlist_passages ( playlist_id, part_order, translation_id , start_book_id , start_chapter , start_number , end_book_id , end_chapter , end_number) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
-                        playlist_id,
-                        order,
-                        translation_id,
-                        from_book,
-                        from_chapter,
-                        from_verse_number,
-                        to_book,
-                        to_chapter,
-                        to_verse_number
-                    )
-                    .execute(&mut *transaction)
-                    .await
-                    .with_context(|| format!("Nelze uložit pasáž playlistu '{}' do databáze", self.name))?; // TODO: Tu pasáž lze i pojmenovat, až budeme mít Display pro Passage/VerseIndex
-                }
-                PlaylistItemMetadata::Song(song_id) => {
-                    query!(
-                        "INSERT INTO playlist_songs (playlist_id, part_order, song_id) VALUES ($1, $2, $3)",
-                        playlist_id,
-                        order,
-                        song_id
-                    )
-                    .execute(&mut *transaction)
-                    .await
-                    .with_context(|| format!("Nelze uložit píseň s ID {} playlistu '{}' do databáze", song_id, self.name))?;
-                }
-            }
-        }
+        save_roles(&self.roles, &mut transaction, playlist_id)
+            .await
+            .with_context(|| format!("Nelze uložit role playlistu '{}'", self.name))?;
 
         transaction
             .commit()
@@ -782,6 +1841,304 @@ impl PlaylistMetadata {
     }
 }
 
+impl Checksum for PlaylistMetadata {
+    fn canonical_content(&self) -> String {
+        let mut roles: Vec<_> = self.roles.iter().collect();
+        roles.sort();
+
+        let mut lines = vec![
+            format!("name: {:?}", self.name),
+            format!("created: {}", self.created.to_rfc3339()),
+            format!("theme: {:?}", self.theme_id),
+        ];
+        lines.extend(
+            roles
+                .into_iter()
+                .map(|(role, person)| format!("role {role}: {person:?}")),
+        );
+        lines.extend(self.items.iter().enumerate().map(|(position, entry)| {
+            format!(
+                "item {position}: {:?}, duration {:?}, key {:?}, arrangement {:?}, cue {:?}, enabled {}",
+                entry.item,
+                entry.expected_duration,
+                entry.song_key,
+                entry.arrangement,
+                entry.lighting_cue,
+                entry.enabled
+            )
+        }));
+
+        lines.join("\n")
+    }
+}
+
+/// Uloží data položek playlistu `playlist_id` (očekávané délky, tóniny, ...),
+/// položky již musí být v databázi.
+///
+/// ### Transakce
+/// Volající je odpovědný za commit/rollback transakce, tato funkce pouze použije danou
+/// transakci k přístupu do databáze, ale commit neprovádí.
+async fn save_entries(
+    entries: &[PlaylistEntry],
+    transaction: &mut Transaction<'_, Sqlite>,
+    playlist_id: i64,
+) -> Result<()> {
+    for (order, entry) in entries.iter().enumerate() {
+        let order = order as i64;
+
+        query!(
+            "UPDATE playlist_parts SET expected_duration = $1, lighting_cue = $2, enabled = $3 WHERE playlist_id = $4 AND part_order = $5",
+            entry.expected_duration,
+            entry.lighting_cue,
+            entry.enabled,
+            playlist_id,
+            order
+        )
+        .execute(&mut **transaction)
+        .await
+        .with_context(|| format!("Nelze uložit data položky {order}"))?;
+
+        if let PlaylistItemMetadata::Song(_) = entry.item {
+            let arrangement = entry
+                .arrangement
+                .as_ref()
+                .map(|arrangement| arrangement.join(DB_ARRANGEMENT_SEPARATOR));
+
+            query!(
+                "UPDATE playlist_songs SET song_key = $1, arrangement = $2 WHERE playlist_id = $3 AND part_order = $4",
+                entry.song_key,
+                arrangement,
+                playlist_id,
+                order
+            )
+            .execute(&mut **transaction)
+            .await
+            .with_context(|| format!("Nelze uložit tóninu a pořadí písně na pozici {order}"))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Načte data položek playlistu `playlist_id` (očekávané délky, tóniny, ...) a spojí je
+/// s položkami `items`, které musí být seřazené podle pořadí.
+async fn load_entries(
+    conn: &mut PoolConnection<Sqlite>,
+    playlist_id: i64,
+    items: Vec<PlaylistItemMetadata>,
+) -> Result<Vec<PlaylistEntry>> {
+    let records = query!(
+        "SELECT playlist_parts.expected_duration, playlist_parts.lighting_cue, playlist_parts.enabled, playlist_songs.song_key, playlist_songs.arrangement FROM playlist_parts LEFT JOIN playlist_songs ON playlist_songs.playlist_id = playlist_parts.playlist_id AND playlist_songs.part_order = playlist_parts.part_order WHERE playlist_parts.playlist_id = $1 ORDER BY playlist_parts.part_order ASC",
+        playlist_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .context("Nelze načíst data položek z databáze")?;
+
+    Ok(items
+        .into_iter()
+        .zip(records)
+        .enumerate()
+        .map(|(order, (item, record))| PlaylistEntry {
+            item,
+            expected_duration: record.expected_duration.map(|duration| duration as u32),
+            song_key: record.song_key,
+            arrangement: record.arrangement.as_deref().map(arrangement_from_db),
+            lighting_cue: record.lighting_cue.map(|cue| cue as u8),
+            enabled: record.enabled != 0,
+            saved_order: Some(order),
+        })
+        .collect())
+}
+
+/// Převede hodnotu sloupce `arrangement` na tagy vlastního pořadí písně
+fn arrangement_from_db(arrangement: &str) -> Vec<PartTag> {
+    arrangement
+        .split(DB_ARRANGEMENT_SEPARATOR)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Uloží role playlistu `playlist_id`, původní role playlistu nahradí.
+///
+/// ### Transakce
+/// Volající je odpovědný za commit/rollback transakce, tato funkce pouze použije danou
+/// transakci k přístupu do databáze, ale commit neprovádí.
+async fn save_roles(
+    roles: &HashMap<String, String>,
+    transaction: &mut Transaction<'_, Sqlite>,
+    playlist_id: i64,
+) -> Result<()> {
+    query!(
+        "DELETE FROM playlist_roles WHERE playlist_id = $1",
+        playlist_id
+    )
+    .execute(&mut **transaction)
+    .await
+    .context("Nelze smazat staré role playlistu")?;
+
+    for (role, person) in roles {
+        query!(
+            "INSERT INTO playlist_roles (playlist_id, role, person) VALUES ($1, $2, $3)",
+            playlist_id,
+            role,
+            person
+        )
+        .execute(&mut **transaction)
+        .await
+        .with_context(|| format!("Nelze uložit roli {role}"))?;
+    }
+
+    Ok(())
+}
+
+/// Načte role playlistu `playlist_id` jako mapu role -> jméno.
+async fn load_roles(
+    conn: &mut PoolConnection<Sqlite>,
+    playlist_id: i64,
+) -> Result<HashMap<String, String>> {
+    query!(
+        "SELECT role, person FROM playlist_roles WHERE playlist_id = $1",
+        playlist_id
+    )
+    .map(|record| (record.role, record.person))
+    .fetch_all(conn.as_mut())
+    .await
+    .map(|roles| roles.into_iter().collect())
+    .context("Nelze načíst role playlistu z databáze")
+}
+
+/// Převede zlomy slajdů pasáže na hodnotu sloupce `slide_breaks` (vektor uložený jako text)
+fn slide_breaks_to_db(slide_breaks: Option<&[usize]>) -> Option<String> {
+    slide_breaks.map(|breaks| {
+        breaks
+            .iter()
+            .map(|verse| verse.to_string())
+            .collect::<Vec<_>>()
+            .join(DB_SLIDE_BREAKS_SEPARATOR)
+    })
+}
+
+/// Zparsuje zlomy slajdů pasáže ze sloupce `slide_breaks`, pokud nejsou čísla, vrací Error.
+fn slide_breaks_from_db(value: Option<&str>) -> Result<Option<Vec<usize>>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    value
+        .split(DB_SLIDE_BREAKS_SEPARATOR)
+        .filter(|verse| !verse.is_empty())
+        .map(|verse| {
+            verse
+                .parse()
+                .with_context(|| format!("Nevalidní zlom slajdu '{verse}' v databázi"))
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(Some)
+}
+
+/// Načte všechny pasáže playlistu `playlist_id` (i se zlomy slajdů) jako mapu
+/// pořadí položky -> pasáž. Verše všech pasáží se načtou jedním dotazem.
+async fn load_passages(
+    playlist_id: i64,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<HashMap<i64, (Passage, Option<Vec<usize>>)>> {
+    let mut verses: HashMap<i64, Vec<(u8, String)>> = HashMap::new();
+    for record in query!(
+        r#"SELECT playlist_passages.part_order AS "part_order!", verses.number AS "number!",
+            verses.content AS "content!"
+        FROM playlist_passages
+        JOIN verses AS start_verse ON start_verse.translation_id = playlist_passages.translation_id
+            AND start_verse.book_id = playlist_passages.start_book_id
+            AND start_verse.chapter = playlist_passages.start_chapter
+            AND start_verse.number = playlist_passages.start_number
+        JOIN verses AS end_verse ON end_verse.translation_id = playlist_passages.translation_id
+            AND end_verse.book_id = playlist_passages.end_book_id
+            AND end_verse.chapter = playlist_passages.end_chapter
+            AND end_verse.number = playlist_passages.end_number
+        JOIN verses ON verses.translation_id = playlist_passages.translation_id
+            AND verses.verse_order BETWEEN start_verse.verse_order AND end_verse.verse_order
+        WHERE playlist_passages.playlist_id = $1
+        ORDER BY playlist_passages.part_order, verses.verse_order"#,
+        playlist_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .with_context(|| format!("Nelze načíst verše pasáží playlistu s id {playlist_id}"))?
+    {
+        verses
+            .entry(record.part_order)
+            .or_default()
+            .push((record.number as u8, record.content));
+    }
+
+    let records = query!(
+        r#"SELECT playlist_passages.part_order AS "part_order!",
+            playlist_passages.translation_id AS "translation_id!",
+            translations.name AS "translation_name!",
+            playlist_passages.start_book_id AS "start_book_id!",
+            playlist_passages.start_chapter AS "start_chapter!",
+            playlist_passages.start_number AS "start_number!",
+            playlist_passages.end_book_id AS "end_book_id!",
+            playlist_passages.end_chapter AS "end_chapter!",
+            playlist_passages.end_number AS "end_number!",
+            playlist_passages.slide_breaks,
+            verse_headings.content AS "heading?"
+        FROM playlist_passages
+        JOIN translations ON translations.id = playlist_passages.translation_id
+        LEFT JOIN verse_headings ON verse_headings.translation_id = playlist_passages.translation_id
+            AND verse_headings.book_id = playlist_passages.start_book_id
+            AND verse_headings.chapter = playlist_passages.start_chapter
+            AND verse_headings.number = playlist_passages.start_number
+        WHERE playlist_passages.playlist_id = $1"#,
+        playlist_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .with_context(|| format!("Nelze načíst pasáže playlistu s id {playlist_id}"))?;
+
+    let verse_index = |book: i64, chapter: i64, number: i64| {
+        Book::try_from(book as u8)
+            .ok()
+            .and_then(|book| VerseIndex::try_new(book, chapter as u8, number as u8))
+            .with_context(|| {
+                format!("Nelze najít verš v knize {book}, kapitole {chapter} s číslem {number}")
+            })
+    };
+
+    records
+        .into_iter()
+        .map(|record| {
+            let start = verse_index(
+                record.start_book_id,
+                record.start_chapter,
+                record.start_number,
+            )?;
+            let end = verse_index(record.end_book_id, record.end_chapter, record.end_number)?;
+            let passage_verses = verses
+                .remove(&record.part_order)
+                .filter(|verses| !verses.is_empty())
+                .with_context(|| {
+                    format!(
+                        "Nelze načíst pasáž od {start:?} do {end:?} v překladu {}",
+                        record.translation_id
+                    )
+                })?;
+
+            let passage = Passage::from_loaded(
+                record.translation_id,
+                record.translation_name,
+                (start, end),
+                passage_verses,
+                record.heading,
+            );
+            let breaks = slide_breaks_from_db(record.slide_breaks.as_deref())?;
+
+            Ok((record.part_order, (passage, breaks)))
+        })
+        .collect()
+}
+
 /// Co všechno může být rozdíl mezi dvěma [`PlaylistMetadata`].
 #[derive(Debug, PartialEq, Eq)]
 enum PlaylistMetadataDiff {
@@ -798,6 +2155,30 @@ enum PlaylistMetadataDiff {
 pub enum PlaylistItem {
     BiblePassage(Passage),
     Song(Song),
+    CustomText {
+        title: String,
+        body: String,
+    },
+    /// Obrázek, `data` je jeho zmenšená verze (JPEG), viz [`crate::media`]
+    Image {
+        media_id: i64,
+        name: String,
+        data: Vec<u8>,
+    },
+}
+
+/// Odkud pochází položka načteného [`Playlist`], viz [`Playlist::item_origins`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemOrigin {
+    /// Pozice položky v načteném playlistu, položky bloku mají pozici bloku
+    pub position: usize,
+    /// ID playlistu, ve kterém je položka uložena (u položky bloku ID bloku)
+    pub playlist_id: i64,
+    /// Pořadí položky v playlistu `playlist_id`
+    pub part_order: usize,
+    /// Název bloku, ze kterého položka pochází (u vnořených bloků toho nejvnějšího),
+    /// `None` u položek přímo v playlistu
+    pub block: Option<String>,
 }
 
 /// Struktura reprezentující playlist, která vlastní obsah svých položek. Je tedy "nezávislá",
@@ -809,15 +2190,78 @@ pub struct Playlist {
     pub name: String,
     pub created: DateTime<Utc>,
     pub items: Vec<PlaylistItem>,
+    /// Motiv playlistu, pokud je nastaven, přebije motivy jednotlivých položek
+    pub theme: Option<SlideTheme>,
+    /// Preferované motivy jednotlivých položek (u písní jejich motiv), vždy stejně dlouhý jako `items`
+    pub item_themes: Vec<Option<SlideTheme>>,
+    /// Zlomy slajdů jednotlivých položek (pouze u pasáží, viz [`PlaylistItemMetadata::BiblePassage`]),
+    /// vždy stejně dlouhý jako `items`
+    pub slide_breaks: Vec<Option<Vec<usize>>>,
+    /// Tóniny písní pro tuto bohoslužbu (u pasáží vždy `None`), vždy stejně dlouhý jako `items`
+    pub song_keys: Vec<Option<String>>,
+    /// Čísla světelných cue položek (`None` = bez cue), vždy stejně dlouhý jako `items`
+    pub lighting_cues: Vec<Option<u8>>,
+    /// Překlady písní (u pasáží a písní bez překladu `None`), vždy stejně dlouhý jako `items`
+    pub song_translations: Vec<Option<Song>>,
+    /// Zmenšené verze obrázků na pozadí použitých motivů (id média -> data),
+    /// viz [`crate::theme::BackgroundImage`]
+    pub backgrounds: HashMap<i64, Vec<u8>>,
+    /// Odkud pochází jednotlivé položky (bloky jsou rozbalené), vždy stejně dlouhý jako `items`
+    pub item_origins: Vec<ItemOrigin>,
+    /// Zda se položky promítají (viz [`PlaylistMetadata::set_item_enabled()`], položky
+    /// vypnutého bloku jsou vypnuté všechny), vždy stejně dlouhý jako `items`
+    pub enabled: Vec<bool>,
 }
 
 impl Playlist {
-    /// Načte playlist s daným ID z databáze.
-    pub async fn load(id: i64, conn: &mut PoolConnection<Sqlite>) -> Result<Self> {
-        let playlist_record = query!("SELECT id, name, created FROM playlists WHERE id = $1", id)
-            .fetch_one(conn.as_mut())
-            .await
-            .with_context(|| format!("Playlist s id {id} nebyl nalezen"))?;
+    /// Načte playlist s daným ID z databáze. Pokud takový playlist neexistuje, vrátí
+    /// [`EkklesDataError::NotFound`]. Písně s vlastním pořadím částí (viz
+    /// [`PlaylistMetadata::set_arrangement()`]) mají toto pořadí v [`Song::order`], pokud
+    /// odkazuje na část, kterou píseň nemá, vrací [`EkklesDataError::InvalidData`].
+    ///
+    /// Bloky (viz [dokumentace modulu](crate::playlist)) se rozbalí na své položky, motiv
+    /// bloku přebije motivy jeho položek a světelné cue bloku cue jeho první položky. Pokud
+    /// playlist (i nepřímo) obsahuje sám sebe, vrací [`EkklesDataError::InvalidData`].
+    pub async fn load(id: i64, conn: &mut PoolConnection<Sqlite>) -> crate::error::Result<Self> {
+        span::in_span(
+            "playlist.load_for_presentation",
+            format!("playlist={id}"),
+            async move { Self::load_nested(id, conn, &mut Vec::new()).await },
+        )
+        .await
+    }
+
+    /// Rekurzivní část [`Playlist::load()`], `visiting` jsou ID playlistů, které se právě
+    /// načítají (kvůli detekci cyklu)
+    fn load_nested<'a>(
+        id: i64,
+        conn: &'a mut PoolConnection<Sqlite>,
+        visiting: &'a mut Vec<i64>,
+    ) -> BoxFuture<'a, crate::error::Result<Self>> {
+        async move {
+        if visiting.contains(&id) {
+            return Err(EkklesDataError::InvalidData(format!(
+                "Playlist s id {id} obsahuje (i nepřímo) sám sebe jako blok"
+            )));
+        }
+        visiting.push(id);
+
+        let playlist_record = query!(
+            "SELECT id, name, created, theme_id FROM playlists WHERE id = $1",
+            id
+        )
+        .fetch_one(conn.as_mut())
+        .await
+        .with_context(|| format!("Playlist s id {id} nebyl nalezen"))?;
+
+        let theme = match playlist_record.theme_id {
+            Some(theme_id) => Some(
+                SlideTheme::load_from_db(theme_id, conn)
+                    .await
+                    .context("Nelze načíst motiv playlistu")?,
+            ),
+            None => None,
+        };
 
         let name = playlist_record.name;
         let created = NaiveDateTime::parse_from_str(&playlist_record.created, DB_DATETIME_FORMAT)
@@ -830,86 +2274,287 @@ impl Playlist {
             .and_utc();
 
         let parts = query!(
-            "SELECT part_order, kind FROM playlist_parts WHERE playlist_id = $1 ORDER BY part_order ASC",
+            "SELECT part_order, kind, lighting_cue, enabled FROM playlist_parts WHERE playlist_id = $1 ORDER BY part_order ASC",
             id
         ).fetch_all(conn.as_mut()).await
             .context("Nelze načíst další část playlistu z databáze")?
         ;
 
+        // Obsah položek se načte hromadně (jeden dotaz pro každý druh položky), ne zvlášť
+        // pro každou položku, jinak by dlouhý playlist potřeboval desítky dotazů
+        let mut playlist_songs: HashMap<i64, (i64, Option<String>, Option<String>)> = query!(
+            "SELECT part_order, song_id, song_key, arrangement FROM playlist_songs WHERE playlist_id = $1",
+            id
+        )
+        .fetch_all(conn.as_mut())
+        .await
+        .with_context(|| format!("Nelze načíst písně playlistu s id {id}"))?
+        .into_iter()
+        .map(|record| {
+            (
+                record.part_order,
+                (record.song_id, record.song_key, record.arrangement),
+            )
+        })
+        .collect();
+        let songs = Song::load_for_playlist(id, conn)
+            .await
+            .context("Nelze načíst písně do playlistu")?;
+        let mut passages = load_passages(id, conn).await?;
+        let mut texts: HashMap<i64, (String, String)> = query!(
+            "SELECT part_order, title, body FROM playlist_texts WHERE playlist_id = $1",
+            id
+        )
+        .fetch_all(conn.as_mut())
+        .await
+        .with_context(|| format!("Nelze načíst texty playlistu s id {id}"))?
+        .into_iter()
+        .map(|record| (record.part_order, (record.title, record.body)))
+        .collect();
+        let mut images: HashMap<i64, (i64, String, Vec<u8>)> = query!(
+            r#"SELECT playlist_images.part_order AS "part_order!", media.id AS "media_id!",
+                media.name AS "name!", media.optimized AS "optimized!"
+            FROM playlist_images JOIN media ON media.id = playlist_images.media_id
+            WHERE playlist_images.playlist_id = $1"#,
+            id
+        )
+        .fetch_all(conn.as_mut())
+        .await
+        .with_context(|| format!("Nelze načíst obrázky playlistu s id {id}"))?
+        .into_iter()
+        .map(|record| {
+            (
+                record.part_order,
+                (record.media_id, record.name, record.optimized),
+            )
+        })
+        .collect();
+        let mut blocks: HashMap<i64, i64> = query!(
+            "SELECT part_order, block_id FROM playlist_blocks WHERE playlist_id = $1",
+            id
+        )
+        .fetch_all(conn.as_mut())
+        .await
+        .with_context(|| format!("Nelze načíst bloky playlistu s id {id}"))?
+        .into_iter()
+        .map(|record| (record.part_order, record.block_id))
+        .collect();
+        // Stejný motiv mívá více písní, každý se načte jen jednou
+        let mut song_themes: HashMap<i64, SlideTheme> = HashMap::new();
+
         // Pořadí vkládání nemusíme řešit, z databáze to přijde již seřazené
         let mut items = Vec::new();
+        let mut item_themes = Vec::new();
+        let mut slide_breaks = Vec::new();
+        let mut song_keys = Vec::new();
+        let mut song_translations = Vec::new();
+        let mut lighting_cues = Vec::new();
+        let mut item_origins = Vec::new();
+        let mut enabled = Vec::new();
+        let mut backgrounds = HashMap::new();
 
         for part_record in parts {
+            let part_order = part_record.part_order;
+            let lighting_cue = part_record.lighting_cue.map(|cue| cue as u8);
+            let part_enabled = part_record.enabled != 0;
+
+            if part_record.kind == DB_PLAYLIST_KIND_BLOCK {
+                let block_id = blocks.remove(&part_order).with_context(|| {
+                    format!(
+                        "Nelze načíst blok do playlistu s id {id} a pořadovým číslem {part_order}"
+                    )
+                })?;
+                let block = Self::load_nested(block_id, conn, visiting).await?;
+
+                let block_theme = block.theme;
+                items.extend(block.items);
+                item_themes.extend(
+                    block
+                        .item_themes
+                        .into_iter()
+                        .map(|theme| block_theme.clone().or(theme)),
+                );
+                slide_breaks.extend(block.slide_breaks);
+                song_keys.extend(block.song_keys);
+                song_translations.extend(block.song_translations);
+                lighting_cues.extend(block.lighting_cues.into_iter().enumerate().map(
+                    |(index, cue)| if index == 0 { lighting_cue.or(cue) } else { cue },
+                ));
+                item_origins.extend(block.item_origins.into_iter().map(|origin| ItemOrigin {
+                    position: part_order as usize,
+                    block: Some(block.name.clone()),
+                    ..origin
+                }));
+                enabled.extend(
+                    block
+                        .enabled
+                        .into_iter()
+                        .map(|enabled| enabled && part_enabled),
+                );
+                backgrounds.extend(block.backgrounds);
+                continue;
+            }
+
+            lighting_cues.push(lighting_cue);
+            item_origins.push(ItemOrigin {
+                position: part_order as usize,
+                playlist_id: id,
+                part_order: part_order as usize,
+                block: None,
+            });
+            enabled.push(part_enabled);
             match part_record.kind.as_str() {
                 DB_PLAYLIST_KIND_SONG => {
-                    let song_id = query!(
-                        "SELECT song_id FROM playlist_songs WHERE playlist_id = $1 AND part_order = $2",
-                        id,
-                        part_record.part_order
-                    ).fetch_one(conn.as_mut()).await.with_context(|| format!("Nelze načíst píseň do playlistu s id {} a pořadovým číslem {}", id, part_record.part_order))?.song_id;
+                    let (song_id, song_key, arrangement) =
+                        playlist_songs.remove(&part_order).with_context(|| {
+                            format!(
+                                "Nelze načíst píseň do playlistu s id {id} a pořadovým číslem {part_order}"
+                            )
+                        })?;
+                    let playlist_song = songs
+                        .get(&song_id)
+                        .with_context(|| format!("Píseň s id {song_id} nebyla nalezena"))?;
+
+                    let song_theme = match playlist_song.theme_id {
+                        Some(theme_id) => Some(match song_themes.get(&theme_id) {
+                            Some(theme) => theme.clone(),
+                            None => {
+                                let theme = SlideTheme::load_from_db(theme_id, conn)
+                                    .await
+                                    .context("Nelze načíst motiv písně")?;
+                                song_themes.insert(theme_id, theme.clone());
+                                theme
+                            }
+                        }),
+                        None => None,
+                    };
+
+                    let translation = match playlist_song.translated_song_id {
+                        Some(translation_id) => Some(
+                            songs
+                                .get(&translation_id)
+                                .map(|translation| translation.song.clone())
+                                .context("Nelze načíst překlad písně")?,
+                        ),
+                        None => None,
+                    };
 
-                    let song = Song::load_from_db(song_id, conn)
-                        .await
-                        .context("Nelze načíst píseň do playlistu")?;
+                    // Vlastní pořadí položky přebije pořadí písně, slajdy se pak tvoří podle něj
+                    let mut song = playlist_song.song.clone();
+                    if let Some(arrangement) = arrangement {
+                        let arrangement = arrangement_from_db(&arrangement);
+                        if let Some(tag) = arrangement
+                            .iter()
+                            .find(|tag| !song.parts.contains_key(*tag))
+                        {
+                            return Err(EkklesDataError::InvalidData(format!(
+                                "Vlastní pořadí písně {} na pozici {part_order} obsahuje neexistující část '{tag}'",
+                                song.title
+                            )));
+                        }
+                        song.order = arrangement;
+                    }
 
                     items.push(PlaylistItem::Song(song));
+                    item_themes.push(song_theme);
+                    slide_breaks.push(None);
+                    song_keys.push(song_key);
+                    song_translations.push(translation);
                 }
                 DB_PLAYLIST_KIND_BIBLE_PASSAGE => {
-                    let passage_record = query!(
-                        "SELECT translation_id , start_book_id , start_chapter , start_number , end_book_id , end_chapter , end_number FROM playlist_passages WHERE playlist_id = $1 AND part_order = $2",
-                        id,
-                        part_record.part_order
-                    ).fetch_one(conn.as_mut()).await.with_context(|| format!("Nelze načíst píseň do playlistu s id {} a pořadovým číslem {}", id, part_record.part_order))?;
-
-                    let start = VerseIndex::try_new(
-                        (passage_record.start_book_id as u8).try_into().unwrap(),
-                        passage_record.start_chapter as u8,
-                        passage_record.start_number as u8,
-                    )
-                    .with_context(|| {
+                    let (passage, breaks) = passages.remove(&part_order).with_context(|| {
                         format!(
-                            "Nelze najít verš v knize {}, kapitole {} s číslem {}",
-                            passage_record.start_book_id,
-                            passage_record.start_chapter,
-                            passage_record.start_number
+                            "Nelze načíst pasáž do playlistu s id {id} a pořadovým číslem {part_order}"
                         )
                     })?;
 
-                    let end = VerseIndex::try_new(
-                        (passage_record.end_book_id as u8).try_into().unwrap(),
-                        passage_record.end_chapter as u8,
-                        passage_record.end_number as u8,
-                    )
-                    .with_context(|| {
+                    items.push(PlaylistItem::BiblePassage(passage));
+                    item_themes.push(None);
+                    slide_breaks.push(breaks);
+                    song_keys.push(None);
+                    song_translations.push(None);
+                }
+                DB_PLAYLIST_KIND_CUSTOM_TEXT => {
+                    let (title, body) = texts.remove(&part_order).with_context(|| {
                         format!(
-                            "Nelze najít verš v knize {}, kapitole {} s číslem {}",
-                            passage_record.end_book_id,
-                            passage_record.end_chapter,
-                            passage_record.end_number
+                            "Nelze načíst text do playlistu s id {id} a pořadovým číslem {part_order}"
                         )
                     })?;
 
-                    let passage = Passage::load(start, end, passage_record.translation_id, conn)
-                        .await
-                        .with_context(|| {
+                    items.push(PlaylistItem::CustomText { title, body });
+                    item_themes.push(None);
+                    slide_breaks.push(None);
+                    song_keys.push(None);
+                    song_translations.push(None);
+                }
+                DB_PLAYLIST_KIND_IMAGE => {
+                    let (media_id, name, data) =
+                        images.remove(&part_order).with_context(|| {
                             format!(
-                                "Nelze načíst pasáž od {:?} do {:?} v překladu {}",
-                                start, end, passage_record.translation_id
+                                "Nelze načíst obrázek do playlistu s id {id} a pořadovým číslem {part_order}"
                             )
                         })?;
 
-                    items.push(PlaylistItem::BiblePassage(passage));
+                    items.push(PlaylistItem::Image {
+                        media_id,
+                        name,
+                        data,
+                    });
+                    item_themes.push(None);
+                    slide_breaks.push(None);
+                    song_keys.push(None);
+                    song_translations.push(None);
+                }
+                _ => {
+                    return Err(EkklesDataError::InvalidData(format!(
+                        "Neznámý druh části playlistu: {}",
+                        part_record.kind
+                    )));
                 }
-                _ => bail!("Neznámý druh části playlistu: {}", part_record.kind),
             }
         }
 
+        let background_ids = theme
+            .iter()
+            .chain(item_themes.iter().flatten())
+            .filter_map(|theme| theme.background_image)
+            .map(|image| image.media_id);
+        for media_id in background_ids {
+            if let Entry::Vacant(entry) = backgrounds.entry(media_id) {
+                let data = media::load_optimized(media_id, conn)
+                    .await
+                    .context("Nelze načíst obrázek na pozadí motivu")?;
+                entry.insert(data);
+            }
+        }
+        visiting.pop();
+
         Ok(Self {
             id,
             name,
             created,
             items,
+            theme,
+            item_themes,
+            slide_breaks,
+            song_keys,
+            lighting_cues,
+            song_translations,
+            backgrounds,
+            item_origins,
+            enabled,
         })
+        }
+        .boxed()
+    }
+
+    /// Vrátí motiv, kterým se má promítat položka na indexu `index`. Motiv playlistu
+    /// má přednost před motivem položky, pokud nemá motiv ani jeden, vrací `None`
+    /// (použije se motiv z nastavení).
+    pub fn theme_for_item(&self, index: usize) -> Option<&SlideTheme> {
+        self.theme
+            .as_ref()
+            .or_else(|| self.item_themes.get(index).and_then(|theme| theme.as_ref()))
     }
 
     pub fn into_items(self) -> Vec<PlaylistItem> {
@@ -921,22 +2566,16 @@ impl Playlist {
 mod tests {
 
     use pretty_assertions::assert_eq;
-    use sqlx::{SqlitePool, query_file};
+    use sqlx::SqlitePool;
 
     use super::*;
+    use crate::test_util;
 
     /// Funkce na vytvoření in-memory databáze pro testování. Vytvoří holou databázi
     /// a nasype do ní dvě písně a prvních 10 veršů genesis pro testování. Též vytvoří
-    /// prázdný playlist s ID 0. Pro detaily viz soubory ve `query_file!()`
+    /// prázdný playlist s ID 0. Pro detaily viz [`test_util::setup_db_with_test_data()`]
     async fn setup_test_db() -> SqlitePool {
-        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
-
-        query_file!("db/init_db.sql").execute(&pool).await.unwrap();
-
-        query_file!("db/fill_test_db.sql")
-            .execute(&pool)
-            .await
-            .unwrap();
+        let pool = test_util::setup_db_with_test_data().await;
 
         // Vložíme testovací playlist na kterém se budou operace na itemech zkoušet
         query!("INSERT INTO playlists (id, 'name') VALUES (0, 'test')")
@@ -991,6 +2630,7 @@ mod tests {
             translation_id: 0,
             from: VerseIndex::try_new(Book::Genesis, 1, 1).unwrap(),
             to: VerseIndex::try_new(Book::Genesis, 1, 10).unwrap(),
+            slide_breaks: None,
         };
 
         let mut tx1 = pool.begin().await.unwrap();
@@ -1031,6 +2671,7 @@ mod tests {
                 record.end_number as u8,
             )
             .unwrap(),
+            slide_breaks: slide_breaks_from_db(record.slide_breaks.as_deref()).unwrap(),
         })
         .fetch_one(&pool)
         .await
@@ -1039,6 +2680,278 @@ mod tests {
         assert_eq!(passage_from_db, bible_passage);
     }
 
+    #[tokio::test]
+    async fn metadata_item_slide_breaks_test() {
+        let pool = setup_test_db().await;
+
+        let bible_passage = PlaylistItemMetadata::BiblePassage {
+            translation_id: 0,
+            from: VerseIndex::try_new(Book::Genesis, 1, 1).unwrap(),
+            to: VerseIndex::try_new(Book::Genesis, 1, 10).unwrap(),
+            slide_breaks: Some(vec![3, 7]),
+        };
+        let unsplit_passage = PlaylistItemMetadata::BiblePassage {
+            translation_id: 0,
+            from: VerseIndex::try_new(Book::Genesis, 1, 1).unwrap(),
+            to: VerseIndex::try_new(Book::Genesis, 1, 10).unwrap(),
+            slide_breaks: Some(vec![]),
+        };
+
+        let mut tx1 = pool.begin().await.unwrap();
+        bible_passage.insert(&mut tx1, 0, 0).await.unwrap();
+        unsplit_passage.insert(&mut tx1, 0, 1).await.unwrap();
+        tx1.commit().await.unwrap();
+
+        let items = PlaylistItemMetadata::load_many(&mut pool.acquire().await.unwrap(), 0)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            items,
+            vec![bible_passage, unsplit_passage],
+            "Prázdný seznam zlomů (vše na jednom slajdu) se nesmí zaměnit za automatické rozdělení"
+        );
+    }
+
+    #[tokio::test]
+    async fn metadata_item_custom_text_test() {
+        let pool = setup_test_db().await;
+
+        let text = PlaylistItemMetadata::CustomText {
+            title: String::from("Oznámení"),
+            body: String::from("Po bohoslužbě je kafe\nVšichni jste zváni"),
+        };
+
+        let mut tx1 = pool.begin().await.unwrap();
+        text.insert(&mut tx1, 0, 0).await.unwrap();
+        PlaylistItemMetadata::Song(0)
+            .insert(&mut tx1, 0, 1)
+            .await
+            .unwrap();
+        tx1.commit().await.unwrap();
+
+        let kind =
+            query!("SELECT kind FROM playlist_parts WHERE playlist_id = 0 AND part_order = 0")
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+                .kind;
+        assert_eq!(kind, DB_PLAYLIST_KIND_CUSTOM_TEXT);
+
+        let text_from_db = PlaylistItemMetadata::load_one(pool.acquire().await.unwrap(), 0, 0)
+            .await
+            .unwrap();
+        assert_eq!(text_from_db, text);
+
+        let mut tx2 = pool.begin().await.unwrap();
+        text.delete(&mut tx2, 0, 0).await.unwrap();
+        tx2.commit().await.unwrap();
+
+        let items = PlaylistItemMetadata::load_many(&mut pool.acquire().await.unwrap(), 0)
+            .await
+            .unwrap();
+        assert_eq!(items, vec![PlaylistItemMetadata::Song(0)]);
+    }
+
+    #[test]
+    fn reorder_items_keeps_item_properties() {
+        let mut playlist = PlaylistMetadata::new("test");
+        playlist.push_song(0);
+        playlist.push_song(1);
+        playlist.push_custom_text("Oznámení", "Po bohoslužbě je kafe");
+        playlist.push_song(2);
+        playlist.set_song_key(1, Some("D")).unwrap();
+
+        assert!(playlist.reorder_items(&[0, 1, 2]).is_err());
+        assert!(playlist.reorder_items(&[0, 1, 1, 3]).is_err());
+
+        playlist.reorder_items(&[0, 3, 1, 2]).unwrap();
+        assert_eq!(
+            playlist.get_items().cloned().collect::<Vec<_>>(),
+            &[
+                PlaylistItemMetadata::Song(0),
+                PlaylistItemMetadata::Song(2),
+                PlaylistItemMetadata::Song(1),
+                PlaylistItemMetadata::CustomText {
+                    title: String::from("Oznámení"),
+                    body: String::from("Po bohoslužbě je kafe"),
+                },
+            ]
+        );
+        assert_eq!(playlist.get_song_key(2), Some("D"));
+        assert_eq!(playlist.get_song_key(1), None);
+    }
+
+    #[test]
+    fn arrangement_is_validated() {
+        let tags = |tags: &[&str]| Some(tags.iter().map(|tag| tag.to_string()).collect());
+
+        let mut playlist = PlaylistMetadata::new("test");
+        playlist.push_custom_text("Oznámení", "Po bohoslužbě je kafe");
+        playlist.push_song(0);
+
+        assert!(playlist.set_arrangement(1, tags(&[])).is_err());
+        assert!(playlist.set_arrangement(1, tags(&["V1", ""])).is_err());
+        assert!(playlist.set_arrangement(1, tags(&["V1 C"])).is_err());
+        assert!(
+            playlist.set_arrangement(0, tags(&["V1"])).is_err(),
+            "Text nemá pořadí částí"
+        );
+        assert!(playlist.set_arrangement(2, tags(&["V1"])).is_err());
+
+        playlist
+            .set_arrangement(1, tags(&["V1", "C", "C"]))
+            .unwrap();
+        assert_eq!(
+            playlist.get_arrangement(1),
+            Some([String::from("V1"), String::from("C"), String::from("C")].as_slice())
+        );
+        assert_eq!(playlist.get_arrangement(0), None);
+
+        playlist.set_arrangement(1, None).unwrap();
+        assert_eq!(playlist.get_arrangement(1), None);
+    }
+
+    #[test]
+    fn replace_custom_text_in_place() {
+        let mut playlist = PlaylistMetadata::new("test");
+        playlist.push_song(0);
+        playlist.push_custom_text("Uvítání", "Vítejte");
+        playlist.set_expected_duration(1, Some(30)).unwrap();
+
+        playlist
+            .replace_custom_text(1, "Uvítání", "Vítejte na bohoslužbě")
+            .unwrap();
+        assert_eq!(
+            *playlist.get_item(1).unwrap(),
+            PlaylistItemMetadata::CustomText {
+                title: String::from("Uvítání"),
+                body: String::from("Vítejte na bohoslužbě"),
+            }
+        );
+        assert_eq!(playlist.get_expected_duration(1), Some(30));
+
+        assert!(playlist.replace_custom_text(0, "A", "B").is_err());
+        assert!(playlist.replace_custom_text(2, "A", "B").is_err());
+        assert!(
+            playlist.set_song_key(1, Some("D")).is_err(),
+            "Text nemá tóninu"
+        );
+    }
+
+    #[test]
+    fn replace_song_in_place() {
+        let genesis = |verse| VerseIndex::try_new(Book::Genesis, 1, verse).unwrap();
+
+        let mut playlist = PlaylistMetadata::new("test");
+        playlist.push_bible_passage(0, genesis(1), genesis(5));
+        playlist.push_song(0);
+        playlist.set_expected_duration(1, Some(240)).unwrap();
+        playlist.set_song_key(1, Some("D")).unwrap();
+        playlist
+            .set_arrangement(1, Some(vec![String::from("V1"), String::from("C")]))
+            .unwrap();
+
+        let new_song = Song {
+            title: String::from("Nová píseň"),
+            author: None,
+            parts: HashMap::from([
+                (String::from("V1"), String::from("Sloka")),
+                (String::from("V2"), String::from("Druhá sloka")),
+            ]),
+            order: vec![String::from("V1"), String::from("V2")],
+            metadata: Default::default(),
+        };
+
+        playlist.replace_song(1, 1, &new_song).unwrap();
+        assert_eq!(
+            *playlist.get_item(1).unwrap(),
+            PlaylistItemMetadata::Song(1)
+        );
+        assert_eq!(playlist.get_expected_duration(1), Some(240));
+        assert_eq!(playlist.get_song_key(1), Some("D"));
+        assert_eq!(
+            playlist.get_arrangement(1),
+            Some([String::from("V1")].as_slice()),
+            "Refrén nová píseň nemá"
+        );
+
+        // Pokud z vlastního pořadí nic nezbude, zruší se
+        playlist
+            .set_arrangement(1, Some(vec![String::from("C")]))
+            .unwrap();
+        playlist.replace_song(1, 0, &new_song).unwrap();
+        assert_eq!(playlist.get_arrangement(1), None);
+
+        assert!(playlist.replace_song(0, 1, &new_song).is_err());
+        assert!(playlist.replace_song(2, 1, &new_song).is_err());
+    }
+
+    #[test]
+    fn replace_passage_in_place() {
+        let genesis = |verse| VerseIndex::try_new(Book::Genesis, 1, verse).unwrap();
+
+        let mut playlist = PlaylistMetadata::new("test");
+        playlist.push_song(0);
+        playlist.push_bible_passage(0, genesis(1), genesis(5));
+        playlist.push_song(1);
+        playlist.set_expected_duration(1, Some(90)).unwrap();
+        playlist.set_slide_breaks(1, Some(vec![3])).unwrap();
+
+        playlist
+            .replace_bible_passage(1, 1, genesis(1), genesis(5))
+            .unwrap();
+        assert_eq!(
+            *playlist.get_item(1).unwrap(),
+            PlaylistItemMetadata::BiblePassage {
+                translation_id: 1,
+                from: genesis(1),
+                to: genesis(5),
+                slide_breaks: Some(vec![3]),
+            },
+            "Při změně pouze překladu se zlomy slajdů zachovají"
+        );
+
+        playlist
+            .replace_bible_passage(1, 1, genesis(2), genesis(8))
+            .unwrap();
+        assert_eq!(
+            playlist.get_items().cloned().collect::<Vec<_>>(),
+            &[
+                PlaylistItemMetadata::Song(0),
+                PlaylistItemMetadata::BiblePassage {
+                    translation_id: 1,
+                    from: genesis(2),
+                    to: genesis(8),
+                    slide_breaks: None,
+                },
+                PlaylistItemMetadata::Song(1),
+            ]
+        );
+        assert_eq!(playlist.get_expected_duration(1), Some(90));
+
+        assert!(
+            playlist
+                .replace_bible_passage(0, 1, genesis(1), genesis(2))
+                .is_err()
+        );
+        assert!(
+            playlist
+                .replace_bible_passage(3, 1, genesis(1), genesis(2))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn slide_breaks_db_roundtrip() {
+        assert_eq!(slide_breaks_to_db(None), None);
+        assert_eq!(slide_breaks_to_db(Some(&[2, 5])), Some(String::from("2,5")));
+        assert_eq!(slide_breaks_from_db(Some("2,5")).unwrap(), Some(vec![2, 5]));
+        assert_eq!(slide_breaks_from_db(Some("")).unwrap(), Some(vec![]));
+        assert_eq!(slide_breaks_from_db(None).unwrap(), None);
+        assert!(slide_breaks_from_db(Some("2,x")).is_err());
+    }
+
     #[tokio::test]
     async fn metadata_item_insert_same_order_test() {
         let pool = setup_test_db().await;
@@ -1048,6 +2961,7 @@ mod tests {
             translation_id: 0,
             from: VerseIndex::try_new(Book::Genesis, 1, 1).unwrap(),
             to: VerseIndex::try_new(Book::Genesis, 1, 10).unwrap(),
+            slide_breaks: None,
         };
 
         let mut tx1 = pool.begin().await.unwrap();
@@ -1074,6 +2988,7 @@ mod tests {
             translation_id: 0,
             from: VerseIndex::try_new(Book::Genesis, 1, 1).unwrap(),
             to: VerseIndex::try_new(Book::Genesis, 1, 10).unwrap(),
+            slide_breaks: None,
         };
 
         let mut tx1 = pool.begin().await.unwrap();
@@ -1116,6 +3031,7 @@ mod tests {
             translation_id: 0,
             from: VerseIndex::try_new(Book::Genesis, 1, 1).unwrap(),
             to: VerseIndex::try_new(Book::Genesis, 1, 10).unwrap(),
+            slide_breaks: None,
         };
 
         let mut tx1 = pool.begin().await.unwrap();
@@ -1134,7 +3050,7 @@ mod tests {
         tx1.commit().await.unwrap();
 
         let items =
-            PlaylistItemMetadata::load_many(pool.acquire().await.unwrap(), playlist_id).await;
+            PlaylistItemMetadata::load_many(&mut pool.acquire().await.unwrap(), playlist_id).await;
 
         assert!(items.is_ok());
 
@@ -1150,6 +3066,7 @@ mod tests {
             translation_id: 0,
             from: VerseIndex::try_new(Book::Genesis, 1, 1).unwrap(),
             to: VerseIndex::try_new(Book::Genesis, 1, 10).unwrap(),
+            slide_breaks: None,
         };
 
         let mut tx1 = pool.begin().await.unwrap();
@@ -1195,6 +3112,7 @@ mod tests {
             translation_id: 0,
             from: VerseIndex::try_new(Book::Genesis, 1, 1).unwrap(),
             to: VerseIndex::try_new(Book::Genesis, 1, 10).unwrap(),
+            slide_breaks: None,
         };
 
         let playlist_id = 0;
@@ -1219,6 +3137,7 @@ mod tests {
             translation_id: 0,
             from: VerseIndex::try_new(Book::Genesis, 1, 1).unwrap(),
             to: VerseIndex::try_new(Book::Genesis, 1, 10).unwrap(),
+            slide_breaks: None,
         };
 
         let mut tx1 = pool.begin().await.unwrap();
@@ -1244,7 +3163,8 @@ mod tests {
 
         tx2.commit().await.unwrap();
 
-        let res = PlaylistItemMetadata::load_many(pool.acquire().await.unwrap(), playlist_id).await;
+        let res =
+            PlaylistItemMetadata::load_many(&mut pool.acquire().await.unwrap(), playlist_id).await;
 
         assert!(res.is_ok_and(|vec| vec.is_empty()))
     }
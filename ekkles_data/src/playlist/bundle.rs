@@ -0,0 +1,438 @@
+//! Přenosný balíček playlistu.
+//!
+//! Balíček je jeden JSON soubor, který obsahuje playlist i s obsahem položek, které
+//! odkazují do databáze (slova písní, obrázky). Vedoucí chval si tak může připravit
+//! playlist doma a na počítači ve sboru jej naimportovat, aniž by se přenášela celá
+//! SQLite databáze.
+//!
+//! ### Co se přenáší
+//! - Písně celé (slova, pořadí, metadata), při importu se použije píseň se stejným názvem,
+//!   pokud již v databázi je, jinak se uloží jako nová
+//! - Pasáže pouze jako odkaz (název překladu a rozsah), překlad musí v cílové databázi být
+//! - Obrázky pouze ve zmenšené verzi pro výstup, originál se nepřenáší
+//! - Očekávané délky položek, tóniny písní a role lidí na bohoslužbě
+//! - Bloky (vložené playlisty) rozbalené na své položky, v cílové databázi tedy nezávisí
+//!   na původním bloku
+//!
+//! Motiv playlistu se nepřenáší, motivy jsou vázané na konkrétní databázi.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use sqlx::{Sqlite, SqlitePool, pool::PoolConnection};
+
+use super::{PlaylistItemMetadata, PlaylistMetadata, is_name_available};
+use crate::{
+    Song, SongMetadata,
+    bible::{
+        get_available_translations,
+        indexing::{Book, VerseIndex},
+    },
+    media::{self, NewMedia},
+    span,
+};
+
+/// Verze formátu balíčku, zvyšuje se při nekompatibilní změně
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+/// Přípona souborů s balíčkem playlistu (pro dialogy výběru souboru)
+pub const BUNDLE_EXTENSION: &str = "json";
+
+/// Playlist připravený k serializaci do balíčku
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PlaylistBundle {
+    format_version: u32,
+    name: String,
+    items: Vec<BundleItem>,
+    /// Lidé v rolích na bohoslužbě (role -> jméno)
+    roles: BTreeMap<String, String>,
+}
+
+/// Položka playlistu v balíčku
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct BundleItem {
+    content: BundleItemContent,
+    /// Očekávaná délka položky v sekundách
+    expected_duration: Option<u32>,
+    /// Tónina písně pro tuto bohoslužbu
+    song_key: Option<String>,
+    /// Číslo světelného cue (v balíčcích ze starších verzí chybí)
+    #[serde(default)]
+    lighting_cue: Option<u8>,
+    /// Vlastní pořadí částí písně pro tuto bohoslužbu (v balíčcích ze starších verzí chybí)
+    #[serde(default)]
+    arrangement: Option<Vec<String>>,
+    /// Zda se položka promítá (v balíčcích ze starších verzí chybí, tam jsou všechny zapnuté)
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum BundleItemContent {
+    Song(BundleSong),
+    BiblePassage {
+        /// Název překladu, ID překladu se v jiné databázi liší
+        translation: String,
+        from: BundleVerse,
+        to: BundleVerse,
+        slide_breaks: Option<Vec<usize>>,
+    },
+    CustomText {
+        title: String,
+        body: String,
+    },
+    Image {
+        name: String,
+        /// Zmenšená verze obrázku (JPEG)
+        data: Vec<u8>,
+        width: u32,
+        height: u32,
+    },
+}
+
+/// Píseň v balíčku, viz [`Song`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct BundleSong {
+    title: String,
+    author: Option<String>,
+    parts: BTreeMap<String, String>,
+    order: Vec<String>,
+    ccli_number: Option<String>,
+    copyright: Option<String>,
+    key: Option<String>,
+    capo: Option<u8>,
+    topics: Vec<String>,
+}
+
+/// Index verše v balíčku, kniha je v tradičním pořadí (od 0), viz [`Book`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct BundleVerse {
+    book: u8,
+    chapter: u8,
+    number: u8,
+}
+
+impl From<Song> for BundleSong {
+    fn from(song: Song) -> Self {
+        Self {
+            title: song.title,
+            author: song.author,
+            parts: song.parts.into_iter().collect(),
+            order: song.order,
+            ccli_number: song.metadata.ccli_number,
+            copyright: song.metadata.copyright,
+            key: song.metadata.key,
+            capo: song.metadata.capo,
+            topics: song.metadata.topics,
+        }
+    }
+}
+
+impl From<BundleSong> for Song {
+    fn from(song: BundleSong) -> Self {
+        Self {
+            title: song.title,
+            author: song.author,
+            parts: song.parts.into_iter().collect(),
+            order: song.order,
+            metadata: SongMetadata {
+                ccli_number: song.ccli_number,
+                copyright: song.copyright,
+                key: song.key,
+                capo: song.capo,
+                topics: song.topics,
+            },
+        }
+    }
+}
+
+impl From<VerseIndex> for BundleVerse {
+    fn from(verse: VerseIndex) -> Self {
+        let (book, chapter, number) = verse.destructure_numeric();
+        Self {
+            book,
+            chapter,
+            number,
+        }
+    }
+}
+
+impl TryFrom<BundleVerse> for VerseIndex {
+    type Error = anyhow::Error;
+
+    fn try_from(verse: BundleVerse) -> Result<Self> {
+        VerseIndex::try_new(Book::try_from(verse.book)?, verse.chapter, verse.number)
+            .ok_or(anyhow!("Nevalidní index verše v balíčku"))
+    }
+}
+
+impl PlaylistBundle {
+    fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Nelze serializovat balíček playlistu")
+    }
+
+    fn from_json(json: &str) -> Result<Self> {
+        let bundle: Self =
+            serde_json::from_str(json).context("Soubor není validní balíček playlistu")?;
+
+        if bundle.format_version != BUNDLE_FORMAT_VERSION {
+            bail!(
+                "Nepodporovaná verze balíčku playlistu {} (podporovaná je {})",
+                bundle.format_version,
+                BUNDLE_FORMAT_VERSION
+            );
+        }
+
+        Ok(bundle)
+    }
+}
+
+impl PlaylistMetadata {
+    /// Exportuje playlist i s obsahem položek do přenosného balíčku v souboru `path`,
+    /// viz [dokumentace modulu](crate::playlist::bundle). Pokud nějaká položka odkazuje
+    /// na neexistující data (smazaná píseň, překlad, obrázek), vrací Error.
+    pub async fn export_bundle(
+        &self,
+        path: &Path,
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> Result<()> {
+        let translations: HashMap<i64, String> = get_available_translations(conn)
+            .await?
+            .into_iter()
+            .collect();
+        let media_infos = media::get_available(conn).await?;
+        let expanded = self.expand_blocks(conn).await?;
+
+        let mut items = Vec::with_capacity(expanded.items.len());
+        for entry in &expanded.items {
+            let content = match &entry.item {
+                PlaylistItemMetadata::Song(song_id) => {
+                    BundleItemContent::Song(Song::load_from_db(*song_id, conn).await?.into())
+                }
+                PlaylistItemMetadata::BiblePassage {
+                    translation_id,
+                    from,
+                    to,
+                    slide_breaks,
+                } => BundleItemContent::BiblePassage {
+                    translation: translations
+                        .get(translation_id)
+                        .with_context(|| format!("Překlad s id {translation_id} neexistuje"))?
+                        .clone(),
+                    from: (*from).into(),
+                    to: (*to).into(),
+                    slide_breaks: slide_breaks.clone(),
+                },
+                PlaylistItemMetadata::CustomText { title, body } => BundleItemContent::CustomText {
+                    title: title.clone(),
+                    body: body.clone(),
+                },
+                PlaylistItemMetadata::Image(media_id) => {
+                    let info = media_infos
+                        .iter()
+                        .find(|info| info.id == *media_id)
+                        .with_context(|| format!("Obrázek s id {media_id} neexistuje"))?;
+                    BundleItemContent::Image {
+                        name: info.name.clone(),
+                        data: media::load_optimized(*media_id, conn).await?,
+                        width: info.width,
+                        height: info.height,
+                    }
+                }
+                PlaylistItemMetadata::Block(_) => unreachable!("Bloky jsou rozbalené"),
+            };
+
+            items.push(BundleItem {
+                content,
+                expected_duration: entry.expected_duration,
+                song_key: entry.song_key.clone(),
+                lighting_cue: entry.lighting_cue,
+                arrangement: entry.arrangement.clone(),
+                enabled: entry.enabled,
+            });
+        }
+
+        let bundle = PlaylistBundle {
+            format_version: BUNDLE_FORMAT_VERSION,
+            name: self.name.clone(),
+            items,
+            roles: self.roles.clone().into_iter().collect(),
+        };
+
+        fs::write(path, bundle.to_json()?)
+            .with_context(|| format!("Nelze zapsat balíček playlistu {}", path.display()))
+    }
+
+    /// Naimportuje playlist z přenosného balíčku v souboru `path`, viz
+    /// [dokumentace modulu](crate::playlist::bundle). Písně, které v databázi nejsou,
+    /// a obrázky se rovnou uloží do databáze, vrácený playlist ale ještě uložen není
+    /// (status [`super::PlaylistMetadataStatus::Transient`]), to je na volajícím.
+    ///
+    /// Pokud playlist se stejným názvem již existuje, dostane importovaný playlist
+    /// název s pořadovým číslem, např. "Neděle (2)".
+    ///
+    /// ### Ošetření chyb
+    /// Vrací Error, pokud soubor není validní balíček nebo pokud v databázi chybí
+    /// překlad některé z pasáží. Písně a obrázky uložené před chybou v databázi zůstanou.
+    pub async fn import_bundle(path: &Path, pool: &SqlitePool) -> Result<Self> {
+        span::in_span(
+            "playlist.import_bundle",
+            format!("soubor={}", path.display()),
+            async move {
+                let json = fs::read_to_string(path).with_context(|| {
+                    format!("Nelze přečíst balíček playlistu {}", path.display())
+                })?;
+                let bundle = PlaylistBundle::from_json(&json)?;
+
+                let translations: HashMap<String, i64> =
+                    get_available_translations(&mut pool.acquire().await?)
+                        .await?
+                        .into_iter()
+                        .map(|(id, name)| (name, id))
+                        .collect();
+
+                let mut name = bundle.name.clone();
+                let mut suffix = 2;
+                while !is_name_available(pool.acquire().await?, &name).await? {
+                    name = format!("{} ({suffix})", bundle.name);
+                    suffix += 1;
+                }
+
+                let mut playlist = PlaylistMetadata::new(&name);
+                for (position, item) in bundle.items.into_iter().enumerate() {
+                    match item.content {
+                        BundleItemContent::Song(song) => {
+                            let song_id = match Song::exists_in_db(&song.title, pool).await {
+                                Ok(song_id) => song_id,
+                                Err(e) if e.is_not_found() => {
+                                    Song::from(song).save_to_db(pool).await?
+                                }
+                                Err(e) => return Err(e.into()),
+                            };
+                            playlist.push_song(song_id);
+                            playlist.set_song_key(position, item.song_key.as_deref())?;
+                            playlist.set_arrangement(position, item.arrangement)?;
+                        }
+                        BundleItemContent::BiblePassage {
+                            translation,
+                            from,
+                            to,
+                            slide_breaks,
+                        } => {
+                            let translation_id = *translations.get(&translation).with_context(|| {
+                        format!("Překlad '{translation}' není v databázi, nejdříve jej naimportuj")
+                    })?;
+                            playlist.push_bible_passage(
+                                translation_id,
+                                from.try_into()?,
+                                to.try_into()?,
+                            );
+                            playlist.set_slide_breaks(position, slide_breaks)?;
+                        }
+                        BundleItemContent::CustomText { title, body } => {
+                            playlist.push_custom_text(&title, &body);
+                        }
+                        BundleItemContent::Image {
+                            name,
+                            data,
+                            width,
+                            height,
+                        } => {
+                            let media_id = NewMedia::from_optimized(name, data, width, height)
+                                .save_to_db(pool)
+                                .await?;
+                            playlist.push_image(media_id);
+                        }
+                    }
+                    playlist.set_expected_duration(position, item.expected_duration)?;
+                    playlist.set_lighting_cue(position, item.lighting_cue)?;
+                    playlist.set_item_enabled(position, item.enabled)?;
+                }
+
+                for (role, person) in bundle.roles {
+                    playlist.set_role(&role, Some(&person))?;
+                }
+
+                Ok(playlist)
+            },
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn bundle_json_roundtrip() {
+        let bundle = PlaylistBundle {
+            format_version: BUNDLE_FORMAT_VERSION,
+            name: String::from("Neděle"),
+            items: vec![
+                BundleItem {
+                    content: BundleItemContent::Song(BundleSong {
+                        title: String::from("Haleluja"),
+                        author: None,
+                        parts: BTreeMap::from([(String::from("V1"), String::from("Haleluja"))]),
+                        order: vec![String::from("V1"), String::from("V1")],
+                        ccli_number: Some(String::from("123")),
+                        copyright: None,
+                        key: Some(String::from("G")),
+                        capo: None,
+                        topics: vec![String::from("Chvála")],
+                    }),
+                    expected_duration: Some(240),
+                    song_key: Some(String::from("A")),
+                    lighting_cue: Some(12),
+                    arrangement: Some(vec![String::from("V1")]),
+                    enabled: true,
+                },
+                BundleItem {
+                    content: BundleItemContent::BiblePassage {
+                        translation: String::from("ČEP"),
+                        from: VerseIndex::try_new(Book::John, 3, 16).unwrap().into(),
+                        to: VerseIndex::try_new(Book::John, 3, 18).unwrap().into(),
+                        slide_breaks: Some(vec![1]),
+                    },
+                    expected_duration: None,
+                    song_key: None,
+                    lighting_cue: None,
+                    arrangement: None,
+                    enabled: false,
+                },
+            ],
+            roles: BTreeMap::from([(String::from("Kazatel"), String::from("Jan Novák"))]),
+        };
+
+        let parsed = PlaylistBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, bundle);
+
+        let BundleItemContent::BiblePassage { from, .. } = parsed.items[1].content else {
+            panic!("Druhá položka by měla být pasáž");
+        };
+        assert_eq!(
+            VerseIndex::try_from(from).unwrap(),
+            VerseIndex::try_new(Book::John, 3, 16).unwrap()
+        );
+    }
+
+    #[test]
+    fn unsupported_bundle_version() {
+        let json = r#"{"format_version": 999, "name": "Neděle", "items": [], "roles": {}}"#;
+
+        assert!(PlaylistBundle::from_json(json).is_err());
+        assert!(PlaylistBundle::from_json("není json").is_err());
+    }
+}
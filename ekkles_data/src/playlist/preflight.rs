@@ -0,0 +1,522 @@
+//! Kontrola playlistu před spuštěním prezentace.
+//!
+//! Načtení prezentace selže na první chybě (smazaná píseň, překlad, ...) a uživatel se
+//! to dozví až těsně před bohoslužbou jako fatální chybu. Kontrola projde všechny položky
+//! playlistu předem a vrátí seznam všech problémů, aby je šlo opravit.
+//!
+//! Média (obrázky na pozadí i obrázky v playlistu) jsou uložena přímo v databázi, chybějící
+//! soubory na disku tedy kontrolovat nemusíme, pouze smazaná média.
+//!
+//! Položky bloků (viz [`PlaylistItemMetadata::Block`]) se kontrolují také, jejich problémy
+//! se hlásí u bloku, opravují se totiž v playlistu bloku.
+//!
+//! V licenčním režimu se navíc pomocí [`check_licenses()`] hlásí písně bez licence
+//! (viz [`crate::license`]), jejich promítnutí musí uživatel potvrdit.
+
+use std::fmt::Display;
+
+use anyhow::{Context, Result};
+use sqlx::{Sqlite, pool::PoolConnection, query};
+
+use super::{PlaylistItemMetadata, PlaylistMetadata};
+use crate::{
+    PartTag, Song,
+    bible::indexing::VerseIndex,
+    error::EkklesDataError,
+    license::{self, LicenseWarning},
+};
+
+/// Problém položky playlistu nalezený při kontrole
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreflightIssue {
+    /// Píseň byla z databáze smazána
+    MissingSong { item_index: usize, song_id: i64 },
+    /// Píseň nelze načíst (např. pořadí odkazuje na neexistující část)
+    InvalidSong { item_index: usize, reason: String },
+    /// Část písně neobsahuje žádný text, promítne se prázdný slajd
+    EmptyPart { item_index: usize, tag: PartTag },
+    /// Vlastní pořadí částí písně (viz [`PlaylistMetadata::set_arrangement()`]) odkazuje
+    /// na část, kterou píseň nemá (např. byla z písně mezitím odebrána)
+    UnknownArrangementPart { item_index: usize, tag: PartTag },
+    /// Vlastní text je prázdný, promítne se prázdný slajd
+    EmptyText { item_index: usize },
+    /// Obrázek byl z úložiště médií smazán
+    MissingImage { item_index: usize, media_id: i64 },
+    /// Překlad pasáže byl z databáze smazán
+    MissingTranslation {
+        item_index: usize,
+        translation_id: i64,
+    },
+    /// Překlad neobsahuje počáteční nebo koncový verš pasáže
+    MissingVerses {
+        item_index: usize,
+        from: VerseIndex,
+        to: VerseIndex,
+    },
+    /// Blok nelze rozbalit (např. obsahuje playlist, do kterého je vložený)
+    InvalidBlock { item_index: usize, reason: String },
+    /// Píseň nemusí být licencovaná (hlásí se pouze v licenčním režimu)
+    Unlicensed {
+        item_index: usize,
+        warning: LicenseWarning,
+    },
+}
+
+impl PreflightIssue {
+    /// Index položky playlistu, které se problém týká
+    pub fn item_index(&self) -> usize {
+        match self {
+            PreflightIssue::MissingSong { item_index, .. }
+            | PreflightIssue::InvalidSong { item_index, .. }
+            | PreflightIssue::EmptyPart { item_index, .. }
+            | PreflightIssue::UnknownArrangementPart { item_index, .. }
+            | PreflightIssue::EmptyText { item_index }
+            | PreflightIssue::MissingImage { item_index, .. }
+            | PreflightIssue::MissingTranslation { item_index, .. }
+            | PreflightIssue::MissingVerses { item_index, .. }
+            | PreflightIssue::InvalidBlock { item_index, .. }
+            | PreflightIssue::Unlicensed { item_index, .. } => *item_index,
+        }
+    }
+
+    /// Vrátí `true`, pokud kvůli problému nelze prezentaci vůbec načíst,
+    /// ostatní problémy jsou pouze varování.
+    pub fn is_blocking(&self) -> bool {
+        !matches!(
+            self,
+            PreflightIssue::EmptyPart { .. }
+                | PreflightIssue::EmptyText { .. }
+                | PreflightIssue::Unlicensed { .. }
+        )
+    }
+}
+
+impl Display for PreflightIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let position = self.item_index() + 1;
+        match self {
+            PreflightIssue::MissingSong { song_id, .. } => {
+                write!(f, "{position}. položka: píseň (id {song_id}) byla smazána")
+            }
+            PreflightIssue::InvalidSong { reason, .. } => {
+                write!(f, "{position}. položka: píseň nelze načíst ({reason})")
+            }
+            PreflightIssue::EmptyPart { tag, .. } => {
+                write!(f, "{position}. položka: část písně '{tag}' je prázdná")
+            }
+            PreflightIssue::UnknownArrangementPart { tag, .. } => {
+                write!(
+                    f,
+                    "{position}. položka: vlastní pořadí obsahuje část '{tag}', kterou píseň nemá"
+                )
+            }
+            PreflightIssue::EmptyText { .. } => {
+                write!(f, "{position}. položka: text je prázdný")
+            }
+            PreflightIssue::MissingImage { media_id, .. } => {
+                write!(f, "{position}. položka: obrázek (id {media_id}) byl smazán")
+            }
+            PreflightIssue::MissingTranslation { translation_id, .. } => {
+                write!(
+                    f,
+                    "{position}. položka: překlad pasáže (id {translation_id}) byl smazán"
+                )
+            }
+            PreflightIssue::MissingVerses { from, to, .. } => {
+                write!(
+                    f,
+                    "{position}. položka: překlad neobsahuje pasáž {from} - {to}"
+                )
+            }
+            PreflightIssue::InvalidBlock { reason, .. } => {
+                write!(f, "{position}. položka: blok nelze načíst ({reason})")
+            }
+            PreflightIssue::Unlicensed { warning, .. } => {
+                write!(
+                    f,
+                    "{position}. položka: píseň je {warning}, její promítnutí je potřeba potvrdit"
+                )
+            }
+        }
+    }
+}
+
+/// Zkontroluje všechny položky playlistu `playlist` a vrátí seznam nalezených problémů
+/// v pořadí položek. Vypnuté položky se nepromítají, přeskočí se. Pokud nastane chyba
+/// při čtení z databáze, vrací Error.
+pub async fn check(
+    playlist: &PlaylistMetadata,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<Vec<PreflightIssue>> {
+    let mut issues = Vec::new();
+
+    for (item_index, item) in playlist.get_items().enumerate() {
+        if !playlist.is_item_enabled(item_index) {
+            continue;
+        }
+        let PlaylistItemMetadata::Block(block_id) = item else {
+            let arrangement = playlist.get_arrangement(item_index);
+            check_item(item_index, item, arrangement, conn, &mut issues).await?;
+            continue;
+        };
+
+        match load_block(*block_id, conn).await {
+            Ok(block) => {
+                for (block_index, block_item) in block.get_items().enumerate() {
+                    if !block.is_item_enabled(block_index) {
+                        continue;
+                    }
+                    let arrangement = block.get_arrangement(block_index);
+                    check_item(item_index, block_item, arrangement, conn, &mut issues).await?;
+                }
+            }
+            Err(EkklesDataError::InvalidData(reason)) => {
+                issues.push(PreflightIssue::InvalidBlock { item_index, reason })
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Načte blok s ID `block_id` s rozbalenými vnořenými bloky
+async fn load_block(
+    block_id: i64,
+    conn: &mut PoolConnection<Sqlite>,
+) -> crate::error::Result<PlaylistMetadata> {
+    PlaylistMetadata::load_with(block_id, conn)
+        .await?
+        .expand_blocks(conn)
+        .await
+}
+
+/// Zkontroluje položku `item` (s vlastním pořadím `arrangement`), problémy hlásí u položky
+/// na indexu `item_index`. Bloky kontroluje volající po rozbalení, zde se přeskočí.
+async fn check_item(
+    item_index: usize,
+    item: &PlaylistItemMetadata,
+    arrangement: Option<&[PartTag]>,
+    conn: &mut PoolConnection<Sqlite>,
+    issues: &mut Vec<PreflightIssue>,
+) -> Result<()> {
+    match item {
+        PlaylistItemMetadata::Song(song_id) => {
+            check_song(item_index, *song_id, arrangement, conn, issues).await?
+        }
+        PlaylistItemMetadata::BiblePassage {
+            translation_id,
+            from,
+            to,
+            ..
+        } => check_passage(item_index, *translation_id, *from, *to, conn, issues).await?,
+        PlaylistItemMetadata::CustomText { body, .. } => {
+            if body.trim().is_empty() {
+                issues.push(PreflightIssue::EmptyText { item_index });
+            }
+        }
+        PlaylistItemMetadata::Image(media_id) => {
+            let exists = query!("SELECT id FROM media WHERE id = $1", media_id)
+                .fetch_optional(conn.as_mut())
+                .await
+                .with_context(|| format!("Nelze zkontrolovat existenci obrázku s id {media_id}"))?
+                .is_some();
+
+            if !exists {
+                issues.push(PreflightIssue::MissingImage {
+                    item_index,
+                    media_id: *media_id,
+                });
+            }
+        }
+        PlaylistItemMetadata::Block(_) => {}
+    }
+
+    Ok(())
+}
+
+/// Zkontroluje licence všech promítaných písní playlistu `playlist` a vrátí problémy s nimi
+/// v pořadí položek. Pokud nastane chyba při čtení z databáze, vrací Error.
+pub async fn check_licenses(
+    playlist: &PlaylistMetadata,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<Vec<PreflightIssue>> {
+    let warnings = license::get_license_warnings(conn).await?;
+    let mut issues = Vec::new();
+
+    for (item_index, item) in playlist.get_items().enumerate() {
+        if !playlist.is_item_enabled(item_index) {
+            continue;
+        }
+        let song_ids = match item {
+            PlaylistItemMetadata::Song(song_id) => vec![*song_id],
+            PlaylistItemMetadata::Block(block_id) => {
+                let block = load_block(*block_id, conn).await?;
+                block
+                    .get_items()
+                    .enumerate()
+                    .filter(|(block_index, _)| block.is_item_enabled(*block_index))
+                    .filter_map(|(_, item)| match item {
+                        PlaylistItemMetadata::Song(song_id) => Some(*song_id),
+                        _ => None,
+                    })
+                    .collect()
+            }
+            PlaylistItemMetadata::BiblePassage { .. }
+            | PlaylistItemMetadata::CustomText { .. }
+            | PlaylistItemMetadata::Image(_) => Vec::new(),
+        };
+
+        issues.extend(song_ids.into_iter().filter_map(|song_id| {
+            warnings
+                .get(&song_id)
+                .map(|warning| PreflightIssue::Unlicensed {
+                    item_index,
+                    warning: *warning,
+                })
+        }));
+    }
+
+    Ok(issues)
+}
+
+async fn check_song(
+    item_index: usize,
+    song_id: i64,
+    arrangement: Option<&[PartTag]>,
+    conn: &mut PoolConnection<Sqlite>,
+    issues: &mut Vec<PreflightIssue>,
+) -> Result<()> {
+    let exists = query!("SELECT id FROM songs WHERE id = $1", song_id)
+        .fetch_optional(conn.as_mut())
+        .await
+        .with_context(|| format!("Nelze zkontrolovat existenci písně s id {song_id}"))?
+        .is_some();
+
+    if !exists {
+        issues.push(PreflightIssue::MissingSong {
+            item_index,
+            song_id,
+        });
+        return Ok(());
+    }
+
+    match Song::load_from_db(song_id, conn).await {
+        Ok(song) => {
+            let mut empty_tags: Vec<&PartTag> = song
+                .parts
+                .iter()
+                .filter(|(_, lyrics)| lyrics.trim().is_empty())
+                .map(|(tag, _)| tag)
+                .collect();
+            empty_tags.sort();
+
+            issues.extend(empty_tags.into_iter().map(|tag| PreflightIssue::EmptyPart {
+                item_index,
+                tag: tag.clone(),
+            }));
+
+            let mut unknown_tags: Vec<&PartTag> = arrangement
+                .unwrap_or_default()
+                .iter()
+                .filter(|tag| !song.parts.contains_key(*tag))
+                .collect();
+            unknown_tags.sort();
+            unknown_tags.dedup();
+
+            issues.extend(unknown_tags.into_iter().map(|tag| {
+                PreflightIssue::UnknownArrangementPart {
+                    item_index,
+                    tag: tag.clone(),
+                }
+            }));
+        }
+        Err(e) => issues.push(PreflightIssue::InvalidSong {
+            item_index,
+            reason: format!("{e:#}"),
+        }),
+    }
+
+    Ok(())
+}
+
+async fn check_passage(
+    item_index: usize,
+    translation_id: i64,
+    from: VerseIndex,
+    to: VerseIndex,
+    conn: &mut PoolConnection<Sqlite>,
+    issues: &mut Vec<PreflightIssue>,
+) -> Result<()> {
+    let translation_exists = query!("SELECT id FROM translations WHERE id = $1", translation_id)
+        .fetch_optional(conn.as_mut())
+        .await
+        .with_context(|| format!("Nelze zkontrolovat existenci překladu s id {translation_id}"))?
+        .is_some();
+
+    if !translation_exists {
+        issues.push(PreflightIssue::MissingTranslation {
+            item_index,
+            translation_id,
+        });
+        return Ok(());
+    }
+
+    for verse in [from, to] {
+        let (book, chapter, number) = verse.destructure_numeric();
+        let exists = query!(
+            "SELECT verse_order FROM verses WHERE translation_id = $1 AND book_id = $2 AND chapter = $3 AND number = $4",
+            translation_id,
+            book,
+            chapter,
+            number
+        )
+        .fetch_optional(conn.as_mut())
+        .await
+        .with_context(|| format!("Nelze zkontrolovat existenci verše {verse}"))?
+        .is_some();
+
+        if !exists {
+            issues.push(PreflightIssue::MissingVerses {
+                item_index,
+                from,
+                to,
+            });
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use sqlx::SqlitePool;
+
+    use super::*;
+    use crate::{bible::indexing::Book, test_util};
+
+    async fn setup_test_db() -> SqlitePool {
+        test_util::setup_db_with_test_data().await
+    }
+
+    #[tokio::test]
+    async fn valid_playlist_has_no_issues() {
+        let pool = setup_test_db().await;
+
+        let mut playlist = PlaylistMetadata::new("test");
+        playlist.push_song(0);
+        playlist.push_bible_passage(
+            0,
+            VerseIndex::try_new(Book::Genesis, 1, 1).unwrap(),
+            VerseIndex::try_new(Book::Genesis, 1, 10).unwrap(),
+        );
+
+        let issues = check(&playlist, &mut pool.acquire().await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(issues, vec![]);
+    }
+
+    #[tokio::test]
+    async fn all_issues_are_reported() {
+        let pool = setup_test_db().await;
+        query!("UPDATE song_parts SET lyrics = ' ' WHERE song_id = 1 AND tag = 'V2'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let genesis = |verse| VerseIndex::try_new(Book::Genesis, 1, verse).unwrap();
+        let mut playlist = PlaylistMetadata::new("test");
+        playlist.push_song(42);
+        playlist.push_song(1);
+        playlist
+            .set_arrangement(1, Some(vec![String::from("V2"), String::from("X")]))
+            .unwrap();
+        playlist.push_bible_passage(7, genesis(1), genesis(2));
+        playlist.push_bible_passage(0, genesis(5), genesis(20));
+        playlist.push_custom_text("Oznámení", "  ");
+        playlist.push_image(99);
+
+        let issues = check(&playlist, &mut pool.acquire().await.unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            issues,
+            vec![
+                PreflightIssue::MissingSong {
+                    item_index: 0,
+                    song_id: 42
+                },
+                PreflightIssue::EmptyPart {
+                    item_index: 1,
+                    tag: String::from("V2")
+                },
+                PreflightIssue::UnknownArrangementPart {
+                    item_index: 1,
+                    tag: String::from("X")
+                },
+                PreflightIssue::MissingTranslation {
+                    item_index: 2,
+                    translation_id: 7
+                },
+                PreflightIssue::MissingVerses {
+                    item_index: 3,
+                    from: genesis(5),
+                    to: genesis(20)
+                },
+                PreflightIssue::EmptyText { item_index: 4 },
+                PreflightIssue::MissingImage {
+                    item_index: 5,
+                    media_id: 99
+                },
+            ]
+        );
+        assert_eq!(
+            issues.iter().filter(|issue| issue.is_blocking()).count(),
+            5,
+            "Prázdná část písně a prázdný text jsou pouze varování"
+        );
+    }
+
+    #[tokio::test]
+    async fn unlicensed_songs_are_reported() {
+        let pool = setup_test_db().await;
+        query!("UPDATE songs SET ccli_number = '27824'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        query!("UPDATE songs SET ccli_number = NULL WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+        license::set_licensed(0, false, &pool).await.unwrap();
+
+        let genesis = |verse| VerseIndex::try_new(Book::Genesis, 1, verse).unwrap();
+        let mut playlist = PlaylistMetadata::new("test");
+        playlist.push_bible_passage(0, genesis(1), genesis(2));
+        playlist.push_song(1);
+        playlist.push_song(0);
+
+        let issues = check_licenses(&playlist, &mut pool.acquire().await.unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            issues,
+            vec![
+                PreflightIssue::Unlicensed {
+                    item_index: 1,
+                    warning: LicenseWarning::MissingCcliNumber
+                },
+                PreflightIssue::Unlicensed {
+                    item_index: 2,
+                    warning: LicenseWarning::NotLicensed
+                },
+            ]
+        );
+        assert!(issues.iter().all(|issue| !issue.is_blocking()));
+    }
+}
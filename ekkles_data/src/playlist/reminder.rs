@@ -0,0 +1,259 @@
+//! Pozvánka na bohoslužbu pro tým (kazatel, chvály, projekce, zvuk) sestavená z playlistu.
+//!
+//! Playlist sám datum bohoslužby nemá, zadává jej uživatel při vytvoření pozvánky.
+//! Pozvánku lze uložit jako událost do kalendáře ([`ServiceReminder::to_ics()`], formát
+//! iCalendar podle [RFC 5545](https://www.rfc-editor.org/rfc/rfc5545)) nebo vložit do
+//! e-mailu jako text ([`ServiceReminder::email_text()`]).
+//!
+//! ### Čas
+//! Čas bohoslužby je místní a v kalendáři se ukládá jako "plovoucí" (bez časového pásma),
+//! kalendář příjemce jej tedy zobrazí ve svém místním čase. Pro sbor, jehož členové jsou
+//! ve stejném pásmu, je to přesně to, co chceme.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use sqlx::{Sqlite, pool::PoolConnection, query};
+
+use super::{PlaylistItemMetadata, PlaylistMetadata};
+
+/// Výchozí délka bohoslužby v kalendáři
+pub const DEFAULT_SERVICE_DURATION: TimeDelta = TimeDelta::minutes(90);
+/// Přípona souborů s událostí kalendáře (pro dialogy výběru souboru)
+pub const ICS_EXTENSION: &str = "ics";
+
+/// Formát data a času v textu pozvánky
+const TEXT_DATETIME_FORMAT: &str = "%d.%m.%Y v %H:%M";
+/// Formát místního ("plovoucího") času v iCalendar
+const ICS_LOCAL_FORMAT: &str = "%Y%m%dT%H%M%S";
+/// Formát času v UTC v iCalendar
+const ICS_UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+/// Maximální délka řádku iCalendar v bajtech (bez CRLF), delší se zalamují
+const ICS_LINE_LIMIT: usize = 75;
+
+/// Pozvánka na bohoslužbu, viz [dokumentace modulu](crate::playlist::reminder)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceReminder {
+    /// Název playlistu
+    pub name: String,
+    /// Začátek bohoslužby v místním čase
+    pub start: NaiveDateTime,
+    /// Délka bohoslužby
+    pub duration: TimeDelta,
+    /// Popisy položek playlistu v pořadí (název písně s tóninou, rozsah pasáže, ...)
+    pub set_list: Vec<String>,
+    /// Obsazené role jako dvojice (role, jméno)
+    pub roles: Vec<(String, String)>,
+}
+
+impl ServiceReminder {
+    /// Sestaví pozvánku na bohoslužbu podle playlistu `playlist` začínající v `start`
+    /// (místní čas) s výchozí délkou [`DEFAULT_SERVICE_DURATION`]. Bloky jsou v programu
+    /// rozbalené na své položky. Názvy písní a obrázků načte z databáze, pokud některá
+    /// položka v databázi není, vrací Error.
+    pub async fn for_playlist(
+        playlist: &PlaylistMetadata,
+        start: NaiveDateTime,
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> Result<Self> {
+        let expanded = playlist.expand_blocks(conn).await?;
+        let mut set_list = Vec::with_capacity(expanded.get_items().len());
+
+        for (position, item) in expanded.get_items().enumerate() {
+            let entry = match item {
+                PlaylistItemMetadata::Song(id) => {
+                    let title = query!("SELECT title FROM songs WHERE id = $1", id)
+                        .fetch_one(conn.as_mut())
+                        .await
+                        .with_context(|| format!("Píseň s id {id} nebyla nalezena"))?
+                        .title;
+                    match expanded.get_song_key(position) {
+                        Some(key) => format!("{title} ({key})"),
+                        None => title,
+                    }
+                }
+                PlaylistItemMetadata::BiblePassage { from, to, .. } => format!("{from} - {to}"),
+                PlaylistItemMetadata::CustomText { title, .. } => title.clone(),
+                PlaylistItemMetadata::Image(id) => {
+                    query!("SELECT name FROM media WHERE id = $1", id)
+                        .fetch_one(conn.as_mut())
+                        .await
+                        .with_context(|| format!("Médium s id {id} nebylo nalezeno"))?
+                        .name
+                }
+                PlaylistItemMetadata::Block(_) => unreachable!("Bloky jsou rozbalené"),
+            };
+            set_list.push(entry);
+        }
+
+        Ok(Self {
+            name: playlist.get_name().to_string(),
+            start,
+            duration: DEFAULT_SERVICE_DURATION,
+            set_list,
+            roles: playlist
+                .get_roles()
+                .into_iter()
+                .map(|(role, person)| (role.to_string(), person.to_string()))
+                .collect(),
+        })
+    }
+
+    /// Předmět e-mailu s pozvánkou
+    pub fn email_subject(&self) -> String {
+        format!(
+            "Bohoslužba {}, {}",
+            self.name,
+            self.start.format(TEXT_DATETIME_FORMAT)
+        )
+    }
+
+    /// Popis bohoslužby (služby a program), tělo e-mailu i popis události v kalendáři
+    pub fn description(&self) -> String {
+        let mut description = format!(
+            "Bohoslužba {} ({})\n",
+            self.start.format(TEXT_DATETIME_FORMAT),
+            self.name
+        );
+
+        if !self.roles.is_empty() {
+            description.push_str("\nSlužby:\n");
+            for (role, person) in &self.roles {
+                description.push_str(&format!("  {role}: {person}\n"));
+            }
+        }
+
+        if !self.set_list.is_empty() {
+            description.push_str("\nProgram:\n");
+            for (index, entry) in self.set_list.iter().enumerate() {
+                description.push_str(&format!("  {}. {entry}\n", index + 1));
+            }
+        }
+
+        description
+    }
+
+    /// Text e-mailu s pozvánkou včetně předmětu (na prvním řádku), k vložení do e-mailového klienta
+    pub fn email_text(&self) -> String {
+        format!(
+            "Předmět: {}\n\n{}",
+            self.email_subject(),
+            self.description()
+        )
+    }
+
+    /// Výchozí název souboru s událostí kalendáře
+    pub fn default_file_name(&self) -> String {
+        format!(
+            "bohosluzba_{}.{ICS_EXTENSION}",
+            self.start.format("%Y-%m-%d")
+        )
+    }
+
+    /// Vrátí pozvánku jako kalendář iCalendar s jednou událostí. `uid` jednoznačně
+    /// identifikuje událost (opětovný import stejné pozvánky ji v kalendáři aktualizuje),
+    /// `stamp` je čas vytvoření pozvánky.
+    pub fn to_ics(&self, uid: &str, stamp: DateTime<Utc>) -> String {
+        let end = self.start + self.duration;
+        let lines = [
+            String::from("BEGIN:VCALENDAR"),
+            String::from("VERSION:2.0"),
+            String::from("PRODID:-//Ekkles//Pozvánka na bohoslužbu//CS"),
+            String::from("BEGIN:VEVENT"),
+            format!("UID:{}", escape_ics(uid)),
+            format!("DTSTAMP:{}", stamp.format(ICS_UTC_FORMAT)),
+            format!("DTSTART:{}", self.start.format(ICS_LOCAL_FORMAT)),
+            format!("DTEND:{}", end.format(ICS_LOCAL_FORMAT)),
+            format!(
+                "SUMMARY:{}",
+                escape_ics(&format!("Bohoslužba {}", self.name))
+            ),
+            format!("DESCRIPTION:{}", escape_ics(&self.description())),
+            String::from("END:VEVENT"),
+            String::from("END:VCALENDAR"),
+        ];
+
+        lines.iter().map(|line| fold_ics_line(line)).collect()
+    }
+}
+
+/// Escapuje text hodnoty iCalendar (zpětné lomítko, středník, čárka, nový řádek)
+fn escape_ics(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Zalomí řádek iCalendar na řádky dlouhé nejvýše [`ICS_LINE_LIMIT`] bajtů (pokračovací
+/// řádky začínají mezerou), nikdy uprostřed znaku UTF-8. Každý řádek ukončí CRLF.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut line_len = 0;
+
+    for ch in line.chars() {
+        if line_len + ch.len_utf8() > ICS_LINE_LIMIT {
+            folded.push_str("\r\n ");
+            // Mezera na začátku pokračovacího řádku se započítává do jeho délky
+            line_len = 1;
+        }
+        folded.push(ch);
+        line_len += ch.len_utf8();
+    }
+    folded.push_str("\r\n");
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn reminder() -> ServiceReminder {
+        ServiceReminder {
+            name: String::from("Neděle, 18. 10."),
+            start: NaiveDate::from_ymd_opt(2026, 10, 18)
+                .unwrap()
+                .and_hms_opt(9, 30, 0)
+                .unwrap(),
+            duration: DEFAULT_SERVICE_DURATION,
+            set_list: vec![
+                String::from("Chválím Tě, Pane (G)"),
+                String::from("Jan 3:16 - Jan 3:18"),
+            ],
+            roles: vec![(String::from("Kazatel"), String::from("Jan Novák"))],
+        }
+    }
+
+    #[test]
+    fn email_text_contains_roles_and_set_list() {
+        assert_eq!(
+            reminder().email_text(),
+            "Předmět: Bohoslužba Neděle, 18. 10., 18.10.2026 v 09:30\n\
+            \n\
+            Bohoslužba 18.10.2026 v 09:30 (Neděle, 18. 10.)\n\
+            \n\
+            Služby:\n  Kazatel: Jan Novák\n\
+            \n\
+            Program:\n  1. Chválím Tě, Pane (G)\n  2. Jan 3:16 - Jan 3:18\n"
+        );
+    }
+
+    #[test]
+    fn ics_is_escaped_and_folded() {
+        let stamp = DateTime::from_timestamp(1_760_000_000, 0).unwrap();
+        let ics = reminder().to_ics("ekkles-1@ekkles", stamp);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(ics.contains("\r\nDTSTART:20261018T093000\r\nDTEND:20261018T110000\r\n"));
+        assert!(ics.contains("\r\nSUMMARY:Bohoslužba Neděle\\, 18. 10.\r\n"));
+        assert!(ics.split("\r\n").all(|line| line.len() <= ICS_LINE_LIMIT));
+
+        // Po spojení zalomených řádků zůstane popis celý
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains("\\nProgram:\\n  1. Chválím Tě\\, Pane (G)\\n"));
+    }
+}
@@ -0,0 +1,265 @@
+//! Modul pro záznam průběhu prezentace.
+//!
+//! Během prezentace se v paměti zaznamenává, kdy byla která položka playlistu promítána
+//! ([`PresentationLog`]). Na konci prezentace (nebo při ukončení programu) se záznam
+//! uloží do databáze pomocí [`PresentationLog::flush()`], aby šlo později zjistit,
+//! jak dlouho jednotlivé položky trvaly.
+//!
+//! ### Ukládání času
+//! Stejně jako u playlistů je čas reprezentován v UTC a do databáze se ukládá
+//! s přesností na sekundy.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDateTime, SubsecRound, TimeDelta, Utc};
+use sqlx::{Acquire, Sqlite, pool::PoolConnection, query};
+
+/// Formátovací řetězec pro převod času z/do databáze, stejný jako u playlistů
+const DB_DATETIME_FORMAT: &str = "%F %T";
+
+/// Jeden záznam o promítání položky playlistu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresentationLogEntry {
+    /// Pořadí položky v playlistu (odpovídá `part_order` v databázi)
+    pub part_order: i64,
+    /// Kdy se položka začala promítat
+    pub started: DateTime<Utc>,
+    /// Kdy se položka přestala promítat
+    pub ended: DateTime<Utc>,
+}
+
+impl PresentationLogEntry {
+    /// Jak dlouho byla položka promítána
+    pub fn duration(&self) -> TimeDelta {
+        self.ended - self.started
+    }
+}
+
+/// Záznam průběhu prezentace jednoho playlistu, drží se v paměti
+/// a do databáze se zapíše pomocí [`PresentationLog::flush()`].
+#[derive(Debug, Clone)]
+pub struct PresentationLog {
+    playlist_id: i64,
+    /// Uzavřené záznamy, které ještě nebyly zapsány do databáze
+    entries: Vec<PresentationLogEntry>,
+    /// Právě promítaná položka a čas, od kdy se promítá
+    current: Option<(i64, DateTime<Utc>)>,
+}
+
+impl PresentationLog {
+    pub fn new(playlist_id: i64) -> Self {
+        Self {
+            playlist_id,
+            entries: Vec::new(),
+            current: None,
+        }
+    }
+
+    pub fn get_playlist_id(&self) -> i64 {
+        self.playlist_id
+    }
+
+    /// Zaznamená, že se začala promítat položka s pořadím `part_order`. Pokud se již
+    /// promítá stejná položka (např. přechod mezi slajdy jedné písně), je to no-op.
+    /// Vrací, zda se začala promítat jiná položka.
+    pub fn item_presented(&mut self, part_order: i64) -> bool {
+        if self
+            .current
+            .is_some_and(|(current_order, _)| current_order == part_order)
+        {
+            return false;
+        }
+
+        self.finish();
+        self.current = Some((part_order, Utc::now().round_subsecs(0)));
+        true
+    }
+
+    /// Uzavře záznam o právě promítané položce (pokud nějaká je).
+    pub fn finish(&mut self) {
+        if let Some((part_order, started)) = self.current.take() {
+            self.entries.push(PresentationLogEntry {
+                part_order,
+                started,
+                ended: Utc::now().round_subsecs(0),
+            });
+        }
+    }
+
+    /// Přečísluje dosud nezapsané záznamy (i právě promítanou položku) po změně pořadí
+    /// položek playlistu během prezentace, `order[i]` je původní pořadí položky, která
+    /// je nyní na pozici `i` (viz [`crate::playlist::PlaylistMetadata::reorder_items`]).
+    pub fn renumber(&mut self, order: &[usize]) {
+        let new_part_order = |part_order: i64| {
+            order
+                .iter()
+                .position(|&original| original as i64 == part_order)
+                .map_or(part_order, |position| position as i64)
+        };
+
+        for entry in &mut self.entries {
+            entry.part_order = new_part_order(entry.part_order);
+        }
+        if let Some((part_order, _)) = &mut self.current {
+            *part_order = new_part_order(*part_order);
+        }
+    }
+
+    /// Sečte, jak dlouho byla promítána jednotlivá položka (podle pořadí v playlistu).
+    /// Počítá pouze s uzavřenými záznamy, které ještě nebyly zapsány do databáze,
+    /// volající tedy typicky nejdříve zavolá [`PresentationLog::finish()`].
+    pub fn measured_durations(&self) -> BTreeMap<i64, TimeDelta> {
+        let mut durations = BTreeMap::new();
+
+        for entry in &self.entries {
+            *durations
+                .entry(entry.part_order)
+                .or_insert(TimeDelta::zero()) += entry.duration();
+        }
+
+        durations
+    }
+
+    /// Vrátí uzavřené záznamy, které ještě nebyly zapsány do databáze
+    pub fn get_entries(&self) -> &[PresentationLogEntry] {
+        &self.entries
+    }
+
+    /// Uzavře právě promítanou položku a zapíše všechny záznamy do databáze. Zapsané
+    /// záznamy z paměti odstraní, opakované volání tedy nic nezduplikuje.
+    ///
+    /// ### Integrita databáze
+    /// Zápis probíhá v transakci, buď se zapíšou všechny záznamy, nebo žádný
+    /// (v tom případě zůstanou v paměti a vrací se Error).
+    pub async fn flush(&mut self, conn: &mut PoolConnection<Sqlite>) -> Result<()> {
+        self.finish();
+
+        if self.entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut transaction = conn
+            .begin()
+            .await
+            .context("Nelze získat transakci na poolu databáze")?;
+
+        for entry in &self.entries {
+            let started = entry.started.format(DB_DATETIME_FORMAT).to_string();
+            let ended = entry.ended.format(DB_DATETIME_FORMAT).to_string();
+
+            // Píseň se zapíše přímo k záznamu, playlist se může později změnit
+            query!(
+                "INSERT INTO presentation_log (playlist_id, part_order, song_id, started, ended) VALUES ($1, $2, (SELECT song_id FROM playlist_songs WHERE playlist_id = $1 AND part_order = $2), $3, $4)",
+                self.playlist_id,
+                entry.part_order,
+                started,
+                ended
+            )
+            .execute(&mut *transaction)
+            .await
+            .context("Nelze zapsat záznam o prezentaci")?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .context("Commit transakce zápisu záznamu o prezentaci selhal")?;
+
+        self.entries.clear();
+
+        Ok(())
+    }
+}
+
+/// Načte všechny záznamy o prezentaci playlistu `playlist_id` seřazené podle času
+/// začátku. Pokud se vyskytne chyba v databázi, vrací Error.
+pub async fn load_for_playlist(
+    playlist_id: i64,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<Vec<PresentationLogEntry>> {
+    let records = query!(
+        "SELECT part_order, started, ended FROM presentation_log WHERE playlist_id = $1 ORDER BY started ASC",
+        playlist_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .context("Nelze načíst záznamy o prezentaci z databáze")?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            let parse = |value: &str| {
+                NaiveDateTime::parse_from_str(value, DB_DATETIME_FORMAT)
+                    .map(|datetime| datetime.and_utc())
+                    .with_context(|| format!("Nelze zparsovat datum z databáze {value}"))
+            };
+
+            Ok(PresentationLogEntry {
+                part_order: record.part_order,
+                started: parse(&record.started)?,
+                ended: parse(&record.ended)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn measured_durations_are_summed_per_item() {
+        let start = Utc::now().round_subsecs(0);
+        let mut log = PresentationLog::new(0);
+        log.entries = vec![
+            PresentationLogEntry {
+                part_order: 0,
+                started: start,
+                ended: start + TimeDelta::seconds(30),
+            },
+            PresentationLogEntry {
+                part_order: 1,
+                started: start + TimeDelta::seconds(30),
+                ended: start + TimeDelta::seconds(40),
+            },
+            PresentationLogEntry {
+                part_order: 0,
+                started: start + TimeDelta::seconds(40),
+                ended: start + TimeDelta::seconds(55),
+            },
+        ];
+
+        let durations = log.measured_durations();
+
+        assert_eq!(durations.get(&0), Some(&TimeDelta::seconds(45)));
+        assert_eq!(durations.get(&1), Some(&TimeDelta::seconds(10)));
+    }
+
+    #[test]
+    fn presenting_same_item_does_not_split_entry() {
+        let mut log = PresentationLog::new(0);
+        log.item_presented(0);
+        log.item_presented(0);
+        log.item_presented(1);
+        log.finish();
+
+        let orders: Vec<i64> = log.get_entries().iter().map(|e| e.part_order).collect();
+        assert_eq!(orders, vec![0, 1]);
+    }
+
+    #[test]
+    fn renumbering_follows_new_item_order() {
+        let mut log = PresentationLog::new(0);
+        log.item_presented(0);
+        log.item_presented(2);
+        log.renumber(&[0, 2, 1]);
+        log.item_presented(2);
+        log.finish();
+
+        let orders: Vec<i64> = log.get_entries().iter().map(|e| e.part_order).collect();
+        assert_eq!(orders, vec![0, 1, 2]);
+    }
+}
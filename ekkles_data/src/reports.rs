@@ -0,0 +1,463 @@
+//! Modul pro přehledy o používání písní a jejich export do CSV, typicky pro každoroční
+//! hlášení licenční organizaci (CCLI).
+//!
+//! ### Použití písně
+//! Za použití písně se považuje její promítnutí v rámci prezentace playlistu, prezentace
+//! jej zaznamená pomocí [`Song::record_usage()`]. Položka playlistu promítnutá vícekrát
+//! během jednoho dne (např. návrat k refrénu) se počítá jako jedno použití. Použití jsou
+//! uložena u písně, zůstanou tedy i po úpravě nebo smazání playlistu.
+//!
+//! ### Formát CSV
+//! Hodnoty jsou odděleny čárkou, hodnoty obsahující čárku, uvozovky nebo nový řádek jsou
+//! uzavřeny v uvozovkách ([RFC 4180](https://www.rfc-editor.org/rfc/rfc4180)). Časy jsou
+//! převedeny do místního časového pásma.
+//!
+//! ### Hlášení CCLI
+//! [`ccli_report_csv()`] sestaví hlášení ve tvaru, který očekává CCLI: název písně, její
+//! CCLI číslo a počet použití ve sledovaném období ([`ReportPeriod`], viz
+//! [`Song::usage_report()`]). Písně bez CCLI čísla nelze nahlásit, v hlášení tedy nejsou.
+
+use std::{cmp::Reverse, fmt::Display};
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Days, Local, NaiveDate, NaiveDateTime, NaiveTime, SubsecRound, Utc};
+use sqlx::{Sqlite, SqlitePool, pool::PoolConnection, query};
+
+use crate::{Song, error};
+
+/// Formátovací řetězec pro převod času z databáze, stejný jako u playlistů
+const DB_DATETIME_FORMAT: &str = "%F %T";
+/// Formátovací řetězec pro čas v exportovaném CSV
+const CSV_DATETIME_FORMAT: &str = "%F %T";
+
+/// Přehled, který lze exportovat do CSV
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Report {
+    /// Všechny písně v databázi včetně nikdy nepoužitých
+    SongLibrary,
+    /// Pouze použité písně seřazené podle počtu použití
+    SongUsage,
+    /// Jednotlivé záznamy o promítání položek playlistů
+    PresentationLog,
+    /// Hlášení pro CCLI za dané období
+    Ccli(ReportPeriod),
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Report::SongLibrary => "Knihovna písní",
+            Report::SongUsage => "Používání písní",
+            Report::PresentationLog => "Záznam prezentací",
+            Report::Ccli(_) => "Hlášení CCLI",
+        })
+    }
+}
+
+impl Report {
+    /// Přehledy, které nepotřebují žádné další parametry
+    pub const ALL: [Report; 3] = [
+        Report::SongLibrary,
+        Report::SongUsage,
+        Report::PresentationLog,
+    ];
+
+    /// Výchozí název souboru s exportovaným přehledem
+    pub fn default_file_name(&self) -> &'static str {
+        match self {
+            Report::SongLibrary => "knihovna_pisni.csv",
+            Report::SongUsage => "pouzivani_pisni.csv",
+            Report::PresentationLog => "zaznam_prezentaci.csv",
+            Report::Ccli(_) => "hlaseni_ccli.csv",
+        }
+    }
+
+    /// Sestaví přehled z databáze a vrátí jej jako CSV
+    pub async fn to_csv(&self, conn: &mut PoolConnection<Sqlite>) -> Result<String> {
+        match self {
+            Report::SongLibrary => song_library_csv(conn).await,
+            Report::SongUsage => song_usage_csv(conn).await,
+            Report::PresentationLog => presentation_log_csv(conn).await,
+            Report::Ccli(period) => ccli_report_csv(period, conn).await,
+        }
+    }
+}
+
+/// Sledované období přehledu, obsahuje oba krajní dny (v místním čase)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportPeriod {
+    from: NaiveDate,
+    to: NaiveDate,
+}
+
+impl ReportPeriod {
+    /// Vytvoří období od `from` do `to` (včetně), pokud `from` je po `to`, vrací Error.
+    pub fn new(from: NaiveDate, to: NaiveDate) -> Result<Self> {
+        if from > to {
+            bail!("Začátek období {from} je až po jeho konci {to}");
+        }
+
+        Ok(Self { from, to })
+    }
+
+    pub fn get_from(&self) -> NaiveDate {
+        self.from
+    }
+
+    pub fn get_to(&self) -> NaiveDate {
+        self.to
+    }
+
+    /// Vrátí hranice období ve tvaru, v jakém je uložen čas v databázi (UTC), tedy
+    /// začátek prvního dne (včetně) a začátek dne po posledním dni (vyjma).
+    fn db_bounds(&self) -> (String, String) {
+        let day_start = |date: NaiveDate| {
+            let naive = date.and_time(NaiveTime::MIN);
+            naive
+                .and_local_timezone(Local)
+                .earliest()
+                .map(|local| local.with_timezone(&Utc))
+                // Půlnoc v místním čase nemusí existovat (přechod na letní čas), bereme ji jako UTC
+                .unwrap_or(naive.and_utc())
+                .format(DB_DATETIME_FORMAT)
+                .to_string()
+        };
+
+        (day_start(self.from), day_start(self.to + Days::new(1)))
+    }
+}
+
+/// Souhrn používání jedné písně
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SongUsage {
+    pub song_id: i64,
+    pub title: String,
+    pub author: Option<String>,
+    /// Číslo písně v licenční databázi CCLI
+    pub ccli_number: Option<String>,
+    /// Počet použití, viz dokumentace modulu
+    pub times_used: i64,
+    /// Kdy se píseň naposledy začala promítat, `None` pokud nebyla nikdy použita
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+/// Načte souhrn používání všech písní v databázi (i nikdy nepoužitých) seřazený podle názvu.
+/// Pokud je dáno období `period`, započítají se pouze použití v tomto období.
+pub async fn load_song_usage(
+    period: Option<&ReportPeriod>,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<Vec<SongUsage>> {
+    let (from, to) = period.map(ReportPeriod::db_bounds).unzip();
+    let records = query!(
+        r#"SELECT songs.id AS "id!", songs.title AS "title!", songs.author, songs.ccli_number,
+            COUNT(uses.id) AS "times_used!: i64",
+            MAX(uses.presented) AS "last_used?: String"
+        FROM songs LEFT JOIN (
+            SELECT id, song_id, presented FROM song_usage
+            WHERE ($1 IS NULL OR presented >= $1) AND ($2 IS NULL OR presented < $2)
+        ) AS uses ON uses.song_id = songs.id
+        GROUP BY songs.id
+        ORDER BY songs.title"#,
+        from,
+        to
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .context("Nelze načíst přehled používání písní z databáze")?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            Ok(SongUsage {
+                song_id: record.id,
+                title: record.title,
+                author: record.author,
+                ccli_number: record.ccli_number,
+                times_used: record.times_used,
+                last_used: record
+                    .last_used
+                    .as_deref()
+                    .map(parse_db_datetime)
+                    .transpose()?,
+            })
+        })
+        .collect()
+}
+
+impl Song {
+    /// Zaznamená použití písně, položky s pořadím `part_order` playlistu `playlist_id`,
+    /// právě teď (viz [dokumentace modulu](crate::reports)). Pokud položka není píseň,
+    /// nebo již byla tento den zaznamenána, nic se nezapíše. Vrací, zda se použití zapsalo.
+    pub async fn record_usage(
+        playlist_id: i64,
+        part_order: i64,
+        pool: &SqlitePool,
+    ) -> error::Result<bool> {
+        let presented = Utc::now()
+            .round_subsecs(0)
+            .format(DB_DATETIME_FORMAT)
+            .to_string();
+
+        let result = query!(
+            "INSERT INTO song_usage (song_id, playlist_id, part_order, presented)
+            SELECT song_id, $1, $2, $3 FROM playlist_songs
+            WHERE playlist_id = $1 AND part_order = $2 AND NOT EXISTS (
+                SELECT 1 FROM song_usage
+                WHERE playlist_id = $1 AND part_order = $2 AND date(presented) = date($3)
+            )",
+            playlist_id,
+            part_order,
+            presented
+        )
+        .execute(pool)
+        .await
+        .with_context(|| {
+            format!("Nelze zaznamenat použití položky {part_order} playlistu {playlist_id}")
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Vrátí souhrn používání písní použitých v období od `from` do `to` (včetně, v místním
+    /// čase) seřazený podle názvu. Pokud je `from` po `to`, vrací Error.
+    pub async fn usage_report(
+        from: NaiveDate,
+        to: NaiveDate,
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> error::Result<Vec<SongUsage>> {
+        let period = ReportPeriod::new(from, to)?;
+
+        Ok(load_song_usage(Some(&period), conn)
+            .await?
+            .into_iter()
+            .filter(|song| song.times_used > 0)
+            .collect())
+    }
+
+    /// Vrátí nejvýše `limit` písní jako dvojice (id, název) seřazené podle toho, kdy byly
+    /// naposledy použity nebo zařazeny do playlistu (podle času vytvoření playlistu),
+    /// nejnovější jsou první. Písně, které nebyly nikdy použity ani zařazeny, nevrací.
+    pub async fn recently_used(
+        limit: u32,
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> error::Result<Vec<(i64, String)>> {
+        Ok(query!(
+            r#"SELECT songs.id AS "id!", songs.title AS "title!"
+            FROM songs JOIN (
+                SELECT song_id, presented AS used FROM song_usage
+                UNION ALL
+                SELECT playlist_songs.song_id, playlists.created
+                FROM playlist_songs JOIN playlists ON playlists.id = playlist_songs.playlist_id
+            ) AS uses ON uses.song_id = songs.id
+            GROUP BY songs.id
+            ORDER BY MAX(uses.used) DESC, songs.title
+            LIMIT $1"#,
+            limit
+        )
+        .map(|record| (record.id, record.title))
+        .fetch_all(conn.as_mut())
+        .await
+        .context("Nelze načíst naposledy použité písně z databáze")?)
+    }
+
+    /// Vrátí nejvýše `limit` nejčastěji použitých písní (viz [dokumentace modulu](crate::reports))
+    /// jako dvojice (id, název), nejpoužívanější jsou první. Nikdy nepoužité písně nevrací.
+    pub async fn most_used(
+        limit: u32,
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> error::Result<Vec<(i64, String)>> {
+        Ok(query!(
+            r#"SELECT songs.id AS "id!", songs.title
+            FROM songs JOIN song_usage ON song_usage.song_id = songs.id
+            GROUP BY songs.id
+            ORDER BY COUNT(song_usage.id) DESC, songs.title
+            LIMIT $1"#,
+            limit
+        )
+        .map(|record| (record.id, record.title))
+        .fetch_all(conn.as_mut())
+        .await
+        .context("Nelze načíst nejpoužívanější písně z databáze")?)
+    }
+}
+
+/// Vrátí CSV se všemi písněmi v databázi (název, autor, CCLI, naposledy použita, počet použití)
+pub async fn song_library_csv(conn: &mut PoolConnection<Sqlite>) -> Result<String> {
+    let usage = load_song_usage(None, conn).await?;
+    Ok(song_usage_to_csv(&usage))
+}
+
+/// Vrátí CSV pouze s použitými písněmi, nejčastěji používané jsou první
+pub async fn song_usage_csv(conn: &mut PoolConnection<Sqlite>) -> Result<String> {
+    let mut usage: Vec<_> = load_song_usage(None, conn)
+        .await?
+        .into_iter()
+        .filter(|song| song.times_used > 0)
+        .collect();
+    // Řazení je stabilní, při stejném počtu použití zůstanou písně seřazené podle názvu
+    usage.sort_by_key(|song| Reverse(song.times_used));
+
+    Ok(song_usage_to_csv(&usage))
+}
+
+/// Vrátí CSV se všemi záznamy o promítání (playlist, pořadí položky, píseň, začátek, konec,
+/// délka v sekundách) seřazenými podle začátku. Píseň je ta, která se tehdy promítala, i když
+/// byl playlist mezitím upraven. Pokud položka není píseň, je sloupec píseň prázdný.
+pub async fn presentation_log_csv(conn: &mut PoolConnection<Sqlite>) -> Result<String> {
+    let records = query!(
+        r#"SELECT playlists.name AS playlist_name, presentation_log.part_order,
+            songs.title AS "song_title?", presentation_log.started, presentation_log.ended
+        FROM presentation_log
+        JOIN playlists ON playlists.id = presentation_log.playlist_id
+        LEFT JOIN songs ON songs.id = presentation_log.song_id
+        ORDER BY presentation_log.started ASC"#
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .context("Nelze načíst záznamy o prezentaci z databáze")?;
+
+    let mut rows = Vec::with_capacity(records.len());
+    for record in records {
+        let started = parse_db_datetime(&record.started)?;
+        let ended = parse_db_datetime(&record.ended)?;
+        rows.push(vec![
+            record.playlist_name,
+            // Pořadí je v databázi od 0, uživatel položky čísluje od 1
+            (record.part_order + 1).to_string(),
+            record.song_title.unwrap_or_default(),
+            format_datetime(started),
+            format_datetime(ended),
+            (ended - started).num_seconds().to_string(),
+        ]);
+    }
+
+    Ok(to_csv(
+        &[
+            "Playlist",
+            "Pořadí",
+            "Píseň",
+            "Začátek",
+            "Konec",
+            "Délka (s)",
+        ],
+        rows,
+    ))
+}
+
+/// Vrátí hlášení pro CCLI (název písně, CCLI číslo, počet použití) za období `period`.
+/// Obsahuje pouze písně s CCLI číslem, které byly v období použity, seřazené podle názvu.
+pub async fn ccli_report_csv(
+    period: &ReportPeriod,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<String> {
+    let usage = Song::usage_report(period.from, period.to, conn).await?;
+
+    Ok(to_csv(
+        &["Song Title", "CCLI Song Number", "Times Used"],
+        usage.into_iter().filter_map(|song| {
+            let ccli_number = song.ccli_number?;
+            Some(vec![song.title, ccli_number, song.times_used.to_string()])
+        }),
+    ))
+}
+
+fn song_usage_to_csv(usage: &[SongUsage]) -> String {
+    to_csv(
+        &[
+            "Název",
+            "Autor",
+            "CCLI",
+            "Naposledy použita",
+            "Počet použití",
+        ],
+        usage.iter().map(|song| {
+            vec![
+                song.title.clone(),
+                song.author.clone().unwrap_or_default(),
+                song.ccli_number.clone().unwrap_or_default(),
+                song.last_used.map(format_datetime).unwrap_or_default(),
+                song.times_used.to_string(),
+            ]
+        }),
+    )
+}
+
+fn parse_db_datetime(value: &str) -> Result<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, DB_DATETIME_FORMAT)
+        .map(|datetime| datetime.and_utc())
+        .with_context(|| format!("Nelze zparsovat datum z databáze {value}"))
+}
+
+fn format_datetime(datetime: DateTime<Utc>) -> String {
+    datetime
+        .with_timezone(&Local)
+        .format(CSV_DATETIME_FORMAT)
+        .to_string()
+}
+
+/// Sestaví CSV z hlavičky `header` a řádků `rows`, každý řádek je ukončen `\n`.
+fn to_csv(header: &[&str], rows: impl IntoIterator<Item = Vec<String>>) -> String {
+    let mut csv = String::new();
+    push_csv_row(&mut csv, header.iter().copied());
+    for row in rows {
+        push_csv_row(&mut csv, row.iter().map(String::as_str));
+    }
+
+    csv
+}
+
+fn push_csv_row<'a>(csv: &mut String, fields: impl Iterator<Item = &'a str>) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            csv.push(',');
+        }
+
+        if field.contains([',', '"', '\n', '\r']) {
+            csv.push('"');
+            csv.push_str(&field.replace('"', "\"\""));
+            csv.push('"');
+        } else {
+            csv.push_str(field);
+        }
+    }
+    csv.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn period_must_not_be_reversed() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+
+        assert!(ReportPeriod::new(day(2), day(1)).is_err());
+        let period = ReportPeriod::new(day(1), day(1)).unwrap();
+        let (from, to) = period.db_bounds();
+        assert!(from < to);
+    }
+
+    #[test]
+    fn csv_quoting() {
+        let csv = to_csv(
+            &["Název", "Autor"],
+            [
+                vec![
+                    String::from("Christ Arose"),
+                    String::from("Robert Lowry, 1874"),
+                ],
+                vec![String::from("Píseň \"Haleluja\""), String::new()],
+                vec![String::from("Dva\nřádky"), String::from("Nikdo")],
+            ],
+        );
+
+        assert_eq!(
+            csv,
+            "Název,Autor\n\
+            Christ Arose,\"Robert Lowry, 1874\"\n\
+            \"Píseň \"\"Haleluja\"\"\",\n\
+            \"Dva\nřádky\",Nikdo\n"
+        );
+    }
+}
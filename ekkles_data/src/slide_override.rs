@@ -0,0 +1,237 @@
+//! Modul pro úpravy jednotlivých slajdů playlistu.
+//!
+//! Pro speciální případy lze jednomu konkrétnímu slajdu změnit velikost textu,
+//! zarovnání nebo barvu pozadí. Úprava je identifikována pořadím položky v playlistu
+//! a pořadím slajdu v rámci této položky. V databázi je vázaná na položku playlistu,
+//! při uložení editovaného playlistu se úpravy přenesou s položkou na její novou pozici
+//! (viz [`restore_moved`]), se smazanou nebo nahrazenou položkou se smažou.
+
+use std::{collections::HashMap, fmt::Display};
+
+use anyhow::{Context, Result, anyhow};
+use sqlx::{Sqlite, Transaction, pool::PoolConnection, query};
+
+use crate::theme::{Rgb, hex_to_rgb, rgb_to_hex};
+
+/// Zarovnání textu na slajdu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlideAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl SlideAlignment {
+    /// Všechna zarovnání v pořadí, v jakém se nabízí uživateli
+    pub const ALL: [SlideAlignment; 3] = [
+        SlideAlignment::Left,
+        SlideAlignment::Center,
+        SlideAlignment::Right,
+    ];
+
+    /// Hodnota sloupce `alignment` v tabulce `slide_overrides`
+    fn to_db(self) -> &'static str {
+        match self {
+            SlideAlignment::Left => "left",
+            SlideAlignment::Center => "center",
+            SlideAlignment::Right => "right",
+        }
+    }
+
+    fn from_db(value: &str) -> Result<Self> {
+        match value {
+            "left" => Ok(SlideAlignment::Left),
+            "center" => Ok(SlideAlignment::Center),
+            "right" => Ok(SlideAlignment::Right),
+            _ => Err(anyhow!("Neznámé zarovnání slajdu: {value}")),
+        }
+    }
+}
+
+impl Display for SlideAlignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SlideAlignment::Left => "Vlevo",
+            SlideAlignment::Center => "Na střed",
+            SlideAlignment::Right => "Vpravo",
+        })
+    }
+}
+
+/// Úprava jednoho slajdu, `None` znamená, že se daná vlastnost nemění
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlideOverride {
+    /// Škálování velikosti textu (stejná stupnice jako v prezentéru, 0 až 255)
+    pub text_scale: Option<u8>,
+    pub alignment: Option<SlideAlignment>,
+    pub background_color: Option<Rgb>,
+}
+
+impl SlideOverride {
+    /// Vrátí `true`, pokud úprava nic nemění
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Načte všechny úpravy slajdů playlistu `playlist_id`. Klíčem je dvojice
+/// (pořadí položky v playlistu, pořadí slajdu v rámci položky).
+pub async fn load_for_playlist(
+    playlist_id: i64,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<HashMap<(usize, usize), SlideOverride>> {
+    let records = query!(
+        "SELECT part_order, slide_index, text_scale, alignment, background_color FROM slide_overrides WHERE playlist_id = $1",
+        playlist_id
+    )
+    .fetch_all(conn.as_mut())
+    .await
+    .context("Nelze načíst úpravy slajdů z databáze")?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            let slide_override = SlideOverride {
+                text_scale: record.text_scale.map(|scale| scale as u8),
+                alignment: record
+                    .alignment
+                    .as_deref()
+                    .map(SlideAlignment::from_db)
+                    .transpose()?,
+                background_color: record
+                    .background_color
+                    .as_deref()
+                    .map(|color| {
+                        hex_to_rgb(color).ok_or(anyhow!("Nevalidní barva pozadí v databázi"))
+                    })
+                    .transpose()?,
+            };
+
+            Ok((
+                (record.part_order as usize, record.slide_index as usize),
+                slide_override,
+            ))
+        })
+        .collect()
+}
+
+/// Uloží úpravu slajdu `slide_index` položky `part_order` playlistu `playlist_id`.
+/// Pokud úprava nic nemění, smaže ji z databáze.
+pub async fn save(
+    playlist_id: i64,
+    part_order: usize,
+    slide_index: usize,
+    slide_override: &SlideOverride,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<()> {
+    let part_order = part_order as i64;
+    let slide_index = slide_index as i64;
+
+    if slide_override.is_empty() {
+        query!(
+            "DELETE FROM slide_overrides WHERE playlist_id = $1 AND part_order = $2 AND slide_index = $3",
+            playlist_id,
+            part_order,
+            slide_index
+        )
+        .execute(conn.as_mut())
+        .await
+        .context("Nelze smazat úpravu slajdu z databáze")?;
+
+        return Ok(());
+    }
+
+    let alignment = slide_override.alignment.map(SlideAlignment::to_db);
+    let background_color = slide_override.background_color.map(rgb_to_hex);
+
+    query!(
+        "INSERT OR REPLACE INTO slide_overrides (playlist_id, part_order, slide_index, text_scale, alignment, background_color) VALUES ($1, $2, $3, $4, $5, $6)",
+        playlist_id,
+        part_order,
+        slide_index,
+        slide_override.text_scale,
+        alignment,
+        background_color
+    )
+    .execute(conn.as_mut())
+    .await
+    .context("Nelze uložit úpravu slajdu do databáze")?;
+
+    Ok(())
+}
+
+/// Úprava slajdu tak, jak je uložená v databázi, odložená stranou při přeuložení
+/// položek playlistu, viz [`load_stored`]
+pub(crate) struct StoredOverride {
+    part_order: i64,
+    slide_index: i64,
+    text_scale: Option<i64>,
+    alignment: Option<String>,
+    background_color: Option<String>,
+}
+
+/// Načte všechny úpravy slajdů playlistu `playlist_id` beze změny, aby je šlo po přeuložení
+/// položek playlistu vrátit pomocí [`restore_moved`].
+///
+/// ### Transakce
+/// Volající je odpovědný za commit/rollback transakce, tato funkce pouze použije danou
+/// transakci k přístupu do databáze, ale commit neprovádí.
+pub(crate) async fn load_stored(
+    playlist_id: i64,
+    transaction: &mut Transaction<'_, Sqlite>,
+) -> Result<Vec<StoredOverride>> {
+    query!(
+        "SELECT part_order, slide_index, text_scale, alignment, background_color FROM slide_overrides WHERE playlist_id = $1",
+        playlist_id
+    )
+    .map(|record| StoredOverride {
+        part_order: record.part_order,
+        slide_index: record.slide_index,
+        text_scale: record.text_scale,
+        alignment: record.alignment,
+        background_color: record.background_color,
+    })
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Nelze načíst úpravy slajdů z databáze")
+}
+
+/// Vrátí úpravy `stored` načtené pomocí [`load_stored`] k položkám playlistu `playlist_id`
+/// na jejich nových pozicích (položky již musí být v databázi). Na pozici `i` je nyní
+/// položka, která byla uložena s pořadím `saved_orders[i]`, úpravy položek, které
+/// v playlistu už nejsou (`None` = nová nebo nahrazená položka), se zahodí.
+///
+/// ### Transakce
+/// Volající je odpovědný za commit/rollback transakce, tato funkce pouze použije danou
+/// transakci k přístupu do databáze, ale commit neprovádí.
+pub(crate) async fn restore_moved(
+    playlist_id: i64,
+    stored: Vec<StoredOverride>,
+    saved_orders: &[Option<usize>],
+    transaction: &mut Transaction<'_, Sqlite>,
+) -> Result<()> {
+    for slide_override in stored {
+        let Some(part_order) = saved_orders
+            .iter()
+            .position(|saved| *saved == Some(slide_override.part_order as usize))
+        else {
+            continue;
+        };
+        let part_order = part_order as i64;
+
+        query!(
+            "INSERT INTO slide_overrides (playlist_id, part_order, slide_index, text_scale, alignment, background_color) VALUES ($1, $2, $3, $4, $5, $6)",
+            playlist_id,
+            part_order,
+            slide_override.slide_index,
+            slide_override.text_scale,
+            slide_override.alignment,
+            slide_override.background_color
+        )
+        .execute(&mut **transaction)
+        .await
+        .with_context(|| format!("Nelze přenést úpravu slajdu na položku {part_order}"))?;
+    }
+
+    Ok(())
+}
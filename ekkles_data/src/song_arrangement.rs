@@ -0,0 +1,116 @@
+//! Pojmenovaná pořadí částí písně (např. "Celá", "Krátká", "Akustická"). Jedna píseň jich
+//! může mít více, vybírají se při přidání písně do playlistu nebo v editoru playlistu,
+//! kde se vybrané pořadí nastaví jako vlastní pořadí položky (viz
+//! [`crate::playlist::PlaylistMetadata::set_arrangement()`]). Výchozí pořadí písně
+//! ([`crate::Song::order`]) se nemění.
+
+use std::fmt::Display;
+
+use anyhow::{Context, Result, bail};
+use sqlx::{Sqlite, SqlitePool, pool::PoolConnection, query};
+
+use crate::PartTag;
+
+/// Oddělovač tagů ve sloupci `part_order`, stejný jako u pořadí písně
+const TAG_SPLIT_STRING: &str = " ";
+
+/// Pojmenované pořadí částí písně
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SongArrangement {
+    /// Název pořadí, v rámci písně jedinečný (bez ohledu na velikost písmen)
+    pub name: String,
+    /// Pořadí částí písně, umožňuje opakování jedné části
+    pub order: Vec<PartTag>,
+}
+
+impl Display for SongArrangement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.order.join(TAG_SPLIT_STRING))
+    }
+}
+
+impl SongArrangement {
+    /// Uloží pořadí k písni `song_id`, pořadí se stejným názvem bude přepsáno. Pokud je
+    /// název prázdný, pořadí prázdné nebo obsahuje tag, který píseň nemá, vrací Error.
+    pub async fn save_to_db(&self, song_id: i64, pool: &SqlitePool) -> Result<()> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            bail!("Název pořadí nesmí být prázdný");
+        }
+        if self.order.is_empty() {
+            bail!("Pořadí \"{name}\" nesmí být prázdné");
+        }
+
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Nelze získat připojení k databázi z poolu")?;
+
+        let tags: Vec<PartTag> = query!("SELECT tag FROM song_parts WHERE song_id = $1", song_id)
+            .fetch_all(&mut *transaction)
+            .await
+            .with_context(|| format!("Nelze načíst části písně s id {song_id}"))?
+            .into_iter()
+            .map(|record| record.tag)
+            .collect();
+        if let Some(unknown) = self.order.iter().find(|tag| !tags.contains(tag)) {
+            bail!("Píseň nemá část {unknown} z pořadí \"{name}\"");
+        }
+
+        let part_order = self.order.join(TAG_SPLIT_STRING);
+        query!(
+            "INSERT OR REPLACE INTO song_arrangements (song_id, name, part_order) VALUES ($1, $2, $3)",
+            song_id,
+            name,
+            part_order
+        )
+        .execute(&mut *transaction)
+        .await
+        .with_context(|| format!("Nelze uložit pořadí \"{name}\" písně s id {song_id}"))?;
+
+        transaction
+            .commit()
+            .await
+            .context("Nelze provést COMMIT uložení pořadí písně")
+    }
+
+    /// Načte pojmenovaná pořadí písně `song_id` seřazená podle názvu
+    pub async fn load_for_song(
+        song_id: i64,
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> Result<Vec<Self>> {
+        let records = query!(
+            "SELECT name, part_order FROM song_arrangements WHERE song_id = $1 ORDER BY name",
+            song_id
+        )
+        .fetch_all(conn.as_mut())
+        .await
+        .with_context(|| format!("Nelze načíst pořadí písně s id {song_id}"))?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| Self {
+                name: record.name,
+                order: record
+                    .part_order
+                    .split(TAG_SPLIT_STRING)
+                    .map(str::to_string)
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Smaže pořadí s názvem `name` písně `song_id`, pokud neexistuje, nic se nestane
+    pub async fn delete_from_db(song_id: i64, name: &str, pool: &SqlitePool) -> Result<()> {
+        query!(
+            "DELETE FROM song_arrangements WHERE song_id = $1 AND name = $2",
+            song_id,
+            name
+        )
+        .execute(pool)
+        .await
+        .with_context(|| format!("Nelze smazat pořadí \"{name}\" písně s id {song_id}"))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,343 @@
+//! Modul pro parsování písní ve formátu [ChordPro](https://www.chordpro.org/chordpro/)
+//! (soubory `.cho`, `.crd`, `.chordpro`) do formátu používaného Ekklesem.
+//!
+//! ### Podporované direktivy
+//! - `{title}` (`{t}`) - název písně (povinný)
+//! - `{artist}`, `{composer}`, `{lyricist}` - autoři, spojí se do jednoho autora písně
+//! - `{start_of_verse}`/`{end_of_verse}` (`{sov}`/`{eov}`), obdobně `chorus` (`soc`/`eoc`)
+//!   a `bridge` (`sob`/`eob`) - části písně
+//! - `{chorus}` - zopakování posledního refrénu
+//! - `{ccli}`, `{copyright}`, `{key}`, `{capo}`, `{tag}` - doplňující údaje, viz [`SongMetadata`]
+//!
+//! Ostatní direktivy (komentáře, akordové diagramy, taby, ...) jsou ignorovány. Akordy
+//! v hranatých závorkách (např. `[Am]`) jsou ze slov odstraněny. Text mimo označené části
+//! se rozdělí na sloky podle prázdných řádků.
+
+use std::{collections::HashMap, fs::read_to_string, path::Path};
+
+use anyhow::{Context, Result, bail};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{PartTag, Song, SongMetadata};
+
+lazy_static! {
+    /// Matchne direktivu `{název: hodnota}` nebo `{název}`, název uloží do capture grupy `name`
+    /// a nepovinnou hodnotu do capture grupy `value`.
+    static ref DIRECTIVE_REGEX: Regex =
+        Regex::new(r"^\{\s*(?P<name>[\w-]+)\s*(?:[:\s]\s*(?P<value>.*?))?\s*\}$").unwrap();
+    /// Matchne akord v hranatých závorkách
+    static ref CHORD_REGEX: Regex = Regex::new(r"\[[^\]]*\]").unwrap();
+}
+
+/// Druh části písně
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SectionKind {
+    Verse,
+    Chorus,
+    Bridge,
+}
+
+impl SectionKind {
+    /// Prefix tagu části, viz [`PartTag`]
+    fn tag_prefix(&self) -> &'static str {
+        match self {
+            SectionKind::Verse => "V",
+            SectionKind::Chorus => "C",
+            SectionKind::Bridge => "B",
+        }
+    }
+}
+
+/// Rozpracovaná část písně
+#[derive(Debug)]
+struct Section {
+    kind: SectionKind,
+    lines: Vec<String>,
+    /// Část je otevřena direktivou (jinak vznikla z textu mimo direktivy a uzavře ji prázdný řádek)
+    explicit: bool,
+}
+
+/// Stav parsování, části písně a pořadí (indexy do `sections`) v jakém se zpívají
+#[derive(Debug, Default)]
+struct ChordproParser {
+    title: Option<String>,
+    authors: Vec<String>,
+    metadata: SongMetadata,
+    sections: Vec<(SectionKind, Vec<String>)>,
+    order: Vec<usize>,
+    current: Option<Section>,
+    /// Ignorují se řádky až do konce právě otevřené nepodporované části (např. `{start_of_tab}`)
+    skipping: bool,
+}
+
+impl ChordproParser {
+    /// Uzavře právě otevřenou část, pokud není prázdná, přidá ji do písně
+    fn close_section(&mut self) {
+        if let Some(section) = self.current.take()
+            && !section.lines.is_empty()
+        {
+            self.order.push(self.sections.len());
+            self.sections.push((section.kind, section.lines));
+        }
+    }
+
+    fn open_section(&mut self, kind: SectionKind) {
+        self.close_section();
+        self.current = Some(Section {
+            kind,
+            lines: Vec::new(),
+            explicit: true,
+        });
+    }
+
+    fn directive(&mut self, name: &str, value: Option<&str>) {
+        let value = value.map(str::trim).filter(|value| !value.is_empty());
+
+        match name.to_lowercase().as_str() {
+            "title" | "t" => self.title = value.map(str::to_string),
+            "artist" | "composer" | "lyricist" => {
+                if let Some(author) = value
+                    && !self.authors.iter().any(|existing| existing == author)
+                {
+                    self.authors.push(author.to_string());
+                }
+            }
+            "ccli" => self.metadata.ccli_number = value.map(str::to_string),
+            "copyright" => self.metadata.copyright = value.map(str::to_string),
+            "key" => self.metadata.key = value.map(str::to_string),
+            "capo" => self.metadata.capo = value.and_then(|capo| capo.parse().ok()),
+            "tag" => {
+                if let Some(topics) = value {
+                    self.metadata.add_topics(topics);
+                }
+            }
+            "start_of_verse" | "sov" => self.open_section(SectionKind::Verse),
+            "start_of_chorus" | "soc" => self.open_section(SectionKind::Chorus),
+            "start_of_bridge" | "sob" => self.open_section(SectionKind::Bridge),
+            "end_of_verse" | "eov" | "end_of_chorus" | "eoc" | "end_of_bridge" | "eob" => {
+                self.close_section()
+            }
+            "chorus" => {
+                self.close_section();
+                if let Some(last_chorus) = self
+                    .sections
+                    .iter()
+                    .rposition(|(kind, _)| *kind == SectionKind::Chorus)
+                {
+                    self.order.push(last_chorus);
+                }
+            }
+            name if name.starts_with("start_of_") => {
+                self.close_section();
+                self.skipping = true;
+            }
+            name if name.starts_with("end_of_") => self.skipping = false,
+            _ => {}
+        }
+    }
+
+    fn line(&mut self, line: &str) {
+        if self.skipping || line.starts_with('#') {
+            return;
+        }
+
+        if line.is_empty() {
+            // Prázdný řádek uzavře pouze část vzniklou z textu mimo direktivy
+            if self
+                .current
+                .as_ref()
+                .is_some_and(|section| !section.explicit)
+            {
+                self.close_section();
+            }
+            return;
+        }
+
+        let stripped = CHORD_REGEX.replace_all(line, "");
+        let stripped = stripped.split_whitespace().collect::<Vec<_>>().join(" ");
+        // Řádek pouze s akordy není součástí slov
+        if stripped.is_empty() {
+            return;
+        }
+
+        self.current
+            .get_or_insert_with(|| Section {
+                kind: SectionKind::Verse,
+                lines: Vec::new(),
+                explicit: false,
+            })
+            .lines
+            .push(stripped);
+    }
+
+    /// Vytvoří z nalezených částí píseň. Tagy částí se číslují podle druhu (V1, V2, ...),
+    /// pokud je daného druhu (refrén, bridge) pouze jedna část, je bez čísla (C, B).
+    fn into_song(mut self) -> Result<Song> {
+        self.close_section();
+
+        let title = self.title.context("Píseň musí mít název ({title})")?;
+        if self.sections.is_empty() {
+            bail!("Nepodařilo se extrahovat slova z písně {title}");
+        }
+
+        let count = |sought: SectionKind| {
+            self.sections
+                .iter()
+                .filter(|(kind, _)| *kind == sought)
+                .count()
+        };
+        let mut numbers: HashMap<&'static str, usize> = HashMap::new();
+        let tags: Vec<PartTag> = self
+            .sections
+            .iter()
+            .map(|(kind, _)| {
+                let number = numbers.entry(kind.tag_prefix()).or_default();
+                *number += 1;
+                if *kind != SectionKind::Verse && count(*kind) == 1 {
+                    kind.tag_prefix().to_string()
+                } else {
+                    format!("{}{}", kind.tag_prefix(), number)
+                }
+            })
+            .collect();
+
+        let order = self
+            .order
+            .iter()
+            .map(|index| tags[*index].clone())
+            .collect();
+        let parts = tags
+            .into_iter()
+            .zip(self.sections)
+            .map(|(tag, (_, lines))| (tag, lines.join("\n")))
+            .collect();
+
+        Ok(Song {
+            title,
+            author: (!self.authors.is_empty()).then(|| self.authors.join(", ")),
+            parts,
+            order,
+            metadata: self.metadata,
+        })
+    }
+}
+
+impl Song {
+    /// Zparsuje píseň ve formátu ChordPro ze souboru `file`.
+    /// Pokud se vše zdaří, vrátí načtenou píseň, jinak vrací Error.
+    ///
+    /// Více informací o způsobu parsování viz [`Song::parse_from_chordpro()`]
+    pub fn parse_from_chordpro_file(file: &Path) -> Result<Self> {
+        let input = read_to_string(file)
+            .with_context(|| format!("Nepodařilo se přečíst soubor {}", file.display()))?;
+
+        Song::parse_from_chordpro(&input)
+            .with_context(|| format!("Nepodařilo se zparsovat soubor {}", file.display()))
+    }
+
+    /// Zparsuje píseň `input` ve formátu ChordPro, viz dokumentace modulu.
+    /// Píseň musí mít název a alespoň jednu neprázdnou část, jinak vrací Error.
+    pub fn parse_from_chordpro(input: &str) -> Result<Self> {
+        let mut parser = ChordproParser::default();
+
+        for line in input.lines() {
+            let line = line.trim();
+
+            match DIRECTIVE_REGEX.captures(line) {
+                Some(captures) => parser.directive(
+                    &captures["name"],
+                    captures.name("value").map(|value| value.as_str()),
+                ),
+                None => parser.line(line),
+            }
+        }
+
+        parser.into_song()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn sections_and_chords() {
+        let input = "\
+{title: Amazing Grace}
+{artist: John Newton}
+{composer: William Walker}
+{key: G}
+{capo: 2}
+{ccli: 22025}
+{tag: Milost}
+# Komentář souboru
+{c: Capo 2}
+
+{start_of_verse: Verse 1}
+[G]Amazing [G7]grace, how [C]sweet the [G]sound
+[G] [D]
+That saved a wretch like me
+{end_of_verse}
+
+{soc}
+[C]Chorus line
+{eoc}
+
+{sov}
+Second verse
+{eov}
+{chorus}
+{start_of_tab}
+e|---0---|
+{end_of_tab}
+";
+
+        let song = Song::parse_from_chordpro(input).unwrap();
+
+        assert_eq!(song.title, "Amazing Grace");
+        assert_eq!(song.author.as_deref(), Some("John Newton, William Walker"));
+        assert_eq!(song.order, vec!["V1", "C", "V2", "C"]);
+        assert_eq!(
+            song.parts,
+            HashMap::from([
+                (
+                    String::from("V1"),
+                    String::from("Amazing grace, how sweet the sound\nThat saved a wretch like me")
+                ),
+                (String::from("C"), String::from("Chorus line")),
+                (String::from("V2"), String::from("Second verse")),
+            ])
+        );
+        assert_eq!(
+            song.metadata,
+            SongMetadata {
+                ccli_number: Some(String::from("22025")),
+                copyright: None,
+                key: Some(String::from("G")),
+                capo: Some(2),
+                topics: vec![String::from("Milost")],
+            }
+        );
+        assert!(song.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn text_without_sections() {
+        let input = "{t: Bez značek}\n\nPrvní [D]sloka\nřádek dva\n\n\nDruhá sloka\n";
+
+        let song = Song::parse_from_chordpro(input).unwrap();
+
+        assert_eq!(song.author, None);
+        assert_eq!(song.order, vec!["V1", "V2"]);
+        assert_eq!(song.parts["V1"], "První sloka\nřádek dva");
+        assert_eq!(song.parts["V2"], "Druhá sloka");
+    }
+
+    #[test]
+    fn missing_title_or_lyrics() {
+        assert!(Song::parse_from_chordpro("{soc}\nRefrén\n{eoc}").is_err());
+        assert!(Song::parse_from_chordpro("{title: Prázdná}\n{soc}\n{eoc}").is_err());
+    }
+}
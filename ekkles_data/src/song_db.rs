@@ -2,13 +2,29 @@
 
 use std::collections::HashMap;
 
-use crate::Song;
-use anyhow::{Context, Result};
+use crate::{
+    Song, SongMetadata,
+    author::link_song_authors,
+    error::{EkklesDataError, Result},
+    span, verify,
+};
+use anyhow::Context;
 use futures::TryStreamExt;
 use sqlx::{Sqlite, SqliteConnection, SqlitePool, pool::PoolConnection, query};
 
 const TAG_SPLIT_STRING: &str = " ";
 
+/// Píseň načtená pro prezentaci playlistu spolu s odkazy na svůj motiv a překlad,
+/// viz [`Song::load_for_playlist()`]
+#[derive(Debug, Clone)]
+pub(crate) struct PlaylistSong {
+    pub song: Song,
+    /// Preferovaný motiv písně, viz [`crate::theme::get_song_theme_id()`]
+    pub theme_id: Option<i64>,
+    /// Píseň, která je překladem této písně, viz [`Song::get_translation_id()`]
+    pub translated_song_id: Option<i64>,
+}
+
 impl Song {
     /// Uloží danou píseň do lokální SQlite databáze, ke které se připojí pomocí `pool`.
     ///
@@ -19,34 +35,171 @@ impl Song {
     /// ### Ošetření chyb
     /// Chyba nastane pokud:
     /// - Píseň není validní (tag v pořadí, který se nevyskytuje ve slovech)
+    /// - Píseň se stejným názvem už v databázi je ([`EkklesDataError::Conflict`])
     /// - Píseň nebo její slova nesplňují integritní omezení databáze
     ///
+    /// Píseň je zároveň propojena s normalizovanými autory (viz [`crate::author`]).
+    ///
     /// Pokud během ukládání písně do databáze nastane chyba, je proveden rollback celé písně.
     /// Tedy po chybě by databáze měla být ve stejném stavu jako před zavoláním této funkce.
+    ///
+    /// Při zapnuté kontrole zápisu (viz [`crate::verify`]) se uložená píseň načte zpět
+    /// a pokud se liší, vrací [`EkklesDataError::InvalidData`] (píseň už je uložená).
     pub async fn save_to_db(&self, pool: &SqlitePool) -> Result<i64> {
-        self.check_invariants()
-            .context("Nelze uložit nevalidní píseň")?;
+        span::in_span("song.save", format!("song={:?}", self.title), async move {
+            self.check_invariants()
+                .context("Nelze uložit nevalidní píseň")?;
+
+            let mut transaction = pool
+                .begin()
+                .await
+                .context("Nelze získat připojení k databázi z poolu")?;
+
+            let song_id = self.insert_row(&mut transaction).await?;
+            self.save_contents(song_id, &mut transaction).await?;
+
+            transaction
+                .commit()
+                .await
+                .context("Nelze provést COMMIT uložení písně")?;
+
+            if verify::is_enabled() {
+                self.verify_saved(song_id, pool).await?;
+            }
+
+            Ok(song_id)
+        })
+        .await
+    }
+
+    /// Uloží píseň do databáze, pokud v ní již píseň se stejným názvem je, přepíše ji
+    /// na místě (metadata, části, témata i autory) a zachová její id. Odkazy na píseň
+    /// (playlisty, čísla ve zpěvnících, překlad) tak zůstanou platné.
+    ///
+    /// Vrací id uložené písně. Chyby jsou stejné jako u [`Song::save_to_db()`], vše probíhá
+    /// v jedné transakci, po chybě je databáze ve stejném stavu jako před zavoláním
+    /// (kromě chyby kontroly zápisu, ta nastává až po uložení).
+    pub async fn upsert_to_db(&self, pool: &SqlitePool) -> Result<i64> {
+        span::in_span(
+            "song.upsert",
+            format!("song={:?}", self.title),
+            async move {
+                self.check_invariants()
+                    .context("Nelze uložit nevalidní píseň")?;
+
+                let mut transaction = pool
+                    .begin()
+                    .await
+                    .context("Nelze získat připojení k databázi z poolu")?;
+
+                let existing_id = query!("SELECT id FROM songs WHERE title = $1", self.title)
+                    .fetch_optional(&mut *transaction)
+                    .await
+                    .with_context(|| format!("Nelze vyhledat píseň {} v databázi", self.title))?
+                    .map(|record| record.id.expect("Id je primární klíč, musí být přítomen"));
 
-        let mut transaction = pool
-            .begin()
+                let song_id = match existing_id {
+                    Some(song_id) => {
+                        self.update_row(song_id, &mut transaction).await?;
+                        song_id
+                    }
+                    None => self.insert_row(&mut transaction).await?,
+                };
+                self.save_contents(song_id, &mut transaction).await?;
+
+                transaction
+                    .commit()
+                    .await
+                    .context("Nelze provést COMMIT uložení písně")?;
+
+                if verify::is_enabled() {
+                    self.verify_saved(song_id, pool).await?;
+                }
+
+                Ok(song_id)
+            },
+        )
+        .await
+    }
+
+    /// Načte píseň `song_id` z databáze a porovná ji s touto, viz [`crate::verify`]. Pokud
+    /// se liší, vrací [`EkklesDataError::InvalidData`].
+    pub async fn verify_saved(&self, song_id: i64, pool: &SqlitePool) -> Result<()> {
+        let mut conn = pool
+            .acquire()
             .await
             .context("Nelze získat připojení k databázi z poolu")?;
+        let saved = Self::load_from_db(song_id, &mut conn).await?;
 
+        verify::compare(&format!("Píseň {}", self.title), self, &saved)
+    }
+
+    /// Vloží do tabulky `songs` nový řádek s písní, vrací jeho id
+    async fn insert_row(&self, conn: &mut SqliteConnection) -> Result<i64> {
         let part_order = self.order.join(TAG_SPLIT_STRING);
+        let metadata = &self.metadata;
 
-        let song_id = query!(
+        Ok(query!(
             "
-            INSERT INTO songs (title, author, part_order) VALUES ($1, $2, $3)
+            INSERT INTO songs (title, author, part_order, ccli_number, copyright, song_key, capo)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             ",
             self.title,
             self.author,
-            part_order
+            part_order,
+            metadata.ccli_number,
+            metadata.copyright,
+            metadata.key,
+            metadata.capo
         )
-        .execute(&mut *transaction)
+        .execute(&mut *conn)
         .await
         .context(format!("Nelze uložit píseň {} do databáze", self.title))?
-        .last_insert_rowid();
+        .last_insert_rowid())
+    }
+
+    /// Přepíše řádek písně `song_id` v tabulce `songs` a smaže její části, témata
+    /// a propojení s autory, aby je šlo uložit znovu pomocí [`Song::save_contents()`]
+    async fn update_row(&self, song_id: i64, conn: &mut SqliteConnection) -> Result<()> {
+        let part_order = self.order.join(TAG_SPLIT_STRING);
+        let metadata = &self.metadata;
 
+        query!(
+            "
+            UPDATE songs SET author = $1, part_order = $2, ccli_number = $3, copyright = $4,
+                song_key = $5, capo = $6
+            WHERE id = $7
+            ",
+            self.author,
+            part_order,
+            metadata.ccli_number,
+            metadata.copyright,
+            metadata.key,
+            metadata.capo,
+            song_id
+        )
+        .execute(&mut *conn)
+        .await
+        .with_context(|| format!("Nelze přepsat píseň {} v databázi", self.title))?;
+
+        query!("DELETE FROM song_parts WHERE song_id = $1", song_id)
+            .execute(&mut *conn)
+            .await
+            .with_context(|| format!("Nelze smazat původní části písně {}", self.title))?;
+        query!("DELETE FROM song_topics WHERE song_id = $1", song_id)
+            .execute(&mut *conn)
+            .await
+            .with_context(|| format!("Nelze smazat původní témata písně {}", self.title))?;
+        query!("DELETE FROM song_authors WHERE song_id = $1", song_id)
+            .execute(&mut *conn)
+            .await
+            .with_context(|| format!("Nelze smazat původní autory písně {}", self.title))?;
+
+        Ok(())
+    }
+
+    /// Uloží části, témata a autory písně `song_id`
+    async fn save_contents(&self, song_id: i64, conn: &mut SqliteConnection) -> Result<()> {
         // TODO: Toto by šlo přepsat, abych místo sekvenčního ukládání spojil všechny query
         // do jedné future pomocí `join_all` a na tom awaitnout
         for (tag, lyrics) in self.parts.iter() {
@@ -56,31 +209,63 @@ impl Song {
                 tag,
                 lyrics
             )
-            .execute(&mut *transaction)
+            .execute(&mut *conn)
             .await
             .with_context(|| format!("Nelze uložit část {} písně {}", tag, self.title))?;
         }
 
-        transaction
-            .commit()
+        for topic in self.metadata.topics.iter() {
+            query!(
+                "INSERT OR IGNORE INTO song_topics (song_id, topic) VALUES ($1, $2)",
+                song_id,
+                topic
+            )
+            .execute(&mut *conn)
             .await
-            .context("Nelze provést COMMIT uložení písně")?;
+            .with_context(|| format!("Nelze uložit téma {} písně {}", topic, self.title))?;
+        }
 
-        Ok(song_id)
+        if let Some(author) = &self.author {
+            link_song_authors(song_id, author, conn)
+                .await
+                .with_context(|| format!("Nelze uložit autory písně {}", self.title))?;
+        }
+
+        Ok(())
     }
 
-    /// Pokud píseň s názvem `title` v databázi existuje, vrátí její `id`, pokud se
-    /// vystkytne při přístupu do databáze chyba nebo daná píseň neexistuje, vrátí Error.
+    /// Pokud píseň s názvem `title` v databázi existuje, vrátí její `id`. Pokud daná píseň
+    /// neexistuje, vrátí [`EkklesDataError::NotFound`], pokud se vystkytne při přístupu
+    /// do databáze chyba, vrátí [`EkklesDataError::Db`].
     pub async fn exists_in_db(title: &str, pool: &SqlitePool) -> Result<i64> {
-        query!("SELECT id FROM songs WHERE title = $1", title)
+        Ok(query!("SELECT id FROM songs WHERE title = $1", title)
             .fetch_one(pool)
             .await
-            .with_context(|| format!("Píseň s názvem '{}' nebyla nalezena", title))
-            .map(|record| record.id.unwrap())
+            .with_context(|| format!("Píseň s názvem '{}' nebyla nalezena", title))?
+            .id
+            .unwrap())
     }
 
     /// Smaže píseň s daným `id` z databáze, pokud nastane problém vrátí Error.
+    ///
+    /// Píseň, která je v nějakém playlistu (viz [`Song::referencing_playlists()`]), se nesmaže
+    /// a vrací se [`EkklesDataError::Conflict`] s názvy těchto playlistů, jinak by je nešlo
+    /// načíst. Píseň je nejdříve potřeba z playlistů odebrat.
     pub async fn delete_from_db(id: i64, pool: &SqlitePool) -> Result<()> {
+        let mut conn = pool
+            .acquire()
+            .await
+            .context("Nelze získat připojení k databázi z poolu")?;
+        let playlists = Self::referencing_playlists(id, &mut conn).await?;
+        if !playlists.is_empty() {
+            let names: Vec<&str> = playlists.iter().map(|(_, name)| name.as_str()).collect();
+            return Err(EkklesDataError::Conflict(format!(
+                "Píseň s id {} nelze smazat, je v playlistech: {}",
+                id,
+                names.join(", ")
+            )));
+        }
+
         query!("DELETE FROM songs WHERE id = $1", id)
             .execute(pool)
             .await
@@ -89,15 +274,36 @@ impl Song {
         Ok(())
     }
 
+    /// Vrátí dvojice (id, název) playlistů, ve kterých je píseň s `id`, seřazené podle názvu.
+    /// Pokud se vyskytne při čtení chyba, vrací `Error`.
+    pub async fn referencing_playlists(
+        id: i64,
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> Result<Vec<(i64, String)>> {
+        Ok(query!(
+            r#"SELECT id AS "id!", name FROM playlists
+            WHERE id IN (SELECT playlist_id FROM playlist_songs WHERE song_id = $1)
+            ORDER BY name ASC"#,
+            id
+        )
+        .map(|record| (record.id, record.name))
+        .fetch_all(conn.as_mut())
+        .await
+        .with_context(|| format!("Nelze načíst playlisty obsahující píseň s id {id}"))?)
+    }
+
     /// Načte píseň s `id` z SQLite databáze pomocí `conn`.
     ///
     /// ### Ošetření chyb
     /// Vrátí Error, když:
+    /// - Píseň s `id` neexistuje ([`EkklesDataError::NotFound`])
     /// - Se vyskytnou chyby při čtení z databáze
     /// - Načtená píseň nesplňuje invariant (viz dokumentace [Song])
     pub async fn load_from_db(id: i64, conn: &mut PoolConnection<Sqlite>) -> Result<Self> {
+        span::in_span("song.load", format!("song={id}"), async move {
         let record = query!(
-            "SELECT title, author, part_order FROM songs WHERE id = $1",
+            r#"SELECT title, author, part_order, ccli_number, copyright, song_key, capo AS "capo?: u8"
+            FROM songs WHERE id = $1"#,
             id
         )
         .fetch_one(conn.as_mut())
@@ -124,15 +330,140 @@ impl Song {
         {
             parts.insert(record.tag, record.lyrics);
         }
+        drop(lyrics);
+
+        let topics = query!(
+            "SELECT topic FROM song_topics WHERE song_id = $1 ORDER BY rowid",
+            id
+        )
+        .map(|record| record.topic)
+        .fetch_all(conn.as_mut())
+        .await
+        .context("Nelze načíst témata písně z databáze")?;
 
         let song = Self {
             title,
             author,
             parts,
             order,
+            metadata: SongMetadata {
+                ccli_number: record.ccli_number,
+                copyright: record.copyright,
+                key: record.song_key,
+                capo: record.capo,
+                topics,
+            },
         };
 
-        song.check_invariants().map(|_| song)
+        song.check_invariants()?;
+        Ok(song)
+        })
+        .await
+    }
+
+    /// Načte všechny písně playlistu `playlist_id` i s jejich překlady jako mapu
+    /// id písně -> píseň. Na rozdíl od opakovaného [`Song::load_from_db()`] potřebuje
+    /// pro libovolně dlouhý playlist pouze tři dotazy (písně, jejich části a témata).
+    ///
+    /// ### Ošetření chyb
+    /// Vrátí Error, když:
+    /// - Se vyskytnou chyby při čtení z databáze
+    /// - Některá načtená píseň nesplňuje invariant (viz dokumentace [Song])
+    pub(crate) async fn load_for_playlist(
+        playlist_id: i64,
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> Result<HashMap<i64, PlaylistSong>> {
+        // Písně playlistu a jejich překlady, podmínka je stejná pro všechny tři dotazy
+        let records = query!(
+            r#"SELECT id AS "id!", title, author, part_order, ccli_number, copyright, song_key,
+                capo AS "capo?: u8", theme_id, translated_song_id
+            FROM songs WHERE id IN (
+                SELECT song_id FROM playlist_songs WHERE playlist_id = $1
+                UNION
+                SELECT songs.translated_song_id FROM playlist_songs
+                JOIN songs ON songs.id = playlist_songs.song_id
+                WHERE playlist_songs.playlist_id = $1
+            )"#,
+            playlist_id
+        )
+        .fetch_all(conn.as_mut())
+        .await
+        .with_context(|| format!("Nelze načíst písně playlistu s id {playlist_id}"))?;
+
+        let mut parts: HashMap<i64, HashMap<String, String>> = HashMap::new();
+        let mut part_records = query!(
+            "SELECT song_id, tag, lyrics FROM song_parts WHERE song_id IN (
+                SELECT song_id FROM playlist_songs WHERE playlist_id = $1
+                UNION
+                SELECT songs.translated_song_id FROM playlist_songs
+                JOIN songs ON songs.id = playlist_songs.song_id
+                WHERE playlist_songs.playlist_id = $1
+            )",
+            playlist_id
+        )
+        .fetch(conn.as_mut());
+        while let Some(record) = part_records
+            .try_next()
+            .await
+            .context("Nelze načíst část písně z databáze")?
+        {
+            parts
+                .entry(record.song_id)
+                .or_default()
+                .insert(record.tag, record.lyrics);
+        }
+        drop(part_records);
+
+        let mut topics: HashMap<i64, Vec<String>> = HashMap::new();
+        for record in query!(
+            "SELECT song_id, topic FROM song_topics WHERE song_id IN (
+                SELECT song_id FROM playlist_songs WHERE playlist_id = $1
+                UNION
+                SELECT songs.translated_song_id FROM playlist_songs
+                JOIN songs ON songs.id = playlist_songs.song_id
+                WHERE playlist_songs.playlist_id = $1
+            ) ORDER BY rowid",
+            playlist_id
+        )
+        .fetch_all(conn.as_mut())
+        .await
+        .context("Nelze načíst témata písní z databáze")?
+        {
+            topics.entry(record.song_id).or_default().push(record.topic);
+        }
+
+        records
+            .into_iter()
+            .map(|record| {
+                let song = Self {
+                    title: record.title,
+                    author: record.author,
+                    parts: parts.remove(&record.id).unwrap_or_default(),
+                    order: record
+                        .part_order
+                        .split(TAG_SPLIT_STRING)
+                        .map(|str| str.to_string())
+                        .collect(),
+                    metadata: SongMetadata {
+                        ccli_number: record.ccli_number,
+                        copyright: record.copyright,
+                        key: record.song_key,
+                        capo: record.capo,
+                        topics: topics.remove(&record.id).unwrap_or_default(),
+                    },
+                };
+                song.check_invariants()?;
+
+                Ok((
+                    record.id,
+                    PlaylistSong {
+                        song,
+                        theme_id: record.theme_id,
+                        translated_song_id: record.translated_song_id,
+                    },
+                ))
+            })
+            .collect()
     }
 
     /// Získá vektor dvojic (id, název) všech dostupných písní v databázi. Pokud se vyskytne
@@ -140,7 +471,7 @@ impl Song {
     pub async fn get_available_from_db(
         conn: &mut PoolConnection<Sqlite>,
     ) -> Result<Vec<(i64, String)>> {
-        query!("SELECT id, title FROM songs")
+        Ok(query!("SELECT id, title FROM songs")
             .map(|record| {
                 (
                     record.id.expect("Id je primární klíč, musí být přítomen"),
@@ -149,6 +480,85 @@ impl Song {
             })
             .fetch_all(conn.as_mut())
             .await
-            .context("Nelze načíst seznam písní z databáze")
+            .context("Nelze načíst seznam písní z databáze")?)
+    }
+
+    /// Získá vektor dvojic (id, název) všech písní autora s id `author_id` (viz [`crate::author`]).
+    /// Pokud se vyskytne při čtení chyba, vrací `Error`.
+    pub async fn get_available_by_author_from_db(
+        author_id: i64,
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> Result<Vec<(i64, String)>> {
+        Ok(query!(
+            r#"SELECT id AS "id!", title FROM songs WHERE id IN (SELECT song_id FROM song_authors WHERE author_id = $1)"#,
+            author_id
+        )
+        .map(|record| (record.id, record.title))
+        .fetch_all(conn.as_mut())
+        .await
+        .with_context(|| format!("Nelze načíst seznam písní autora s id {author_id}"))?)
+    }
+
+    /// Vrátí id písně, která je překladem písně `id`, pokud píseň překlad nemá, vrací `None`.
+    pub async fn get_translation_id(
+        id: i64,
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> Result<Option<i64>> {
+        Ok(
+            query!("SELECT translated_song_id FROM songs WHERE id = $1", id)
+                .fetch_one(conn.as_mut())
+                .await
+                .with_context(|| format!("Píseň s id {id} nebyla nalezena"))?
+                .translated_song_id,
+        )
+    }
+
+    /// Nastaví písni `id` překlad `translation_id` (`None` = bez překladu). Části písně
+    /// a překladu se při promítání párují podle tagů. Píseň nemůže být překladem sama sebe,
+    /// v takovém případě vrací [`EkklesDataError::InvalidData`].
+    pub async fn set_translation_id(
+        id: i64,
+        translation_id: Option<i64>,
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> Result<()> {
+        if translation_id == Some(id) {
+            return Err(EkklesDataError::InvalidData(format!(
+                "Píseň s id {id} nemůže být překladem sama sebe"
+            )));
+        }
+
+        query!(
+            "UPDATE songs SET translated_song_id = $1 WHERE id = $2",
+            translation_id,
+            id
+        )
+        .execute(conn.as_mut())
+        .await
+        .with_context(|| format!("Nelze nastavit překlad písni s id {id}"))?;
+
+        Ok(())
+    }
+
+    /// Nastaví písni `id` číslo v licenční databázi CCLI (`None` = neznámé).
+    /// Prázdné číslo (po oříznutí bílých znaků) je považováno za neznámé.
+    pub async fn set_ccli_number(
+        id: i64,
+        ccli_number: Option<&str>,
+        pool: &SqlitePool,
+    ) -> Result<()> {
+        let ccli_number = ccli_number
+            .map(str::trim)
+            .filter(|number| !number.is_empty());
+
+        query!(
+            "UPDATE songs SET ccli_number = $1 WHERE id = $2",
+            ccli_number,
+            id
+        )
+        .execute(pool)
+        .await
+        .with_context(|| format!("Nelze nastavit CCLI číslo písni s id {id}"))?;
+
+        Ok(())
     }
 }
@@ -0,0 +1,218 @@
+//! Porovnání dvou verzí písně pro řešení konfliktů při hromadném importu. Importovaná
+//! píseň se stejným názvem, jako má píseň v databázi, ale s jinými slovy, je konflikt
+//! ([`ImportConflict`]), o kterém rozhodne uživatel (viz [`ConflictResolution`]) podle
+//! rozdílu slov obou verzí ([`diff_lines()`]).
+
+use anyhow::Context;
+use sqlx::SqlitePool;
+
+use crate::{Song, error::Result, span};
+
+/// Řádek rozdílu dvou textů, viz [`diff_lines()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Řádek je v obou textech
+    Same(String),
+    /// Řádek je pouze v původním textu
+    Removed(String),
+    /// Řádek je pouze v novém textu
+    Added(String),
+}
+
+/// Porovná texty `old` a `new` po řádcích (nejdelší společná podposloupnost řádků).
+/// Odebrané řádky jsou vždy před přidanými na stejném místě.
+///
+/// Složitost je kvadratická v počtu řádků, pro slova písní to nevadí.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // common[i][j] = délka nejdelší společné podposloupnosti old[i..] a new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(
+        old[i..]
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string())),
+    );
+    diff.extend(
+        new[j..]
+            .iter()
+            .map(|line| DiffLine::Added(line.to_string())),
+    );
+
+    diff
+}
+
+impl Song {
+    /// Slova písně jako prostý text v pořadí prezentace (viz [`Song::slide_sequence()`]),
+    /// každá část začíná řádkem s tagem `[tag]`. Slouží k porovnání verzí písně.
+    pub fn lyrics_text(&self) -> String {
+        self.slide_sequence()
+            .into_iter()
+            .map(|(tag, lyrics)| format!("[{tag}]\n{}\n", lyrics.unwrap_or_default()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Rozhodnutí uživatele o konfliktu při importu, viz [`ImportConflict::resolve()`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Ponechá píseň v databázi, importovaná se zahodí
+    #[default]
+    KeepMine,
+    /// Přepíše píseň v databázi importovanou (na místě, zůstane v playlistech)
+    TakeTheirs,
+    /// Ponechá obě, importovaná se uloží s číslem za názvem (např. "Píseň (2)")
+    KeepBoth,
+}
+
+/// Importovaná píseň, která má stejný název jako píseň v databázi, ale jiná slova
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportConflict {
+    /// Id písně v databázi
+    pub existing_id: i64,
+    /// Píseň v databázi
+    pub existing: Song,
+    /// Importovaná píseň
+    pub incoming: Song,
+}
+
+/// Výsledek kontroly importované písně, viz [`check_import()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportCheck {
+    /// Píseň s tímto názvem v databázi není, lze ji rovnou uložit
+    New(Box<Song>),
+    /// V databázi je píseň se stejným názvem i slovy (pod daným id), není co importovat
+    Identical(i64),
+    /// V databázi je píseň se stejným názvem, ale jinými slovy
+    Conflict(Box<ImportConflict>),
+}
+
+/// Zkontroluje, zda importovaná píseň `incoming` koliduje s písní se stejným názvem
+/// v databázi. Za stejnou se považuje píseň se stejnými částmi a pořadím, rozdíly
+/// v metadatech (autor, CCLI, ...) konflikt nejsou.
+pub async fn check_import(incoming: Song, pool: &SqlitePool) -> Result<ImportCheck> {
+    span::in_span(
+        "song.import_check",
+        format!("song={:?}", incoming.title),
+        async move {
+            let existing_id = match Song::exists_in_db(&incoming.title, pool).await {
+                Ok(id) => id,
+                Err(e) if e.is_not_found() => return Ok(ImportCheck::New(Box::new(incoming))),
+                Err(e) => return Err(e),
+            };
+
+            let mut conn = pool
+                .acquire()
+                .await
+                .context("Nelze získat připojení k databázi z poolu")?;
+            let existing = Song::load_from_db(existing_id, &mut conn).await?;
+
+            if existing.parts == incoming.parts && existing.order == incoming.order {
+                Ok(ImportCheck::Identical(existing_id))
+            } else {
+                Ok(ImportCheck::Conflict(Box::new(ImportConflict {
+                    existing_id,
+                    existing,
+                    incoming,
+                })))
+            }
+        },
+    )
+    .await
+}
+
+impl ImportConflict {
+    /// Rozdíl slov písně v databázi a importované písně, viz [`Song::lyrics_text()`]
+    pub fn diff(&self) -> Vec<DiffLine> {
+        diff_lines(&self.existing.lyrics_text(), &self.incoming.lyrics_text())
+    }
+
+    /// Provede rozhodnutí `resolution` o konfliktu, vrací id písně, která po rozhodnutí
+    /// obsahuje importovanou verzi (u [`ConflictResolution::KeepMine`] id písně v databázi).
+    pub async fn resolve(&self, resolution: ConflictResolution, pool: &SqlitePool) -> Result<i64> {
+        match resolution {
+            ConflictResolution::KeepMine => Ok(self.existing_id),
+            ConflictResolution::TakeTheirs => self.incoming.upsert_to_db(pool).await,
+            ConflictResolution::KeepBoth => {
+                let mut number = 2;
+                let title = loop {
+                    let title = format!("{} ({number})", self.incoming.title);
+                    match Song::exists_in_db(&title, pool).await {
+                        Ok(_) => number += 1,
+                        Err(e) if e.is_not_found() => break title,
+                        Err(e) => return Err(e),
+                    }
+                };
+
+                Song {
+                    title,
+                    ..self.incoming.clone()
+                }
+                .save_to_db(pool)
+                .await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn lines_are_diffed() {
+        let old = "[V1]\nChválím Tě, Pane\nz celého srdce\n\n[C]\nHaleluja";
+        let new = "[V1]\nChválím Tě, Bože\nz celého srdce\n\n[C]\nHaleluja\nAmen";
+
+        assert_eq!(
+            diff_lines(old, new),
+            vec![
+                DiffLine::Same(String::from("[V1]")),
+                DiffLine::Removed(String::from("Chválím Tě, Pane")),
+                DiffLine::Added(String::from("Chválím Tě, Bože")),
+                DiffLine::Same(String::from("z celého srdce")),
+                DiffLine::Same(String::new()),
+                DiffLine::Same(String::from("[C]")),
+                DiffLine::Same(String::from("Haleluja")),
+                DiffLine::Added(String::from("Amen")),
+            ]
+        );
+        assert_eq!(
+            diff_lines("", "Nový"),
+            vec![DiffLine::Added(String::from("Nový"))]
+        );
+        assert_eq!(
+            diff_lines("Starý", ""),
+            vec![DiffLine::Removed(String::from("Starý"))]
+        );
+    }
+}
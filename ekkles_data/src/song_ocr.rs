@@ -0,0 +1,187 @@
+//! Modul pro rozpoznání slov písně z naskenované stránky zpěvníku (obrázek nebo PDF)
+//! pomocí OCR. Rozpoznaný text se očistí od typických chyb OCR (viz [`clean_ocr_text()`])
+//! a dál se zpracuje stejně jako vložený prostý text (viz [`crate::song_text`]).
+//!
+//! Text rozpoznává program `tesseract`, stránky PDF se na obrázky převádí programem
+//! `pdftoppm` (z balíku poppler), oba musí být nainstalované v systému. Spuštění programů
+//! blokuje, funkce tohoto modulu by se proto měly volat mimo vlákno GUI.
+
+use anyhow::{Context, Result, bail};
+use lazy_static::lazy_static;
+use log::{debug, warn};
+use regex::Regex;
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Jazyky, ve kterých tesseract rozpoznává text (čeština a angličtina)
+pub const OCR_LANGUAGES: &str = "ces+eng";
+/// Přípony souborů, ze kterých lze text rozpoznat
+pub const OCR_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "tif", "tiff", "pdf"];
+const TESSERACT_PROGRAM: &str = "tesseract";
+const PDFTOPPM_PROGRAM: &str = "pdftoppm";
+/// Rozlišení, ve kterém se stránky PDF převádí na obrázky, tesseractu nejlépe vyhovuje 300 DPI
+const PDF_DPI: &str = "300";
+
+/// Pořadové číslo převodu PDF, aby se dočasné složky souběžných převodů nepřekrývaly
+static PDF_CONVERSIONS: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    /// Matchne posloupnost mezer a tabulátorů uvnitř řádku
+    static ref SPACES_REGEX: Regex = Regex::new(r"[ \t]+").unwrap();
+}
+
+/// Rozpozná text souboru `path`, obrázku nebo PDF (podle přípony, u PDF všech stránek),
+/// a očistí jej pomocí [`clean_ocr_text()`]. Pokud nelze spustit `tesseract` (`pdftoppm`)
+/// nebo skončí s chybou, vrací Error.
+pub fn recognize_file(path: &Path) -> Result<String> {
+    let is_pdf = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"));
+
+    let text = if is_pdf {
+        recognize_pdf(path)?
+    } else {
+        recognize_image(path)?
+    };
+
+    Ok(clean_ocr_text(&text))
+}
+
+/// Očistí text rozpoznaný OCR tak, aby šel rozdělit na části písně:
+/// - Konec stránky (form feed) je oddělovač části, stejně jako prázdný řádek
+/// - Ligatury (`ﬁ`, `ﬂ`) se rozloží na jednotlivá písmena
+/// - Více mezer za sebou se sloučí do jedné, řádky se oříznou
+/// - Řádky bez písmen a číslic (šum z notových osnov, okrajů stránky) a řádky tvořené
+///   pouze číslem (čísla stránek) se vynechají
+/// - Více prázdných řádků za sebou se sloučí do jednoho, na začátku a konci textu se vynechají
+pub fn clean_ocr_text(text: &str) -> String {
+    let text = text
+        .replace('\u{c}', "\n\n")
+        .replace('ﬁ', "fi")
+        .replace('ﬂ', "fl");
+
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = SPACES_REGEX.replace_all(line.trim(), " ");
+        let is_noise = !line.is_empty() && !line.chars().any(char::is_alphanumeric);
+        let is_page_number = !line.is_empty() && line.chars().all(|c| c.is_ascii_digit());
+        if is_noise || is_page_number {
+            continue;
+        }
+
+        if line.is_empty() && lines.last().is_none_or(String::is_empty) {
+            continue;
+        }
+        lines.push(line.into_owned());
+    }
+
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+
+    lines.join("\n")
+}
+
+/// Rozpozná text obrázku `path` programem `tesseract`
+fn recognize_image(path: &Path) -> Result<String> {
+    debug!("Rozpoznávám text obrázku {path:?}");
+    let output = Command::new(TESSERACT_PROGRAM)
+        .arg(path)
+        .arg("stdout")
+        .args(["-l", OCR_LANGUAGES])
+        .output()
+        .with_context(|| format!("Nelze spustit {TESSERACT_PROGRAM}, je nainstalovaný?"))?;
+
+    if !output.status.success() {
+        bail!(
+            "{TESSERACT_PROGRAM} skončil s chybou ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    String::from_utf8(output.stdout).context("Rozpoznaný text není platné UTF-8")
+}
+
+/// Převede stránky PDF `path` na obrázky do dočasné složky a rozpozná jejich text,
+/// stránky jsou v textu odděleny prázdným řádkem
+fn recognize_pdf(path: &Path) -> Result<String> {
+    let dir = env::temp_dir().join(format!(
+        "ekkles-ocr-{}-{}",
+        std::process::id(),
+        PDF_CONVERSIONS.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(&dir).context("Nelze vytvořit dočasnou složku pro stránky PDF")?;
+
+    let text = recognize_pdf_pages(path, &dir);
+    if let Err(e) = fs::remove_dir_all(&dir) {
+        warn!("Nelze smazat dočasnou složku {dir:?}: {e}");
+    }
+
+    text
+}
+
+fn recognize_pdf_pages(path: &Path, dir: &Path) -> Result<String> {
+    debug!("Převádím stránky PDF {path:?} na obrázky");
+    let status = Command::new(PDFTOPPM_PROGRAM)
+        .args(["-r", PDF_DPI, "-png"])
+        .arg(path)
+        .arg(dir.join("page"))
+        .status()
+        .with_context(|| format!("Nelze spustit {PDFTOPPM_PROGRAM}, je nainstalovaný?"))?;
+
+    if !status.success() {
+        bail!("{PDFTOPPM_PROGRAM} skončil s chybou ({status})");
+    }
+
+    // Čísla stránek v názvech souborů jsou doplněná nulami, stačí seřadit podle názvu
+    let mut pages = fs::read_dir(dir)
+        .context("Nelze přečíst převedené stránky PDF")?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()
+        .context("Nelze přečíst převedené stránky PDF")?;
+    pages.sort();
+
+    if pages.is_empty() {
+        bail!("PDF {path:?} nemá žádné stránky");
+    }
+
+    let pages = pages
+        .iter()
+        .map(|page| recognize_image(page))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(pages.join("\n\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn noise_and_page_numbers_removed() {
+        let text = "  124\n\nPán   je můj  pastýř,\n| — ~\nnic mi ne-\nchybí\n\n\n\n2. Na pastvách\n\u{c}125\nRefrén:  Haleluja\n\n";
+
+        assert_eq!(
+            clean_ocr_text(text),
+            "Pán je můj pastýř,\nnic mi ne-\nchybí\n\n2. Na pastvách\n\nRefrén: Haleluja"
+        );
+    }
+
+    #[test]
+    fn ligatures_split() {
+        assert_eq!(clean_ocr_text("ﬁnal ﬂow"), "final flow");
+    }
+
+    #[test]
+    fn missing_file_is_error() {
+        let path = env::temp_dir().join(format!("ekkles-ocr-missing-{}.png", std::process::id()));
+
+        assert!(recognize_file(&path).is_err());
+    }
+}
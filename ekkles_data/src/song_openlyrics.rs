@@ -0,0 +1,355 @@
+//! Modul pro parsování písní ve formátu [OpenLyrics](https://docs.openlyrics.org/)
+//! (XML, který používá např. [OpenLP](https://openlp.org/)) do formátu používaného Ekklesem.
+//!
+//! ### Mapování
+//! - `<title>` - název písně (použije se první, povinný)
+//! - `<author>` - autoři, spojí se do jednoho autora písně
+//! - `<verse name="v1">` - část písně, tag vznikne z názvu převodem na velká písmena
+//!   (`v1` → `V1`, `c` → `C`), viz [`PartTag`]
+//! - `<verseOrder>` - pořadí částí, pokud chybí, použije se pořadí zapsaných částí
+//! - `<ccliNo>`, `<copyright>`, `<key>`, `<themes><theme>` - doplňující údaje, viz [`SongMetadata`]
+//! - `<songbook name="EZ" entry="452"/>` - číslo ve zpěvníku, viz [`hymn_number_from_openlyrics()`]
+//!
+//! Řádky jsou odděleny elementem `<br/>` (verze 0.8+) nebo jsou v elementech `<line>`
+//! (verze 0.7). Akordy a komentáře jsou ze slov odstraněny. Pokud je píseň ve více
+//! jazycích (více částí se stejným názvem), použije se první z nich.
+
+use std::{collections::HashMap, fs::read_to_string, path::Path};
+
+use anyhow::{Context, Result, bail};
+use log::warn;
+use roxmltree::{Document, Node};
+
+use crate::{PartTag, Song, SongMetadata};
+
+/// Najde první element s názvem `name` (bez ohledu na jmenný prostor)
+fn find_elem<'a, 'input>(document: &'a Document<'input>, name: &str) -> Option<Node<'a, 'input>> {
+    document
+        .descendants()
+        .find(|node| node.is_element() && node.tag_name().name() == name)
+}
+
+/// Převede název části písně z OpenLyrics (např. `v1`, `c`) na tag Ekklesu (`V1`, `C`)
+fn part_tag(verse_name: &str) -> PartTag {
+    verse_name.trim().to_uppercase()
+}
+
+/// Posbírá text elementu `node` do `out`, `<br/>` a konec `<line>` převede na nový řádek,
+/// komentáře přeskočí, z ostatních elementů (akordy, formátování) vezme pouze jejich text
+fn collect_text(node: Node, out: &mut String) {
+    for child in node.children() {
+        if child.is_text() {
+            // Zalomení řádků v samotném textu nejsou významná (slouží jen k formátování XML)
+            out.push_str(&child.text().unwrap_or_default().replace(['\n', '\r'], " "));
+            continue;
+        }
+
+        match child.tag_name().name() {
+            "br" => out.push('\n'),
+            "comment" => {}
+            "line" => {
+                collect_text(child, out);
+                out.push('\n');
+            }
+            _ => collect_text(child, out),
+        }
+    }
+}
+
+/// Vrátí slova části písně `verse`, řádky s normalizovanými mezerami, bez prázdných řádků
+fn verse_lyrics(verse: Node) -> String {
+    let mut raw = String::new();
+    for lines in verse
+        .children()
+        .filter(|node| node.is_element() && node.tag_name().name() == "lines")
+    {
+        collect_text(lines, &mut raw);
+        raw.push('\n');
+    }
+
+    raw.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Song {
+    /// Zparsuje píseň ve formátu OpenLyrics ze souboru `file`.
+    /// Pokud se vše zdaří, vrátí načtenou píseň, jinak vrací Error.
+    ///
+    /// Více informací o způsobu parsování viz [`Song::parse_from_openlyrics()`]
+    pub fn parse_from_openlyrics_file(file: &Path) -> Result<Self> {
+        let xml = read_to_string(file)
+            .with_context(|| format!("Nepodařilo se přečíst soubor {}", file.display()))?;
+
+        Song::parse_from_openlyrics(&xml)
+            .with_context(|| format!("Nepodařilo se zparsovat soubor {}", file.display()))
+    }
+
+    /// Zparsuje píseň `xml` ve formátu OpenLyrics, viz dokumentace modulu.
+    /// Píseň musí mít název a alespoň jednu neprázdnou část, jinak vrací Error.
+    /// Tagy v `<verseOrder>`, ke kterým neexistuje žádná část, jsou vynechány.
+    pub fn parse_from_openlyrics(xml: &str) -> Result<Self> {
+        let document = Document::parse(xml).context("Nelze zparsovat XML")?;
+
+        let title = find_elem(&document, "title")
+            .and_then(|elem| elem.text())
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .context("Píseň musí mít název")?
+            .to_string();
+
+        let mut authors: Vec<String> = Vec::new();
+        for author in document
+            .descendants()
+            .filter(|node| node.is_element() && node.tag_name().name() == "author")
+            .filter_map(|elem| elem.text())
+            .map(str::trim)
+            .filter(|author| !author.is_empty())
+        {
+            if !authors.iter().any(|existing| existing == author) {
+                authors.push(author.to_string());
+            }
+        }
+
+        let mut parts = HashMap::new();
+        let mut written_order = Vec::new();
+        for verse in document
+            .descendants()
+            .filter(|node| node.is_element() && node.tag_name().name() == "verse")
+        {
+            let Some(name) = verse.attribute("name") else {
+                continue;
+            };
+            let tag = part_tag(name);
+            // Další jazykové verze téže části přeskočíme
+            if tag.is_empty() || parts.contains_key(&tag) {
+                continue;
+            }
+
+            let lyrics = verse_lyrics(verse);
+            if !lyrics.is_empty() {
+                written_order.push(tag.clone());
+                parts.insert(tag, lyrics);
+            }
+        }
+
+        if parts.is_empty() {
+            bail!("Nepodařilo se extrahovat slova z písně {title}");
+        }
+
+        let order = match find_elem(&document, "verseOrder").and_then(|elem| elem.text()) {
+            Some(order) if !order.trim().is_empty() => order
+                .split_whitespace()
+                .map(part_tag)
+                .filter(|tag| {
+                    let exists = parts.contains_key(tag);
+                    if !exists {
+                        warn!("Píseň {title} má v pořadí neexistující část {tag}, vynechávám ji");
+                    }
+                    exists
+                })
+                .collect(),
+            _ => written_order,
+        };
+
+        let elem_text = |name: &str| {
+            SongMetadata::non_empty(find_elem(&document, name).and_then(|elem| elem.text()))
+        };
+        let mut metadata = SongMetadata {
+            ccli_number: elem_text("ccliNo"),
+            copyright: elem_text("copyright"),
+            key: elem_text("key"),
+            capo: None,
+            topics: Vec::new(),
+        };
+        for theme in document
+            .descendants()
+            .filter(|node| node.is_element() && node.tag_name().name() == "theme")
+            .filter_map(|elem| elem.text())
+        {
+            metadata.add_topics(theme);
+        }
+
+        Ok(Self {
+            title,
+            author: (!authors.is_empty()).then(|| authors.join(", ")),
+            parts,
+            order,
+            metadata,
+        })
+    }
+}
+
+/// Vytáhne z dokumentu písně `xml` ve formátu OpenLyrics obsah elementu `ccliNo`
+/// (číslo písně v licenční databázi CCLI). Pokud element chybí nebo je prázdný,
+/// vrací `Ok(None)`, pokud nelze XML zparsovat, vrací Error.
+pub fn ccli_number_from_openlyrics(xml: &str) -> Result<Option<String>> {
+    let document = Document::parse(xml).context("Nelze zparsovat XML")?;
+
+    Ok(find_elem(&document, "ccliNo")
+        .and_then(|elem| elem.text())
+        .map(str::trim)
+        .filter(|number| !number.is_empty())
+        .map(str::to_string))
+}
+
+/// Vytáhne z dokumentu písně `xml` ve formátu OpenLyrics číslo písně v prvním zpěvníku
+/// (`<songbook name="EZ" entry="452"/>`) ve tvaru "EZ 452", dále jej lze zpracovat pomocí
+/// [`crate::songbook::SongbookNumber::parse()`]. Pokud zpěvník nemá číslo, vrací `Ok(None)`,
+/// pokud nelze XML zparsovat, vrací Error.
+pub fn hymn_number_from_openlyrics(xml: &str) -> Result<Option<String>> {
+    let document = Document::parse(xml).context("Nelze zparsovat XML")?;
+
+    let Some(songbook) = find_elem(&document, "songbook") else {
+        return Ok(None);
+    };
+    let Some(entry) = songbook
+        .attribute("entry")
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(match songbook.attribute("name").map(str::trim) {
+        Some(name) if !name.is_empty() => format!("{name} {entry}"),
+        _ => entry.to_string(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const AMAZING_GRACE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<song xmlns="http://openlyrics.info/namespace/2009/song" version="0.8" createdIn="OpenLP 2.4">
+  <properties>
+    <titles>
+      <title>Amazing Grace</title>
+      <title lang="cs">Úžasná milost</title>
+    </titles>
+    <authors>
+      <author>John Newton</author>
+      <author type="music">William Walker</author>
+    </authors>
+    <verseOrder>v1 c v2 c x9</verseOrder>
+    <ccliNo> 22025 </ccliNo>
+    <copyright>Public Domain</copyright>
+    <key>G</key>
+    <themes>
+      <theme>Milost</theme>
+      <theme lang="en">Grace</theme>
+    </themes>
+    <songbooks>
+      <songbook name="EZ" entry="452"/>
+    </songbooks>
+  </properties>
+  <lyrics>
+    <verse name="v1" lang="en">
+      <lines><chord name="G"/>Amazing grace how <chord name="C"/>sweet the sound<br/>
+        that saved a wretch like me<comment>pomalu</comment></lines>
+    </verse>
+    <verse name="v1" lang="cs">
+      <lines>Úžasná milost</lines>
+    </verse>
+    <verse name="c">
+      <lines>Refrén</lines>
+      <lines>druhý slajd refrénu</lines>
+    </verse>
+    <verse name="v2">
+      <lines>Second verse</lines>
+    </verse>
+  </lyrics>
+</song>"#;
+
+    #[test]
+    fn verses_and_order() {
+        let song = Song::parse_from_openlyrics(AMAZING_GRACE).unwrap();
+
+        assert_eq!(song.title, "Amazing Grace");
+        assert_eq!(song.author.as_deref(), Some("John Newton, William Walker"));
+        assert_eq!(song.order, vec!["V1", "C", "V2", "C"]);
+        assert_eq!(
+            song.parts,
+            HashMap::from([
+                (
+                    String::from("V1"),
+                    String::from("Amazing grace how sweet the sound\nthat saved a wretch like me")
+                ),
+                (
+                    String::from("C"),
+                    String::from("Refrén\ndruhý slajd refrénu")
+                ),
+                (String::from("V2"), String::from("Second verse")),
+            ])
+        );
+        assert!(song.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn old_format_without_order() {
+        let xml = r#"<song><properties><titles><title>Stará</title></titles></properties>
+<lyrics>
+  <verse name="v1"><lines><line>První řádek</line><line>Druhý řádek</line></lines></verse>
+  <verse name="b"><lines><line>Most</line></lines></verse>
+</lyrics></song>"#;
+
+        let song = Song::parse_from_openlyrics(xml).unwrap();
+
+        assert_eq!(song.author, None);
+        assert_eq!(song.order, vec!["V1", "B"]);
+        assert_eq!(song.parts["V1"], "První řádek\nDruhý řádek");
+    }
+
+    #[test]
+    fn missing_title_or_lyrics() {
+        assert!(
+            Song::parse_from_openlyrics(
+                "<song><lyrics><verse name=\"v1\"><lines>a</lines></verse></lyrics></song>"
+            )
+            .is_err()
+        );
+        assert!(
+            Song::parse_from_openlyrics(
+                "<song><properties><titles><title>A</title></titles></properties></song>"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn ccli_and_hymn_number() {
+        assert_eq!(
+            ccli_number_from_openlyrics(AMAZING_GRACE)
+                .unwrap()
+                .as_deref(),
+            Some("22025")
+        );
+        assert_eq!(
+            hymn_number_from_openlyrics(AMAZING_GRACE)
+                .unwrap()
+                .as_deref(),
+            Some("EZ 452")
+        );
+        assert_eq!(hymn_number_from_openlyrics("<song/>").unwrap(), None);
+    }
+
+    #[test]
+    fn metadata() {
+        let song = Song::parse_from_openlyrics(AMAZING_GRACE).unwrap();
+
+        assert_eq!(
+            song.metadata,
+            SongMetadata {
+                ccli_number: Some(String::from("22025")),
+                copyright: Some(String::from("Public Domain")),
+                key: Some(String::from("G")),
+                capo: None,
+                topics: vec![String::from("Milost"), String::from("Grace")],
+            }
+        );
+    }
+}
@@ -0,0 +1,378 @@
+//! Modul pro vytvoření písně z prostého textu (typicky zkopírovaného z webu nebo e-mailu).
+//! Text se heuristicky rozdělí na části a navrhne se pořadí, které lze před uložením
+//! písně upravit.
+//!
+//! ### Heuristiky
+//! - Části jsou od sebe odděleny prázdným řádkem
+//! - Část začínající značkou `Refrén:`, `Ref:`, `R:`, `Chorus:` nebo `C:` je refrén (`C`),
+//!   značka `Bridge:`, `Most:` nebo `B:` označuje bridge (`B`)
+//! - Část tvořená pouze značkou (např. `Refrén`) je opakování posledního refrénu/bridge
+//! - Část začínající číslem (`2.` nebo `2)`) je sloka s tímto číslem
+//! - Část se stejnými slovy jako některá předchozí část je jejím opakováním
+//! - Ostatní části jsou sloky číslované postupně (`V1`, `V2`, ...)
+//!
+//! Slova lze získat i z HTML stránky se slovy písně, viz [`lyrics_from_html()`].
+
+use anyhow::{Result, bail};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::{PartTag, Song};
+
+/// Tag prvního refrénu, další refrény dostanou číselnou příponu
+const CHORUS_TAG: &str = "C";
+/// Tag prvního bridge, další bridge dostanou číselnou příponu
+const BRIDGE_TAG: &str = "B";
+/// Předpona tagu sloky, za ní následuje číslo sloky
+const VERSE_TAG_PREFIX: &str = "V";
+
+lazy_static! {
+    /// Matchne značku refrénu/bridge na prvním řádku části, značku uloží do capture grupy
+    /// `marker` a případná slova za dvojtečkou (tečkou) do capture grupy `rest`.
+    static ref MARKER_REGEX: Regex = Regex::new(
+        r"(?i)^\s*(?P<marker>refrén|refren|chorus|ref|r|c|bridge|most|b)\s*(?:[:.]+\s*(?P<rest>.*))?$"
+    )
+    .unwrap();
+    /// Matchne číslo sloky na začátku prvního řádku části, číslo uloží do capture grupy
+    /// `number` a slova za ním do capture grupy `rest`.
+    static ref VERSE_NUMBER_REGEX: Regex =
+        Regex::new(r"^\s*(?P<number>\d+)\s*[.)]\s*(?P<rest>.*)$").unwrap();
+    /// Matchne elementy HTML, jejichž obsah nejsou slova stránky
+    static ref HTML_SKIPPED_REGEX: Regex = Regex::new(
+        r"(?is)<!--.*?-->|<head\b.*?</head>|<script\b.*?</script>|<style\b.*?</style>"
+    )
+    .unwrap();
+    /// Matchne element `<pre>`, jeho obsah uloží do capture grupy `content`
+    static ref HTML_PRE_REGEX: Regex =
+        Regex::new(r"(?is)<pre\b[^>]*>(?P<content>.*?)</pre>").unwrap();
+    /// Matchne konec odstavce, ten odděluje části písně
+    static ref HTML_PARAGRAPH_REGEX: Regex = Regex::new(r"(?i)</p\s*>").unwrap();
+    /// Matchne zalomení řádku nebo hranici blokového elementu
+    static ref HTML_LINE_BREAK_REGEX: Regex =
+        Regex::new(r"(?i)<br\s*/?>|</?(?:div|h[1-6]|li|tr|table|section|article)\b[^>]*>").unwrap();
+    /// Matchne libovolný (zbylý) tag
+    static ref HTML_TAG_REGEX: Regex = Regex::new(r"(?s)<[^>]*>").unwrap();
+    /// Matchne HTML entitu, její název (nebo číslo) uloží do capture grupy `entity`
+    static ref HTML_ENTITY_REGEX: Regex =
+        Regex::new(r"&(?P<entity>#[xX][0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);").unwrap();
+}
+
+/// Druh části určený podle jejího prvního řádku
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PartKind {
+    Chorus,
+    Bridge,
+    /// Sloka, případně s číslem uvedeným v textu
+    Verse(Option<u32>),
+}
+
+/// Návrh rozdělení písně na části, viz [dokumentace modulu](self)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposedSong {
+    /// Části písně v pořadí jejich prvního výskytu jako dvojice (tag, slova)
+    pub parts: Vec<(PartTag, String)>,
+    /// Navržené pořadí částí, tagy se mohou opakovat
+    pub order: Vec<PartTag>,
+}
+
+impl ProposedSong {
+    /// Rozdělí prostý text `text` na části a navrhne jejich pořadí podle heuristik
+    /// popsaných v [dokumentaci modulu](self).
+    pub fn from_text(text: &str) -> Self {
+        let normalized = text.replace("\r\n", "\n");
+
+        let mut parts: Vec<(PartTag, String)> = Vec::new();
+        let mut order = Vec::new();
+        let mut next_verse = 1;
+        let mut last_chorus: Option<PartTag> = None;
+        let mut last_bridge: Option<PartTag> = None;
+
+        for block in split_blocks(&normalized) {
+            let (kind, lyrics) = classify_block(&block);
+
+            if lyrics.is_empty() {
+                // Pouze značka, jde o opakování poslední části daného druhu
+                let repeated = match kind {
+                    PartKind::Chorus => last_chorus.clone(),
+                    PartKind::Bridge => last_bridge.clone(),
+                    PartKind::Verse(_) => None,
+                };
+                order.extend(repeated);
+                continue;
+            }
+
+            let tag = match parts.iter().find(|(_, existing)| *existing == lyrics) {
+                Some((tag, _)) => tag.clone(),
+                None => {
+                    let tag = match kind {
+                        PartKind::Chorus => unique_tag(CHORUS_TAG, &parts),
+                        PartKind::Bridge => unique_tag(BRIDGE_TAG, &parts),
+                        PartKind::Verse(number) => {
+                            let number = number.unwrap_or(next_verse);
+                            next_verse = number + 1;
+                            unique_tag(&format!("{VERSE_TAG_PREFIX}{number}"), &parts)
+                        }
+                    };
+                    parts.push((tag.clone(), lyrics));
+                    tag
+                }
+            };
+
+            match kind {
+                PartKind::Chorus => last_chorus = Some(tag.clone()),
+                PartKind::Bridge => last_bridge = Some(tag.clone()),
+                PartKind::Verse(_) => {}
+            }
+            order.push(tag);
+        }
+
+        Self { parts, order }
+    }
+
+    /// Sestaví z (případně upraveného) návrhu píseň s názvem `title` a autorem `author`.
+    /// Pokud je název prázdný, tagy se opakují nebo píseň nesplňuje invarianty
+    /// (viz [`Song`]), vrací Error s popisem chyby.
+    pub fn into_song(self, title: String, author: Option<String>) -> Result<Song> {
+        if title.trim().is_empty() {
+            bail!("Píseň musí mít název");
+        }
+
+        let mut parts = HashMap::new();
+        for (tag, lyrics) in self.parts {
+            if parts.insert(tag.clone(), lyrics).is_some() {
+                bail!("Tag '{tag}' je u více částí písně");
+            }
+        }
+
+        let song = Song {
+            title,
+            author,
+            parts,
+            order: self.order,
+            metadata: Default::default(),
+        };
+
+        song.check_invariants().map(|_| song)
+    }
+}
+
+/// Vytáhne ze zdrojového kódu HTML stránky `html` její text tak, aby jej šlo dále
+/// zpracovat pomocí [`ProposedSong::from_text()`]. Pokud stránka obsahuje elementy
+/// `<pre>` (weby se slovy je často používají pro slova s akordy), vezme se pouze jejich obsah.
+/// Odstavce jsou odděleny prázdným řádkem, zalomení řádků a blokové elementy novým řádkem.
+///
+/// Nejde o plnohodnotný HTML parser, výsledek je pouze návrh, který si musí uživatel zkontrolovat.
+pub fn lyrics_from_html(html: &str) -> String {
+    let html = HTML_SKIPPED_REGEX.replace_all(html, "");
+
+    let pre_blocks: Vec<_> = HTML_PRE_REGEX
+        .captures_iter(&html)
+        .map(|captures| captures["content"].to_string())
+        .collect();
+    let html = if pre_blocks.is_empty() {
+        html.replace(['\r', '\n'], " ")
+    } else {
+        pre_blocks.join("\n\n").replace("\r\n", "\n")
+    };
+
+    let text = HTML_PARAGRAPH_REGEX.replace_all(&html, "\n\n");
+    let text = HTML_LINE_BREAK_REGEX.replace_all(&text, "\n");
+    let text = HTML_TAG_REGEX.replace_all(&text, "");
+    let text = HTML_ENTITY_REGEX.replace_all(&text, |captures: &regex::Captures| {
+        decode_entity(&captures["entity"]).unwrap_or_else(|| captures[0].to_string())
+    });
+
+    text.lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Dekóduje HTML entitu (bez `&` a `;`), pokud ji nezná, vrací `None`
+fn decode_entity(entity: &str) -> Option<String> {
+    let decoded = match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        _ => {
+            let code = match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => entity.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)?
+        }
+    };
+
+    Some(decoded.to_string())
+}
+
+/// Rozdělí text na bloky řádků oddělené (alespoň jedním) prázdným řádkem
+fn split_blocks(text: &str) -> Vec<Vec<&str>> {
+    let mut blocks = Vec::new();
+    let mut current = Vec::new();
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(line);
+        }
+    }
+
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+
+    blocks
+}
+
+/// Určí druh části podle jejího prvního řádku a vrátí jej spolu se slovy části
+/// (bez značky nebo čísla sloky)
+fn classify_block(block: &[&str]) -> (PartKind, String) {
+    let (first, rest) = block
+        .split_first()
+        .expect("Blok z `split_blocks()` nikdy není prázdný");
+
+    let (kind, first_line) = if let Some(captures) = MARKER_REGEX.captures(first) {
+        let kind = match captures["marker"].to_lowercase().as_str() {
+            "bridge" | "most" | "b" => PartKind::Bridge,
+            _ => PartKind::Chorus,
+        };
+        (kind, captures.name("rest").map_or("", |rest| rest.as_str()))
+    } else if let Some(captures) = VERSE_NUMBER_REGEX.captures(first) {
+        let number = captures["number"].parse().ok();
+        (
+            PartKind::Verse(number),
+            captures.name("rest").map_or("", |rest| rest.as_str()),
+        )
+    } else {
+        (PartKind::Verse(None), *first)
+    };
+
+    let lyrics = std::iter::once(first_line)
+        .chain(rest.iter().copied())
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    (kind, lyrics)
+}
+
+/// Vrátí `base`, pokud jej ještě žádná část nemá, jinak `base` s nejmenší volnou číselnou
+/// příponou od 2
+fn unique_tag(base: &str, parts: &[(PartTag, String)]) -> PartTag {
+    let is_free = |candidate: &str| parts.iter().all(|(tag, _)| tag != candidate);
+
+    if is_free(base) {
+        return base.to_string();
+    }
+
+    (2..)
+        .map(|suffix| format!("{base}{suffix}"))
+        .find(|candidate| is_free(candidate))
+        .expect("Nekonečný iterátor vždy najde volný tag")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn tags(tags: &[&str]) -> Vec<PartTag> {
+        tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    #[test]
+    fn blank_lines_and_markers() {
+        let text = "Prvni sloka\nradek dva\n\nRefrén: Haleluja\nchvalme Pána\n\n\n2. Druha sloka\nradek dva\n\nRefrén\n";
+
+        let proposed = ProposedSong::from_text(text);
+
+        assert_eq!(
+            proposed.parts,
+            vec![
+                (String::from("V1"), String::from("Prvni sloka\nradek dva")),
+                (String::from("C"), String::from("Haleluja\nchvalme Pána")),
+                (String::from("V2"), String::from("Druha sloka\nradek dva")),
+            ]
+        );
+        assert_eq!(proposed.order, tags(&["V1", "C", "V2", "C"]));
+    }
+
+    #[test]
+    fn repeated_lyrics_reuse_tag() {
+        let text = "Sloka\r\n\r\nChorus:\r\nSing\r\n\r\nDruha sloka\r\n\r\nSing\r\n\r\nBridge:\r\nMost\r\n\r\nSing";
+
+        let proposed = ProposedSong::from_text(text);
+
+        assert_eq!(proposed.order, tags(&["V1", "C", "V2", "C", "B", "C"]));
+        assert_eq!(proposed.parts.len(), 4);
+    }
+
+    #[test]
+    fn marker_needs_colon_or_own_line() {
+        let (kind, lyrics) = classify_block(&["Radost je v Pánu", "druhý řádek"]);
+        assert_eq!(kind, PartKind::Verse(None));
+        assert_eq!(lyrics, "Radost je v Pánu\ndruhý řádek");
+
+        let (kind, lyrics) = classify_block(&["R.: Radost je v Pánu"]);
+        assert_eq!(kind, PartKind::Chorus);
+        assert_eq!(lyrics, "Radost je v Pánu");
+
+        let (kind, lyrics) = classify_block(&["3) Třetí sloka"]);
+        assert_eq!(kind, PartKind::Verse(Some(3)));
+        assert_eq!(lyrics, "Třetí sloka");
+    }
+
+    #[test]
+    fn html_paragraphs_and_entities() {
+        let html = "<html><head><title>Píseň</title><style>p { color: red; }</style></head>\n\
+            <body><h1>Píseň</h1><script>alert('x');</script>\n\
+            <p>První&nbsp;sloka<br>\n   řádek&#32;dva</p>\n\
+            <p>Ref: Haleluja<br/>chval&#x0161;</p><!-- reklama --></body></html>";
+
+        assert_eq!(
+            lyrics_from_html(html),
+            "Píseň\nPrvní sloka\nřádek dva\n\nRef: Haleluja\nchvalš"
+        );
+    }
+
+    #[test]
+    fn html_prefers_pre_blocks() {
+        let html = "<p>Menu</p><pre class=\"lyrics\">1. Sloka\n&lt;G&gt;\n\nR: Refrén</pre>";
+
+        let proposed = ProposedSong::from_text(&lyrics_from_html(html));
+
+        assert_eq!(proposed.order, tags(&["V1", "C"]));
+        assert_eq!(proposed.parts[0].1, "Sloka\n<G>");
+    }
+
+    #[test]
+    fn into_song_checks_tags() {
+        let proposed = ProposedSong::from_text("Sloka\n\nR: Refrén");
+        let song = proposed
+            .clone()
+            .into_song(String::from("Píseň"), None)
+            .unwrap();
+        assert_eq!(song.order, tags(&["V1", "C"]));
+
+        assert!(proposed.clone().into_song(String::from(" "), None).is_err());
+
+        let mut duplicate = proposed.clone();
+        duplicate.parts[1].0 = String::from("V1");
+        assert!(duplicate.into_song(String::from("Píseň"), None).is_err());
+
+        let mut missing = proposed;
+        missing.order.push(String::from("B"));
+        assert!(missing.into_song(String::from("Píseň"), None).is_err());
+    }
+}
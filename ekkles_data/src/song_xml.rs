@@ -1,12 +1,13 @@
 //! Modul pro parsování dat z formátu, který používá [Opensong](https://opensong.org/development/file-formats/)
-//! do formátu používaného Ekklesem.
+//! do formátu používaného Ekklesem a pro export písní zpět do tohoto formátu
+//! (viz [`Song::to_xml()`]).
 //!
 //! ### Výkonnost
 //! Tento modul není napsán s ohledem na výkon, spousta klonování `String`ů,
 //! kde by se dalo něco znovupoužít. Pokud to bude problém, lze to přepsat,
 //! ale jelikož je to pouze pro jednorázový import, mělo by to být v pořádku
 
-use crate::{PartTag, Song};
+use crate::{PartTag, Song, SongMetadata};
 use anyhow::{Context, Result, bail};
 use lazy_static::lazy_static;
 use regex::{self, Regex, RegexBuilder};
@@ -21,6 +22,18 @@ const XML_AUTHOR_ELEM_NAME: &str = "author";
 const XML_LYRICS_ELEM_NAME: &str = "lyrics";
 /// Název XML elementu obsahující pořadí částí písně
 const XML_ORDER_ELEM_NAME: &str = "presentation";
+/// Název XML elementu obsahující číslo písně ve zpěvníku
+const XML_HYMN_NUMBER_ELEM_NAME: &str = "hymn_number";
+/// Název XML elementu obsahující číslo písně v licenční databázi CCLI
+const XML_CCLI_ELEM_NAME: &str = "ccli";
+/// Název XML elementu obsahující informace o autorských právech
+const XML_COPYRIGHT_ELEM_NAME: &str = "copyright";
+/// Název XML elementu obsahující tóninu písně
+const XML_KEY_ELEM_NAME: &str = "key";
+/// Název XML elementu obsahující pozici kapodastru
+const XML_CAPO_ELEM_NAME: &str = "capo";
+/// Název XML elementu obsahující témata písně (oddělená `;`)
+const XML_THEME_ELEM_NAME: &str = "theme";
 
 lazy_static! {
     /// Matchne řádek (včetně znaku nového řádku) s akordy.
@@ -54,6 +67,8 @@ impl Song {
     /// - Název (povinný, jinak chyba)
     /// - Autor (nepovinný)
     /// - Slova (povinné), ty se posléze zparsují (odstraní se akordy pro kytaru a rozdělí se do příslušných částí - sloka, refrén, ...)
+    /// - Doplňující údaje (nepovinné) - CCLI číslo, autorská práva, tónina, kapodastr a témata,
+    ///   viz [`SongMetadata`], neplatná pozice kapodastru je ignorována
     ///
     /// Pokud je element `presentation` neprázdný, použije se pořadí z něj,
     /// jinak se použije pořadí zapsaných částí písně ve slovech.
@@ -107,13 +122,142 @@ impl Song {
 
         let parts: HashMap<_, _> = lyrics.into_iter().map(|x| (x.0, x.1)).collect();
 
+        let elem_text = |name: &str| {
+            SongMetadata::non_empty(
+                document
+                    .descendants()
+                    .filter(|node| node.is_element())
+                    .find(|elem| elem.tag_name().name() == name)
+                    .and_then(|node| node.text()),
+            )
+        };
+        let mut metadata = SongMetadata {
+            ccli_number: elem_text(XML_CCLI_ELEM_NAME),
+            copyright: elem_text(XML_COPYRIGHT_ELEM_NAME),
+            key: elem_text(XML_KEY_ELEM_NAME),
+            capo: elem_text(XML_CAPO_ELEM_NAME).and_then(|capo| capo.parse().ok()),
+            topics: Vec::new(),
+        };
+        if let Some(topics) = elem_text(XML_THEME_ELEM_NAME) {
+            metadata.add_topics(&topics);
+        }
+
         Ok(Self {
             title,
             author,
             parts,
             order,
+            metadata,
         })
     }
+
+    /// Vytvoří z písně dokument v [XML formátu](https://opensong.org/development/file-formats/)
+    /// Opensongu, který lze znovu zparsovat pomocí [`Song::parse_from_xml()`].
+    ///
+    /// Části písně se zapíší v pořadí, ve kterém se poprvé objeví v `order`, pořadí
+    /// samotné se uloží do elementu `presentation`. Nepovinné údaje se zapíší pouze,
+    /// pokud je píseň má.
+    pub fn to_xml(&self) -> String {
+        let mut tags: Vec<&PartTag> = Vec::with_capacity(self.parts.len());
+        for tag in &self.order {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        // Podle invariantů by nemělo nastat, ale ať se žádná část neztratí
+        let mut unordered: Vec<&PartTag> = self
+            .parts
+            .keys()
+            .filter(|tag| !tags.contains(tag))
+            .collect();
+        unordered.sort();
+        tags.extend(unordered);
+
+        let mut lyrics = String::new();
+        for tag in tags {
+            if !lyrics.is_empty() {
+                lyrics.push('\n');
+            }
+            lyrics.push_str(&format!("[{tag}]\n"));
+            for line in self.parts[tag].lines() {
+                lyrics.push_str(&format!(" {line}\n"));
+            }
+        }
+
+        let elem = |name: &str, text: &str| format!("  <{name}>{}</{name}>\n", escape_xml(text));
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<song>\n");
+        xml.push_str(&elem(XML_TITLE_ELEM_NAME, &self.title));
+        if let Some(author) = &self.author {
+            xml.push_str(&elem(XML_AUTHOR_ELEM_NAME, author));
+        }
+        if let Some(copyright) = &self.metadata.copyright {
+            xml.push_str(&elem(XML_COPYRIGHT_ELEM_NAME, copyright));
+        }
+        if let Some(ccli_number) = &self.metadata.ccli_number {
+            xml.push_str(&elem(XML_CCLI_ELEM_NAME, ccli_number));
+        }
+        if let Some(key) = &self.metadata.key {
+            xml.push_str(&elem(XML_KEY_ELEM_NAME, key));
+        }
+        if let Some(capo) = self.metadata.capo {
+            xml.push_str(&elem(XML_CAPO_ELEM_NAME, &capo.to_string()));
+        }
+        if !self.metadata.topics.is_empty() {
+            xml.push_str(&elem(XML_THEME_ELEM_NAME, &self.metadata.topics.join("; ")));
+        }
+        xml.push_str(&elem(XML_ORDER_ELEM_NAME, &self.order.join(" ")));
+        xml.push_str(&format!(
+            "  <{XML_LYRICS_ELEM_NAME}>{}</{XML_LYRICS_ELEM_NAME}>\n</song>\n",
+            escape_xml(&lyrics)
+        ));
+
+        xml
+    }
+}
+
+/// Nahradí v `text` znaky, které mají v XML speciální význam, odpovídajícími entitami
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Vytáhne z dokumentu písně `xml` obsah elementu `hymn_number` (číslo písně ve zpěvníku,
+/// např. "EZ 452"), dále jej lze zpracovat pomocí [`crate::songbook::SongbookNumber::parse()`].
+/// Pokud element chybí nebo je prázdný, vrací `Ok(None)`, pokud nelze XML zparsovat, vrací Error.
+pub fn hymn_number_from_xml(xml: &str) -> Result<Option<String>> {
+    optional_elem_text(xml, XML_HYMN_NUMBER_ELEM_NAME)
+}
+
+/// Vytáhne z dokumentu písně `xml` obsah elementu `ccli` (číslo písně v licenční databázi CCLI).
+/// Pokud element chybí nebo je prázdný, vrací `Ok(None)`, pokud nelze XML zparsovat, vrací Error.
+pub fn ccli_number_from_xml(xml: &str) -> Result<Option<String>> {
+    optional_elem_text(xml, XML_CCLI_ELEM_NAME)
+}
+
+/// Najde v dokumentu `xml` první element s názvem `elem_name` a vrátí jeho oříznutý text,
+/// pokud element chybí nebo je prázdný, vrací `Ok(None)`.
+fn optional_elem_text(xml: &str, elem_name: &str) -> Result<Option<String>> {
+    let document = Document::parse(xml).context("Nelze zparsovat XML")?;
+
+    let text = document
+        .descendants()
+        .filter(|node| node.is_element())
+        .find(|elem| elem.tag_name().name() == elem_name)
+        .and_then(|node| node.text())
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string);
+
+    Ok(text)
 }
 
 /// Zpracuje slova z jejich surové reprezentace v XML do vektoru dvojic `(tag, část)`.
@@ -364,6 +508,13 @@ mod tests {
                 String::from("V3"),
                 String::from("C"),
             ],
+            metadata: SongMetadata {
+                ccli_number: Some(String::from("27783")),
+                copyright: Some(String::from("Public Domain")),
+                key: None,
+                capo: Some(3),
+                topics: vec![String::from("Christ: Victory")],
+            },
         };
 
         let haleluja_expected = Song {
@@ -406,6 +557,7 @@ mod tests {
                 String::from("V2a"),
                 String::from("V2b"),
             ],
+            metadata: Default::default(),
         };
 
         let christ_arose_result =
@@ -416,4 +568,62 @@ mod tests {
         assert_eq!(christ_arose_result, christ_arose_expected);
         assert_eq!(haleluja_result, haleluja_expected);
     }
+
+    #[test]
+    fn hymn_number_test() {
+        let xml = "<song><title>Hrad přepevný</title><hymn_number> EZ 343 </hymn_number></song>";
+        assert_eq!(
+            hymn_number_from_xml(xml).unwrap(),
+            Some(String::from("EZ 343"))
+        );
+
+        let xml = "<song><title>Bez čísla</title><hymn_number></hymn_number></song>";
+        assert_eq!(hymn_number_from_xml(xml).unwrap(), None);
+    }
+
+    #[test]
+    fn ccli_number_test() {
+        let xml = "<song><title>Christ Arose</title><ccli>27824</ccli></song>";
+        assert_eq!(
+            ccli_number_from_xml(xml).unwrap(),
+            Some(String::from("27824"))
+        );
+
+        let xml = "<song><title>Bez CCLI</title><ccli></ccli></song>";
+        assert_eq!(ccli_number_from_xml(xml).unwrap(), None);
+    }
+
+    #[test]
+    fn to_xml_roundtrip() {
+        let song = Song {
+            title: String::from("Chvála & dík <Tobě>"),
+            author: Some(String::from("Jan \"Honza\" Novák")),
+            parts: HashMap::from([
+                (
+                    String::from("V1"),
+                    String::from("Chválím Tě, Pane,\nz celého srdce"),
+                ),
+                (String::from("C"), String::from("Haleluja")),
+            ]),
+            order: vec![String::from("C"), String::from("V1"), String::from("C")],
+            metadata: SongMetadata {
+                ccli_number: Some(String::from("27783")),
+                copyright: Some(String::from("Public Domain")),
+                key: Some(String::from("G")),
+                capo: Some(2),
+                topics: vec![String::from("Chvála"), String::from("Díkůvzdání")],
+            },
+        };
+
+        let xml = song.to_xml();
+        assert!(xml.contains("<title>Chvála &amp; dík &lt;Tobě&gt;</title>"));
+        assert_eq!(Song::parse_from_xml(&xml).unwrap(), song);
+
+        let minimal = Song {
+            author: None,
+            metadata: SongMetadata::default(),
+            ..song
+        };
+        assert_eq!(Song::parse_from_xml(&minimal.to_xml()).unwrap(), minimal);
+    }
 }
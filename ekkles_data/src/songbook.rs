@@ -0,0 +1,173 @@
+//! Modul pro čísla písní ve zpěvnících (např. "EZ 452" nebo "Hosana 123"). Jedna píseň
+//! může být ve více zpěvnících, v každém má nejvýše jedno číslo. Čísla se importují
+//! z elementu `hymn_number` Opensongu, viz [`crate::song_xml::hymn_number_from_xml()`].
+
+use std::{collections::HashMap, fmt::Display};
+
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use sqlx::{Sqlite, SqlitePool, pool::PoolConnection, query};
+
+lazy_static! {
+    /// Matchne číslo písně ve zpěvníku (např. "EZ 452" nebo "452a"), název zpěvníku uloží
+    /// do capture grupy `songbook` (nepovinná) a číslo do capture grupy `number`.
+    static ref SONGBOOK_NUMBER_REGEX: Regex =
+        Regex::new(r"^\s*(?:(?P<songbook>.*\S)\s+)?(?P<number>\d+[[:alpha:]]?)\s*$").unwrap();
+}
+
+/// Číslo písně v daném zpěvníku
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SongbookNumber {
+    /// Název (typicky zkratka) zpěvníku
+    pub songbook: String,
+    /// Číslo písně, může obsahovat písmenný suffix (např. "452a")
+    pub number: String,
+}
+
+impl Display for SongbookNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.songbook, self.number)
+    }
+}
+
+impl SongbookNumber {
+    /// Zparsuje číslo písně ve tvaru "zpěvník číslo" (např. "EZ 452"). Pokud v `reference`
+    /// chybí název zpěvníku, použije se `default_songbook`, pokud ani ten není dán, nebo
+    /// `reference` není ve správném tvaru, vrací `None`.
+    pub fn parse(reference: &str, default_songbook: Option<&str>) -> Option<Self> {
+        let captures = SONGBOOK_NUMBER_REGEX.captures(reference)?;
+
+        let songbook = captures
+            .name("songbook")
+            .map(|songbook| songbook.as_str())
+            .or(default_songbook)?
+            .trim();
+        if songbook.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            songbook: songbook.to_string(),
+            number: captures["number"].to_string(),
+        })
+    }
+
+    /// Uloží číslo písně `song_id`, zpěvník, který v databázi ještě není, vytvoří.
+    /// Pokud již píseň v daném zpěvníku číslo má, bude přepsáno.
+    pub async fn save_to_db(&self, song_id: i64, pool: &SqlitePool) -> Result<()> {
+        let mut transaction = pool
+            .begin()
+            .await
+            .context("Nelze získat připojení k databázi z poolu")?;
+
+        query!(
+            "INSERT INTO songbooks (name) VALUES ($1) ON CONFLICT (name) DO NOTHING",
+            self.songbook
+        )
+        .execute(&mut *transaction)
+        .await
+        .with_context(|| format!("Nelze uložit zpěvník {}", self.songbook))?;
+
+        let songbook_id = query!("SELECT id FROM songbooks WHERE name = $1", self.songbook)
+            .fetch_one(&mut *transaction)
+            .await
+            .with_context(|| format!("Nelze načíst id zpěvníku {}", self.songbook))?
+            .id
+            .expect("Id je primární klíč, musí být přítomen");
+
+        query!(
+            "INSERT OR REPLACE INTO songbook_entries (songbook_id, song_id, number) VALUES ($1, $2, $3)",
+            songbook_id,
+            song_id,
+            self.number
+        )
+        .execute(&mut *transaction)
+        .await
+        .with_context(|| format!("Nelze uložit číslo {self} písně s id {song_id}"))?;
+
+        transaction
+            .commit()
+            .await
+            .context("Nelze provést COMMIT uložení čísla písně")
+    }
+
+    /// Načte čísla všech písní, které jsou v nějakém zpěvníku, jako mapu
+    /// id písně -> čísla ve zpěvnících (seřazená podle názvu zpěvníku).
+    pub async fn load_all_from_db(
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> Result<HashMap<i64, Vec<Self>>> {
+        let records = query!(
+            "SELECT songbook_entries.song_id, songbooks.name, songbook_entries.number
+            FROM songbook_entries JOIN songbooks ON songbooks.id = songbook_entries.songbook_id
+            ORDER BY songbooks.name"
+        )
+        .fetch_all(conn.as_mut())
+        .await
+        .context("Nelze načíst čísla písní ve zpěvnících")?;
+
+        let mut numbers: HashMap<i64, Vec<Self>> = HashMap::new();
+        for record in records {
+            numbers.entry(record.song_id).or_default().push(Self {
+                songbook: record.name,
+                number: record.number,
+            });
+        }
+
+        Ok(numbers)
+    }
+}
+
+/// Doplní k názvům písní `songs` (dvojice id, název) jejich čísla ve zpěvnících,
+/// např. "Hrad přepevný (EZ 343)". Díky tomu lze písně vyhledat i podle čísla.
+pub async fn append_songbook_numbers(
+    songs: Vec<(i64, String)>,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<Vec<(i64, String)>> {
+    let numbers = SongbookNumber::load_all_from_db(conn).await?;
+
+    Ok(songs
+        .into_iter()
+        .map(|(id, title)| match numbers.get(&id) {
+            Some(numbers) => {
+                let numbers: Vec<_> = numbers.iter().map(|number| number.to_string()).collect();
+                (id, format!("{title} ({})", numbers.join(", ")))
+            }
+            None => (id, title),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_songbook_number() {
+        assert_eq!(
+            SongbookNumber::parse("EZ 452", None),
+            Some(SongbookNumber {
+                songbook: String::from("EZ"),
+                number: String::from("452"),
+            })
+        );
+        assert_eq!(
+            SongbookNumber::parse(" Svítá 2 12b ", None),
+            Some(SongbookNumber {
+                songbook: String::from("Svítá 2"),
+                number: String::from("12b"),
+            })
+        );
+        assert_eq!(
+            SongbookNumber::parse("123", Some("Hosana")),
+            Some(SongbookNumber {
+                songbook: String::from("Hosana"),
+                number: String::from("123"),
+            })
+        );
+        assert_eq!(SongbookNumber::parse("123", None), None);
+        assert_eq!(SongbookNumber::parse("EZ", Some("Hosana")), None);
+        assert_eq!(SongbookNumber::parse("", Some("Hosana")), None);
+    }
+}
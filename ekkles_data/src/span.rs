@@ -0,0 +1,51 @@
+//! Úseky (spany) práce s databází pro dohledání chyb v logu. Každé uložení, načtení
+//! a import (playlistu, písně, balíčku) dostane při spuštění pořadové číslo a do logu
+//! se zapíše jeho začátek a konec (doba trvání, případně chyba) i s ID dotčených dat,
+//! např. `[#12] playlist.save playlist=5: selhalo za 3.1 ms: ...`. Záznamy z nepovedené
+//! bohoslužby tak jde spárovat, i když se úseky prolínají.
+//!
+//! Záznamy se logují pod cílem [`SPAN_TARGET`] (začátek a konec na úrovni `debug`,
+//! selhání na `warn`), aby je šlo v logu odfiltrovat nebo zachytit zvlášť.
+
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+
+use log::{debug, warn};
+
+/// Cíl (target) logů s úseky práce s databází
+pub const SPAN_TARGET: &str = "ekkles::span";
+
+/// Pořadové číslo dalšího úseku
+static NEXT_SPAN_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Provede `operation` jako úsek s názvem `name` (např. `playlist.save`), `subject` popisuje
+/// dotčená data (např. `playlist=5`). Začátek i konec úseku se zalogují, výsledek operace
+/// se vrací beze změny.
+pub async fn in_span<T, E: Display>(
+    name: &'static str,
+    subject: String,
+    operation: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let id = NEXT_SPAN_ID.fetch_add(1, Ordering::Relaxed);
+    debug!(target: SPAN_TARGET, "[#{id}] {name} {subject}: začátek");
+    let start = Instant::now();
+
+    let result = operation.await;
+
+    let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+    match &result {
+        Ok(_) => debug!(
+            target: SPAN_TARGET,
+            "[#{id}] {name} {subject}: hotovo za {elapsed_ms:.1} ms"
+        ),
+        Err(e) => warn!(
+            target: SPAN_TARGET,
+            "[#{id}] {name} {subject}: selhalo za {elapsed_ms:.1} ms: {e:#}"
+        ),
+    }
+
+    result
+}
@@ -0,0 +1,341 @@
+//! Kontrola pravopisu slov písní podle slovníků ve formátu hunspell (dvojice souborů
+//! `<jazyk>.aff` a `<jazyk>.dic`, např. `cs_CZ` a `en_US`). Písně bývají česky i anglicky,
+//! slovo je proto správně, pokud jej zná alespoň jeden z načtených slovníků.
+//!
+//! Slovníky bývají kromě UTF-8 i v kódování ISO8859-2 (české) nebo ISO8859-1, kódování
+//! se pozná podle direktivy `SET` v `.aff` souboru.
+
+use anyhow::{Context, Result, anyhow, bail};
+use log::{debug, warn};
+use spellbook::Dictionary;
+use std::{
+    fmt::{self, Debug},
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// Jazyky slovníků, které se načítají, pokud nejsou zvoleny jiné
+pub const DEFAULT_LANGUAGES: [&str; 2] = ["cs_CZ", "en_US"];
+/// Nejvyšší počet návrhů oprav jednoho slova
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Znaky ISO8859-2 od `0xA0` do `0xFF`, nižší se shodují s ASCII (a ISO8859-1)
+const ISO8859_2_HIGH: [char; 96] = [
+    '\u{00a0}', '\u{0104}', '\u{02d8}', '\u{0141}', '\u{00a4}', '\u{013d}', '\u{015a}', '\u{00a7}',
+    '\u{00a8}', '\u{0160}', '\u{015e}', '\u{0164}', '\u{0179}', '\u{00ad}', '\u{017d}', '\u{017b}',
+    '\u{00b0}', '\u{0105}', '\u{02db}', '\u{0142}', '\u{00b4}', '\u{013e}', '\u{015b}', '\u{02c7}',
+    '\u{00b8}', '\u{0161}', '\u{015f}', '\u{0165}', '\u{017a}', '\u{02dd}', '\u{017e}', '\u{017c}',
+    '\u{0154}', '\u{00c1}', '\u{00c2}', '\u{0102}', '\u{00c4}', '\u{0139}', '\u{0106}', '\u{00c7}',
+    '\u{010c}', '\u{00c9}', '\u{0118}', '\u{00cb}', '\u{011a}', '\u{00cd}', '\u{00ce}', '\u{010e}',
+    '\u{0110}', '\u{0143}', '\u{0147}', '\u{00d3}', '\u{00d4}', '\u{0150}', '\u{00d6}', '\u{00d7}',
+    '\u{0158}', '\u{016e}', '\u{00da}', '\u{0170}', '\u{00dc}', '\u{00dd}', '\u{0162}', '\u{00df}',
+    '\u{0155}', '\u{00e1}', '\u{00e2}', '\u{0103}', '\u{00e4}', '\u{013a}', '\u{0107}', '\u{00e7}',
+    '\u{010d}', '\u{00e9}', '\u{0119}', '\u{00eb}', '\u{011b}', '\u{00ed}', '\u{00ee}', '\u{010f}',
+    '\u{0111}', '\u{0144}', '\u{0148}', '\u{00f3}', '\u{00f4}', '\u{0151}', '\u{00f6}', '\u{00f7}',
+    '\u{0159}', '\u{016f}', '\u{00fa}', '\u{0171}', '\u{00fc}', '\u{00fd}', '\u{0163}', '\u{02d9}',
+];
+
+/// Kontrola pravopisu podle jednoho nebo více slovníků
+pub struct SpellChecker {
+    /// Načtené slovníky jako dvojice (jazyk, slovník)
+    dictionaries: Vec<(String, Dictionary)>,
+}
+
+impl Debug for SpellChecker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpellChecker")
+            .field("languages", &self.languages().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl SpellChecker {
+    /// Vytvoří kontrolu pravopisu bez slovníků, slovníky se přidají
+    /// pomocí [`SpellChecker::add_dictionary()`]
+    pub fn new() -> Self {
+        Self {
+            dictionaries: Vec::new(),
+        }
+    }
+
+    /// Načte slovníky jazyků `languages`, každý z první složky v `dirs`, ve které je.
+    /// Chybějící slovník se přeskočí, pokud se nenačte žádný, vrací Error.
+    pub fn load(dirs: &[PathBuf], languages: &[&str]) -> Result<Self> {
+        let mut checker = Self::new();
+        for language in languages {
+            let Some(dir) = dirs.iter().find(|dir| dictionary_exists(dir, language)) else {
+                warn!("Slovník jazyka {language} nenalezen v žádné ze složek {dirs:?}");
+                continue;
+            };
+
+            debug!("Načítám slovník jazyka {language} ze složky {dir:?}");
+            let aff = fs::read(dir.join(format!("{language}.aff")))
+                .with_context(|| format!("Nelze přečíst slovník jazyka {language}"))?;
+            let dic = fs::read(dir.join(format!("{language}.dic")))
+                .with_context(|| format!("Nelze přečíst slovník jazyka {language}"))?;
+            checker.add_dictionary(language, &aff, &dic)?;
+        }
+
+        if checker.dictionaries.is_empty() {
+            bail!("Nenalezen žádný slovník pro kontrolu pravopisu (hledáno v {dirs:?})");
+        }
+
+        Ok(checker)
+    }
+
+    /// Přidá slovník jazyka `language` z obsahu jeho souborů `.aff` a `.dic`. Pokud je
+    /// slovník v nepodporovaném kódování nebo jej nelze naparsovat, vrací Error.
+    pub fn add_dictionary(&mut self, language: &str, aff: &[u8], dic: &[u8]) -> Result<()> {
+        let encoding = declared_encoding(aff);
+        let aff = decode(aff, encoding.as_deref())
+            .with_context(|| format!("Nelze dekódovat slovník jazyka {language}"))?;
+        let dic = decode(dic, encoding.as_deref())
+            .with_context(|| format!("Nelze dekódovat slovník jazyka {language}"))?;
+        // Chyba parsování neimplementuje `std::error::Error`, zachová se pouze její popis
+        let dictionary = Dictionary::new(&aff, &dic)
+            .map_err(|e| anyhow!("Nelze naparsovat slovník jazyka {language}: {e}"))?;
+
+        self.dictionaries.push((language.to_string(), dictionary));
+        Ok(())
+    }
+
+    /// Vrátí jazyky načtených slovníků v pořadí, ve kterém byly přidány
+    pub fn languages(&self) -> impl Iterator<Item = &str> {
+        self.dictionaries
+            .iter()
+            .map(|(language, _)| language.as_str())
+    }
+
+    /// Zda slovo `word` zná alespoň jeden ze slovníků
+    pub fn is_correct(&self, word: &str) -> bool {
+        self.dictionaries
+            .iter()
+            .any(|(_, dictionary)| dictionary.check(word))
+    }
+
+    /// Vrátí rozsahy (v bytech) slov v řádku `line`, která nezná žádný ze slovníků
+    pub fn misspelled_ranges(&self, line: &str) -> Vec<Range<usize>> {
+        words(line)
+            .filter(|(_, word)| !self.is_correct(word))
+            .map(|(range, _)| range)
+            .collect()
+    }
+
+    /// Vrátí slova z textu `text`, která nezná žádný ze slovníků, každé jednou
+    /// v pořadí jejich prvního výskytu
+    pub fn misspelled_words(&self, text: &str) -> Vec<String> {
+        let mut misspelled: Vec<String> = Vec::new();
+        for (_, word) in text.lines().flat_map(words) {
+            if !misspelled.iter().any(|known| known == word) && !self.is_correct(word) {
+                misspelled.push(word.to_string());
+            }
+        }
+
+        misspelled
+    }
+
+    /// Vrátí nejvýše [`MAX_SUGGESTIONS`] návrhů oprav slova `word` ze všech slovníků
+    pub fn suggestions(&self, word: &str) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        for (_, dictionary) in &self.dictionaries {
+            let mut found = Vec::new();
+            dictionary.suggest(word, &mut found);
+            for suggestion in found {
+                if !suggestions.contains(&suggestion) {
+                    suggestions.push(suggestion);
+                }
+            }
+        }
+
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+}
+
+impl Default for SpellChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Vrátí slova řádku `line` spolu s jejich rozsahy (v bytech). Slovo je souvislá posloupnost
+/// písmen, apostrof uvnitř slova (např. `don't`) je jeho součástí.
+pub fn words(line: &str) -> impl Iterator<Item = (Range<usize>, &str)> {
+    let mut chars = line.char_indices().peekable();
+    std::iter::from_fn(move || {
+        let (start, _) = chars.find(|(_, c)| c.is_alphabetic())?;
+        let mut end = line.len();
+        while let Some(&(index, c)) = chars.peek() {
+            let inner_apostrophe = is_apostrophe(c)
+                && line[index + c.len_utf8()..]
+                    .chars()
+                    .next()
+                    .is_some_and(char::is_alphabetic);
+            if c.is_alphabetic() || inner_apostrophe {
+                chars.next();
+            } else {
+                end = index;
+                break;
+            }
+        }
+
+        Some((start..end, &line[start..end]))
+    })
+}
+
+/// Nahradí v textu `text` všechny výskyty celého slova `word` slovem `replacement`
+pub fn replace_word(text: &str, word: &str, replacement: &str) -> String {
+    let mut replaced = String::with_capacity(text.len());
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            replaced.push('\n');
+        }
+
+        let mut last = 0;
+        for (range, _) in words(line).filter(|(_, found)| *found == word) {
+            replaced.push_str(&line[last..range.start]);
+            replaced.push_str(replacement);
+            last = range.end;
+        }
+        replaced.push_str(&line[last..]);
+    }
+
+    replaced
+}
+
+fn is_apostrophe(c: char) -> bool {
+    c == '\'' || c == '’'
+}
+
+/// Zda jsou ve složce `dir` oba soubory slovníku jazyka `language`
+fn dictionary_exists(dir: &Path, language: &str) -> bool {
+    dir.join(format!("{language}.aff")).is_file() && dir.join(format!("{language}.dic")).is_file()
+}
+
+/// Vrátí kódování z direktivy `SET` souboru `.aff`, pokud ji obsahuje
+fn declared_encoding(aff: &[u8]) -> Option<String> {
+    aff.split(|&byte| byte == b'\n')
+        .find_map(|line| line.strip_prefix(b"SET "))
+        .map(|encoding| {
+            String::from_utf8_lossy(encoding)
+                .trim()
+                .to_ascii_uppercase()
+        })
+}
+
+/// Dekóduje obsah souboru slovníku v kódování `encoding`, bez kódování (chybí direktiva
+/// `SET`) je obsah v UTF-8, pokud je platné, jinak v ISO8859-1 (výchozí v hunspellu)
+fn decode(bytes: &[u8], encoding: Option<&str>) -> Result<String> {
+    match encoding {
+        Some("UTF-8") => Ok(String::from_utf8(bytes.to_vec())?),
+        Some("ISO8859-2" | "ISO-8859-2") => Ok(bytes
+            .iter()
+            .map(|&byte| match byte {
+                0xa0.. => ISO8859_2_HIGH[usize::from(byte - 0xa0)],
+                _ => char::from(byte),
+            })
+            .collect()),
+        Some("ISO8859-1" | "ISO-8859-1") => {
+            Ok(bytes.iter().map(|&byte| char::from(byte)).collect())
+        }
+        Some(encoding) => bail!("Nepodporované kódování slovníku {encoding}"),
+        None => match String::from_utf8(bytes.to_vec()) {
+            Ok(text) => Ok(text),
+            Err(_) => decode(bytes, Some("ISO8859-1")),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn checker() -> SpellChecker {
+        let mut checker = SpellChecker::new();
+        checker
+            .add_dictionary(
+                "cs_CZ",
+                "SET UTF-8\nTRY aáeéěiíoóuúůyýsšcčrřzžtťdďnň\n".as_bytes(),
+                "4\nPán\nsláva\nchvála\nmilost\n".as_bytes(),
+            )
+            .unwrap();
+        checker
+            .add_dictionary(
+                "en_US",
+                b"SET UTF-8\nTRY esianrtolcdugmphbyfvkwz'\n",
+                b"3\ngrace\nglory\ndon't\n",
+            )
+            .unwrap();
+        checker
+    }
+
+    #[test]
+    fn words_with_apostrophes() {
+        let words: Vec<_> = words("Don't stop, 'Pán' je 2x-krát").collect();
+        assert_eq!(
+            words,
+            vec![
+                (0..5, "Don't"),
+                (6..10, "stop"),
+                (13..17, "Pán"),
+                (19..21, "je"),
+                (23..24, "x"),
+                (25..30, "krát"),
+            ]
+        );
+    }
+
+    #[test]
+    fn misspelled_in_any_language() {
+        let checker = checker();
+        let line = "Sláva, chvála! Glory gracee, don't chvla";
+
+        assert_eq!(checker.misspelled_ranges(line), vec![23..29, 37..42]);
+        assert_eq!(
+            checker.misspelled_words(&format!("{line}\nchvla milost")),
+            vec![String::from("gracee"), String::from("chvla")]
+        );
+        assert_eq!(
+            checker.languages().collect::<Vec<_>>(),
+            vec!["cs_CZ", "en_US"]
+        );
+    }
+
+    #[test]
+    fn suggestions_from_all_dictionaries() {
+        let checker = checker();
+
+        assert!(
+            checker
+                .suggestions("chvla")
+                .contains(&String::from("chvála"))
+        );
+        assert!(
+            checker
+                .suggestions("gracee")
+                .contains(&String::from("grace"))
+        );
+    }
+
+    #[test]
+    fn iso8859_2_dictionary() {
+        let mut checker = SpellChecker::new();
+        checker
+            .add_dictionary("cs_CZ", b"SET ISO8859-2\n", b"2\nsl\xe1va\n\xb9\xedp\n")
+            .unwrap();
+
+        assert!(checker.is_correct("sláva"));
+        assert!(checker.is_correct("šíp"));
+        assert!(!checker.is_correct("slava"));
+    }
+
+    #[test]
+    fn replace_whole_words() {
+        assert_eq!(
+            replace_word("milost, milostí\nmilost", "milost", "Milost"),
+            "Milost, milostí\nMilost"
+        );
+    }
+}
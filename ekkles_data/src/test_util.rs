@@ -0,0 +1,146 @@
+//! In-memory databáze naplněné daty pro testy, aby si je testy jednotlivých crate
+//! (včetně GUI) nemusely skládat samy z SQL. Modul je dostupný pouze s feature
+//! `test-util` (a v testech tohoto crate), do běžného překladu se nedostane.
+//!
+//! Všechny funkce při chybě panikaří, jsou určené výhradně pro testy.
+
+use std::{collections::HashMap, fs};
+
+use sqlx::{SqlitePool, query_file};
+
+use crate::{Song, bible::parse_bible_from_xml, database::MIGRATOR};
+
+/// Bible (ČEP) pro testování, na absolutní cestě, aby ji šlo načíst i z testů jiných crate
+const TEST_BIBLE_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/CzechPrekladBible.xml"
+);
+
+/// Funkce na vytvoření in-memory databáze pro testování. Vytvoří holou databázi
+/// pouze se strukturou tabulek (po všech migracích), ale bez dat.
+pub async fn setup_bare_db() -> SqlitePool {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+    MIGRATOR.run(&pool).await.unwrap();
+
+    pool
+}
+
+/// Funkce na vytvoření in-memory databáze pro testování. Vytvoří databázi a nasype
+/// do ní dvě písně a prvních 10 veršů Genesis, viz `db/fill_test_db.sql`.
+pub async fn setup_db_with_test_data() -> SqlitePool {
+    let pool = setup_bare_db().await;
+
+    query_file!("db/fill_test_db.sql")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    pool
+}
+
+/// Funkce na vytvoření in-memory databáze pro testování. Vytvoří databázi
+/// a přidá do ní bibli pro testování.
+pub async fn setup_db_with_bible() -> SqlitePool {
+    let pool = setup_bare_db().await;
+
+    let xml_data = fs::read_to_string(TEST_BIBLE_PATH).unwrap();
+
+    parse_bible_from_xml(&xml_data, &pool).await.unwrap();
+
+    pool
+}
+
+/// Funkce na vytvoření in-memory databáze pro testování. Vytvoří databázi
+/// a přidá do ní bibli (translation_id 0) pro testování + 2 písně (id 0 a 1).
+pub async fn setup_db_with_bible_and_songs() -> SqlitePool {
+    let pool = setup_db_with_bible().await;
+
+    let haleluja = Song {
+        title: String::from("Haleluja (Svatý Pán Bůh Všemohoucí)"),
+        author: None,
+        parts: HashMap::from([
+            (
+                String::from("C"),
+                String::from("Haleluja, haleluja,\nvládne nám všemocný Bůh a Král."),
+            ),
+            (
+                String::from("V1a"),
+                String::from(
+                    "Haleluja, Svatý, Svatý,\nSvatý Pán Bůh Všemohoucí,\nhoden je On sám,\nBeránek, náš Pán,\npřijmout chválu,",
+                ),
+            ),
+            (
+                String::from("V1b"),
+                String::from(
+                    "Svatý, Svatý Pán Bůh Všemohoucí,\nhoden je On sám,\nBeránek, náš Pán,\npřijmout chválu.",
+                ),
+            ),
+            (
+                String::from("V2a"),
+                String::from(
+                    "Haleluja, Svatý, Svatý,\nTy jsi náš Bůh Všemohoucí,\npřijmi, Pane náš,\npřijmi, Pane náš,\nnaši chválu,",
+                ),
+            ),
+            (
+                String::from("V2b"),
+                String::from(
+                    "Svatý, Ty jsi náš Bůh Všemohoucí,\npřijmi, Pane náš,\npřijmi, Pane náš,\nchválu.",
+                ),
+            ),
+        ]),
+        order: vec![
+            String::from("C"),
+            String::from("V1a"),
+            String::from("V1b"),
+            String::from("V2a"),
+            String::from("V2b"),
+        ],
+        metadata: Default::default(),
+    };
+
+    let christ_arose = Song {
+        title: String::from("Christ Arose"),
+        author: Some(String::from("Robert Lowry, 1874")),
+        parts: HashMap::from([
+            (
+                String::from("V1"),
+                String::from(
+                    "Low in the grave He lay, Jesus my Savior!\nWaiting the coming day, Je____sus my Lord!",
+                ),
+            ),
+            (
+                String::from("C"),
+                String::from(
+                    "(Spirited!) Up from the grave He arose,\nWith a mighty triumph o'er His foes;\nHe arose a victor from the dark do_main,\nAnd He lives forever with His saints to   reign,\nHe arose! He arose! Hallelujah! Christ arose!",
+                ),
+            ),
+            (
+                String::from("V2"),
+                String::from(
+                    "Vainly they watch His bed, Jesus my Savior!\nVainly they seal the dead, Je____sus my Lord!",
+                ),
+            ),
+            (
+                String::from("V3"),
+                String::from(
+                    "Death cannot keep his prey, Jesus my Savior!\nHe tore the bars away, Je____sus my Lord!",
+                ),
+            ),
+        ]),
+        order: vec![
+            String::from("V1"),
+            String::from("C"),
+            String::from("V2"),
+            String::from("C"),
+            String::from("V3"),
+            String::from("C"),
+        ],
+        metadata: Default::default(),
+    };
+
+    haleluja.save_to_db(&pool).await.unwrap();
+    christ_arose.save_to_db(&pool).await.unwrap();
+
+    pool
+}
@@ -0,0 +1,180 @@
+//! Modul pro pojmenované motivy slajdů (barva textu a pozadí, obrázek na pozadí).
+//!
+//! Motiv lze přiřadit písni (preferovaný motiv, např. hymny na světlém pozadí)
+//! nebo playlistu. Motiv playlistu má přednost před motivem písně.
+//!
+//! Obrázek na pozadí pochází z úložiště médií (viz [`crate::media`]), aby byl text
+//! na pestré fotce čitelný, lze jej ztmavit. Pokud je obrázek z úložiště smazán,
+//! motivu zůstane pouze barva pozadí.
+
+use anyhow::{Context, Result, anyhow};
+use sqlx::{Sqlite, SqlitePool, pool::PoolConnection, query};
+
+/// Barva reprezentovaná trojicí `[r, g, b]`
+pub type Rgb = [u8; 3];
+
+/// Nejvyšší možné ztmavení obrázku na pozadí (v procentech)
+pub const MAX_BACKGROUND_DIMMING: u8 = 100;
+
+/// Pojmenovaný motiv slajdů
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlideTheme {
+    pub name: String,
+    pub text_color: Rgb,
+    pub background_color: Rgb,
+    /// Obrázek na pozadí, vykresluje se přes barvu pozadí
+    pub background_image: Option<BackgroundImage>,
+}
+
+/// Obrázek na pozadí motivu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackgroundImage {
+    /// ID média, viz [`crate::media`]
+    pub media_id: i64,
+    /// Ztmavení obrázku v procentech (0 = beze změny, [`MAX_BACKGROUND_DIMMING`] = černá)
+    pub dimming: u8,
+}
+
+/// Převede barvu na řetězec ve tvaru `#rrggbb`
+pub fn rgb_to_hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+/// Zparsuje barvu z řetězce ve tvaru `#rrggbb` (`#` je nepovinný), pokud
+/// řetězec není v tomto tvaru, vrací `None`.
+pub fn hex_to_rgb(input: &str) -> Option<Rgb> {
+    let hex = input.trim().trim_start_matches('#');
+
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+
+    Some([r, g, b])
+}
+
+impl SlideTheme {
+    /// Uloží motiv do databáze a vrátí jeho id. Pokud motiv se stejným názvem
+    /// již existuje, vrací Error.
+    pub async fn save_to_db(&self, pool: &SqlitePool) -> Result<i64> {
+        let text_color = rgb_to_hex(self.text_color);
+        let background_color = rgb_to_hex(self.background_color);
+        let background_media_id = self.background_image.map(|image| image.media_id);
+        let background_dimming = self
+            .background_image
+            .map_or(0, |image| image.dimming.min(MAX_BACKGROUND_DIMMING));
+
+        query!(
+            "INSERT INTO themes (name, text_color, background_color, background_media_id, background_dimming) VALUES ($1, $2, $3, $4, $5)",
+            self.name,
+            text_color,
+            background_color,
+            background_media_id,
+            background_dimming
+        )
+        .execute(pool)
+        .await
+        .with_context(|| format!("Nelze uložit motiv '{}' do databáze", self.name))
+        .map(|result| result.last_insert_rowid())
+    }
+
+    /// Načte motiv s daným `id` z databáze.
+    pub async fn load_from_db(id: i64, conn: &mut PoolConnection<Sqlite>) -> Result<Self> {
+        let record = query!(
+            "SELECT name, text_color, background_color, background_media_id, background_dimming FROM themes WHERE id = $1",
+            id
+        )
+        .fetch_one(conn.as_mut())
+        .await
+        .with_context(|| format!("Motiv s id {id} nebyl nalezen"))?;
+
+        Ok(Self {
+            text_color: hex_to_rgb(&record.text_color)
+                .ok_or(anyhow!("Nevalidní barva textu v databázi"))?,
+            background_color: hex_to_rgb(&record.background_color)
+                .ok_or(anyhow!("Nevalidní barva pozadí v databázi"))?,
+            background_image: record
+                .background_media_id
+                .map(|media_id| -> Result<BackgroundImage> {
+                    Ok(BackgroundImage {
+                        media_id,
+                        dimming: record
+                            .background_dimming
+                            .try_into()
+                            .context("Nevalidní ztmavení pozadí v databázi")?,
+                    })
+                })
+                .transpose()?,
+            name: record.name,
+        })
+    }
+
+    /// Smaže motiv s daným `id` z databáze. Písně a playlisty, které jej používaly,
+    /// se vrátí k výchozímu motivu (o to se postará `ON DELETE SET NULL`).
+    pub async fn delete_from_db(id: i64, pool: &SqlitePool) -> Result<()> {
+        query!("DELETE FROM themes WHERE id = $1", id)
+            .execute(pool)
+            .await
+            .with_context(|| format!("Nelze smazat motiv s id {id} z databáze"))?;
+
+        Ok(())
+    }
+}
+
+/// Vrátí dvojice (id, název) všech motivů v databázi seřazené podle názvu.
+pub async fn get_available(conn: &mut PoolConnection<Sqlite>) -> Result<Vec<(i64, String)>> {
+    query!(r#"SELECT id AS "id!", name FROM themes ORDER BY name ASC"#)
+        .map(|record| (record.id, record.name))
+        .fetch_all(conn.as_mut())
+        .await
+        .context("Nelze načíst motivy z databáze")
+}
+
+/// Vrátí id preferovaného motivu písně `song_id`, pokud žádný nemá, vrací `None`.
+pub async fn get_song_theme_id(
+    song_id: i64,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<Option<i64>> {
+    query!("SELECT theme_id FROM songs WHERE id = $1", song_id)
+        .fetch_one(conn.as_mut())
+        .await
+        .with_context(|| format!("Píseň s id {song_id} nebyla nalezena"))
+        .map(|record| record.theme_id)
+}
+
+/// Nastaví písni `song_id` preferovaný motiv `theme_id` (`None` = výchozí motiv).
+pub async fn set_song_theme_id(
+    song_id: i64,
+    theme_id: Option<i64>,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Result<()> {
+    query!(
+        "UPDATE songs SET theme_id = $1 WHERE id = $2",
+        theme_id,
+        song_id
+    )
+    .execute(conn.as_mut())
+    .await
+    .with_context(|| format!("Nelze nastavit motiv písni s id {song_id}"))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn hex_color_roundtrip() {
+        let color = [0x12, 0xab, 0xff];
+        assert_eq!(hex_to_rgb(&rgb_to_hex(color)), Some(color));
+        assert_eq!(hex_to_rgb("12abff"), Some(color));
+        assert_eq!(hex_to_rgb("#12abf"), None);
+        assert_eq!(hex_to_rgb("#12abfž"), None);
+    }
+}
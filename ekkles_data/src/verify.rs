@@ -0,0 +1,140 @@
+//! Kontrola zápisu (read-after-write). Po uložení playlistu nebo písně se uložená data
+//! načtou z databáze zpět a porovnají s verzí v paměti. Tiché poškození dat při ukládání
+//! (např. špatně rozdělené pořadí částí písně) se tak odhalí hned, ne až při bohoslužbě.
+//!
+//! Kontrola zdvojnásobuje práci s databází při ukládání, je proto ve výchozím stavu
+//! vypnutá a zapíná se v nastavení, viz [`set_enabled()`]. Rozdíl se hlásí jako
+//! [`EkklesDataError::InvalidData`] s kontrolními součty obou verzí (viz [`Checksum`])
+//! a prvním rozdílným řádkem. Data v databázi zůstanou, jak byla uložena, aby šlo
+//! poškození prozkoumat.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::debug;
+
+use crate::{
+    Song,
+    error::{EkklesDataError, Result},
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Zapne nebo vypne kontrolu zápisu pro všechna další uložení
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Zda je kontrola zápisu zapnutá, viz [`set_enabled()`]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Obsah, který lze po uložení porovnat s načtenou verzí
+pub trait Checksum {
+    /// Obsah po řádcích v kanonické podobě, tedy nezávislé na pořadí prvků v `HashMap`.
+    /// Stav uložení (id, ...) do obsahu nepatří.
+    fn canonical_content(&self) -> String;
+
+    /// Kontrolní součet kanonického obsahu (FNV-1a, 64 bitů), stejný napříč spuštěními
+    fn checksum(&self) -> u64 {
+        self.canonical_content()
+            .bytes()
+            .fold(0xcbf29ce484222325, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+            })
+    }
+}
+
+impl Checksum for Song {
+    fn canonical_content(&self) -> String {
+        let mut parts: Vec<_> = self.parts.iter().collect();
+        parts.sort();
+
+        let mut lines = vec![
+            format!("title: {:?}", self.title),
+            format!("author: {:?}", self.author),
+            format!("order: {:?}", self.order),
+        ];
+        lines.extend(
+            parts
+                .into_iter()
+                .map(|(tag, lyrics)| format!("part {tag}: {lyrics:?}")),
+        );
+        lines.push(format!("metadata: {:?}", self.metadata));
+
+        lines.join("\n")
+    }
+}
+
+/// Porovná `saved` (načtené z databáze) s `in_memory`, `what` popisuje data v chybové hlášce
+/// (např. "Playlist Neděle"). Pokud se liší, vrací [`EkklesDataError::InvalidData`].
+pub(crate) fn compare<T: Checksum>(what: &str, in_memory: &T, saved: &T) -> Result<()> {
+    let (expected, actual) = (in_memory.canonical_content(), saved.canonical_content());
+    if expected == actual {
+        debug!(
+            "{what}: uloženo bez rozdílu (kontrolní součet {:016x})",
+            in_memory.checksum()
+        );
+        return Ok(());
+    }
+
+    let difference = expected
+        .lines()
+        .zip(actual.lines())
+        .find(|(expected, actual)| expected != actual)
+        .map(|(expected, actual)| format!("uloženo \"{expected}\", v databázi \"{actual}\""))
+        .unwrap_or_else(|| String::from("liší se počet řádků"));
+
+    Err(EkklesDataError::InvalidData(format!(
+        "{what}: uložená data se liší od dat v databázi (kontrolní součet {:016x}, v databázi {:016x}), {difference}",
+        in_memory.checksum(),
+        saved.checksum()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn song() -> Song {
+        Song {
+            title: String::from("Chválím Tě, Pane"),
+            author: None,
+            parts: HashMap::from([
+                (String::from("V1"), String::from("Chválím Tě, Pane")),
+                (String::from("C"), String::from("Haleluja")),
+            ]),
+            order: vec![String::from("V1"), String::from("C")],
+            metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn checksum_does_not_depend_on_part_order() {
+        let mut reordered = song();
+        reordered.parts = HashMap::from([
+            (String::from("C"), String::from("Haleluja")),
+            (String::from("V1"), String::from("Chválím Tě, Pane")),
+        ]);
+
+        assert_eq!(song().checksum(), reordered.checksum());
+        assert!(compare("Píseň", &song(), &reordered).is_ok());
+    }
+
+    #[test]
+    fn difference_is_reported() {
+        let mut mangled = song();
+        mangled.order = vec![String::from("V1 C")];
+
+        let err = compare("Píseň", &song(), &mangled).unwrap_err().to_string();
+
+        assert!(err.starts_with("Píseň: uložená data se liší od dat v databázi"));
+        assert!(
+            err.ends_with("uloženo \"order: [\"V1\", \"C\"]\", v databázi \"order: [\"V1 C\"]\"")
+        );
+    }
+}
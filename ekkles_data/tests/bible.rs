@@ -1,8 +1,10 @@
 use common::setup_db_with_bible;
-use ekkles_data::bible::indexing::{Book, Passage, VerseIndex};
-use ekkles_data::bible::parse_bible_from_xml;
+use ekkles_data::bible::indexing::{
+    Book, Passage, VerseIndex, chapters_in_book, verses_in_chapter,
+};
+use ekkles_data::bible::{get_available_translations, get_verse_counts, parse_bible_from_xml};
 use pretty_assertions::assert_eq;
-use sqlx::query;
+use sqlx::{Sqlite, pool::PoolConnection, query};
 use tokio::fs::read_to_string;
 
 mod common;
@@ -27,14 +29,14 @@ async fn storing_bible() {
         "Já Hospodin jsem tvůj Bůh, který jsem tě vyvedl z egyptské země, z domu otroctví.",
     );
 
-    let book_id_john = query!("SELECT (id) FROM books WHERE title = $1", "Jan")
+    let book_id_john = query!("SELECT (id) FROM books WHERE osis_id = $1", "John")
         .fetch_one(&db)
         .await
         .unwrap()
         .id
         .unwrap();
 
-    let book_id_exodus = query!("SELECT (id) FROM books WHERE title = $1", "Exodus")
+    let book_id_exodus = query!("SELECT (id) FROM books WHERE osis_id = $1", "Exod")
         .fetch_one(&db)
         .await
         .unwrap()
@@ -211,3 +213,137 @@ async fn load_passage_over_book_boundary_test() {
 
     assert_eq!(verses, expected_verses);
 }
+
+/// Minimální bible se všemi knihami, každá kniha má jediný verš, žalm 23 a 24 mají nadpisy
+fn bible_with_headings() -> String {
+    let books: String = (1..=66)
+        .map(|number| match number {
+            19 => String::from(
+                r#"<book number="19">
+                    <chapter number="23">
+                        <verse number="0">Žalm Davidův.</verse>
+                        <verse number="1">Hospodin je můj pastýř, nic nepostrádám.</verse>
+                        <verse number="2">Dává mi spočinout na travnatých nivách.</verse>
+                    </chapter>
+                    <chapter number="24">
+                        <title>Davidův, žalm.</title>
+                        <verse number="1">Hospodinova je země i všechno, co je na ní.</verse>
+                        <verse number="2"><heading>Vjezd krále</heading>Neboť on ji založil na mořích.</verse>
+                    </chapter>
+                </book>"#,
+            ),
+            number => format!(
+                r#"<book number="{number}"><chapter number="1"><verse number="1">Verš</verse></chapter></book>"#
+            ),
+        })
+        .collect();
+
+    format!(r#"<bible translation="Nadpisy"><testament name="Old">{books}</testament></bible>"#)
+}
+
+/// Načte pasáž žalmu `chapter` od verše `from` po `to`
+async fn load_psalm(
+    chapter: u8,
+    from: u8,
+    to: u8,
+    translation_id: i64,
+    conn: &mut PoolConnection<Sqlite>,
+) -> Passage {
+    Passage::load(
+        VerseIndex::try_new(Book::Psalms, chapter, from).unwrap(),
+        VerseIndex::try_new(Book::Psalms, chapter, to).unwrap(),
+        translation_id,
+        conn,
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn storing_psalm_headings() {
+    let db = common::setup_bare_db().await;
+
+    parse_bible_from_xml(&bible_with_headings(), &db)
+        .await
+        .unwrap();
+
+    let translation_id = query!("SELECT id FROM translations")
+        .fetch_one(&db)
+        .await
+        .unwrap()
+        .id;
+    let mut conn = db.acquire().await.unwrap();
+
+    let psalm_23 = load_psalm(23, 1, 2, translation_id, &mut conn).await;
+    assert_eq!(psalm_23.get_heading(), Some("Žalm Davidův."));
+    assert_eq!(
+        psalm_23.get_verses()[0],
+        (1, String::from("Hospodin je můj pastýř, nic nepostrádám.")),
+        "Nadpis nesmí být samostatným veršem ani součástí textu verše"
+    );
+    assert_eq!(
+        load_psalm(23, 2, 2, translation_id, &mut conn)
+            .await
+            .get_heading(),
+        None
+    );
+
+    let psalm_24 = load_psalm(24, 1, 1, translation_id, &mut conn).await;
+    assert_eq!(psalm_24.get_heading(), Some("Davidův, žalm."));
+    let psalm_24_2 = load_psalm(24, 2, 2, translation_id, &mut conn).await;
+    assert_eq!(psalm_24_2.get_heading(), Some("Vjezd krále"));
+    assert_eq!(
+        psalm_24_2.get_verses()[0].1,
+        "Neboť on ji založil na mořích."
+    );
+}
+
+#[tokio::test]
+async fn versification_matches_reference_translation() {
+    let db = setup_db_with_bible().await;
+
+    let chapters = query!(
+        r#"SELECT book_id AS "book_id!", chapter AS "chapter!", MAX(number) AS "last_verse!: i64"
+        FROM verses GROUP BY book_id, chapter"#
+    )
+    .fetch_all(&db)
+    .await
+    .unwrap();
+
+    for chapter in &chapters {
+        let book = Book::try_from(chapter.book_id as u8).unwrap();
+        let verses = verses_in_chapter(book, chapter.chapter as u8);
+        assert!(
+            verses.is_some_and(|verses| verses.contains(&(chapter.last_verse as u8))),
+            "{book} {}:{} z překladu nelze vybrat",
+            chapter.chapter,
+            chapter.last_verse
+        );
+    }
+
+    // Tabulka nemá žádné kapitoly navíc (referenční překlad dělí i Malachiáše na 4 kapitoly)
+    let num_chapters: usize = (0..66)
+        .map(|book| chapters_in_book(Book::try_from(book).unwrap()).count())
+        .sum();
+    assert_eq!(num_chapters, chapters.len());
+}
+
+#[tokio::test]
+async fn verse_counts_of_translation() {
+    let db = setup_db_with_bible().await;
+    let mut conn = db.acquire().await.unwrap();
+    let (translation_id, _) = get_available_translations(&mut conn).await.unwrap()[0];
+
+    let counts = get_verse_counts(translation_id, &mut conn).await.unwrap();
+
+    assert_eq!(counts.len(), 1190);
+    assert_eq!(counts.get(&(Book::John, 3)), Some(&36));
+    assert_eq!(counts.get(&(Book::Psalms, 117)), Some(&2));
+    assert_eq!(counts.get(&(Book::John, 22)), None);
+    assert!(
+        get_verse_counts(i64::MAX, &mut conn)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
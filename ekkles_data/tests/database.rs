@@ -0,0 +1,185 @@
+use std::env;
+
+use ekkles_data::database::{
+    MIGRATOR, checkpoint_and_close, open_database, open_or_create_database, overview,
+};
+use pretty_assertions::assert_eq;
+use sqlx::{SqlitePool, query_scalar, raw_sql};
+use tokio::fs::{File, remove_file};
+
+async fn applied_migrations(db: &SqlitePool) -> i64 {
+    query_scalar("SELECT COUNT(*) FROM _sqlx_migrations WHERE success")
+        .fetch_one(db)
+        .await
+        .unwrap()
+}
+
+async fn books(db: &SqlitePool) -> i64 {
+    query_scalar("SELECT COUNT(*) FROM books")
+        .fetch_one(db)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn new_database_is_migrated() {
+    let path = env::temp_dir().join(format!("ekkles-new-db-{}.sqlite3", std::process::id()));
+    let _ = remove_file(&path).await;
+
+    let db = open_or_create_database(&path).await.unwrap();
+    assert_eq!(
+        applied_migrations(&db).await,
+        MIGRATOR.iter().count() as i64
+    );
+    assert_eq!(books(&db).await, 66);
+    db.close().await;
+
+    // Opětovné otevření nic nového neaplikuje
+    let db = open_or_create_database(&path).await.unwrap();
+    assert_eq!(
+        applied_migrations(&db).await,
+        MIGRATOR.iter().count() as i64
+    );
+    assert_eq!(books(&db).await, 66);
+    db.close().await;
+
+    remove_file(&path).await.unwrap();
+}
+
+#[tokio::test]
+async fn database_created_before_migrations_is_migrated() {
+    let path = env::temp_dir().join(format!("ekkles-old-db-{}.sqlite3", std::process::id()));
+    File::create(&path).await.unwrap();
+
+    // Databáze vytvořená jednorázovým skriptem (schéma před zavedením migrací),
+    // bez záznamu o migracích
+    let db = open_database(&path).await.unwrap();
+    raw_sql(include_str!("data/init_db.sql"))
+        .execute(&db)
+        .await
+        .unwrap();
+    raw_sql(
+        "INSERT INTO songs (title, part_order) VALUES ('Stará píseň', 'V1');
+        INSERT INTO playlists (id, name) VALUES (1, 'Stará bohoslužba');
+        INSERT INTO playlist_parts (playlist_id, part_order, kind) VALUES (1, 0, 'song');
+        INSERT INTO playlist_songs (playlist_id, part_order, song_id) VALUES (1, 0, 1);",
+    )
+    .execute(&db)
+    .await
+    .unwrap();
+    db.close().await;
+
+    let db = open_or_create_database(&path).await.unwrap();
+    let songs: i64 = query_scalar("SELECT COUNT(*) FROM songs")
+        .fetch_one(&db)
+        .await
+        .unwrap();
+
+    let items: i64 = query_scalar("SELECT COUNT(*) FROM playlist_parts WHERE enabled")
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    let first_book: String = query_scalar("SELECT osis_id FROM books WHERE book_order = 0")
+        .fetch_one(&db)
+        .await
+        .unwrap();
+
+    assert_eq!(songs, 1);
+    assert_eq!(items, 1);
+    assert_eq!(books(&db).await, 66);
+    assert_eq!(first_book, "Gen");
+    assert_eq!(
+        applied_migrations(&db).await,
+        MIGRATOR.iter().count() as i64
+    );
+    db.close().await;
+
+    remove_file(&path).await.unwrap();
+}
+
+#[tokio::test]
+async fn overview_reports_schema_version_and_counts() {
+    let path = env::temp_dir().join(format!("ekkles-overview-{}.sqlite3", std::process::id()));
+    let _ = remove_file(&path).await;
+    let db = open_or_create_database(&path).await.unwrap();
+
+    sqlx::query("INSERT INTO songs (title, part_order) VALUES ('Píseň', 'V1')")
+        .execute(&db)
+        .await
+        .unwrap();
+
+    let overview = overview(&db).await.unwrap();
+
+    assert_eq!(overview.schema_version, overview.latest_schema_version);
+    assert_eq!(
+        overview.latest_schema_version,
+        MIGRATOR.iter().map(|migration| migration.version).max()
+    );
+    assert_eq!(overview.songs, 1);
+    assert_eq!(overview.playlists, 0);
+
+    checkpoint_and_close(db).await.unwrap();
+    remove_file(&path).await.unwrap();
+}
+
+#[tokio::test]
+async fn foreign_keys_and_wal_are_enabled() {
+    let path = env::temp_dir().join(format!("ekkles-pragmas-{}.sqlite3", std::process::id()));
+    let _ = remove_file(&path).await;
+    let db = open_or_create_database(&path).await.unwrap();
+
+    let foreign_keys: i64 = query_scalar("PRAGMA foreign_keys")
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    let journal_mode: String = query_scalar("PRAGMA journal_mode")
+        .fetch_one(&db)
+        .await
+        .unwrap();
+
+    assert_eq!(foreign_keys, 1);
+    assert_eq!(journal_mode, "wal");
+
+    checkpoint_and_close(db).await.unwrap();
+    remove_file(&path).await.unwrap();
+}
+
+#[tokio::test]
+async fn deleting_rows_cascades() {
+    let path = env::temp_dir().join(format!("ekkles-cascade-{}.sqlite3", std::process::id()));
+    let _ = remove_file(&path).await;
+    let db = open_or_create_database(&path).await.unwrap();
+
+    raw_sql(
+        "INSERT INTO songs (id, title, part_order) VALUES (1, 'Píseň', 'V1 C');
+        INSERT INTO song_parts (song_id, tag, lyrics) VALUES (1, 'V1', 'Sloka'), (1, 'C', 'Refrén');
+        INSERT INTO playlists (id, name) VALUES (1, 'Bohoslužba');
+        INSERT INTO playlist_parts (playlist_id, part_order, kind) VALUES (1, 0, 'song');
+        INSERT INTO playlist_songs (playlist_id, part_order, song_id) VALUES (1, 0, 1);",
+    )
+    .execute(&db)
+    .await
+    .unwrap();
+
+    let count = |table: &str| {
+        let db = db.clone();
+        let sql = format!("SELECT COUNT(*) FROM {table}");
+        async move { query_scalar::<_, i64>(&sql).fetch_one(&db).await.unwrap() }
+    };
+
+    raw_sql("DELETE FROM playlists WHERE id = 1")
+        .execute(&db)
+        .await
+        .unwrap();
+    assert_eq!(count("playlist_parts").await, 0);
+    assert_eq!(count("playlist_songs").await, 0);
+
+    raw_sql("DELETE FROM songs WHERE id = 1")
+        .execute(&db)
+        .await
+        .unwrap();
+    assert_eq!(count("song_parts").await, 0);
+
+    checkpoint_and_close(db).await.unwrap();
+    remove_file(&path).await.unwrap();
+}
@@ -1,21 +1,58 @@
 // Jaké případy užití otestujeme:
 //  - Vytvoření nového playlistu a jeho uložení (prázdný playlist)
-//  - Vytvoření nového playlistu, modifikace (všeho druhu) a jeho uložení
+//  - Vytvoření nového playlistu, modifikace (všeho druhu) a jeho uložení, uložený playlist
+//    projde kontrolou zápisu
+//  - Smazání playlistu podle ID bez jeho načtení (z výběru playlistů)
+//  - Seznam playlistů pro výběr obsahuje čas vytvoření a počet položek
 //  - Načtení existujícího playlistu, jeho úprava a opětovné uložení
+//  - Tóniny písní se uloží a při přesunu položek se přesouvají s nimi
+//  - Vlastní pořadí částí písně se uloží a prezentace podle něj tvoří pořadí písně, pořadí
+//    s neexistující částí nelze prezentovat
+//  - Světelná cue položek se uloží, přesouvají s položkami a načtou i pro prezentaci
+//  - Vypnuté položky zůstanou v playlistu, stav se uloží, přesouvá s položkami a načte
+//    i pro prezentaci, do odhadované délky se nezapočítávají
+//  - Vlastní texty se uloží, upraví a načtou i pro prezentaci
+//  - Playlist vložený jako blok se pro prezentaci rozbalí na své položky, cyklus bloků
+//    nelze uložit a playlist použitý jako blok nelze smazat
+//  - Hromadně načtené písně (i opakované a s překladem) a pasáže pro prezentaci odpovídají
+//    jednotlivě načteným
+//  - Obrázky z úložiště médií se uloží a pro prezentaci se načtou i s daty
+//  - Obrázek na pozadí motivu playlistu se pro prezentaci načte, po smazání média zmizí
+//  - Role lidí na bohoslužbě se uloží, přepíší a nabízejí se pro doplňování
+//  - Pozvánka na bohoslužbu obsahuje program (písně s tóninou) a role
+//  - Promítnuté písně se započítají do přehledu používání písní (CSV export)
+//  - Naposledy použité (i jen zařazené) a nejpoužívanější písně pro rychlý výběr
+//  - Hlášení CCLI obsahuje jen písně s CCLI číslem použité ve sledovaném období, použití
+//    písní zůstanou zachována i po smazání playlistu
+//  - Export playlistu do balíčku a jeho import do jiné databáze (písně se přenesou s ním)
+//  - Export celé databáze do archivu (písně v XML, playlisty, odkazy na pasáže)
+//  - Úpravy slajdů opětovné uložení playlistu přežijí, přesouvají se s položkami a se
+//    smazanou položkou zmizí
 //
 // TODO: - chce to další funkce pro songs, chcu umět hleda písně, aby to vracelo třá vektor (název, id)
 
 mod common;
+use chrono::{Days, Local, NaiveDate};
 use ekkles_data::{
     Song,
+    archive::{ArchiveSummary, export_archive},
     bible::{
         self, get_available_translations,
         indexing::{Book, VerseIndex},
     },
-    playlist::{PlaylistItemMetadata, PlaylistMetadata, PlaylistMetadataStatus},
+    media,
+    playlist::{
+        self as playlist_db, ItemOrigin, Playlist, PlaylistItem, PlaylistItemMetadata,
+        PlaylistMetadata, PlaylistMetadataStatus, reminder::ServiceReminder,
+    },
+    presentation_log::PresentationLog,
+    reports::{self, Report, ReportPeriod},
+    slide_override::{self, SlideAlignment, SlideOverride},
+    theme::{BackgroundImage, SlideTheme},
 };
 use pretty_assertions::assert_eq;
 use sqlx::query;
+use std::{collections::HashMap, env};
 
 #[tokio::test]
 async fn save_empty() {
@@ -74,6 +111,10 @@ async fn save_modified() {
             .await
             .unwrap();
         assert_eq!(loaded_playlist, playlist);
+        playlist
+            .verify_saved(&mut pool.acquire().await.unwrap())
+            .await
+            .unwrap();
     } else {
         panic!();
     }
@@ -144,6 +185,101 @@ async fn delete_playlist() {
     assert!(passages.is_empty());
 }
 
+#[tokio::test]
+async fn available_playlists_with_item_counts() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+
+    let mut empty = PlaylistMetadata::new("Prázdný playlist");
+    empty
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let mut announcements = PlaylistMetadata::new("Oznámení");
+    announcements.push_custom_text("Oznámení", "Vítejte");
+    announcements.push_custom_text("Sbírka", "Děkujeme");
+    announcements
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let available = playlist_db::get_available(pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let counts: Vec<(&str, usize)> = available
+        .iter()
+        .filter(|info| ["Prázdný playlist", "Oznámení"].contains(&info.name.as_str()))
+        .map(|info| (info.name.as_str(), info.num_items))
+        .collect();
+
+    assert_eq!(counts, vec![("Prázdný playlist", 0), ("Oznámení", 2)]);
+    // Čas vytvoření se zaokrouhluje na celé sekundy, může tedy být až o půl sekundy napřed
+    let latest = chrono::Utc::now() + chrono::TimeDelta::seconds(1);
+    assert!(
+        available.iter().all(|info| info.created <= latest),
+        "Čas vytvoření se musí načíst z databáze"
+    );
+}
+
+#[tokio::test]
+async fn delete_playlist_by_id() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+
+    let mut playlist = PlaylistMetadata::new("Starý playlist");
+    playlist.push_custom_text("Oznámení", "Vítejte");
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let PlaylistMetadataStatus::Clean(id) = playlist.get_status() else {
+        panic!("Playlist není po uložení ve stavu clean");
+    };
+
+    playlist_db::delete_by_id(id, &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    assert!(
+        playlist_db::is_name_available(pool.acquire().await.unwrap(), "Starý playlist")
+            .await
+            .unwrap()
+    );
+    let items = query!("SELECT * FROM playlist_parts WHERE playlist_id = $1", id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert!(items.is_empty());
+
+    // Již smazaný playlist smazat nelze
+    assert!(
+        playlist_db::delete_by_id(id, &mut pool.acquire().await.unwrap())
+            .await
+            .unwrap_err()
+            .is_not_found()
+    );
+}
+
+#[tokio::test]
+async fn taken_playlist_name_is_conflict() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+
+    let mut playlist = PlaylistMetadata::new("Neděle");
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let err = PlaylistMetadata::new("Neděle")
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap_err();
+    assert!(err.is_conflict(), "{err:?}");
+
+    let err = PlaylistMetadata::load(i64::MAX, pool.acquire().await.unwrap())
+        .await
+        .unwrap_err();
+    assert!(err.is_not_found(), "{err:?}");
+}
+
 #[tokio::test]
 async fn delete_item() {
     let pool = common::setup_db_with_bible_and_songs().await;
@@ -194,14 +330,15 @@ async fn delete_item() {
         .await
         .unwrap();
 
-    let items = loaded_playlist.get_items();
+    let items: Vec<_> = loaded_playlist.get_items().cloned().collect();
 
     assert_eq!(
         items,
         &[PlaylistItemMetadata::BiblePassage {
             translation_id,
             from: VerseIndex::try_new(Book::John, 1, 1).unwrap(),
-            to: VerseIndex::try_new(Book::John, 1, 1).unwrap()
+            to: VerseIndex::try_new(Book::John, 1, 1).unwrap(),
+            slide_breaks: None,
         }]
     );
 
@@ -263,7 +400,7 @@ async fn swap_items() {
         .await
         .unwrap();
 
-    let items = loaded_playlist.get_items();
+    let items: Vec<_> = loaded_playlist.get_items().cloned().collect();
 
     assert_eq!(
         items,
@@ -271,9 +408,1077 @@ async fn swap_items() {
             PlaylistItemMetadata::BiblePassage {
                 translation_id,
                 from: VerseIndex::try_new(Book::John, 1, 1).unwrap(),
-                to: VerseIndex::try_new(Book::John, 1, 1).unwrap()
+                to: VerseIndex::try_new(Book::John, 1, 1).unwrap(),
+                slide_breaks: None,
             },
             PlaylistItemMetadata::Song(song_id)
         ]
     );
 }
+
+#[tokio::test]
+async fn slide_overrides_survive_saves() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+    let mut conn = pool.acquire().await.unwrap();
+
+    let songs = Song::get_available_from_db(&mut conn).await.unwrap();
+    let mut playlist = PlaylistMetadata::new("Playlist s úpravami slajdů");
+    playlist.push_song(songs[0].0);
+    playlist.push_song(songs[1].0);
+    playlist.push_song(songs[0].0);
+    playlist.save(&mut conn).await.unwrap();
+
+    let id = if let PlaylistMetadataStatus::Clean(id) = playlist.get_status() {
+        id
+    } else {
+        panic!("Playlist není po uložení ve stavu clean");
+    };
+
+    let bigger = SlideOverride {
+        text_scale: Some(150),
+        ..Default::default()
+    };
+    let centered = SlideOverride {
+        alignment: Some(SlideAlignment::Center),
+        ..Default::default()
+    };
+    slide_override::save(id, 0, 1, &bigger, &mut conn)
+        .await
+        .unwrap();
+    slide_override::save(id, 2, 0, &centered, &mut conn)
+        .await
+        .unwrap();
+
+    // Úprava, která položky nepřesouvá, nechá úpravy slajdů na místě (i po dalším uložení)
+    for duration in [240, 300] {
+        playlist.set_expected_duration(0, Some(duration)).unwrap();
+        playlist.save(&mut conn).await.unwrap();
+
+        assert_eq!(
+            slide_override::load_for_playlist(id, &mut conn)
+                .await
+                .unwrap(),
+            HashMap::from([((0, 1), bigger.clone()), ((2, 0), centered.clone())])
+        );
+    }
+
+    // Úpravy se přesouvají s položkami, se smazanou položkou zmizí
+    playlist.swap_items(0, 2).unwrap();
+    playlist.delete_item(1).unwrap();
+    playlist.save(&mut conn).await.unwrap();
+
+    assert_eq!(
+        slide_override::load_for_playlist(id, &mut conn)
+            .await
+            .unwrap(),
+        HashMap::from([((0, 0), centered), ((1, 1), bigger)])
+    );
+}
+
+#[tokio::test]
+async fn replaced_song_keeps_overrides() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+    let mut conn = pool.acquire().await.unwrap();
+
+    let songs = Song::get_available_from_db(&mut conn).await.unwrap();
+    let new_song = Song::load_from_db(songs[1].0, &mut conn).await.unwrap();
+    let kept_tag = new_song.order[0].clone();
+
+    let mut playlist = PlaylistMetadata::new("Playlist s nahrazenou písní");
+    playlist.push_song(songs[0].0);
+    playlist.set_song_key(0, Some("E")).unwrap();
+    playlist
+        .set_arrangement(0, Some(vec![kept_tag.clone(), String::from("NEEXISTUJE")]))
+        .unwrap();
+    playlist.save(&mut conn).await.unwrap();
+
+    let id = if let PlaylistMetadataStatus::Clean(id) = playlist.get_status() {
+        id
+    } else {
+        panic!("Playlist není po uložení ve stavu clean");
+    };
+
+    let bigger = SlideOverride {
+        text_scale: Some(150),
+        ..Default::default()
+    };
+    slide_override::save(id, 0, 0, &bigger, &mut conn)
+        .await
+        .unwrap();
+
+    playlist.replace_song(0, songs[1].0, &new_song).unwrap();
+    playlist.save(&mut conn).await.unwrap();
+
+    let loaded = PlaylistMetadata::load(id, pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        *loaded.get_item(0).unwrap(),
+        PlaylistItemMetadata::Song(songs[1].0)
+    );
+    assert_eq!(loaded.get_song_key(0), Some("E"));
+    assert_eq!(
+        loaded.get_arrangement(0),
+        Some([kept_tag].as_slice()),
+        "Části, které nová píseň nemá, se z pořadí vypustí"
+    );
+    assert_eq!(
+        slide_override::load_for_playlist(id, &mut conn)
+            .await
+            .unwrap(),
+        HashMap::from([((0, 0), bigger)])
+    );
+}
+
+#[tokio::test]
+async fn song_keys() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+
+    let mut playlist = PlaylistMetadata::new("Testovací playlist");
+
+    let song_id = Song::get_available_from_db(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap()
+        .first()
+        .unwrap()
+        .0;
+    let translation_id = get_available_translations(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap()
+        .first()
+        .unwrap()
+        .0;
+
+    playlist.push_song(song_id);
+    playlist.push_bible_passage(
+        translation_id,
+        VerseIndex::try_new(Book::John, 1, 1).unwrap(),
+        VerseIndex::try_new(Book::John, 1, 1).unwrap(),
+    );
+
+    assert!(playlist.set_song_key(0, Some("H#")).is_err());
+    assert!(playlist.set_song_key(1, Some("G")).is_err());
+    playlist.set_song_key(0, Some("F#m")).unwrap();
+
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let id = if let PlaylistMetadataStatus::Clean(id) = playlist.get_status() {
+        id
+    } else {
+        panic!("Playlist není po uložení ve stavu clean");
+    };
+
+    // Tónina se musí při prohození přesunout spolu s písní
+    playlist.swap_items(0, 1).unwrap();
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let loaded_playlist = PlaylistMetadata::load(id, pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(loaded_playlist.get_song_key(0), None);
+    assert_eq!(loaded_playlist.get_song_key(1), Some("F#m"));
+    assert_eq!(loaded_playlist, playlist);
+}
+
+#[tokio::test]
+async fn song_arrangements() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+
+    let song_id = Song::get_available_from_db(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap()
+        .first()
+        .unwrap()
+        .0;
+    let song = Song::load_from_db(song_id, &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let mut tags: Vec<String> = song.parts.keys().cloned().collect();
+    tags.sort();
+    let arrangement = vec![tags[0].clone(), tags[1].clone(), tags[0].clone()];
+
+    let mut playlist = PlaylistMetadata::new("Testovací playlist");
+    playlist.push_song(song_id);
+    playlist.push_custom_text("Oznámení", "Po bohoslužbě je oběd");
+    playlist.push_song(song_id);
+    playlist
+        .set_arrangement(2, Some(arrangement.clone()))
+        .unwrap();
+
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let id = if let PlaylistMetadataStatus::Clean(id) = playlist.get_status() {
+        id
+    } else {
+        panic!("Playlist není po uložení ve stavu clean");
+    };
+
+    // Pořadí se musí při prohození přesunout spolu s písní
+    playlist.swap_items(1, 2).unwrap();
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let loaded_playlist = PlaylistMetadata::load(id, pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        loaded_playlist.get_arrangement(1),
+        Some(arrangement.as_slice())
+    );
+    assert_eq!(loaded_playlist.get_arrangement(0), None);
+    assert_eq!(loaded_playlist, playlist);
+
+    let presented = Playlist::load(id, &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let orders: Vec<&[String]> = presented
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            PlaylistItem::Song(song) => Some(song.order.as_slice()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(orders, vec![song.order.as_slice(), arrangement.as_slice()]);
+
+    // Část mohla být z písně mezitím odebrána, taková prezentace se nenačte
+    playlist
+        .set_arrangement(0, Some(vec![String::from("Neexistuje")]))
+        .unwrap();
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    assert!(
+        Playlist::load(id, &mut pool.acquire().await.unwrap())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn lighting_cues() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+
+    let mut playlist = PlaylistMetadata::new("Testovací playlist");
+
+    playlist.push_custom_text("Uvítání", "Vítejte na bohoslužbě");
+    playlist.push_custom_text("Kázání", "Jan 3,16");
+
+    assert!(playlist.set_lighting_cue(1, Some(0)).is_err());
+    assert!(playlist.set_lighting_cue(2, Some(5)).is_err());
+    playlist.set_lighting_cue(1, Some(5)).unwrap();
+
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let id = if let PlaylistMetadataStatus::Clean(id) = playlist.get_status() {
+        id
+    } else {
+        panic!("Playlist není po uložení ve stavu clean");
+    };
+
+    // Cue se musí při prohození přesunout spolu s položkou
+    playlist.swap_items(0, 1).unwrap();
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let loaded_playlist = PlaylistMetadata::load(id, pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(loaded_playlist.get_lighting_cue(0), Some(5));
+    assert_eq!(loaded_playlist.get_lighting_cue(1), None);
+    assert_eq!(loaded_playlist, playlist);
+
+    let presented = Playlist::load(id, &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(presented.lighting_cues, vec![Some(5), None]);
+}
+
+#[tokio::test]
+async fn disabled_items() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+
+    let mut playlist = PlaylistMetadata::new("Testovací playlist");
+
+    playlist.push_custom_text("Uvítání", "Vítejte na bohoslužbě");
+    playlist.push_custom_text("Píseň navíc", "Zazpíváme, pokud zbude čas");
+    playlist.set_expected_duration(0, Some(60)).unwrap();
+    playlist.set_expected_duration(1, Some(240)).unwrap();
+
+    assert!(playlist.set_item_enabled(2, false).is_err());
+    playlist.set_item_enabled(1, false).unwrap();
+    assert_eq!(playlist.get_total_expected_duration(), (60, 0));
+
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let id = if let PlaylistMetadataStatus::Clean(id) = playlist.get_status() {
+        id
+    } else {
+        panic!("Playlist není po uložení ve stavu clean");
+    };
+
+    // Stav se musí při prohození přesunout spolu s položkou
+    playlist.swap_items(0, 1).unwrap();
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let loaded_playlist = PlaylistMetadata::load(id, pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(loaded_playlist.get_items().len(), 2);
+    assert!(!loaded_playlist.is_item_enabled(0));
+    assert!(loaded_playlist.is_item_enabled(1));
+    assert_eq!(loaded_playlist, playlist);
+
+    let presented = Playlist::load(id, &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(presented.enabled, vec![false, true]);
+}
+
+#[tokio::test]
+async fn custom_texts() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+
+    let mut playlist = PlaylistMetadata::new("Testovací playlist");
+
+    let song_id = Song::get_available_from_db(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap()
+        .first()
+        .unwrap()
+        .0;
+
+    playlist.push_custom_text("Uvítání", "Vítejte na bohoslužbě");
+    playlist.push_song(song_id);
+    playlist.push_custom_text("Oznámení", "Po bohoslužbě je kafe");
+
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let id = if let PlaylistMetadataStatus::Clean(id) = playlist.get_status() {
+        id
+    } else {
+        panic!("Playlist není po uložení ve stavu clean");
+    };
+
+    // Úprava a přesun textu se musí uložit i u již uloženého playlistu
+    playlist
+        .replace_custom_text(2, "Oznámení", "Po bohoslužbě je oběd")
+        .unwrap();
+    playlist.swap_items(1, 2).unwrap();
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let loaded_playlist = PlaylistMetadata::load(id, pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        loaded_playlist.get_items().cloned().collect::<Vec<_>>(),
+        &[
+            PlaylistItemMetadata::CustomText {
+                title: String::from("Uvítání"),
+                body: String::from("Vítejte na bohoslužbě"),
+            },
+            PlaylistItemMetadata::CustomText {
+                title: String::from("Oznámení"),
+                body: String::from("Po bohoslužbě je oběd"),
+            },
+            PlaylistItemMetadata::Song(song_id),
+        ]
+    );
+
+    let presented = Playlist::load(id, &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        &presented.items[1],
+        PlaylistItem::CustomText { body, .. } if body == "Po bohoslužbě je oběd"
+    ));
+    assert_eq!(presented.item_themes.len(), 3);
+    assert_eq!(presented.song_keys[1], None);
+}
+
+#[tokio::test]
+async fn blocks() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+
+    let mut block = PlaylistMetadata::new("Večeře Páně");
+    block.push_custom_text("Ustanovení", "Pán Ježíš v noci, kdy byl zrazen");
+    block.push_custom_text("Modlitba", "Otče náš");
+    block
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let PlaylistMetadataStatus::Clean(block_id) = block.get_status() else {
+        panic!("Playlist není po uložení ve stavu clean");
+    };
+
+    let mut playlist = PlaylistMetadata::new("Neděle");
+    playlist.push_custom_text("Uvítání", "Vítejte na bohoslužbě");
+    playlist.push_block(block_id);
+    playlist.push_custom_text("Oznámení", "Po bohoslužbě je kafe");
+    playlist.set_lighting_cue(1, Some(7)).unwrap();
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let PlaylistMetadataStatus::Clean(id) = playlist.get_status() else {
+        panic!("Playlist není po uložení ve stavu clean");
+    };
+
+    let loaded_playlist = PlaylistMetadata::load(id, pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(loaded_playlist, playlist);
+
+    let expanded = loaded_playlist
+        .expand_blocks(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(expanded.get_items().len(), 4);
+    assert_eq!(expanded.get_item(1), block.get_item(0));
+
+    let presented = Playlist::load(id, &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    assert!(matches!(
+        &presented.items[2],
+        PlaylistItem::CustomText { body, .. } if body == "Otče náš"
+    ));
+    // Cue bloku se odešle s jeho první položkou
+    assert_eq!(presented.lighting_cues, vec![None, Some(7), None, None]);
+    let in_block = |part_order| ItemOrigin {
+        position: 1,
+        playlist_id: block_id,
+        part_order,
+        block: Some(String::from("Večeře Páně")),
+    };
+    assert_eq!(
+        presented.item_origins,
+        vec![
+            ItemOrigin {
+                position: 0,
+                playlist_id: id,
+                part_order: 0,
+                block: None,
+            },
+            in_block(0),
+            in_block(1),
+            ItemOrigin {
+                position: 2,
+                playlist_id: id,
+                part_order: 2,
+                block: None,
+            },
+        ]
+    );
+
+    // Playlist obsahující blok se do bloku vložit nenabízí a nelze jej tam ani uložit
+    let options =
+        playlist_db::get_block_options(Some(block_id), &mut pool.acquire().await.unwrap())
+            .await
+            .unwrap();
+    assert!(
+        options
+            .iter()
+            .all(|(option_id, _)| ![id, block_id].contains(option_id))
+    );
+    let options = playlist_db::get_block_options(Some(id), &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    assert!(options.contains(&(block_id, String::from("Večeře Páně"))));
+
+    block.push_block(id);
+    assert!(
+        block
+            .save(&mut pool.acquire().await.unwrap())
+            .await
+            .is_err()
+    );
+
+    // Blok použitý v playlistu nelze smazat, po smazání playlistu už ano
+    assert!(
+        playlist_db::delete_by_id(block_id, &mut pool.acquire().await.unwrap())
+            .await
+            .unwrap_err()
+            .is_conflict()
+    );
+    playlist_db::delete_by_id(id, &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    playlist_db::delete_by_id(block_id, &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn presentation_matches_items_loaded_one_by_one() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+
+    let songs = Song::get_available_from_db(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let (song_id, translated_song_id) = (songs[0].0, songs[1].0);
+    Song::set_translation_id(
+        song_id,
+        Some(translated_song_id),
+        &mut pool.acquire().await.unwrap(),
+    )
+    .await
+    .unwrap();
+    let translation_id = get_available_translations(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap()
+        .first()
+        .unwrap()
+        .0;
+    let (from, to) = (
+        VerseIndex::try_new(Book::John, 1, 1).unwrap(),
+        VerseIndex::try_new(Book::John, 1, 5).unwrap(),
+    );
+
+    let mut playlist = PlaylistMetadata::new("Dlouhý playlist");
+    playlist.push_song(song_id);
+    playlist.push_bible_passage(translation_id, from, to);
+    playlist.push_song(translated_song_id);
+    playlist.push_song(song_id);
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let PlaylistMetadataStatus::Clean(id) = playlist.get_status() else {
+        panic!("Playlist by měl být po uložení čistý");
+    };
+
+    let mut conn = pool.acquire().await.unwrap();
+    let presented = Playlist::load(id, &mut conn).await.unwrap();
+    let song = Song::load_from_db(song_id, &mut conn).await.unwrap();
+    let translated_song = Song::load_from_db(translated_song_id, &mut conn)
+        .await
+        .unwrap();
+    let passage = bible::indexing::Passage::load(from, to, translation_id, &mut conn)
+        .await
+        .unwrap();
+
+    assert_eq!(presented.items.len(), 4);
+    assert!(matches!(&presented.items[0], PlaylistItem::Song(loaded) if *loaded == song));
+    assert!(
+        matches!(&presented.items[2], PlaylistItem::Song(loaded) if *loaded == translated_song)
+    );
+    assert!(matches!(&presented.items[3], PlaylistItem::Song(loaded) if *loaded == song));
+    let PlaylistItem::BiblePassage(loaded_passage) = &presented.items[1] else {
+        panic!("Druhá položka by měla být pasáž");
+    };
+    assert_eq!(loaded_passage.get_verses(), passage.get_verses());
+    assert_eq!(loaded_passage.get_heading(), passage.get_heading());
+    assert_eq!(
+        presented.song_translations,
+        vec![
+            Some(translated_song.clone()),
+            None,
+            None,
+            Some(translated_song)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn images() {
+    let pool = common::setup_bare_db().await;
+
+    let media_id = query!(
+        "INSERT INTO media (name, original, optimized, width, height) VALUES ('Téma kázání', x'89504e47', x'ffd8ffe0', 1, 1)"
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    let mut playlist = PlaylistMetadata::new("Kázání");
+    playlist.push_custom_text("Uvítání", "Vítejte");
+    playlist.add_image(media_id, 0);
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let PlaylistMetadataStatus::Clean(id) = playlist.get_status() else {
+        panic!("Playlist by měl být po uložení čistý");
+    };
+
+    let loaded_playlist = PlaylistMetadata::load(id, pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        *loaded_playlist.get_item(0).unwrap(),
+        PlaylistItemMetadata::Image(media_id)
+    );
+
+    let presented = Playlist::load(id, &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        &presented.items[0],
+        PlaylistItem::Image { media_id: image_id, name, data }
+            if *image_id == media_id && name == "Téma kázání" && data == &[0xff, 0xd8, 0xff, 0xe0]
+    ));
+    assert_eq!(presented.item_themes.len(), 2);
+}
+
+#[tokio::test]
+async fn theme_background_image() {
+    let pool = common::setup_bare_db().await;
+
+    let media_id = query!(
+        "INSERT INTO media (name, original, optimized, width, height) VALUES ('Západ slunce', x'89504e47', x'ffd8ffe0', 1, 1)"
+    )
+    .execute(&pool)
+    .await
+    .unwrap()
+    .last_insert_rowid();
+
+    let background_image = Some(BackgroundImage {
+        media_id,
+        dimming: 40,
+    });
+    let theme_id = SlideTheme {
+        name: String::from("Západ"),
+        text_color: [0xff, 0xff, 0xff],
+        background_color: [0x00, 0x00, 0x00],
+        background_image,
+    }
+    .save_to_db(&pool)
+    .await
+    .unwrap();
+
+    let mut playlist = PlaylistMetadata::new("Večerní chvály");
+    playlist.push_custom_text("Uvítání", "Vítejte");
+    playlist.set_theme_id(Some(theme_id));
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let PlaylistMetadataStatus::Clean(id) = playlist.get_status() else {
+        panic!("Playlist by měl být po uložení čistý");
+    };
+
+    let presented = Playlist::load(id, &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        presented.theme_for_item(0).unwrap().background_image,
+        background_image
+    );
+    assert_eq!(
+        presented.backgrounds.get(&media_id),
+        Some(&vec![0xff, 0xd8, 0xff, 0xe0])
+    );
+
+    // Motivu po smazání obrázku zůstane pouze barva pozadí
+    media::delete(media_id, &pool).await.unwrap();
+    let presented = Playlist::load(id, &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(presented.theme_for_item(0).unwrap().background_image, None);
+    assert!(presented.backgrounds.is_empty());
+}
+
+#[tokio::test]
+async fn service_roles() {
+    let pool = common::setup_bare_db().await;
+
+    let mut playlist = PlaylistMetadata::new("Neděle");
+    assert!(playlist.set_role("Kuchař", Some("Jan")).is_err());
+    playlist.set_role("Zvuk", Some(" Petr ")).unwrap();
+    playlist.set_role("Kazatel", Some("Jan Novák")).unwrap();
+    playlist.set_role("Projekce", Some("")).unwrap();
+
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let PlaylistMetadataStatus::Clean(id) = playlist.get_status() else {
+        panic!("Playlist není po uložení ve stavu clean");
+    };
+
+    let mut loaded_playlist = PlaylistMetadata::load(id, pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        loaded_playlist.get_roles(),
+        vec![("Kazatel", "Jan Novák"), ("Zvuk", "Petr")]
+    );
+
+    loaded_playlist.set_role("Zvuk", None).unwrap();
+    loaded_playlist.set_role("Projekce", Some("Marie")).unwrap();
+    loaded_playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let mut conn = pool.acquire().await.unwrap();
+    let all_roles = playlist_db::get_all_roles(&mut conn).await.unwrap();
+    assert_eq!(
+        all_roles[&id],
+        vec![
+            (String::from("Kazatel"), String::from("Jan Novák")),
+            (String::from("Projekce"), String::from("Marie")),
+        ]
+    );
+    assert_eq!(
+        playlist_db::get_known_people(&mut conn).await.unwrap(),
+        vec![String::from("Jan Novák"), String::from("Marie")]
+    );
+}
+
+#[tokio::test]
+async fn service_reminder() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+    let songs = Song::get_available_from_db(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let (song_id, song_title) = songs[0].clone();
+
+    let mut playlist = PlaylistMetadata::new("Neděle");
+    playlist.push_song(song_id);
+    playlist.set_song_key(0, Some("G")).unwrap();
+    playlist.push_custom_text("Oznámení", "Po bohoslužbě je oběd");
+    playlist.set_role("Kazatel", Some("Jan Novák")).unwrap();
+
+    let start = NaiveDate::from_ymd_opt(2026, 10, 18)
+        .unwrap()
+        .and_hms_opt(9, 30, 0)
+        .unwrap();
+    let reminder =
+        ServiceReminder::for_playlist(&playlist, start, &mut pool.acquire().await.unwrap())
+            .await
+            .unwrap();
+
+    assert_eq!(
+        reminder.set_list,
+        vec![format!("{song_title} (G)"), String::from("Oznámení")]
+    );
+    assert_eq!(
+        reminder.roles,
+        vec![(String::from("Kazatel"), String::from("Jan Novák"))]
+    );
+    assert_eq!(reminder.default_file_name(), "bohosluzba_2026-10-18.ics");
+}
+
+#[tokio::test]
+async fn song_usage_report() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+    let songs = Song::get_available_from_db(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let (used_id, used_title) = songs[0].clone();
+    Song::set_ccli_number(used_id, Some(" 27824 "), &pool)
+        .await
+        .unwrap();
+
+    let mut playlist = PlaylistMetadata::new("Neděle, 1. 6.");
+    playlist.push_song(used_id);
+    playlist.push_song(used_id);
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let PlaylistMetadataStatus::Clean(id) = playlist.get_status() else {
+        panic!("Playlist není po uložení ve stavu clean");
+    };
+
+    // Návrat k první položce je stále jedno použití, druhá položka je další použití
+    let mut log = PresentationLog::new(id);
+    for part_order in [0, 1, 0] {
+        log.item_presented(part_order);
+        Song::record_usage(id, part_order, &pool).await.unwrap();
+    }
+    log.flush(&mut pool.acquire().await.unwrap()).await.unwrap();
+
+    let mut conn = pool.acquire().await.unwrap();
+    let usage = reports::load_song_usage(None, &mut conn).await.unwrap();
+    let used = usage.iter().find(|song| song.song_id == used_id).unwrap();
+    assert_eq!(used.times_used, 2);
+    assert_eq!(used.ccli_number.as_deref(), Some("27824"));
+    assert!(used.last_used.is_some());
+    let unused = usage
+        .iter()
+        .find(|song| song.song_id == songs[1].0)
+        .unwrap();
+    assert_eq!(unused.times_used, 0);
+    assert_eq!(unused.last_used, None);
+
+    let library = Report::SongLibrary.to_csv(&mut conn).await.unwrap();
+    assert_eq!(library.lines().count(), songs.len() + 1);
+    let usage_csv = Report::SongUsage.to_csv(&mut conn).await.unwrap();
+    let lines: Vec<_> = usage_csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].starts_with(&format!("{used_title},")));
+    assert!(lines[1].ends_with(",2"));
+
+    let log_csv = Report::PresentationLog.to_csv(&mut conn).await.unwrap();
+    let lines: Vec<_> = log_csv.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[1].starts_with(&format!("\"Neděle, 1. 6.\",1,{used_title},")));
+
+    // Záznam o prezentaci se nezmění nahrazením písně v playlistu
+    let new_song = Song::load_from_db(songs[1].0, &mut conn).await.unwrap();
+    playlist.replace_song(0, songs[1].0, &new_song).unwrap();
+    playlist.save(&mut conn).await.unwrap();
+    let log_csv = Report::PresentationLog.to_csv(&mut conn).await.unwrap();
+    assert!(
+        log_csv
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with(&format!("\"Neděle, 1. 6.\",1,{used_title},"))
+    );
+}
+
+#[tokio::test]
+async fn recently_and_most_used_songs() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+    let songs = Song::get_available_from_db(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let mut conn = pool.acquire().await.unwrap();
+    assert_eq!(Song::recently_used(5, &mut conn).await.unwrap(), vec![]);
+    assert_eq!(Song::most_used(5, &mut conn).await.unwrap(), vec![]);
+
+    // První píseň je použita ve dvou playlistech, druhá je pouze zařazena do playlistu
+    let mut ids = Vec::new();
+    for (name, song_ids) in [
+        ("Minulá neděle", vec![songs[0].0]),
+        ("Neděle", vec![songs[0].0, songs[1].0]),
+    ] {
+        let mut playlist = PlaylistMetadata::new(name);
+        for song_id in song_ids {
+            playlist.push_song(song_id);
+        }
+        playlist.save(&mut conn).await.unwrap();
+        let PlaylistMetadataStatus::Clean(id) = playlist.get_status() else {
+            panic!("Playlist není po uložení ve stavu clean");
+        };
+        ids.push(id);
+    }
+    Song::record_usage(ids[0], 0, &pool).await.unwrap();
+    Song::record_usage(ids[1], 0, &pool).await.unwrap();
+
+    let recent = Song::recently_used(5, &mut conn).await.unwrap();
+    let recent_ids: Vec<i64> = recent.iter().map(|(id, _)| *id).collect();
+    assert_eq!(recent_ids.len(), 2);
+    assert!(recent_ids.contains(&songs[0].0) && recent_ids.contains(&songs[1].0));
+    assert_eq!(Song::recently_used(1, &mut conn).await.unwrap().len(), 1);
+
+    assert_eq!(
+        Song::most_used(5, &mut conn).await.unwrap(),
+        vec![songs[0].clone()]
+    );
+}
+
+#[tokio::test]
+async fn ccli_report() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+    let songs = Song::get_available_from_db(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let (reported_id, reported_title) = songs[0].clone();
+    Song::set_ccli_number(reported_id, Some("27824"), &pool)
+        .await
+        .unwrap();
+
+    // Druhá píseň nemá CCLI číslo, do hlášení se tedy nedostane
+    let mut playlist = PlaylistMetadata::new("Neděle");
+    playlist.push_song(reported_id);
+    playlist.push_song(songs[1].0);
+    playlist
+        .save(&mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let PlaylistMetadataStatus::Clean(id) = playlist.get_status() else {
+        panic!("Playlist není po uložení ve stavu clean");
+    };
+
+    assert!(Song::record_usage(id, 0, &pool).await.unwrap());
+    assert!(Song::record_usage(id, 1, &pool).await.unwrap());
+    // Položka je za den zaznamenána nejvýše jednou
+    assert!(!Song::record_usage(id, 0, &pool).await.unwrap());
+
+    let mut conn = pool.acquire().await.unwrap();
+    let today = Local::now().date_naive();
+    let current = ReportPeriod::new(today - Days::new(1), today + Days::new(1)).unwrap();
+    assert_eq!(
+        Report::Ccli(current).to_csv(&mut conn).await.unwrap(),
+        format!("Song Title,CCLI Song Number,Times Used\n{reported_title},27824,1\n")
+    );
+
+    let past = ReportPeriod::new(today - Days::new(30), today - Days::new(2)).unwrap();
+    assert_eq!(
+        Report::Ccli(past).to_csv(&mut conn).await.unwrap(),
+        "Song Title,CCLI Song Number,Times Used\n"
+    );
+
+    // Použití zůstanou zachována i po smazání playlistu
+    playlist_db::delete_by_id(id, &mut conn).await.unwrap();
+    let usage = Song::usage_report(today, today, &mut conn).await.unwrap();
+    let titles: Vec<_> = usage.iter().map(|song| song.title.as_str()).collect();
+    assert_eq!(titles.len(), 2);
+    assert!(titles.contains(&reported_title.as_str()));
+    assert!(
+        Song::usage_report(today, today - Days::new(1), &mut conn)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn bundle_export_import() {
+    let source = common::setup_db_with_bible_and_songs().await;
+    let songs = Song::get_available_from_db(&mut source.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let translation_id = get_available_translations(&mut source.acquire().await.unwrap())
+        .await
+        .unwrap()
+        .first()
+        .unwrap()
+        .0;
+
+    let mut playlist = PlaylistMetadata::new("Neděle");
+    playlist.push_song(songs[0].0);
+    playlist.set_song_key(0, Some("A")).unwrap();
+    playlist.push_bible_passage(
+        translation_id,
+        VerseIndex::try_new(Book::John, 3, 16).unwrap(),
+        VerseIndex::try_new(Book::John, 3, 18).unwrap(),
+    );
+    playlist.push_custom_text("Oznámení", "Po bohoslužbě je oběd");
+    playlist.set_role("Kazatel", Some("Jan Novák")).unwrap();
+
+    let path = env::temp_dir().join(format!("ekkles-bundle-{}.json", std::process::id()));
+    playlist
+        .export_bundle(&path, &mut source.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    // V cílové databázi je jen bible, píseň se musí naimportovat z balíčku
+    let target = common::setup_db_with_bible().await;
+    let mut imported = PlaylistMetadata::import_bundle(&path, &target)
+        .await
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(imported.get_status(), PlaylistMetadataStatus::Transient);
+    assert_eq!(imported.get_name(), "Neděle");
+    assert_eq!(imported.get_song_key(0), Some("A"));
+    assert_eq!(imported.get_role("Kazatel"), Some("Jan Novák"));
+
+    let imported_songs = Song::get_available_from_db(&mut target.acquire().await.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(imported_songs.len(), 1);
+    assert_eq!(imported_songs[0].1, songs[0].1);
+    assert_eq!(
+        *imported.get_item(0).unwrap(),
+        PlaylistItemMetadata::Song(imported_songs[0].0)
+    );
+    assert_eq!(
+        imported.get_items().skip(1).collect::<Vec<_>>(),
+        playlist.get_items().skip(1).collect::<Vec<_>>()
+    );
+
+    imported
+        .save(&mut target.acquire().await.unwrap())
+        .await
+        .unwrap();
+    assert!(matches!(
+        imported.get_status(),
+        PlaylistMetadataStatus::Clean(_)
+    ));
+}
+
+#[tokio::test]
+async fn archive_export() {
+    let db = common::setup_db_with_bible_and_songs().await;
+    let songs = Song::get_available_from_db(&mut db.acquire().await.unwrap())
+        .await
+        .unwrap();
+    let translation_id = get_available_translations(&mut db.acquire().await.unwrap())
+        .await
+        .unwrap()
+        .first()
+        .unwrap()
+        .0;
+
+    let mut playlist = PlaylistMetadata::new("Neděle");
+    playlist.push_song(songs[0].0);
+    playlist.push_bible_passage(
+        translation_id,
+        VerseIndex::try_new(Book::John, 3, 16).unwrap(),
+        VerseIndex::try_new(Book::John, 3, 18).unwrap(),
+    );
+    playlist
+        .save(&mut db.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    let dir = env::temp_dir().join(format!("ekkles-archive-{}", std::process::id()));
+    let summary = export_archive(&dir, &db).await.unwrap();
+    assert_eq!(
+        summary,
+        ArchiveSummary {
+            songs: songs.len(),
+            playlists: 1,
+            translations: 1,
+        }
+    );
+
+    // Písně lze z archivu znovu zparsovat
+    for (id, title) in &songs {
+        let song =
+            Song::parse_from_xml_file(&dir.join("songs").join(format!("{title}.xml"))).unwrap();
+        let original = Song::load_from_db(*id, &mut db.acquire().await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(song, original);
+    }
+    assert!(dir.join("playlists").join("Neděle.json").is_file());
+    let passages = std::fs::read_to_string(dir.join("bible").join("passages.txt")).unwrap();
+    assert!(passages.starts_with("Neděle: Jan 3:16 - Jan 3:18 ("));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,6 +1,13 @@
 use std::collections::HashMap;
 
-use ekkles_data::Song;
+use ekkles_data::{
+    Song, SongMetadata,
+    author::get_authors_from_db,
+    playlist::PlaylistMetadata,
+    song_arrangement::SongArrangement,
+    song_diff::{ConflictResolution, ImportCheck, check_import},
+    songbook::{SongbookNumber, append_songbook_numbers},
+};
 
 mod common;
 
@@ -48,6 +55,7 @@ async fn save_load_happy_path() {
             String::from("V2a"),
             String::from("V2b"),
         ],
+        metadata: Default::default(),
     };
 
     let id = match song.save_to_db(&pool).await {
@@ -65,6 +73,33 @@ async fn save_load_happy_path() {
             panic!()
         }
     }
+    song.verify_saved(id, &pool).await.unwrap();
+}
+
+#[tokio::test]
+async fn save_load_metadata() {
+    let pool = common::setup_bare_db().await;
+
+    let song = Song {
+        title: String::from("Christ Arose"),
+        author: Some(String::from("Robert Lowry")),
+        parts: HashMap::from([(String::from("V1"), String::from("Low in the grave He lay"))]),
+        order: vec![String::from("V1")],
+        metadata: SongMetadata {
+            ccli_number: Some(String::from("27783")),
+            copyright: Some(String::from("Public Domain")),
+            key: Some(String::from("Bb")),
+            capo: Some(3),
+            topics: vec![String::from("Velikonoce"), String::from("Christ: Victory")],
+        },
+    };
+
+    let id = song.save_to_db(&pool).await.unwrap();
+    let loaded = Song::load_from_db(id, &mut pool.acquire().await.unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(loaded, song);
 }
 
 #[tokio::test]
@@ -112,7 +147,367 @@ async fn save_corrupted_song() {
             String::from("V2b"),
             String::from("Neexistující_tag"),
         ],
+        metadata: Default::default(),
     };
 
     assert!(song.save_to_db(&pool).await.is_err());
 }
+
+#[tokio::test]
+async fn link_translation() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+    let mut conn = pool.acquire().await.unwrap();
+
+    let ids: Vec<i64> = Song::get_available_from_db(&mut conn)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    let (original, translation) = (ids[0], ids[1]);
+
+    assert_eq!(
+        Song::get_translation_id(original, &mut conn).await.unwrap(),
+        None
+    );
+
+    Song::set_translation_id(original, Some(translation), &mut conn)
+        .await
+        .unwrap();
+    assert_eq!(
+        Song::get_translation_id(original, &mut conn).await.unwrap(),
+        Some(translation)
+    );
+
+    assert!(
+        Song::set_translation_id(original, Some(original), &mut conn)
+            .await
+            .is_err(),
+        "Píseň nemůže být překladem sama sebe"
+    );
+
+    Song::set_translation_id(original, None, &mut conn)
+        .await
+        .unwrap();
+    assert_eq!(
+        Song::get_translation_id(original, &mut conn).await.unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn authors_registry() {
+    let pool = common::setup_bare_db().await;
+
+    let song = |title: &str, author: &str| Song {
+        title: String::from(title),
+        author: Some(String::from(author)),
+        parts: HashMap::from([(String::from("V1"), String::from("Slova"))]),
+        order: vec![String::from("V1")],
+        metadata: Default::default(),
+    };
+
+    let shall_we = song("Shall We Gather at the River", "Robert Lowry, 1874")
+        .save_to_db(&pool)
+        .await
+        .unwrap();
+    let together = song("Společná píseň", "robert lowry & Fanny Crosby")
+        .save_to_db(&pool)
+        .await
+        .unwrap();
+
+    let mut conn = pool.acquire().await.unwrap();
+    let authors = get_authors_from_db(&mut conn).await.unwrap();
+    let names: Vec<&str> = authors.iter().map(|(_, name)| name.as_str()).collect();
+    assert_eq!(names, vec!["Fanny Crosby", "Robert Lowry"]);
+
+    let lowry = authors[1].0;
+    let mut songs: Vec<i64> = Song::get_available_by_author_from_db(lowry, &mut conn)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    songs.sort();
+    assert_eq!(songs, vec![shall_we, together]);
+
+    let crosby = authors[0].0;
+    let songs = Song::get_available_by_author_from_db(crosby, &mut conn)
+        .await
+        .unwrap();
+    assert_eq!(songs, vec![(together, String::from("Společná píseň"))]);
+}
+
+#[tokio::test]
+async fn songbook_numbers() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+    let mut conn = pool.acquire().await.unwrap();
+
+    let songs = Song::get_available_from_db(&mut conn).await.unwrap();
+    let (id, title) = songs[0].clone();
+
+    SongbookNumber::parse("EZ 452", None)
+        .unwrap()
+        .save_to_db(id, &pool)
+        .await
+        .unwrap();
+    SongbookNumber::parse("12", Some("Hosana"))
+        .unwrap()
+        .save_to_db(id, &pool)
+        .await
+        .unwrap();
+    // Nové číslo ve stejném zpěvníku přepíše původní
+    SongbookNumber::parse("ez 453", None)
+        .unwrap()
+        .save_to_db(id, &pool)
+        .await
+        .unwrap();
+
+    let numbered = append_songbook_numbers(songs.clone(), &mut conn)
+        .await
+        .unwrap();
+    assert_eq!(numbered[0], (id, format!("{title} (EZ 453, Hosana 12)")));
+    assert_eq!(numbered[1], songs[1]);
+}
+
+#[tokio::test]
+async fn song_arrangements() {
+    let pool = common::setup_bare_db().await;
+    let mut conn = pool.acquire().await.unwrap();
+
+    let song = Song {
+        title: String::from("Píseň s více pořadími"),
+        author: None,
+        parts: HashMap::from([
+            (String::from("V1"), String::from("Sloka")),
+            (String::from("C"), String::from("Refrén")),
+        ]),
+        order: vec![String::from("V1"), String::from("C")],
+        metadata: Default::default(),
+    };
+    let id = song.save_to_db(&pool).await.unwrap();
+    let arrangement = |name: &str, order: &[&str]| SongArrangement {
+        name: String::from(name),
+        order: order.iter().map(|tag| tag.to_string()).collect(),
+    };
+
+    arrangement("Krátká", &["C"])
+        .save_to_db(id, &pool)
+        .await
+        .unwrap();
+    arrangement("Celá", &["V1", "C", "C"])
+        .save_to_db(id, &pool)
+        .await
+        .unwrap();
+    // Stejný název (bez ohledu na velikost písmen) přepíše původní pořadí
+    arrangement("krátká", &["C", "C"])
+        .save_to_db(id, &pool)
+        .await
+        .unwrap();
+    assert!(
+        arrangement("Neznámá část", &["V2"])
+            .save_to_db(id, &pool)
+            .await
+            .is_err()
+    );
+    assert!(
+        arrangement(" ", &["C"])
+            .save_to_db(id, &pool)
+            .await
+            .is_err()
+    );
+    assert!(
+        arrangement("Prázdná", &[])
+            .save_to_db(id, &pool)
+            .await
+            .is_err()
+    );
+
+    assert_eq!(
+        SongArrangement::load_for_song(id, &mut conn).await.unwrap(),
+        vec![
+            arrangement("Celá", &["V1", "C", "C"]),
+            arrangement("krátká", &["C", "C"]),
+        ]
+    );
+    // Výchozí pořadí písně zůstává
+    assert_eq!(
+        Song::load_from_db(id, &mut conn).await.unwrap().order,
+        song.order
+    );
+
+    SongArrangement::delete_from_db(id, "Celá", &pool)
+        .await
+        .unwrap();
+    Song::delete_from_db(id, &pool).await.unwrap();
+    assert!(
+        SongArrangement::load_for_song(id, &mut conn)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn song_in_playlist_is_not_deleted() {
+    let pool = common::setup_db_with_bible_and_songs().await;
+    let mut conn = pool.acquire().await.unwrap();
+
+    let songs = Song::get_available_from_db(&mut conn).await.unwrap();
+    let (used, unused) = (songs[0].0, songs[1].0);
+
+    let mut playlist = PlaylistMetadata::new("Nedělní bohoslužba");
+    playlist.push_song(used);
+    playlist.push_song(used);
+    playlist.save(&mut conn).await.unwrap();
+
+    let playlists = Song::referencing_playlists(used, &mut conn).await.unwrap();
+    assert_eq!(playlists.len(), 1);
+    assert_eq!(playlists[0].1, "Nedělní bohoslužba");
+    assert!(
+        Song::referencing_playlists(unused, &mut conn)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let err = Song::delete_from_db(used, &pool).await.unwrap_err();
+    assert!(err.is_conflict());
+    assert!(format!("{err:#}").contains("Nedělní bohoslužba"));
+    assert!(Song::load_from_db(used, &mut conn).await.is_ok());
+
+    Song::delete_from_db(unused, &pool).await.unwrap();
+    assert!(
+        Song::load_from_db(unused, &mut conn)
+            .await
+            .unwrap_err()
+            .is_not_found()
+    );
+}
+
+#[tokio::test]
+async fn upsert_keeps_song_id() {
+    let pool = common::setup_bare_db().await;
+    let mut conn = pool.acquire().await.unwrap();
+
+    let mut song = Song {
+        title: String::from("Píseň k přepsání"),
+        author: Some(String::from("Jan Novák")),
+        parts: HashMap::from([
+            (String::from("V1"), String::from("Původní sloka")),
+            (String::from("C"), String::from("Původní refrén")),
+        ]),
+        order: vec![String::from("V1"), String::from("C")],
+        metadata: Default::default(),
+    };
+    let id = song.upsert_to_db(&pool).await.unwrap();
+
+    let mut playlist = PlaylistMetadata::new("Playlist s přepsanou písní");
+    playlist.push_song(id);
+    playlist.save(&mut conn).await.unwrap();
+
+    song.author = Some(String::from("Marie Nováková"));
+    song.parts = HashMap::from([(String::from("V1"), String::from("Nová sloka"))]);
+    song.order = vec![String::from("V1"), String::from("V1")];
+    song.metadata.ccli_number = Some(String::from("1234567"));
+
+    assert_eq!(song.upsert_to_db(&pool).await.unwrap(), id);
+    assert_eq!(Song::load_from_db(id, &mut conn).await.unwrap(), song);
+    assert_eq!(
+        Song::referencing_playlists(id, &mut conn)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // Píseň je propojená pouze s novým autorem
+    for (author_id, name) in get_authors_from_db(&mut conn).await.unwrap() {
+        let songs = Song::get_available_by_author_from_db(author_id, &mut conn)
+            .await
+            .unwrap();
+        let expected = match name.as_str() {
+            "Marie Nováková" => vec![(id, song.title.clone())],
+            _ => vec![],
+        };
+        assert_eq!(songs, expected, "Písně autora {name}");
+    }
+}
+
+#[tokio::test]
+async fn import_conflicts_are_resolved() {
+    let pool = common::setup_bare_db().await;
+    let mut conn = pool.acquire().await.unwrap();
+
+    let mine = Song {
+        title: String::from("Konfliktní píseň"),
+        author: None,
+        parts: HashMap::from([(String::from("V1"), String::from("Moje sloka"))]),
+        order: vec![String::from("V1")],
+        metadata: Default::default(),
+    };
+    let id = mine.save_to_db(&pool).await.unwrap();
+
+    let new = Song {
+        title: String::from("Nová píseň"),
+        ..mine.clone()
+    };
+    assert_eq!(
+        check_import(new.clone(), &pool).await.unwrap(),
+        ImportCheck::New(Box::new(new))
+    );
+    // Jiná metadata konflikt nejsou
+    let identical = Song {
+        author: Some(String::from("Jan Novák")),
+        ..mine.clone()
+    };
+    assert_eq!(
+        check_import(identical, &pool).await.unwrap(),
+        ImportCheck::Identical(id)
+    );
+
+    let theirs = Song {
+        parts: HashMap::from([(String::from("V1"), String::from("Jejich sloka"))]),
+        ..mine.clone()
+    };
+    let ImportCheck::Conflict(conflict) = check_import(theirs.clone(), &pool).await.unwrap() else {
+        panic!("Píseň s jinými slovy musí být konflikt");
+    };
+    assert_eq!(conflict.existing_id, id);
+    assert_eq!(conflict.existing, mine);
+
+    assert_eq!(
+        conflict
+            .resolve(ConflictResolution::KeepMine, &pool)
+            .await
+            .unwrap(),
+        id
+    );
+    assert_eq!(Song::load_from_db(id, &mut conn).await.unwrap(), mine);
+
+    // Obě verze: importovaná dostane číslo za názvem, napodruhé další
+    let both = conflict
+        .resolve(ConflictResolution::KeepBoth, &pool)
+        .await
+        .unwrap();
+    let both = Song::load_from_db(both, &mut conn).await.unwrap();
+    assert_eq!(both.title, "Konfliktní píseň (2)");
+    assert_eq!(both.parts, theirs.parts);
+    let again = conflict
+        .resolve(ConflictResolution::KeepBoth, &pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        Song::load_from_db(again, &mut conn).await.unwrap().title,
+        "Konfliktní píseň (3)"
+    );
+
+    assert_eq!(
+        conflict
+            .resolve(ConflictResolution::TakeTheirs, &pool)
+            .await
+            .unwrap(),
+        id
+    );
+    assert_eq!(Song::load_from_db(id, &mut conn).await.unwrap(), theirs);
+}
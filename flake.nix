@@ -72,6 +72,10 @@
               ./Cargo.lock
               (craneLib.fileset.commonCargoSources ./ekkles_data)
               (craneLib.fileset.commonCargoSources crate)
+              # Migrace databáze, které do programu vkládá makro `sqlx::migrate!`
+              (lib.fileset.fileFilter (file: file.hasExt "sql") ./ekkles_data/migrations)
+              # Verzifikace Bible, ze které build skript `ekkles_data` generuje tabulku
+              ./ekkles_data/data/versification.txt
             ];
         };
 
@@ -91,6 +95,26 @@
           libxkbcommon
         ];
 
+        # Slovníky pro kontrolu pravopisu v editoru písní (hledají se v EKKLES_DICTIONARIES_PATH)
+        spellcheckDictionaries = pkgs.symlinkJoin {
+          name = "ekkles-dictionaries";
+          paths = with pkgs.hunspellDicts; [
+            cs_CZ
+            en_US
+          ];
+        };
+
+        # Programy pro rozpoznání textu naskenovaných zpěvníků (OCR), spouští se z PATH
+        ocrRuntimeDeps = with pkgs; [
+          (tesseract.override {
+            enableLanguages = [
+              "ces"
+              "eng"
+            ];
+          })
+          poppler_utils
+        ];
+
         desktopFile = pkgs.makeDesktopItem {
           name = "ekkles";
           exec = "ekkles";
@@ -113,7 +137,9 @@
             postInstall = ''
               # Protože winit používá dl_open(), aby dynamicky otevřel knihovny,
               # wrapneme program a natvrdo nastavíme cestu ke knihovnám, které zkusí otevřít
-              wrapProgram $out/bin/ekkles --set LD_LIBRARY_PATH ${builtins.toString (pkgs.lib.makeLibraryPath icedRuntimeDeps)}
+              wrapProgram $out/bin/ekkles --set LD_LIBRARY_PATH ${builtins.toString (pkgs.lib.makeLibraryPath icedRuntimeDeps)} \
+                --set-default EKKLES_DICTIONARIES_PATH ${spellcheckDictionaries}/share/hunspell \
+                --prefix PATH : ${pkgs.lib.makeBinPath ocrRuntimeDeps}
 
               # Překopírujeme desktop file, aby to šlo pohodlně otevřít na ploše
               mkdir -p $out/share/applications
@@ -1,12 +1,14 @@
-use std::{fmt::Display, sync::LazyLock};
+use std::{collections::HashMap, fmt::Display, sync::LazyLock};
 
 use anyhow::{Result, anyhow, bail};
 use ekkles_data::{
     bible::{
-        get_available_translations,
+        cross_reference::{self, CrossReference},
+        get_available_translations, get_verse_counts,
         indexing::{Book, Passage, VerseIndex, chapters_in_book, verses_in_chapter},
+        strongs::{self, TaggedWord},
     },
-    playlist::PlaylistMetadata,
+    playlist::{PlaylistItemMetadata, PlaylistMetadata},
 };
 use iced::{
     Alignment, Element, Length, Padding, Task,
@@ -18,13 +20,16 @@ use iced::{
 use log::{debug, trace};
 use regex::Regex;
 
-use crate::{Ekkles, Screen, playlist_editor::PlaylistEditor};
+use crate::{Ekkles, Screen, passage_comparer::PassageComparer, playlist_editor::PlaylistEditor};
 
 #[derive(Debug, Clone)]
 pub enum Message {
     LoadTranslations,
     TranslationsLoaded(Vec<TranslationPickerItem>),
     TranslationPicked(TranslationPickerItem),
+    /// Načte počty veršů v kapitolách vybraného překladu, viz [`BiblePicker::verse_counts`]
+    LoadVerseCounts,
+    VerseCountsLoaded(i64, HashMap<(Book, u8), u8>),
     QuickPickerContentChanged(String),
     FromBookPicked(Book),
     FromChapterPicked(u8),
@@ -35,7 +40,21 @@ pub enum Message {
     SelectionChanged,
     SetPreview(Passage),
     ClearPreview,
+    /// Přepne náhled pasáže na interlineární zobrazení se Strongovými čísly (nepromítá se)
+    ToggleInterlinear,
+    LoadInterlinear,
+    InterlinearLoaded(Vec<(u8, Vec<TaggedWord>)>),
+    ToggleStudyPanel,
+    /// Načte křížové odkazy z prvního verše výběru
+    LoadCrossReferences(VerseIndex),
+    CrossReferencesLoaded(VerseIndex, Vec<CrossReference>),
+    PreviewCrossReference(VerseIndex, VerseIndex),
+    /// Přidá pasáž křížového odkazu na konec playlistu, na rozdíl od [`Message::PickPassage`]
+    /// se nevrací do editoru, aby šlo přidat více pasáží
+    AddCrossReference(VerseIndex, VerseIndex),
     PickPassage,
+    /// Otevře porovnání vybrané pasáže ve všech překladech
+    ComparePassage,
     ReturnToEditor,
 }
 
@@ -50,16 +69,38 @@ pub struct BiblePicker {
     playlist: PlaylistMetadata,
     translations: Option<Vec<TranslationPickerItem>>,
     quick_picker_content: String,
+    /// Nápověda pod rychlým výběrem (poslední verš kapitoly, proč nelze výběr použít)
+    quick_hint: String,
+    /// Proč nelze použít obsah rychlého výběru, pokud je `Some`, pasáž nelze vybrat
+    /// (jinak by se vybral předchozí validní výběr)
+    quick_error: Option<String>,
     picked_translation: Option<TranslationPickerItem>,
+    /// Poslední verše kapitol vybraného překladu, `None` dokud nejsou načteny (do té doby
+    /// se výběr kontroluje pouze vůči tabulce verzifikace), viz [`get_verse_counts()`]
+    verse_counts: Option<HashMap<(Book, u8), u8>>,
     indexes: BiblePickerIndexes,
     preview: Option<Passage>,
+    /// Zda se místo náhledu pasáže zobrazují Strongova čísla
+    interlinear_open: bool,
+    /// Označená slova veršů náhledu, `None` pokud nejsou načtena
+    interlinear: Option<Vec<(u8, Vec<TaggedWord>)>>,
+    /// Zda je zobrazen panel s křížovými odkazy
+    study_panel_open: bool,
+    /// Verš, jehož křížové odkazy jsou načteny, a samotné odkazy
+    cross_references: Option<(VerseIndex, Vec<CrossReference>)>,
+    /// Index upravované pasáže v playlistu, vybraná pasáž ji nahradí na místě.
+    /// `None` pokud se vybírá nová pasáž.
+    edited_item: Option<usize>,
+    /// Pozice v playlistu, na kterou se vloží další nová pasáž, `None` = na konec playlistu
+    insert_position: Option<usize>,
     err_msg: String,
+    info_msg: String,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct TranslationPickerItem {
-    id: i64,
-    name: String,
+    pub id: i64,
+    pub name: String,
 }
 
 impl Display for TranslationPickerItem {
@@ -74,15 +115,87 @@ impl BiblePicker {
             playlist,
             translations: None,
             quick_picker_content: String::new(),
+            quick_hint: String::new(),
+            quick_error: None,
             picked_translation: None,
+            verse_counts: None,
             indexes: BiblePickerIndexes::new(),
             preview: None,
+            interlinear_open: false,
+            interlinear: None,
+            study_panel_open: false,
+            cross_references: None,
+            edited_item: None,
+            insert_position: None,
             err_msg: String::new(),
+            info_msg: String::new(),
+        }
+    }
+
+    /// Vytvoří picker, jehož vybrané pasáže se vloží za položku na indexu `selected_index`
+    /// playlistu `playlist` (v pořadí, v jakém byly vybrány), pokud není žádná položka
+    /// vybraná, vloží se na konec.
+    pub fn insert_after(playlist: PlaylistMetadata, selected_index: Option<usize>) -> Self {
+        let mut picker = Self::new(playlist);
+        picker.insert_position = selected_index.map(|index| index + 1);
+        picker
+    }
+
+    /// Vytvoří picker pro úpravu pasáže na indexu `item_index` playlistu `playlist`,
+    /// výběr je předvyplněn jejím rozsahem. Pokud na daném indexu není pasáž, zpanikaří.
+    pub fn edit_item(playlist: PlaylistMetadata, item_index: usize) -> Self {
+        let (from, to) = match playlist.get_item(item_index) {
+            Some(PlaylistItemMetadata::BiblePassage { from, to, .. }) => (*from, *to),
+            _ => panic!("Upravovat lze pouze pasáže"),
+        };
+        let (from_book, from_chapter, from_verse) = from.destructure();
+        let (to_book, to_chapter, to_verse) = to.destructure();
+
+        let mut picker = Self::new(playlist);
+        picker.indexes = BiblePickerIndexes {
+            picked_from_book: Some(from_book),
+            picked_from_chapter: Some(from_chapter),
+            picked_from_verse: Some(from_verse),
+            picked_to_book: Some(to_book),
+            picked_to_chapter: Some(to_chapter),
+            picked_to_verse: Some(to_verse),
+        };
+        picker.edited_item = Some(item_index);
+        picker
+    }
+
+    /// Vrátí playlist, do kterého se vybírá
+    pub fn get_playlist(&self) -> &PlaylistMetadata {
+        &self.playlist
+    }
+
+    /// Vloží pasáž do playlistu, při úpravě pasáže jí nahradí upravovanou položku,
+    /// jinak ji přidá jako novou položku, viz [`BiblePicker::add_passage()`].
+    pub fn insert_passage(&mut self, translation_id: i64, from: VerseIndex, to: VerseIndex) {
+        match self.edited_item {
+            Some(index) => self
+                .playlist
+                .replace_bible_passage(index, translation_id, from, to)
+                .expect("Upravovaná položka musí být pasáž"),
+            None => self.add_passage(translation_id, from, to),
+        }
+    }
+
+    /// Přidá pasáž do playlistu jako novou položku na pozici pro vkládání (další pasáž
+    /// se vloží až za ni), případně na konec playlistu.
+    fn add_passage(&mut self, translation_id: i64, from: VerseIndex, to: VerseIndex) {
+        match self.insert_position {
+            Some(position) => {
+                self.playlist
+                    .add_bible_passage(translation_id, from, to, position);
+                self.insert_position = Some(position + 1);
+            }
+            None => self.playlist.push_bible_passage(translation_id, from, to),
         }
     }
 
     pub fn view(&self) -> Element<Message> {
-        let quick_picker = row![
+        let quick_input = row![
             pick_list(
                 // TODO: Opravdu je tu nutné klonovat?
                 self.translations.clone().unwrap_or(vec![]),
@@ -100,6 +213,17 @@ impl BiblePicker {
                 .on_submit(Message::PickPassage)
                 .width(Length::FillPortion(3))
         ];
+        let quick_picker = column![
+            quick_input,
+            text(&self.quick_hint)
+                .size(14)
+                .style(if self.quick_error.is_some() {
+                    widget::text::danger
+                } else {
+                    widget::text::default
+                })
+        ]
+        .spacing(5);
 
         let detailed_picker = row![
             pick_list(
@@ -188,8 +312,27 @@ impl BiblePicker {
             .width(Length::FillPortion(1)),
         ];
 
-        let passage_preview = match &self.preview {
-            Some(passage) => {
+        let passage_preview = match (&self.preview, &self.interlinear) {
+            (Some(_), Some(verses)) if self.interlinear_open => {
+                let interlinear_text = if verses.is_empty() {
+                    String::from("Překlad neobsahuje Strongova čísla")
+                } else {
+                    verses
+                        .iter()
+                        .map(|(verse_number, words)| {
+                            let words: Vec<String> = words
+                                .iter()
+                                .map(|word| format!("{} [{}]", word.word, word.strongs.join(" ")))
+                                .collect();
+                            format!("{verse_number}: {}\n", words.join(" "))
+                        })
+                        .collect()
+                };
+                container(scrollable(
+                    container(text(interlinear_text)).padding(Padding::ZERO.right(10)),
+                ))
+            }
+            (Some(passage), _) => {
                 let preview_text = passage
                     .get_verses()
                     .iter()
@@ -200,17 +343,40 @@ impl BiblePicker {
                     container(text(preview_text)).padding(Padding::ZERO.right(10)), // Padding, aby scrollbar nepřekrýval text
                 ))
             }
-            None => container(vertical_space()),
+            (None, _) => container(vertical_space()),
         };
 
+        let passage_preview = column![
+            button(if self.interlinear_open {
+                "Skrýt Strongova čísla"
+            } else {
+                "Strongova čísla"
+            })
+            .on_press(Message::ToggleInterlinear),
+            passage_preview.height(200),
+        ]
+        .spacing(10)
+        .align_x(Alignment::End);
+
         let submit_button = column![
-            button("Vybrat")
-                .style(widget::button::success)
-                .on_press(Message::PickPassage)
+            button(if self.edited_item.is_some() {
+                "Nahradit"
+            } else {
+                "Vybrat"
+            })
+            .style(widget::button::success)
+            .on_press(Message::PickPassage)
+            .width(Length::Fill),
+            button("Porovnat překlady")
+                .on_press(Message::ComparePassage)
                 .width(Length::Fill),
             text(&self.err_msg)
                 .style(widget::text::danger)
                 .width(Length::Fill)
+                .center(),
+            text(&self.info_msg)
+                .style(widget::text::success)
+                .width(Length::Fill)
                 .center()
         ]
         .spacing(10);
@@ -228,13 +394,15 @@ impl BiblePicker {
                 column![
                     quick_picker,
                     detailed_picker,
-                    passage_preview.height(200),
+                    passage_preview,
                     submit_button
                 ]
                 .spacing(100)
                 .align_x(Alignment::Center)
                 .width(Length::FillPortion(2)),
-                container("").width(Length::FillPortion(1))
+                container(self.view_study_panel())
+                    .width(Length::FillPortion(1))
+                    .padding(30)
             ]
             .padding(10)
             .height(Length::Fill)
@@ -242,6 +410,61 @@ impl BiblePicker {
         ))
     }
 
+    /// Panel s křížovými odkazy prvního verše výběru, pokud je skrytý, zobrazí se pouze
+    /// tlačítko pro jeho otevření
+    fn view_study_panel(&self) -> Element<Message> {
+        if !self.study_panel_open {
+            return button("Křížové odkazy")
+                .on_press(Message::ToggleStudyPanel)
+                .width(Length::Fill)
+                .into();
+        }
+
+        let cross_references: Element<Message> = match &self.cross_references {
+            None => text("Vyber verš").into(),
+            Some((_, references)) if references.is_empty() => {
+                text("Žádné křížové odkazy (lze je importovat v nastavení)").into()
+            }
+            Some((verse, references)) => column![
+                text(format!("Odkazy z {verse}")),
+                scrollable(
+                    column(references.iter().map(|reference| {
+                        let (from, to) = reference.to;
+                        let label = if from == to {
+                            from.to_string()
+                        } else {
+                            format!("{from} - {to}")
+                        };
+
+                        row![
+                            text(label).width(Length::Fill),
+                            button("Náhled").on_press(Message::PreviewCrossReference(from, to)),
+                            button("Přidat")
+                                .style(widget::button::success)
+                                .on_press(Message::AddCrossReference(from, to)),
+                        ]
+                        .spacing(5)
+                        .align_y(Alignment::Center)
+                        .into()
+                    }))
+                    .spacing(5)
+                    .padding(Padding::ZERO.right(10)), // Padding, aby scrollbar nepřekrýval tlačítka
+                )
+            ]
+            .spacing(10)
+            .into(),
+        };
+
+        column![
+            button("Skrýt křížové odkazy")
+                .on_press(Message::ToggleStudyPanel)
+                .width(Length::Fill),
+            cross_references,
+        ]
+        .spacing(10)
+        .into()
+    }
+
     pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
         let picker = match &mut state.screen {
             Screen::PickBible(picker) => picker,
@@ -274,14 +497,90 @@ impl BiblePicker {
             }
             Message::TranslationsLoaded(translations) => {
                 debug!("Překlady načteny {:#?}", translations);
-                picker.picked_translation = translations.first().cloned();
+                // Při úpravě pasáže předvybereme její překlad, jinak překlad z nastavení,
+                // pokud v databázi existuje, jinak první
+                let edited_translation_id =
+                    picker
+                        .edited_item
+                        .and_then(|index| match picker.playlist.get_item(index) {
+                            Some(PlaylistItemMetadata::BiblePassage { translation_id, .. }) => {
+                                Some(*translation_id)
+                            }
+                            _ => None,
+                        });
+                let default_translation = state.config.settings.default_translation.as_deref();
+                picker.picked_translation = translations
+                    .iter()
+                    .find(|item| Some(item.id) == edited_translation_id)
+                    .or_else(|| {
+                        translations
+                            .iter()
+                            .find(|item| Some(item.name.as_str()) == default_translation)
+                    })
+                    .or(translations.first())
+                    .cloned();
                 picker.translations = Some(translations);
-                Task::none()
+
+                let load_verse_counts = Task::done(Message::LoadVerseCounts.into());
+                // Předvyplněnou pasáž rovnou zobrazíme v náhledu
+                if picker.edited_item.is_some() {
+                    Task::batch([
+                        load_verse_counts,
+                        Task::done(Message::SelectionChanged.into()),
+                    ])
+                } else {
+                    load_verse_counts
+                }
             }
             Message::TranslationPicked(item) => {
                 debug!("Byl vybrán překlad: {}", item);
                 picker.picked_translation = Some(item);
-                Task::done(Message::SelectionChanged.into())
+                picker.verse_counts = None;
+                Task::batch([
+                    Task::done(Message::LoadVerseCounts.into()),
+                    Task::done(Message::SelectionChanged.into()),
+                ])
+            }
+            Message::LoadVerseCounts => {
+                let Some(translation) = &picker.picked_translation else {
+                    return Task::none();
+                };
+
+                debug!("Načítám počty veršů překladu {}", translation.name);
+                let translation_id = translation.id;
+                let conn = state.db.acquire();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await?;
+                        get_verse_counts(translation_id, &mut conn).await
+                    },
+                    move |res| match res {
+                        Ok(counts) => Message::VerseCountsLoaded(translation_id, counts).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::VerseCountsLoaded(translation_id, counts) => {
+                // Mezitím mohl uživatel vybrat jiný překlad
+                if picker
+                    .picked_translation
+                    .as_ref()
+                    .is_none_or(|translation| translation.id != translation_id)
+                {
+                    return Task::none();
+                }
+
+                debug!("Načteny počty veršů {} kapitol překladu", counts.len());
+                picker.verse_counts = Some(counts);
+                // Rychlý výběr se zkontroluje znovu, tentokrát vůči překladu
+                if picker.quick_picker_content.is_empty() {
+                    Task::none()
+                } else {
+                    Task::done(
+                        Message::QuickPickerContentChanged(picker.quick_picker_content.clone())
+                            .into(),
+                    )
+                }
             }
             Message::FromBookPicked(book) => {
                 debug!("Vybrána kniha (od) {}", book);
@@ -309,6 +608,7 @@ impl BiblePicker {
             }
             Message::ToBookPicked(book) => {
                 debug!("Vybrána kniha (do) {}", book);
+                picker.clear_quick_error();
                 picker.indexes.picked_to_book = Some(book);
                 picker.indexes.picked_to_chapter = None;
                 picker.indexes.picked_to_verse = None;
@@ -316,12 +616,14 @@ impl BiblePicker {
             }
             Message::ToChapterPicked(chapter) => {
                 debug!("Vybrána kapitola (do) {}", chapter);
+                picker.clear_quick_error();
                 picker.indexes.picked_to_chapter = Some(chapter);
                 picker.indexes.picked_to_verse = None;
                 Task::done(Message::SelectionChanged.into())
             }
             Message::ToVersePicked(verse) => {
                 debug!("Vybrán verš (do) {}", verse);
+                picker.clear_quick_error();
                 picker.indexes.picked_to_verse = Some(verse);
                 Task::done(Message::SelectionChanged.into())
             }
@@ -330,12 +632,17 @@ impl BiblePicker {
                 state.screen = Screen::EditPlaylist(PlaylistEditor::new(picker.playlist.clone()));
                 Task::done(crate::playlist_editor::Message::LoadSongNameCache.into())
             }
+            Message::PickPassage | Message::ComparePassage if picker.quick_error.is_some() => {
+                debug!("Rychlý výběr není validní, zobrazuji chybovou hlášku");
+                picker.err_msg = picker.quick_error.clone().unwrap_or_default();
+                Task::none()
+            }
             Message::PickPassage => match picker.validate() {
                 Ok((from, to)) => {
                     debug!(
-                        "Pasáž úspěšně zvalidována, přidávám ji na konec playlistu a vracím se do editoru"
+                        "Pasáž úspěšně zvalidována, vkládám ji do playlistu a vracím se do editoru"
                     );
-                    picker.playlist.push_bible_passage(
+                    picker.insert_passage(
                         picker
                             .picked_translation
                             .as_ref()
@@ -353,9 +660,36 @@ impl BiblePicker {
                     Task::none()
                 }
             },
+            Message::ComparePassage => match picker.validate() {
+                Ok((from, to)) => {
+                    debug!("Otevírám porovnání pasáže {from} - {to} ve všech překladech");
+                    let screen =
+                        std::mem::replace(&mut state.screen, Screen::ErrorOccurred(String::new()));
+                    let Screen::PickBible(picker) = screen else {
+                        unreachable!("Obrazovka byla zkontrolována na začátku update");
+                    };
+                    state.screen = Screen::ComparePassages(PassageComparer::new(picker, from, to));
+                    Task::done(crate::passage_comparer::Message::LoadTranslations.into())
+                }
+                Err(err) => {
+                    debug!("Pasáž není validní, zobrazuji chybovou hlášku");
+                    picker.err_msg = err.to_string();
+                    Task::none()
+                }
+            },
             Message::SelectionChanged => match picker.validate() {
                 Ok((from, to)) => {
                     trace!("Detekována validní pasáž, načítám preview");
+                    let load_cross_references = if picker.study_panel_open
+                        && picker
+                            .cross_references
+                            .as_ref()
+                            .is_none_or(|(verse, _)| *verse != from)
+                    {
+                        Task::done(Message::LoadCrossReferences(from).into())
+                    } else {
+                        Task::none()
+                    };
                     let conn = state.db.acquire();
                     let translation_id = picker
                         .picked_translation
@@ -372,6 +706,7 @@ impl BiblePicker {
                             Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
                         },
                     )
+                    .chain(load_cross_references)
                 }
                 Err(_) => {
                     trace!("Pasáž není validní, vyčišťuji preview");
@@ -382,11 +717,122 @@ impl BiblePicker {
                 debug!("Nastavena pasáž pro preview");
                 picker.err_msg.clear();
                 picker.preview = Some(passage);
+                picker.interlinear = None;
+                if picker.interlinear_open {
+                    Task::done(Message::LoadInterlinear.into())
+                } else {
+                    Task::none()
+                }
+            }
+            Message::ToggleInterlinear => {
+                picker.interlinear_open = !picker.interlinear_open;
+                debug!("Interlineární náhled zobrazen: {}", picker.interlinear_open);
+                if picker.interlinear_open && picker.interlinear.is_none() {
+                    Task::done(Message::LoadInterlinear.into())
+                } else {
+                    Task::none()
+                }
+            }
+            Message::LoadInterlinear => {
+                let (Some(passage), Some(translation)) =
+                    (&picker.preview, &picker.picked_translation)
+                else {
+                    return Task::none();
+                };
+
+                let (from, to) = passage.get_range();
+                let translation_id = translation.id;
+                debug!("Načítám Strongova čísla pasáže {from} - {to}");
+                let conn = state.db.acquire();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await?;
+                        strongs::load_for_passage(from, to, translation_id, &mut conn).await
+                    },
+                    |res| match res {
+                        Ok(verses) => Message::InterlinearLoaded(verses).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::InterlinearLoaded(verses) => {
+                debug!("Strongova čísla načtena pro {} veršů", verses.len());
+                picker.interlinear = Some(verses);
+                Task::none()
+            }
+            Message::ToggleStudyPanel => {
+                picker.study_panel_open = !picker.study_panel_open;
+                debug!(
+                    "Panel křížových odkazů otevřen: {}",
+                    picker.study_panel_open
+                );
+                match picker.indexes.validate() {
+                    Ok((from, _)) if picker.study_panel_open => {
+                        Task::done(Message::LoadCrossReferences(from).into())
+                    }
+                    _ => Task::none(),
+                }
+            }
+            Message::LoadCrossReferences(verse) => {
+                debug!("Načítám křížové odkazy verše {verse}");
+                let conn = state.db.acquire();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await?;
+                        cross_reference::get_for_verse(verse, &mut conn).await
+                    },
+                    move |res| match res {
+                        Ok(references) => Message::CrossReferencesLoaded(verse, references).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::CrossReferencesLoaded(verse, references) => {
+                debug!(
+                    "Načteno {} křížových odkazů verše {verse}",
+                    references.len()
+                );
+                picker.cross_references = Some((verse, references));
+                Task::none()
+            }
+            Message::PreviewCrossReference(from, to) => {
+                let Some(translation) = &picker.picked_translation else {
+                    picker.err_msg = String::from("Nebyl vybrán příslušný překlad");
+                    return Task::none();
+                };
+
+                // Výběr se nemění, aby zůstaly zobrazené křížové odkazy původního verše
+                debug!("Načítám náhled křížového odkazu {from} - {to}");
+                let translation_id = translation.id;
+                let conn = state.db.acquire();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await?;
+                        Passage::load(from, to, translation_id, &mut conn).await
+                    },
+                    |res| match res {
+                        Ok(passage) => Message::SetPreview(passage).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::AddCrossReference(from, to) => {
+                let Some(translation) = &picker.picked_translation else {
+                    picker.err_msg = String::from("Nebyl vybrán příslušný překlad");
+                    return Task::none();
+                };
+
+                debug!("Přidávám křížový odkaz {from} - {to} do playlistu");
+                let translation_id = translation.id;
+                picker.add_passage(translation_id, from, to);
+                picker.err_msg.clear();
+                picker.info_msg = format!("Přidáno {from} - {to}");
                 Task::none()
             }
             Message::ClearPreview => {
                 debug!("Mažu preview");
                 picker.preview = None;
+                picker.interlinear = None;
                 Task::none()
             }
             Message::QuickPickerContentChanged(input) => {
@@ -394,17 +840,130 @@ impl BiblePicker {
                 picker.quick_picker_content = input;
                 let indexes = picker.parse_quick_selection();
 
-                if indexes.validate().is_ok() {
-                    trace!("Quick input je validní, nastavuji výběr na {:#?}", indexes);
-                    picker.indexes = indexes;
-                    Task::done(Message::SelectionChanged.into())
-                } else {
-                    Task::none()
+                if !indexes.is_filled() {
+                    // Rozepsaný odkaz (např. "Jan 3") nehlásíme, chyba se zobrazí až při
+                    // pokusu o vložení pasáže
+                    picker.quick_hint.clear();
+                    picker.quick_error =
+                        (!picker.quick_picker_content.trim().is_empty()).then(|| {
+                            format!(
+                                "Odkaz \"{}\" nelze rozpoznat, zadej jej např. jako Jan 3:16-18",
+                                picker.quick_picker_content
+                            )
+                        });
+                    return Task::none();
+                }
+
+                match picker.check_quick_bounds(indexes) {
+                    Ok((indexes, hint)) => {
+                        trace!("Quick input je validní, nastavuji výběr na {:#?}", indexes);
+                        picker.indexes = indexes;
+                        picker.quick_hint = hint;
+                        picker.quick_error = None;
+                        Task::done(Message::SelectionChanged.into())
+                    }
+                    Err(reason) => {
+                        trace!("Quick input není validní: {reason}");
+                        picker.quick_hint = reason.clone();
+                        picker.quick_error = Some(reason);
+                        Task::none()
+                    }
                 }
             }
         }
     }
 
+    /// Zahodí nápovědu a chybu rychlého výběru, volá se při ručním výběru v pickerech
+    fn clear_quick_error(&mut self) {
+        self.quick_hint.clear();
+        self.quick_error = None;
+    }
+
+    /// Vrátí číslo posledního verše kapitoly `chapter` knihy `book`. Pokud jsou načtené
+    /// počty veršů vybraného překladu ([`BiblePicker::verse_counts`]), kapitola musí být
+    /// v překladu (a zároveň v tabulce verzifikace), jinak se vrací důvod pro uživatele.
+    fn last_verse(&self, book: Book, chapter: u8) -> Result<u8, String> {
+        let missing = || {
+            let last_chapter = match &self.verse_counts {
+                Some(counts) => counts
+                    .keys()
+                    .filter(|(counted_book, _)| *counted_book == book)
+                    .map(|(_, counted_chapter)| *counted_chapter)
+                    .max(),
+                None => Some(*chapters_in_book(book).end()),
+            };
+            match last_chapter {
+                Some(last_chapter) => {
+                    format!("{book} nemá kapitolu {chapter}, poslední je {last_chapter}")
+                }
+                None => format!("{book} ve vybraném překladu není"),
+            }
+        };
+
+        let last = *verses_in_chapter(book, chapter).ok_or_else(missing)?.end();
+        match &self.verse_counts {
+            Some(counts) => counts
+                .get(&(book, chapter))
+                .map(|count| (*count).min(last))
+                .ok_or_else(missing),
+            None => Ok(last),
+        }
+    }
+
+    /// Zkontroluje kompletně vyplněné indexy rychlého výběru vůči verzifikaci vybraného
+    /// překladu (viz [`BiblePicker::last_verse()`]). Neexistující kapitola nebo začátek
+    /// pasáže za koncem kapitoly se odmítnou, konec pasáže za koncem kapitoly se zkrátí
+    /// na její poslední verš ("Jan 3:16-99" -> "Jan 3:16-36").
+    ///
+    /// Vrací (případně zkrácené) indexy s nápovědou pro uživatele, nebo důvod odmítnutí.
+    fn check_quick_bounds(
+        &self,
+        mut indexes: BiblePickerIndexes,
+    ) -> Result<(BiblePickerIndexes, String), String> {
+        let (
+            Some(from_book),
+            Some(from_chapter),
+            Some(from_verse),
+            Some(to_book),
+            Some(to_chapter),
+            Some(to_verse),
+        ) = (
+            indexes.picked_from_book,
+            indexes.picked_from_chapter,
+            indexes.picked_from_verse,
+            indexes.picked_to_book,
+            indexes.picked_to_chapter,
+            indexes.picked_to_verse,
+        )
+        else {
+            return Err(String::from("Picker není celý vyplněný"));
+        };
+
+        let last_from = self.last_verse(from_book, from_chapter)?;
+        if from_verse == 0 || from_verse > last_from {
+            return Err(format!(
+                "{from_book} {from_chapter} nemá verš {from_verse}, poslední verš je {from_book} {from_chapter}:{last_from}"
+            ));
+        }
+        let last_to = self.last_verse(to_book, to_chapter)?;
+
+        let hint = if to_verse > last_to {
+            indexes.picked_to_verse = Some(last_to);
+            format!(
+                "Konec pasáže zkrácen na {to_book} {to_chapter}:{last_to}, kapitola nemá více veršů"
+            )
+        } else if (from_book, from_chapter) == (to_book, to_chapter) {
+            format!("Poslední verš: {from_book} {from_chapter}:{last_from}")
+        } else {
+            format!(
+                "Poslední verše: {from_book} {from_chapter}:{last_from}, {to_book} {to_chapter}:{last_to}"
+            )
+        };
+
+        indexes.validate().map_err(|e| e.to_string())?;
+        Ok((indexes, hint))
+    }
+
     /// Zvaliduje, že pasáž je korektně vybraná. Kapitola, kniha i verš jsou legální
     /// v obou případech a jsou ve správném pořadí. Také zkontroluje,
     /// že byl vybrán překlad. Pokud cokoliv z tohoto není splněno, vrací Error.
@@ -424,7 +983,8 @@ impl BiblePicker {
     /// Pokud není druhá kniha/kapitola/verš uvedeny, bude použita první.
     /// Pokud je pouze první trojice uvedena, je to chápáno jako referekce jediného verše.
     ///
-    /// - Parsování knih funguje podle [`Book::parse()`].
+    /// - Parsování knih funguje podle [`Book::parse()`], knihy lze zadat česky
+    ///   i anglicky, s tečkou za pořadím i bez ní ("1. Janova", "1 John").
     fn parse_quick_selection(&self) -> BiblePickerIndexes {
         // Statická proměnná, která se inicializuje při prvním přístupu
         // a poté do konce běhu programu nemění svou hodnotu.
@@ -432,7 +992,7 @@ impl BiblePicker {
         // a kompilace může být poměrně drahá.
         static REGEX: LazyLock<Regex> = LazyLock::new(|| {
             Regex::new(
-                r"^(?P<from_book>((\d\.)|\p{Letter}+)? *\p{Letter}+) *(?P<from_chapter>\d+) *: *(?P<from_verse>\d+) *(- *(?P<to_book>(\d\.)? *\p{Letter}+)? *((?P<to_chapter>\d+)? *:)? *(?P<to_verse>\d+))?$"
+                r"^(?P<from_book>((\d\.?)|\p{Letter}+)? *\p{Letter}+) *(?P<from_chapter>\d+) *: *(?P<from_verse>\d+) *(- *(?P<to_book>(\d\.?)? *\p{Letter}+)? *((?P<to_chapter>\d+)? *:)? *(?P<to_verse>\d+))?$"
             ).expect("Nelze zkompilovat regex")
         });
 
@@ -621,6 +1181,28 @@ mod tests {
                     picked_to_verse: Some(3),
                 },
             ),
+            (
+                "1 John 4:7-2 John 1:3",
+                BiblePickerIndexes {
+                    picked_from_book: Some(Book::John1),
+                    picked_from_chapter: Some(4),
+                    picked_from_verse: Some(7),
+                    picked_to_book: Some(Book::John2),
+                    picked_to_chapter: Some(1),
+                    picked_to_verse: Some(3),
+                },
+            ),
+            (
+                "1Cor 13:4-7",
+                BiblePickerIndexes {
+                    picked_from_book: Some(Book::Corinthians1),
+                    picked_from_chapter: Some(13),
+                    picked_from_verse: Some(4),
+                    picked_to_book: Some(Book::Corinthians1),
+                    picked_to_chapter: Some(13),
+                    picked_to_verse: Some(7),
+                },
+            ),
         ];
 
         let mut picker = BiblePicker::new(PlaylistMetadata::new(""));
@@ -631,4 +1213,119 @@ mod tests {
             assert_eq!(result, expected);
         }
     }
+
+    /// Kompletně vyplněné indexy pickeru od `from` do `to` (kniha, kapitola, verš)
+    fn filled_indexes(from: (Book, u8, u8), to: (Book, u8, u8)) -> BiblePickerIndexes {
+        BiblePickerIndexes {
+            picked_from_book: Some(from.0),
+            picked_from_chapter: Some(from.1),
+            picked_from_verse: Some(from.2),
+            picked_to_book: Some(to.0),
+            picked_to_chapter: Some(to.1),
+            picked_to_verse: Some(to.2),
+        }
+    }
+
+    #[test]
+    fn picker_validation() {
+        let (from, to) = filled_indexes((Book::John, 3, 16), (Book::John, 3, 18))
+            .validate()
+            .unwrap();
+        assert_eq!(from, VerseIndex::try_new(Book::John, 3, 16).unwrap());
+        assert_eq!(to, VerseIndex::try_new(Book::John, 3, 18).unwrap());
+
+        // Jednoveršová pasáž je validní
+        assert!(
+            filled_indexes((Book::John, 3, 16), (Book::John, 3, 16))
+                .validate()
+                .is_ok()
+        );
+        // Začátek za koncem
+        assert!(
+            filled_indexes((Book::Mark, 1, 1), (Book::Matthew, 28, 20))
+                .validate()
+                .is_err()
+        );
+        // Verš, který v kapitole není
+        assert!(
+            filled_indexes((Book::John, 3, 16), (Book::John, 3, 99))
+                .validate()
+                .is_err()
+        );
+        // Nevyplněný picker
+        assert!(BiblePickerIndexes::new().validate().is_err());
+
+        // Bez vybraného překladu nelze pasáž vybrat ani s validními indexy
+        let mut picker = BiblePicker::new(PlaylistMetadata::new(""));
+        picker.indexes = filled_indexes((Book::John, 3, 16), (Book::John, 3, 18));
+        assert!(picker.validate().is_err());
+    }
+
+    #[test]
+    fn quick_selection_is_checked_against_versification() {
+        let mut picker = BiblePicker::new(PlaylistMetadata::new(""));
+
+        // Konec pasáže za koncem kapitoly se zkrátí
+        let (indexes, hint) = picker
+            .check_quick_bounds(filled_indexes((Book::John, 3, 16), (Book::John, 3, 99)))
+            .unwrap();
+        assert_eq!(
+            indexes,
+            filled_indexes((Book::John, 3, 16), (Book::John, 3, 36))
+        );
+        assert!(hint.contains("Jan 3:36"));
+
+        // Začátek za koncem kapitoly a neexistující kapitola se odmítnou
+        let reason = picker
+            .check_quick_bounds(filled_indexes((Book::John, 3, 99), (Book::John, 3, 99)))
+            .unwrap_err();
+        assert!(reason.contains("poslední verš je Jan 3:36"));
+        assert!(
+            picker
+                .check_quick_bounds(filled_indexes((Book::John, 30, 1), (Book::John, 30, 1)))
+                .is_err()
+        );
+
+        // S načtenými počty veršů překladu se kontroluje vůči překladu
+        picker.verse_counts = Some(HashMap::from([
+            ((Book::Malachi, 1), 14),
+            ((Book::Malachi, 2), 17),
+            ((Book::Malachi, 3), 18),
+        ]));
+        assert!(
+            picker
+                .check_quick_bounds(filled_indexes(
+                    (Book::Malachi, 3, 20),
+                    (Book::Malachi, 3, 20)
+                ))
+                .is_err()
+        );
+        let reason = picker
+            .check_quick_bounds(filled_indexes((Book::Malachi, 4, 1), (Book::Malachi, 4, 1)))
+            .unwrap_err();
+        assert!(reason.contains("poslední je 3"));
+    }
+
+    #[test]
+    fn passages_are_inserted_after_selection() {
+        let verse = |verse| VerseIndex::try_new(Book::John, 3, verse).unwrap();
+        let mut playlist = PlaylistMetadata::new("");
+        playlist.push_song(0);
+        playlist.push_song(1);
+
+        // Vybraná je první píseň, pasáže se vkládají za ni v pořadí výběru
+        let mut picker = BiblePicker::insert_after(playlist, Some(0));
+        picker.insert_passage(0, verse(16), verse(16));
+        picker.insert_passage(0, verse(17), verse(17));
+
+        let starts: Vec<_> = picker
+            .get_playlist()
+            .get_items()
+            .map(|item| match item {
+                PlaylistItemMetadata::BiblePassage { from, .. } => Some(*from),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(starts, vec![None, Some(verse(16)), Some(verse(17)), None]);
+    }
 }
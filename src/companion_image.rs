@@ -0,0 +1,88 @@
+//! Zrcadlení promítání do obrázku na disku (viz [`crate::config::Settings::companion_image_path`]).
+//!
+//! Během prezentace se v pravidelném intervalu pořídí snímek prezentačního okna a zapíše
+//! se jako PNG na zvolenou cestu. OBS (zdroj "Obrázek") nebo přehrávač digital signage
+//! tak mohou promítání zrcadlit bez jakékoliv další integrace. Soubor se nahrazuje
+//! atomicky (zápis do dočasného souboru a přejmenování), čtenář tedy nikdy nenarazí
+//! na napůl zapsaný obrázek.
+
+use std::{
+    ffi::OsString,
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Context, Result, anyhow};
+use iced::window::Screenshot;
+use image::{ImageFormat, RgbaImage};
+use log::trace;
+
+/// Výchozí interval zápisu obrázku
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+/// Nejkratší povolený interval, častější snímání by zbytečně zatěžovalo GPU
+pub const MIN_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Cesta k dočasnému souboru vedle `path`, který se po zapsání přejmenuje na `path`
+/// (musí být ve stejné složce, aby bylo přejmenování atomické)
+fn temporary_path(path: &Path) -> PathBuf {
+    let mut file_name = OsString::from(".");
+    file_name.push(path.file_name().unwrap_or_default());
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
+
+/// Zakóduje snímek prezentačního okna `screenshot` jako PNG a atomicky jím nahradí
+/// soubor na cestě `path`. Pokud snímek nelze zakódovat nebo zapsat, vrací Error.
+pub async fn write(path: PathBuf, screenshot: Screenshot) -> Result<()> {
+    tokio::task::spawn_blocking(move || {
+        let image = RgbaImage::from_raw(
+            screenshot.size.width,
+            screenshot.size.height,
+            screenshot.as_ref().to_vec(),
+        )
+        .ok_or(anyhow!("Snímek okna nemá očekávanou velikost"))?;
+
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .context("Nelze zakódovat snímek prezentace")?;
+
+        replace_file(&path, &png)
+    })
+    .await
+    .context("Úloha zápisu obrázku prezentace selhala")?
+}
+
+/// Atomicky nahradí obsah souboru `path` daty `content`
+fn replace_file(path: &Path, content: &[u8]) -> Result<()> {
+    let temporary = temporary_path(path);
+    trace!("Zapisuji obrázek prezentace do {}", path.display());
+
+    fs::write(&temporary, content)
+        .with_context(|| format!("Nelze zapsat dočasný soubor {}", temporary.display()))?;
+    fs::rename(&temporary, path)
+        .with_context(|| format!("Nelze nahradit obrázek prezentace {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn file_is_replaced_without_leftovers() {
+        let path = env::temp_dir().join(format!("ekkles-companion-{}.png", std::process::id()));
+
+        replace_file(&path, b"prvni").unwrap();
+        replace_file(&path, b"druhy").unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"druhy");
+        assert!(!temporary_path(&path).exists());
+        fs::remove_file(&path).unwrap();
+    }
+}
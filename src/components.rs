@@ -1,3 +1,4 @@
+use ekkles_data::media::MediaInfo;
 use iced::{
     Element, Length,
     task::Handle,
@@ -6,23 +7,40 @@ use iced::{
 
 use crate::Message;
 
+pub mod lyrics_diff;
 pub mod playlist_item_styles;
+pub mod spell_highlight;
 
 #[derive(Debug, Clone, Copy)]
 pub enum TopButtonsMessage {
     Playlists,
     Songs,
+    Settings,
 }
 
 pub enum TopButtonsPickedSection {
     Songs,
     Playlists,
+    Settings,
 }
 
 pub fn top_buttons(picked: TopButtonsPickedSection) -> Element<'static, TopButtonsMessage> {
-    let (song_msg, playlist_msg) = match picked {
-        TopButtonsPickedSection::Songs => (None, Some(TopButtonsMessage::Playlists)),
-        TopButtonsPickedSection::Playlists => (Some(TopButtonsMessage::Songs), None),
+    let (song_msg, playlist_msg, settings_msg) = match picked {
+        TopButtonsPickedSection::Songs => (
+            None,
+            Some(TopButtonsMessage::Playlists),
+            Some(TopButtonsMessage::Settings),
+        ),
+        TopButtonsPickedSection::Playlists => (
+            Some(TopButtonsMessage::Songs),
+            None,
+            Some(TopButtonsMessage::Settings),
+        ),
+        TopButtonsPickedSection::Settings => (
+            Some(TopButtonsMessage::Songs),
+            Some(TopButtonsMessage::Playlists),
+            None,
+        ),
     };
     row![
         button("Písně")
@@ -30,8 +48,127 @@ pub fn top_buttons(picked: TopButtonsPickedSection) -> Element<'static, TopButto
             .width(Length::FillPortion(1)),
         button("Playlisty")
             .on_press_maybe(playlist_msg)
+            .width(Length::FillPortion(1)),
+        button("Nastavení")
+            .on_press_maybe(settings_msg)
             .width(Length::FillPortion(1))
     ]
     .width(Length::Fill)
     .into()
 }
+
+/// Naformátuje délku v sekundách do tvaru `m:ss` (případně `h:mm:ss`)
+pub fn format_duration(seconds: u32) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// Položka výběru motivu slajdů, `id` je `None` pro výchozí motiv (z nastavení,
+/// případně motiv písně)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThemeOption {
+    pub id: Option<i64>,
+    pub name: String,
+}
+
+impl ThemeOption {
+    /// Sestaví nabídku motivů z dvojic (id, název) z databáze, první položkou je výchozí motiv
+    pub fn options(themes: &[(i64, String)]) -> Vec<Self> {
+        std::iter::once(Self {
+            id: None,
+            name: String::from("Výchozí"),
+        })
+        .chain(themes.iter().map(|(id, name)| Self {
+            id: Some(*id),
+            name: name.clone(),
+        }))
+        .collect()
+    }
+}
+
+impl std::fmt::Display for ThemeOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Položka výběru překladu písně, `id` je `None` pro píseň bez překladu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationOption {
+    pub id: Option<i64>,
+    pub name: String,
+}
+
+impl TranslationOption {
+    /// Sestaví nabídku překladů písně `song_id` z dvojic (id, název) všech písní, první
+    /// položkou je "bez překladu", samotná píseň v nabídce není
+    pub fn options(songs: &[(i64, String)], song_id: i64) -> Vec<Self> {
+        std::iter::once(Self {
+            id: None,
+            name: String::from("Bez překladu"),
+        })
+        .chain(
+            songs
+                .iter()
+                .filter(|(id, _)| *id != song_id)
+                .map(|(id, name)| Self {
+                    id: Some(*id),
+                    name: name.clone(),
+                }),
+        )
+        .collect()
+    }
+}
+
+impl std::fmt::Display for TranslationOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Položka výběru obrázku z úložiště médií
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaOption {
+    pub id: i64,
+    pub name: String,
+}
+
+impl From<&MediaInfo> for MediaOption {
+    fn from(value: &MediaInfo) -> Self {
+        Self {
+            id: value.id,
+            name: value.name.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for MediaOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Položka výběru playlistu, který se vloží jako blok, viz
+/// [`ekkles_data::playlist::PlaylistItemMetadata::Block`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockOption {
+    pub id: i64,
+    pub name: String,
+}
+
+impl From<(i64, String)> for BlockOption {
+    fn from((id, name): (i64, String)) -> Self {
+        Self { id, name }
+    }
+}
+
+impl std::fmt::Display for BlockOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
@@ -0,0 +1,151 @@
+//! Zobrazení rozdílu dvou verzí slov písně po řádcích (viz [`ekkles_data::song_diff`]),
+//! vlevo původní verze, vpravo nová. Přidané, odebrané a změněné řádky jsou barevně
+//! zvýrazněné. Používá se všude, kde uživatel porovnává verze písně (rozhodnutí
+//! konfliktů importu, ...).
+
+use ekkles_data::song_diff::DiffLine;
+use iced::{
+    Background, Color, Element, Length, Theme, color,
+    widget::{Space, column, container, row, text},
+};
+
+const ADDED_COLOR: Color = color!(0xc8f0c8);
+const REMOVED_COLOR: Color = color!(0xf6c8c8);
+const CHANGED_COLOR: Color = color!(0xf8e8b0);
+const TEXT_SIZE: u16 = 14;
+const COLUMN_SPACING: u16 = 20;
+
+/// Řádek zobrazení rozdílu, levá a pravá strana
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DiffRow<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+    Changed(&'a str, &'a str),
+}
+
+/// Seskupí řádky rozdílu do řádků zobrazení. Odebrané řádky, hned za kterými jsou přidané,
+/// se spárují (první odebraný s prvním přidaným, ...) jako změněné, zbylé zůstanou
+/// odebrané, resp. přidané.
+fn diff_rows(diff: &[DiffLine]) -> Vec<DiffRow<'_>> {
+    let mut rows = Vec::with_capacity(diff.len());
+    let mut index = 0;
+
+    while index < diff.len() {
+        match &diff[index] {
+            DiffLine::Same(line) => {
+                rows.push(DiffRow::Same(line));
+                index += 1;
+            }
+            DiffLine::Added(line) => {
+                rows.push(DiffRow::Added(line));
+                index += 1;
+            }
+            DiffLine::Removed(_) => {
+                let removed: Vec<&str> = diff[index..]
+                    .iter()
+                    .map_while(|line| match line {
+                        DiffLine::Removed(line) => Some(line.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                let added: Vec<&str> = diff[index + removed.len()..]
+                    .iter()
+                    .map_while(|line| match line {
+                        DiffLine::Added(line) => Some(line.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                index += removed.len() + added.len();
+
+                for pair in 0..removed.len().max(added.len()) {
+                    rows.push(match (removed.get(pair), added.get(pair)) {
+                        (Some(&old), Some(&new)) => DiffRow::Changed(old, new),
+                        (Some(&old), None) => DiffRow::Removed(old),
+                        (None, Some(&new)) => DiffRow::Added(new),
+                        (None, None) => unreachable!("Pár je v rozsahu delší ze skupin"),
+                    });
+                }
+            }
+        }
+    }
+
+    rows
+}
+
+/// Jedna strana řádku rozdílu, `background` je barva zvýraznění (`None` pro nezměněný řádek)
+fn cell<'a, Message: 'a>(line: &'a str, background: Option<Color>) -> Element<'a, Message> {
+    container(text(line).size(TEXT_SIZE))
+        .width(Length::FillPortion(1))
+        .style(move |_: &Theme| container::Style {
+            background: background.map(Background::Color),
+            text_color: background.map(|_| Color::BLACK),
+            ..Default::default()
+        })
+        .into()
+}
+
+fn empty_cell<'a, Message: 'a>() -> Element<'a, Message> {
+    Space::new(Length::FillPortion(1), Length::Shrink).into()
+}
+
+/// Zobrazí rozdíl `diff` vedle sebe, nad stranami jsou nadpisy `old_label` a `new_label`
+pub fn lyrics_diff<'a, Message: 'a>(
+    diff: &'a [DiffLine],
+    old_label: &'a str,
+    new_label: &'a str,
+) -> Element<'a, Message> {
+    let header = row![
+        text(old_label).width(Length::FillPortion(1)),
+        text(new_label).width(Length::FillPortion(1)),
+    ]
+    .spacing(COLUMN_SPACING);
+
+    let rows = diff_rows(diff).into_iter().map(|diff_row| {
+        let (old, new) = match diff_row {
+            DiffRow::Same(line) => (cell(line, None), cell(line, None)),
+            DiffRow::Removed(line) => (cell(line, Some(REMOVED_COLOR)), empty_cell()),
+            DiffRow::Added(line) => (empty_cell(), cell(line, Some(ADDED_COLOR))),
+            DiffRow::Changed(old, new) => (
+                cell(old, Some(CHANGED_COLOR)),
+                cell(new, Some(CHANGED_COLOR)),
+            ),
+        };
+        row![old, new].spacing(COLUMN_SPACING).into()
+    });
+
+    column![header, column(rows)].spacing(5).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn removed_and_added_lines_are_paired() {
+        let line = |kind: fn(String) -> DiffLine, content: &str| kind(content.to_string());
+        let diff = vec![
+            line(DiffLine::Same, "[V1]"),
+            line(DiffLine::Removed, "Chválím Tě, Pane"),
+            line(DiffLine::Removed, "z celého srdce"),
+            line(DiffLine::Added, "Chválím Tě, Bože"),
+            line(DiffLine::Same, "[C]"),
+            line(DiffLine::Added, "Amen"),
+            line(DiffLine::Removed, "Haleluja"),
+        ];
+
+        assert_eq!(
+            diff_rows(&diff),
+            vec![
+                DiffRow::Same("[V1]"),
+                DiffRow::Changed("Chválím Tě, Pane", "Chválím Tě, Bože"),
+                DiffRow::Removed("z celého srdce"),
+                DiffRow::Same("[C]"),
+                DiffRow::Added("Amen"),
+                DiffRow::Removed("Haleluja"),
+            ]
+        );
+    }
+}
@@ -2,6 +2,11 @@ use iced::{Background, Border, Color, Theme, border::Radius, color, widget::butt
 
 const SONG_COLOR: Color = color!(0x02a2f6);
 const PASSAGE_COLOR: Color = color!(0xfeaf4d);
+const CUSTOM_TEXT_COLOR: Color = color!(0x8fd16a);
+const IMAGE_COLOR: Color = color!(0xc39bd3);
+const BLOCK_COLOR: Color = color!(0x9ec5c8);
+const SKIPPED_COLOR: Color = color!(0xd0d0d0);
+const SKIPPED_TEXT_COLOR: Color = color!(0x808080);
 
 pub fn song(_theme: &Theme, _status: button::Status) -> button::Style {
     button::Style {
@@ -38,3 +43,70 @@ pub fn passage_selected(theme: &Theme, status: button::Status) -> button::Style
     style.border.color = Color::BLACK;
     style
 }
+
+pub fn custom_text(_theme: &Theme, _status: button::Status) -> button::Style {
+    button::Style {
+        background: Some(Background::Color(CUSTOM_TEXT_COLOR)),
+        border: Border {
+            radius: Radius::new(0),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+pub fn custom_text_selected(theme: &Theme, status: button::Status) -> button::Style {
+    let mut style = custom_text(theme, status);
+    style.border.width = 5.0;
+    style.border.color = Color::BLACK;
+    style
+}
+
+pub fn image(_theme: &Theme, _status: button::Status) -> button::Style {
+    button::Style {
+        background: Some(Background::Color(IMAGE_COLOR)),
+        border: Border {
+            radius: Radius::new(0),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+pub fn image_selected(theme: &Theme, status: button::Status) -> button::Style {
+    let mut style = image(theme, status);
+    style.border.width = 5.0;
+    style.border.color = Color::BLACK;
+    style
+}
+
+pub fn block(_theme: &Theme, _status: button::Status) -> button::Style {
+    button::Style {
+        background: Some(Background::Color(BLOCK_COLOR)),
+        border: Border {
+            radius: Radius::new(0),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+pub fn block_selected(theme: &Theme, status: button::Status) -> button::Style {
+    let mut style = block(theme, status);
+    style.border.width = 5.0;
+    style.border.color = Color::BLACK;
+    style
+}
+
+/// Slajd položky, která se při prezentaci přeskakuje (bez ohledu na druh položky)
+pub fn skipped(_theme: &Theme, _status: button::Status) -> button::Style {
+    button::Style {
+        background: Some(Background::Color(SKIPPED_COLOR)),
+        text_color: SKIPPED_TEXT_COLOR,
+        border: Border {
+            radius: Radius::new(0),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
@@ -0,0 +1,103 @@
+//! Zvýraznění překlepů v textovém editoru podle kontroly pravopisu
+//! (viz [`ekkles_data::spellcheck`]). Editor v icedu umí u zvýrazněného textu měnit pouze
+//! barvu a font, překlepy jsou proto místo podtržení vlnovkou obarvené červeně.
+//!
+//! Použití: `text_editor(...).highlight_with::<SpellHighlighter>(settings, SpellHighlighter::format)`
+
+use std::{ops::Range, sync::Arc};
+
+use ekkles_data::spellcheck::SpellChecker;
+use iced::{
+    Color, Font, Theme, color,
+    widget::{core::text::highlighter::Format, text::Highlighter},
+};
+
+const MISSPELLED_COLOR: Color = color!(0xd02020);
+
+/// Nastavení zvýraznění, bez kontroly pravopisu (slovníky se nenačetly) se nic nezvýrazňuje
+#[derive(Debug, Clone, Default)]
+pub struct SpellSettings {
+    pub checker: Option<Arc<SpellChecker>>,
+    /// Slova, která uživatel označil jako správná, nezvýrazňují se
+    pub ignored: Vec<String>,
+}
+
+impl PartialEq for SpellSettings {
+    fn eq(&self, other: &Self) -> bool {
+        let same_checker = match (&self.checker, &other.checker) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        same_checker && self.ignored == other.ignored
+    }
+}
+
+/// Zvýraznění překlepu
+#[derive(Debug, Clone, Copy)]
+pub struct Misspelled;
+
+#[derive(Debug)]
+pub struct SpellHighlighter {
+    settings: SpellSettings,
+    /// Index řádku, který se bude zvýrazňovat jako další
+    current_line: usize,
+}
+
+impl SpellHighlighter {
+    /// Formát zvýrazněného překlepu pro [`iced::widget::TextEditor::highlight_with()`]
+    pub fn format(_highlight: &Misspelled, _theme: &Theme) -> Format<Font> {
+        Format {
+            color: Some(MISSPELLED_COLOR),
+            font: None,
+        }
+    }
+}
+
+impl Highlighter for SpellHighlighter {
+    type Settings = SpellSettings;
+    type Highlight = Misspelled;
+    type Iterator<'a> = std::vec::IntoIter<(Range<usize>, Misspelled)>;
+
+    fn new(settings: &Self::Settings) -> Self {
+        Self {
+            settings: settings.clone(),
+            current_line: 0,
+        }
+    }
+
+    fn update(&mut self, new_settings: &Self::Settings) {
+        // Jiné slovníky nebo ignorovaná slova, celý text se musí zvýraznit znovu
+        self.settings = new_settings.clone();
+        self.current_line = 0;
+    }
+
+    fn change_line(&mut self, line: usize) {
+        self.current_line = self.current_line.min(line);
+    }
+
+    fn highlight_line(&mut self, line: &str) -> Self::Iterator<'_> {
+        self.current_line += 1;
+
+        let Some(checker) = &self.settings.checker else {
+            return Vec::new().into_iter();
+        };
+        checker
+            .misspelled_ranges(line)
+            .into_iter()
+            .filter(|range| {
+                !self
+                    .settings
+                    .ignored
+                    .iter()
+                    .any(|word| *word == line[range.clone()])
+            })
+            .map(|range| (range, Misspelled))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn current_line(&self) -> usize {
+        self.current_line
+    }
+}
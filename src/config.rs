@@ -1,37 +1,1086 @@
-use std::{env, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 
+use anyhow::{Context, Result, anyhow, bail};
 use const_format::{Case, formatcp, map_ascii_case};
+use ekkles_data::{
+    bible::verse_cache,
+    theme::{MAX_BACKGROUND_DIMMING, Rgb, SlideTheme},
+    verify,
+};
+use iced::{Color, widget::image};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
 
-use crate::PROGRAM_NAME;
+use crate::{
+    PROGRAM_NAME, companion_image,
+    slide_render::{MAX_VERSES_PER_SLIDE, VERSES_PER_SLIDE},
+};
 
+/// Přepínač při spuštění programu, kterým se zapne režim hosta, viz [`Config::guest_mode`]
+const GUEST_MODE_FLAG: &str = "--guest";
+/// Přepínač při spuštění programu, kterým se zapne offline režim, viz [`Config::is_offline()`]
+const OFFLINE_FLAG: &str = "--offline";
+/// Přepínač při spuštění programu, kterým se zapne diagnostický režim, viz [`crate::diagnostics`]
+const DIAGNOSTICS_FLAG: &str = "--diagnostics";
+/// Název profilu, který se vytvoří, pokud žádný neexistuje
+pub const DEFAULT_PROFILE_NAME: &str = "Výchozí";
+const PIN_MIN_LENGTH: usize = 4;
+const PIN_MAX_LENGTH: usize = 8;
 const DATABASE_NAME: &str = "database.sqlite3";
+const SETTINGS_NAME: &str = "settings.toml";
+const THUMBNAILS_DIR_NAME: &str = "thumbnails";
+const PLUGINS_DIR_NAME: &str = "plugins";
+const DICTIONARIES_DIR_NAME: &str = "dictionaries";
+/// Složka se slovníky hunspellu nainstalovanými v systému
+const SYSTEM_DICTIONARIES_PATH: &str = "/usr/share/hunspell";
+/// Výchozí port MQTT brokeru (nešifrované spojení)
+pub const DEFAULT_MQTT_PORT: u16 = 1883;
+/// Výchozí prefix MQTT témat, viz [`MqttSettings::topic_prefix()`]
+pub const DEFAULT_MQTT_TOPIC_PREFIX: &str = "ekkles";
+/// Standardní UDP port Art-Net uzlů
+pub const DEFAULT_ARTNET_PORT: u16 = 6454;
+/// Počet kanálů v jednom DMX universe
+pub const DMX_CHANNELS: u16 = 512;
+/// Nejvyšší číslo universe, které lze adresovat přes Art-Net (15bitová port-address)
+pub const MAX_ARTNET_UNIVERSE: u16 = 0x7fff;
+/// Výchozí počet minut před koncem bohoslužby, kdy se začne varovat
+pub const SERVICE_END_WARNING_MINUTES: u8 = 10;
+/// Nejvyšší počet minut před koncem bohoslužby, kdy se začne varovat
+pub const MAX_SERVICE_END_WARNING_MINUTES: u8 = 60;
+/// Výchozí velikost cache veršů Bible v MB, viz [`ekkles_data::bible::verse_cache`]
+pub const VERSE_CACHE_MB: u16 = 16;
+/// Nejvyšší velikost cache veršů Bible v MB
+pub const MAX_VERSE_CACHE_MB: u16 = 256;
+/// Kolik naposledy otevřených databází (a složek importu) se pamatuje, viz [`RecentFiles`]
+pub const MAX_RECENT_FILES: usize = 8;
 const DEFAULT_USER_DATA_DIR: &str = ".local/share";
+const DEFAULT_USER_CONFIG_DIR: &str = ".config";
+const DEFAULT_USER_CACHE_DIR: &str = ".cache";
 const DB_PATH_ENV: &str = formatcp!("{}_DB_PATH", map_ascii_case!(Case::Upper, PROGRAM_NAME));
+const SETTINGS_PATH_ENV: &str = formatcp!(
+    "{}_SETTINGS_PATH",
+    map_ascii_case!(Case::Upper, PROGRAM_NAME)
+);
+const CACHE_PATH_ENV: &str = formatcp!("{}_CACHE_PATH", map_ascii_case!(Case::Upper, PROGRAM_NAME));
+const PLUGINS_PATH_ENV: &str = formatcp!(
+    "{}_PLUGINS_PATH",
+    map_ascii_case!(Case::Upper, PROGRAM_NAME)
+);
+const DICTIONARIES_PATH_ENV: &str = formatcp!(
+    "{}_DICTIONARIES_PATH",
+    map_ascii_case!(Case::Upper, PROGRAM_NAME)
+);
 
 /// Konfigurace Ekklesu
 #[derive(Debug)]
 pub struct Config {
     /// Cesta k databázi s daty
     pub db_path: PathBuf,
+    /// Cesta k souboru s uživatelským nastavením, pokud je `None`, nastavení se neukládá
+    pub settings_path: Option<PathBuf>,
+    /// Uživatelské nastavení aktivního profilu, viz [`Settings`]
+    pub settings: Settings,
+    /// Pojmenované profily, nastavení aktivního z nich je v [`Config::settings`]
+    pub profiles: Profiles,
+    /// Složka s náhledy slajdů, pokud je `None`, náhledy se neukládají na disk
+    pub thumbnails_path: Option<PathBuf>,
+    /// Složka s pluginy (viz [`crate::plugins`]), pokud je `None`, žádné se nenačítají
+    pub plugins_path: Option<PathBuf>,
+    /// Složky se slovníky pro kontrolu pravopisu (viz [`ekkles_data::spellcheck`]),
+    /// prohledávají se v tomto pořadí
+    pub dictionaries_paths: Vec<PathBuf>,
+    /// Režim hosta, playlisty lze pouze prezentovat, ne upravovat nebo mazat.
+    /// Zapíná se přepínačem [`GUEST_MODE_FLAG`] při spuštění nebo zamčením úprav
+    /// ve výběru playlistů, vypnout jej lze pouze restartem programu.
+    pub guest_mode: bool,
+    /// Offline režim zapnutý přepínačem [`OFFLINE_FLAG`] při spuštění (bez ohledu na nastavení)
+    pub offline_flag: bool,
+    /// Diagnostický režim (zaznamenávání SQL dotazů) zapnutý přepínačem [`DIAGNOSTICS_FLAG`]
+    /// při spuštění, viz [`crate::diagnostics`]
+    pub diagnostics: bool,
 }
 
 impl Config {
     pub fn new() -> Self {
-        Self { db_path: db_path() }
+        let settings_path = settings_path();
+
+        let profiles = match &settings_path {
+            Some(path) if path.exists() => Profiles::load(path).unwrap_or_else(|e| {
+                warn!("Nelze načíst nastavení, používám výchozí: {:?}", e);
+                Profiles::default()
+            }),
+            _ => Profiles::default(),
+        };
+        let settings = profiles.active_settings().clone();
+
+        Self {
+            db_path: db_path(&settings),
+            settings_path,
+            settings,
+            profiles,
+            thumbnails_path: cache_path().map(|path| path.join(THUMBNAILS_DIR_NAME)),
+            plugins_path: plugins_path(),
+            dictionaries_paths: dictionaries_paths(),
+            guest_mode: env::args().skip(1).any(|arg| arg == GUEST_MODE_FLAG),
+            offline_flag: env::args().skip(1).any(|arg| arg == OFFLINE_FLAG),
+            diagnostics: diagnostics_flag(),
+        }
+    }
+
+    /// Zda je zapnutý offline režim, ve kterém Ekkles nepřistupuje k síti, viz [`crate::network`].
+    /// Zapíná se v nastavení ([`Settings::offline_mode`]), přepínačem [`OFFLINE_FLAG`]
+    /// při spuštění, nebo je zapnutý vždy, pokud byl Ekkles přeložen bez podpory sítě.
+    pub fn is_offline(&self) -> bool {
+        self.offline_flag || self.settings.offline_mode || !crate::network::NETWORK_SUPPORTED
+    }
+
+    /// Uloží aktuální nastavení (do aktivního profilu) do souboru s nastavením. Pokud
+    /// není cesta k souboru nastavena, je to no-op. V případě chyby při zápisu vrací Error.
+    pub fn save_settings(&mut self) -> Result<()> {
+        self.settings.apply_data_settings();
+        self.profiles
+            .profiles
+            .insert(self.profiles.active.clone(), self.settings.clone());
+
+        match &self.settings_path {
+            Some(path) => self.profiles.save(path),
+            None => {
+                info!("Cesta k souboru s nastavením není nastavena, neukládám");
+                Ok(())
+            }
+        }
+    }
+
+    /// Vrátí cestu k databázi profilu `name`, pokud profil neexistuje, vrací `None`.
+    pub fn profile_db_path(&self, name: &str) -> Option<PathBuf> {
+        if name == self.profiles.active {
+            return Some(self.db_path.clone());
+        }
+        self.profiles.profiles.get(name).map(db_path)
+    }
+
+    /// Přepne na profil `name` (jeho nastavení i cestu k databázi), volbu neukládá.
+    /// Databázi samotnou nepřepíná, to je na volajícím. Pokud profil neexistuje,
+    /// vrací Error a nic nemění.
+    pub fn switch_profile(&mut self, name: &str) -> Result<()> {
+        let settings = self.profiles.activate(name, self.settings.clone())?;
+        self.db_path = db_path(&settings);
+        settings.apply_data_settings();
+        self.settings = settings;
+
+        Ok(())
+    }
+
+    /// Vytvoří nový profil `name` jako kopii nastavení aktivního profilu a uloží jej.
+    pub fn create_profile(&mut self, name: &str) -> Result<()> {
+        self.profiles.create(name, self.settings.clone())?;
+
+        self.save_settings()
+    }
+
+    /// Smaže profil `name` a uloží zbylé profily. Aktivní profil smazat nelze.
+    pub fn delete_profile(&mut self, name: &str) -> Result<()> {
+        self.profiles.delete(name)?;
+
+        self.save_settings()
+    }
+}
+
+/// Pojmenované profily (např. pro jednotlivé sbory nebo místa), každý má vlastní
+/// nastavení včetně databáze a vzhledu. Ukládají se do souboru s nastavením.
+///
+/// ### Formát
+/// Soubor s nastavením ze starších verzí (pouze jedno [`Settings`] bez profilů) se načte
+/// jako jediný profil [`DEFAULT_PROFILE_NAME`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profiles {
+    /// Název aktivního (naposledy zvoleného) profilu
+    pub active: String,
+    /// Nastavení jednotlivých profilů podle názvu
+    pub profiles: BTreeMap<String, Settings>,
+    /// Naposledy otevřené databáze a složky importu, společné pro všechny profily
+    pub recent: RecentFiles,
+}
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Self::from_settings(Settings::default())
+    }
+}
+
+impl Profiles {
+    /// Vytvoří jediný profil [`DEFAULT_PROFILE_NAME`] s nastavením `settings`
+    fn from_settings(settings: Settings) -> Self {
+        Self {
+            active: String::from(DEFAULT_PROFILE_NAME),
+            profiles: BTreeMap::from([(String::from(DEFAULT_PROFILE_NAME), settings)]),
+            recent: RecentFiles::default(),
+        }
+    }
+
+    /// Načte profily ze souboru s nastavením na cestě `path`, pokud soubor nelze přečíst
+    /// nebo není ve správném formátu, vrací Error.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Nelze přečíst soubor s nastavením {}", path.display()))?;
+
+        Self::parse(&content)
+            .with_context(|| format!("Nelze zparsovat soubor s nastavením {}", path.display()))
+    }
+
+    /// Zparsuje profily z obsahu souboru s nastavením. Pokud aktivní profil neexistuje,
+    /// aktivuje se první profil (případně se vytvoří výchozí).
+    fn parse(content: &str) -> Result<Self> {
+        let table: toml::Table = toml::from_str(content)?;
+
+        let mut profiles = if table.contains_key("profiles") {
+            table.try_into()?
+        } else {
+            Self::from_settings(table.try_into()?)
+        };
+
+        if !profiles.profiles.contains_key(&profiles.active) {
+            match profiles.profiles.keys().next() {
+                Some(first) => profiles.active = first.clone(),
+                None => profiles = Self::default(),
+            }
+        }
+
+        Ok(profiles)
+    }
+
+    /// Uloží profily do souboru s nastavením na cestě `path`, viz [`Settings::save()`].
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self).context("Nelze serializovat nastavení")?;
+
+        write_settings_file(path, &content)
+    }
+
+    /// Nastavení aktivního profilu
+    pub fn active_settings(&self) -> &Settings {
+        &self.profiles[&self.active]
+    }
+
+    /// Názvy všech profilů (seřazené)
+    pub fn names(&self) -> Vec<String> {
+        self.profiles.keys().cloned().collect()
+    }
+
+    /// Uloží `current` (nastavení aktivního profilu) a aktivuje profil `name`,
+    /// vrátí jeho nastavení. Pokud profil neexistuje, vrací Error a nic nemění.
+    fn activate(&mut self, name: &str, current: Settings) -> Result<Settings> {
+        if !self.profiles.contains_key(name) {
+            bail!("Profil \"{name}\" neexistuje");
+        }
+
+        self.profiles.insert(self.active.clone(), current);
+        self.active = name.to_string();
+
+        Ok(self.profiles[name].clone())
+    }
+
+    /// Přidá nový profil `name` s nastavením `settings`. Název nesmí být prázdný
+    /// a nesmí se shodovat s existujícím profilem.
+    fn create(&mut self, name: &str, settings: Settings) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("Název profilu nesmí být prázdný");
+        }
+        if self.profiles.contains_key(name) {
+            bail!("Profil s názvem \"{name}\" již existuje");
+        }
+
+        self.profiles.insert(name.to_string(), settings);
+        Ok(())
+    }
+
+    /// Smaže profil `name`, aktivní profil smazat nelze.
+    fn delete(&mut self, name: &str) -> Result<()> {
+        if name == self.active {
+            bail!("Aktivní profil nelze smazat, nejdříve přepni na jiný");
+        }
+
+        self.profiles
+            .remove(name)
+            .with_context(|| format!("Profil \"{name}\" neexistuje"))?;
+        Ok(())
+    }
+}
+
+/// Naposledy otevřené databáze a složky, ze kterých se importovaly písně, aby je při
+/// přepínání mezi prostředími (např. sbory) nebylo nutné pokaždé hledat v dialogu.
+/// Nejnovější je první, pamatuje se nejvýše [`MAX_RECENT_FILES`] položek.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecentFiles {
+    /// Naposledy otevřené databáze
+    pub databases: Vec<PathBuf>,
+    /// Složky, ze kterých se naposledy importovaly písně
+    pub import_dirs: Vec<PathBuf>,
+}
+
+impl RecentFiles {
+    /// Zapamatuje si otevřenou databázi `path`
+    pub fn add_database(&mut self, path: &Path) {
+        push_recent(&mut self.databases, path);
+    }
+
+    /// Zapamatuje si složku `dir`, ze které se importovaly písně
+    pub fn add_import_dir(&mut self, dir: &Path) {
+        push_recent(&mut self.import_dirs, dir);
+    }
+
+    /// Naposledy otevřené databáze kromě `current`, které na disku stále existují
+    pub fn other_databases(&self, current: &Path) -> Vec<PathBuf> {
+        self.databases
+            .iter()
+            .filter(|path| path.as_path() != current && path.is_file())
+            .cloned()
+            .collect()
+    }
+
+    /// Poslední složka importu, která na disku stále existuje (dialog importu se otevře v ní)
+    pub fn last_import_dir(&self) -> Option<PathBuf> {
+        self.import_dirs.iter().find(|dir| dir.is_dir()).cloned()
+    }
+
+    /// Zapomene všechny naposledy otevřené databáze i složky
+    pub fn clear(&mut self) {
+        self.databases.clear();
+        self.import_dirs.clear();
+    }
+}
+
+/// Přesune (případně přidá) `path` na začátek seznamu `recent`, nejstarší položky
+/// nad [`MAX_RECENT_FILES`] zahodí
+fn push_recent(recent: &mut Vec<PathBuf>, path: &Path) {
+    recent.retain(|recent_path| recent_path != path);
+    recent.insert(0, path.to_path_buf());
+    recent.truncate(MAX_RECENT_FILES);
+}
+
+/// Uživatelské nastavení, které se ukládá do souboru a lze jej jako celek
+/// exportovat a importovat (tzv. profil). Díky tomu lze rychle nastavit
+/// náhradní počítač na stejné chování, jako měl původní.
+///
+/// ### Formát
+/// Nastavení je uloženo ve formátu TOML, chybějící položky jsou doplněny
+/// na výchozí hodnoty, aby šly načíst i profily ze starších verzí.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Vzhled prezentovaných slajdů
+    pub theme: PresentationTheme,
+    /// Klávesové zkratky prezentéru
+    pub keymap: Keymap,
+    /// Název překladu, který bude předvybrán při výběru biblické pasáže
+    pub default_translation: Option<String>,
+    /// Na jakém monitoru se otevře prezentační okno
+    pub monitor: MonitorMapping,
+    /// Schválené weby (doménová jména), ze kterých lze stáhnout slova písní.
+    /// Schvaluje se ručně v souboru s nastavením, výchozí je žádný web.
+    pub lyrics_sources: Vec<String>,
+    /// Licenční režim, u písní bez licence (viz [`ekkles_data::license`]) se zobrazuje
+    /// varování a jejich promítnutí je potřeba potvrdit
+    pub licensing_mode: bool,
+    /// Databáze zvolená v aplikaci (přepínání mezi databázemi, např. dvou sborů),
+    /// pokud je `None`, použije se výchozí umístění, viz [`db_path()`].
+    /// Do exportovaného profilu se neukládá, cesta platí pouze pro tento počítač.
+    pub database_path: Option<PathBuf>,
+    /// PIN vyžadovaný pro mazání playlistů a profilů a pro vstup do nastavení (počítač sdílí
+    /// více dobrovolníků), pokud je `None`, nic se nevyžaduje. Jde pouze o ochranu před
+    /// nechtěnými zásahy, v souboru s nastavením je uložen v čitelné podobě.
+    /// Do exportovaného profilu se neukládá.
+    pub pin: Option<String>,
+    /// Makra prezentéru spouštěná klávesovou zkratkou, exportují se spolu s profilem
+    pub macros: Vec<PresenterMacro>,
+    /// Rychlé slajdy promítnutelné kdykoliv během prezentace, viz [`QuickSlide`]
+    pub quick_slides: Vec<QuickSlide>,
+    /// Offline režim, Ekkles nepřistupuje k síti (sbory se zákazem sítě na promítacím
+    /// počítači), exportuje se spolu s profilem, viz [`Config::is_offline()`]
+    pub offline_mode: bool,
+    /// Příkazy spouštěné při událostech prezentace, viz [`crate::event_hooks`]. Zadávají se
+    /// ručně v souboru s nastavením a do exportovaného profilu se neukládají, skripty
+    /// platí pouze pro tento počítač.
+    pub event_hooks: EventHooks,
+    /// Počet veršů na slajdu při automatickém rozdělení pasáže, pokud je `None`, použije
+    /// se výchozí [`VERSES_PER_SLIDE`], viz [`Settings::verses_per_slide()`]
+    pub verses_per_slide: Option<u8>,
+    /// Zobrazovat nad prvním veršem pasáže nadpis (např. nadpis žalmu), pokud jej překlad má,
+    /// viz [`ekkles_data::bible::heading`]
+    pub passage_headings: bool,
+    /// Nejvyšší počet řádků části písně na jednom slajdu, delší části se rozdělí na více
+    /// slajdů (viz [`crate::slide_render::split_song_part()`]), `None` = části se nedělí
+    pub max_song_lines: Option<u8>,
+    /// Cesta, kam se během prezentace průběžně zapisuje promítaný slajd jako PNG (pro OBS
+    /// nebo digital signage), `None` = nezapisuje se, viz [`crate::companion_image`].
+    /// Do exportovaného profilu se neukládá, cesta platí pouze pro tento počítač.
+    pub companion_image_path: Option<PathBuf>,
+    /// Interval zápisu obrázku prezentace v milisekundách, pokud je `None`, použije se
+    /// výchozí, viz [`Settings::companion_image_interval()`]
+    pub companion_image_interval_ms: Option<u64>,
+    /// Publikování stavu prezentace do MQTT brokeru, viz [`crate::network::mqtt`]
+    pub mqtt: MqttSettings,
+    /// Odesílání světelných cue položek playlistu přes Art-Net, viz [`crate::network::lighting`]
+    pub lighting: LightingSettings,
+    /// Varování před koncem bohoslužby a akce v jejím konci, viz [`ServiceEndSettings`]
+    pub service_end: ServiceEndSettings,
+    /// Odpočet před začátkem bohoslužby, viz [`CountdownSettings`]
+    pub countdown: CountdownSettings,
+    /// Velikost cache veršů Bible v MB (0 = vypnutá), pokud je `None`, použije se výchozí
+    /// [`VERSE_CACHE_MB`], viz [`Settings::verse_cache_mb()`]
+    pub verse_cache_mb: Option<u16>,
+    /// Po uložení písně nebo playlistu je načíst zpět a porovnat (kontrola poškození dat
+    /// při ukládání), viz [`ekkles_data::verify`]
+    pub verify_saves: bool,
+}
+
+impl Settings {
+    /// Načte nastavení ze souboru na cestě `path`, pokud soubor nelze přečíst nebo
+    /// není ve správném formátu, vrací Error.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Nelze přečíst soubor s nastavením {}", path.display()))?;
+
+        toml::from_str(&content)
+            .with_context(|| format!("Nelze zparsovat soubor s nastavením {}", path.display()))
+    }
+
+    /// Uloží nastavení do souboru na cestě `path`, pokud neexistuje složka, ve které
+    /// má soubor být, bude vytvořena. Existující soubor bude přepsán.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self).context("Nelze serializovat nastavení")?;
+
+        write_settings_file(path, &content)
+    }
+
+    /// Exportuje celé nastavení jako profil do souboru `path`.
+    pub fn export_profile(&self, path: &Path) -> Result<()> {
+        let profile = Self {
+            database_path: None,
+            pin: None,
+            event_hooks: EventHooks::default(),
+            companion_image_path: None,
+            ..self.clone()
+        };
+        profile.save(path).context("Nelze exportovat profil")
+    }
+
+    /// Importuje profil ze souboru `path`, vrátí nové nastavení (aktuální nastavení
+    /// nijak nemodifikuje, je na volajícím, aby jej nahradil). Cestu k databázi, PIN,
+    /// příkazy událostí ani cestu obrázku prezentace profil nenese, volající by měl
+    /// zachovat ty aktuální.
+    pub fn import_profile(path: &Path) -> Result<Self> {
+        let profile = Self::load(path).context("Nelze importovat profil")?;
+        profile
+            .keymap
+            .validate()
+            .context("Nelze importovat profil, klávesové zkratky se kryjí")?;
+
+        Ok(profile)
+    }
+
+    /// Vrátí počet veršů na slajdu, omezený na 1 až [`MAX_VERSES_PER_SLIDE`]
+    pub fn verses_per_slide(&self) -> u8 {
+        self.verses_per_slide
+            .unwrap_or(VERSES_PER_SLIDE)
+            .clamp(1, MAX_VERSES_PER_SLIDE)
+    }
+
+    /// Vrátí velikost cache veršů Bible v MB, nejvýše [`MAX_VERSE_CACHE_MB`]
+    pub fn verse_cache_mb(&self) -> u16 {
+        self.verse_cache_mb
+            .unwrap_or(VERSE_CACHE_MB)
+            .min(MAX_VERSE_CACHE_MB)
+    }
+
+    /// Předá nastavení práce s daty do [`ekkles_data`], tedy velikost cache veršů Bible
+    /// (viz [`Settings::verse_cache_mb()`]) a kontrolu zápisu (viz [`Settings::verify_saves`])
+    pub fn apply_data_settings(&self) {
+        verse_cache::set_capacity(usize::from(self.verse_cache_mb()) * 1024 * 1024);
+        verify::set_enabled(self.verify_saves);
+    }
+
+    /// Vrátí interval zápisu obrázku prezentace, nejkratší je
+    /// [`companion_image::MIN_INTERVAL`]
+    pub fn companion_image_interval(&self) -> Duration {
+        self.companion_image_interval_ms
+            .map(Duration::from_millis)
+            .unwrap_or(companion_image::DEFAULT_INTERVAL)
+            .max(companion_image::MIN_INTERVAL)
+    }
+
+    /// Zjistí, zda je web `host` schválený zdroj slov písní, tedy zda je v
+    /// [`Settings::lyrics_sources`] on sám nebo některá jeho nadřazená doména.
+    pub fn is_approved_lyrics_source(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+        self.lyrics_sources.iter().any(|source| {
+            let source = source.trim().to_lowercase();
+            !source.is_empty()
+                && (host == source
+                    || host
+                        .strip_suffix(&source)
+                        .is_some_and(|subdomain| subdomain.ends_with('.')))
+        })
+    }
+
+    /// Zjistí, zda `input` odpovídá nastavenému PINu, pokud PIN nastaven není, vrací `true`.
+    pub fn check_pin(&self, input: &str) -> bool {
+        self.pin.as_deref().is_none_or(|pin| pin == input.trim())
+    }
+}
+
+/// Zvaliduje PIN zadaný v nastavení, prázdný vstup znamená bez PINu (`None`).
+/// PIN musí mít [`PIN_MIN_LENGTH`] až [`PIN_MAX_LENGTH`] číslic, jinak vrací Error.
+pub fn parse_pin(input: &str) -> Result<Option<String>> {
+    let pin = input.trim();
+    if pin.is_empty() {
+        return Ok(None);
+    }
+
+    if !pin.chars().all(|c| c.is_ascii_digit()) {
+        bail!("PIN smí obsahovat pouze číslice");
+    }
+    if !(PIN_MIN_LENGTH..=PIN_MAX_LENGTH).contains(&pin.len()) {
+        bail!("PIN musí mít {PIN_MIN_LENGTH} až {PIN_MAX_LENGTH} číslic");
+    }
+
+    Ok(Some(pin.to_string()))
+}
+
+/// Zparsuje makra zadaná v nastavení, jedno na řádek (viz [`PresenterMacro`]),
+/// prázdné řádky se přeskakují. Dvě makra nesmí mít stejnou klávesu.
+pub fn parse_macros(input: &str) -> Result<Vec<PresenterMacro>> {
+    let mut macros: Vec<PresenterMacro> = Vec::new();
+
+    for (line_number, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let presenter_macro: PresenterMacro = line
+            .parse()
+            .with_context(|| format!("Chyba na řádku {}", line_number + 1))?;
+        if macros.iter().any(|other| other.key == presenter_macro.key) {
+            bail!(
+                "Klávesa \"{}\" je přiřazena více makrům",
+                presenter_macro.key
+            );
+        }
+        macros.push(presenter_macro);
+    }
+
+    Ok(macros)
+}
+
+/// Vzhled prezentovaných slajdů
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresentationTheme {
+    /// Barva textu
+    pub text_color: Rgb,
+    /// Barva pozadí
+    pub background_color: Rgb,
+    /// Obrázek na pozadí, pochází pouze z motivů v databázi, do nastavení se neukládá
+    #[serde(skip)]
+    pub background_image: Option<PresentationBackground>,
+}
+
+/// Obrázek na pozadí slajdů připravený k vykreslení
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresentationBackground {
+    /// ID média, ze kterého obrázek pochází
+    pub media_id: i64,
+    /// Zmenšená verze obrázku
+    pub handle: image::Handle,
+    /// Ztmavení obrázku v procentech, viz [`ekkles_data::theme::BackgroundImage`]
+    pub dimming: u8,
+}
+
+impl PresentationBackground {
+    /// Barva poloprůhledné vrstvy, která obrázek ztmaví
+    pub fn dimming_color(&self) -> Color {
+        Color {
+            a: f32::from(self.dimming.min(MAX_BACKGROUND_DIMMING))
+                / f32::from(MAX_BACKGROUND_DIMMING),
+            ..Color::BLACK
+        }
+    }
+}
+
+impl Default for PresentationTheme {
+    fn default() -> Self {
+        Self {
+            text_color: [0xff, 0xff, 0xff],
+            background_color: [0x00, 0x00, 0x00],
+            background_image: None,
+        }
+    }
+}
+
+impl PresentationTheme {
+    pub fn text_color(&self) -> Color {
+        let [r, g, b] = self.text_color;
+        Color::from_rgb8(r, g, b)
+    }
+
+    pub fn background_color(&self) -> Color {
+        let [r, g, b] = self.background_color;
+        Color::from_rgb8(r, g, b)
+    }
+
+    /// Motiv z databáze přebije barvy z nastavení. Obrázek na pozadí se dohledá
+    /// v `backgrounds` (id média -> obrázek), pokud tam není, zůstane pouze barva pozadí.
+    pub fn from_slide_theme(
+        theme: &SlideTheme,
+        backgrounds: &BTreeMap<i64, image::Handle>,
+    ) -> Self {
+        Self {
+            text_color: theme.text_color,
+            background_color: theme.background_color,
+            background_image: theme.background_image.and_then(|background| {
+                backgrounds
+                    .get(&background.media_id)
+                    .map(|handle| PresentationBackground {
+                        media_id: background.media_id,
+                        handle: handle.clone(),
+                        dimming: background.dimming,
+                    })
+            }),
+        }
+    }
+}
+
+/// Klávesové zkratky prezentéru pro změnu režimu prezentace.
+///
+/// ### Formát
+/// Každá zkratka je jeden znak, při porovnání se používají malá písmena
+/// (tak, jak je hlásí [`iced::keyboard::Key::Character`]).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keymap {
+    pub freeze: String,
+    pub normal: String,
+    pub blank: String,
+    /// Přepíná jazyk písní s překladem (originál → překlad → obojí)
+    pub language: String,
+    /// Zopakuje vybranou část písně (vloží její kopii za ni), viz
+    /// [`crate::presenter::Message::RepeatSongPart`]
+    pub repeat_part: String,
+}
+
+impl Keymap {
+    /// Vrátí všechny zkratky spolu s popisem, co dělají
+    pub fn keys(&self) -> [(&String, &'static str); 5] {
+        [
+            (&self.freeze, "zmrazení"),
+            (&self.normal, "normál"),
+            (&self.blank, "prázdný snímek"),
+            (&self.language, "přepnutí jazyka"),
+            (&self.repeat_part, "opakování části písně"),
+        ]
+    }
+
+    /// Zkontroluje, že se žádné dvě zkratky nekryjí (jedna z nich by nešla použít),
+    /// pokud ano, vrací Error s popisem kolize.
+    pub fn validate(&self) -> Result<()> {
+        let keys = self.keys();
+        for (i, (key, name)) in keys.iter().enumerate() {
+            if let Some((_, other)) = keys[..i].iter().find(|(other_key, _)| other_key == key) {
+                bail!("Klávesa \"{key}\" pro {name} je již použita pro {other}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            freeze: String::from("f"),
+            normal: String::from("n"),
+            blank: String::from("b"),
+            language: String::from("j"),
+            repeat_part: String::from("r"),
+        }
+    }
+}
+
+/// Určuje, kde se otevře prezentační okno. Okno se otevře v režimu celé obrazovky
+/// na monitoru, na kterém leží bod `position`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MonitorMapping {
+    /// Pozice (x, y) v logických pixelech, pokud je `None`, nechá se umístění
+    /// okna na operačním systému
+    pub position: Option<(f32, f32)>,
+}
+
+/// Příkazy shellu spouštěné při událostech prezentace (např. přepnutí světel na pódiu),
+/// pokud je příkaz `None`, při dané události se nic nespouští.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventHooks {
+    /// Otevření prezentačního okna
+    pub presentation_started: Option<String>,
+    /// Přepnutí na jiný slajd
+    pub slide_changed: Option<String>,
+    /// Začernění prezentace
+    pub blanked: Option<String>,
+    /// Jakákoliv změna režimu prezentace (normál, začernění, zmrazení, rychlý slajd)
+    pub mode_changed: Option<String>,
+    /// Ukončení prezentace
+    pub presentation_ended: Option<String>,
+}
+
+/// Připojení k MQTT brokeru pro chrámy s domácí automatizací (světla, kamery, ...).
+/// Pokud je adresa brokeru prázdná, MQTT se nepoužívá.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttSettings {
+    /// Adresa brokeru ve tvaru `host` nebo `host:port`, výchozí port je [`DEFAULT_MQTT_PORT`]
+    pub broker: String,
+    /// Prefix témat, pokud je prázdný, použije se [`DEFAULT_MQTT_TOPIC_PREFIX`]
+    pub topic_prefix: String,
+    /// Zda přijímat ovládání prezentace z tématu `prefix/control`
+    pub control: bool,
+}
+
+impl MqttSettings {
+    /// Vrátí adresu brokeru jako dvojici (host, port), pokud je MQTT vypnuté, vrací `None`.
+    /// Pokud adresa není validní, vrací Error.
+    pub fn broker_address(&self) -> Result<Option<(String, u16)>> {
+        host_and_port(&self.broker, DEFAULT_MQTT_PORT).context("Neplatná adresa MQTT brokeru")
+    }
+
+    /// Vrátí prefix témat bez okrajových lomítek
+    pub fn topic_prefix(&self) -> String {
+        match self.topic_prefix.trim().trim_matches('/') {
+            "" => String::from(DEFAULT_MQTT_TOPIC_PREFIX),
+            prefix => prefix.to_string(),
+        }
+    }
+}
+
+/// Odesílání světelných cue osvětlovacímu pultu přes Art-Net: číslo cue položky playlistu
+/// se při jejím promítnutí nastaví jako hodnota kanálu `channel` v DMX universe `universe`
+/// (pult na něj reaguje spuštěním cue, např. ztlumením světel v lodi při kázání).
+/// Pokud je adresa uzlu prázdná, cue se neodesílají.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LightingSettings {
+    /// Adresa Art-Net uzlu (nebo broadcastu sítě) ve tvaru `host` nebo `host:port`,
+    /// výchozí port je [`DEFAULT_ARTNET_PORT`]
+    pub node: String,
+    /// Číslo DMX universe (Art-Net port-address, 0 až [`MAX_ARTNET_UNIVERSE`])
+    pub universe: u16,
+    /// Kanál v universe (1 až [`DMX_CHANNELS`]), 0 znamená první kanál
+    pub channel: u16,
+}
+
+impl LightingSettings {
+    /// Vrátí adresu Art-Net uzlu jako dvojici (host, port), pokud je odesílání cue vypnuté,
+    /// vrací `None`. Pokud adresa není validní, vrací Error.
+    pub fn node_address(&self) -> Result<Option<(String, u16)>> {
+        host_and_port(&self.node, DEFAULT_ARTNET_PORT).context("Neplatná adresa Art-Net uzlu")
+    }
+
+    /// Vrátí kanál, na který se cue odesílá, omezený na 1 až [`DMX_CHANNELS`]
+    pub fn channel(&self) -> u16 {
+        self.channel.clamp(1, DMX_CHANNELS)
+    }
+}
+
+/// Hlídání konce bohoslužby (sbory s více bohoslužbami za sebou): obsluha v prezentéru
+/// zadá čas konce, s blížícím se koncem se v ovládacím okně zobrazuje stále výraznější
+/// varování a v čase konce se provede [`ServiceEndAction`].
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServiceEndSettings {
+    /// Kolik minut před koncem se začne varovat, pokud je `None`, použije se výchozí
+    /// [`SERVICE_END_WARNING_MINUTES`], viz [`ServiceEndSettings::warning_minutes()`]
+    pub warning_minutes: Option<u8>,
+    /// Co se stane v čase konce
+    pub action: ServiceEndAction,
+}
+
+impl ServiceEndSettings {
+    /// Vrátí počet minut před koncem, kdy se začne varovat, omezený na 1 až
+    /// [`MAX_SERVICE_END_WARNING_MINUTES`]
+    pub fn warning_minutes(&self) -> u8 {
+        self.warning_minutes
+            .unwrap_or(SERVICE_END_WARNING_MINUTES)
+            .clamp(1, MAX_SERVICE_END_WARNING_MINUTES)
+    }
+}
+
+/// Odpočet před začátkem bohoslužby: před první položkou playlistu se promítá slajd
+/// "Bohoslužba začíná za 04:32", v čase začátku prezentace sama přejde na první slajd.
+/// Čas začátku se zadává v prezentéru, zde je pouze předvyplněný.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CountdownSettings {
+    /// Předvyplněný čas začátku bohoslužby (`HH:MM`), `None` = nic se nepředvyplní
+    pub start_time: Option<String>,
+    /// Barva pozadí slajdu s odpočtem, `None` = pozadí z motivu prezentace
+    pub background_color: Option<Rgb>,
+}
+
+impl CountdownSettings {
+    /// Vrátí motiv slajdu s odpočtem, tedy motiv prezentace `theme` s barvou pozadí
+    /// z nastavení odpočtu (pokud je nastavená, obrázek na pozadí se pak nepoužije)
+    pub fn theme(&self, theme: &PresentationTheme) -> PresentationTheme {
+        match self.background_color {
+            Some(background_color) => PresentationTheme {
+                background_color,
+                background_image: None,
+                ..theme.clone()
+            },
+            None => theme.clone(),
+        }
+    }
+}
+
+/// Akce provedená prezentérem v čase konce bohoslužby
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceEndAction {
+    /// Pouze se zobrazí varování, prezentace pokračuje
+    #[default]
+    Warn,
+    /// Prezentace se začerní
+    Blank,
+    /// Promítne se rychlý slajd s daným názvem (např. poděkování a pozvánka na příště),
+    /// viz [`QuickSlide`]
+    QuickSlide(String),
+}
+
+impl fmt::Display for ServiceEndAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceEndAction::Warn => write!(f, "Pouze varovat"),
+            ServiceEndAction::Blank => write!(f, "Začernit"),
+            ServiceEndAction::QuickSlide(name) => write!(f, "Rychlý slajd \"{name}\""),
+        }
+    }
+}
+
+/// Rozdělí adresu `address` ve tvaru `host` nebo `host:port` na dvojici (host, port),
+/// pokud port chybí, použije se `default_port`. Prázdná adresa znamená vypnutou službu
+/// a vrací `None`. Pokud port není číslo nebo chybí host, vrací Error.
+fn host_and_port(address: &str, default_port: u16) -> Result<Option<(String, u16)>> {
+    let address = address.trim();
+    if address.is_empty() {
+        return Ok(None);
+    }
+
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) => (
+            host,
+            port.parse()
+                .with_context(|| format!("Neplatný port \"{port}\""))?,
+        ),
+        None => (address, default_port),
+    };
+    if host.is_empty() {
+        bail!("Chybí adresa");
+    }
+
+    Ok(Some((host.to_string(), port)))
+}
+
+/// Rychlý (nouzový) slajd, který lze kdykoliv během prezentace promítnout vyhrazenou
+/// klávesou a stejnou klávesou se vrátit na předchozí slajd (např. oznámení o špatně
+/// zaparkovaném autě nebo výzva ke ztlumení telefonů).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuickSlide {
+    /// Název slajdu, zobrazuje se pouze obsluze
+    pub name: String,
+    /// Klávesa promítající slajd, jeden znak obdobně jako v [`Keymap`]
+    pub key: String,
+    /// Promítaný text
+    pub content: String,
+}
+
+/// Makro prezentéru, pojmenovaná posloupnost akcí spouštěná jednou klávesou
+/// (např. jednotný začátek bohoslužby nebo nouzové začernění plátna).
+///
+/// ### Formát
+/// V editoru nastavení se makro zapisuje na jeden řádek jako
+/// `klávesa | název | krok, krok, ...`, formát kroků viz [`MacroStep`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresenterMacro {
+    pub name: String,
+    /// Klávesa spouštějící makro, jeden znak obdobně jako v [`Keymap`]
+    pub key: String,
+    pub steps: Vec<MacroStep>,
+}
+
+impl fmt::Display for PresenterMacro {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steps: Vec<String> = self.steps.iter().map(MacroStep::to_string).collect();
+        write!(f, "{} | {} | {}", self.key, self.name, steps.join(", "))
+    }
+}
+
+impl FromStr for PresenterMacro {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.splitn(3, '|').map(str::trim);
+        let (Some(key), Some(name), Some(steps)) = (parts.next(), parts.next(), parts.next())
+        else {
+            bail!("Makro musí být ve tvaru \"klávesa | název | krok, krok, ...\"");
+        };
+
+        if key.chars().count() != 1 {
+            bail!("Klávesa makra musí být právě jeden znak");
+        }
+        if name.is_empty() {
+            bail!("Název makra nesmí být prázdný");
+        }
+        let steps = steps
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<MacroStep>>>()
+            .with_context(|| format!("Neplatný krok makra \"{name}\""))?;
+
+        Ok(Self {
+            name: name.to_string(),
+            key: key.to_lowercase(),
+            steps,
+        })
+    }
+}
+
+/// Jeden krok makra prezentéru.
+///
+/// ### Formát
+/// Kroky se zapisují (i ukládají do souboru s nastavením) textově: `blank`, `normal`,
+/// `freeze`, `next`, `prev`, `goto <číslo slajdu>` a `wait <sekundy>`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum MacroStep {
+    Blank,
+    Normal,
+    Freeze,
+    Next,
+    Prev,
+    /// Skok na slajd s daným pořadím (číslováno od 1)
+    GoTo(usize),
+    /// Počkat, než se provede další krok
+    Wait(Duration),
+}
+
+impl fmt::Display for MacroStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MacroStep::Blank => write!(f, "blank"),
+            MacroStep::Normal => write!(f, "normal"),
+            MacroStep::Freeze => write!(f, "freeze"),
+            MacroStep::Next => write!(f, "next"),
+            MacroStep::Prev => write!(f, "prev"),
+            MacroStep::GoTo(slide) => write!(f, "goto {slide}"),
+            MacroStep::Wait(duration) => write!(f, "wait {}", duration.as_secs_f64()),
+        }
+    }
+}
+
+impl FromStr for MacroStep {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lowercase = s.trim().to_lowercase();
+        let (command, argument) = match lowercase.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, Some(argument.trim())),
+            None => (lowercase.as_str(), None),
+        };
+
+        let step = match (command, argument) {
+            ("blank", None) => MacroStep::Blank,
+            ("normal", None) => MacroStep::Normal,
+            ("freeze", None) => MacroStep::Freeze,
+            ("next", None) => MacroStep::Next,
+            ("prev", None) => MacroStep::Prev,
+            ("goto", Some(slide)) => match slide.parse() {
+                Ok(slide) if slide >= 1 => MacroStep::GoTo(slide),
+                _ => bail!("Číslo slajdu \"{slide}\" musí být kladné celé číslo"),
+            },
+            ("wait", Some(seconds)) => {
+                let duration = seconds
+                    .parse()
+                    .ok()
+                    .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                    .ok_or_else(|| anyhow!("Neplatný počet sekund \"{seconds}\""))?;
+                MacroStep::Wait(duration)
+            }
+            _ => bail!("Neznámý krok makra \"{}\"", s.trim()),
+        };
+
+        Ok(step)
+    }
+}
+
+impl TryFrom<String> for MacroStep {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
     }
 }
 
+impl From<MacroStep> for String {
+    fn from(value: MacroStep) -> Self {
+        value.to_string()
+    }
+}
+
+/// Zapíše `content` do souboru s nastavením na cestě `path`, pokud neexistuje složka,
+/// ve které má soubor být, bude vytvořena. Existující soubor bude přepsán.
+fn write_settings_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).with_context(|| {
+            format!(
+                "Nelze vytvořit složku pro nastavení {}",
+                directory.display()
+            )
+        })?;
+    }
+
+    fs::write(path, content)
+        .with_context(|| format!("Nelze zapsat nastavení do {}", path.display()))
+}
+
 /// Vrátí cestu k databázi, nalezne ji následujícím způsobem:
 /// - Podle proměnné prostředí EKKLES_DB_PATH
+/// - Podle databáze zvolené v aplikaci ([`Settings::database_path`])
 /// - Složka pro uživatelská data
 ///   - Podle $XDG_DATA_HOME a pokud je prázdná, tak ~/.local/share
 /// - V ní se vytvoří (pokud neexistuje složka) s názvem programu [`crate::PROGRAM_NAME`]
 /// - V ní se vybere soubor [`DATABASE_NAME`]
-fn db_path() -> PathBuf {
+/// Zda byl program spuštěn s přepínačem [`DIAGNOSTICS_FLAG`]. Zjišťuje se už před
+/// načtením konfigurace, protože podle něj se inicializuje logger.
+pub fn diagnostics_flag() -> bool {
+    env::args().skip(1).any(|arg| arg == DIAGNOSTICS_FLAG)
+}
+
+fn db_path(settings: &Settings) -> PathBuf {
     if let Ok(path) = env::var(DB_PATH_ENV) {
         return path.into();
     };
 
+    if let Some(path) = &settings.database_path {
+        return path.clone();
+    }
+
     if cfg!(debug_assertions) {
         panic!(
             "Během vývoje nebudu modifikovat domovskou složku, nastav si proměnnou {DB_PATH_ENV} na cestu k vývojové databázi"
@@ -53,3 +1102,474 @@ fn db_path() -> PathBuf {
 
     db_path
 }
+
+/// Vrátí cestu k souboru s nastavením, nalezne ji obdobně jako [`db_path()`]:
+/// - Podle proměnné prostředí EKKLES_SETTINGS_PATH
+/// - Během vývoje (bez této proměnné) se nastavení neukládá, vrací `None`
+/// - Podle $XDG_CONFIG_HOME a pokud je prázdná, tak ~/.config
+/// - V ní složka s názvem programu [`crate::PROGRAM_NAME`] a v ní soubor [`SETTINGS_NAME`]
+fn settings_path() -> Option<PathBuf> {
+    if let Ok(path) = env::var(SETTINGS_PATH_ENV) {
+        return Some(path.into());
+    };
+
+    if cfg!(debug_assertions) {
+        warn!(
+            "Během vývoje nebudu modifikovat domovskou složku, nastavení se nebude ukládat (nastav {SETTINGS_PATH_ENV})"
+        );
+        return None;
+    }
+
+    let user_config_directory = match env::var("XDG_CONFIG_HOME") {
+        Ok(s) => PathBuf::from(s),
+        Err(_) => {
+            let home_dir =
+                PathBuf::from(env::var("HOME").expect("Proměnná prostředí HOME není definovaná"));
+            home_dir.join(DEFAULT_USER_CONFIG_DIR)
+        }
+    };
+
+    Some(user_config_directory.join(PROGRAM_NAME).join(SETTINGS_NAME))
+}
+
+/// Vrátí cestu ke složce s cache (náhledy slajdů), nalezne ji obdobně jako [`settings_path()`]:
+/// - Podle proměnné prostředí EKKLES_CACHE_PATH
+/// - Během vývoje (bez této proměnné) se cache na disk neukládá, vrací `None`
+/// - Podle $XDG_CACHE_HOME a pokud je prázdná, tak ~/.cache
+/// - V ní složka s názvem programu [`crate::PROGRAM_NAME`]
+fn cache_path() -> Option<PathBuf> {
+    if let Ok(path) = env::var(CACHE_PATH_ENV) {
+        return Some(path.into());
+    };
+
+    if cfg!(debug_assertions) {
+        return None;
+    }
+
+    let user_cache_directory = match env::var("XDG_CACHE_HOME") {
+        Ok(s) => PathBuf::from(s),
+        Err(_) => {
+            let home_dir =
+                PathBuf::from(env::var("HOME").expect("Proměnná prostředí HOME není definovaná"));
+            home_dir.join(DEFAULT_USER_CACHE_DIR)
+        }
+    };
+
+    Some(user_cache_directory.join(PROGRAM_NAME))
+}
+
+/// Vrátí cestu ke složce s pluginy, nalezne ji obdobně jako [`db_path()`]:
+/// - Podle proměnné prostředí EKKLES_PLUGINS_PATH
+/// - Během vývoje jinak žádné pluginy nenačítá
+/// - Složka [`PLUGINS_DIR_NAME`] ve složce pro uživatelská data programu
+fn plugins_path() -> Option<PathBuf> {
+    if let Ok(path) = env::var(PLUGINS_PATH_ENV) {
+        return Some(path.into());
+    };
+
+    if cfg!(debug_assertions) {
+        return None;
+    }
+
+    let user_data_directory = match env::var("XDG_DATA_HOME") {
+        Ok(s) => PathBuf::from(s),
+        Err(_) => {
+            let home_dir =
+                PathBuf::from(env::var("HOME").expect("Proměnná prostředí HOME není definovaná"));
+            home_dir.join(DEFAULT_USER_DATA_DIR)
+        }
+    };
+
+    Some(
+        user_data_directory
+            .join(PROGRAM_NAME)
+            .join(PLUGINS_DIR_NAME),
+    )
+}
+
+/// Vrátí složky, ve kterých se hledají slovníky pro kontrolu pravopisu, v tomto pořadí:
+/// - Podle proměnné prostředí EKKLES_DICTIONARIES_PATH
+/// - Složka [`DICTIONARIES_DIR_NAME`] ve složce pro uživatelská data programu (během vývoje se
+///   vynechává, obdobně jako u [`plugins_path()`])
+/// - Systémové slovníky hunspellu [`SYSTEM_DICTIONARIES_PATH`]
+fn dictionaries_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Ok(path) = env::var(DICTIONARIES_PATH_ENV) {
+        paths.push(path.into());
+    }
+
+    if !cfg!(debug_assertions) {
+        let user_data_directory = match env::var("XDG_DATA_HOME") {
+            Ok(s) => PathBuf::from(s),
+            Err(_) => {
+                let home_dir = PathBuf::from(
+                    env::var("HOME").expect("Proměnná prostředí HOME není definovaná"),
+                );
+                home_dir.join(DEFAULT_USER_DATA_DIR)
+            }
+        };
+        paths.push(
+            user_data_directory
+                .join(PROGRAM_NAME)
+                .join(DICTIONARIES_DIR_NAME),
+        );
+    }
+
+    paths.push(PathBuf::from(SYSTEM_DICTIONARIES_PATH));
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn profile_roundtrip() {
+        let settings = Settings {
+            theme: PresentationTheme {
+                text_color: [0xff, 0xee, 0xdd],
+                background_color: [0x10, 0x20, 0x30],
+                background_image: None,
+            },
+            keymap: Keymap {
+                freeze: String::from("z"),
+                normal: String::from("n"),
+                blank: String::from("c"),
+                language: String::from("l"),
+                repeat_part: String::from("o"),
+            },
+            default_translation: Some(String::from("Český studijní překlad")),
+            monitor: MonitorMapping {
+                position: Some((1920.0, 0.0)),
+            },
+            lyrics_sources: vec![String::from("zpevnik.example.cz")],
+            licensing_mode: true,
+            database_path: Some(PathBuf::from("/srv/ekkles/sbor.sqlite3")),
+            pin: Some(String::from("1234")),
+            macros: vec![PresenterMacro {
+                name: String::from("Začátek"),
+                key: String::from("z"),
+                steps: vec![
+                    MacroStep::Blank,
+                    MacroStep::Wait(Duration::from_millis(1500)),
+                    MacroStep::GoTo(1),
+                    MacroStep::Normal,
+                ],
+            }],
+            quick_slides: vec![QuickSlide {
+                name: String::from("Auto"),
+                key: String::from("a"),
+                content: String::from("Majitel vozidla SPZ 1A2 3456,\ndostavte se prosím k autu"),
+            }],
+            offline_mode: true,
+            verify_saves: true,
+            passage_headings: true,
+            service_end: ServiceEndSettings {
+                warning_minutes: Some(15),
+                action: ServiceEndAction::QuickSlide(String::from("Auto")),
+            },
+            countdown: CountdownSettings {
+                start_time: Some(String::from("09:30")),
+                background_color: Some([0x20, 0x40, 0x60]),
+            },
+            ..Default::default()
+        };
+
+        let serialized = toml::to_string_pretty(&settings).unwrap();
+        let deserialized: Settings = toml::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized, settings);
+    }
+
+    #[test]
+    fn partial_profile_uses_defaults() {
+        let deserialized: Settings = toml::from_str("default_translation = \"ČEP\"").unwrap();
+
+        assert_eq!(deserialized.keymap, Keymap::default());
+        assert_eq!(deserialized.default_translation, Some(String::from("ČEP")));
+    }
+
+    #[test]
+    fn keymap_rejects_duplicate_keys() {
+        assert!(Keymap::default().validate().is_ok());
+
+        let keymap = Keymap {
+            normal: String::from("f"),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            keymap.validate().unwrap_err().to_string(),
+            "Klávesa \"f\" pro normál je již použita pro zmrazení"
+        );
+    }
+
+    #[test]
+    fn approved_lyrics_sources() {
+        let settings = Settings {
+            lyrics_sources: vec![String::from("Zpevnik.cz"), String::from(" ")],
+            ..Default::default()
+        };
+
+        assert!(settings.is_approved_lyrics_source("zpevnik.cz"));
+        assert!(settings.is_approved_lyrics_source("www.zpevnik.cz"));
+        assert!(!settings.is_approved_lyrics_source("falesnyzpevnik.cz"));
+        assert!(!settings.is_approved_lyrics_source("zpevnik.cz.example.com"));
+        assert!(!Settings::default().is_approved_lyrics_source("zpevnik.cz"));
+    }
+
+    #[test]
+    fn mqtt_broker_address() {
+        let mqtt = |broker: &str| MqttSettings {
+            broker: String::from(broker),
+            ..Default::default()
+        };
+
+        assert_eq!(mqtt(" ").broker_address().unwrap(), None);
+        assert_eq!(
+            mqtt("automatizace.local").broker_address().unwrap(),
+            Some((String::from("automatizace.local"), DEFAULT_MQTT_PORT))
+        );
+        assert_eq!(
+            mqtt("192.168.1.10:1884").broker_address().unwrap(),
+            Some((String::from("192.168.1.10"), 1884))
+        );
+        assert!(mqtt("broker:port").broker_address().is_err());
+        assert!(mqtt(":1883").broker_address().is_err());
+        assert_eq!(mqtt("").topic_prefix(), DEFAULT_MQTT_TOPIC_PREFIX);
+    }
+
+    #[test]
+    fn lighting_node_address() {
+        let lighting = LightingSettings {
+            node: String::from("2.255.255.255"),
+            channel: 600,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            lighting.node_address().unwrap(),
+            Some((String::from("2.255.255.255"), DEFAULT_ARTNET_PORT))
+        );
+        assert_eq!(lighting.channel(), DMX_CHANNELS);
+        assert_eq!(LightingSettings::default().node_address().unwrap(), None);
+        assert_eq!(LightingSettings::default().channel(), 1);
+    }
+
+    #[test]
+    fn verses_per_slide_is_clamped() {
+        let verses_per_slide = |verses_per_slide| {
+            Settings {
+                verses_per_slide,
+                ..Default::default()
+            }
+            .verses_per_slide()
+        };
+
+        assert_eq!(verses_per_slide(None), VERSES_PER_SLIDE);
+        assert_eq!(verses_per_slide(Some(3)), 3);
+        assert_eq!(verses_per_slide(Some(0)), 1);
+        assert_eq!(verses_per_slide(Some(u8::MAX)), MAX_VERSES_PER_SLIDE);
+    }
+
+    #[test]
+    fn verse_cache_size_is_clamped() {
+        let verse_cache_mb = |verse_cache_mb| {
+            Settings {
+                verse_cache_mb,
+                ..Default::default()
+            }
+            .verse_cache_mb()
+        };
+
+        assert_eq!(verse_cache_mb(None), VERSE_CACHE_MB);
+        assert_eq!(verse_cache_mb(Some(0)), 0);
+        assert_eq!(verse_cache_mb(Some(u16::MAX)), MAX_VERSE_CACHE_MB);
+    }
+
+    #[test]
+    fn exported_profile_has_no_database_path() {
+        let settings = Settings {
+            database_path: Some(PathBuf::from("/srv/ekkles/sbor.sqlite3")),
+            licensing_mode: true,
+            pin: Some(String::from("1234")),
+            event_hooks: EventHooks {
+                blanked: Some(String::from("svetla --ztlumit")),
+                ..Default::default()
+            },
+            companion_image_path: Some(PathBuf::from("/tmp/obs/slajd.png")),
+            ..Default::default()
+        };
+        let path = env::temp_dir().join(format!("ekkles-profile-{}.toml", std::process::id()));
+
+        settings.export_profile(&path).unwrap();
+        let imported = Settings::import_profile(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(imported.database_path, None);
+        assert_eq!(imported.pin, None);
+        assert_eq!(imported.event_hooks, EventHooks::default());
+        assert_eq!(imported.companion_image_path, None);
+        assert!(imported.licensing_mode);
+    }
+
+    #[test]
+    fn pin_validation() {
+        assert_eq!(parse_pin("  ").unwrap(), None);
+        assert_eq!(parse_pin(" 1234 ").unwrap(), Some(String::from("1234")));
+        assert!(parse_pin("12a4").is_err());
+        assert!(parse_pin("123").is_err());
+        assert!(parse_pin("123456789").is_err());
+
+        let settings = Settings {
+            pin: Some(String::from("1234")),
+            ..Default::default()
+        };
+        assert!(settings.check_pin("1234"));
+        assert!(!settings.check_pin("4321"));
+        assert!(Settings::default().check_pin(""));
+    }
+
+    #[test]
+    fn macro_parsing() {
+        let parsed: PresenterMacro = " U | Úvod |blank, wait 2,GOTO 1, wait 0.5, normal"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            parsed,
+            PresenterMacro {
+                name: String::from("Úvod"),
+                key: String::from("u"),
+                steps: vec![
+                    MacroStep::Blank,
+                    MacroStep::Wait(Duration::from_secs(2)),
+                    MacroStep::GoTo(1),
+                    MacroStep::Wait(Duration::from_millis(500)),
+                    MacroStep::Normal,
+                ],
+            }
+        );
+        assert_eq!(
+            parsed.to_string(),
+            "u | Úvod | blank, wait 2, goto 1, wait 0.5, normal"
+        );
+        assert_eq!(
+            parsed.to_string().parse::<PresenterMacro>().unwrap(),
+            parsed
+        );
+
+        assert!("u | Úvod".parse::<PresenterMacro>().is_err());
+        assert!("uv | Úvod | blank".parse::<PresenterMacro>().is_err());
+        assert!("u |  | blank".parse::<PresenterMacro>().is_err());
+        assert!("goto 0".parse::<MacroStep>().is_err());
+        assert!("wait -1".parse::<MacroStep>().is_err());
+        assert!("blank 2".parse::<MacroStep>().is_err());
+        assert!("skok".parse::<MacroStep>().is_err());
+    }
+
+    #[test]
+    fn macros_parsing() {
+        let macros = parse_macros("u | Úvod | blank, goto 1\n\n  \nx | Nouze | blank\n").unwrap();
+        assert_eq!(macros.len(), 2);
+        assert_eq!(macros[1].name, "Nouze");
+
+        assert!(parse_macros("").unwrap().is_empty());
+        assert!(parse_macros("u | Úvod | blank\nu | Jiné | normal").is_err());
+        assert!(parse_macros("u | Úvod | blank\nx | Jiné | skok").is_err());
+    }
+
+    #[test]
+    fn legacy_settings_become_default_profile() {
+        let profiles = Profiles::parse("default_translation = \"ČEP\"").unwrap();
+
+        assert_eq!(profiles.active, DEFAULT_PROFILE_NAME);
+        assert_eq!(profiles.names(), vec![String::from(DEFAULT_PROFILE_NAME)]);
+        assert_eq!(
+            profiles.active_settings().default_translation,
+            Some(String::from("ČEP"))
+        );
+    }
+
+    #[test]
+    fn profiles_roundtrip_and_unknown_active() {
+        let mut profiles = Profiles::default();
+        profiles
+            .create(
+                " Sbor B ",
+                Settings {
+                    licensing_mode: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let serialized = toml::to_string_pretty(&profiles).unwrap();
+        assert_eq!(Profiles::parse(&serialized).unwrap(), profiles);
+
+        let mut unknown_active = profiles.clone();
+        unknown_active.active = String::from("Smazaný");
+        let serialized = toml::to_string_pretty(&unknown_active).unwrap();
+        assert_eq!(Profiles::parse(&serialized).unwrap().active, "Sbor B");
+    }
+
+    #[test]
+    fn create_activate_and_delete_profiles() {
+        let mut profiles = Profiles::default();
+        let current = Settings {
+            default_translation: Some(String::from("ČEP")),
+            ..Default::default()
+        };
+
+        assert!(profiles.create("  ", Settings::default()).is_err());
+        profiles.create("Sbor B", Settings::default()).unwrap();
+        assert!(profiles.create("Sbor B", Settings::default()).is_err());
+        assert!(profiles.activate("Neexistující", current.clone()).is_err());
+        assert_eq!(profiles.active, DEFAULT_PROFILE_NAME);
+
+        let activated = profiles.activate("Sbor B", current.clone()).unwrap();
+        assert_eq!(activated, Settings::default());
+        assert_eq!(profiles.active, "Sbor B");
+        // Nastavení původně aktivního profilu se při přepnutí uložilo
+        assert_eq!(profiles.profiles[DEFAULT_PROFILE_NAME], current);
+
+        assert!(profiles.delete("Sbor B").is_err());
+        profiles.delete(DEFAULT_PROFILE_NAME).unwrap();
+        assert_eq!(profiles.names(), vec![String::from("Sbor B")]);
+    }
+
+    #[test]
+    fn recent_files_are_deduplicated_and_limited() {
+        let mut recent = RecentFiles::default();
+        for i in 0..MAX_RECENT_FILES + 2 {
+            recent.add_database(Path::new(&format!("/sbor/{i}.sqlite3")));
+        }
+        recent.add_database(Path::new("/sbor/5.sqlite3"));
+
+        assert_eq!(recent.databases.len(), MAX_RECENT_FILES);
+        assert_eq!(recent.databases[0], PathBuf::from("/sbor/5.sqlite3"));
+        assert_eq!(recent.databases[1], PathBuf::from("/sbor/9.sqlite3"));
+        assert_eq!(
+            recent
+                .databases
+                .iter()
+                .filter(|path| path.as_path() == Path::new("/sbor/5.sqlite3"))
+                .count(),
+            1
+        );
+        // Nejstarší databáze vypadly
+        assert!(!recent.databases.contains(&PathBuf::from("/sbor/0.sqlite3")));
+
+        // Neexistující složky se nenabízejí
+        recent.add_import_dir(&env::temp_dir());
+        recent.add_import_dir(Path::new("/neexistujici/slozka"));
+        assert_eq!(recent.last_import_dir(), Some(env::temp_dir()));
+
+        let mut profiles = Profiles::default();
+        profiles.recent = recent;
+        let serialized = toml::to_string_pretty(&profiles).unwrap();
+        assert_eq!(Profiles::parse(&serialized).unwrap(), profiles);
+    }
+}
@@ -0,0 +1,460 @@
+//! Diagnostický režim, zapíná se přepínačem `--diagnostics` při spuštění (viz
+//! [`crate::config::diagnostics_flag`]). Zaznamenává každý SQL dotaz i s dobou jeho trvání
+//! a záznamy zobrazuje na obrazovce diagnostiky (přístupné z nastavení), aby šlo dohledat,
+//! proč je např. načítání playlistu na pomalém počítači pomalé.
+//!
+//! Dotazy loguje sqlx (cíl [`SQLX_QUERY_TARGET`], na úrovni `debug`, pomalé na `warn`),
+//! záznamy zachytává [`DiagnosticsLogger`], ostatní logy předává beze změny
+//! `pretty_env_logger`u. Bez diagnostického režimu se používá přímo `pretty_env_logger`.
+//!
+//! Obrazovka diagnostiky zobrazuje i statistiky cache veršů Bible
+//! ([`ekkles_data::bible::verse_cache`]), ty jsou dostupné vždy.
+//!
+//! I bez diagnostického režimu se zachytávají události, tedy úseky práce s databází
+//! ([`ekkles_data::span`]) a všechna varování a chyby. Po nepovedené bohoslužbě je tak
+//! lze zkopírovat do schránky jako diagnostický balíček spolu s verzí schématu a počty
+//! dat v databázi, viz [`diagnostic_bundle()`].
+
+use std::{
+    collections::VecDeque,
+    env,
+    sync::{Mutex, PoisonError},
+};
+
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveTime};
+use ekkles_data::{
+    bible::verse_cache::{self, VerseCacheStats},
+    database,
+    span::SPAN_TARGET,
+};
+use iced::{
+    Alignment, Element, Length, Task,
+    widget::{button, checkbox, column, container, row, scrollable, text},
+};
+use log::{Level, LevelFilter, Log, Metadata, Record, debug};
+use sqlx::SqlitePool;
+
+use crate::{Ekkles, Screen, settings::SettingsEditor};
+
+/// Cíl (target) logů, pod kterým sqlx loguje provedené dotazy
+const SQLX_QUERY_TARGET: &str = "sqlx::query";
+/// Proměnná prostředí s filtry logů, stejná jako u `pretty_env_logger::init()`
+const LOG_FILTERS_ENV: &str = "RUST_LOG";
+/// Kolik posledních dotazů se uchovává, starší se zahazují
+const MAX_RECORDS: usize = 2000;
+/// Kolik posledních událostí se uchovává, starší se zahazují
+const MAX_EVENTS: usize = 1000;
+
+/// Zaznamenané dotazy, nejstarší první
+static QUERY_LOG: Mutex<VecDeque<QueryRecord>> = Mutex::new(VecDeque::new());
+/// Zaznamenané události (naformátované řádky logu), nejstarší první
+static EVENT_LOG: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Jeden zaznamenaný SQL dotaz
+#[derive(Debug, Clone)]
+pub struct QueryRecord {
+    /// Kdy byl dotaz dokončen
+    pub time: NaiveTime,
+    /// Doba trvání dotazu v sekundách, pokud ji šlo ze zprávy sqlx vyčíst
+    pub elapsed_secs: Option<f64>,
+    /// Zpráva od sqlx (shrnutí, text dotazu, počty řádků, doba trvání)
+    pub message: String,
+}
+
+/// Logger zachytávající události a v diagnostickém režimu i dotazy od sqlx, všechny
+/// záznamy předává vnitřnímu loggeru (ten je filtruje podle `RUST_LOG`)
+struct DiagnosticsLogger {
+    inner: Box<dyn Log>,
+    /// Zda se zachytávají dotazy od sqlx (diagnostický režim)
+    capture_queries: bool,
+}
+
+fn is_query(metadata: &Metadata) -> bool {
+    metadata.target().starts_with(SQLX_QUERY_TARGET)
+}
+
+/// Zda je záznam událostí, tedy úsek práce s databází, varování nebo chyba
+fn is_event(metadata: &Metadata) -> bool {
+    metadata.target() == SPAN_TARGET || metadata.level() <= Level::Warn
+}
+
+impl DiagnosticsLogger {
+    fn captures_query(&self, metadata: &Metadata) -> bool {
+        self.capture_queries && is_query(metadata)
+    }
+}
+
+impl Log for DiagnosticsLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.captures_query(metadata) || is_event(metadata) || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if is_event(record.metadata()) {
+            let mut event_log = EVENT_LOG.lock().unwrap_or_else(PoisonError::into_inner);
+            if event_log.len() >= MAX_EVENTS {
+                event_log.pop_front();
+            }
+            event_log.push_back(format!(
+                "{} {:<5} {}: {}",
+                Local::now().time().format("%H:%M:%S%.3f"),
+                record.level(),
+                record.target(),
+                record.args()
+            ));
+        }
+
+        if self.captures_query(record.metadata()) {
+            let message = record.args().to_string();
+            let mut query_log = QUERY_LOG.lock().unwrap_or_else(PoisonError::into_inner);
+            if query_log.len() >= MAX_RECORDS {
+                query_log.pop_front();
+            }
+            query_log.push_back(QueryRecord {
+                time: Local::now().time(),
+                elapsed_secs: elapsed_secs(&message),
+                message,
+            });
+        }
+
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Inicializuje logger, na výstup logy vypisuje `pretty_env_logger` podle filtrů
+/// v `RUST_LOG`. Události zachytává vždy, v diagnostickém režimu (`diagnostics`) navíc
+/// zachytává dotazy od sqlx, obojí bez ohledu na filtry v `RUST_LOG`. Pokud už je nějaký
+/// logger nastaven, vrací Error.
+pub fn init_logger(diagnostics: bool) -> Result<()> {
+    let mut builder = pretty_env_logger::formatted_builder();
+    if let Ok(filters) = env::var(LOG_FILTERS_ENV) {
+        builder.parse_filters(&filters);
+    }
+    let inner = builder.build();
+    // Dotazy od sqlx i úseky práce s databází se logují na úrovni `debug`, musí projít
+    // globálním filtrem
+    let max_level = inner.filter().max(LevelFilter::Debug);
+
+    log::set_boxed_logger(Box::new(DiagnosticsLogger {
+        inner: Box::new(inner),
+        capture_queries: diagnostics,
+    }))
+    .context("Nelze inicializovat logger")?;
+    log::set_max_level(max_level);
+
+    Ok(())
+}
+
+/// Vrátí kopii zaznamenaných dotazů, nejstarší první
+pub fn query_records() -> Vec<QueryRecord> {
+    QUERY_LOG
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// Zahodí všechny zaznamenané dotazy
+pub fn clear_query_records() {
+    QUERY_LOG
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+/// Vrátí kopii zaznamenaných událostí, nejstarší první
+pub fn event_records() -> Vec<String> {
+    EVENT_LOG
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// Sestaví diagnostický balíček k nahlášení chyby: verzi Ekklesu, verzi schématu a počty
+/// dat v databázi `db` (viz [`database::overview()`]), zaznamenané události a pokud nějaké
+/// jsou, i zaznamenané SQL dotazy. Pokud nelze přečíst přehled databáze, vrací Error.
+pub async fn diagnostic_bundle(db: SqlitePool) -> Result<String> {
+    let overview = database::overview(&db).await?;
+    let version =
+        |version: Option<i64>| version.map_or(String::from("žádná"), |v| v.to_string());
+
+    let mut bundle = format!(
+        "Ekkles {}, diagnostický balíček z {}\n\
+        Verze schématu databáze: {} (nejnovější známá: {})\n\
+        Písní: {}, playlistů: {}, překladů Bible: {}, médií: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        Local::now().format("%d.%m.%Y %H:%M:%S"),
+        version(overview.schema_version),
+        version(overview.latest_schema_version),
+        overview.songs,
+        overview.playlists,
+        overview.translations,
+        overview.media
+    );
+
+    bundle.push_str("\nUdálosti (práce s databází, varování a chyby):\n");
+    for event in event_records() {
+        bundle.push_str(&event);
+        bundle.push('\n');
+    }
+
+    let queries = query_records();
+    if !queries.is_empty() {
+        bundle.push_str("\nSQL dotazy:\n");
+        for record in queries {
+            bundle.push_str(&format!(
+                "{} {}\n",
+                record.time.format("%H:%M:%S%.3f"),
+                record.message
+            ));
+        }
+    }
+
+    Ok(bundle)
+}
+
+/// Vyčte dobu trvání dotazu ze zprávy sqlx (pole `elapsed_secs=<sekundy>`)
+fn elapsed_secs(message: &str) -> Option<f64> {
+    let (_, rest) = message.split_once("elapsed_secs=")?;
+    let number: String = rest
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '-'))
+        .collect();
+
+    number.parse().ok()
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Znovu načte zaznamenané dotazy
+    Refresh,
+    Clear,
+    SortBySlowestToggled(bool),
+    /// Zkopíruje diagnostický balíček do schránky, viz [`diagnostic_bundle()`]
+    CopyBundle,
+    BundleCopied,
+    BundleFailed(String),
+    ReturnToSettings,
+}
+
+impl From<Message> for crate::Message {
+    fn from(value: Message) -> Self {
+        crate::Message::Diagnostics(value)
+    }
+}
+
+#[derive(Debug)]
+pub struct DiagnosticsPanel {
+    /// Zda je zapnutý diagnostický režim, jinak se dotazy nezaznamenávají
+    enabled: bool,
+    records: Vec<QueryRecord>,
+    /// Řadit dotazy od nejpomalejšího místo chronologicky
+    sort_by_slowest: bool,
+    verse_cache: VerseCacheStats,
+    /// Výsledek posledního kopírování diagnostického balíčku
+    bundle_msg: String,
+}
+
+impl DiagnosticsPanel {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            records: query_records(),
+            sort_by_slowest: false,
+            verse_cache: verse_cache::stats(),
+            bundle_msg: String::new(),
+        }
+    }
+
+    /// Celková doba trvání zaznamenaných dotazů v sekundách
+    fn total_elapsed_secs(&self) -> f64 {
+        self.records
+            .iter()
+            .filter_map(|record| record.elapsed_secs)
+            .sum()
+    }
+
+    fn view_verse_cache(&self) -> Element<Message> {
+        let stats = &self.verse_cache;
+        let mb = |bytes: usize| bytes as f64 / (1024.0 * 1024.0);
+
+        column![
+            text("Cache veršů Bible"),
+            text(format!(
+                "Obsazeno {:.1} z {:.0} MB, pasáží: {}, různých veršů: {}",
+                mb(stats.bytes),
+                mb(stats.capacity),
+                stats.passages,
+                stats.verses
+            )),
+            text(format!(
+                "Nalezeno v cache: {}, načteno z databáze: {}, zahozeno pasáží: {}",
+                stats.hits, stats.misses, stats.evictions
+            )),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let content: Element<Message> = if !self.enabled {
+            text("Diagnostický režim není zapnutý, spusťte Ekkles s přepínačem --diagnostics")
+                .into()
+        } else {
+            let mut records: Vec<&QueryRecord> = self.records.iter().collect();
+            if self.sort_by_slowest {
+                records.sort_by(|a, b| {
+                    b.elapsed_secs
+                        .unwrap_or_default()
+                        .total_cmp(&a.elapsed_secs.unwrap_or_default())
+                });
+            } else {
+                records.reverse();
+            }
+
+            let records = records.into_iter().map(|record| {
+                let elapsed = record
+                    .elapsed_secs
+                    .map(|secs| format!("{:.1} ms", secs * 1000.0))
+                    .unwrap_or_else(|| String::from("?"));
+                row![
+                    text(record.time.format("%H:%M:%S%.3f").to_string()),
+                    text(elapsed).width(80),
+                    text(&record.message).width(Length::Fill),
+                ]
+                .spacing(10)
+                .into()
+            });
+
+            column![
+                text(format!(
+                    "Zaznamenaných dotazů: {}, celkem {:.1} ms",
+                    self.records.len(),
+                    self.total_elapsed_secs() * 1000.0
+                )),
+                row![
+                    button("Obnovit").on_press(Message::Refresh),
+                    button("Vymazat").on_press(Message::Clear),
+                    checkbox("Od nejpomalejšího", self.sort_by_slowest)
+                        .on_toggle(Message::SortBySlowestToggled),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+                scrollable(column(records).spacing(5)).height(Length::Fill),
+            ]
+            .spacing(10)
+            .into()
+        };
+
+        container(
+            column![
+                self.view_verse_cache(),
+                row![
+                    button("Kopírovat diagnostický balíček").on_press(Message::CopyBundle),
+                    text(&self.bundle_msg),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+                text("Diagnostika: SQL dotazy"),
+                content,
+                button("Zpět").on_press(Message::ReturnToSettings),
+            ]
+            .spacing(20)
+            .align_x(Alignment::Center),
+        )
+        .padding(30)
+        .center_x(Length::Fill)
+        .into()
+    }
+
+    /// Update funkce pro obrazovku diagnostiky. Pokud je tato funkce zavolána nad jinou
+    /// obrazovkou než [`Screen::Diagnostics`], zpanikaří.
+    pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
+        let panel = match &mut state.screen {
+            Screen::Diagnostics(panel) => panel,
+            screen => panic!(
+                "Update pro diagnostiku zavolán nad jinou obrazovkou {:?}",
+                screen
+            ),
+        };
+
+        match msg {
+            Message::Refresh => {
+                panel.records = query_records();
+                panel.verse_cache = verse_cache::stats();
+                Task::none()
+            }
+            Message::Clear => {
+                debug!("Mažu zaznamenané SQL dotazy");
+                clear_query_records();
+                panel.records.clear();
+                Task::none()
+            }
+            Message::SortBySlowestToggled(sort_by_slowest) => {
+                panel.sort_by_slowest = sort_by_slowest;
+                Task::none()
+            }
+            Message::CopyBundle => {
+                debug!("Kopíruji diagnostický balíček do schránky");
+                panel.bundle_msg.clear();
+                Task::perform(diagnostic_bundle(state.db.clone()), |res| res).then(
+                    |res| match res {
+                        Ok(bundle) => iced::clipboard::write(bundle)
+                            .chain(Task::done(Message::BundleCopied.into())),
+                        Err(e) => Task::done(Message::BundleFailed(format!("{:#}", e)).into()),
+                    },
+                )
+            }
+            Message::BundleCopied => {
+                panel.bundle_msg = String::from("Diagnostický balíček je ve schránce");
+                Task::none()
+            }
+            Message::BundleFailed(err) => {
+                debug!("Sestavení diagnostického balíčku selhalo: {err}");
+                panel.bundle_msg = format!("Diagnostický balíček nelze sestavit: {err}");
+                Task::none()
+            }
+            Message::ReturnToSettings => {
+                debug!("Vracím se do nastavení");
+                state.screen = Screen::Settings(SettingsEditor::new(&state.config.settings));
+                Task::done(crate::settings::Message::LoadTranslations.into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn elapsed_is_read_from_sqlx_message() {
+        let message = r#"SELECT * FROM playlists … db.statement="SELECT * FROM playlists" rows_affected=0 rows_returned=12 elapsed=1.52ms elapsed_secs=0.00152"#;
+
+        assert_eq!(elapsed_secs(message), Some(0.00152));
+        assert_eq!(elapsed_secs("SELECT 1"), None);
+    }
+
+    #[test]
+    fn spans_and_warnings_are_events() {
+        let metadata = |target, level| Metadata::builder().target(target).level(level).build();
+
+        assert!(is_event(&metadata(SPAN_TARGET, Level::Debug)));
+        assert!(is_event(&metadata("ekkles::presenter", Level::Warn)));
+        assert!(is_event(&metadata("ekkles::presenter", Level::Error)));
+        assert!(!is_event(&metadata("ekkles::presenter", Level::Debug)));
+        assert!(!is_event(&metadata(SQLX_QUERY_TARGET, Level::Debug)));
+    }
+}
@@ -0,0 +1,164 @@
+//! Spouštění příkazů při událostech prezentace (viz [`crate::config::EventHooks`]),
+//! umožňuje jednoduché integrace bez pluginu, např. přepnutí světel na pódiu skriptem.
+//!
+//! Příkaz se spustí v shellu (`sh -c`, na Windows `cmd /C`) s proměnnými prostředí:
+//! - `EKKLES_EVENT`: název události ([`PresentationEvent::name()`])
+//! - `EKKLES_SLIDE_INDEX`, `EKKLES_ITEM_INDEX`, `EKKLES_MODE`, `EKKLES_SLIDE_TEXT`,
+//!   `EKKLES_SLIDE_FOOTER`: promítaný slajd (viz [`PresentedSlide`]), u konce
+//!   prezentace chybí
+//! - `EKKLES_EVENT_JSON`: celá událost jako JSON `{"event": ..., "slide": ...}`
+//!
+//! Příkazy zadává správce ručně do souboru s nastavením, spouštějí se proto i v offline
+//! režimu a v režimu hosta. Na dokončení příkazu se nečeká ve vlákně GUI, jeho chyba
+//! (nebo nenulový návratový kód) se pouze zaloguje.
+
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+
+use crate::{config::EventHooks, plugins::PresentedSlide};
+
+/// Událost prezentace, při které lze spustit příkaz
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresentationEvent {
+    Started,
+    SlideChanged,
+    Blanked,
+    ModeChanged,
+    Ended,
+}
+
+impl PresentationEvent {
+    /// Název události předávaný příkazu
+    pub fn name(self) -> &'static str {
+        match self {
+            PresentationEvent::Started => "started",
+            PresentationEvent::SlideChanged => "slide_changed",
+            PresentationEvent::Blanked => "blanked",
+            PresentationEvent::ModeChanged => "mode_changed",
+            PresentationEvent::Ended => "ended",
+        }
+    }
+
+    /// Vrátí příkaz nastavený pro tuto událost v `hooks`, prázdný příkaz se nespouští
+    pub fn command(self, hooks: &EventHooks) -> Option<&str> {
+        let command = match self {
+            PresentationEvent::Started => &hooks.presentation_started,
+            PresentationEvent::SlideChanged => &hooks.slide_changed,
+            PresentationEvent::Blanked => &hooks.blanked,
+            PresentationEvent::ModeChanged => &hooks.mode_changed,
+            PresentationEvent::Ended => &hooks.presentation_ended,
+        };
+
+        command
+            .as_deref()
+            .map(str::trim)
+            .filter(|command| !command.is_empty())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct EventPayload<'a> {
+    event: PresentationEvent,
+    slide: Option<&'a PresentedSlide>,
+}
+
+/// Vrátí událost `event` se slajdem `slide` jako JSON `{"event": ..., "slide": ...}`,
+/// stejně se předává i do MQTT, viz [`crate::network::mqtt`]
+pub fn event_json(event: PresentationEvent, slide: Option<&PresentedSlide>) -> Result<String> {
+    serde_json::to_string(&EventPayload { event, slide })
+        .context("Nelze serializovat událost prezentace")
+}
+
+/// Sestaví příkaz `command` pro událost `event` se slajdem `slide` (viz dokumentace modulu)
+fn build_command(
+    command: &str,
+    event: PresentationEvent,
+    slide: Option<&PresentedSlide>,
+) -> Result<Command> {
+    let mut process = if cfg!(windows) {
+        let mut process = Command::new("cmd");
+        process.arg("/C").arg(command);
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.arg("-c").arg(command);
+        process
+    };
+
+    let payload = event_json(event, slide)?;
+    process
+        .env("EKKLES_EVENT", event.name())
+        .env("EKKLES_EVENT_JSON", payload);
+
+    if let Some(slide) = slide {
+        process
+            .env("EKKLES_SLIDE_INDEX", slide.slide_index.to_string())
+            .env("EKKLES_ITEM_INDEX", slide.item_index.to_string())
+            .env("EKKLES_MODE", slide.mode)
+            .env("EKKLES_SLIDE_TEXT", &slide.text)
+            .env("EKKLES_SLIDE_FOOTER", &slide.footer);
+    }
+
+    Ok(process)
+}
+
+/// Spustí příkaz `command` pro událost `event` se slajdem `slide` a počká na jeho
+/// dokončení. Pokud příkaz nelze spustit nebo skončí s chybou, vrací Error. Blokuje,
+/// proto by se měl volat mimo vlákno GUI.
+pub fn run(command: &str, event: PresentationEvent, slide: Option<&PresentedSlide>) -> Result<()> {
+    let status = build_command(command, event, slide)?
+        .status()
+        .with_context(|| format!("Nelze spustit příkaz \"{command}\""))?;
+
+    if !status.success() {
+        bail!("Příkaz \"{command}\" skončil s chybou ({status})");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn empty_command_is_not_run() {
+        let hooks = EventHooks {
+            blanked: Some(String::from("  ")),
+            slide_changed: Some(String::from(" svetla --zapnout ")),
+            ..EventHooks::default()
+        };
+
+        assert_eq!(PresentationEvent::Blanked.command(&hooks), None);
+        assert_eq!(PresentationEvent::Started.command(&hooks), None);
+        assert_eq!(
+            PresentationEvent::SlideChanged.command(&hooks),
+            Some("svetla --zapnout")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_gets_slide_metadata() {
+        let slide = PresentedSlide {
+            slide_index: 3,
+            item_index: 1,
+            mode: "normal",
+            text: String::from("Haleluja"),
+            footer: String::from("Píseň"),
+        };
+
+        run(
+            r#"test "$EKKLES_EVENT" = slide_changed && test "$EKKLES_SLIDE_INDEX" = 3 && test "$EKKLES_SLIDE_TEXT" = Haleluja"#,
+            PresentationEvent::SlideChanged,
+            Some(&slide),
+        )
+        .unwrap();
+        assert!(run("exit 1", PresentationEvent::Ended, None).is_err());
+    }
+}
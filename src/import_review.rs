@@ -0,0 +1,250 @@
+//! Obrazovka pro rozhodnutí konfliktů hromadného importu písní. Konflikt je importovaná
+//! píseň se stejným názvem jako píseň v databázi, ale s jinými slovy (viz
+//! [`ekkles_data::song_diff`]). U každého konfliktu se zobrazí rozdíl slov obou verzí
+//! vedle sebe a uživatel rozhodne, kterou ponechat. Rozhodnutí se provedou najednou,
+//! poté se obrazovka vrátí na výběr písně.
+
+use ekkles_data::{
+    playlist::PlaylistMetadata,
+    song_diff::{ConflictResolution, DiffLine, ImportConflict},
+};
+use iced::{
+    Alignment, Element, Length, Task,
+    widget::{button, column, container, row, scrollable, text},
+};
+use log::debug;
+
+use crate::{Ekkles, Screen, components::lyrics_diff::lyrics_diff, song_picker::SongPicker};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Rozhodnutí o konfliktu na daném indexu
+    ResolutionPicked(usize, ConflictResolution),
+    /// Stejné rozhodnutí pro všechny konflikty
+    ResolveAll(ConflictResolution),
+    /// Provede všechna rozhodnutí
+    Apply,
+    /// Rozhodnutí byla provedena (počet přepsaných písní, počet písní uložených vedle původních)
+    Applied(usize, usize),
+    ApplyFailed(String),
+    /// Vrátí se na výběr písně bez provedení rozhodnutí (ponechají se písně v databázi)
+    Cancel,
+}
+
+impl From<Message> for crate::Message {
+    fn from(value: Message) -> Self {
+        crate::Message::ImportReview(value)
+    }
+}
+
+/// Konflikt s předpočítaným rozdílem slov a rozhodnutím uživatele
+#[derive(Debug)]
+struct ReviewedConflict {
+    conflict: ImportConflict,
+    diff: Vec<DiffLine>,
+    resolution: ConflictResolution,
+}
+
+#[derive(Debug)]
+pub struct ImportReview {
+    /// Výběr písně, na který se po rozhodnutí vrátí
+    picker: SongPicker,
+    conflicts: Vec<ReviewedConflict>,
+    /// Shrnutí importu písní bez konfliktu
+    import_summary: String,
+    /// Rozhodnutí se právě provádějí
+    applying: bool,
+    err_msg: String,
+}
+
+impl ImportReview {
+    pub fn new(picker: SongPicker, conflicts: Vec<ImportConflict>, import_summary: String) -> Self {
+        Self {
+            picker,
+            conflicts: conflicts
+                .into_iter()
+                .map(|conflict| ReviewedConflict {
+                    diff: conflict.diff(),
+                    conflict,
+                    resolution: ConflictResolution::default(),
+                })
+                .collect(),
+            import_summary,
+            applying: false,
+            err_msg: String::new(),
+        }
+    }
+
+    /// Vrátí playlist, do kterého se vybírá
+    pub fn get_playlist(&self) -> &PlaylistMetadata {
+        self.picker.get_playlist()
+    }
+
+    /// Tlačítka rozhodnutí, zvolené (pokud nějaké) je zvýrazněné
+    fn view_resolutions(
+        current: Option<ConflictResolution>,
+        on_press: impl Fn(ConflictResolution) -> Message,
+    ) -> Element<'static, Message> {
+        let choices = [
+            (ConflictResolution::KeepMine, "Ponechat moji"),
+            (ConflictResolution::TakeTheirs, "Vzít importovanou"),
+            (ConflictResolution::KeepBoth, "Ponechat obě"),
+        ];
+
+        row(choices.into_iter().map(|(resolution, label)| {
+            button(label)
+                .style(if Some(resolution) == current {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+                .on_press(on_press(resolution))
+                .into()
+        }))
+        .spacing(10)
+        .into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let conflicts = self.conflicts.iter().enumerate().map(|(index, reviewed)| {
+            column![
+                text(&reviewed.conflict.incoming.title).size(20),
+                lyrics_diff(&reviewed.diff, "V databázi", "Importovaná"),
+                Self::view_resolutions(Some(reviewed.resolution), move |resolution| {
+                    Message::ResolutionPicked(index, resolution)
+                }),
+            ]
+            .spacing(10)
+            .into()
+        });
+
+        // Zvýrazní společné rozhodnutí, pokud jsou všechna stejná
+        let common = self
+            .conflicts
+            .first()
+            .map(|first| first.resolution)
+            .filter(|first| {
+                self.conflicts
+                    .iter()
+                    .all(|reviewed| reviewed.resolution == *first)
+            });
+
+        column![
+            text(format!(
+                "{}. Písně se stejným názvem, ale jinými slovy ({}):",
+                self.import_summary,
+                self.conflicts.len()
+            )),
+            row![
+                text("Všechny:"),
+                Self::view_resolutions(common, Message::ResolveAll),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+            scrollable(container(column(conflicts).spacing(30)).padding(10)).height(Length::Fill),
+            text(&self.err_msg).style(text::danger),
+            row![
+                button("Zpět (ponechat písně v databázi)").on_press(Message::Cancel),
+                button("Provést rozhodnutí")
+                    .style(button::success)
+                    .on_press_maybe((!self.applying).then_some(Message::Apply)),
+            ]
+            .spacing(20),
+        ]
+        .spacing(20)
+        .padding(20)
+        .into()
+    }
+
+    /// Vrátí se na výběr písně, který po zobrazení zprávy `msg` znovu načte písně
+    fn return_to_picker(state: &mut Ekkles, msg: String) -> Task<crate::Message> {
+        let screen = std::mem::replace(&mut state.screen, Screen::ErrorOccurred(String::new()));
+        state.screen = match screen {
+            Screen::ReviewImport(review) => Screen::PickSong(review.picker),
+            screen => screen,
+        };
+        Task::done(crate::song_picker::Message::ConflictsResolved(msg).into())
+    }
+
+    /// Update funkce pro rozhodnutí konfliktů importu. Pokud je tato funkce zavolána nad
+    /// jinou obrazovkou než [`Screen::ReviewImport`], zpanikaří.
+    pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
+        let review = match &mut state.screen {
+            Screen::ReviewImport(review) => review,
+            screen => panic!(
+                "Update pro rozhodnutí konfliktů importu zavolán nad jinou obrazovkou {:?}",
+                screen
+            ),
+        };
+
+        match msg {
+            Message::ResolutionPicked(index, resolution) => {
+                review.conflicts[index].resolution = resolution;
+                Task::none()
+            }
+            Message::ResolveAll(resolution) => {
+                for reviewed in &mut review.conflicts {
+                    reviewed.resolution = resolution;
+                }
+                Task::none()
+            }
+            Message::Apply => {
+                debug!(
+                    "Provádím rozhodnutí {} konfliktů importu",
+                    review.conflicts.len()
+                );
+                review.applying = true;
+                review.err_msg.clear();
+                let decisions: Vec<_> = review
+                    .conflicts
+                    .iter()
+                    .map(|reviewed| (reviewed.conflict.clone(), reviewed.resolution))
+                    .collect();
+                let db = state.db.clone();
+                Task::perform(
+                    async move {
+                        let (mut replaced, mut duplicated) = (0, 0);
+                        for (conflict, resolution) in decisions {
+                            conflict.resolve(resolution, &db).await?;
+                            match resolution {
+                                ConflictResolution::KeepMine => {}
+                                ConflictResolution::TakeTheirs => replaced += 1,
+                                ConflictResolution::KeepBoth => duplicated += 1,
+                            }
+                        }
+                        Ok::<_, anyhow::Error>((replaced, duplicated))
+                    },
+                    |res| match res {
+                        Ok((replaced, duplicated)) => Message::Applied(replaced, duplicated).into(),
+                        Err(e) => Message::ApplyFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::Applied(replaced, duplicated) => {
+                debug!("Přepsáno {replaced} písní, uloženo vedle původních {duplicated}");
+                let msg = format!(
+                    "{}, přepsáno: {replaced}, uloženo vedle původních: {duplicated}",
+                    review.import_summary
+                );
+                Self::return_to_picker(state, msg)
+            }
+            Message::ApplyFailed(err) => {
+                debug!("Rozhodnutí konfliktů importu selhalo: {err}");
+                // Část rozhodnutí mohla být provedena, jejich opakování nevadí (přepsání
+                // je idempotentní), pouze "ponechat obě" by vytvořilo další kopii
+                review.applying = false;
+                review.err_msg = format!("Rozhodnutí nelze provést: {err}");
+                Task::none()
+            }
+            Message::Cancel => {
+                debug!("Ponechávám písně v databázi, vracím se na výběr písně");
+                let msg = format!(
+                    "{}, ponecháno v databázi: {}",
+                    review.import_summary,
+                    review.conflicts.len()
+                );
+                Self::return_to_picker(state, msg)
+            }
+        }
+    }
+}
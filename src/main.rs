@@ -6,13 +6,33 @@ use log::info;
 use sqlx::SqlitePool;
 
 mod bible_picker;
+mod companion_image;
 mod components;
 mod config;
+mod diagnostics;
 mod error_screen;
+mod event_hooks;
+mod import_review;
+mod network;
+mod passage_comparer;
 mod pick_playlist;
+mod pin_prompt;
 mod playlist_editor;
+mod plugins;
 mod presenter;
+mod profile_picker;
+mod reading_plan_picker;
+mod reports;
+mod service_summary;
+mod settings;
+mod shutdown;
+mod sleep_inhibitor;
+mod slide_break_editor;
+mod slide_export;
+mod slide_render;
+mod song_from_text;
 mod song_picker;
+mod thumbnail_cache;
 mod update;
 
 const PROGRAM_NAME: &str = "Ekkles";
@@ -22,22 +42,53 @@ const PROGRAM_NAME: &str = "Ekkles";
 enum Screen {
     /// Vybírání playlistu k editaci
     PickPlaylist(pick_playlist::PlaylistPicker),
+    /// Výběr a správa pojmenovaných profilů
+    PickProfile(profile_picker::ProfilePicker),
     /// Nastala nezotavitelná chyba
     ErrorOccurred(String),
     /// Editování playlistu
     EditPlaylist(playlist_editor::PlaylistEditor),
     /// Vybírání písně k zařazení do playlistu
     PickSong(song_picker::SongPicker),
+    /// Rozhodnutí konfliktů hromadného importu písní
+    ReviewImport(import_review::ImportReview),
+    /// Vytvoření nové písně z vloženého textu
+    SongFromText(song_from_text::SongFromText),
     /// Vybírání biblické pasáže k zařazení do playlistu
     PickBible(bible_picker::BiblePicker),
+    /// Porovnání vybrané pasáže ve všech překladech
+    ComparePassages(passage_comparer::PassageComparer),
+    /// Úprava zlomů slajdů pasáže v playlistu
+    EditSlideBreaks(slide_break_editor::SlideBreakEditor),
+    /// Vkládání čtení z plánu čtení Bible do playlistu
+    PickReadingPlan(reading_plan_picker::ReadingPlanPicker),
     /// Prezentování playlistu
     Presenter(presenter::Presenter),
+    /// Úprava uživatelského nastavení
+    Settings(settings::SettingsEditor),
+    /// Export přehledů o používání písní do CSV
+    Reports(reports::ReportExporter),
+    /// Zaznamenané SQL dotazy v diagnostickém režimu
+    Diagnostics(diagnostics::DiagnosticsPanel),
+    /// Dotaz na uložení neuloženého playlistu před ukončením programu
+    ConfirmQuit(shutdown::QuitPrompt),
+    /// Zadání PINu před chráněnou operací
+    ConfirmPin(pin_prompt::PinPrompt),
+    /// Shrnutí po skončení prezentace
+    ServiceSummary(service_summary::ServiceSummary),
 }
 
 struct Ekkles {
     main_window_id: Id,
     db: SqlitePool,
+    config: Config,
     screen: Screen,
+    /// Správný PIN byl zadán, následující chráněná zpráva projde bez dotazu (jednorázové)
+    pin_unlocked: bool,
+    /// Načtené pluginy, viz [`plugins`]
+    plugins: plugins::Plugins,
+    /// Publikování událostí prezentace do MQTT, `None` dokud není navázáno spojení
+    mqtt: Option<network::mqtt::MqttPublisher>,
 }
 
 #[derive(Debug, Clone)]
@@ -46,18 +97,46 @@ enum Message {
     ShouldQuit,
     /// Bylo otevřeno hlavní okno, spouští se na začátku
     WindowOpened(Id),
-    /// Bylo zavřeno hlavní okno, měli bychom ukončit prezentování
+    /// Uživatel chce zavřít hlavní okno, spustíme řízené ukončení
+    WindowCloseRequested(Id),
+    /// Bylo zavřeno okno
     WindowClosed(Id),
     /// Message z obrazovky "PlaylistPicker"
     PlaylistPicker(pick_playlist::Message),
+    /// Message z obrazovky "ProfilePicker"
+    ProfilePicker(profile_picker::Message),
     /// Message z obrazovky "PlaylistEditor"
     PlaylistEditor(playlist_editor::Message),
     /// Message z obrazovky "SongPicker"
     SongPicker(song_picker::Message),
+    /// Message z obrazovky "ImportReview"
+    ImportReview(import_review::Message),
+    /// Message z obrazovky "SongFromText"
+    SongFromText(song_from_text::Message),
     /// Message z obrazovky "BiblePicker"
     BiblePicker(bible_picker::Message),
+    /// Message z obrazovky "PassageComparer"
+    PassageComparer(passage_comparer::Message),
+    /// Message z obrazovky "SlideBreakEditor"
+    SlideBreakEditor(slide_break_editor::Message),
+    /// Message z obrazovky "ReadingPlanPicker"
+    ReadingPlanPicker(reading_plan_picker::Message),
     /// Message z obrazovky "Presenter"
     Presenter(presenter::Message),
+    /// Message z obrazovky "Settings"
+    Settings(settings::Message),
+    /// Message z obrazovky "ReportExporter"
+    ReportExporter(reports::Message),
+    /// Message z obrazovky "Diagnostics"
+    Diagnostics(diagnostics::Message),
+    /// Message řízeného ukončení programu
+    Shutdown(shutdown::Message),
+    /// Message z obrazovky "PinPrompt"
+    PinPrompt(pin_prompt::Message),
+    /// Message z obrazovky "ServiceSummary"
+    ServiceSummary(service_summary::Message),
+    /// Událost spojení s MQTT brokerem, viz [`network::mqtt`]
+    Mqtt(network::mqtt::MqttEvent),
     /// Nastala nezotavitelná chyba, měli bychom ukončit program. (ukládat pouhou String
     /// reprezentaci je ošklivé, ale [`anyhow::Error`] neimplementuje [`Clone`]
     /// a [`Message`] musí být `Clone`)
@@ -68,8 +147,13 @@ impl Ekkles {
     fn boot() -> (Self, Task<Message>) {
         let config = Config::new();
         info!("Bootuji ekkles s následující konfigurací: {:#?}", config);
+        config.settings.apply_data_settings();
 
-        let (id, open_window_task) = window::open(Settings::default());
+        // Zavření hlavního okna obsloužíme sami, viz [`shutdown`]
+        let (id, open_window_task) = window::open(Settings {
+            exit_on_close_request: false,
+            ..Settings::default()
+        });
 
         let async_rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -77,15 +161,32 @@ impl Ekkles {
             .expect("Nelze sestrojit async runtime");
         let db = async_rt
             .block_on(ekkles_data::database::open_or_create_database(
-                config.db_path,
+                config.db_path.clone(),
             ))
             .expect("Nelze se připojit k databázi");
 
+        // Pluginy mohou přistupovat k síti, v offline režimu se proto nenačítají
+        let plugins = match &config.plugins_path {
+            Some(path) if !config.is_offline() => plugins::Plugins::load_dir(path),
+            _ => plugins::Plugins::default(),
+        };
+
+        // Při více profilech se nejdříve vybere profil (otevřená je databáze naposledy aktivního)
+        let screen = if config.profiles.profiles.len() > 1 {
+            Screen::PickProfile(profile_picker::ProfilePicker::new(&config))
+        } else {
+            Screen::PickPlaylist(pick_playlist::PlaylistPicker::new())
+        };
+
         (
             Self {
                 main_window_id: id,
                 db,
-                screen: Screen::PickPlaylist(pick_playlist::PlaylistPicker::new()),
+                config,
+                screen,
+                pin_unlocked: false,
+                plugins,
+                mqtt: None,
             },
             open_window_task.map(|id| Message::WindowOpened(id)),
         )
@@ -93,28 +194,76 @@ impl Ekkles {
 
     fn subscription(&self) -> Subscription<Message> {
         let window_closed_events = iced::window::close_events().map(|id| Message::WindowClosed(id));
+        let close_requests = iced::window::close_requests().map(Message::WindowCloseRequested);
 
         let screen_specific_events = match &self.screen {
             Screen::PickPlaylist(_) => Subscription::none(),
+            Screen::PickProfile(_) => Subscription::none(),
             Screen::ErrorOccurred(_) => Subscription::none(),
             Screen::EditPlaylist(_) => Subscription::none(),
             Screen::PickSong(_) => Subscription::none(),
+            Screen::ReviewImport(_) => Subscription::none(),
+            Screen::SongFromText(_) => Subscription::none(),
             Screen::PickBible(_) => Subscription::none(),
+            Screen::ComparePassages(_) => Subscription::none(),
+            Screen::EditSlideBreaks(_) => Subscription::none(),
+            Screen::PickReadingPlan(_) => Subscription::none(),
             Screen::Presenter(presenter) => presenter.subscription(),
+            Screen::Settings(_) => Subscription::none(),
+            Screen::Reports(_) => Subscription::none(),
+            Screen::Diagnostics(_) => Subscription::none(),
+            Screen::ConfirmQuit(_) => Subscription::none(),
+            Screen::ConfirmPin(_) => Subscription::none(),
+            Screen::ServiceSummary(_) => Subscription::none(),
         };
 
-        Subscription::batch([window_closed_events, screen_specific_events])
+        let mqtt_events =
+            network::mqtt::subscription(&self.config.settings.mqtt, self.config.is_offline())
+                .map(Message::Mqtt);
+
+        Subscription::batch([
+            window_closed_events,
+            close_requests,
+            screen_specific_events,
+            mqtt_events,
+        ])
     }
 
     fn view(&self, window_id: Id) -> Element<Message> {
         if window_id == self.main_window_id {
             match &self.screen {
-                Screen::PickPlaylist(picker) => picker.view().map(|msg| msg.into()),
+                Screen::PickPlaylist(picker) => {
+                    picker.view(self.config.guest_mode).map(|msg| msg.into())
+                }
+                Screen::PickProfile(picker) => {
+                    picker.view(self.config.guest_mode).map(|msg| msg.into())
+                }
                 Screen::ErrorOccurred(err) => error_screen::view(err),
-                Screen::EditPlaylist(editor) => editor.view().map(|msg| msg.into()),
-                Screen::PickSong(song_picker) => song_picker.view().map(|msg| msg.into()),
+                Screen::EditPlaylist(editor) => editor
+                    .view(self.config.guest_mode, &self.plugins)
+                    .map(|msg| msg.into()),
+                Screen::PickSong(song_picker) => {
+                    song_picker.view(&self.plugins).map(|msg| msg.into())
+                }
+                Screen::ReviewImport(review) => review.view().map(|msg| msg.into()),
+                Screen::SongFromText(form) => {
+                    form.view(self.config.is_offline()).map(|msg| msg.into())
+                }
                 Screen::PickBible(bible_picker) => bible_picker.view().map(|msg| msg.into()),
-                Screen::Presenter(presenter) => presenter.view_control().map(|msg| msg.into()),
+                Screen::ComparePassages(comparer) => comparer.view().map(|msg| msg.into()),
+                Screen::EditSlideBreaks(editor) => editor.view().map(|msg| msg.into()),
+                Screen::PickReadingPlan(picker) => picker.view().map(|msg| msg.into()),
+                Screen::Presenter(presenter) => presenter
+                    .view_control(self.config.guest_mode)
+                    .map(|msg| msg.into()),
+                Screen::Settings(settings) => settings.view().map(|msg| msg.into()),
+                Screen::Reports(exporter) => exporter.view().map(|msg| msg.into()),
+                Screen::Diagnostics(panel) => panel.view().map(|msg| msg.into()),
+                Screen::ConfirmQuit(prompt) => prompt.view().map(|msg| msg.into()),
+                Screen::ConfirmPin(prompt) => prompt.view().map(|msg| msg.into()),
+                Screen::ServiceSummary(summary) => {
+                    summary.view(self.config.guest_mode).map(|msg| msg.into())
+                }
             }
         } else if let Screen::Presenter(presenter) = &self.screen
             && presenter
@@ -132,8 +281,8 @@ impl Ekkles {
 }
 
 fn main() -> iced::Result {
-    // Inicializace loggeru
-    pretty_env_logger::init();
+    // Inicializace loggeru, v diagnostickém režimu zachytává i SQL dotazy
+    diagnostics::init_logger(config::diagnostics_flag()).expect("Nelze inicializovat logger");
 
     // Hlavní event-loop
     iced::daemon(Ekkles::boot, Ekkles::update, Ekkles::view)
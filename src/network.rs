@@ -0,0 +1,154 @@
+//! Jediné místo, odkud Ekkles přistupuje k síti (stahování slov písní ze schválených
+//! webů, viz [`crate::config::Settings::lyrics_sources`], MQTT, viz [`mqtt`], a světelná
+//! cue přes Art-Net, viz [`lighting`]).
+//!
+//! Některé sbory mají na promítacím počítači striktní zákaz síťového provozu, proto
+//! lze síť vypnout dvěma způsoby:
+//! - Za běhu offline režimem (viz [`crate::config::Config::is_offline()`]), každá funkce
+//!   tohoto modulu jej zkontroluje dřív, než se pokusí cokoliv otevřít.
+//! - Při překladu bez feature `network` (`cargo build --no-default-features`), pak
+//!   v programu není HTTP klient vůbec přeložen a Ekkles je offline vždy.
+//!
+//! Veškerý síťový kód proto musí vést přes tento modul, jinde se síť nepoužívá.
+
+use anyhow::{Result, bail};
+
+use crate::config::Settings;
+
+pub mod lighting;
+pub mod mqtt;
+
+/// Zda byl Ekkles přeložen s podporou sítě (feature `network`)
+pub const NETWORK_SUPPORTED: bool = cfg!(feature = "network");
+
+/// Vrací Error, pokud je přístup k síti zakázán, ať už offline režimem (`offline`),
+/// nebo překladem bez podpory sítě.
+fn ensure_online(offline: bool) -> Result<()> {
+    if !NETWORK_SUPPORTED {
+        bail!("Ekkles byl přeložen bez podpory sítě");
+    }
+    if offline {
+        bail!("Je zapnutý offline režim, přístup k síti je zakázán");
+    }
+
+    Ok(())
+}
+
+/// Zkontroluje, že `input` je adresa (http/https) stránky na některém ze schválených
+/// webů z nastavení `settings`, a vrátí ji v normalizované podobě. V offline režimu
+/// (`offline`) nebo pokud adresa není validní či schválená, vrací Error.
+pub fn lyrics_url(input: &str, settings: &Settings, offline: bool) -> Result<String> {
+    ensure_online(offline)?;
+    http::approved_url(input, settings)
+}
+
+/// Stáhne HTML stránku na adrese `url`. V offline režimu (`offline`) vrací Error dřív,
+/// než se otevře jakékoliv spojení. Pokud stránku nelze stáhnout nebo server odpoví
+/// chybou, vrací Error.
+pub async fn fetch_page(url: String, offline: bool) -> Result<String> {
+    ensure_online(offline)?;
+    http::get(&url).await
+}
+
+#[cfg(feature = "network")]
+mod http {
+    use anyhow::{Context, Result, bail};
+    use reqwest::Url;
+
+    use crate::config::Settings;
+
+    pub fn approved_url(input: &str, settings: &Settings) -> Result<String> {
+        let url = Url::parse(input.trim()).context("Neplatná adresa stránky")?;
+
+        let approved = matches!(url.scheme(), "http" | "https")
+            && url
+                .host_str()
+                .is_some_and(|host| settings.is_approved_lyrics_source(host));
+        if !approved {
+            bail!(
+                "Web {} není mezi schválenými zdroji slov, slova vlož ručně",
+                url.host_str().unwrap_or(url.as_str())
+            );
+        }
+
+        Ok(url.to_string())
+    }
+
+    pub async fn get(url: &str) -> Result<String> {
+        let response = reqwest::get(url)
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Nelze stáhnout stránku {url}"))?;
+
+        response
+            .text()
+            .await
+            .with_context(|| format!("Nelze přečíst obsah stránky {url}"))
+    }
+}
+
+/// Náhrada za [`http`] při překladu bez podpory sítě, [`ensure_online()`] sem nikdy
+/// nepustí, ale pro jistotu i tady vrací Error.
+#[cfg(not(feature = "network"))]
+mod http {
+    use anyhow::{Result, bail};
+
+    use crate::config::Settings;
+
+    pub fn approved_url(_input: &str, _settings: &Settings) -> Result<String> {
+        bail!("Ekkles byl přeložen bez podpory sítě")
+    }
+
+    pub async fn get(_url: &str) -> Result<String> {
+        bail!("Ekkles byl přeložen bez podpory sítě")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn offline_mode_refuses_before_connecting() {
+        // Adresa z dokumentačního rozsahu (RFC 5737), pokus o spojení by visel až do timeoutu
+        let res = tokio::time::timeout(
+            Duration::from_secs(1),
+            fetch_page(String::from("http://192.0.2.1/"), true),
+        )
+        .await
+        .expect("Offline režim se nesmí pokusit o spojení");
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn offline_mode_refuses_lyrics_url() {
+        let settings = Settings {
+            lyrics_sources: vec![String::from("zpevnik.cz")],
+            ..Settings::default()
+        };
+
+        assert!(lyrics_url("https://zpevnik.cz/pisen", &settings, true).is_err());
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn only_approved_lyrics_urls() {
+        use pretty_assertions::assert_eq;
+
+        let settings = Settings {
+            lyrics_sources: vec![String::from("zpevnik.cz")],
+            ..Settings::default()
+        };
+
+        assert_eq!(
+            lyrics_url(" https://www.zpevnik.cz/pisen ", &settings, false).unwrap(),
+            "https://www.zpevnik.cz/pisen"
+        );
+        assert!(lyrics_url("https://example.com/pisen", &settings, false).is_err());
+        assert!(lyrics_url("ftp://zpevnik.cz/pisen", &settings, false).is_err());
+        assert!(lyrics_url("zpevnik.cz/pisen", &settings, false).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+//! Odesílání světelných cue osvětlovacímu pultu přes Art-Net (viz
+//! [`crate::config::LightingSettings`]), aby se např. světla v lodi ztlumila samy, když
+//! se začne promítat text kázání.
+//!
+//! Cue se posílá jako jeden paket ArtDmx (UDP), ve kterém má nastavený kanál hodnotu
+//! čísla cue a ostatní kanály nulu, universe by proto měl být pro Ekkles vyhrazený.
+//! Pult si hodnotu kanálu přeloží na spuštění cue (většina pultů to umí jako "DMX
+//! remote"/"DMX trigger").
+
+use anyhow::{Result, bail};
+
+use crate::config::{DMX_CHANNELS, LightingSettings, MAX_ARTNET_UNIVERSE};
+
+/// Hlavička každého Art-Net paketu
+const ARTNET_ID: &[u8; 8] = b"Art-Net\0";
+/// OpCode paketu ArtDmx (v paketu little-endian)
+const OP_DMX: u16 = 0x5000;
+/// Verze protokolu Art-Net (v paketu big-endian)
+const PROTOCOL_VERSION: u16 = 14;
+
+/// Sestaví paket ArtDmx pro universe `universe`, ve kterém má kanál `channel` (od 1)
+/// hodnotu `value` a všechny předchozí kanály nulu. Pokud je universe nebo kanál mimo
+/// rozsah, vrací Error.
+pub fn art_dmx_packet(universe: u16, channel: u16, value: u8) -> Result<Vec<u8>> {
+    if universe > MAX_ARTNET_UNIVERSE {
+        bail!("DMX universe {universe} nelze adresovat přes Art-Net");
+    }
+    if !(1..=DMX_CHANNELS).contains(&channel) {
+        bail!("DMX kanál {channel} je mimo rozsah 1 až {DMX_CHANNELS}");
+    }
+
+    // Délka dat musí být sudá
+    let length = channel + channel % 2;
+    let mut data = vec![0; length as usize];
+    data[channel as usize - 1] = value;
+
+    let mut packet = Vec::with_capacity(18 + data.len());
+    packet.extend_from_slice(ARTNET_ID);
+    packet.extend_from_slice(&OP_DMX.to_le_bytes());
+    packet.extend_from_slice(&PROTOCOL_VERSION.to_be_bytes());
+    // Sekvence (0 = nepoužívá se) a fyzický port
+    packet.extend_from_slice(&[0, 0]);
+    // Port-address: SubUni (nižší byte) a Net (vyšší 7 bitů)
+    packet.extend_from_slice(&universe.to_le_bytes());
+    packet.extend_from_slice(&length.to_be_bytes());
+    packet.extend_from_slice(&data);
+
+    Ok(packet)
+}
+
+/// Odešle cue `cue` uzlu z `settings`. V offline režimu (`offline`), bez podpory sítě
+/// nebo pokud adresa uzlu není nastavená či validní, vrací Error dřív, než se cokoliv odešle.
+pub async fn send_cue(settings: LightingSettings, cue: u8, offline: bool) -> Result<()> {
+    super::ensure_online(offline)?;
+
+    let Some((host, port)) = settings.node_address()? else {
+        bail!("Není nastavená adresa Art-Net uzlu");
+    };
+    let packet = art_dmx_packet(settings.universe, settings.channel(), cue)?;
+
+    udp::send(&host, port, &packet).await
+}
+
+#[cfg(feature = "network")]
+mod udp {
+    use anyhow::{Context, Result};
+    use tokio::net::UdpSocket;
+
+    pub async fn send(host: &str, port: u16, packet: &[u8]) -> Result<()> {
+        let socket = UdpSocket::bind(("0.0.0.0", 0))
+            .await
+            .context("Nelze otevřít UDP socket")?;
+        // Art-Net se často posílá na broadcast adresu sítě
+        socket
+            .set_broadcast(true)
+            .context("Nelze povolit broadcast na UDP socketu")?;
+        socket
+            .send_to(packet, (host, port))
+            .await
+            .with_context(|| format!("Nelze odeslat cue na {host}:{port}"))?;
+
+        Ok(())
+    }
+}
+
+/// Náhrada za [`udp`] při překladu bez podpory sítě, [`super::ensure_online()`] sem nikdy
+/// nepustí, ale pro jistotu i tady vrací Error.
+#[cfg(not(feature = "network"))]
+mod udp {
+    use anyhow::{Result, bail};
+
+    pub async fn send(_host: &str, _port: u16, _packet: &[u8]) -> Result<()> {
+        bail!("Ekkles byl přeložen bez podpory sítě")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn art_dmx_packet_layout() {
+        let packet = art_dmx_packet(0x0123, 3, 42).unwrap();
+
+        assert_eq!(&packet[..8], b"Art-Net\0");
+        assert_eq!(&packet[8..18], &[0x00, 0x50, 0, 14, 0, 0, 0x23, 0x01, 0, 4]);
+        assert_eq!(&packet[18..], &[0, 0, 42, 0]);
+
+        assert!(art_dmx_packet(0, 0, 1).is_err());
+        assert!(art_dmx_packet(0, DMX_CHANNELS + 1, 1).is_err());
+        assert!(art_dmx_packet(MAX_ARTNET_UNIVERSE + 1, 1, 1).is_err());
+    }
+}
@@ -0,0 +1,182 @@
+//! Publikování stavu prezentace do MQTT brokeru (viz [`crate::config::MqttSettings`])
+//! pro chrámy s domácí automatizací, volitelně i ovládání prezentace z brokeru.
+//!
+//! ### Témata
+//! - `prefix/<událost>`: každá událost prezentace (viz [`PresentationEvent::name()`]),
+//!   obsah je JSON stejný jako u příkazů událostí, viz [`crate::event_hooks::event_json()`]
+//! - `prefix/control`: ovládání prezentace (pokud je zapnuté), obsah je jeden krok
+//!   ve stejném formátu jako u maker, viz [`MacroStep`] (např. `next` nebo `goto 3`)
+//!
+//! Stejně jako ostatní síťové funkce je MQTT v offline režimu i při překladu bez feature
+//! `network` vypnuté (bez ní v programu MQTT klient vůbec není).
+
+// Bez podpory sítě se spojení nikdy nenaváže, takže se události nikdy nevytvoří
+#![cfg_attr(not(feature = "network"), allow(dead_code))]
+
+use std::fmt;
+
+use anyhow::Result;
+use iced::Subscription;
+
+use crate::{
+    config::{MacroStep, MqttSettings},
+    event_hooks::PresentationEvent,
+};
+
+/// Téma (za prefixem), ze kterého se přijímá ovládání prezentace
+const CONTROL_TOPIC: &str = "control";
+
+/// Událost spojení s brokerem, předává se do [`crate::Message::Mqtt`]
+#[derive(Debug, Clone)]
+pub enum MqttEvent {
+    /// Spojení bylo navázáno, přes daný publisher lze publikovat události
+    Connected(MqttPublisher),
+    /// Z brokeru přišel příkaz pro ovládání prezentace
+    Control(MacroStep),
+}
+
+/// Publikuje události prezentace do brokeru, do kterého je připojena [`subscription()`]
+#[derive(Clone)]
+pub struct MqttPublisher {
+    client: imp::Client,
+    prefix: String,
+}
+
+impl fmt::Debug for MqttPublisher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MqttPublisher")
+            .field("prefix", &self.prefix)
+            .finish()
+    }
+}
+
+impl MqttPublisher {
+    /// Zařadí událost `event` s obsahem `payload` k publikování (nečeká na odeslání).
+    /// Pokud je fronta k odeslání plná nebo spojení zaniklo, vrací Error.
+    pub fn publish(&self, event: PresentationEvent, payload: String) -> Result<()> {
+        imp::publish(
+            &self.client,
+            format!("{}/{}", self.prefix, event.name()),
+            payload,
+        )
+    }
+}
+
+/// Vrátí subscription, která drží spojení s brokerem z `settings` (po výpadku se
+/// znovu připojí) a předává [`MqttEvent`]. V offline režimu (`offline`), bez podpory
+/// sítě nebo bez nastaveného brokeru nic nedělá.
+pub fn subscription(settings: &MqttSettings, offline: bool) -> Subscription<MqttEvent> {
+    if super::ensure_online(offline).is_err() || settings.broker.trim().is_empty() {
+        return Subscription::none();
+    }
+
+    Subscription::run_with(settings.clone(), imp::connect)
+}
+
+#[cfg(feature = "network")]
+mod imp {
+    use std::time::Duration;
+
+    use anyhow::{Context, Result};
+    use iced::futures::{SinkExt, Stream};
+    use log::{debug, info, warn};
+    use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+
+    use super::{CONTROL_TOPIC, MqttEvent, MqttPublisher};
+    use crate::{PROGRAM_NAME, config::MqttSettings};
+
+    pub type Client = AsyncClient;
+
+    /// Velikost front pro odchozí zprávy a události pro GUI
+    const CHANNEL_CAPACITY: usize = 32;
+    const KEEP_ALIVE: Duration = Duration::from_secs(30);
+    /// Prodleva před opětovným připojením po výpadku spojení
+    const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+    pub fn publish(client: &Client, topic: String, payload: String) -> Result<()> {
+        client
+            .try_publish(topic, QoS::AtMostOnce, false, payload)
+            .context("Nelze publikovat událost do MQTT")
+    }
+
+    pub fn connect(settings: &MqttSettings) -> impl Stream<Item = MqttEvent> + use<> {
+        let settings = settings.clone();
+
+        iced::stream::channel(CHANNEL_CAPACITY, |mut output| async move {
+            let (host, port) = match settings.broker_address() {
+                Ok(Some(address)) => address,
+                Ok(None) => return,
+                Err(e) => {
+                    warn!("Nelze se připojit k MQTT brokeru: {:#}", e);
+                    return;
+                }
+            };
+            let prefix = settings.topic_prefix();
+            let control_topic = format!("{prefix}/{CONTROL_TOPIC}");
+
+            info!("Připojuji se k MQTT brokeru {host}:{port}");
+            let mut options = MqttOptions::new(PROGRAM_NAME, host, port);
+            options.set_keep_alive(KEEP_ALIVE);
+            let (client, mut eventloop) = AsyncClient::new(options, CHANNEL_CAPACITY);
+
+            let publisher = MqttPublisher {
+                client: client.clone(),
+                prefix,
+            };
+            if output.send(MqttEvent::Connected(publisher)).await.is_err() {
+                return;
+            }
+
+            loop {
+                match eventloop.poll().await {
+                    // Broker si odběr po výpadku nepamatuje, přihlašujeme se po každém připojení
+                    Ok(Event::Incoming(Packet::ConnAck(_))) if settings.control => {
+                        debug!("Připojeno k MQTT brokeru, odebírám {control_topic}");
+                        if let Err(e) = client.try_subscribe(&control_topic, QoS::AtMostOnce) {
+                            warn!("Nelze odebírat ovládání z MQTT: {:?}", e);
+                        }
+                    }
+                    Ok(Event::Incoming(Packet::Publish(publish)))
+                        if publish.topic == control_topic =>
+                    {
+                        let command = String::from_utf8_lossy(&publish.payload).parse();
+                        match command {
+                            Ok(command) => {
+                                if output.send(MqttEvent::Control(command)).await.is_err() {
+                                    return;
+                                }
+                            }
+                            Err(e) => warn!("Neplatný příkaz z MQTT: {:#}", e),
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!("Spojení s MQTT brokerem selhalo: {e}");
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Náhrada za [`imp`] při překladu bez podpory sítě, [`subscription()`] se nikdy
+/// nepřipojí, takže publisher neexistuje
+#[cfg(not(feature = "network"))]
+mod imp {
+    use anyhow::{Result, bail};
+    use iced::futures::{Stream, stream};
+
+    use super::MqttEvent;
+    use crate::config::MqttSettings;
+
+    pub type Client = ();
+
+    pub fn publish(_client: &Client, _topic: String, _payload: String) -> Result<()> {
+        bail!("Ekkles byl přeložen bez podpory sítě")
+    }
+
+    pub fn connect(_settings: &MqttSettings) -> impl Stream<Item = MqttEvent> + use<> {
+        stream::empty()
+    }
+}
@@ -0,0 +1,229 @@
+//! Obrazovka pro porovnání jedné pasáže ve všech nainstalovaných překladech vedle sebe.
+//! Pasáž se načítá ve všech překladech souběžně, kterýkoliv z nich lze přidat do playlistu.
+
+use ekkles_data::{
+    bible::{
+        get_available_translations,
+        indexing::{Passage, VerseIndex},
+    },
+    playlist::PlaylistMetadata,
+};
+use iced::{
+    Element, Length, Padding, Task,
+    widget::{self, button, column, container, row, scrollable, text},
+};
+use log::debug;
+
+use crate::{
+    Ekkles, Screen,
+    bible_picker::{BiblePicker, TranslationPickerItem},
+    playlist_editor::PlaylistEditor,
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    LoadTranslations,
+    TranslationsLoaded(Vec<TranslationPickerItem>),
+    /// Pasáž v překladu s daným id byla načtena, případně chyba (např. překlad verš nemá)
+    PassageLoaded(i64, Result<Passage, String>),
+    /// Vloží pasáž v překladu s daným id do playlistu a vrátí se do editoru, viz
+    /// [`BiblePicker::insert_passage()`]
+    AddPassage(i64),
+    ReturnToPicker,
+}
+
+impl From<Message> for crate::Message {
+    fn from(value: Message) -> Self {
+        crate::Message::PassageComparer(value)
+    }
+}
+
+/// Načtení pasáže v jednom překladu
+#[derive(Debug)]
+enum Rendering {
+    Loading,
+    Loaded(Passage),
+    Failed(String),
+}
+
+#[derive(Debug)]
+pub struct PassageComparer {
+    /// Výběr pasáže, do kterého se lze vrátit beze změny
+    picker: BiblePicker,
+    from: VerseIndex,
+    to: VerseIndex,
+    /// Pasáž v jednotlivých překladech, `None` pokud se překlady ještě načítají
+    renderings: Option<Vec<(TranslationPickerItem, Rendering)>>,
+}
+
+impl PassageComparer {
+    pub fn new(picker: BiblePicker, from: VerseIndex, to: VerseIndex) -> Self {
+        Self {
+            picker,
+            from,
+            to,
+            renderings: None,
+        }
+    }
+
+    /// Vrátí playlist, do kterého se vybírá
+    pub fn get_playlist(&self) -> &PlaylistMetadata {
+        self.picker.get_playlist()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let renderings: Element<Message> = match &self.renderings {
+            None => text("Načítám překlady...").into(),
+            Some(renderings) if renderings.is_empty() => text("Žádné překlady").into(),
+            Some(renderings) => row(renderings.iter().map(|(translation, rendering)| {
+                let (content, add_message) = match rendering {
+                    Rendering::Loading => (String::from("Načítám..."), None),
+                    Rendering::Loaded(passage) => (
+                        passage
+                            .get_verses()
+                            .iter()
+                            .map(|(verse_number, text)| format!("{verse_number}: {text}\n"))
+                            .collect(),
+                        Some(Message::AddPassage(translation.id)),
+                    ),
+                    Rendering::Failed(err) => (err.clone(), None),
+                };
+
+                column![
+                    text(&translation.name).size(20),
+                    scrollable(container(text(content)).padding(Padding::ZERO.right(10)))
+                        .height(Length::Fill),
+                    button("Přidat")
+                        .style(widget::button::success)
+                        .on_press_maybe(add_message)
+                        .width(Length::Fill),
+                ]
+                .spacing(10)
+                .width(Length::FillPortion(1))
+                .into()
+            }))
+            .spacing(20)
+            .into(),
+        };
+
+        column![
+            row![
+                button("Zpět").on_press(Message::ReturnToPicker),
+                text(format!("{} - {}", self.from, self.to)).size(24),
+            ]
+            .spacing(20),
+            renderings,
+        ]
+        .spacing(20)
+        .padding(20)
+        .into()
+    }
+
+    /// Update funkce pro porovnání překladů. Pokud je tato funkce zavolána nad jinou
+    /// obrazovkou než [`Screen::ComparePassages`], zpanikaří.
+    pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
+        let comparer = match &mut state.screen {
+            Screen::ComparePassages(comparer) => comparer,
+            screen => panic!(
+                "Update pro porovnání překladů zavolán nad jinou obrazovkou {:?}",
+                screen
+            ),
+        };
+
+        match msg {
+            Message::LoadTranslations => {
+                debug!("Načítám seznam překladů k porovnání");
+                let conn = state.db.acquire();
+                Task::perform(
+                    async {
+                        let mut conn = conn.await?;
+                        get_available_translations(&mut conn).await
+                    },
+                    |res| match res {
+                        Ok(translations) => Message::TranslationsLoaded(
+                            translations
+                                .into_iter()
+                                .map(|(id, name)| TranslationPickerItem { id, name })
+                                .collect(),
+                        )
+                        .into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::TranslationsLoaded(translations) => {
+                debug!(
+                    "Načítám pasáž {} - {} v {} překladech",
+                    comparer.from,
+                    comparer.to,
+                    translations.len()
+                );
+                let (from, to) = (comparer.from, comparer.to);
+
+                // Každý překlad se načítá ve vlastní úloze s vlastním připojením, souběžně
+                let tasks = translations.iter().map(|translation| {
+                    let translation_id = translation.id;
+                    let conn = state.db.acquire();
+                    Task::perform(
+                        async move {
+                            let mut conn = conn.await?;
+                            Passage::load(from, to, translation_id, &mut conn).await
+                        },
+                        move |res| {
+                            Message::PassageLoaded(
+                                translation_id,
+                                res.map_err(|e| format!("{:#}", e)),
+                            )
+                            .into()
+                        },
+                    )
+                });
+                let tasks = Task::batch(tasks.collect::<Vec<_>>());
+
+                comparer.renderings = Some(
+                    translations
+                        .into_iter()
+                        .map(|translation| (translation, Rendering::Loading))
+                        .collect(),
+                );
+                tasks
+            }
+            Message::PassageLoaded(translation_id, res) => {
+                debug!("Načtena pasáž v překladu s id {translation_id}");
+                if let Some((_, rendering)) = comparer
+                    .renderings
+                    .iter_mut()
+                    .flatten()
+                    .find(|(translation, _)| translation.id == translation_id)
+                {
+                    *rendering = match res {
+                        Ok(passage) => Rendering::Loaded(passage),
+                        Err(err) => Rendering::Failed(err),
+                    };
+                }
+                Task::none()
+            }
+            Message::AddPassage(translation_id) => {
+                debug!(
+                    "Vkládám pasáž v překladu s id {translation_id} do playlistu a vracím se do editoru"
+                );
+                comparer
+                    .picker
+                    .insert_passage(translation_id, comparer.from, comparer.to);
+                let playlist = comparer.get_playlist().clone();
+                state.screen = Screen::EditPlaylist(PlaylistEditor::new(playlist));
+                Task::done(crate::playlist_editor::Message::LoadSongNameCache.into())
+            }
+            Message::ReturnToPicker => {
+                debug!("Vracím se na výběr pasáže");
+                let screen =
+                    std::mem::replace(&mut state.screen, Screen::ErrorOccurred(String::new()));
+                state.screen = match screen {
+                    Screen::ComparePassages(comparer) => Screen::PickBible(comparer.picker),
+                    screen => screen,
+                };
+                Task::none()
+            }
+        }
+    }
+}
@@ -1,30 +1,161 @@
-use std::fmt::Display;
+use std::{fmt::Display, path::PathBuf};
 
 use crate::{
     Ekkles, Screen,
     components::{TopButtonsMessage, top_buttons},
     playlist_editor,
+    settings::{self, SettingsEditor},
+};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use ekkles_data::{
+    database,
+    playlist::{
+        self, PlaylistMetadata,
+        reminder::{ICS_EXTENSION, ServiceReminder},
+    },
 };
-use anyhow::Context;
-use ekkles_data::playlist::{self, PlaylistMetadata};
 use iced::{
-    Element, Length, Task,
-    widget::{button, column, combo_box, container, row, text, text::danger, text_input},
+    Alignment, Element, Length, Task,
+    widget::{
+        button, column, combo_box, container, pick_list, row, scrollable, text,
+        text::{danger, success},
+        text_input,
+    },
 };
-use log::{debug, trace};
+use log::{debug, info, trace, warn};
+use sqlx::SqlitePool;
+
+/// Přípona souborů s databází nabízená v dialozích
+const DATABASE_EXTENSION: &str = "sqlite3";
+/// Výchozí název souboru nové databáze
+const NEW_DATABASE_FILE_NAME: &str = "database.sqlite3";
+/// Výška seznamu playlistů
+const PLAYLIST_LIST_HEIGHT: f32 = 250.0;
+/// Formát data vytvoření playlistu v seznamu (a data bohoslužby v pozvánce)
+const DATE_FORMAT: &str = "%d.%m.%Y";
+/// Formát času bohoslužby v pozvánce
+const TIME_FORMAT: &str = "%H:%M";
+/// Výchozí čas začátku bohoslužby v pozvánce
+const DEFAULT_SERVICE_TIME: &str = "09:30";
 
 #[derive(Debug)]
 pub struct PlaylistPicker {
     pub playlists: Option<combo_box::State<PlaylistPickerItem>>,
     pub picked_playlist: Option<PlaylistPickerItem>,
     pub new_playlist_name: String,
+    /// Playlisty v databázi pro seznam s datem vytvoření a tlačítky pro otevření a smazání
+    pub playlist_list: Vec<PlaylistPickerItem>,
+    /// Text, podle kterého se filtruje seznam playlistů (název, lidé v rolích)
+    pub search: String,
+    /// Řazení seznamu playlistů
+    pub sort: PlaylistSort,
+    /// Playlist, u kterého se čeká na potvrzení smazání
+    pub delete_confirmation: Option<PlaylistPickerItem>,
+    /// Rozpracovaná pozvánka na bohoslužbu týmu, viz [`ReminderForm`]
+    pub reminder_form: Option<ReminderForm>,
+    /// Naposledy otevřené databáze (kromě aktuální), lze na ně přepnout bez dialogu,
+    /// viz [`crate::config::RecentFiles`]
+    pub recent_databases: Vec<PathBuf>,
     pub err_msg: Option<String>,
+    pub info_msg: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct PlaylistPickerItem {
     pub id: i64,
+    /// Název playlistu, případně doplněný o lidi v rolích
     pub name: String,
+    pub created: DateTime<Utc>,
+    pub num_items: usize,
+}
+
+/// Formulář pozvánky na bohoslužbu podle playlistu (do kalendáře nebo e-mailu), viz
+/// [`ServiceReminder`]. Datum a čas jsou v textové podobě, validují se až při vytvoření.
+#[derive(Debug, Clone)]
+pub struct ReminderForm {
+    pub item: PlaylistPickerItem,
+    pub date: String,
+    pub time: String,
+    pub err_msg: String,
+    pub info_msg: String,
+}
+
+impl ReminderForm {
+    /// Formulář s nejbližší nedělí (dnešek, pokud je neděle) a [`DEFAULT_SERVICE_TIME`]
+    fn new(item: PlaylistPickerItem) -> Self {
+        let today = Local::now().date_naive();
+        let days_to_sunday = (7 - today.weekday().num_days_from_sunday()) % 7;
+        let sunday = today + Days::new(days_to_sunday.into());
+
+        Self {
+            item,
+            date: sunday.format(DATE_FORMAT).to_string(),
+            time: String::from(DEFAULT_SERVICE_TIME),
+            err_msg: String::new(),
+            info_msg: String::new(),
+        }
+    }
+
+    /// Zvaliduje zadané datum a čas bohoslužby
+    fn start(&self) -> Result<NaiveDateTime> {
+        let date: String = self.date.chars().filter(|c| !c.is_whitespace()).collect();
+        let date = NaiveDate::parse_from_str(&date, DATE_FORMAT)
+            .with_context(|| format!("Datum '{date}' musí být ve tvaru dd.mm.rrrr"))?;
+        let time = NaiveTime::parse_from_str(self.time.trim(), TIME_FORMAT)
+            .with_context(|| format!("Čas '{}' musí být ve tvaru hh:mm", self.time.trim()))?;
+
+        Ok(date.and_time(time))
+    }
+}
+
+/// Načte playlist s id `playlist_id` a sestaví z něj pozvánku na bohoslužbu začínající v `start`
+async fn load_reminder(
+    playlist_id: i64,
+    start: NaiveDateTime,
+    db: SqlitePool,
+) -> Result<ServiceReminder> {
+    let playlist = PlaylistMetadata::load(
+        playlist_id,
+        db.acquire()
+            .await
+            .context("Nelze získat připojení k databázi")?,
+    )
+    .await?;
+    let mut conn = db
+        .acquire()
+        .await
+        .context("Nelze získat připojení k databázi")?;
+
+    ServiceReminder::for_playlist(&playlist, start, &mut conn).await
+}
+
+/// Řazení seznamu playlistů
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlaylistSort {
+    /// Nejnovější playlisty nahoře, staré bohoslužby jsou tak na konci seznamu
+    #[default]
+    Newest,
+    Oldest,
+    Name,
+}
+
+impl PlaylistSort {
+    pub const ALL: [PlaylistSort; 3] = [
+        PlaylistSort::Newest,
+        PlaylistSort::Oldest,
+        PlaylistSort::Name,
+    ];
+}
+
+impl Display for PlaylistSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PlaylistSort::Newest => "Od nejnovějšího",
+            PlaylistSort::Oldest => "Od nejstaršího",
+            PlaylistSort::Name => "Podle názvu",
+        })
+    }
 }
 
 impl Display for PlaylistPickerItem {
@@ -38,6 +169,7 @@ impl From<TopButtonsMessage> for Message {
         match value {
             TopButtonsMessage::Playlists => Message::TopButtonPlaylists,
             TopButtonsMessage::Songs => Message::TopButtonSongs,
+            TopButtonsMessage::Settings => Message::TopButtonSettings,
         }
     }
 }
@@ -52,14 +184,95 @@ impl From<Message> for crate::Message {
 pub enum Message {
     TopButtonSongs,
     TopButtonPlaylists,
+    TopButtonSettings,
     LoadPlaylists,
-    PlaylistsLoaded(Vec<(i64, String)>),
+    PlaylistsLoaded(Vec<PlaylistPickerItem>),
+    SearchChanged(String),
+    SortPicked(PlaylistSort),
     PickedPlaylist(i64),
     NewPlaylistNameChanged(String),
     CreateNewPlaylist,
     ValidateNewPlaylistName,
     NameAlreadyTaken,
     EditPlaylist(PlaylistMetadata),
+    /// Zeptá se na potvrzení smazání playlistu
+    RequestDeletePlaylist(PlaylistPickerItem),
+    CancelDeletePlaylist,
+    /// Smaže playlist s daným ID (po potvrzení)
+    DeletePlaylist(i64),
+    PlaylistDeleted,
+    /// Playlist nelze smazat (např. je vložený jako blok v jiném playlistu)
+    PlaylistDeleteFailed(String),
+    /// Otevře dialog pro výběr existující databáze, na kterou se přepne
+    SwitchDatabase,
+    /// Otevře dialog pro uložení nové (prázdné) databáze, na kterou se přepne
+    CreateDatabase,
+    /// Přepne na naposledy otevřenou databázi (bez dialogu)
+    OpenRecentDatabase(PathBuf),
+    /// Nová databáze je otevřená, nahradí aktuální
+    DatabaseOpened(SqlitePool, PathBuf),
+    DatabaseSwitchFailed(String),
+    DialogCancelled,
+    /// Otevře dialog pro výběr balíčku playlistu (viz [`playlist::bundle`]), který naimportuje
+    ImportPlaylist,
+    ImportFailed(String),
+    /// Zapne režim hosta (do restartu programu), viz [`crate::config::Config::guest_mode`]
+    EnableGuestMode,
+    /// Otevře formulář pozvánky na bohoslužbu podle playlistu, viz [`ReminderForm`]
+    RequestReminder(PlaylistPickerItem),
+    ReminderDateChanged(String),
+    ReminderTimeChanged(String),
+    /// Uloží pozvánku jako událost kalendáře (`.ics`)
+    ExportReminderIcs,
+    /// Zkopíruje text e-mailu s pozvánkou do schránky
+    CopyReminderEmail,
+    /// Pozvánka byla vytvořena, zpráva pro uživatele
+    ReminderDone(String),
+    ReminderFailed(String),
+    CloseReminder,
+}
+
+impl Message {
+    /// Zda je zpráva povolená v režimu hosta, tedy nic nevytváří ani nemění
+    pub fn allowed_in_guest_mode(&self) -> bool {
+        match self {
+            Message::TopButtonPlaylists
+            | Message::LoadPlaylists
+            | Message::PlaylistsLoaded(_)
+            | Message::SearchChanged(_)
+            | Message::SortPicked(_)
+            | Message::PickedPlaylist(_)
+            | Message::EditPlaylist(_)
+            | Message::DialogCancelled
+            | Message::EnableGuestMode => true,
+            Message::TopButtonSongs
+            | Message::TopButtonSettings
+            | Message::NewPlaylistNameChanged(_)
+            | Message::CreateNewPlaylist
+            | Message::ValidateNewPlaylistName
+            | Message::NameAlreadyTaken
+            | Message::SwitchDatabase
+            | Message::CreateDatabase
+            | Message::OpenRecentDatabase(_)
+            | Message::DatabaseOpened(..)
+            | Message::DatabaseSwitchFailed(_)
+            | Message::ImportPlaylist
+            | Message::ImportFailed(_)
+            | Message::RequestDeletePlaylist(_)
+            | Message::CancelDeletePlaylist
+            | Message::DeletePlaylist(_)
+            | Message::PlaylistDeleted
+            | Message::PlaylistDeleteFailed(_)
+            | Message::RequestReminder(_)
+            | Message::ReminderDateChanged(_)
+            | Message::ReminderTimeChanged(_)
+            | Message::ExportReminderIcs
+            | Message::CopyReminderEmail
+            | Message::ReminderDone(_)
+            | Message::ReminderFailed(_)
+            | Message::CloseReminder => false,
+        }
+    }
 }
 
 /// Update funkce pro PickPlaylist. Pokud bude zavolána na jiné obrazovce, zpanikaří.
@@ -78,13 +291,25 @@ pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
             debug!("Jsem v playlistu a klikám, abych se do něj znovu dostal, ignoruju");
             Task::none()
         }
+        Message::TopButtonSettings => {
+            debug!("Přecházím do nastavení");
+            state.screen = Screen::Settings(SettingsEditor::new(&state.config.settings));
+            Task::done(settings::Message::LoadTranslations.into())
+        }
         Message::PlaylistsLoaded(playlists) => {
             debug!("Načetly se playlisty");
-            let options = playlists
-                .into_iter()
-                .map(|(id, name)| PlaylistPickerItem { id, name })
-                .collect();
-            picker.playlists = Some(iced::widget::combo_box::State::new(options));
+            picker.playlist_list = playlists.clone();
+            picker.playlists = Some(iced::widget::combo_box::State::new(playlists));
+            Task::none()
+        }
+        Message::SearchChanged(search) => {
+            trace!("Filtruji playlisty podle \"{search}\"");
+            picker.search = search;
+            Task::none()
+        }
+        Message::SortPicked(sort) => {
+            debug!("Řadím playlisty: {sort}");
+            picker.sort = sort;
             Task::none()
         }
         Message::PickedPlaylist(id) => {
@@ -120,9 +345,16 @@ pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
             Task::done(Message::EditPlaylist(new_playlist).into())
         }
         Message::ValidateNewPlaylistName => {
+            let name = match validate_new_playlist_name(&picker.new_playlist_name) {
+                Ok(name) => name.to_string(),
+                Err(e) => {
+                    picker.err_msg = Some(e.to_string());
+                    return Task::none();
+                }
+            };
+
             debug!("Zjišťuji, jestli se v databázi nachází playlist s daným názvem");
             let conn = state.db.acquire();
-            let name = picker.new_playlist_name.clone();
             Task::perform(
                 async move {
                     let conn = conn.await.context("Nelze získat připojení k databázi")?;
@@ -148,6 +380,45 @@ pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
             ));
             Task::none()
         }
+        Message::RequestDeletePlaylist(item) => {
+            debug!("Žádám o potvrzení smazání playlistu \"{}\"", item.name);
+            picker.delete_confirmation = Some(item);
+            Task::none()
+        }
+        Message::CancelDeletePlaylist => {
+            picker.delete_confirmation = None;
+            Task::none()
+        }
+        Message::DeletePlaylist(id) => {
+            debug!("Mažu playlist s ID {id}");
+            picker.delete_confirmation = None;
+            let conn = state.db.acquire();
+            Task::perform(
+                async move {
+                    let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
+                    playlist::delete_by_id(id, &mut conn).await
+                },
+                |res| match res {
+                    Ok(_) => Message::PlaylistDeleted.into(),
+                    Err(e) if e.is_conflict() => {
+                        Message::PlaylistDeleteFailed(e.to_string()).into()
+                    }
+                    Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                },
+            )
+        }
+        Message::PlaylistDeleted => {
+            picker.err_msg = None;
+            picker.info_msg = Some(String::from("Playlist smazán"));
+            picker.picked_playlist = None;
+            Task::done(Message::LoadPlaylists.into())
+        }
+        Message::PlaylistDeleteFailed(err_msg) => {
+            debug!("Playlist nelze smazat: {err_msg}");
+            picker.info_msg = None;
+            picker.err_msg = Some(err_msg);
+            Task::none()
+        }
         Message::EditPlaylist(playlist) => {
             debug!("Vybrán playlist, přecházím na editaci {:#?}", playlist);
             state.screen = Screen::EditPlaylist(playlist_editor::PlaylistEditor::new(playlist));
@@ -155,12 +426,49 @@ pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
         }
         Message::LoadPlaylists => {
             debug!("Načítám seznam playlistů pro výběr playlistů");
+            picker.recent_databases = state
+                .config
+                .profiles
+                .recent
+                .other_databases(&state.config.db_path);
             // Vyrobíme future, kterou awaitneme v asynchronním bloku v Perform a ta nám vydá connection
             let conn = state.db.acquire();
+            let roles_conn = state.db.acquire();
             Task::perform(
                 async move {
+                    let roles = {
+                        let mut conn = roles_conn
+                            .await
+                            .context("Nelze získat připojení k databázi")?;
+                        playlist::get_all_roles(&mut conn).await?
+                    };
                     let conn = conn.await.context("Nelze získat připojení k databázi")?;
-                    playlist::get_available(conn).await
+                    let playlists = playlist::get_available(conn).await?;
+
+                    // Lidé v rolích se zobrazí u názvu, lze tedy playlist vyhledat i podle nich
+                    Ok::<_, anyhow::Error>(
+                        playlists
+                            .into_iter()
+                            .map(|info| {
+                                let name = match roles.get(&info.id) {
+                                    Some(roles) => {
+                                        let roles: Vec<_> = roles
+                                            .iter()
+                                            .map(|(role, person)| format!("{role}: {person}"))
+                                            .collect();
+                                        format!("{} ({})", info.name, roles.join(", "))
+                                    }
+                                    None => info.name,
+                                };
+                                PlaylistPickerItem {
+                                    id: info.id,
+                                    name,
+                                    created: info.created,
+                                    num_items: info.num_items,
+                                }
+                            })
+                            .collect(),
+                    )
                 },
                 |res| match res {
                     Ok(pls) => Message::PlaylistsLoaded(pls).into(),
@@ -168,6 +476,253 @@ pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
                 },
             )
         }
+        Message::SwitchDatabase => {
+            debug!("Vybírám databázi, na kterou se přepne");
+            Task::perform(
+                async {
+                    let Some(file) = rfd::AsyncFileDialog::new()
+                        .add_filter("Databáze Ekklesu", &[DATABASE_EXTENSION])
+                        .pick_file()
+                        .await
+                    else {
+                        return Ok(None);
+                    };
+
+                    let path = file.path().to_path_buf();
+                    let db = database::open_ekkles_database(&path).await?;
+                    Ok(Some((db, path)))
+                },
+                database_opened,
+            )
+        }
+        Message::CreateDatabase => {
+            debug!("Vybírám umístění nové databáze");
+            Task::perform(
+                async {
+                    let Some(file) = rfd::AsyncFileDialog::new()
+                        .add_filter("Databáze Ekklesu", &[DATABASE_EXTENSION])
+                        .set_file_name(NEW_DATABASE_FILE_NAME)
+                        .save_file()
+                        .await
+                    else {
+                        return Ok(None);
+                    };
+
+                    // Přepsání existujícího souboru potvrdil uživatel v dialogu
+                    let path = file.path().to_path_buf();
+                    let db = database::create_new_database(&path).await?;
+                    Ok(Some((db, path)))
+                },
+                database_opened,
+            )
+        }
+        Message::OpenRecentDatabase(path) => {
+            debug!(
+                "Přepínám na naposledy otevřenou databázi {}",
+                path.display()
+            );
+            Task::perform(
+                async move {
+                    let db = database::open_ekkles_database(&path).await?;
+                    Ok(Some((db, path)))
+                },
+                database_opened,
+            )
+        }
+        Message::DatabaseOpened(db, path) => {
+            info!("Přepínám na databázi {}", path.display());
+            // I opouštěnou databázi si zapamatujeme, aby šlo přepnout zpět
+            let recent = &mut state.config.profiles.recent;
+            recent.add_database(&state.config.db_path);
+            recent.add_database(&path);
+            state.config.db_path = path.clone();
+            state.config.settings.database_path = Some(path.clone());
+            let mut new_picker = PlaylistPicker::new();
+            match state.config.save_settings() {
+                Ok(_) => {
+                    new_picker.info_msg = Some(format!("Otevřena databáze {}", path.display()))
+                }
+                Err(e) => {
+                    new_picker.err_msg = Some(format!(
+                        "Databáze otevřena, ale volbu nelze uložit: {:#}",
+                        e
+                    ))
+                }
+            }
+            state.screen = Screen::PickPlaylist(new_picker);
+
+            replace_database(state, db)
+        }
+        Message::DatabaseSwitchFailed(err_msg) => {
+            debug!("Přepnutí databáze selhalo: {err_msg}");
+            picker.info_msg = None;
+            picker.err_msg = Some(err_msg);
+            Task::none()
+        }
+        Message::DialogCancelled => {
+            debug!("Dialog pro výběr souboru zrušen");
+            Task::none()
+        }
+        Message::ImportPlaylist => {
+            debug!("Vybírám balíček playlistu k importu");
+            let db = state.db.clone();
+            Task::future(async move {
+                let Some(file) = rfd::AsyncFileDialog::new()
+                    .add_filter("Balíček playlistu", &[playlist::bundle::BUNDLE_EXTENSION])
+                    .pick_file()
+                    .await
+                else {
+                    return Ok(None);
+                };
+
+                PlaylistMetadata::import_bundle(file.path(), &db)
+                    .await
+                    .map(Some)
+            })
+            .then(|res| match res {
+                Ok(Some(playlist)) => Task::done(Message::EditPlaylist(playlist).into()).chain(
+                    Task::done(crate::playlist_editor::Message::LoadSongNameCache.into()),
+                ),
+                Ok(None) => Task::done(Message::DialogCancelled.into()),
+                Err(e) => Task::done(Message::ImportFailed(format!("{:#}", e)).into()),
+            })
+        }
+        Message::ImportFailed(err_msg) => {
+            warn!("Import playlistu selhal: {err_msg}");
+            picker.err_msg = Some(format!("Playlist nelze naimportovat: {err_msg}"));
+            Task::none()
+        }
+        Message::EnableGuestMode => {
+            info!("Zapínám režim hosta, úpravy jsou zamčené do restartu programu");
+            state.config.guest_mode = true;
+            Task::none()
+        }
+        Message::RequestReminder(item) => {
+            debug!(
+                "Otevírám pozvánku na bohoslužbu podle playlistu \"{}\"",
+                item.name
+            );
+            picker.reminder_form = Some(ReminderForm::new(item));
+            Task::none()
+        }
+        Message::ReminderDateChanged(date) => {
+            if let Some(form) = &mut picker.reminder_form {
+                form.date = date;
+            }
+            Task::none()
+        }
+        Message::ReminderTimeChanged(time) => {
+            if let Some(form) = &mut picker.reminder_form {
+                form.time = time;
+            }
+            Task::none()
+        }
+        Message::ExportReminderIcs | Message::CopyReminderEmail => {
+            let Some(form) = &mut picker.reminder_form else {
+                return Task::none();
+            };
+            form.err_msg.clear();
+            form.info_msg.clear();
+            let start = match form.start() {
+                Ok(start) => start,
+                Err(e) => {
+                    form.err_msg = format!("{:#}", e);
+                    return Task::none();
+                }
+            };
+            let (playlist_id, db) = (form.item.id, state.db.clone());
+
+            if matches!(msg, Message::CopyReminderEmail) {
+                debug!("Kopíruji text e-mailu s pozvánkou do schránky");
+                return Task::perform(load_reminder(playlist_id, start, db), |res| res).then(
+                    |res| match res {
+                        Ok(reminder) => {
+                            iced::clipboard::write(reminder.email_text()).chain(Task::done(
+                                Message::ReminderDone(String::from(
+                                    "Text e-mailu je ve schránce, stačí jej vložit do e-mailu",
+                                ))
+                                .into(),
+                            ))
+                        }
+                        Err(e) => Task::done(Message::ReminderFailed(format!("{:#}", e)).into()),
+                    },
+                );
+            }
+
+            debug!("Ukládám pozvánku do kalendáře");
+            Task::perform(
+                async move {
+                    let reminder = load_reminder(playlist_id, start, db).await?;
+                    let Some(file) = rfd::AsyncFileDialog::new()
+                        .add_filter("Kalendář", &[ICS_EXTENSION])
+                        .set_file_name(reminder.default_file_name())
+                        .save_file()
+                        .await
+                    else {
+                        return Ok(None);
+                    };
+
+                    let uid = format!(
+                        "ekkles-playlist-{playlist_id}-{}@ekkles",
+                        start.format("%Y%m%dT%H%M")
+                    );
+                    std::fs::write(file.path(), reminder.to_ics(&uid, Utc::now())).with_context(
+                        || format!("Nelze zapsat pozvánku do {}", file.path().display()),
+                    )?;
+
+                    Ok(Some(file.path().display().to_string()))
+                },
+                |res: Result<Option<String>>| match res {
+                    Ok(Some(path)) => {
+                        Message::ReminderDone(format!("Pozvánka uložena do {path}")).into()
+                    }
+                    Ok(None) => Message::DialogCancelled.into(),
+                    Err(e) => Message::ReminderFailed(format!("{:#}", e)).into(),
+                },
+            )
+        }
+        Message::ReminderDone(msg) => {
+            debug!("{msg}");
+            if let Some(form) = &mut picker.reminder_form {
+                form.info_msg = msg;
+            }
+            Task::none()
+        }
+        Message::ReminderFailed(err) => {
+            warn!("Nelze vytvořit pozvánku: {err}");
+            if let Some(form) = &mut picker.reminder_form {
+                form.err_msg = format!("Nelze vytvořit pozvánku: {err}");
+            }
+            Task::none()
+        }
+        Message::CloseReminder => {
+            picker.reminder_form = None;
+            Task::none()
+        }
+    }
+}
+
+/// Nahradí otevřenou databázi za `db`, původní řádně uzavře (checkpoint) a až poté
+/// načte playlisty z nové databáze, volající tedy musí přejít na výběr playlistů.
+pub fn replace_database(state: &mut Ekkles, db: SqlitePool) -> Task<crate::Message> {
+    let old_db = std::mem::replace(&mut state.db, db);
+
+    Task::perform(
+        async move {
+            if let Err(e) = database::checkpoint_and_close(old_db).await {
+                warn!("Nelze řádně uzavřít původní databázi: {:?}", e);
+            }
+        },
+        |_| Message::LoadPlaylists.into(),
+    )
+}
+
+/// Převede výsledek otevření databáze z dialogu na [`Message`]
+fn database_opened(res: anyhow::Result<Option<(SqlitePool, PathBuf)>>) -> crate::Message {
+    match res {
+        Ok(Some((db, path))) => Message::DatabaseOpened(db, path).into(),
+        Ok(None) => Message::DialogCancelled.into(),
+        Err(e) => Message::DatabaseSwitchFailed(format!("{:#}", e)).into(),
     }
 }
 
@@ -177,11 +732,19 @@ impl PlaylistPicker {
             playlists: None,
             picked_playlist: None,
             new_playlist_name: String::from(""),
+            playlist_list: Vec::new(),
+            search: String::new(),
+            sort: PlaylistSort::default(),
+            delete_confirmation: None,
+            reminder_form: None,
+            recent_databases: Vec::new(),
             err_msg: None,
+            info_msg: None,
         }
     }
 
-    pub fn view(&self) -> Element<Message> {
+    /// V režimu hosta (`guest_mode`) se zobrazí pouze výběr playlistu k prezentaci
+    pub fn view(&self, guest_mode: bool) -> Element<Message> {
         let box_with_playlists = if self.playlists.is_some() {
             Into::<Element<Message>>::into(combo_box(
                 self.playlists.as_ref().unwrap(),
@@ -193,12 +756,29 @@ impl PlaylistPicker {
             text("Načítám playlisty z databáze").into()
         };
 
+        if guest_mode {
+            return container(
+                column![
+                    text("Režim hosta: playlisty lze pouze prezentovat, ne upravovat"),
+                    column!["Vyber playlist", box_with_playlists].spacing(10),
+                    self.view_playlist_list(guest_mode),
+                ]
+                .spacing(30)
+                .max_width(1000),
+            )
+            .padding(10)
+            .center_x(Length::FillPortion(1))
+            .center_y(Length::Fill)
+            .into();
+        }
+
         column![
             top_buttons(crate::components::TopButtonsPickedSection::Playlists)
                 .map(|msg| msg.into()),
             container(
                 column![
                     column!["Vyber playlist", box_with_playlists].spacing(10),
+                    self.view_playlist_list(guest_mode),
                     column![
                         "Nebo vytvoř nový",
                         row![
@@ -208,8 +788,26 @@ impl PlaylistPicker {
                             button("Vytvořit!").on_press(Message::ValidateNewPlaylistName),
                         ]
                         .spacing(10),
+                        button("Importovat playlist…").on_press(Message::ImportPlaylist),
                         text(self.err_msg.clone().unwrap_or(String::from(""))).style(danger)
                     ]
+                    .spacing(10),
+                    column![
+                        "Databáze (např. pro jiný sbor s vlastním zpěvníkem)",
+                        row![
+                            button("Přepnout databázi…").on_press(Message::SwitchDatabase),
+                            button("Nová databáze…").on_press(Message::CreateDatabase),
+                        ]
+                        .spacing(10),
+                        self.view_recent_databases(),
+                        text(self.info_msg.clone().unwrap_or_default()).style(success)
+                    ]
+                    .spacing(10),
+                    column![
+                        "Předáváš počítač zástupci? Zamkni úpravy, playlisty půjde jen prezentovat \
+                         (odemkne je až restart programu)",
+                        button("Zamknout úpravy (režim hosta)").on_press(Message::EnableGuestMode),
+                    ]
                     .spacing(10)
                 ]
                 .spacing(30)
@@ -221,6 +819,116 @@ impl PlaylistPicker {
         ]
         .into()
     }
+
+    /// Tlačítka pro přepnutí na naposledy otevřené databáze, pokud nějaké jsou
+    fn view_recent_databases(&self) -> Element<Message> {
+        if self.recent_databases.is_empty() {
+            return column![].into();
+        }
+
+        let databases = self.recent_databases.iter().map(|path| {
+            button(text(path.display().to_string()))
+                .style(button::secondary)
+                .on_press(Message::OpenRecentDatabase(path.clone()))
+                .into()
+        });
+
+        column!["Naposledy otevřené:", column(databases).spacing(5)]
+            .spacing(5)
+            .into()
+    }
+
+    /// Formulář pozvánky na bohoslužbu (datum a čas) s tlačítky pro uložení do kalendáře
+    /// a zkopírování textu e-mailu
+    fn view_reminder_form(form: &ReminderForm) -> Element<Message> {
+        column![
+            text!(
+                "Pozvánka na bohoslužbu podle playlistu \"{}\"",
+                form.item.name
+            ),
+            row![
+                text("Datum"),
+                text_input("dd.mm.rrrr", &form.date).on_input(Message::ReminderDateChanged),
+                text("Čas"),
+                text_input("hh:mm", &form.time).on_input(Message::ReminderTimeChanged),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+            row![
+                button("Uložit do kalendáře (.ics)…").on_press(Message::ExportReminderIcs),
+                button("Zkopírovat text e-mailu").on_press(Message::CopyReminderEmail),
+                button("Zavřít").on_press(Message::CloseReminder),
+            ]
+            .spacing(10),
+            text(&form.err_msg).style(danger),
+            text(&form.info_msg).style(success),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// Seznam playlistů s vyhledáváním a řazením, u každého je datum vytvoření, počet položek
+    /// a tlačítka pro otevření, pozvánku na bohoslužbu a smazání (mazaný playlist je nutné
+    /// potvrdit). V režimu hosta (`guest_mode`) nelze mazat ani vytvářet pozvánky.
+    fn view_playlist_list(&self, guest_mode: bool) -> Element<Message> {
+        if let Some(item) = &self.delete_confirmation {
+            return column![
+                text!(
+                    "Opravdu smazat playlist \"{}\"? Nelze to vrátit.",
+                    item.name
+                ),
+                row![
+                    button("Smazat")
+                        .style(button::danger)
+                        .on_press(Message::DeletePlaylist(item.id)),
+                    button("Zrušit").on_press(Message::CancelDeletePlaylist),
+                ]
+                .spacing(10),
+            ]
+            .spacing(10)
+            .into();
+        }
+
+        if let Some(form) = &self.reminder_form {
+            return Self::view_reminder_form(form);
+        }
+
+        let rows = filter_and_sort(&self.playlist_list, &self.search, self.sort)
+            .into_iter()
+            .map(|item| {
+                let created: DateTime<Local> = DateTime::from(item.created);
+                let mut item_row = row![
+                    text(&item.name).width(Length::Fill),
+                    text(created.format(DATE_FORMAT).to_string()),
+                    text!("Položek: {}", item.num_items),
+                    button("Otevřít").on_press(Message::PickedPlaylist(item.id)),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center);
+                if !guest_mode {
+                    item_row = item_row
+                        .push(button("Pozvánka…").on_press(Message::RequestReminder(item.clone())));
+                    item_row = item_row.push(
+                        button("Smazat")
+                            .style(button::danger)
+                            .on_press(Message::RequestDeletePlaylist(item.clone())),
+                    );
+                }
+                item_row.into()
+            });
+
+        column![
+            row![
+                text_input("Hledat playlist (název, lidé v rolích)", &self.search)
+                    .on_input(Message::SearchChanged),
+                pick_list(PlaylistSort::ALL, Some(self.sort), Message::SortPicked),
+            ]
+            .spacing(10),
+            scrollable(column(rows).spacing(5)).height(Length::Fixed(PLAYLIST_LIST_HEIGHT)),
+        ]
+        .spacing(10)
+        .into()
+    }
 }
 
 impl Default for PlaylistPicker {
@@ -228,3 +936,98 @@ impl Default for PlaylistPicker {
         Self::new()
     }
 }
+
+/// Vrátí playlisty z `playlists`, jejichž název (včetně lidí v rolích) obsahuje `search`
+/// (bez ohledu na velikost písmen), seřazené podle `sort`
+pub fn filter_and_sort<'a>(
+    playlists: &'a [PlaylistPickerItem],
+    search: &str,
+    sort: PlaylistSort,
+) -> Vec<&'a PlaylistPickerItem> {
+    let search = search.trim().to_lowercase();
+    let mut filtered: Vec<&PlaylistPickerItem> = playlists
+        .iter()
+        .filter(|item| item.name.to_lowercase().contains(&search))
+        .collect();
+
+    match sort {
+        PlaylistSort::Newest => filtered.sort_by(|a, b| b.created.cmp(&a.created)),
+        PlaylistSort::Oldest => filtered.sort_by_key(|item| item.created),
+        PlaylistSort::Name => filtered.sort_by_key(|item| item.name.to_lowercase()),
+    }
+
+    filtered
+}
+
+/// Zvaliduje název nového playlistu (při vytvoření i uložení pod novým názvem) a vrátí
+/// jej oříznutý o bílé znaky. Prázdný název není validní. Zda je název volný, se musí
+/// ověřit v databázi, viz [`playlist::is_name_available()`].
+pub fn validate_new_playlist_name(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("Prázdné jméno není validní");
+    }
+
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn playlist_list_search_and_sort() {
+        let item = |id, name: &str, day| PlaylistPickerItem {
+            id,
+            name: String::from(name),
+            created: DateTime::from_timestamp(day * 86_400, 0).unwrap(),
+            num_items: 0,
+        };
+        let playlists = [
+            item(1, "Neděle (Kazatel: Jan Novák)", 1),
+            item(2, "Mládež", 3),
+            item(3, "Adventní neděle", 2),
+        ];
+        let ids = |items: Vec<&PlaylistPickerItem>| -> Vec<i64> {
+            items.iter().map(|item| item.id).collect()
+        };
+
+        assert_eq!(
+            ids(filter_and_sort(&playlists, "", PlaylistSort::Newest)),
+            vec![2, 3, 1]
+        );
+        assert_eq!(
+            ids(filter_and_sort(&playlists, "", PlaylistSort::Oldest)),
+            vec![1, 3, 2]
+        );
+        assert_eq!(
+            ids(filter_and_sort(&playlists, "", PlaylistSort::Name)),
+            vec![3, 2, 1]
+        );
+        assert_eq!(
+            ids(filter_and_sort(
+                &playlists,
+                " NEDĚLE ",
+                PlaylistSort::Oldest
+            )),
+            vec![1, 3]
+        );
+        assert_eq!(
+            ids(filter_and_sort(&playlists, "novák", PlaylistSort::Newest)),
+            vec![1],
+            "Hledat lze i podle lidí v rolích"
+        );
+    }
+
+    #[test]
+    fn new_playlist_name_validation() {
+        assert_eq!(
+            validate_new_playlist_name("  Neděle 1. 6. ").unwrap(),
+            "Neděle 1. 6."
+        );
+        assert!(validate_new_playlist_name("").is_err());
+        assert!(validate_new_playlist_name(" \t\n").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! Ochrana chráněných operací (mazání playlistů a profilů, vstup do nastavení) PINem,
+//! viz [`crate::config::Settings::pin`]. Zprávu spouštějící chráněnou operaci zachytí
+//! [`Ekkles::update`] a místo ní zobrazí dotaz na PIN. Po zadání správného PINu se
+//! vrátíme na předchozí obrazovku a zpráva se odešle znovu.
+
+use iced::{
+    Element, Length, Task,
+    alignment::Horizontal,
+    widget::{button, column, container, row, text, text_input},
+};
+use log::{debug, warn};
+
+use crate::{Ekkles, Screen};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    PinChanged(String),
+    /// Ověřit zadaný PIN a případně provést chráněnou operaci
+    Submit,
+    /// Zrušit operaci a vrátit se na předchozí obrazovku
+    Cancel,
+}
+
+impl From<Message> for crate::Message {
+    fn from(value: Message) -> Self {
+        crate::Message::PinPrompt(value)
+    }
+}
+
+/// Obrazovka s dotazem na PIN před provedením chráněné operace
+#[derive(Debug)]
+pub struct PinPrompt {
+    /// Obrazovka, na kterou se vrátíme po zadání PINu nebo zrušení
+    previous_screen: Box<Screen>,
+    /// Zpráva chráněné operace, odešle se po zadání správného PINu
+    pending: Box<crate::Message>,
+    pin: String,
+    err_msg: String,
+}
+
+impl PinPrompt {
+    pub fn view(&self) -> Element<Message> {
+        container(
+            column![
+                text("Tato operace je chráněna PINem, zadej jej pro pokračování"),
+                text_input("PIN", &self.pin)
+                    .on_input(Message::PinChanged)
+                    .on_submit(Message::Submit)
+                    .secure(true)
+                    .width(200),
+                row![
+                    button("Potvrdit")
+                        .style(button::success)
+                        .on_press(Message::Submit),
+                    button("Zrušit").on_press(Message::Cancel),
+                ]
+                .spacing(10),
+                text(&self.err_msg).style(text::danger),
+            ]
+            .spacing(20)
+            .align_x(Horizontal::Center),
+        )
+        .center(Length::Fill)
+        .into()
+    }
+
+    /// Obrazovka, ze které byla chráněná operace spuštěna
+    pub fn get_previous_screen(&self) -> &Screen {
+        &self.previous_screen
+    }
+}
+
+/// Zobrazí dotaz na PIN, po jeho zadání se odešle zpráva `pending`.
+pub fn prompt(state: &mut Ekkles, pending: crate::Message) -> Task<crate::Message> {
+    let previous_screen =
+        std::mem::replace(&mut state.screen, Screen::ErrorOccurred(String::new()));
+    state.screen = Screen::ConfirmPin(PinPrompt {
+        previous_screen: Box::new(previous_screen),
+        pending: Box::new(pending),
+        pin: String::new(),
+        err_msg: String::new(),
+    });
+    Task::none()
+}
+
+/// Update funkce pro dotaz na PIN. Pokud je tato funkce zavolána nad jinou
+/// obrazovkou než [`Screen::ConfirmPin`], zpanikaří.
+pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
+    let prompt = match &mut state.screen {
+        Screen::ConfirmPin(prompt) => prompt,
+        screen => panic!(
+            "Update pro dotaz na PIN zavolán nad jinou obrazovkou {:?}",
+            screen
+        ),
+    };
+
+    match msg {
+        Message::PinChanged(input) => {
+            prompt.pin = input;
+            Task::none()
+        }
+        Message::Submit if !state.config.settings.check_pin(&prompt.pin) => {
+            warn!("Zadán nesprávný PIN");
+            prompt.pin.clear();
+            prompt.err_msg = String::from("Nesprávný PIN");
+            Task::none()
+        }
+        Message::Submit => {
+            debug!("PIN správný, provádím chráněnou operaci");
+            let Screen::ConfirmPin(prompt) =
+                std::mem::replace(&mut state.screen, Screen::ErrorOccurred(String::new()))
+            else {
+                unreachable!("Obrazovka byla zkontrolována výše");
+            };
+            state.screen = *prompt.previous_screen;
+            state.pin_unlocked = true;
+            Task::done(*prompt.pending)
+        }
+        Message::Cancel => {
+            debug!("Zadání PINu zrušeno");
+            let Screen::ConfirmPin(prompt) =
+                std::mem::replace(&mut state.screen, Screen::ErrorOccurred(String::new()))
+            else {
+                unreachable!("Obrazovka byla zkontrolována výše");
+            };
+            state.screen = *prompt.previous_screen;
+            Task::none()
+        }
+    }
+}
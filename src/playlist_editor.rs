@@ -1,24 +1,42 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use anyhow::Context;
 use ekkles_data::{
-    Song,
-    playlist::{self, PlaylistMetadata, PlaylistMetadataStatus},
+    PartTag, Song,
+    license::{self, LICENSE_WARNING_BADGE, LicenseWarning},
+    media::{self, MediaInfo},
+    playlist::{
+        self, PlaylistMetadata, PlaylistMetadataStatus, SERVICE_ROLES, SONG_KEYS,
+        preflight::{self, PreflightIssue},
+    },
+    song_arrangement::SongArrangement,
+    songbook::append_songbook_numbers,
+    theme,
 };
 use iced::{
     Element, Length, Task,
     alignment::{Horizontal, Vertical},
-    widget::{button, column, container, row, text, text_input},
+    widget::{
+        button, checkbox, column, container, pick_list, row, scrollable, text, text_editor,
+        text_input,
+    },
 };
-use log::{debug, trace};
+use log::{debug, trace, warn};
 use tokio::sync::Mutex;
 
 use crate::{
     Ekkles, Screen,
     bible_picker::BiblePicker,
-    components::{TopButtonsMessage, TopButtonsPickedSection, playlist_item_styles, top_buttons},
+    components::{
+        BlockOption, MediaOption, ThemeOption, TopButtonsMessage, TopButtonsPickedSection,
+        TranslationOption, format_duration, playlist_item_styles, top_buttons,
+    },
     pick_playlist::{self, PlaylistPicker},
+    plugins::{PluginItem, Plugins},
     presenter::Presenter,
+    reading_plan_picker::ReadingPlanPicker,
+    settings::SettingsEditor,
+    slide_break_editor::SlideBreakEditor,
     song_picker::SongPicker,
 };
 
@@ -26,8 +44,63 @@ use crate::{
 pub enum Message {
     TopButtonsPlaylist,
     TopButtonsSongs,
+    TopButtonsSettings,
+    OpenSettings,
     LoadSongNameCache,
     SongNameCacheLoaded(Vec<(i64, String)>),
+    LoadThemes,
+    ThemesLoaded(Vec<(i64, String)>),
+    PlaylistThemePicked(ThemeOption),
+    LoadKnownPeople,
+    KnownPeopleLoaded(Vec<String>),
+    /// Načte obrázky z úložiště médií, které lze vložit do playlistu
+    LoadMedia,
+    MediaLoaded(Vec<MediaInfo>),
+    /// Načte playlisty, které lze vložit jako blok, viz [`playlist::get_block_options()`]
+    LoadBlocks,
+    BlocksLoaded(Vec<(i64, String)>),
+    /// Načte varování k licencím písní, pokud je zapnutý licenční režim
+    LoadLicenseWarnings,
+    LicenseWarningsLoaded(HashMap<i64, LicenseWarning>),
+    /// Píseň s daným ID byla označena jako licencovaná/nelicencovaná
+    SongLicensedToggled(i64, bool),
+    /// Změna jména člověka v dané roli
+    RoleChanged(&'static str, String),
+    /// Bylo vybráno doplnění jména pro danou roli
+    RoleSuggestionPicked(&'static str, String),
+    SongThemeLoaded(Option<i64>),
+    /// Načtena píseň s daným ID pro náhled sekvence slajdů vybrané položky
+    SelectedSongLoaded(i64, Song),
+    /// Byl vybrán motiv pro píseň s daným ID
+    SongThemePicked(i64, ThemeOption),
+    /// Byla vybrána tónina pro píseň na daném indexu
+    SongKeyPicked(usize, &'static str),
+    /// Zruší tóninu písně na daném indexu
+    ClearSongKey(usize),
+    /// Změna vlastního pořadí částí písně na daném indexu (tagy oddělené mezerou,
+    /// prázdné = pořadí písně)
+    ArrangementChanged(usize, String),
+    /// Zruší vlastní pořadí částí písně na daném indexu
+    ResetArrangement(usize),
+    /// Načtena pojmenovaná pořadí písně s daným ID, viz [`SongArrangement`]
+    SongArrangementsLoaded(i64, Vec<SongArrangement>),
+    /// Nastaví písni na daném indexu pojmenované pořadí jako vlastní pořadí
+    SongArrangementApplied(usize, Vec<PartTag>),
+    /// Změna názvu, pod kterým se uloží pořadí písně
+    ArrangementNameChanged(String),
+    /// Uloží vlastní pořadí písně na daném indexu (případně pořadí písně) jako pojmenované
+    /// pořadí písně s daným ID
+    SaveSongArrangement(i64, usize),
+    /// Smaže pojmenované pořadí s daným názvem písně s daným ID
+    DeleteSongArrangement(i64, String),
+    SongArrangementFailed(String),
+    /// Změna čísla světelného cue položky na daném indexu (prázdné = bez cue)
+    LightingCueChanged(usize, String),
+    /// Zapnutí/vypnutí promítání položky na daném indexu (vypnutá zůstává v playlistu)
+    ItemEnabledToggled(usize, bool),
+    SongTranslationLoaded(Option<i64>),
+    /// Byl vybrán překlad pro píseň s daným ID
+    SongTranslationPicked(i64, TranslationOption),
     SavePlaylist,
     PlaylistSavedSuccessfully,
     SavePlaylistAsClicked,
@@ -36,16 +109,55 @@ pub enum Message {
     InvalidNewPlaylistName(String),
     SavePlaylistAs,
     DeletePlaylist,
+    /// Playlist nelze smazat (např. je vložený jako blok v jiném playlistu)
+    DeletePlaylistFailed(String),
+    /// Otevře dialog pro uložení playlistu jako přenosného balíčku, viz [`playlist::bundle`]
+    ExportPlaylist,
+    /// Playlist byl exportován do souboru na dané cestě
+    PlaylistExported(String),
+    ExportFailed(String),
+    ExportDialogCancelled,
     SaveAndExit,
     ReturnToPlaylistPicker,
+    /// Zkontroluje playlist před prezentací, viz [`preflight`]
     LoadPresentation,
+    PreflightChecked(Vec<PreflightIssue>),
+    /// Zavře seznam problémů a vybere položku na daném indexu k opravě
+    FixPreflightIssue(usize),
+    ClosePreflight,
+    /// Uloží playlist a načte prezentaci (bez kontroly)
+    PresentPlaylist,
     StartPresentation(Presenter),
     AddBiblePassage,
+    /// Přejde na plány čtení, odkud lze vložit čtení daného dne
+    AddReadingPlan,
     AddSong,
+    /// Přidá na konec playlistu nový vlastní text a vybere jej k úpravě
+    AddCustomText,
+    /// Změna názvu vlastního textu na daném indexu
+    CustomTextTitleChanged(usize, String),
+    /// Úprava promítaného obsahu vlastního textu na daném indexu
+    CustomTextBodyEdited(usize, text_editor::Action),
+    /// Přidá na konec playlistu vybraný obrázek
+    AddImage(MediaOption),
+    /// Přidá na konec playlistu vybraný playlist jako blok
+    AddBlock(BlockOption),
+    /// Změna dotazu pro zdroje položek z pluginů (např. datum pro lekcionář)
+    PluginQueryChanged(String),
+    /// Přidá na konec playlistu texty ze zdroje položek pluginu na daném indexu
+    AddPluginItems(usize),
+    PluginItemsLoaded(Vec<PluginItem>),
+    PluginItemsFailed(String),
     SelectItem(usize),
     MoveItemUp(usize),
     MoveItemDown(usize),
     DeleteItem(usize),
+    /// Přejde na výběr písně, která nahradí píseň na daném indexu
+    ReplaceSong(usize),
+    /// Přejde na výběr pasáže, která nahradí pasáž na daném indexu
+    EditBiblePassage(usize),
+    /// Přejde na úpravu zlomů slajdů pasáže na daném indexu
+    EditSlideBreaks(usize),
 }
 
 impl From<Message> for crate::Message {
@@ -54,15 +166,147 @@ impl From<Message> for crate::Message {
     }
 }
 
+impl Message {
+    /// Zda je zpráva povolená v režimu hosta, tedy pouze prohlíží a prezentuje playlist,
+    /// nic v něm (ani v písních) nemění
+    pub fn allowed_in_guest_mode(&self) -> bool {
+        match self {
+            Message::LoadSongNameCache
+            | Message::SongNameCacheLoaded(_)
+            | Message::LoadThemes
+            | Message::ThemesLoaded(_)
+            | Message::LoadKnownPeople
+            | Message::KnownPeopleLoaded(_)
+            | Message::LoadMedia
+            | Message::MediaLoaded(_)
+            | Message::LoadBlocks
+            | Message::BlocksLoaded(_)
+            | Message::LoadLicenseWarnings
+            | Message::LicenseWarningsLoaded(_)
+            | Message::SongThemeLoaded(_)
+            | Message::SelectedSongLoaded(..)
+            | Message::SongArrangementsLoaded(..)
+            | Message::SongTranslationLoaded(_)
+            | Message::ReturnToPlaylistPicker
+            | Message::LoadPresentation
+            | Message::PreflightChecked(_)
+            | Message::FixPreflightIssue(_)
+            | Message::ClosePreflight
+            | Message::PresentPlaylist
+            | Message::StartPresentation(_)
+            | Message::SelectItem(_) => true,
+            Message::TopButtonsPlaylist
+            | Message::TopButtonsSongs
+            | Message::TopButtonsSettings
+            | Message::OpenSettings
+            | Message::PlaylistThemePicked(_)
+            | Message::SongLicensedToggled(..)
+            | Message::RoleChanged(..)
+            | Message::RoleSuggestionPicked(..)
+            | Message::SongThemePicked(..)
+            | Message::SongKeyPicked(..)
+            | Message::ClearSongKey(_)
+            | Message::ArrangementChanged(..)
+            | Message::ResetArrangement(_)
+            | Message::SongArrangementApplied(..)
+            | Message::ArrangementNameChanged(_)
+            | Message::SaveSongArrangement(..)
+            | Message::DeleteSongArrangement(..)
+            | Message::SongArrangementFailed(_)
+            | Message::LightingCueChanged(..)
+            | Message::ItemEnabledToggled(..)
+            | Message::SongTranslationPicked(..)
+            | Message::SavePlaylist
+            | Message::PlaylistSavedSuccessfully
+            | Message::SavePlaylistAsClicked
+            | Message::NewPlaylistNameChanged(_)
+            | Message::ValidateNewPlaylistName
+            | Message::InvalidNewPlaylistName(_)
+            | Message::SavePlaylistAs
+            | Message::DeletePlaylist
+            | Message::DeletePlaylistFailed(_)
+            | Message::ExportPlaylist
+            | Message::PlaylistExported(_)
+            | Message::ExportFailed(_)
+            | Message::ExportDialogCancelled
+            | Message::SaveAndExit
+            | Message::AddBiblePassage
+            | Message::AddReadingPlan
+            | Message::AddSong
+            | Message::AddCustomText
+            | Message::CustomTextTitleChanged(..)
+            | Message::CustomTextBodyEdited(..)
+            | Message::AddImage(_)
+            | Message::AddBlock(_)
+            | Message::PluginQueryChanged(_)
+            | Message::AddPluginItems(_)
+            | Message::PluginItemsLoaded(_)
+            | Message::PluginItemsFailed(_)
+            | Message::MoveItemUp(_)
+            | Message::MoveItemDown(_)
+            | Message::DeleteItem(_)
+            | Message::ReplaceSong(_)
+            | Message::EditBiblePassage(_)
+            | Message::EditSlideBreaks(_) => false,
+        }
+    }
+}
+
+/// Kolik nejvýše jmen se nabídne k doplnění při psaní jména do role
+const MAX_ROLE_SUGGESTIONS: usize = 5;
+/// Název nově přidaného vlastního textu
+const NEW_CUSTOM_TEXT_TITLE: &str = "Oznámení";
+
 impl From<TopButtonsMessage> for Message {
     fn from(value: TopButtonsMessage) -> Self {
         match value {
             TopButtonsMessage::Playlists => Message::TopButtonsPlaylist,
             TopButtonsMessage::Songs => Message::TopButtonsSongs,
+            TopButtonsMessage::Settings => Message::TopButtonsSettings,
         }
     }
 }
 
+/// Seznam problémů nalezených při kontrole před prezentací s odkazy na jejich opravu.
+/// Prezentovat přesto lze pouze, pokud jde jen o varování.
+fn view_preflight(issues: &[PreflightIssue]) -> Element<'_, Message> {
+    let can_present = issues.iter().all(|issue| !issue.is_blocking());
+
+    let issues = issues.iter().map(|issue| {
+        row![
+            text(issue.to_string())
+                .style(if issue.is_blocking() {
+                    text::danger
+                } else {
+                    text::default
+                })
+                .width(Length::Fill),
+            button("Opravit").on_press(Message::FixPreflightIssue(issue.item_index())),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center)
+        .into()
+    });
+
+    container(
+        column![
+            text("Před prezentací je potřeba opravit playlist").size(24),
+            scrollable(column(issues).spacing(5)).height(Length::Shrink),
+            row![
+                button("Zpět do editoru").on_press(Message::ClosePreflight),
+                button("Prezentovat přesto")
+                    .style(button::danger)
+                    .on_press_maybe(can_present.then_some(Message::PresentPlaylist)),
+            ]
+            .spacing(10),
+        ]
+        .spacing(20)
+        .max_width(800),
+    )
+    .center(Length::Fill)
+    .into()
+}
+
 #[derive(Debug)]
 pub struct PlaylistEditor {
     /// Editovaný playlist (potřebujeme ho zabalit do `Arc<Mutex<>>`, protože když jej ukládáme,
@@ -70,22 +314,105 @@ pub struct PlaylistEditor {
     playlist: Arc<Mutex<PlaylistMetadata>>,
     new_playlist_name: String,
     new_playlist_err_msg: String,
+    /// Výsledek posledního exportu playlistu do balíčku
+    export_msg: Result<String, String>,
     song_name_cache: Option<Vec<(i64, String)>>,
     selected_index: Option<usize>,
+    /// Nabídka motivů slajdů, první je vždy výchozí motiv
+    theme_options: Vec<ThemeOption>,
+    /// ID motivu vybrané písně (pokud je vybraná položka píseň)
+    selected_song_theme_id: Option<i64>,
+    /// ID překladu vybrané písně
+    selected_song_translation_id: Option<i64>,
+    /// Vybraná píseň (pokud je vybraná položka píseň) pro náhled sekvence jejích slajdů
+    selected_song: Option<Song>,
+    /// Obsah vybraného vlastního textu (pokud je vybraná položka text) pro jeho úpravu
+    custom_text_body: text_editor::Content,
+    /// Textový vstup vlastního pořadí částí vybrané písně (tagy oddělené mezerou)
+    arrangement_input: String,
+    /// Pojmenovaná pořadí vybrané písně
+    song_arrangements: Vec<SongArrangement>,
+    /// Název, pod kterým se uloží pořadí vybrané písně
+    arrangement_name_input: String,
+    /// Chyba posledního uložení nebo smazání pojmenovaného pořadí
+    arrangement_msg: String,
+    /// Problémy nalezené při kontrole před prezentací, pokud se zobrazují
+    preflight_issues: Option<Vec<PreflightIssue>>,
+    /// Textové vstupy jmen lidí v rolích (v playlistu jsou jména bez okrajových mezer)
+    role_inputs: HashMap<&'static str, String>,
+    /// Jména lidí z dřívějších playlistů pro doplňování
+    known_people: Vec<String>,
+    /// Role, do které se právě píše jméno, u ní se nabízí doplnění
+    edited_role: Option<&'static str>,
+    /// Varování k licencím písní (id písně -> varování), `None` pokud je licenční režim vypnutý
+    license_warnings: Option<HashMap<i64, LicenseWarning>>,
+    /// Obrázky z úložiště médií pro vkládání a zobrazení názvů obrázků v playlistu
+    media_options: Option<Vec<MediaOption>>,
+    /// Playlisty pro vkládání bloků a zobrazení názvů bloků v playlistu
+    block_options: Option<Vec<BlockOption>>,
+    /// Dotaz pro zdroje položek z pluginů
+    plugin_query: String,
+    /// Chyba posledního načtení položek z pluginu
+    plugin_err_msg: String,
 }
 
 impl PlaylistEditor {
     pub fn new(playlist: PlaylistMetadata) -> Self {
+        let role_inputs = SERVICE_ROLES
+            .into_iter()
+            .map(|role| {
+                (
+                    role,
+                    playlist.get_role(role).unwrap_or_default().to_string(),
+                )
+            })
+            .collect();
+
         Self {
             playlist: Arc::new(Mutex::new(playlist)),
             new_playlist_name: String::new(),
             new_playlist_err_msg: String::new(),
+            export_msg: Ok(String::new()),
             song_name_cache: None,
             selected_index: None,
+            theme_options: ThemeOption::options(&[]),
+            selected_song_theme_id: None,
+            selected_song_translation_id: None,
+            selected_song: None,
+            custom_text_body: text_editor::Content::new(),
+            arrangement_input: String::new(),
+            song_arrangements: Vec::new(),
+            arrangement_name_input: String::new(),
+            arrangement_msg: String::new(),
+            preflight_issues: None,
+            role_inputs,
+            known_people: Vec::new(),
+            edited_role: None,
+            license_warnings: None,
+            media_options: None,
+            block_options: None,
+            plugin_query: String::new(),
+            plugin_err_msg: String::new(),
         }
     }
 
-    pub fn view(&self) -> Element<Message> {
+    /// Vrátí kopii editovaného playlistu
+    pub fn get_playlist(&self) -> PlaylistMetadata {
+        self.playlist.blocking_lock().clone()
+    }
+
+    /// V režimu hosta (`guest_mode`) lze playlist pouze prohlížet a prezentovat
+    pub fn view(&self, guest_mode: bool, plugins: &Plugins) -> Element<Message> {
+        let top: Element<Message> = if guest_mode {
+            column([]).into()
+        } else {
+            top_buttons(TopButtonsPickedSection::Playlists).map(|msg| msg.into())
+        };
+
+        if let Some(issues) = &self.preflight_issues {
+            return column![top, view_preflight(issues)].into();
+        }
+
         let (playlist_status, playlist_name) = {
             // Tady blokuju čekáním na mutex v GUI kódu, ale contention tohoto mutexu
             // je prakticky nulová (zamykám ho jen při zápisu do DB, který je velice rychlý).
@@ -106,57 +433,240 @@ impl PlaylistEditor {
         // zkonstruované GUI
         let playlist = self.playlist.blocking_lock();
 
-        let playlist_items = playlist
-            .get_items()
-            .iter()
-            .enumerate()
-            .map(|(index, item)| {
-                let msg = if self
-                    .selected_index
-                    .is_some_and(|selected| selected == index)
-                {
-                    None
-                } else {
-                    Some(Message::SelectItem(index))
-                };
+        let playlist_items = playlist.get_items().enumerate().map(|(index, item)| {
+            let msg = if self
+                .selected_index
+                .is_some_and(|selected| selected == index)
+            {
+                None
+            } else {
+                Some(Message::SelectItem(index))
+            };
 
-                match item {
-                    playlist::PlaylistItemMetadata::BiblePassage { from, to, .. } => {
-                        button(text(format!("Pasáž {} - {}", from, to)))
-                            .style(if msg.is_none() {
-                                playlist_item_styles::song_selected
-                            } else {
-                                playlist_item_styles::song
-                            })
-                            .on_press_maybe(msg)
-                            .width(Length::Fill)
-                            .into()
-                    }
-                    playlist::PlaylistItemMetadata::Song(sought_id) => button(text(format!(
-                        "Píseň {}",
-                        self.song_name_cache
-                            .as_ref()
-                            .map(|cache| cache
-                                .iter()
-                                .find(|(id, _)| id == sought_id)
-                                .unwrap()
-                                .1
-                                .as_str())
-                            .unwrap_or("...")
-                    )))
-                    .style(if msg.is_none() {
-                        playlist_item_styles::passage_selected
-                    } else {
-                        playlist_item_styles::passage
-                    })
-                    .on_press_maybe(msg)
-                    .width(Length::Fill)
-                    .into(),
+            // Očekávaná délka položky naměřená při minulé prezentaci, vypnutá položka
+            // se navíc označí
+            let mut duration = playlist
+                .get_expected_duration(index)
+                .map(|duration| format!(" (~{})", format_duration(duration)))
+                .unwrap_or_default();
+            if !playlist.is_item_enabled(index) {
+                duration.push_str(" (vypnuto)");
+            }
+
+            match item {
+                playlist::PlaylistItemMetadata::BiblePassage { from, to, .. } => {
+                    button(text(format!("Pasáž {} - {}{}", from, to, duration)))
+                        .style(if msg.is_none() {
+                            playlist_item_styles::song_selected
+                        } else {
+                            playlist_item_styles::song
+                        })
+                        .on_press_maybe(msg)
+                        .width(Length::Fill)
+                        .into()
                 }
-            });
+                playlist::PlaylistItemMetadata::Song(sought_id) => button(text(format!(
+                    "Píseň {}{}{}{}",
+                    self.song_name_cache
+                        .as_ref()
+                        .map(|cache| cache
+                            .iter()
+                            .find(|(id, _)| id == sought_id)
+                            .unwrap()
+                            .1
+                            .as_str())
+                        .unwrap_or("..."),
+                    playlist
+                        .get_song_key(index)
+                        .map(|key| format!(" [{key}]"))
+                        .unwrap_or_default(),
+                    duration,
+                    self.license_warnings
+                        .as_ref()
+                        .and_then(|warnings| warnings.get(sought_id))
+                        .map(|warning| format!(" {LICENSE_WARNING_BADGE} {warning}"))
+                        .unwrap_or_default(),
+                )))
+                .style(if msg.is_none() {
+                    playlist_item_styles::passage_selected
+                } else {
+                    playlist_item_styles::passage
+                })
+                .on_press_maybe(msg)
+                .width(Length::Fill)
+                .into(),
+                playlist::PlaylistItemMetadata::CustomText { title, .. } => {
+                    button(text(format!("Text {}{}", title, duration)))
+                        .style(if msg.is_none() {
+                            playlist_item_styles::custom_text_selected
+                        } else {
+                            playlist_item_styles::custom_text
+                        })
+                        .on_press_maybe(msg)
+                        .width(Length::Fill)
+                        .into()
+                }
+                playlist::PlaylistItemMetadata::Image(media_id) => {
+                    let name = match &self.media_options {
+                        Some(options) => options
+                            .iter()
+                            .find(|option| option.id == *media_id)
+                            .map_or("(smazaný obrázek)", |option| option.name.as_str()),
+                        None => "...",
+                    };
+                    button(text(format!("Obrázek {}{}", name, duration)))
+                        .style(if msg.is_none() {
+                            playlist_item_styles::image_selected
+                        } else {
+                            playlist_item_styles::image
+                        })
+                        .on_press_maybe(msg)
+                        .width(Length::Fill)
+                        .into()
+                }
+                playlist::PlaylistItemMetadata::Block(block_id) => {
+                    let name = match &self.block_options {
+                        // Playlisty obsahující tento playlist v nabídce nejsou, blok mezi
+                        // nimi ale nikdy není (byl by to cyklus)
+                        Some(options) => options
+                            .iter()
+                            .find(|option| option.id == *block_id)
+                            .map_or("(smazaný playlist)", |option| option.name.as_str()),
+                        None => "...",
+                    };
+                    button(text(format!("Blok {}{}", name, duration)))
+                        .style(if msg.is_none() {
+                            playlist_item_styles::block_selected
+                        } else {
+                            playlist_item_styles::block
+                        })
+                        .on_press_maybe(msg)
+                        .width(Length::Fill)
+                        .into()
+                }
+            }
+        });
+
+        let expected_duration_text = match playlist.get_total_expected_duration() {
+            (0, _) => String::from("Odhadovaná délka není známa"),
+            (total, 0) => format!("Odhadovaná délka: {}", format_duration(total)),
+            (total, unknown) => format!(
+                "Odhadovaná délka: {} (+ {} položek bez odhadu)",
+                format_duration(total),
+                unknown
+            ),
+        };
+
+        let playlist_theme = self
+            .theme_options
+            .iter()
+            .find(|option| option.id == playlist.get_theme_id())
+            .cloned();
 
         let item_manipulation = match self.selected_index {
+            // Host vidí pouze sekvenci slajdů vybrané písně
+            Some(index) if guest_mode => {
+                match playlist.get_item(index).expect("Položka je vybraná") {
+                    playlist::PlaylistItemMetadata::Song(_) => {
+                        column![self.view_slide_sequence(playlist.get_arrangement(index))]
+                    }
+                    playlist::PlaylistItemMetadata::BiblePassage { .. } => column([]),
+                    playlist::PlaylistItemMetadata::CustomText { body, .. } => {
+                        column![text(body.clone())]
+                    }
+                    playlist::PlaylistItemMetadata::Image(_)
+                    | playlist::PlaylistItemMetadata::Block(_) => column([]),
+                }
+            }
             Some(index) => {
+                // Motiv lze nastavit pouze písním, pasáže se promítají motivem playlistu/výchozím
+                let item_specific = match playlist.get_item(index).expect("Položka je vybraná") {
+                    &playlist::PlaylistItemMetadata::Song(song_id) => column![
+                        button("Nahradit píseň…")
+                            .on_press(Message::ReplaceSong(index))
+                            .width(Length::Fill),
+                        text("Motiv písně"),
+                        pick_list(
+                            self.theme_options.clone(),
+                            self.theme_options
+                                .iter()
+                                .find(|option| option.id == self.selected_song_theme_id)
+                                .cloned(),
+                            move |option| Message::SongThemePicked(song_id, option),
+                        )
+                        .width(Length::Fill),
+                        text("Překlad písně"),
+                        self.view_translation_picker(song_id),
+                        text("Tónina pro tuto bohoslužbu"),
+                        row![
+                            pick_list(
+                                SONG_KEYS,
+                                SONG_KEYS
+                                    .iter()
+                                    .find(|key| Some(**key) == playlist.get_song_key(index))
+                                    .copied(),
+                                move |key| Message::SongKeyPicked(index, key),
+                            )
+                            .placeholder("Neurčena")
+                            .width(Length::Fill),
+                            button("Zrušit").on_press_maybe(
+                                playlist
+                                    .get_song_key(index)
+                                    .map(|_| Message::ClearSongKey(index))
+                            ),
+                        ]
+                        .spacing(5),
+                        text("Pořadí částí pro tuto bohoslužbu"),
+                        row![
+                            text_input(
+                                &self
+                                    .selected_song
+                                    .as_ref()
+                                    .map(|song| song.order.join(" "))
+                                    .unwrap_or_else(|| String::from("Pořadí písně")),
+                                &self.arrangement_input,
+                            )
+                            .on_input(move |input| Message::ArrangementChanged(index, input))
+                            .width(Length::Fill),
+                            button("Zrušit").on_press_maybe(
+                                playlist
+                                    .get_arrangement(index)
+                                    .map(|_| Message::ResetArrangement(index))
+                            ),
+                        ]
+                        .spacing(5),
+                        self.view_song_arrangements(song_id, index),
+                        self.view_license_toggle(song_id),
+                        self.view_slide_sequence(playlist.get_arrangement(index)),
+                    ]
+                    .spacing(5),
+                    playlist::PlaylistItemMetadata::BiblePassage { .. } => column![
+                        button("Upravit pasáž")
+                            .on_press(Message::EditBiblePassage(index))
+                            .width(Length::Fill),
+                        button("Rozdělení na slajdy")
+                            .on_press(Message::EditSlideBreaks(index))
+                            .width(Length::Fill),
+                    ]
+                    .spacing(10),
+                    playlist::PlaylistItemMetadata::CustomText { title, .. } => column![
+                        text("Název (zobrazuje se pouze obsluze)"),
+                        text_input("Název textu", title)
+                            .on_input(move |title| Message::CustomTextTitleChanged(index, title)),
+                        text("Promítaný text"),
+                        text_editor(&self.custom_text_body)
+                            .on_action(move |action| Message::CustomTextBodyEdited(index, action))
+                            .height(Length::Fixed(200.0)),
+                    ]
+                    .spacing(5),
+                    playlist::PlaylistItemMetadata::Image(_) => column![text(
+                        "Obrázek se promítne přes celou obrazovku, nové obrázky lze importovat v nastavení"
+                    )],
+                    playlist::PlaylistItemMetadata::Block(_) => column![text(
+                        "Blok se promítne jako položky vloženého playlistu. Jeho úpravy se projeví ve všech playlistech, které jej obsahují."
+                    )],
+                };
+
                 column![
                     button("Posunout nahoru")
                         .on_press_maybe(if index == 0 {
@@ -178,56 +688,135 @@ impl PlaylistEditor {
                         .on_press(Message::DeleteItem(index))
                         .style(button::danger)
                         .width(Length::Fill),
+                    row![
+                        text("Světelné cue").width(Length::Fill),
+                        text_input(
+                            "Bez cue",
+                            &playlist
+                                .get_lighting_cue(index)
+                                .map(|cue| cue.to_string())
+                                .unwrap_or_default()
+                        )
+                        .on_input(move |input| Message::LightingCueChanged(index, input))
+                        .width(Length::Fill),
+                    ]
+                    .spacing(5)
+                    .align_y(Vertical::Center),
+                    checkbox(
+                        "Promítat položku (vypnutá zůstane v playlistu)",
+                        playlist.is_item_enabled(index)
+                    )
+                    .on_toggle(move |enabled| Message::ItemEnabledToggled(index, enabled)),
+                    item_specific,
                 ]
             }
             None => column([]),
         };
 
-        Into::<Element<Message>>::into(column![
-            top_buttons(TopButtonsPickedSection::Playlists).map(|msg| msg.into()),
-            container(row![
-                column![
-                    column![
-                        text(format!("Edituješ playlist \"{}\"", playlist_name)),
-                        button("Uložit")
-                            .on_press_maybe(save_button_msg)
-                            .width(Length::Fill),
-                        row![
-                            text_input("Název nového playlistu", &self.new_playlist_name)
-                                .on_input(Message::NewPlaylistNameChanged)
-                                .on_submit(Message::SavePlaylistAsClicked),
-                            button("Uložit jako").on_press(Message::SavePlaylistAsClicked)
-                        ]
-                        .width(Length::Fill),
-                        text(&self.new_playlist_err_msg)
-                            .style(text::danger)
-                            .width(Length::Fill),
-                        button("Smazat playlist")
-                            .style(button::danger)
-                            .on_press(Message::DeletePlaylist)
-                            .width(Length::Fill),
-                        button("Přidat píseň")
-                            .on_press(Message::AddSong)
-                            .width(Length::Fill),
-                        button("Přidat verše")
-                            .on_press(Message::AddBiblePassage)
-                            .width(Length::Fill),
-                        button("Prezentovat")
-                            .on_press(Message::LoadPresentation)
-                            .width(Length::Fill)
-                    ]
+        let playlist_controls = if guest_mode {
+            column![
+                text(format!(
+                    "Prohlížíš playlist \"{}\" (režim hosta, nelze upravovat)",
+                    playlist_name
+                )),
+                text(expected_duration_text),
+                button("Prezentovat")
+                    .on_press(Message::LoadPresentation)
                     .width(Length::Fill)
-                    .padding(30)
-                    .spacing(10),
-                    container(
-                        button("Zpět")
-                            .width(Length::Fill)
-                            .on_press(Message::SaveAndExit)
+            ]
+        } else {
+            column![
+                text(format!("Edituješ playlist \"{}\"", playlist_name)),
+                text(expected_duration_text),
+                row![
+                    text("Motiv playlistu").width(Length::Fill),
+                    pick_list(
+                        self.theme_options.clone(),
+                        playlist_theme,
+                        Message::PlaylistThemePicked,
                     )
-                    .padding(30)
-                    .align_y(Vertical::Bottom)
-                    .height(Length::Fill)
+                    .width(Length::Fill),
+                ]
+                .spacing(5),
+                self.view_roles(),
+                button("Uložit")
+                    .on_press_maybe(save_button_msg)
+                    .width(Length::Fill),
+                row![
+                    text_input("Název nového playlistu", &self.new_playlist_name)
+                        .on_input(Message::NewPlaylistNameChanged)
+                        .on_submit(Message::SavePlaylistAsClicked),
+                    button("Uložit jako").on_press(Message::SavePlaylistAsClicked)
+                ]
+                .width(Length::Fill),
+                text(&self.new_playlist_err_msg)
+                    .style(text::danger)
+                    .width(Length::Fill),
+                button("Smazat playlist")
+                    .style(button::danger)
+                    .on_press(Message::DeletePlaylist)
+                    .width(Length::Fill),
+                button("Exportovat playlist…")
+                    .on_press(Message::ExportPlaylist)
+                    .width(Length::Fill),
+                match &self.export_msg {
+                    Ok(info) => text(info).style(text::success),
+                    Err(err) => text(err).style(text::danger),
+                }
+                .width(Length::Fill),
+                button("Přidat píseň")
+                    .on_press(Message::AddSong)
+                    .width(Length::Fill),
+                button("Přidat verše")
+                    .on_press(Message::AddBiblePassage)
+                    .width(Length::Fill),
+                button("Plán čtení")
+                    .on_press(Message::AddReadingPlan)
+                    .width(Length::Fill),
+                button("Přidat text")
+                    .on_press(Message::AddCustomText)
+                    .width(Length::Fill),
+                pick_list(
+                    self.media_options.clone().unwrap_or_default(),
+                    None::<MediaOption>,
+                    Message::AddImage,
+                )
+                .placeholder("Přidat obrázek")
+                .width(Length::Fill),
+                pick_list(
+                    self.block_options.clone().unwrap_or_default(),
+                    None::<BlockOption>,
+                    Message::AddBlock,
+                )
+                .placeholder("Přidat blok (jiný playlist)")
+                .width(Length::Fill),
+                self.view_plugin_item_sources(plugins),
+                button("Prezentovat")
+                    .on_press(Message::LoadPresentation)
                     .width(Length::Fill)
+            ]
+        };
+
+        // Host se vrací bez ukládání, playlist nemohl změnit
+        let return_msg = if guest_mode {
+            Message::ReturnToPlaylistPicker
+        } else {
+            Message::SaveAndExit
+        };
+
+        Into::<Element<Message>>::into(column![
+            top,
+            container(row![
+                column![
+                    playlist_controls
+                        .width(Length::Fill)
+                        .padding(30)
+                        .spacing(10),
+                    container(button("Zpět").width(Length::Fill).on_press(return_msg))
+                        .padding(30)
+                        .align_y(Vertical::Bottom)
+                        .height(Length::Fill)
+                        .width(Length::Fill)
                 ]
                 .width(Length::FillPortion(1))
                 .align_x(Horizontal::Center),
@@ -250,6 +839,217 @@ impl PlaylistEditor {
         // .explain(Color::BLACK)
     }
 
+    /// Zdroje položek z pluginů, každý přidá texty podle společného dotazu. Bez pluginů
+    /// se nic nezobrazí.
+    fn view_plugin_item_sources(&self, plugins: &Plugins) -> Element<Message> {
+        let sources: Vec<Element<Message>> = plugins
+            .item_sources()
+            .map(|(index, label)| {
+                button(text(format!("Přidat: {label}")))
+                    .on_press(Message::AddPluginItems(index))
+                    .width(Length::Fill)
+                    .into()
+            })
+            .collect();
+
+        if sources.is_empty() {
+            return column([]).into();
+        }
+
+        column![
+            text_input("Dotaz pro pluginy (např. datum)", &self.plugin_query)
+                .on_input(Message::PluginQueryChanged),
+            column(sources).spacing(5),
+            text(&self.plugin_err_msg)
+                .style(text::danger)
+                .width(Length::Fill),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    /// Jména lidí v rolích na bohoslužbě. Při psaní jména se nabízejí shodná jména
+    /// lidí, kteří už v nějakém playlistu roli měli.
+    fn view_roles(&self) -> Element<Message> {
+        let roles = SERVICE_ROLES.into_iter().map(|role| {
+            let input = self.role_inputs.get(role).map_or("", String::as_str);
+
+            let suggestions = if self.edited_role == Some(role) && !input.trim().is_empty() {
+                let typed = input.trim().to_lowercase();
+                self.known_people
+                    .iter()
+                    .filter(|person| {
+                        person.to_lowercase().contains(&typed) && person.as_str() != input
+                    })
+                    .take(MAX_ROLE_SUGGESTIONS)
+                    .map(|person| {
+                        button(text(person))
+                            .style(button::secondary)
+                            .on_press(Message::RoleSuggestionPicked(role, person.clone()))
+                            .into()
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            column![
+                row![
+                    text(role).width(Length::Fill),
+                    text_input("Jméno", input)
+                        .on_input(move |person| Message::RoleChanged(role, person))
+                        .width(Length::Fill),
+                ]
+                .spacing(5)
+                .align_y(Vertical::Center),
+                row(suggestions).spacing(5),
+            ]
+            .spacing(5)
+            .into()
+        });
+
+        column(roles).spacing(5).into()
+    }
+
+    /// Výběr písně, která je překladem písně `song_id`. Překlad lze při prezentaci
+    /// promítat místo originálu nebo spolu s ním.
+    fn view_translation_picker(&self, song_id: i64) -> Element<Message> {
+        let Some(songs) = &self.song_name_cache else {
+            return text("Načítám písně...").into();
+        };
+
+        let options = TranslationOption::options(songs, song_id);
+        let selected = options
+            .iter()
+            .find(|option| option.id == self.selected_song_translation_id)
+            .cloned();
+
+        pick_list(options, selected, move |option| {
+            Message::SongTranslationPicked(song_id, option)
+        })
+        .width(Length::Fill)
+        .into()
+    }
+
+    /// Pojmenovaná pořadí vybrané písně `song_id` (na indexu `index` playlistu), která lze
+    /// nastavit jako vlastní pořadí, a uložení aktuálního pořadí pod novým názvem
+    fn view_song_arrangements(&self, song_id: i64, index: usize) -> Element<Message> {
+        let arrangements =
+            self.song_arrangements.iter().map(|arrangement| {
+                row![
+                    text(arrangement.to_string()).width(Length::Fill),
+                    button("Použít").on_press(Message::SongArrangementApplied(
+                        index,
+                        arrangement.order.clone()
+                    )),
+                    button("Smazat").style(button::danger).on_press(
+                        Message::DeleteSongArrangement(song_id, arrangement.name.clone())
+                    ),
+                ]
+                .spacing(5)
+                .into()
+            });
+
+        column![
+            column(arrangements).spacing(5),
+            row![
+                text_input("Název pořadí (např. Krátká)", &self.arrangement_name_input)
+                    .on_input(Message::ArrangementNameChanged)
+                    .width(Length::Fill),
+                button("Uložit k písni").on_press_maybe(
+                    (!self.arrangement_name_input.trim().is_empty())
+                        .then_some(Message::SaveSongArrangement(song_id, index))
+                ),
+            ]
+            .spacing(5),
+            text(&self.arrangement_msg).style(text::danger),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    /// Zda je vybraná položka píseň `song_id` (mezitím, co se k ní něco načítalo, mohl
+    /// uživatel vybrat jinou položku)
+    fn is_song_selected(&self, song_id: i64) -> bool {
+        self.selected_index.is_some_and(|index| {
+            self.playlist.blocking_lock().get_item(index)
+                == Some(&playlist::PlaylistItemMetadata::Song(song_id))
+        })
+    }
+
+    /// Přepínač licence písně `song_id`, zobrazuje se pouze v licenčním režimu
+    fn view_license_toggle(&self, song_id: i64) -> Element<Message> {
+        match &self.license_warnings {
+            Some(warnings) => checkbox(
+                "Sbor má k písni licenci",
+                warnings.get(&song_id) != Some(&LicenseWarning::NotLicensed),
+            )
+            .on_toggle(move |licensed| Message::SongLicensedToggled(song_id, licensed))
+            .into(),
+            None => column([]).into(),
+        }
+    }
+
+    /// Náhled sekvence slajdů vybrané písně (jeden řádek na každý výskyt části v pořadí),
+    /// aby šlo chyby v pořadí odhalit ještě před prezentací. Pokud má píseň v playlistu
+    /// vlastní pořadí částí `arrangement`, zobrazí se sekvence podle něj.
+    fn view_slide_sequence(&self, arrangement: Option<&[PartTag]>) -> Element<Message> {
+        let Some(song) = &self.selected_song else {
+            return text("Načítám pořadí slajdů...").into();
+        };
+        let arranged;
+        let song = match arrangement {
+            Some(order) => {
+                arranged = Song {
+                    order: order.to_vec(),
+                    ..song.clone()
+                };
+                &arranged
+            }
+            None => song,
+        };
+
+        let slides =
+            song.slide_sequence()
+                .into_iter()
+                .enumerate()
+                .map(|(index, (tag, content))| {
+                    let row_text = match content {
+                        Some(content) => format!(
+                            "{}. {}: {}",
+                            index + 1,
+                            tag,
+                            content.lines().next().unwrap_or_default()
+                        ),
+                        None => format!("{}. {}: část neexistuje!", index + 1, tag),
+                    };
+
+                    text(row_text)
+                        .style(if content.is_some() {
+                            text::default
+                        } else {
+                            text::danger
+                        })
+                        .into()
+                });
+
+        let unused_parts = song.unused_parts();
+        let unused_parts = if unused_parts.is_empty() {
+            column([])
+        } else {
+            let tags: Vec<&str> = unused_parts.iter().map(|tag| tag.as_str()).collect();
+            column![text(format!("Nepromítnuté části: {}", tags.join(", "))).style(text::secondary)]
+        };
+
+        column![
+            text("Pořadí slajdů"),
+            scrollable(column(slides).spacing(2)).height(Length::Fill),
+            unused_parts,
+        ]
+        .spacing(5)
+        .into()
+    }
+
     /// Update funkce pro editor. Pokud je tato funkce zavolána nad jinou obrazovkou
     /// než [`Screen::EditPlaylist`], zpanikaří.
     pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
@@ -281,7 +1081,7 @@ impl PlaylistEditor {
                     &editor.new_playlist_name
                 );
                 let conn = state.db.acquire();
-                let new_playlist_name = editor.new_playlist_name.clone();
+                let new_playlist_name = editor.new_playlist_name.trim().to_string();
                 let playlist = editor.playlist.clone();
                 Task::perform(
                     async move {
@@ -302,9 +1102,52 @@ impl PlaylistEditor {
                 )
             }
             Message::LoadPresentation => {
+                debug!("Kontroluji playlist před prezentací");
+                let conn = state.db.acquire();
+                let playlist = editor.playlist.blocking_lock().clone();
+                let licensing_mode = state.config.settings.licensing_mode;
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
+                        let mut issues = preflight::check(&playlist, &mut conn).await?;
+                        if licensing_mode {
+                            issues.extend(preflight::check_licenses(&playlist, &mut conn).await?);
+                            // Řazení je stabilní, problémy jedné položky zůstanou u sebe
+                            issues.sort_by_key(|issue| issue.item_index());
+                        }
+                        Ok(issues)
+                    },
+                    |res| match res {
+                        Ok(issues) => Message::PreflightChecked(issues).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::PreflightChecked(issues) => {
+                if issues.is_empty() {
+                    debug!("Kontrola nenašla žádné problémy");
+                    Task::done(Message::PresentPlaylist.into())
+                } else {
+                    debug!("Kontrola našla problémy: {:?}", issues);
+                    editor.preflight_issues = Some(issues);
+                    Task::none()
+                }
+            }
+            Message::FixPreflightIssue(index) => {
+                editor.preflight_issues = None;
+                Task::done(Message::SelectItem(index).into())
+            }
+            Message::ClosePreflight => {
+                editor.preflight_issues = None;
+                Task::none()
+            }
+            Message::PresentPlaylist => {
                 debug!("Načítám prezentaci");
+                editor.preflight_issues = None;
                 let conn = state.db.acquire();
                 let playlist = editor.playlist.clone();
+                let settings = state.config.settings.clone();
+                let thumbnails_path = state.config.thumbnails_path.clone();
                 Task::perform(
                     async move {
                         let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
@@ -320,7 +1163,7 @@ impl PlaylistEditor {
                             unreachable!() // Právě jsme uložili playlist, musí být ve stavu Clean
                         };
 
-                        Presenter::try_new(id, &mut conn).await
+                        Presenter::try_new(id, &mut conn, settings, thumbnails_path).await
                     },
                     |res| match res {
                         Ok(presenter) => Message::StartPresentation(presenter).into(),
@@ -337,15 +1180,47 @@ impl PlaylistEditor {
             Message::AddBiblePassage => {
                 debug!("Přecházím na výběr playlistu");
                 let playlist = editor.playlist.blocking_lock().clone();
-                state.screen = Screen::PickBible(BiblePicker::new(playlist));
+                let selected_index = editor.selected_index;
+                state.screen =
+                    Screen::PickBible(BiblePicker::insert_after(playlist, selected_index));
                 Task::done(crate::Message::BiblePicker(
                     crate::bible_picker::Message::LoadTranslations,
                 ))
             }
+            Message::AddReadingPlan => {
+                debug!("Přecházím na plány čtení");
+                let playlist = editor.playlist.blocking_lock().clone();
+                state.screen = Screen::PickReadingPlan(ReadingPlanPicker::new(playlist));
+                Task::done(crate::reading_plan_picker::Message::LoadTranslations.into())
+            }
+            Message::ReplaceSong(index) => {
+                debug!("Přecházím na výběr písně, která nahradí píseň na indexu {index}");
+                let playlist = editor.playlist.blocking_lock().clone();
+                state.screen = Screen::PickSong(SongPicker::replace_item(playlist, index));
+                Task::done(crate::song_picker::Message::LoadSongs.into())
+            }
+            Message::EditBiblePassage(index) => {
+                debug!("Přecházím na úpravu pasáže na indexu {index}");
+                let playlist = editor.playlist.blocking_lock().clone();
+                state.screen = Screen::PickBible(BiblePicker::edit_item(playlist, index));
+                Task::done(crate::bible_picker::Message::LoadTranslations.into())
+            }
+            Message::EditSlideBreaks(index) => {
+                debug!("Přecházím na úpravu zlomů slajdů položky {index}");
+                let playlist = editor.playlist.blocking_lock().clone();
+                let verses_per_slide = state.config.settings.verses_per_slide().into();
+                state.screen = Screen::EditSlideBreaks(SlideBreakEditor::new(
+                    playlist,
+                    index,
+                    verses_per_slide,
+                ));
+                Task::done(crate::slide_break_editor::Message::LoadPassage.into())
+            }
             Message::AddSong => {
                 debug!("Přecházím na výběr písně");
                 let playlist = editor.playlist.blocking_lock().clone();
-                state.screen = Screen::PickSong(SongPicker::new(playlist));
+                let selected_index = editor.selected_index;
+                state.screen = Screen::PickSong(SongPicker::insert_after(playlist, selected_index));
                 Task::done(crate::Message::SongPicker(
                     crate::song_picker::Message::LoadSongs,
                 ))
@@ -359,16 +1234,18 @@ impl PlaylistEditor {
             Message::SavePlaylistAsClicked => Task::done(Message::ValidateNewPlaylistName.into()),
             Message::ValidateNewPlaylistName => {
                 debug!("Validuji nové jméno pro playlist");
-                if editor.new_playlist_name.trim().is_empty() {
-                    return Task::done(
-                        Message::InvalidNewPlaylistName(String::from("Prázdné jméno není validní"))
-                            .into(),
-                    );
-                }
+                let name =
+                    match pick_playlist::validate_new_playlist_name(&editor.new_playlist_name) {
+                        Ok(name) => name.to_string(),
+                        Err(e) => {
+                            return Task::done(
+                                Message::InvalidNewPlaylistName(e.to_string()).into(),
+                            );
+                        }
+                    };
 
                 debug!("Zjišťuji, jestli se v databázi nachází playlist s daným názvem");
                 let conn = state.db.acquire();
-                let name = editor.new_playlist_name.clone();
                 Task::perform(
                     async move {
                         let conn = conn.await.context("Nelze získat připojení k databázi")?;
@@ -398,6 +1275,27 @@ impl PlaylistEditor {
             Message::TopButtonsSongs => {
                 todo!("Ještě neumím editovat písně")
             }
+            Message::TopButtonsSettings => {
+                debug!("Ukládám playlist a přecházím do nastavení");
+                let conn = state.db.acquire();
+                let playlist = editor.playlist.clone();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
+                        let mut playlist = playlist.lock().await;
+                        playlist.save(&mut conn).await
+                    },
+                    |res| match res {
+                        Ok(_) => Message::OpenSettings.into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::OpenSettings => {
+                debug!("Přecházím do nastavení");
+                state.screen = Screen::Settings(SettingsEditor::new(&state.config.settings));
+                Task::done(crate::settings::Message::LoadTranslations.into())
+            }
             Message::NewPlaylistNameChanged(input) => {
                 trace!("Změnil se nový název playlistu: {input}");
                 editor.new_playlist_name = input;
@@ -421,10 +1319,66 @@ impl PlaylistEditor {
                     },
                     |res| match res {
                         Ok(_) => Message::ReturnToPlaylistPicker.into(),
+                        Err(e) if e.is_conflict() => {
+                            Message::DeletePlaylistFailed(e.to_string()).into()
+                        }
                         Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
                     },
                 )
             }
+            Message::DeletePlaylistFailed(err_msg) => {
+                debug!("Playlist nelze smazat: {err_msg}");
+                editor.new_playlist_err_msg = err_msg;
+                Task::none()
+            }
+            Message::ExportPlaylist => {
+                let playlist = editor.get_playlist();
+                debug!("Exportuji playlist \"{}\" do balíčku", playlist.get_name());
+                let db = state.db.clone();
+                Task::perform(
+                    async move {
+                        let Some(file) = rfd::AsyncFileDialog::new()
+                            .add_filter("Balíček playlistu", &[playlist::bundle::BUNDLE_EXTENSION])
+                            .set_file_name(format!(
+                                "{}.{}",
+                                playlist.get_name(),
+                                playlist::bundle::BUNDLE_EXTENSION
+                            ))
+                            .save_file()
+                            .await
+                        else {
+                            return Ok(None);
+                        };
+
+                        let mut conn = db
+                            .acquire()
+                            .await
+                            .context("Nelze získat připojení k databázi")?;
+                        playlist.export_bundle(file.path(), &mut conn).await?;
+
+                        Ok(Some(file.path().display().to_string()))
+                    },
+                    |res: anyhow::Result<Option<String>>| match res {
+                        Ok(Some(path)) => Message::PlaylistExported(path).into(),
+                        Ok(None) => Message::ExportDialogCancelled.into(),
+                        Err(e) => Message::ExportFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::PlaylistExported(path) => {
+                debug!("Playlist exportován do {path}");
+                editor.export_msg = Ok(format!("Playlist uložen do {path}"));
+                Task::none()
+            }
+            Message::ExportFailed(err_msg) => {
+                debug!("Export playlistu selhal: {err_msg}");
+                editor.export_msg = Err(format!("Playlist nelze exportovat: {err_msg}"));
+                Task::none()
+            }
+            Message::ExportDialogCancelled => {
+                debug!("Dialog pro export playlistu zrušen");
+                Task::none()
+            }
             Message::ReturnToPlaylistPicker => {
                 state.screen = Screen::PickPlaylist(PlaylistPicker::default());
                 Task::done(crate::Message::PlaylistPicker(
@@ -477,7 +1431,9 @@ impl PlaylistEditor {
                 Task::perform(
                     async move {
                         let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
-                        Song::get_available_from_db(&mut conn).await
+                        let songs = Song::get_available_from_db(&mut conn).await?;
+                        // Čísla ve zpěvnících se zobrazí u písní v playlistu
+                        append_songbook_numbers(songs, &mut conn).await
                     },
                     |res| res,
                 )
@@ -485,6 +1441,206 @@ impl PlaylistEditor {
                     Ok(cache) => Task::done(Message::SongNameCacheLoaded(cache).into()),
                     Err(e) => Task::done(crate::Message::FatalErrorOccured(format!("{:?}", e))),
                 })
+                // Do editoru se vždy vstupuje přes načtení cache, motivy načteme zároveň s ní
+                .chain(Task::done(Message::LoadThemes.into()))
+                .chain(Task::done(Message::LoadKnownPeople.into()))
+                .chain(Task::done(Message::LoadLicenseWarnings.into()))
+                .chain(Task::done(Message::LoadMedia.into()))
+                .chain(Task::done(Message::LoadBlocks.into()))
+            }
+            Message::LoadThemes => {
+                debug!("Načítám motivy slajdů");
+                let conn = state.db.acquire();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
+                        theme::get_available(&mut conn).await
+                    },
+                    |res| match res {
+                        Ok(themes) => Message::ThemesLoaded(themes).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::ThemesLoaded(themes) => {
+                debug!("Načteny motivy slajdů: {:?}", themes);
+                editor.theme_options = ThemeOption::options(&themes);
+                Task::none()
+            }
+            Message::LoadMedia => {
+                debug!("Načítám obrázky z úložiště médií");
+                let conn = state.db.acquire();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
+                        media::get_available(&mut conn).await
+                    },
+                    |res| match res {
+                        Ok(media) => Message::MediaLoaded(media).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::MediaLoaded(media) => {
+                debug!("Načteno {} obrázků", media.len());
+                editor.media_options = Some(media.iter().map(MediaOption::from).collect());
+                Task::none()
+            }
+            Message::LoadBlocks => {
+                debug!("Načítám playlisty, které lze vložit jako blok");
+                let id = match editor.playlist.blocking_lock().get_status() {
+                    PlaylistMetadataStatus::Transient => None,
+                    PlaylistMetadataStatus::Clean(id) | PlaylistMetadataStatus::Dirty(id) => {
+                        Some(id)
+                    }
+                };
+                let conn = state.db.acquire();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
+                        Ok::<_, anyhow::Error>(playlist::get_block_options(id, &mut conn).await?)
+                    },
+                    |res| match res {
+                        Ok(blocks) => Message::BlocksLoaded(blocks).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::BlocksLoaded(blocks) => {
+                debug!("Načteno {} playlistů pro vložení jako blok", blocks.len());
+                editor.block_options = Some(blocks.into_iter().map(BlockOption::from).collect());
+                Task::none()
+            }
+            Message::LoadKnownPeople => {
+                debug!("Načítám jména lidí pro doplňování rolí");
+                let conn = state.db.acquire();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
+                        playlist::get_known_people(&mut conn).await
+                    },
+                    |res| match res {
+                        Ok(people) => Message::KnownPeopleLoaded(people).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::KnownPeopleLoaded(people) => {
+                debug!("Načteno {} jmen lidí pro doplňování rolí", people.len());
+                editor.known_people = people;
+                Task::none()
+            }
+            Message::LoadLicenseWarnings => {
+                if !state.config.settings.licensing_mode {
+                    editor.license_warnings = None;
+                    return Task::none();
+                }
+
+                debug!("Načítám varování k licencím písní");
+                let conn = state.db.acquire();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
+                        license::get_license_warnings(&mut conn).await
+                    },
+                    |res| match res {
+                        Ok(warnings) => Message::LicenseWarningsLoaded(warnings).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::LicenseWarningsLoaded(warnings) => {
+                debug!("Načtena varování k licencím {} písní", warnings.len());
+                editor.license_warnings = Some(warnings);
+                Task::none()
+            }
+            Message::SongLicensedToggled(song_id, licensed) => {
+                debug!("Označuji píseň s id {song_id} jako licencovanou: {licensed}");
+                let db = state.db.clone();
+                Task::perform(
+                    async move { license::set_licensed(song_id, licensed, &db).await },
+                    |res| match res {
+                        Ok(_) => Message::LoadLicenseWarnings.into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::RoleChanged(role, person) => {
+                trace!("Měním jméno v roli {role} na {person}");
+                editor
+                    .playlist
+                    .blocking_lock()
+                    .set_role(role, Some(&person))
+                    .expect("Role je ze seznamu rolí");
+                editor.role_inputs.insert(role, person);
+                editor.edited_role = Some(role);
+                Task::none()
+            }
+            Message::RoleSuggestionPicked(role, person) => {
+                debug!("Doplňuji do role {role} jméno {person}");
+                editor
+                    .playlist
+                    .blocking_lock()
+                    .set_role(role, Some(&person))
+                    .expect("Role je ze seznamu rolí");
+                editor.role_inputs.insert(role, person);
+                // Po doplnění už další návrhy nezobrazujeme
+                editor.edited_role = None;
+                Task::none()
+            }
+            Message::PlaylistThemePicked(option) => {
+                debug!("Nastavuji playlistu motiv {}", option);
+                editor.playlist.blocking_lock().set_theme_id(option.id);
+                Task::none()
+            }
+            Message::SongThemeLoaded(theme_id) => {
+                debug!("Načten motiv vybrané písně: {:?}", theme_id);
+                editor.selected_song_theme_id = theme_id;
+                Task::none()
+            }
+            Message::SongThemePicked(song_id, option) => {
+                debug!("Nastavuji písni s id {song_id} motiv {}", option);
+                // Motiv patří písni, ne playlistu, ukládá se tedy rovnou do databáze
+                let conn = state.db.acquire();
+                let theme_id = option.id;
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
+                        theme::set_song_theme_id(song_id, theme_id, &mut conn).await
+                    },
+                    move |res| match res {
+                        Ok(_) => Message::SongThemeLoaded(theme_id).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::SongTranslationLoaded(translation_id) => {
+                debug!("Načten překlad vybrané písně: {:?}", translation_id);
+                editor.selected_song_translation_id = translation_id;
+                Task::none()
+            }
+            Message::SongTranslationPicked(song_id, option) => {
+                debug!("Nastavuji písni s id {song_id} překlad {}", option);
+                // Překlad patří písni, ne playlistu, ukládá se tedy rovnou do databáze
+                let conn = state.db.acquire();
+                let translation_id = option.id;
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
+                        Song::set_translation_id(song_id, translation_id, &mut conn).await
+                    },
+                    move |res| match res {
+                        Ok(_) => Message::SongTranslationLoaded(translation_id).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::SelectedSongLoaded(song_id, song) => {
+                if editor.is_song_selected(song_id) {
+                    debug!("Načtena vybraná píseň {}", song.title);
+                    editor.selected_song = Some(song);
+                }
+                Task::none()
             }
             Message::SongNameCacheLoaded(items) => {
                 debug!("Načtena cache názvů písní");
@@ -494,7 +1650,94 @@ impl PlaylistEditor {
             Message::SelectItem(index) => {
                 debug!("Vybrána položka playlistu {index}");
                 editor.selected_index = Some(index);
-                Task::none()
+                editor.selected_song_theme_id = None;
+                editor.selected_song_translation_id = None;
+                editor.selected_song = None;
+                editor.song_arrangements.clear();
+                editor.arrangement_msg.clear();
+
+                let item = editor
+                    .playlist
+                    .blocking_lock()
+                    .get_item(index)
+                    .cloned()
+                    .expect("Položka je vybraná");
+                match item {
+                    playlist::PlaylistItemMetadata::Song(song_id) => {
+                        editor.arrangement_input = editor
+                            .playlist
+                            .blocking_lock()
+                            .get_arrangement(index)
+                            .map(|arrangement| arrangement.join(" "))
+                            .unwrap_or_default();
+
+                        let conn = state.db.acquire();
+                        let load_theme = Task::perform(
+                            async move {
+                                let mut conn =
+                                    conn.await.context("Nelze získat připojení k databázi")?;
+                                theme::get_song_theme_id(song_id, &mut conn).await
+                            },
+                            |res| match res {
+                                Ok(theme_id) => Message::SongThemeLoaded(theme_id).into(),
+                                Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                            },
+                        );
+
+                        let conn = state.db.acquire();
+                        let load_song = Task::perform(
+                            async move {
+                                let mut conn =
+                                    conn.await.context("Nelze získat připojení k databázi")?;
+                                Song::load_from_db(song_id, &mut conn).await
+                            },
+                            move |res| match res {
+                                Ok(song) => Message::SelectedSongLoaded(song_id, song).into(),
+                                Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                            },
+                        );
+
+                        let conn = state.db.acquire();
+                        let load_translation = Task::perform(
+                            async move {
+                                let mut conn =
+                                    conn.await.context("Nelze získat připojení k databázi")?;
+                                Song::get_translation_id(song_id, &mut conn).await
+                            },
+                            |res| match res {
+                                Ok(translation_id) => {
+                                    Message::SongTranslationLoaded(translation_id).into()
+                                }
+                                Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                            },
+                        );
+
+                        // Bez pojmenovaných pořadí lze pořadí stále zadat ručně
+                        let conn = state.db.acquire();
+                        let load_arrangements = Task::perform(
+                            async move {
+                                let mut conn =
+                                    conn.await.context("Nelze získat připojení k databázi")?;
+                                SongArrangement::load_for_song(song_id, &mut conn).await
+                            },
+                            move |res| match res {
+                                Ok(arrangements) => {
+                                    Message::SongArrangementsLoaded(song_id, arrangements).into()
+                                }
+                                Err(e) => Message::SongArrangementFailed(format!("{:#}", e)).into(),
+                            },
+                        );
+
+                        Task::batch([load_theme, load_song, load_translation, load_arrangements])
+                    }
+                    playlist::PlaylistItemMetadata::BiblePassage { .. } => Task::none(),
+                    playlist::PlaylistItemMetadata::CustomText { body, .. } => {
+                        editor.custom_text_body = text_editor::Content::with_text(&body);
+                        Task::none()
+                    }
+                    playlist::PlaylistItemMetadata::Image(_)
+                    | playlist::PlaylistItemMetadata::Block(_) => Task::none(),
+                }
             }
             Message::MoveItemUp(index) => {
                 debug!("Posunuji položku na indexu {index} na {}", index - 1);
@@ -527,6 +1770,251 @@ impl PlaylistEditor {
                 })
                 .discard()
             }
+            Message::SongKeyPicked(index, key) => {
+                debug!("Nastavuji tóninu {key} písni s indexem {index}");
+                let playlist = editor.playlist.clone();
+                Task::future(async move {
+                    let mut playlist = playlist.lock().await;
+                    playlist
+                        .set_song_key(index, Some(key))
+                        .expect("Tónina je vybrána ze seznamu tónin a položka je píseň");
+                })
+                .discard()
+            }
+            Message::ClearSongKey(index) => {
+                debug!("Ruším tóninu písně s indexem {index}");
+                let playlist = editor.playlist.clone();
+                Task::future(async move {
+                    let mut playlist = playlist.lock().await;
+                    playlist
+                        .set_song_key(index, None)
+                        .expect("Tóninu lze zrušit pouze u písně");
+                })
+                .discard()
+            }
+            Message::ArrangementChanged(index, input) => {
+                trace!("Měním vlastní pořadí písně s indexem {index} na {input}");
+                let arrangement: Vec<PartTag> =
+                    input.split_whitespace().map(str::to_string).collect();
+                editor
+                    .playlist
+                    .blocking_lock()
+                    .set_arrangement(index, (!arrangement.is_empty()).then_some(arrangement))
+                    .expect("Tagy jsou neprázdné bez mezer a položka je píseň");
+                editor.arrangement_input = input;
+                Task::none()
+            }
+            Message::ResetArrangement(index) => {
+                debug!("Ruším vlastní pořadí písně s indexem {index}");
+                editor.arrangement_input.clear();
+                editor
+                    .playlist
+                    .blocking_lock()
+                    .set_arrangement(index, None)
+                    .expect("Pořadí lze zrušit pouze u písně");
+                Task::none()
+            }
+            Message::SongArrangementsLoaded(song_id, arrangements) => {
+                if editor.is_song_selected(song_id) {
+                    debug!("Načteno {} pořadí vybrané písně", arrangements.len());
+                    editor.song_arrangements = arrangements;
+                }
+                Task::none()
+            }
+            Message::SongArrangementApplied(index, order) => {
+                debug!("Nastavuji písni s indexem {index} pořadí {order:?}");
+                editor.arrangement_input = order.join(" ");
+                editor
+                    .playlist
+                    .blocking_lock()
+                    .set_arrangement(index, Some(order))
+                    .expect("Uložené pořadí je neprázdné a položka je píseň");
+                Task::none()
+            }
+            Message::ArrangementNameChanged(name) => {
+                editor.arrangement_name_input = name;
+                Task::none()
+            }
+            Message::SaveSongArrangement(song_id, index) => {
+                let order = match editor.playlist.blocking_lock().get_arrangement(index) {
+                    Some(order) => order.to_vec(),
+                    None => match &editor.selected_song {
+                        Some(song) => song.order.clone(),
+                        None => return Task::none(),
+                    },
+                };
+                let arrangement = SongArrangement {
+                    name: editor.arrangement_name_input.trim().to_string(),
+                    order,
+                };
+                debug!("Ukládám pořadí {arrangement} k písni s id {song_id}");
+                editor.arrangement_msg.clear();
+                editor.arrangement_name_input.clear();
+                let db = state.db.clone();
+                Task::perform(
+                    async move {
+                        arrangement.save_to_db(song_id, &db).await?;
+                        let mut conn = db
+                            .acquire()
+                            .await
+                            .context("Nelze získat připojení k databázi")?;
+                        SongArrangement::load_for_song(song_id, &mut conn).await
+                    },
+                    move |res| match res {
+                        Ok(arrangements) => {
+                            Message::SongArrangementsLoaded(song_id, arrangements).into()
+                        }
+                        Err(e) => Message::SongArrangementFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::DeleteSongArrangement(song_id, name) => {
+                debug!("Mažu pořadí {name} písně s id {song_id}");
+                editor.arrangement_msg.clear();
+                let db = state.db.clone();
+                Task::perform(
+                    async move {
+                        SongArrangement::delete_from_db(song_id, &name, &db).await?;
+                        let mut conn = db
+                            .acquire()
+                            .await
+                            .context("Nelze získat připojení k databázi")?;
+                        SongArrangement::load_for_song(song_id, &mut conn).await
+                    },
+                    move |res| match res {
+                        Ok(arrangements) => {
+                            Message::SongArrangementsLoaded(song_id, arrangements).into()
+                        }
+                        Err(e) => Message::SongArrangementFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::SongArrangementFailed(err) => {
+                warn!("Chyba při práci s pořadími písně: {err}");
+                editor.arrangement_msg = err;
+                Task::none()
+            }
+            Message::AddCustomText => {
+                debug!("Přidávám do playlistu vlastní text");
+                let mut playlist = editor.playlist.blocking_lock();
+                playlist.push_custom_text(NEW_CUSTOM_TEXT_TITLE, "");
+                let index = playlist.get_items().len() - 1;
+                Task::done(Message::SelectItem(index).into())
+            }
+            Message::AddImage(option) => {
+                debug!("Přidávám do playlistu obrázek {}", option.name);
+                let mut playlist = editor.playlist.blocking_lock();
+                playlist.push_image(option.id);
+                let index = playlist.get_items().len() - 1;
+                Task::done(Message::SelectItem(index).into())
+            }
+            Message::AddBlock(option) => {
+                debug!("Přidávám do playlistu blok {}", option.name);
+                let mut playlist = editor.playlist.blocking_lock();
+                playlist.push_block(option.id);
+                let index = playlist.get_items().len() - 1;
+                Task::done(Message::SelectItem(index).into())
+            }
+            Message::PluginQueryChanged(query) => {
+                trace!("Dotaz pro pluginy změněn na {query}");
+                editor.plugin_query = query;
+                Task::none()
+            }
+            Message::AddPluginItems(index) => {
+                let Some(plugin) = state.plugins.get(index) else {
+                    warn!("Plugin s indexem {index} neexistuje");
+                    return Task::none();
+                };
+                debug!(
+                    "Načítám položky z pluginu {} pro dotaz \"{}\"",
+                    plugin.manifest().name,
+                    editor.plugin_query
+                );
+                let query = editor.plugin_query.clone();
+                Task::perform(
+                    tokio::task::spawn_blocking(move || plugin.items(&query)),
+                    |res| match res.context("Plugin při načítání položek spadl") {
+                        Ok(Ok(items)) => Message::PluginItemsLoaded(items).into(),
+                        Ok(Err(e)) | Err(e) => {
+                            Message::PluginItemsFailed(format!("{:#}", e)).into()
+                        }
+                    },
+                )
+            }
+            Message::PluginItemsLoaded(items) => {
+                debug!("Přidávám do playlistu {} textů z pluginu", items.len());
+                editor.plugin_err_msg.clear();
+                let mut playlist = editor.playlist.blocking_lock();
+                for item in &items {
+                    playlist.push_custom_text(&item.title, &item.body);
+                }
+                Task::none()
+            }
+            Message::PluginItemsFailed(err_msg) => {
+                debug!("Načtení položek z pluginu selhalo: {err_msg}");
+                editor.plugin_err_msg = format!("Položky nelze načíst: {err_msg}");
+                Task::none()
+            }
+            Message::LightingCueChanged(index, input) => {
+                trace!("Měním světelné cue položky s indexem {index} na {input}");
+                // Nevalidní vstup (nečíslo, 0, více než 255) ignorujeme, prázdný cue zruší
+                let cue = match input.trim() {
+                    "" => None,
+                    input => match input.parse() {
+                        Ok(cue) if cue > 0 => Some(cue),
+                        _ => return Task::none(),
+                    },
+                };
+                editor
+                    .playlist
+                    .blocking_lock()
+                    .set_lighting_cue(index, cue)
+                    .expect("Cue je nenulové a položka je vybraná");
+                Task::none()
+            }
+            Message::ItemEnabledToggled(index, enabled) => {
+                trace!("Nastavuji promítání položky s indexem {index} na {enabled}");
+                editor
+                    .playlist
+                    .blocking_lock()
+                    .set_item_enabled(index, enabled)
+                    .expect("Položka je vybraná");
+                Task::none()
+            }
+            Message::CustomTextTitleChanged(index, title) => {
+                trace!("Měním název textu s indexem {index} na {title}");
+                let mut playlist = editor.playlist.blocking_lock();
+                let body = match playlist.get_item(index) {
+                    Some(playlist::PlaylistItemMetadata::CustomText { body, .. }) => body.clone(),
+                    _ => panic!("Název lze měnit pouze u textu"),
+                };
+                playlist
+                    .replace_custom_text(index, &title, &body)
+                    .expect("Položka je text");
+                Task::none()
+            }
+            Message::CustomTextBodyEdited(index, action) => {
+                let is_edit = action.is_edit();
+                editor.custom_text_body.perform(action);
+                if is_edit {
+                    trace!("Upravuji obsah textu s indexem {index}");
+                    let mut playlist = editor.playlist.blocking_lock();
+                    let title = match playlist.get_item(index) {
+                        Some(playlist::PlaylistItemMetadata::CustomText { title, .. }) => {
+                            title.clone()
+                        }
+                        _ => panic!("Obsah lze upravovat pouze u textu"),
+                    };
+                    playlist
+                        .replace_custom_text(
+                            index,
+                            &title,
+                            editor.custom_text_body.text().trim_end(),
+                        )
+                        .expect("Položka je text");
+                }
+                Task::none()
+            }
             Message::DeleteItem(index) => {
                 debug!("Mažu položku s indexem {index}");
                 editor.selected_index = None;
@@ -0,0 +1,420 @@
+//! Rozšíření Ekklesu (pluginy) v podobě dynamických knihoven, aby okrajové integrace
+//! (přepínač kamer, lekcionáře jednotlivých církví, ...) nemusely být v jádru programu.
+//!
+//! Pluginy se načítají při startu ze složky [`crate::config::Config::plugins_path`]
+//! (soubory s příponou dynamické knihovny dané platformy). V offline režimu se
+//! nenačítají vůbec, nelze totiž zaručit, že nepřistupují k síti (viz [`crate::network`]).
+//! Plugin běží v procesu Ekklesu se všemi jeho právy, instalovat je potřeba pouze
+//! důvěryhodné pluginy.
+//!
+//! ### ABI
+//! Plugin exportuje následující funkce s C ABI, veškerá data se předávají jako
+//! nulou ukončené řetězce v UTF-8 obsahující JSON:
+//! - `uint32_t ekkles_plugin_abi_version(void)` vrací [`PLUGIN_ABI_VERSION`]
+//! - `const char *ekkles_plugin_manifest(void)` vrací [`PluginManifest`], řetězec vlastní
+//!   plugin a musí být platný po celou dobu běhu programu
+//! - `char *ekkles_plugin_call(const char *hook, const char *input)` zavolá háček `hook`
+//!   se vstupem `input`, vrací `{"ok": výstup}` nebo `{"error": "popis chyby"}`
+//! - `void ekkles_plugin_free(char *output)` uvolní výstup `ekkles_plugin_call`
+//!
+//! Háčky se volají mimo vlákno GUI, i souběžně, plugin tedy musí být thread-safe.
+//!
+//! ### Háčky
+//! - [`HOOK_IMPORT_SONGS`]: importér písní, vstup `{"path": "..."}`, výstup seznam písní
+//!   (viz [`PluginSong`])
+//! - [`HOOK_ITEMS`]: zdroj položek playlistu, vstup `{"query": "..."}` (např. datum),
+//!   výstup seznam textů `[{"title": "...", "body": "..."}]`, do playlistu se vloží jako
+//!   vlastní texty, takže jej lze promítnout i bez pluginu
+//! - [`HOOK_SLIDE_PRESENTED`]: výstup prezentace, dostává každý promítnutý slajd
+//!   (viz [`PresentedSlide`]), výstup se ignoruje
+
+use std::{
+    collections::HashMap,
+    env,
+    ffi::{CStr, CString, c_char},
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use ekkles_data::{Song, SongMetadata};
+use libloading::Library;
+use log::{info, warn};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+/// Verze ABI pluginů, plugin s jinou verzí se nenačte
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+/// Háček importéru písní
+pub const HOOK_IMPORT_SONGS: &str = "import_songs";
+/// Háček zdroje položek playlistu
+pub const HOOK_ITEMS: &str = "items";
+/// Háček výstupu prezentace
+pub const HOOK_SLIDE_PRESENTED: &str = "slide_presented";
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type ManifestFn = unsafe extern "C" fn() -> *const c_char;
+type CallFn = unsafe extern "C" fn(*const c_char, *const c_char) -> *mut c_char;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+/// Popis pluginu, podle kterého se nabízí v GUI
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    /// Importér písní, pokud jej plugin nabízí
+    #[serde(default)]
+    pub importer: Option<ImporterManifest>,
+    /// Popisek zdroje položek playlistu (např. "Lekcionář"), pokud jej plugin nabízí
+    #[serde(default)]
+    pub item_source: Option<String>,
+    /// Zda plugin chce dostávat promítané slajdy
+    #[serde(default)]
+    pub presenter_output: bool,
+}
+
+/// Importér písní z pluginu
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ImporterManifest {
+    /// Popisek importéru (např. "Zpěvník XY")
+    pub label: String,
+    /// Přípony importovaných souborů (bez tečky)
+    pub extensions: Vec<String>,
+}
+
+/// Píseň vrácená importérem z pluginu
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PluginSong {
+    pub title: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Části písně (tag -> slova)
+    pub parts: HashMap<String, String>,
+    /// Pořadí částí, každý tag musí být v `parts`
+    pub order: Vec<String>,
+}
+
+impl TryFrom<PluginSong> for Song {
+    type Error = anyhow::Error;
+
+    fn try_from(value: PluginSong) -> Result<Self> {
+        if let Some(tag) = value
+            .order
+            .iter()
+            .find(|tag| !value.parts.contains_key(*tag))
+        {
+            bail!(
+                "Píseň \"{}\" má v pořadí část \"{tag}\", kterou neobsahuje",
+                value.title
+            );
+        }
+
+        Ok(Song {
+            title: value.title,
+            author: value.author,
+            parts: value.parts,
+            order: value.order,
+            metadata: SongMetadata::default(),
+        })
+    }
+}
+
+/// Text vrácený zdrojem položek playlistu, vloží se jako vlastní text
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PluginItem {
+    pub title: String,
+    pub body: String,
+}
+
+/// Promítnutý slajd předávaný výstupům prezentace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PresentedSlide {
+    /// Index slajdu v prezentaci (od 0)
+    pub slide_index: usize,
+    /// Index položky playlistu, ze které slajd vznikl
+    pub item_index: usize,
+    /// Režim prezentace (`normal`, `blank`, `frozen`, `quick_slide`, `passage`, `countdown`)
+    pub mode: &'static str,
+    /// Promítaný text (u obrázků prázdný)
+    pub text: String,
+    /// Patička slajdu (název písně, rozsah pasáže)
+    pub footer: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ImportInput<'a> {
+    path: &'a Path,
+}
+
+#[derive(Debug, Serialize)]
+struct ItemsInput<'a> {
+    query: &'a str,
+}
+
+/// Odpověď pluginu na zavolání háčku
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PluginResponse<T> {
+    Ok(T),
+    Error(String),
+}
+
+/// Načtený plugin
+pub struct Plugin {
+    manifest: PluginManifest,
+    path: PathBuf,
+    call: CallFn,
+    free: FreeFn,
+    /// Knihovna musí žít, dokud se používají ukazatele na její funkce
+    _library: Library,
+}
+
+impl fmt::Debug for Plugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Plugin")
+            .field("manifest", &self.manifest)
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl Plugin {
+    /// Načte plugin z dynamické knihovny na cestě `path`. Pokud knihovna neexportuje
+    /// všechny funkce ABI, má jinou verzi ABI nebo nevalidní manifest, vrací Error.
+    fn load(path: &Path) -> Result<Self> {
+        // SAFETY: Načtení knihovny spouští její inicializační kód, pluginy ve složce
+        // s pluginy jsou považovány za důvěryhodné (viz dokumentace modulu)
+        let library = unsafe { Library::new(path) }
+            .with_context(|| format!("Nelze načíst knihovnu {}", path.display()))?;
+
+        // SAFETY: Typy funkcí odpovídají ABI popsanému v dokumentaci modulu, ukazatele
+        // na funkce se nepoužijí déle, než žije `library` (je součástí `Plugin`)
+        let (abi_version, manifest, call, free) = unsafe {
+            (
+                *library.get::<AbiVersionFn>(b"ekkles_plugin_abi_version\0")?,
+                *library.get::<ManifestFn>(b"ekkles_plugin_manifest\0")?,
+                *library.get::<CallFn>(b"ekkles_plugin_call\0")?,
+                *library.get::<FreeFn>(b"ekkles_plugin_free\0")?,
+            )
+        };
+
+        // SAFETY: Funkce ABI nemají žádné předpoklady
+        let version = unsafe { abi_version() };
+        if version != PLUGIN_ABI_VERSION {
+            bail!("Plugin má verzi ABI {version}, podporovaná je {PLUGIN_ABI_VERSION}");
+        }
+
+        // SAFETY: Viz výše
+        let manifest = unsafe { manifest() };
+        if manifest.is_null() {
+            bail!("Plugin nevrátil manifest");
+        }
+        // SAFETY: Manifest je podle ABI nulou ukončený řetězec platný po celou dobu běhu
+        let manifest = unsafe { CStr::from_ptr(manifest) }
+            .to_str()
+            .context("Manifest pluginu není v UTF-8")?;
+        let manifest = serde_json::from_str(manifest).context("Nevalidní manifest pluginu")?;
+
+        Ok(Self {
+            manifest,
+            path: path.to_path_buf(),
+            call,
+            free,
+            _library: library,
+        })
+    }
+
+    pub fn manifest(&self) -> &PluginManifest {
+        &self.manifest
+    }
+
+    /// Zavolá háček `hook` se vstupem `input` a vrátí jeho výstup. Pokud plugin vrátí
+    /// chybu nebo výstup není validní, vrací Error. Plugin může volání zdržet, proto
+    /// by se háčky neměly volat z vlákna GUI.
+    fn call<I: Serialize, O: DeserializeOwned>(&self, hook: &str, input: &I) -> Result<O> {
+        let hook_name = hook;
+        let hook = CString::new(hook)?;
+        let input = CString::new(serde_json::to_string(input)?)
+            .context("Vstup pluginu obsahuje nulový znak")?;
+
+        // SAFETY: Oba argumenty jsou nulou ukončené řetězce platné po dobu volání
+        let output = unsafe { (self.call)(hook.as_ptr(), input.as_ptr()) };
+        if output.is_null() {
+            bail!(
+                "Plugin {} nevrátil odpověď na háček {hook_name}",
+                self.manifest.name
+            );
+        }
+
+        // SAFETY: Výstup je podle ABI nulou ukončený řetězec, který po zkopírování
+        // uvolníme funkcí pluginu a dál jej nepoužijeme
+        let output = unsafe {
+            let json = CStr::from_ptr(output).to_string_lossy().into_owned();
+            (self.free)(output);
+            json
+        };
+
+        let response: PluginResponse<O> = serde_json::from_str(&output).with_context(|| {
+            format!(
+                "Nevalidní odpověď pluginu {} na háček {hook_name}",
+                self.manifest.name
+            )
+        })?;
+
+        match response {
+            PluginResponse::Ok(output) => Ok(output),
+            PluginResponse::Error(e) => Err(anyhow!("Plugin {}: {e}", self.manifest.name)),
+        }
+    }
+
+    /// Naimportuje písně ze souboru `path` importérem pluginu
+    pub fn import_songs(&self, path: &Path) -> Result<Vec<Song>> {
+        let songs: Vec<PluginSong> = self.call(HOOK_IMPORT_SONGS, &ImportInput { path })?;
+        songs.into_iter().map(Song::try_from).collect()
+    }
+
+    /// Vrátí texty ze zdroje položek pluginu pro dotaz `query`
+    pub fn items(&self, query: &str) -> Result<Vec<PluginItem>> {
+        self.call(HOOK_ITEMS, &ItemsInput { query })
+    }
+
+    /// Předá pluginu promítnutý slajd
+    pub fn slide_presented(&self, slide: &PresentedSlide) -> Result<()> {
+        self.call::<_, serde::de::IgnoredAny>(HOOK_SLIDE_PRESENTED, slide)
+            .map(|_| ())
+    }
+}
+
+/// Všechny načtené pluginy, indexy pluginů jsou stabilní po celou dobu běhu programu
+#[derive(Debug, Clone, Default)]
+pub struct Plugins {
+    plugins: Vec<Arc<Plugin>>,
+}
+
+impl Plugins {
+    /// Načte všechny pluginy ze složky `dir`, pluginy, které nelze načíst, přeskočí
+    /// (s varováním v logu). Pokud složka neexistuje, nenačte žádné.
+    pub fn load_dir(dir: &Path) -> Self {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                info!("Složku s pluginy {} nelze přečíst: {e}", dir.display());
+                return Self::default();
+            }
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == env::consts::DLL_EXTENSION)
+            })
+            .collect();
+        // Pořadí pluginů (a tím i v GUI) nezávisí na pořadí souborů v adresáři
+        paths.sort();
+
+        let plugins = paths
+            .into_iter()
+            .filter_map(|path| match Plugin::load(&path) {
+                Ok(plugin) => {
+                    info!(
+                        "Načten plugin {} z {}",
+                        plugin.manifest.name,
+                        path.display()
+                    );
+                    Some(Arc::new(plugin))
+                }
+                Err(e) => {
+                    warn!("Nelze načíst plugin {}: {:?}", path.display(), e);
+                    None
+                }
+            })
+            .collect();
+
+        Self { plugins }
+    }
+
+    /// Vrátí plugin na indexu `index`
+    pub fn get(&self, index: usize) -> Option<Arc<Plugin>> {
+        self.plugins.get(index).cloned()
+    }
+
+    /// Importéry písní jako dvojice (index pluginu, importér)
+    pub fn importers(&self) -> impl Iterator<Item = (usize, &ImporterManifest)> {
+        self.plugins
+            .iter()
+            .enumerate()
+            .filter_map(|(index, plugin)| Some((index, plugin.manifest.importer.as_ref()?)))
+    }
+
+    /// Zdroje položek playlistu jako dvojice (index pluginu, popisek)
+    pub fn item_sources(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.plugins
+            .iter()
+            .enumerate()
+            .filter_map(|(index, plugin)| Some((index, plugin.manifest.item_source.as_deref()?)))
+    }
+
+    /// Pluginy, které dostávají promítané slajdy
+    pub fn presenter_outputs(&self) -> Vec<Arc<Plugin>> {
+        self.plugins
+            .iter()
+            .filter(|plugin| plugin.manifest.presenter_output)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn manifest_parsing() {
+        let manifest: PluginManifest = serde_json::from_str(
+            r#"{"name": "Lekcionář", "item_source": "Čtení na neděli", "presenter_output": true}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest,
+            PluginManifest {
+                name: String::from("Lekcionář"),
+                importer: None,
+                item_source: Some(String::from("Čtení na neděli")),
+                presenter_output: true,
+            }
+        );
+    }
+
+    #[test]
+    fn plugin_response_parsing() {
+        let ok: PluginResponse<Vec<PluginItem>> =
+            serde_json::from_str(r#"{"ok": [{"title": "1. čtení", "body": "Iz 9,1-6"}]}"#).unwrap();
+        assert!(matches!(ok, PluginResponse::Ok(items) if items.len() == 1));
+
+        let error: PluginResponse<Vec<PluginItem>> =
+            serde_json::from_str(r#"{"error": "Neznámé datum"}"#).unwrap();
+        assert!(matches!(error, PluginResponse::Error(e) if e == "Neznámé datum"));
+    }
+
+    #[test]
+    fn plugin_song_must_have_all_parts_in_order() {
+        let song = PluginSong {
+            title: String::from("Haleluja"),
+            author: None,
+            parts: HashMap::from([(String::from("V1"), String::from("Haleluja"))]),
+            order: vec![String::from("V1"), String::from("C")],
+        };
+
+        assert!(Song::try_from(song).is_err());
+    }
+
+    #[test]
+    fn missing_plugin_dir_loads_nothing() {
+        let plugins = Plugins::load_dir(Path::new("/neexistujici/slozka/s/pluginy"));
+
+        assert!(plugins.get(0).is_none());
+    }
+}
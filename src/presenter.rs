@@ -1,154 +1,71 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::{Context, Result, anyhow};
-use ekkles_data::playlist::PlaylistItem;
-use ekkles_data::{bible::indexing::VerseIndex, playlist::Playlist};
-use iced::keyboard::{Key, key};
-use iced::widget::button::danger;
-use iced::widget::{Space, button, column, container, radio, row, scrollable, slider, text};
-use iced::window::{Id, Settings};
-use iced::{Alignment, Color, Element, Length, Subscription, Task, Theme};
-use log::{debug, trace};
-use sqlx::Sqlite;
+use chrono::{Local, NaiveDateTime, NaiveTime, TimeDelta};
+use ekkles_data::Song;
+use ekkles_data::bible::get_available_translations;
+use ekkles_data::bible::indexing::{Book, Passage, VerseIndex, parse_numeric_reference};
+use ekkles_data::playlist::{ItemOrigin, Playlist, PlaylistMetadata};
+use ekkles_data::presentation_log::PresentationLog;
+use ekkles_data::slide_override::{self, SlideAlignment, SlideOverride};
+use ekkles_data::theme::{hex_to_rgb, rgb_to_hex};
+use iced::event::{self, Event};
+use iced::keyboard::{self, Key, Modifiers, key};
+use iced::widget::button::{danger, success};
+use iced::widget::{
+    Space, button, checkbox, column, container, pick_list, radio, row, scrollable, slider, text,
+    text_input,
+};
+use iced::window::{Id, Position, Screenshot, Settings};
+use iced::{Alignment, Element, Length, Point, Size, Subscription, Task};
+use log::{debug, trace, warn};
 use sqlx::pool::PoolConnection;
+use sqlx::{Sqlite, SqlitePool};
 
-use crate::components::playlist_item_styles;
+use crate::companion_image;
+use crate::components::{format_duration, playlist_item_styles};
+use crate::config::{
+    self, EventHooks, Keymap, LightingSettings, MacroStep, PresentationTheme, PresenterMacro,
+    QuickSlide, ServiceEndAction, ServiceEndSettings,
+};
+use crate::event_hooks::{self, PresentationEvent};
+use crate::network::{self, mqtt::MqttPublisher};
 use crate::pick_playlist::PlaylistPicker;
+use crate::plugins::{Plugins, PresentedSlide};
+use crate::service_summary::{MeasuredItem, ServiceSummary};
+use crate::sleep_inhibitor::SleepInhibitor;
+use crate::slide_export::{self, Resolution};
+use crate::slide_render::{
+    self, LanguageMode, MAX_VERSES_PER_SLIDE, Slide, SlideStyle, SongSlide,
+    TEXT_SIZE_MULTIPLIER_DEFAULT_U8, countdown_to_slide, default_slide_breaks,
+    normalize_text_multiplier, passage_to_slides, playlist_to_slides, quick_slide_to_slide,
+    rechunk_passages,
+};
+use crate::thumbnail_cache::{self, ThumbnailCache, thumbnail_key};
 use crate::{Ekkles, Screen};
 
-/// Počet veršů na jeden slajd, proteď konstanta
-const VERSES_PER_SLIDE: usize = 2;
-
-const TEXT_SIZE_MULTIPLIER_MIN: f32 = 0.5;
-const TEXT_SIZE_MULTIPLIER_MAX: f32 = 3.0;
-const TEXT_SIZE_MULTIPLIER_DEFAULT: f32 = 1.0;
-/// Jelikož [`iced::widget::slider()`] potřebuje range a range přes f32 hodnoty se nechová dobře,
-/// používám pro range u8 (0..=255) a pomocí [`normalize_text_multiplier`] range poté
-/// normalizuji. Tato default hodnota by se měla promítnout do [`TEXT_SIZE_MULTIPLIER_DEFAULT`].
-const TEXT_SIZE_MULTIPLIER_DEFAULT_U8: u8 = ((TEXT_SIZE_MULTIPLIER_DEFAULT
-    - TEXT_SIZE_MULTIPLIER_MIN)
-    / (TEXT_SIZE_MULTIPLIER_MAX - TEXT_SIZE_MULTIPLIER_MIN)
-    * u8::MAX as f32) as u8;
-
-/// Velikost textu pro hlavní obsah snímku
-const MAIN_TEXT_SIZE: f32 = 70.0;
-/// Velikost textu pro doplňující obsah snímku
-const ADDITIONAL_TEXT_SIZE: f32 = 30.0;
-
-// Poznámka: Musí to být malé písmena, jinak se nematchnou na keycode v subscription()
-const MODE_FREEZE_KEY: &str = "f";
-const MODE_NORMAL_KEY: &str = "n";
-const MODE_BLANK_KEY: &str = "b";
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum Slide {
-    Passage(PassageSlide),
-    Song(SongSlide),
-}
-
-impl Slide {
-    fn present(&self, text_size_multiplier: f32) -> Element<Message> {
-        match self {
-            Slide::Passage(passage_slide) => passage_slide.present(text_size_multiplier),
-            Slide::Song(song_slide) => song_slide.present(text_size_multiplier),
-        }
-    }
-}
-
-/// Jeden slajd při promítání pasáže
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct PassageSlide {
-    /// Název překladu, ze které je pasáž přebraná
-    translation_name: String,
-    /// Indexy celkové pasáže od-do
-    passage_indexes: (VerseIndex, VerseIndex),
-    /// Jednotlivé verše daného slajdu
-    verses: Vec<(u8, String)>,
-}
-
-impl PassageSlide {
-    fn new(
-        translation_name: String,
-        from: VerseIndex,
-        to: VerseIndex,
-        verses: Vec<(u8, String)>,
-    ) -> Self {
-        Self {
-            translation_name,
-            passage_indexes: (from, to),
-            verses,
-        }
-    }
-
-    fn present(&self, text_size_multiplier: f32) -> Element<Message> {
-        let verses_text_size = MAIN_TEXT_SIZE * text_size_multiplier;
-        let indexes_text_size = ADDITIONAL_TEXT_SIZE * text_size_multiplier;
-
-        let verses_text: String = self
-            .verses
-            .iter()
-            .map(|(number, content)| format!("{}: {}", number, content))
-            .collect();
-
-        let indexes_text = format!("{} - {}", self.passage_indexes.0, self.passage_indexes.1);
-
-        let verses = container(text(verses_text).size(verses_text_size)).center(Length::Fill);
-        let indexes = container(
-            text(indexes_text)
-                .align_x(Alignment::Center)
-                .size(indexes_text_size),
-        )
-        .center_x(Length::Fill)
-        .align_bottom(Length::Shrink);
-
-        container(column![verses, indexes])
-            .style(black_background)
-            .into()
-    }
-}
-
-/// Jeden slajd při promítání písně
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct SongSlide {
-    /// Název písně
-    title: String,
-    /// Název části písně
-    part_name: String,
-    /// Obsah dané části písně
-    content: String,
-}
-
-impl SongSlide {
-    fn new(title: String, part_name: String, content: String) -> Self {
-        Self {
-            title,
-            part_name,
-            content,
-        }
-    }
-
-    fn present(&self, text_size_multiplier: f32) -> Element<Message> {
-        let content_size = MAIN_TEXT_SIZE * text_size_multiplier;
-        let title_size = ADDITIONAL_TEXT_SIZE * text_size_multiplier;
-
-        let content = container(
-            text(&self.content)
-                .align_x(Alignment::Center)
-                .size(content_size),
-        )
-        .center(Length::Fill);
-
-        let title = container(
-            text(&self.title)
-                .align_x(Alignment::Center)
-                .size(title_size),
-        )
-        .center_x(Length::Fill)
-        .align_bottom(Length::Shrink);
-
-        container(column![content, title])
-            .style(black_background)
-            .into()
-    }
-}
+/// Za jak dlouho po přepnutí slajdu se pořídí snímek prezentačního okna pro náhled
+/// (okno se musí nejdříve překreslit)
+const THUMBNAIL_CAPTURE_DELAY: Duration = Duration::from_millis(300);
+/// Šířka náhledu slajdu v seznamu slajdů
+const THUMBNAIL_LIST_WIDTH: f32 = 120.0;
+/// Šířka čísla slajdu v seznamu slajdů
+const SLIDE_NUMBER_WIDTH: f32 = 40.0;
+/// Kolik nalezených slajdů se nejvýše zobrazí pod vyhledáváním slajdů
+const MAX_SEARCH_RESULTS: usize = 8;
+/// Velikost živého náhledu promítaného a následujícího slajdu v ovládacím okně
+const LIVE_PREVIEW_SIZE: Size = Size::new(320.0, 180.0);
+/// Jak často se přepočítává čas zbývající do konce bohoslužby
+const SERVICE_END_TICK: Duration = Duration::from_secs(1);
+/// Kolik před koncem bohoslužby se varování zobrazí nejvýrazněji
+const SERVICE_END_IMMINENT: TimeDelta = TimeDelta::minutes(2);
+/// Jak často se přepočítává odpočet před začátkem bohoslužby (častěji než jednou
+/// za sekundu, aby odpočet na slajdu nepřeskakoval sekundy)
+const COUNTDOWN_TICK: Duration = Duration::from_millis(250);
 
 /// Aby bylo možné globálně změnit prezentaci (začernit, zmrazit)
 #[derive(Debug, Clone, Copy)]
@@ -159,6 +76,13 @@ pub enum PresentationMode {
     Blank,
     /// Obrazovka zmražena na snímku s daným indexem
     Frozen(usize),
+    /// Promítá se rychlý slajd s daným indexem, viz [`QuickSlide`]
+    QuickSlide(usize),
+    /// Promítá se slajd s daným indexem pasáže zadané během prezentace, která není
+    /// v playlistu, viz [`Message::SubmitPassageEntry`]
+    Passage(usize),
+    /// Promítá se odpočet před začátkem bohoslužby, viz [`Message::StartCountdown`]
+    Countdown,
 }
 
 /// Ruční implementace [`PartialEq`] a [`Eq`], aby se v případě [`PresentationMode::Frozen`]
@@ -170,6 +94,9 @@ impl PartialEq for PresentationMode {
             (PresentationMode::Normal, PresentationMode::Normal) => true,
             (PresentationMode::Blank, PresentationMode::Blank) => true,
             (PresentationMode::Frozen(_), PresentationMode::Frozen(_)) => true,
+            (PresentationMode::QuickSlide(_), PresentationMode::QuickSlide(_)) => true,
+            (PresentationMode::Passage(_), PresentationMode::Passage(_)) => true,
+            (PresentationMode::Countdown, PresentationMode::Countdown) => true,
             _ => false,
         }
     }
@@ -186,19 +113,138 @@ pub enum Message {
     RequestPrevSlide,
     /// Požaduje přepnutí prezentace na následující slajd
     RequestNextSlide,
+    /// Požaduje přepnutí prezentace na první slajd předchozí položky playlistu
+    RequestPrevItem,
+    /// Požaduje přepnutí prezentace na první slajd následující položky playlistu
+    RequestNextItem,
     /// Přepne prezentaci na slajd s daným indexem
     SelectSlide(usize),
+    /// Změnil se textový vstup s číslem slajdu, na který se má skočit
+    GoToSlideInputChanged(String),
+    /// Přepne prezentaci na slajd s číslem zadaným v textovém vstupu (Enter)
+    GoToSlide,
+    /// Změnil se hledaný výraz pro vyhledání slajdu, viz [`search_slides()`]
+    SlideSearchChanged(String),
+    /// Přepne prezentaci na první nalezený slajd za promítaným slajdem (Enter), opakované
+    /// potvrzení tak prochází všechny nalezené slajdy
+    SubmitSlideSearch,
     /// Zavře prezentační okno
     ClosePresentationWindow,
     /// Prezentační okno je zavřeno
     PresentationWindowClosed,
+    /// Prezentační okno bylo zavřeno zvenčí (OS, Alt+F4 na projektoru), prezentace
+    /// ale pokračuje a uživateli se nabídne jeho znovuotevření
+    PresentationWindowLost,
     /// Změna módu prezentace
     PresentationModeChanged(PresentationMode),
+    /// Změna jazyka, ve kterém se promítají písně s překladem
+    LanguageModeChanged(LanguageMode),
+    /// Přepne na následující jazyk písní, viz [`LanguageMode::next()`]
+    CycleLanguageMode,
     /// Zmrazit prezentaci, stejné jako PresentationModeChanged(PresentationMode::Frozen(_)),
     /// ale bez specifikace indexu. Nutné pro zamražení ze subscription.
     FreezePresentation,
     /// Změna multiplikátoru velikosti textu na snímku
     TextSizeMultiplierChanged(u8),
+    /// Přepne přeskakování položky playlistu s daným indexem, slajdy přeskočené položky
+    /// jsou v seznamu šedé a navigace šipkami (i makry) je přeskakuje
+    ToggleSkipItem(usize),
+    /// Posune ještě nepromítnutou položku na dané pozici (všechny její slajdy) v prezentaci
+    /// o jednu položku dřív, playlist v databázi se nemění
+    MoveRemainingItemEarlier(usize),
+    /// Posune ještě nepromítnutou položku na dané pozici o jednu položku později
+    MoveRemainingItemLater(usize),
+    /// Uloží pořadí položek změněné během prezentace do playlistu
+    SaveItemOrder,
+    /// Pořadí položek bylo uloženo, pořadí položek v playlistu (viz [`item_order()`])
+    ItemOrderSaved(Vec<usize>),
+    ItemOrderSaveFailed(String),
+    /// Zopakuje vybranou část písně (např. refrén navíc), vloží kopii jejích slajdů
+    /// hned za ni, takže stačí pokračovat na následující slajd. Kopie existuje pouze
+    /// do konce prezentace, playlist se nemění.
+    RepeatSongPart,
+    /// Změna počtu veršů na slajdu, pasáže s automatickým rozdělením se hned rozdělí znovu
+    VersesPerSlideChanged(u8),
+    /// Přepne promítání pasáží po jednotlivých verších (responzoriální čtení), po vypnutí
+    /// se pasáže znovu rozdělí podle počtu veršů na slajdu (a ručních zlomů)
+    VerseByVerseToggled(bool),
+    /// Změna velikosti textu pouze na vybraném slajdu
+    OverrideTextScaleChanged(u8),
+    /// Změna zarovnání textu pouze na vybraném slajdu
+    OverrideAlignmentPicked(SlideAlignment),
+    /// Změna barvy pozadí pouze na vybraném slajdu (textový vstup `#rrggbb`)
+    OverrideBackgroundChanged(String),
+    /// Zruší všechny úpravy vybraného slajdu
+    ResetSlideOverride,
+    /// Uloží úpravu vybraného slajdu do databáze
+    SaveSlideOverride,
+    SlideOverrideSaved,
+    SlideOverrideSaveFailed(String),
+    /// Načte z disku náhledy slajdů, které již byly vygenerovány dříve
+    LoadThumbnails,
+    ThumbnailsLoaded(Vec<(u64, Vec<u8>)>),
+    /// Snímek prezentačního okna pořízený pro náhled slajdu s daným klíčem
+    ScreenshotTaken(u64, Screenshot),
+    /// Náhled slajdu s daným klíčem byl vygenerován (PNG)
+    ThumbnailGenerated(u64, Vec<u8>),
+    ThumbnailFailed(String),
+    /// Pořídí snímek prezentačního okna pro obrázek prezentace, viz [`companion_image`]
+    CaptureCompanionImage,
+    CompanionImageCaptured(Screenshot),
+    CompanionImageWritten,
+    CompanionImageFailed(String),
+    ExportResolutionPicked(Resolution),
+    /// Otevře dialog pro uložení vybraného slajdu jako obrázku
+    ExportSlide,
+    SlideExportPathPicked(PathBuf),
+    SlideExportCaptured(PathBuf, Screenshot),
+    SlideExported(PathBuf),
+    SlideExportFailed(String),
+    DialogCancelled,
+    /// Zapne/vypne klávesové zkratky prezentéru (např. když obsluha potřebuje psát
+    /// do jiného programu nebo widgetu a nechce omylem přepnout režim prezentace)
+    HotkeysToggled(bool),
+    /// Byla stisknuta klávesa, význam se určí podle [`Keymap`] v [`Presenter::update`]
+    /// (subscription nemůže zachytávat stav, takže klávesy rozlišujeme až tam)
+    KeyPressed(Key),
+    /// Spustí makro s daným indexem (v [`Presenter::macros`])
+    RunMacro(usize),
+    /// Provede krok makra, (index makra, index kroku), zbylé kroky naplánuje po něm
+    RunMacroStep(usize, usize),
+    /// Promítne rychlý slajd s daným indexem, pokud se již promítá, vrátí se
+    /// do režimu před jeho promítnutím
+    ToggleQuickSlide(usize),
+    /// Změnil se textový vstup s číselným odkazem na pasáž, viz [`parse_numeric_reference()`]
+    PassageEntryChanged(String),
+    /// Připojí znak k číselnému odkazu na pasáž (zadávání naslepo z numerické klávesnice)
+    PassageEntryKey(char),
+    /// Smaže poslední znak číselného odkazu na pasáž
+    PassageEntryBackspace,
+    /// Načte pasáž podle číselného odkazu ve výchozím překladu a promítne ji mimo playlist
+    SubmitPassageEntry,
+    PassageLoaded(Passage),
+    PassageLoadFailed(String),
+    /// Promítne slajd pasáže zadané během prezentace s daným indexem
+    SelectPassageSlide(usize),
+    /// Ukončí promítání pasáže zadané během prezentace a vrátí se do předchozího režimu
+    ClosePassage,
+    /// Změnil se textový vstup s časem konce bohoslužby
+    ServiceEndInputChanged(String),
+    /// Začne hlídat konec bohoslužby v čase zadaném v textovém vstupu (`HH:MM`)
+    SetServiceEnd,
+    ClearServiceEnd,
+    /// Přepočítá čas zbývající do konce bohoslužby, v jeho čase provede
+    /// [`ServiceEndAction`] z nastavení
+    ServiceEndTick,
+    /// Změnil se textový vstup s časem začátku bohoslužby pro odpočet
+    CountdownInputChanged(String),
+    /// Začne promítat odpočet do času začátku bohoslužby zadaného v textovém vstupu
+    /// (`HH:MM`). Odpočet se zruší přepnutím do normálního režimu, začernění nebo zmrazení.
+    StartCountdown,
+    /// Ukončí odpočet předčasně a přejde na první slajd (obsluha začíná dříve)
+    SkipCountdown,
+    /// Přepočítá čas zbývající do začátku bohoslužby, v jeho čase přejde na první slajd
+    CountdownTick,
 }
 
 impl From<Message> for crate::Message {
@@ -207,6 +253,22 @@ impl From<Message> for crate::Message {
     }
 }
 
+impl Message {
+    /// Zda je zpráva povolená v režimu hosta, úpravy slajdů lze použít během prezentace,
+    /// ale ne uložit do databáze
+    pub fn allowed_in_guest_mode(&self) -> bool {
+        !matches!(
+            self,
+            Message::SaveSlideOverride
+                | Message::SlideOverrideSaved
+                | Message::SlideOverrideSaveFailed(_)
+                | Message::SaveItemOrder
+                | Message::ItemOrderSaved(_)
+                | Message::ItemOrderSaveFailed(_)
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Presenter {
     /// Id okna s prezentací
@@ -217,56 +279,103 @@ pub struct Presenter {
     current_presented_index: usize,
     /// Režim prezentace
     mode: PresentationMode,
+    /// Režim prezentace před promítnutím rychlého slajdu, do kterého se po něm vrátí
+    mode_before_quick_slide: PresentationMode,
+    /// Jazyk, ve kterém se promítají písně s překladem
+    language: LanguageMode,
     /// Multiplikátor velikost textu na snímku, při použití se normalizuje do
     /// intervalu `[TEXT_SIZE_MULTIPLIER_MIN]` až [`TEXT_SIZE_MULTIPLIER_MAX`].
     /// Vysvětlení viz: [`TEXT_SIZE_MULTIPLIER_DEFAULT_U8`].
     text_scale: u8,
-}
-
-/// Přetvoří `playlist` na vektor slajdů složený z položek vytvořených z jednotlivých
-/// položek playlistu ve stejném pořadí.
-fn playlist_to_slides(playlist: Playlist, verses_per_slide: usize) -> Vec<Slide> {
-    let items = playlist.into_items();
-    let slides: Vec<Slide> = items
-        .into_iter()
-        .flat_map(|item| match item {
-            PlaylistItem::BiblePassage(passage) => {
-                let name = passage.get_translation_name();
-                let (from, to) = passage.get_range();
-                passage
-                    .get_verses()
-                    .chunks(verses_per_slide)
-                    .map(|verses| {
-                        Slide::Passage(PassageSlide::new(
-                            name.to_string(),
-                            from,
-                            to,
-                            verses.to_vec(),
-                        ))
-                    })
-                    .collect::<Vec<Slide>>()
-            }
-            PlaylistItem::Song(song) => {
-                let title = song.title;
-                song.order
-                    .into_iter()
-                    .map(|part_name| {
-                        let part_content = song
-                            .parts
-                            .get(&part_name)
-                            .expect("Píseň musí obsahovat všechny svoje části");
-                        Slide::Song(SongSlide::new(
-                            title.clone(),
-                            part_name,
-                            part_content.to_string(),
-                        ))
-                    })
-                    .collect()
-            }
-        })
-        .collect();
-
-    slides
+    /// Počet veršů na slajdu u pasáží s automatickým rozdělením (výchozí z nastavení)
+    verses_per_slide: u8,
+    /// Ruční zlomy slajdů jednotlivých položek playlistu, takové pasáže se při změně
+    /// `verses_per_slide` znovu nerozdělují
+    slide_breaks: Vec<Option<Vec<usize>>>,
+    /// Pasáže se promítají po jednom verši bez ohledu na `verses_per_slide` i ruční zlomy
+    verse_by_verse: bool,
+    /// Čísla světelných cue jednotlivých položek playlistu, viz [`network::lighting`]
+    lighting_cues: Vec<Option<u8>>,
+    /// Původ jednotlivých položek (pozice v playlistu, blok), viz [`ItemOrigin`]
+    item_origins: Vec<ItemOrigin>,
+    /// Položka naposledy zalogovaného slajdu, viz [`Presenter::log_current_item()`]
+    logged_item: Option<usize>,
+    /// Klávesové zkratky pro změnu režimu prezentace
+    keymap: Keymap,
+    /// Makra spouštěná klávesovou zkratkou, viz [`PresenterMacro`]
+    macros: Vec<PresenterMacro>,
+    /// Rychlé slajdy z nastavení spolu se slajdy, které se z nich promítají
+    quick_slides: Vec<(QuickSlide, Slide)>,
+    /// Zda se odebírají klávesové zkratky, viz [`Presenter::subscription()`]
+    hotkeys_enabled: bool,
+    /// Číselný odkaz na pasáž (`kniha kapitola verš`), viz [`parse_numeric_reference()`]
+    passage_entry: String,
+    /// Chybová hláška k zadanému odkazu na pasáž
+    passage_entry_msg: String,
+    /// Slajdy pasáže zadané během prezentace, promítají se v režimu [`PresentationMode::Passage`]
+    passage_slides: Vec<Slide>,
+    /// Režim prezentace před promítnutím zadané pasáže, do kterého se po ní vrátí
+    mode_before_passage: PresentationMode,
+    /// Blokování spořiče obrazovky, aktivní, dokud je otevřené prezentační okno
+    /// (v `Arc`, protože `Presenter` musí být `Clone` kvůli [`crate::Message`])
+    sleep_inhibitor: Option<Arc<SleepInhibitor>>,
+    /// Prezentační okno bylo zavřeno zvenčí a ještě nebylo znovu otevřeno
+    output_lost: bool,
+    /// Prezentační okno zavíráme sami (ukončení prezentace)
+    closing: bool,
+    /// Záznam průběhu prezentace, do databáze se zapíše při jejím ukončení
+    log: PresentationLog,
+    /// Úpravy jednotlivých slajdů, vždy stejně dlouhý jako `playlist_slides`
+    slide_overrides: Vec<SlideOverride>,
+    /// Textový vstup pro barvu pozadí vybraného slajdu
+    override_background_input: String,
+    /// Výsledek posledního uložení úpravy slajdu
+    override_msg: String,
+    /// Výsledek posledního uložení pořadí položek
+    item_order_msg: String,
+    /// Indexy položek playlistu, které se při navigaci přeskakují (nic se nemaže)
+    skipped_items: HashSet<usize>,
+    /// Náhledy slajdů pro seznam slajdů v ovládacím okně
+    thumbnails: ThumbnailCache,
+    /// Textový vstup s číslem slajdu (od 1), na který se má skočit
+    goto_input: String,
+    /// Chybová hláška k poslednímu skoku na slajd
+    goto_msg: String,
+    /// Hledaný výraz pro vyhledání slajdu (název písně, tag části, odkaz na verš)
+    slide_search: String,
+    /// Cesta, kam se průběžně zapisuje promítaný slajd, viz [`companion_image`]
+    companion_image_path: Option<PathBuf>,
+    /// Interval zápisu obrázku prezentace
+    companion_image_interval: Duration,
+    /// Zápis obrázku prezentace právě probíhá, další snímek se nepořizuje
+    companion_image_busy: bool,
+    /// Rozlišení, ve kterém se ukládá slajd jako obrázek, viz [`slide_export`]
+    export_resolution: Resolution,
+    /// Výsledek posledního uložení slajdu jako obrázku
+    export_msg: String,
+    /// Varování před koncem bohoslužby a akce v jeho čase
+    service_end_settings: ServiceEndSettings,
+    /// Textový vstup s časem konce bohoslužby (`HH:MM`)
+    service_end_input: String,
+    /// Chybová hláška k zadanému času konce bohoslužby
+    service_end_msg: String,
+    /// Čas konce bohoslužby, `None` = konec se nehlídá
+    service_end: Option<NaiveDateTime>,
+    /// Kolik zbývá do konce bohoslužby (záporné = přetahuje se), přepočítává se
+    /// každou sekundu
+    service_end_remaining: Option<TimeDelta>,
+    /// Akce konce bohoslužby již byla provedena, provádí se pouze jednou
+    service_end_reached: bool,
+    /// Motiv slajdu s odpočtem před začátkem bohoslužby, viz [`config::CountdownSettings`]
+    countdown_theme: PresentationTheme,
+    /// Textový vstup s časem začátku bohoslužby (`HH:MM`)
+    countdown_input: String,
+    /// Chybová hláška k zadanému času začátku bohoslužby
+    countdown_msg: String,
+    /// Čas začátku bohoslužby, `None` = odpočet neběží
+    countdown_start: Option<NaiveDateTime>,
+    /// Kolik zbývá do začátku bohoslužby, přepočítává se každých [`COUNTDOWN_TICK`]
+    countdown_remaining: TimeDelta,
 }
 
 impl Presenter {
@@ -274,22 +383,115 @@ impl Presenter {
         self.presentation_window_id
     }
 
-    /// Vytvoří nový `Presenter`. Playlist musí obsahovat alespoň jednu položku,
-    /// jinak není co prezentovat a funkce vrátí Error.
-    pub async fn try_new(playlist_id: i64, conn: &mut PoolConnection<Sqlite>) -> Result<Presenter> {
+    /// Vytvoří nový `Presenter`, vzhled a klávesové zkratky převezme z `settings`.
+    /// Playlist musí obsahovat alespoň jednu položku, jinak není co prezentovat
+    /// a funkce vrátí Error.
+    pub async fn try_new(
+        playlist_id: i64,
+        conn: &mut PoolConnection<Sqlite>,
+        settings: config::Settings,
+        thumbnails_path: Option<PathBuf>,
+    ) -> Result<Presenter> {
         let playlist = Playlist::load(playlist_id, conn)
             .await
             .context("Nelze načíst playlist z databáze")?;
 
         if playlist.items.is_empty() {
             Err(anyhow!("Nelze prezentovat prázdný playlist"))
+        } else if !playlist.enabled.contains(&true) {
+            Err(anyhow!(
+                "Nelze prezentovat playlist, ve kterém jsou všechny položky vypnuté"
+            ))
         } else {
+            let slide_breaks = playlist.slide_breaks.clone();
+            let lighting_cues = playlist.lighting_cues.clone();
+            let item_origins = playlist.item_origins.clone();
+            let verses_per_slide = settings.verses_per_slide();
+            let playlist_slides = playlist_to_slides(
+                playlist,
+                verses_per_slide.into(),
+                settings.max_song_lines.map(usize::from),
+                settings.passage_headings,
+                &settings.theme,
+            );
+
+            let mut saved_overrides = slide_override::load_for_playlist(playlist_id, conn)
+                .await
+                .context("Nelze načíst úpravy slajdů")?;
+            // Úpravy jsou uložené podle pozice položky v playlistu, slajdy bloků je nemají
+            let slide_overrides: Vec<SlideOverride> = slide_keys(&playlist_slides)
+                .into_iter()
+                .map(|(item_index, slide_index)| {
+                    let origin = &item_origins[item_index];
+                    match origin.block {
+                        Some(_) => SlideOverride::default(),
+                        None => saved_overrides
+                            .remove(&(origin.position, slide_index))
+                            .unwrap_or_default(),
+                    }
+                })
+                .collect();
+            let override_background_input = background_input(&slide_overrides[0]);
+            let quick_slides = settings
+                .quick_slides
+                .iter()
+                .map(|quick_slide| {
+                    let slide = quick_slide_to_slide(quick_slide, &settings.theme);
+                    (quick_slide.clone(), slide)
+                })
+                .collect();
+
             Ok(Presenter {
-                playlist_slides: playlist_to_slides(playlist, VERSES_PER_SLIDE),
+                playlist_slides,
                 current_presented_index: 0,
                 mode: PresentationMode::Normal,
+                mode_before_quick_slide: PresentationMode::Normal,
+                language: LanguageMode::default(),
                 presentation_window_id: None,
                 text_scale: TEXT_SIZE_MULTIPLIER_DEFAULT_U8,
+                verses_per_slide,
+                slide_breaks,
+                verse_by_verse: false,
+                lighting_cues,
+                item_origins,
+                logged_item: None,
+                keymap: settings.keymap,
+                macros: settings.macros,
+                quick_slides,
+                hotkeys_enabled: true,
+                passage_entry: String::new(),
+                passage_entry_msg: String::new(),
+                passage_slides: Vec::new(),
+                mode_before_passage: PresentationMode::Normal,
+                sleep_inhibitor: None,
+                output_lost: false,
+                closing: false,
+                log: PresentationLog::new(playlist_id),
+                slide_overrides,
+                override_background_input,
+                override_msg: String::new(),
+                item_order_msg: String::new(),
+                skipped_items: HashSet::new(),
+                thumbnails: ThumbnailCache::new(thumbnails_path),
+                goto_input: String::new(),
+                goto_msg: String::new(),
+                slide_search: String::new(),
+                companion_image_interval: settings.companion_image_interval(),
+                companion_image_path: settings.companion_image_path,
+                companion_image_busy: false,
+                export_resolution: slide_export::DEFAULT_RESOLUTION,
+                export_msg: String::new(),
+                service_end_settings: settings.service_end,
+                service_end_input: String::new(),
+                service_end_msg: String::new(),
+                service_end: None,
+                service_end_remaining: None,
+                service_end_reached: false,
+                countdown_theme: settings.countdown.theme(&settings.theme),
+                countdown_input: settings.countdown.start_time.clone().unwrap_or_default(),
+                countdown_msg: String::new(),
+                countdown_start: None,
+                countdown_remaining: TimeDelta::zero(),
             })
         }
     }
@@ -298,42 +500,557 @@ impl Presenter {
     ///
     /// # Klávesy
     /// - Šipky ↑↓ pro posouvání právě promítané položky
+    /// - PageUp/PageDown pro skok na první slajd předchozí/následující položky playlistu
     /// - Escape pro ukončení prezentace
+    /// - Zkratky pro změnu režimu prezentace, jazyka písní a opakování části písně podle
+    ///   [`Keymap`] z nastavení
+    /// - Zkratky maker podle [`PresenterMacro`] a rychlých slajdů podle [`QuickSlide`]
+    ///   z nastavení
+    /// - Číslice (a oddělovače) numerické klávesnice pro zadání odkazu na pasáž, Enter
+    ///   ji promítne, viz [`parse_numeric_reference()`]
+    ///
+    /// Klávesy se odebírají pouze, pokud jsou zkratky zapnuté (`hotkeys_enabled`), a to
+    /// pouze ty, které nezpracoval widget s fokusem (psaní do textového vstupu nepřepíná
+    /// režim prezentace) a které nejsou stisknuté s Ctrl, Alt nebo Super, viz [`is_hotkey()`].
+    ///
+    /// Pokud je nastavená cesta obrázku prezentace a prezentační okno je otevřené,
+    /// odebíráme i časovač pro jeho zápis, viz [`companion_image`]. Pokud se hlídá
+    /// konec bohoslužby nebo běží odpočet před jejím začátkem, odebíráme časovač pro
+    /// přepočítání zbývajícího času.
     pub fn subscription(&self) -> Subscription<crate::Message> {
-        iced::keyboard::on_key_press(|key, modifiers| {
-            trace!("Přišel event z klávesnice: {:?}", (key.clone(), modifiers));
-            match (key.as_ref(), modifiers) {
-                (Key::Named(key::Named::ArrowUp), _) => Some(Message::RequestPrevSlide.into()),
-                (Key::Named(key::Named::ArrowDown), _) => Some(Message::RequestNextSlide.into()),
-                (Key::Named(key::Named::Escape), _) => {
-                    Some(Message::ClosePresentationWindow.into())
-                }
-                (Key::Character(MODE_FREEZE_KEY), _) => Some(Message::FreezePresentation.into()),
-                (Key::Character(MODE_NORMAL_KEY), _) => {
-                    Some(Message::PresentationModeChanged(PresentationMode::Normal).into())
-                }
-                (Key::Character(MODE_BLANK_KEY), _) => {
-                    Some(Message::PresentationModeChanged(PresentationMode::Blank).into())
+        let keys = if self.hotkeys_enabled {
+            event::listen_with(|event, status, _window| match event {
+                Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. })
+                    if is_hotkey(status, modifiers) =>
+                {
+                    trace!("Přišel event z klávesnice: {:?}", (key.clone(), modifiers));
+                    Some(Message::KeyPressed(key).into())
                 }
                 _ => None,
+            })
+        } else {
+            Subscription::none()
+        };
+
+        let companion_image = match (&self.companion_image_path, self.presentation_window_id) {
+            (Some(_), Some(_)) => iced::time::every(self.companion_image_interval)
+                .map(|_| Message::CaptureCompanionImage.into()),
+            _ => Subscription::none(),
+        };
+
+        let service_end = match self.service_end {
+            Some(_) => iced::time::every(SERVICE_END_TICK).map(|_| Message::ServiceEndTick.into()),
+            None => Subscription::none(),
+        };
+
+        let countdown = match self.countdown_start {
+            Some(_) => iced::time::every(COUNTDOWN_TICK).map(|_| Message::CountdownTick.into()),
+            None => Subscription::none(),
+        };
+
+        Subscription::batch([keys, companion_image, service_end, countdown])
+    }
+
+    /// Přeloží stisknutou klávesu na zprávu prezentéru, pokud klávesa nemá
+    /// přiřazenou žádnou akci, vrací `None`.
+    fn key_to_message(&self, key: Key) -> Option<Message> {
+        match key.as_ref() {
+            Key::Named(key::Named::ArrowUp) => Some(Message::RequestPrevSlide),
+            Key::Named(key::Named::ArrowDown) => Some(Message::RequestNextSlide),
+            Key::Named(key::Named::PageUp) => Some(Message::RequestPrevItem),
+            Key::Named(key::Named::PageDown) => Some(Message::RequestNextItem),
+            Key::Named(key::Named::Escape) => Some(Message::ClosePresentationWindow),
+            // Rozepsaný odkaz na pasáž z numerické klávesnice
+            Key::Named(key::Named::Enter) if !self.passage_entry.is_empty() => {
+                Some(Message::SubmitPassageEntry)
+            }
+            Key::Named(key::Named::Backspace) if !self.passage_entry.is_empty() => {
+                Some(Message::PassageEntryBackspace)
+            }
+            Key::Named(key::Named::Space) if !self.passage_entry.is_empty() => {
+                Some(Message::PassageEntryKey(' '))
+            }
+            Key::Character(c) if c == self.keymap.freeze => Some(Message::FreezePresentation),
+            Key::Character(c) if c == self.keymap.normal => {
+                Some(Message::PresentationModeChanged(PresentationMode::Normal))
+            }
+            Key::Character(c) if c == self.keymap.blank => {
+                Some(Message::PresentationModeChanged(PresentationMode::Blank))
+            }
+            Key::Character(c) if c == self.keymap.language => Some(Message::CycleLanguageMode),
+            Key::Character(c) if c == self.keymap.repeat_part => Some(Message::RepeatSongPart),
+            Key::Character(c) => self
+                .macros
+                .iter()
+                .position(|presenter_macro| presenter_macro.key == c)
+                .map(Message::RunMacro)
+                .or_else(|| {
+                    self.quick_slides
+                        .iter()
+                        .position(|(quick_slide, _)| quick_slide.key == c)
+                        .map(Message::ToggleQuickSlide)
+                })
+                .or_else(|| {
+                    passage_entry_char(c, &self.passage_entry).map(Message::PassageEntryKey)
+                }),
+            _ => None,
+        }
+    }
+
+    /// Přeloží krok makra (nebo příkaz z MQTT, viz [`crate::network::mqtt`]) na zprávu
+    /// prezentéru, čekání a skok na neexistující slajd žádnou zprávu nemají, vrací `None`.
+    pub fn macro_step_to_message(&self, step: MacroStep) -> Option<Message> {
+        match step {
+            MacroStep::Blank => Some(Message::PresentationModeChanged(PresentationMode::Blank)),
+            MacroStep::Normal => Some(Message::PresentationModeChanged(PresentationMode::Normal)),
+            MacroStep::Freeze => Some(Message::FreezePresentation),
+            MacroStep::Next => Some(Message::RequestNextSlide),
+            MacroStep::Prev => Some(Message::RequestPrevSlide),
+            MacroStep::GoTo(slide) => (1..=self.playlist_slides.len())
+                .contains(&slide)
+                .then(|| Message::SelectSlide(slide - 1)),
+            MacroStep::Wait(_) => None,
+        }
+    }
+
+    /// Znovu rozdělí pasáže s automatickým rozdělením po `verses_per_slide` verších, v režimu
+    /// po jednom verši (`verse_by_verse`) rozdělí všechny pasáže po jednom verši. Úpravy
+    /// slajdů zůstanou u slajdů se stejným klíčem a vybraný (i zmrazený) slajd zůstane
+    /// na stejné položce playlistu.
+    fn rechunk(&mut self, verses_per_slide: u8) {
+        let old_keys = slide_keys(&self.playlist_slides);
+        let slides = std::mem::take(&mut self.playlist_slides);
+        self.verses_per_slide = verses_per_slide;
+        self.playlist_slides = if self.verse_by_verse {
+            rechunk_passages(slides, &[], 1)
+        } else {
+            rechunk_passages(slides, &self.slide_breaks, verses_per_slide.into())
+        };
+
+        let new_keys = slide_keys(&self.playlist_slides);
+        let overrides: HashMap<(usize, usize), SlideOverride> = old_keys
+            .iter()
+            .copied()
+            .zip(std::mem::take(&mut self.slide_overrides))
+            .collect();
+        // Kopie zopakovaných částí mají stejný klíč jako původní slajdy
+        self.slide_overrides = new_keys
+            .iter()
+            .map(|key| overrides.get(key).cloned().unwrap_or_default())
+            .collect();
+
+        self.current_presented_index =
+            remap_slide_index(&old_keys, &new_keys, self.current_presented_index);
+        if let PresentationMode::Frozen(index) = self.mode {
+            self.mode = PresentationMode::Frozen(remap_slide_index(&old_keys, &new_keys, index));
+        }
+        self.override_background_input =
+            background_input(&self.slide_overrides[self.current_presented_index]);
+    }
+
+    /// Prohodí položku (všechny její slajdy) na pozici `item` s položkou za ní, úpravy
+    /// slajdů se přesunou spolu se slajdy. Již promítnuté položky se nepřesouvají,
+    /// viz [`can_swap_with_next()`].
+    fn swap_with_next_item(&mut self, item: usize) {
+        let ranges = item_ranges(&self.playlist_slides);
+        if !can_swap_with_next(&ranges, self.current_presented_index, item) {
+            warn!("Položku {item} nelze přesunout, již byla promítnuta");
+            return;
+        }
+        debug!(
+            "Prohazuji položky prezentace na pozicích {item} a {}",
+            item + 1
+        );
+        self.item_order_msg.clear();
+
+        let (first, second) = (ranges[item].clone(), ranges[item + 1].clone());
+        let range = first.start..second.end;
+
+        self.playlist_slides[range.clone()].rotate_left(first.len());
+        self.slide_overrides[range.clone()].rotate_left(first.len());
+        if let PresentationMode::Frozen(index) = self.mode
+            && range.contains(&index)
+        {
+            let index = if first.contains(&index) {
+                index + second.len()
+            } else {
+                index - first.len()
+            };
+            self.mode = PresentationMode::Frozen(index);
+        }
+    }
+
+    /// Po uložení pořadí položek `order` do playlistu přečísluje položky slajdů (a vše,
+    /// co je podle nich indexované), aby odpovídaly pořadí položek v databázi
+    fn renumber_items(&mut self, order: &[usize]) {
+        for slide in &mut self.playlist_slides {
+            if let Some(position) = order
+                .iter()
+                .position(|&original| original == slide.item_index())
+            {
+                slide.set_item_index(position);
+            }
+        }
+        self.slide_breaks = order
+            .iter()
+            .map(|&original| self.slide_breaks[original].clone())
+            .collect();
+        self.lighting_cues = order
+            .iter()
+            .map(|&original| self.lighting_cues[original])
+            .collect();
+        // Pořadí lze uložit pouze u playlistu bez bloků, pozice je tedy přímo index položky
+        self.item_origins = order
+            .iter()
+            .enumerate()
+            .map(|(position, &original)| ItemOrigin {
+                position,
+                part_order: position,
+                ..self.item_origins[original].clone()
+            })
+            .collect();
+        self.logged_item = self
+            .logged_item
+            .and_then(|logged| order.iter().position(|&original| original == logged));
+        self.skipped_items = order
+            .iter()
+            .enumerate()
+            .filter(|(_, original)| self.skipped_items.contains(original))
+            .map(|(position, _)| position)
+            .collect();
+        self.log.renumber(order);
+    }
+
+    /// Zda lze pořadí položek uložit do playlistu, tedy pořadí se změnilo a každá položka
+    /// je v prezentaci právě jednou (žádné bloky ani vypnuté položky)
+    fn can_save_item_order(&self, guest_mode: bool) -> bool {
+        let order = item_order(&self.playlist_slides);
+        !guest_mode
+            && !is_identity(&order)
+            && order.len() == self.item_origins.len()
+            && self
+                .item_origins
+                .iter()
+                .all(|origin| origin.block.is_none())
+    }
+
+    /// Zaznamená do logu prezentace položku playlistu, ze které je aktuální slajd (u položek
+    /// bloku celý blok). Pokud se začala promítat píseň, zaznamená do databáze `db` i její
+    /// použití (viz [`Song::record_usage()`]) u playlistu, ve kterém píseň je, chyba se
+    /// pouze zaloguje.
+    fn log_current_item(&mut self, db: &SqlitePool) -> Task<crate::Message> {
+        let slide = &self.playlist_slides[self.current_presented_index];
+        let item_index = slide.item_index();
+        let is_song = matches!(slide, Slide::Song(_));
+        let origin = &self.item_origins[item_index];
+
+        self.log.item_presented(origin.position as i64);
+        if self.logged_item.replace(item_index) == Some(item_index) || !is_song {
+            return Task::none();
+        }
+
+        let db = db.clone();
+        let (playlist_id, part_order) = (origin.playlist_id, origin.part_order as i64);
+        Task::future(async move {
+            if let Err(e) = Song::record_usage(playlist_id, part_order, &db).await {
+                warn!("Nelze zaznamenat použití písně: {:?}", e);
+            }
+        })
+        .discard()
+    }
+
+    /// Vrátí právě promítaný slajd pro výstupy prezentace z pluginů
+    fn presented_slide(&self) -> PresentedSlide {
+        let (slide_index, mode) = match self.mode {
+            PresentationMode::Normal => (self.current_presented_index, "normal"),
+            PresentationMode::Blank => (self.current_presented_index, "blank"),
+            PresentationMode::Frozen(index) => (index, "frozen"),
+            PresentationMode::QuickSlide(_) => (self.current_presented_index, "quick_slide"),
+            PresentationMode::Passage(_) => (self.current_presented_index, "passage"),
+            PresentationMode::Countdown => (self.current_presented_index, "countdown"),
+        };
+        let countdown_slide;
+        let slide = match self.mode {
+            PresentationMode::QuickSlide(quick_index) => &self.quick_slides[quick_index].1,
+            PresentationMode::Passage(passage_index) => &self.passage_slides[passage_index],
+            PresentationMode::Countdown => {
+                countdown_slide = self.countdown_slide();
+                &countdown_slide
+            }
+            _ => &self.playlist_slides[slide_index],
+        };
+        let (text, footer) = slide.texts(self.language);
+
+        PresentedSlide {
+            slide_index,
+            item_index: self.playlist_slides[slide_index].item_index(),
+            mode,
+            text,
+            footer,
+        }
+    }
+
+    /// Předá právě promítaný slajd výstupům prezentace z pluginů `plugins`. Pluginy se volají
+    /// mimo vlákno GUI a jejich chyby se pouze zalogují, aby prezentaci nikdy nezdržely.
+    fn notify_plugins(&self, plugins: &Plugins) -> Task<crate::Message> {
+        let outputs = plugins.presenter_outputs();
+        if outputs.is_empty() {
+            return Task::none();
+        }
+
+        let slide = Arc::new(self.presented_slide());
+        Task::batch(outputs.into_iter().map(|plugin| {
+            let slide = slide.clone();
+            Task::future(tokio::task::spawn_blocking(move || {
+                if let Err(e) = plugin.slide_presented(&slide) {
+                    warn!("Výstup prezentace z pluginu selhal: {:#}", e);
+                }
+            }))
+            .discard()
+        }))
+    }
+
+    /// Ohlásí událost `event` s právě promítaným slajdem: publikuje ji do MQTT (pokud je
+    /// `mqtt`, viz [`crate::network::mqtt`]) a spustí pro ni příkaz nastavený v `hooks`
+    /// (viz [`event_hooks`]). Na dokončení příkazu se nečeká a chyby se pouze zalogují.
+    fn emit_event(
+        &self,
+        hooks: &EventHooks,
+        mqtt: Option<&MqttPublisher>,
+        event: PresentationEvent,
+    ) -> Task<crate::Message> {
+        let slide = (event != PresentationEvent::Ended).then(|| self.presented_slide());
+
+        if let Some(mqtt) = mqtt {
+            let published = event_hooks::event_json(event, slide.as_ref())
+                .and_then(|payload| mqtt.publish(event, payload));
+            if let Err(e) = published {
+                warn!("Nelze publikovat událost {} do MQTT: {:#}", event.name(), e);
+            }
+        }
+
+        let Some(command) = event.command(hooks).map(String::from) else {
+            return Task::none();
+        };
+
+        debug!("Spouštím příkaz pro událost {}: {command}", event.name());
+        Task::future(tokio::task::spawn_blocking(move || {
+            if let Err(e) = event_hooks::run(&command, event, slide.as_ref()) {
+                warn!("Příkaz pro událost {} selhal: {:#}", event.name(), e);
+            }
+        }))
+        .discard()
+    }
+
+    /// Odešle světelné cue položky, ze které je aktuální slajd (pokud nějaké má), přes
+    /// Art-Net podle `settings`, viz [`network::lighting`]. Pokud je odesílání vypnuté,
+    /// nic nedělá, chyby se pouze zalogují, aby prezentaci nikdy nezdržely.
+    fn send_lighting_cue(
+        &self,
+        settings: &LightingSettings,
+        offline: bool,
+    ) -> Task<crate::Message> {
+        let item_index = self.playlist_slides[self.current_presented_index].item_index();
+        let Some(cue) = self.lighting_cues.get(item_index).copied().flatten() else {
+            return Task::none();
+        };
+        if settings.node.trim().is_empty() {
+            return Task::none();
+        }
+
+        debug!("Odesílám světelné cue {cue} položky {item_index}");
+        let settings = settings.clone();
+        Task::future(async move {
+            if let Err(e) = network::lighting::send_cue(settings, cue, offline).await {
+                warn!("Nelze odeslat světelné cue {cue}: {:#}", e);
             }
         })
+        .discard()
+    }
+
+    /// Vrátí naměřené délky jednotlivých položek playlistu z uzavřených záznamů
+    /// v logu prezentace, seřazené podle pořadí v playlistu.
+    fn measured_items(&self) -> Vec<MeasuredItem> {
+        self.log
+            .measured_durations()
+            .into_iter()
+            .map(|(part_order, duration)| {
+                let item_index = part_order as usize;
+                let label = self
+                    .playlist_slides
+                    .iter()
+                    .map(|slide| (slide, &self.item_origins[slide.item_index()]))
+                    .find(|(_, origin)| origin.position == item_index)
+                    .map(|(slide, origin)| match &origin.block {
+                        Some(block) => format!("Blok {block}"),
+                        None => item_label(slide),
+                    })
+                    .unwrap_or_default();
+
+                MeasuredItem {
+                    item_index,
+                    label,
+                    duration: duration.num_seconds().max(0) as u32,
+                }
+            })
+            .collect()
+    }
+
+    /// Vrátí kopii záznamu průběhu prezentace (pro zapsání do databáze při ukončení programu)
+    pub fn get_log(&self) -> PresentationLog {
+        self.log.clone()
+    }
+
+    /// Poznamená si, že prezentační okno zavíráme sami, viz [`Presenter::is_closing`]
+    pub fn set_closing(&mut self) {
+        self.closing = true;
+    }
+
+    /// Vrátí `true`, pokud bylo zavření prezentačního okna vyžádáno z aplikace
+    pub fn is_closing(&self) -> bool {
+        self.closing
     }
 
     pub fn get_presentation_window_id(&self) -> Option<Id> {
         self.presentation_window_id
     }
 
+    /// Vrátí výsledný styl slajdu na indexu `index` (globální velikost textu + úpravy slajdu)
+    fn slide_style(&self, index: usize) -> SlideStyle {
+        let mut style = SlideStyle::new(
+            &self.playlist_slides[index],
+            normalize_text_multiplier(self.text_scale),
+            &self.slide_overrides[index],
+        );
+        style.language = self.language;
+        style
+    }
+
+    /// Klíč náhledu slajdu na indexu `index`
+    fn thumbnail_key(&self, index: usize) -> u64 {
+        thumbnail_key(&self.playlist_slides[index], &self.slide_style(index))
+    }
+
+    /// Pokud aktuálně promítaný slajd ještě nemá náhled, pořídí (se zpožděním, až se okno
+    /// překreslí) snímek prezentačního okna, ze kterého se náhled vygeneruje.
+    fn capture_thumbnail(&self) -> Task<crate::Message> {
+        let key = self.thumbnail_key(self.current_presented_index);
+
+        match (self.mode, self.presentation_window_id) {
+            (PresentationMode::Normal, Some(id)) if !self.thumbnails.contains(key) => {
+                trace!("Pořizuji snímek pro náhled slajdu {key:016x}");
+                Task::perform(tokio::time::sleep(THUMBNAIL_CAPTURE_DELAY), |_| ())
+                    .then(move |_| iced::window::screenshot(id))
+                    .map(move |screenshot| Message::ScreenshotTaken(key, screenshot).into())
+            }
+            _ => Task::none(),
+        }
+    }
+
+    /// Přidá k tlačítku slajdu na indexu `index` jeho náhled, pokud již existuje
+    fn with_thumbnail<'a>(
+        &'a self,
+        index: usize,
+        slide_button: Element<'a, Message>,
+    ) -> Element<'a, Message> {
+        match self.thumbnails.get(self.thumbnail_key(index)) {
+            Some(handle) => row![
+                iced::widget::image(handle.clone()).width(Length::Fixed(THUMBNAIL_LIST_WIDTH)),
+                slide_button
+            ]
+            .spacing(5)
+            .align_y(Alignment::Center)
+            .into(),
+            None => slide_button,
+        }
+    }
+
+    /// Index předchozího slajdu, který nepatří k přeskočené položce
+    fn prev_presented_index(&self) -> Option<usize> {
+        prev_unskipped_index(
+            &self.playlist_slides,
+            &self.skipped_items,
+            self.current_presented_index,
+        )
+    }
+
+    /// Index následujícího slajdu, který nepatří k přeskočené položce
+    fn next_presented_index(&self) -> Option<usize> {
+        next_unskipped_index(
+            &self.playlist_slides,
+            &self.skipped_items,
+            self.current_presented_index,
+        )
+    }
+
+    /// Index prvního slajdu předchozí položky, která není přeskočená
+    fn prev_item_index(&self) -> Option<usize> {
+        prev_item_start(
+            &self.playlist_slides,
+            &self.skipped_items,
+            self.current_presented_index,
+        )
+    }
+
+    /// Index prvního slajdu následující položky, která není přeskočená
+    fn next_item_index(&self) -> Option<usize> {
+        next_item_start(
+            &self.playlist_slides,
+            &self.skipped_items,
+            self.current_presented_index,
+        )
+    }
+
+    /// Zda slajd na indexu `index` patří k přeskočené položce
+    fn is_skipped(&self, index: usize) -> bool {
+        self.skipped_items
+            .contains(&self.playlist_slides[index].item_index())
+    }
+
+    /// Index prvního slajdu, který nepatří k přeskočené položce (na něj se přejde po odpočtu)
+    fn first_presented_index(&self) -> usize {
+        (0..self.playlist_slides.len())
+            .find(|index| !self.is_skipped(*index))
+            .unwrap_or(0)
+    }
+
+    /// Slajd s odpočtem do začátku bohoslužby
+    fn countdown_slide(&self) -> Slide {
+        countdown_to_slide(self.countdown_remaining, &self.countdown_theme)
+    }
+
+    /// Ukončí odpočet a přejde na první slajd. Pokud se místo odpočtu právě promítá rychlý
+    /// slajd nebo zadaná pasáž, po jejím skrytí se vrátí do normálního režimu.
+    fn finish_countdown(&mut self) -> Task<crate::Message> {
+        self.countdown_start = None;
+        if self.mode_before_quick_slide == PresentationMode::Countdown {
+            self.mode_before_quick_slide = PresentationMode::Normal;
+        }
+        if self.mode_before_passage == PresentationMode::Countdown {
+            self.mode_before_passage = PresentationMode::Normal;
+        }
+
+        let select_first = Task::done(Message::SelectSlide(self.first_presented_index()).into());
+        if self.mode == PresentationMode::Countdown {
+            select_first.chain(Task::done(
+                Message::PresentationModeChanged(PresentationMode::Normal).into(),
+            ))
+        } else {
+            select_first
+        }
+    }
+
     fn is_first_slide_selected(&self) -> bool {
-        self.current_presented_index == 0
+        self.prev_presented_index().is_none()
     }
 
     fn is_last_slide_selected(&self) -> bool {
-        self.current_presented_index == self.playlist_slides.len() - 1
+        self.next_presented_index().is_none()
     }
 
     /// Zkonstruuje GUI pro ovládací okno
-    pub fn view_control(&self) -> Element<Message> {
+    /// V režimu hosta (`guest_mode`) nelze úpravy slajdů ukládat
+    pub fn view_control(&self, guest_mode: bool) -> Element<Message> {
         // Na několika místech se musí explicitně specifikovat typ, protože automatická
         // inference typů shoří kvůli ukazateli na funkci
         type MsgAndStyle = (
@@ -341,11 +1058,15 @@ impl Presenter {
             fn(&iced::Theme, iced::widget::button::Status) -> iced::widget::button::Style,
         );
 
-        let slide_list =
-            self.playlist_slides
-                .iter()
-                .enumerate()
-                .map(|(index, slide)| match slide {
+        let ranges = item_ranges(&self.playlist_slides);
+        let slide_list = self
+            .playlist_slides
+            .iter()
+            .enumerate()
+            .map(|(index, slide)| {
+                // Vybraný slajd má vždy svůj styl, i když patří k přeskočené položce
+                let skipped = index != self.current_presented_index && self.is_skipped(index);
+                let slide_button: Element<Message> = match slide {
                     Slide::Passage(slide) => {
                         let (from, to) = slide.passage_indexes;
                         let (maybe_msg, style): MsgAndStyle =
@@ -360,12 +1081,24 @@ impl Presenter {
                         button(text!("Pasáž {} - {}", from, to))
                             .width(Length::Fill)
                             .on_press_maybe(maybe_msg)
-                            .style(style)
+                            .style(if skipped {
+                                playlist_item_styles::skipped
+                            } else {
+                                style
+                            })
                             .into()
                     }
                     Slide::Song(slide) => {
-                        let title = &slide.title;
-                        let part_name = &slide.part_name;
+                        let title = match &slide.key {
+                            Some(key) => format!("{} [{}]", slide.title, key),
+                            None => slide.title.clone(),
+                        };
+                        let part_name = match slide.part_piece {
+                            Some((piece, pieces)) => {
+                                format!("{} ({piece}/{pieces})", slide.part_name)
+                            }
+                            None => slide.part_name.clone(),
+                        };
                         let (maybe_msg, style): MsgAndStyle =
                             if index == self.current_presented_index {
                                 (None, playlist_item_styles::song_selected)
@@ -378,10 +1111,102 @@ impl Presenter {
                         button(text!("Píseň {}: {}", title, part_name))
                             .width(Length::Fill)
                             .on_press_maybe(maybe_msg)
-                            .style(style)
+                            .style(if skipped {
+                                playlist_item_styles::skipped
+                            } else {
+                                style
+                            })
                             .into()
                     }
-                });
+                    Slide::Text(slide) => {
+                        let (maybe_msg, style): MsgAndStyle =
+                            if index == self.current_presented_index {
+                                (None, playlist_item_styles::custom_text_selected)
+                            } else {
+                                (
+                                    Some(Message::SelectSlide(index)),
+                                    playlist_item_styles::custom_text,
+                                )
+                            };
+                        button(text!("Text {}", slide.title))
+                            .width(Length::Fill)
+                            .on_press_maybe(maybe_msg)
+                            .style(if skipped {
+                                playlist_item_styles::skipped
+                            } else {
+                                style
+                            })
+                            .into()
+                    }
+                    Slide::Image(slide) => {
+                        let (maybe_msg, style): MsgAndStyle =
+                            if index == self.current_presented_index {
+                                (None, playlist_item_styles::image_selected)
+                            } else {
+                                (
+                                    Some(Message::SelectSlide(index)),
+                                    playlist_item_styles::image,
+                                )
+                            };
+                        button(text!("Obrázek {}", slide.name))
+                            .width(Length::Fill)
+                            .on_press_maybe(maybe_msg)
+                            .style(if skipped {
+                                playlist_item_styles::skipped
+                            } else {
+                                style
+                            })
+                            .into()
+                    }
+                };
+                let slide_row = row![
+                    text!("{}", index + 1).width(Length::Fixed(SLIDE_NUMBER_WIDTH)),
+                    self.with_thumbnail(index, slide_button)
+                ]
+                .spacing(5)
+                .align_y(Alignment::Center);
+
+                // Slajdy položky seskupí nadpis nad jejím prvním slajdem, položku lze v něm
+                // přeskočit, ještě nepromítnuté položky lze i přesouvat
+                let current = self.current_presented_index;
+                let Some(item) = ranges.iter().position(|range| range.start == index) else {
+                    return slide_row.into();
+                };
+                let item_index = slide.item_index();
+                let label = match &self.item_origins[item_index].block {
+                    Some(block) => format!("{}. {} (blok {block})", item + 1, item_label(slide)),
+                    None => format!("{}. {}", item + 1, item_label(slide)),
+                };
+                let skip_label = if self.skipped_items.contains(&item_index) {
+                    "Nepřeskakovat"
+                } else {
+                    "Přeskočit"
+                };
+                let mut header = row![
+                    text(label).size(18).width(Length::Fill),
+                    button(skip_label).on_press(Message::ToggleSkipItem(item_index)),
+                ]
+                .spacing(5)
+                .align_y(Alignment::Center);
+                if index > current {
+                    header = header
+                        .push(
+                            button("Dřív").on_press_maybe(
+                                item.checked_sub(1)
+                                    .filter(|&prev| can_swap_with_next(&ranges, current, prev))
+                                    .map(|_| Message::MoveRemainingItemEarlier(item)),
+                            ),
+                        )
+                        .push(
+                            button("Později").on_press_maybe(
+                                can_swap_with_next(&ranges, current, item)
+                                    .then_some(Message::MoveRemainingItemLater(item)),
+                            ),
+                        );
+                }
+
+                column![header, slide_row].spacing(5).into()
+            });
 
         let first_slide_selected = self.is_first_slide_selected();
         let last_slide_selected = self.is_last_slide_selected();
@@ -398,24 +1223,27 @@ impl Presenter {
         };
 
         let style_control = column![
+            self.view_live_preview(),
+            Space::with_height(Length::Fixed(30.0)),
             radio(
-                String::from("Normál (") + MODE_NORMAL_KEY + ")",
+                format!("Normál ({})", self.keymap.normal),
                 PresentationMode::Normal,
                 Some(self.mode),
                 Message::PresentationModeChanged
             ),
             radio(
-                String::from("Prázdný snímek (") + MODE_BLANK_KEY + ")",
+                format!("Prázdný snímek ({})", self.keymap.blank),
                 PresentationMode::Blank,
                 Some(self.mode),
                 Message::PresentationModeChanged
             ),
             radio(
-                String::from("Zmrazit (") + MODE_FREEZE_KEY + ")",
+                format!("Zmrazit ({})", self.keymap.freeze),
                 PresentationMode::Frozen(self.current_presented_index),
                 Some(self.mode),
                 Message::PresentationModeChanged
             ),
+            self.view_language_control(),
             Space::with_height(Length::Fixed(30.0)),
             text("Škálování velikosti textu"),
             row![
@@ -427,12 +1255,26 @@ impl Presenter {
                 button("Resetovat").on_press_maybe(reset_text_size_button_msg)
             ]
             .spacing(5)
-            .align_y(Alignment::Center)
+            .align_y(Alignment::Center),
+            text(format!("Veršů na slajdu: {}", self.verses_per_slide)),
+            slider(
+                1..=MAX_VERSES_PER_SLIDE,
+                self.verses_per_slide,
+                Message::VersesPerSlideChanged
+            ),
+            checkbox(
+                "Po jednom verši (responzoriální čtení)",
+                self.verse_by_verse
+            )
+            .on_toggle(Message::VerseByVerseToggled),
+            Space::with_height(Length::Fixed(30.0)),
+            self.view_slide_override(guest_mode),
         ]
         .spacing(10)
         .padding(30);
 
         let presentation_control = column![
+            checkbox("Klávesové zkratky", self.hotkeys_enabled).on_toggle(Message::HotkeysToggled),
             button("Nahoru")
                 .width(Length::Fill)
                 .on_press_maybe(if first_slide_selected {
@@ -447,16 +1289,109 @@ impl Presenter {
                 } else {
                     Some(Message::RequestNextSlide)
                 }),
-            Space::with_height(Length::Fixed(30.0)),
-            button("Ukončit prezentaci (ESC)")
-                .width(Length::Fill)
-                .style(danger)
-                .on_press(Message::ClosePresentationWindow),
-        ]
-        .spacing(10)
-        .padding(30);
-
-        Into::<Element<Message>>::into(container(
+            row![
+                button("Předchozí položka (PgUp)")
+                    .width(Length::Fill)
+                    .on_press_maybe(self.prev_item_index().map(|_| Message::RequestPrevItem)),
+                button("Další položka (PgDn)")
+                    .width(Length::Fill)
+                    .on_press_maybe(self.next_item_index().map(|_| Message::RequestNextItem)),
+            ]
+            .spacing(5),
+            button(text(format!(
+                "Opakovat část písně ({})",
+                self.keymap.repeat_part
+            )))
+            .width(Length::Fill)
+            .on_press_maybe(
+                song_part_range(&self.playlist_slides, self.current_presented_index)
+                    .map(|_| Message::RepeatSongPart)
+            ),
+            text_input(
+                &format!("Číslo slajdu (1 - {})", self.playlist_slides.len()),
+                &self.goto_input
+            )
+            .on_input(Message::GoToSlideInputChanged)
+            .on_submit(Message::GoToSlide),
+            text(&self.goto_msg).style(text::danger),
+            self.view_slide_search(),
+            text_input(
+                "Pasáž: kniha kapitola verš (43 3 16 = Jan 3:16)",
+                &self.passage_entry
+            )
+            .on_input(Message::PassageEntryChanged)
+            .on_submit(Message::SubmitPassageEntry),
+            text(&self.passage_entry_msg).style(text::danger),
+            self.view_passage_control(),
+            text("Odpočet před začátkem bohoslužby"),
+            row![
+                text_input("HH:MM", &self.countdown_input)
+                    .on_input(Message::CountdownInputChanged)
+                    .on_submit(Message::StartCountdown),
+                button("Spustit").on_press(Message::StartCountdown),
+                button("Začít hned").on_press_maybe(
+                    self.countdown_start
+                        .is_some()
+                        .then_some(Message::SkipCountdown)
+                ),
+            ]
+            .spacing(5),
+            text(&self.countdown_msg).style(text::danger),
+            text("Konec bohoslužby"),
+            row![
+                text_input("HH:MM", &self.service_end_input)
+                    .on_input(Message::ServiceEndInputChanged)
+                    .on_submit(Message::SetServiceEnd),
+                button("Nastavit").on_press(Message::SetServiceEnd),
+                button("Zrušit").on_press_maybe(
+                    self.service_end
+                        .is_some()
+                        .then_some(Message::ClearServiceEnd)
+                ),
+            ]
+            .spacing(5),
+            text(&self.service_end_msg).style(text::danger),
+            button("Uložit pořadí položek do playlistu")
+                .width(Length::Fill)
+                .on_press_maybe(
+                    self.can_save_item_order(guest_mode)
+                        .then_some(Message::SaveItemOrder)
+                ),
+            text(&self.item_order_msg),
+            self.view_macros(),
+            self.view_quick_slides(),
+            Space::with_height(Length::Fixed(30.0)),
+            button("Ukončit prezentaci (ESC)")
+                .width(Length::Fill)
+                .style(danger)
+                .on_press(Message::ClosePresentationWindow),
+        ]
+        .spacing(10)
+        .padding(30);
+
+        let output_lost_banner = if self.output_lost {
+            container(
+                row![
+                    text("Výstup ztracen, prezentační okno bylo zavřeno. Otevřít znovu?")
+                        .size(20)
+                        .width(Length::Fill),
+                    button("Znovu otevřít")
+                        .style(success)
+                        .on_press(Message::OpenPresentationWindow),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+            )
+            .style(container::danger)
+            .padding(15)
+            .width(Length::Fill)
+        } else {
+            container(column![])
+        };
+
+        Into::<Element<Message>>::into(container(column![
+            output_lost_banner,
+            self.view_service_end_warning(),
             row![
                 presentation_control
                     .width(Length::FillPortion(1))
@@ -471,22 +1406,368 @@ impl Presenter {
             .padding(10)
             .height(Length::Fill)
             .align_y(Alignment::Center),
-        ))
+        ]))
+    }
+
+    /// Zkonstruuje varování před koncem bohoslužby, čím blíže je konec, tím je výraznější
+    /// (viz [`ServiceEndUrgency`]). Pokud se konec nehlídá, nezobrazí nic.
+    fn view_service_end_warning(&self) -> Element<Message> {
+        let Some(remaining) = self.service_end_remaining else {
+            return column![].into();
+        };
+
+        let warning = TimeDelta::minutes(self.service_end_settings.warning_minutes().into());
+        let time = format_duration(
+            u32::try_from(remaining.num_seconds().unsigned_abs()).unwrap_or(u32::MAX),
+        );
+        let banner = |content: String| -> Element<Message> {
+            container(text(content).size(24))
+                .style(container::danger)
+                .padding(15)
+                .width(Length::Fill)
+                .into()
+        };
+
+        match service_end_urgency(remaining, warning) {
+            ServiceEndUrgency::Calm => container(text!("Do konce bohoslužby zbývá {time}"))
+                .padding([5, 15])
+                .into(),
+            ServiceEndUrgency::Approaching => container(
+                text!("Do konce bohoslužby zbývá {time}")
+                    .size(20)
+                    .style(text::danger),
+            )
+            .padding([5, 15])
+            .into(),
+            ServiceEndUrgency::Imminent => banner(format!("Bohoslužba končí za {time}!")),
+            ServiceEndUrgency::Overtime => {
+                banner(format!("Čas bohoslužby vypršel, přetahuje se o {time}"))
+            }
+        }
+    }
+
+    /// Vrátí zprávu, kterou se v čase konce bohoslužby provede [`ServiceEndAction`]
+    /// z nastavení. Pokud se nemá nic provést (nebo už je provedeno), vrací `None`.
+    fn service_end_message(&self) -> Option<Message> {
+        match &self.service_end_settings.action {
+            ServiceEndAction::Warn => None,
+            ServiceEndAction::Blank => (self.mode != PresentationMode::Blank)
+                .then_some(Message::PresentationModeChanged(PresentationMode::Blank)),
+            ServiceEndAction::QuickSlide(name) => {
+                let Some(index) = self
+                    .quick_slides
+                    .iter()
+                    .position(|(quick_slide, _)| &quick_slide.name == name)
+                else {
+                    warn!("Rychlý slajd \"{name}\" pro konec bohoslužby neexistuje");
+                    return None;
+                };
+                (!matches!(self.mode, PresentationMode::QuickSlide(shown) if shown == index))
+                    .then_some(Message::ToggleQuickSlide(index))
+            }
+        }
+    }
+
+    /// Zkonstruuje vyhledávání slajdu a pod ním seznam nalezených slajdů (nejvýše
+    /// [`MAX_SEARCH_RESULTS`]), kliknutím na nalezený slajd se na něj přejde
+    fn view_slide_search(&self) -> Element<Message> {
+        let input = text_input(
+            "Hledat slajd: píseň, část (C), verš (Jan 3:16)",
+            &self.slide_search,
+        )
+        .on_input(Message::SlideSearchChanged)
+        .on_submit(Message::SubmitSlideSearch);
+        if self.slide_search.trim().is_empty() {
+            return input.into();
+        }
+
+        let found = search_slides(&self.playlist_slides, &self.slide_search);
+        if found.is_empty() {
+            return column![input, text("Nic nenalezeno")].spacing(5).into();
+        }
+
+        let results = found.iter().take(MAX_SEARCH_RESULTS).map(|&index| {
+            let slide = &self.playlist_slides[index];
+            let label = match slide {
+                Slide::Song(song) => format!("{}: {}", item_label(slide), song.part_name),
+                _ => item_label(slide),
+            };
+            button(text!("{}. {label}", index + 1))
+                .width(Length::Fill)
+                .style(button::text)
+                .on_press_maybe(
+                    (index != self.current_presented_index).then_some(Message::SelectSlide(index)),
+                )
+                .into()
+        });
+        let more = found.len().saturating_sub(MAX_SEARCH_RESULTS);
+
+        let mut search = column![input, column(results)].spacing(5);
+        if more > 0 {
+            search = search.push(text!("… a {more} dalších"));
+        }
+        search.into()
+    }
+
+    /// Zkonstruuje ovládání pasáže zadané během prezentace, pokud se nepromítá, nezobrazí nic
+    fn view_passage_control(&self) -> Element<Message> {
+        let PresentationMode::Passage(passage_index) = self.mode else {
+            return column![].into();
+        };
+
+        let passage = match &self.passage_slides[passage_index] {
+            Slide::Passage(slide) => {
+                let (from, to) = slide.passage_indexes;
+                format!("{from} - {to}")
+            }
+            _ => String::new(),
+        };
+
+        row![
+            text!(
+                "Pasáž {passage} ({}/{})",
+                passage_index + 1,
+                self.passage_slides.len()
+            )
+            .width(Length::Fill),
+            button("Zpět na playlist")
+                .style(danger)
+                .on_press(Message::ClosePassage),
+        ]
+        .spacing(5)
+        .align_y(Alignment::Center)
+        .into()
+    }
+
+    /// Zkonstruuje tlačítka pro spuštění maker, pokud žádná makra nejsou nastavena,
+    /// nezobrazí nic
+    fn view_macros(&self) -> Element<Message> {
+        if self.macros.is_empty() {
+            return column![].into();
+        }
+
+        let macro_buttons = self
+            .macros
+            .iter()
+            .enumerate()
+            .map(|(index, presenter_macro)| {
+                button(text!("{} ({})", presenter_macro.name, presenter_macro.key))
+                    .width(Length::Fill)
+                    .on_press(Message::RunMacro(index))
+                    .into()
+            });
+
+        column![
+            Space::with_height(Length::Fixed(20.0)),
+            text("Makra"),
+            column(macro_buttons).spacing(5),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// Zkonstruuje tlačítka pro promítnutí rychlých slajdů, promítaný slajd je zvýrazněn
+    /// a jeho tlačítko vrací zpět. Pokud žádné rychlé slajdy nejsou nastaveny, nezobrazí nic.
+    fn view_quick_slides(&self) -> Element<Message> {
+        if self.quick_slides.is_empty() {
+            return column![].into();
+        }
+
+        let quick_slide_buttons =
+            self.quick_slides
+                .iter()
+                .enumerate()
+                .map(|(index, (quick_slide, _))| {
+                    // Rovnost režimů nerozlišuje index rychlého slajdu, porovnáváme ručně
+                    let shown =
+                        matches!(self.mode, PresentationMode::QuickSlide(shown) if shown == index);
+                    let style: fn(&iced::Theme, button::Status) -> button::Style =
+                        if shown { danger } else { button::primary };
+                    button(text!("{} ({})", quick_slide.name, quick_slide.key))
+                        .width(Length::Fill)
+                        .style(style)
+                        .on_press(Message::ToggleQuickSlide(index))
+                        .into()
+                });
+
+        column![
+            Space::with_height(Length::Fixed(20.0)),
+            text("Rychlé slajdy"),
+            column(quick_slide_buttons).spacing(5),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// Zkonstruuje přepínač jazyka písní, pokud žádná píseň v playlistu nemá překlad,
+    /// nezobrazí nic
+    fn view_language_control(&self) -> Element<Message> {
+        let has_translation = self
+            .playlist_slides
+            .iter()
+            .any(|slide| matches!(slide, Slide::Song(song) if song.translation.is_some()));
+        if !has_translation {
+            return column![].into();
+        }
+
+        column![
+            Space::with_height(Length::Fixed(20.0)),
+            text(format!("Jazyk písní ({})", self.keymap.language)),
+            radio(
+                "Originál",
+                LanguageMode::Original,
+                Some(self.language),
+                Message::LanguageModeChanged
+            ),
+            radio(
+                "Překlad",
+                LanguageMode::Translation,
+                Some(self.language),
+                Message::LanguageModeChanged
+            ),
+            radio(
+                "Obojí",
+                LanguageMode::Bilingual,
+                Some(self.language),
+                Message::LanguageModeChanged
+            ),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// Zkonstruuje ovládání úprav vybraného slajdu
+    fn view_slide_override(&self, guest_mode: bool) -> Element<Message> {
+        let slide_override = &self.slide_overrides[self.current_presented_index];
+
+        column![
+            text("Úpravy vybraného slajdu"),
+            text("Velikost textu"),
+            slider(
+                u8::MIN..=u8::MAX,
+                slide_override.text_scale.unwrap_or(self.text_scale),
+                Message::OverrideTextScaleChanged
+            ),
+            pick_list(
+                SlideAlignment::ALL,
+                slide_override.alignment,
+                Message::OverrideAlignmentPicked
+            )
+            .placeholder("Zarovnání textu")
+            .width(Length::Fill),
+            text_input("Barva pozadí (#rrggbb)", &self.override_background_input)
+                .on_input(Message::OverrideBackgroundChanged),
+            row![
+                button("Uložit úpravy")
+                    .style(success)
+                    .on_press_maybe((!guest_mode).then_some(Message::SaveSlideOverride)),
+                button("Zrušit úpravy").on_press_maybe(if slide_override.is_empty() {
+                    None
+                } else {
+                    Some(Message::ResetSlideOverride)
+                }),
+            ]
+            .spacing(5),
+            text(&self.override_msg),
+            row![
+                pick_list(
+                    slide_export::RESOLUTIONS,
+                    Some(self.export_resolution),
+                    Message::ExportResolutionPicked
+                ),
+                // Slajd se fotí z prezentačního okna, musí na něm tedy být vidět
+                button("Uložit slajd jako obrázek…").on_press_maybe(
+                    (self.mode == PresentationMode::Normal
+                        && self.presentation_window_id.is_some())
+                    .then_some(Message::ExportSlide)
+                ),
+            ]
+            .spacing(5)
+            .align_y(Alignment::Center),
+            text(&self.export_msg),
+        ]
+        .spacing(10)
+        .into()
     }
 
     /// Zkonstruuuje GUI pro prezentační okno
     pub fn view_presentation(&self) -> Element<Message> {
-        let text_size_multiplier = normalize_text_multiplier(self.text_scale);
+        self.render_presented(slide_render::REFERENCE_SIZE)
+    }
+
+    /// Vykreslí to, co se právě promítá (podle režimu prezentace), ve velikosti `size`
+    fn render_presented(&self, size: Size) -> Element<Message> {
+        let current_slide = &self.playlist_slides[self.current_presented_index];
 
-        match self.mode {
-            PresentationMode::Normal => {
-                self.playlist_slides[self.current_presented_index].present(text_size_multiplier)
+        let rendered_index = match self.mode {
+            PresentationMode::Normal => self.current_presented_index,
+            PresentationMode::Blank => return slide_render::render_blank(current_slide.theme()),
+            PresentationMode::Frozen(frozen_index) => frozen_index,
+            PresentationMode::QuickSlide(quick_index) => {
+                let (_, quick_slide) = &self.quick_slides[quick_index];
+                let style = SlideStyle::new(
+                    quick_slide,
+                    normalize_text_multiplier(self.text_scale),
+                    &SlideOverride::default(),
+                );
+                return slide_render::render(quick_slide, &style, size);
             }
-            PresentationMode::Blank => blank_slide(),
-            PresentationMode::Frozen(frozen_index) => {
-                self.playlist_slides[frozen_index].present(text_size_multiplier)
+            PresentationMode::Passage(passage_index) => {
+                let passage_slide = &self.passage_slides[passage_index];
+                let style = SlideStyle::new(
+                    passage_slide,
+                    normalize_text_multiplier(self.text_scale),
+                    &SlideOverride::default(),
+                );
+                return slide_render::render(passage_slide, &style, size);
             }
-        }
+            PresentationMode::Countdown => {
+                let countdown_slide = self.countdown_slide();
+                let style = SlideStyle::new(
+                    &countdown_slide,
+                    normalize_text_multiplier(self.text_scale),
+                    &SlideOverride::default(),
+                );
+                return slide_render::render(&countdown_slide, &style, size);
+            }
+        };
+
+        slide_render::render(
+            &self.playlist_slides[rendered_index],
+            &self.slide_style(rendered_index),
+            size,
+        )
+    }
+
+    /// Zmenšený náhled toho, co právě vidí shromáždění, a slajdu, který přijde na řadu
+    /// po přechodu dolů, aby obsluha nemusela kontrolovat projektor
+    fn view_live_preview(&self) -> Element<Message> {
+        let preview = |content: Element<'_, Message>| {
+            container(content)
+                .width(Length::Fixed(LIVE_PREVIEW_SIZE.width))
+                .height(Length::Fixed(LIVE_PREVIEW_SIZE.height))
+                .clip(true)
+        };
+
+        let next = match self.next_presented_index() {
+            Some(next_index) => column![
+                text("Další"),
+                preview(slide_render::render(
+                    &self.playlist_slides[next_index],
+                    &self.slide_style(next_index),
+                    LIVE_PREVIEW_SIZE
+                )),
+            ],
+            None => column![text("Další: konec playlistu")],
+        };
+
+        column![
+            text("Promítá se"),
+            preview(self.render_presented(LIVE_PREVIEW_SIZE)),
+            next.spacing(5),
+        ]
+        .spacing(5)
+        .into()
     }
 
     pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
@@ -498,26 +1779,347 @@ impl Presenter {
         match msg {
             Message::SelectSlide(index) => {
                 debug!("Vybírám slajd s indexem {index}");
+                let item_changed = presenter.playlist_slides[presenter.current_presented_index]
+                    .item_index()
+                    != presenter.playlist_slides[index].item_index();
                 presenter.current_presented_index = index;
+                presenter.override_background_input =
+                    background_input(&presenter.slide_overrides[index]);
+                presenter.override_msg.clear();
+                Task::batch([
+                    presenter.log_current_item(&state.db),
+                    presenter.capture_thumbnail(),
+                    presenter.notify_plugins(&state.plugins),
+                    presenter.emit_event(
+                        &state.config.settings.event_hooks,
+                        state.mqtt.as_ref(),
+                        PresentationEvent::SlideChanged,
+                    ),
+                    if item_changed {
+                        presenter.send_lighting_cue(
+                            &state.config.settings.lighting,
+                            state.config.is_offline(),
+                        )
+                    } else {
+                        Task::none()
+                    },
+                ])
+            }
+            Message::GoToSlideInputChanged(input) => {
+                trace!("Změnil se vstup pro skok na slajd: {input}");
+                presenter.goto_input = input;
+                presenter.goto_msg.clear();
+                Task::none()
+            }
+            Message::GoToSlide => {
+                match slide_index_from_input(&presenter.goto_input, presenter.playlist_slides.len())
+                {
+                    Some(index) => {
+                        debug!("Skáču na slajd s indexem {index}");
+                        presenter.goto_input.clear();
+                        Task::done(Message::SelectSlide(index).into())
+                    }
+                    None => {
+                        presenter.goto_msg = format!(
+                            "Slajd '{}' neexistuje, zadej číslo 1 až {}",
+                            presenter.goto_input.trim(),
+                            presenter.playlist_slides.len()
+                        );
+                        Task::none()
+                    }
+                }
+            }
+            Message::SlideSearchChanged(query) => {
+                trace!("Změnil se hledaný výraz slajdu: {query}");
+                presenter.slide_search = query;
+                Task::none()
+            }
+            Message::SubmitSlideSearch => {
+                let found = search_slides(&presenter.playlist_slides, &presenter.slide_search);
+                let current = presenter.current_presented_index;
+                match found
+                    .iter()
+                    .find(|&&index| index > current)
+                    .or(found.first())
+                {
+                    Some(&index) => {
+                        debug!(
+                            "Skáču na nalezený slajd s indexem {index} ('{}')",
+                            presenter.slide_search
+                        );
+                        Task::done(Message::SelectSlide(index).into())
+                    }
+                    None => Task::none(),
+                }
+            }
+            Message::OverrideTextScaleChanged(scale) => {
+                trace!("Měním velikost textu vybraného slajdu na {scale}");
+                presenter.slide_overrides[presenter.current_presented_index].text_scale =
+                    Some(scale);
+                Task::none()
+            }
+            Message::OverrideAlignmentPicked(alignment) => {
+                debug!("Měním zarovnání vybraného slajdu na {:?}", alignment);
+                presenter.slide_overrides[presenter.current_presented_index].alignment =
+                    Some(alignment);
+                Task::none()
+            }
+            Message::OverrideBackgroundChanged(input) => {
+                trace!("Změnila se barva pozadí vybraného slajdu: {input}");
+                // Nevalidní (rozepsanou) barvu ignorujeme, prázdný vstup úpravu zruší
+                let index = presenter.current_presented_index;
+                let slide_override = &mut presenter.slide_overrides[index];
+                if input.trim().is_empty() {
+                    slide_override.background_color = None;
+                } else if let Some(color) = hex_to_rgb(&input) {
+                    slide_override.background_color = Some(color);
+                }
+                presenter.override_background_input = input;
+                Task::none()
+            }
+            Message::ResetSlideOverride => {
+                debug!("Ruším úpravy vybraného slajdu");
+                presenter.slide_overrides[presenter.current_presented_index] =
+                    SlideOverride::default();
+                presenter.override_background_input.clear();
+                Task::done(Message::SaveSlideOverride.into())
+            }
+            Message::SaveSlideOverride => {
+                let index = presenter.current_presented_index;
+                let keys = slide_keys(&presenter.playlist_slides);
+                let (item_index, slide_index) = keys[index];
+                let origin = &presenter.item_origins[item_index];
+                if let Some(block) = &origin.block {
+                    presenter.override_msg =
+                        format!("Slajdy bloku {block} nelze upravit, upravte je v jeho playlistu");
+                    return Task::none();
+                }
+                let part_order = origin.position;
+                debug!("Ukládám úpravu slajdu {slide_index} položky {part_order}");
+                let slide_override = presenter.slide_overrides[index].clone();
+                // Kopie zopakované části sdílí úpravy s původním slajdem
+                for (key, live_override) in keys.iter().zip(&mut presenter.slide_overrides) {
+                    if *key == keys[index] {
+                        *live_override = slide_override.clone();
+                    }
+                }
+                let playlist_id = presenter.log.get_playlist_id();
+                let conn = state.db.acquire();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
+                        slide_override::save(
+                            playlist_id,
+                            part_order,
+                            slide_index,
+                            &slide_override,
+                            &mut conn,
+                        )
+                        .await
+                    },
+                    |res| match res {
+                        Ok(_) => Message::SlideOverrideSaved.into(),
+                        Err(e) => Message::SlideOverrideSaveFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::SlideOverrideSaved => {
+                debug!("Úprava slajdu uložena");
+                presenter.override_msg = String::from("Úpravy uloženy");
+                presenter.capture_thumbnail()
+            }
+            Message::LoadThumbnails => {
+                let Some(dir) = presenter.thumbnails.get_dir().map(PathBuf::from) else {
+                    return Task::none();
+                };
+                debug!("Načítám náhledy slajdů z {}", dir.display());
+                let keys = (0..presenter.playlist_slides.len())
+                    .map(|index| presenter.thumbnail_key(index))
+                    .filter(|key| !presenter.thumbnails.contains(*key))
+                    .collect();
+                Task::perform(thumbnail_cache::load_from_disk(dir, keys), |thumbnails| {
+                    Message::ThumbnailsLoaded(thumbnails).into()
+                })
+            }
+            Message::ThumbnailsLoaded(thumbnails) => {
+                debug!("Načteno {} náhledů slajdů", thumbnails.len());
+                for (key, png) in thumbnails {
+                    presenter.thumbnails.insert(key, png);
+                }
+                Task::none()
+            }
+            Message::ScreenshotTaken(key, screenshot) => {
+                // Mezitím se mohl slajd/režim změnit, pak by snímek neodpovídal klíči
+                let current_key = presenter.thumbnail_key(presenter.current_presented_index);
+                if presenter.mode != PresentationMode::Normal || current_key != key {
+                    trace!("Snímek pro náhled {key:016x} je zastaralý, zahazuji");
+                    return Task::none();
+                }
+
+                let dir = presenter.thumbnails.get_dir().map(PathBuf::from);
+                Task::perform(
+                    thumbnail_cache::encode_and_store(dir, key, screenshot),
+                    move |res| match res {
+                        Ok(png) => Message::ThumbnailGenerated(key, png).into(),
+                        Err(e) => Message::ThumbnailFailed(format!("{:?}", e)).into(),
+                    },
+                )
+            }
+            Message::ThumbnailGenerated(key, png) => {
+                presenter.thumbnails.insert(key, png);
+                Task::none()
+            }
+            Message::ThumbnailFailed(err_msg) => {
+                warn!("Nelze vygenerovat náhled slajdu: {err_msg}");
+                Task::none()
+            }
+            Message::CaptureCompanionImage => {
+                match presenter.presentation_window_id {
+                    // Pomalý disk nesmí hromadit rozepsané snímky
+                    Some(id) if !presenter.companion_image_busy => {
+                        presenter.companion_image_busy = true;
+                        iced::window::screenshot(id)
+                            .map(|screenshot| Message::CompanionImageCaptured(screenshot).into())
+                    }
+                    _ => Task::none(),
+                }
+            }
+            Message::CompanionImageCaptured(screenshot) => {
+                let Some(path) = presenter.companion_image_path.clone() else {
+                    presenter.companion_image_busy = false;
+                    return Task::none();
+                };
+                Task::perform(companion_image::write(path, screenshot), |res| match res {
+                    Ok(_) => Message::CompanionImageWritten.into(),
+                    Err(e) => Message::CompanionImageFailed(format!("{:#}", e)).into(),
+                })
+            }
+            Message::CompanionImageWritten => {
+                presenter.companion_image_busy = false;
+                Task::none()
+            }
+            Message::CompanionImageFailed(err_msg) => {
+                warn!("Nelze zapsat obrázek prezentace: {err_msg}");
+                presenter.companion_image_busy = false;
+                Task::none()
+            }
+            Message::ExportResolutionPicked(resolution) => {
+                presenter.export_resolution = resolution;
+                Task::none()
+            }
+            Message::ExportSlide => Task::perform(
+                rfd::AsyncFileDialog::new()
+                    .add_filter("Obrázek PNG", &["png"])
+                    .set_file_name(slide_export::DEFAULT_FILE_NAME)
+                    .save_file(),
+                |file| match file {
+                    Some(file) => Message::SlideExportPathPicked(file.path().to_path_buf()).into(),
+                    None => Message::DialogCancelled.into(),
+                },
+            ),
+            Message::SlideExportPathPicked(path) => match presenter.presentation_window_id {
+                Some(id) => iced::window::screenshot(id).map(move |screenshot| {
+                    Message::SlideExportCaptured(path.clone(), screenshot).into()
+                }),
+                None => {
+                    presenter.export_msg = String::from("Prezentační okno není otevřené");
+                    Task::none()
+                }
+            },
+            Message::SlideExportCaptured(path, screenshot) => {
+                let resolution = presenter.export_resolution;
+                Task::perform(
+                    slide_export::save(path.clone(), screenshot, resolution),
+                    move |res| match res {
+                        Ok(_) => Message::SlideExported(path).into(),
+                        Err(e) => Message::SlideExportFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::SlideExported(path) => {
+                presenter.export_msg = format!("Slajd uložen do {}", path.display());
+                Task::none()
+            }
+            Message::SlideExportFailed(err_msg) => {
+                warn!("Nelze uložit slajd jako obrázek: {err_msg}");
+                presenter.export_msg = err_msg;
+                Task::none()
+            }
+            Message::DialogCancelled => {
+                debug!("Dialog pro výběr souboru zrušen");
+                Task::none()
+            }
+            Message::SlideOverrideSaveFailed(err_msg) => {
+                warn!("Nelze uložit úpravu slajdu: {err_msg}");
+                presenter.override_msg = err_msg;
                 Task::none()
             }
             Message::ClosePresentationWindow => {
                 debug!("Ukončuji prezentaci, vracím se na seznam playlistů");
-                iced::window::close(
-                    presenter
-                        .presentation_window_id
-                        .expect("Nelze zavřít prezentační okno, pokud nebylo otevřeno"),
-                )
-                .chain(Task::done(Message::PresentationWindowClosed.into()))
+                // Poznamenáme si, že okno zavíráme sami, aby událost o zavření okna
+                // nebyla vyhodnocena jako ztráta výstupu
+                presenter.closing = true;
+                match presenter.presentation_window_id {
+                    Some(id) => iced::window::close(id)
+                        .chain(Task::done(Message::PresentationWindowClosed.into())),
+                    // Okno už bylo zavřeno zvenčí, není co zavírat
+                    None => Task::done(Message::PresentationWindowClosed.into()),
+                }
+            }
+            Message::PresentationWindowLost => {
+                warn!("Prezentační okno bylo zavřeno zvenčí, nabízím znovuotevření");
+                presenter.presentation_window_id = None;
+                presenter.sleep_inhibitor = None;
+                presenter.output_lost = true;
+                Task::none()
             }
             Message::PresentationWindowClosed => {
-                state.screen = Screen::PickPlaylist(PlaylistPicker::new());
-                Task::done(crate::pick_playlist::Message::LoadPlaylists.into())
+                let event = presenter.emit_event(
+                    &state.config.settings.event_hooks,
+                    state.mqtt.as_ref(),
+                    PresentationEvent::Ended,
+                );
+                presenter.sleep_inhibitor = None;
+                presenter.log.finish();
+                let measured_items = presenter.measured_items();
+                let log = presenter.log.clone();
+                let conn = state.db.acquire();
+
+                // Pokud se nic nepromítalo, není co nabízet k uložení
+                let show_summary = !measured_items.is_empty();
+                state.screen = if show_summary {
+                    Screen::ServiceSummary(ServiceSummary::new(
+                        log.get_playlist_id(),
+                        measured_items,
+                    ))
+                } else {
+                    Screen::PickPlaylist(PlaylistPicker::new())
+                };
+
+                Task::batch([
+                    event,
+                    Task::perform(flush_log(log, conn), move |res| {
+                        if let Err(e) = res {
+                            warn!("Nelze uložit záznam o prezentaci: {:?}", e);
+                        }
+
+                        if show_summary {
+                            crate::service_summary::Message::LogSaved.into()
+                        } else {
+                            crate::pick_playlist::Message::LoadPlaylists.into()
+                        }
+                    }),
+                ])
             }
             Message::OpenPresentationWindow => {
                 debug!("Otevírám prezentační okno");
+                let position = match state.config.settings.monitor.position {
+                    Some((x, y)) => Position::Specific(Point::new(x, y)),
+                    None => Position::default(),
+                };
                 let (id, task) = iced::window::open(Settings {
                     fullscreen: true,
+                    position,
                     ..Settings::default()
                 });
                 presenter.presentation_window_id = Some(id);
@@ -526,34 +2128,225 @@ impl Presenter {
             Message::PresentationWindowOpened(id) => {
                 debug!("Prezentační okno otevřeno pod id {id}");
                 presenter.presentation_window_id = Some(id);
-                Task::none()
+                // Znovuotevření okna po jeho ztrátě není začátek prezentace
+                let started = !presenter.output_lost;
+                presenter.output_lost = false;
+                let log_task = presenter.log_current_item(&state.db);
+                presenter.sleep_inhibitor = match SleepInhibitor::try_new() {
+                    Ok(inhibitor) => Some(Arc::new(inhibitor)),
+                    Err(e) => {
+                        warn!("Nelze zablokovat spořič obrazovky: {:?}", e);
+                        None
+                    }
+                };
+                let event = if started {
+                    Task::batch([
+                        presenter.emit_event(
+                            &state.config.settings.event_hooks,
+                            state.mqtt.as_ref(),
+                            PresentationEvent::Started,
+                        ),
+                        presenter.send_lighting_cue(
+                            &state.config.settings.lighting,
+                            state.config.is_offline(),
+                        ),
+                    ])
+                } else {
+                    Task::none()
+                };
+                Task::batch([
+                    Task::done(Message::LoadThumbnails.into()),
+                    presenter.capture_thumbnail(),
+                    log_task,
+                    event,
+                ])
             }
             Message::PresentationModeChanged(presentation_mode) => {
                 debug!("Nastavuji prezentační režim na {:?}", presentation_mode);
+                let changed = presenter.mode != presentation_mode;
                 presenter.mode = presentation_mode;
-                Task::none()
+                // Přepnutím do jiného režimu převzala obsluha prezentaci, odpočet se ruší
+                // (rychlý slajd ani zadaná pasáž jej neruší, po nich se k odpočtu vrátí)
+                if matches!(
+                    presentation_mode,
+                    PresentationMode::Normal
+                        | PresentationMode::Blank
+                        | PresentationMode::Frozen(_)
+                ) && presenter.countdown_start.take().is_some()
+                {
+                    debug!("Ruším odpočet před začátkem bohoslužby");
+                }
+
+                let mut events = Vec::new();
+                if changed {
+                    events.push(PresentationEvent::ModeChanged);
+                    if presentation_mode == PresentationMode::Blank {
+                        events.push(PresentationEvent::Blanked);
+                    }
+                }
+                let hooks = &state.config.settings.event_hooks;
+                let events = events
+                    .into_iter()
+                    .map(|event| presenter.emit_event(hooks, state.mqtt.as_ref(), event));
+                Task::batch(events.chain([presenter.notify_plugins(&state.plugins)]))
+            }
+            Message::LanguageModeChanged(language) => {
+                debug!("Nastavuji jazyk písní na {:?}", language);
+                presenter.language = language;
+                presenter.capture_thumbnail()
+            }
+            Message::CycleLanguageMode => {
+                Task::done(Message::LanguageModeChanged(presenter.language.next()).into())
             }
             Message::TextSizeMultiplierChanged(multiplier) => {
                 debug!("Nastavuji multiplikátor velikosti textu na {multiplier}");
                 presenter.text_scale = multiplier;
                 Task::none()
             }
+            Message::VersesPerSlideChanged(verses_per_slide) => {
+                if verses_per_slide == presenter.verses_per_slide {
+                    return Task::none();
+                }
+                debug!("Nastavuji počet veršů na slajdu na {verses_per_slide}");
+                presenter.rechunk(verses_per_slide);
+                Task::batch([
+                    Task::done(Message::LoadThumbnails.into()),
+                    presenter.capture_thumbnail(),
+                ])
+            }
+            Message::ToggleSkipItem(item_index) => {
+                if presenter.skipped_items.remove(&item_index) {
+                    debug!("Položka {item_index} se již nepřeskakuje");
+                } else {
+                    debug!("Přeskakuji položku {item_index}");
+                    presenter.skipped_items.insert(item_index);
+                }
+                Task::none()
+            }
+            Message::MoveRemainingItemEarlier(item) => {
+                if let Some(prev) = item.checked_sub(1) {
+                    presenter.swap_with_next_item(prev);
+                }
+                Task::none()
+            }
+            Message::MoveRemainingItemLater(item) => {
+                presenter.swap_with_next_item(item);
+                Task::none()
+            }
+            Message::SaveItemOrder => {
+                let order = item_order(&presenter.playlist_slides);
+                let playlist_id = presenter.log.get_playlist_id();
+                debug!("Ukládám pořadí položek {order:?} do playlistu {playlist_id}");
+                let db = state.db.clone();
+                Task::perform(
+                    async move {
+                        let conn = db
+                            .acquire()
+                            .await
+                            .context("Nelze získat připojení k databázi")?;
+                        let mut playlist = PlaylistMetadata::load(playlist_id, conn).await?;
+                        playlist.reorder_items(&order)?;
+
+                        let mut conn = db
+                            .acquire()
+                            .await
+                            .context("Nelze získat připojení k databázi")?;
+                        playlist.save(&mut conn).await.map(|_| order)
+                    },
+                    |res| match res {
+                        Ok(order) => Message::ItemOrderSaved(order).into(),
+                        Err(e) => Message::ItemOrderSaveFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::ItemOrderSaved(order) => {
+                debug!("Pořadí položek uloženo");
+                presenter.renumber_items(&order);
+                presenter.item_order_msg = String::from("Pořadí uloženo");
+                Task::none()
+            }
+            Message::ItemOrderSaveFailed(err_msg) => {
+                warn!("Nelze uložit pořadí položek: {err_msg}");
+                presenter.item_order_msg = err_msg;
+                Task::none()
+            }
+            Message::RepeatSongPart => {
+                match song_part_range(
+                    &presenter.playlist_slides,
+                    presenter.current_presented_index,
+                ) {
+                    Some(range) => {
+                        debug!("Opakuji část písně (slajdy {range:?})");
+                        repeat_slides(
+                            &mut presenter.playlist_slides,
+                            &mut presenter.slide_overrides,
+                            range.clone(),
+                        );
+                        if let PresentationMode::Frozen(index) = presenter.mode
+                            && index >= range.end
+                        {
+                            presenter.mode = PresentationMode::Frozen(index + range.len());
+                        }
+                    }
+                    None => debug!("Vybraný slajd není z písně, není co opakovat"),
+                }
+                Task::none()
+            }
+            Message::VerseByVerseToggled(verse_by_verse) => {
+                debug!("Promítání pasáží po jednom verši: {verse_by_verse}");
+                presenter.verse_by_verse = verse_by_verse;
+                presenter.rechunk(presenter.verses_per_slide);
+                Task::batch([
+                    Task::done(Message::LoadThumbnails.into()),
+                    presenter.capture_thumbnail(),
+                ])
+            }
             Message::RequestPrevSlide => {
                 debug!("Požadavek k přechodu na předchozí slajd");
-                if presenter.is_first_slide_selected() {
-                    Task::none()
-                } else {
-                    let new_slide_index = presenter.current_presented_index - 1;
-                    Task::done(Message::SelectSlide(new_slide_index).into())
+                // Během promítání zadané pasáže se posouvá v ní
+                if let PresentationMode::Passage(passage_index) = presenter.mode {
+                    return match prev_slide_index(passage_index) {
+                        Some(index) => Task::done(Message::SelectPassageSlide(index).into()),
+                        None => Task::none(),
+                    };
+                }
+                match presenter.prev_presented_index() {
+                    Some(index) => Task::done(Message::SelectSlide(index).into()),
+                    None => Task::none(),
                 }
             }
             Message::RequestNextSlide => {
                 debug!("Požadavek k přechodu na následující slajd");
-                if presenter.is_last_slide_selected() {
-                    Task::none()
-                } else {
-                    let new_slide_index = presenter.current_presented_index + 1;
-                    Task::done(Message::SelectSlide(new_slide_index).into())
+                if let PresentationMode::Passage(passage_index) = presenter.mode {
+                    return match next_slide_index(passage_index, presenter.passage_slides.len()) {
+                        Some(index) => Task::done(Message::SelectPassageSlide(index).into()),
+                        None => Task::none(),
+                    };
+                }
+                match presenter.next_presented_index() {
+                    Some(index) => Task::done(Message::SelectSlide(index).into()),
+                    None => Task::none(),
+                }
+            }
+            Message::RequestPrevItem => {
+                debug!("Požadavek k přechodu na předchozí položku");
+                // Zadaná pasáž není položkou playlistu, mezi položkami se v ní nepřeskakuje
+                if let PresentationMode::Passage(_) = presenter.mode {
+                    return Task::none();
+                }
+                match presenter.prev_item_index() {
+                    Some(index) => Task::done(Message::SelectSlide(index).into()),
+                    None => Task::none(),
+                }
+            }
+            Message::RequestNextItem => {
+                debug!("Požadavek k přechodu na následující položku");
+                if let PresentationMode::Passage(_) = presenter.mode {
+                    return Task::none();
+                }
+                match presenter.next_item_index() {
+                    Some(index) => Task::done(Message::SelectSlide(index).into()),
+                    None => Task::none(),
                 }
             }
             Message::FreezePresentation => {
@@ -564,40 +2357,899 @@ impl Presenter {
                         .into(),
                 )
             }
+            Message::HotkeysToggled(enabled) => {
+                debug!("Klávesové zkratky prezentéru: {enabled}");
+                presenter.hotkeys_enabled = enabled;
+                Task::none()
+            }
+            Message::KeyPressed(key) => match presenter.key_to_message(key) {
+                Some(msg) => Task::done(msg.into()),
+                None => Task::none(),
+            },
+            Message::ToggleQuickSlide(quick_index) => {
+                match presenter.mode {
+                    PresentationMode::QuickSlide(shown_index) if shown_index == quick_index => {
+                        debug!("Skrývám rychlý slajd {quick_index}");
+                        presenter.mode = presenter.mode_before_quick_slide;
+                    }
+                    PresentationMode::QuickSlide(_) => {
+                        debug!("Přepínám na rychlý slajd {quick_index}");
+                        presenter.mode = PresentationMode::QuickSlide(quick_index);
+                    }
+                    mode => {
+                        debug!("Promítám rychlý slajd {quick_index}");
+                        presenter.mode_before_quick_slide = mode;
+                        presenter.mode = PresentationMode::QuickSlide(quick_index);
+                    }
+                }
+                Task::batch([
+                    presenter.notify_plugins(&state.plugins),
+                    presenter.emit_event(
+                        &state.config.settings.event_hooks,
+                        state.mqtt.as_ref(),
+                        PresentationEvent::ModeChanged,
+                    ),
+                ])
+            }
+            Message::PassageEntryChanged(input) => {
+                presenter.passage_entry = input;
+                Task::none()
+            }
+            Message::PassageEntryKey(c) => {
+                presenter.passage_entry.push(c);
+                Task::none()
+            }
+            Message::PassageEntryBackspace => {
+                presenter.passage_entry.pop();
+                Task::none()
+            }
+            Message::SubmitPassageEntry => {
+                let (from, to) = match parse_numeric_reference(&presenter.passage_entry) {
+                    Ok(range) => range,
+                    Err(e) => {
+                        presenter.passage_entry_msg = format!("{:#}", e);
+                        presenter.passage_entry.clear();
+                        return Task::none();
+                    }
+                };
+
+                debug!("Načítám zadanou pasáž {from} - {to}");
+                let conn = state.db.acquire();
+                let default_translation = state.config.settings.default_translation.clone();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await?;
+                        let translations = get_available_translations(&mut conn).await?;
+                        let (translation_id, _) = translations
+                            .iter()
+                            .find(|(_, name)| Some(name) == default_translation.as_ref())
+                            .or(translations.first())
+                            .context("V databázi není žádný překlad Bible")?;
+                        Passage::load(from, to, *translation_id, &mut conn).await
+                    },
+                    |res| match res {
+                        Ok(passage) => Message::PassageLoaded(passage).into(),
+                        Err(e) => Message::PassageLoadFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::PassageLoaded(passage) => {
+                let verses_per_slide = if presenter.verse_by_verse {
+                    1
+                } else {
+                    presenter.verses_per_slide.into()
+                };
+                let item_index =
+                    presenter.playlist_slides[presenter.current_presented_index].item_index();
+                presenter.passage_slides = passage_to_slides(
+                    &passage,
+                    &default_slide_breaks(passage.get_verses().len(), verses_per_slide),
+                    state.config.settings.passage_headings,
+                    item_index,
+                    &state.config.settings.theme,
+                );
+                if !matches!(presenter.mode, PresentationMode::Passage(_)) {
+                    presenter.mode_before_passage = presenter.mode;
+                }
+                presenter.passage_entry.clear();
+                presenter.passage_entry_msg.clear();
+                Task::done(Message::PresentationModeChanged(PresentationMode::Passage(0)).into())
+            }
+            Message::PassageLoadFailed(e) => {
+                warn!("Nelze načíst zadanou pasáž: {e}");
+                presenter.passage_entry_msg = e;
+                presenter.passage_entry.clear();
+                Task::none()
+            }
+            Message::SelectPassageSlide(index) => {
+                debug!("Promítám slajd {index} zadané pasáže");
+                presenter.mode = PresentationMode::Passage(index);
+                presenter.notify_plugins(&state.plugins)
+            }
+            Message::ClosePassage => {
+                debug!("Ukončuji promítání zadané pasáže");
+                Task::done(Message::PresentationModeChanged(presenter.mode_before_passage).into())
+            }
+            Message::ServiceEndInputChanged(input) => {
+                presenter.service_end_input = input;
+                Task::none()
+            }
+            Message::SetServiceEnd => {
+                let now = Local::now().naive_local();
+                match time_today_from_input(&presenter.service_end_input, now) {
+                    Some(end) => {
+                        debug!("Hlídám konec bohoslužby v {end}");
+                        presenter.service_end = Some(end);
+                        presenter.service_end_remaining = Some(end - now);
+                        presenter.service_end_reached = false;
+                        presenter.service_end_msg.clear();
+                    }
+                    None => {
+                        presenter.service_end_msg = format!(
+                            "Neplatný čas \"{}\", zadej jej ve tvaru HH:MM",
+                            presenter.service_end_input.trim()
+                        );
+                    }
+                }
+                Task::none()
+            }
+            Message::ClearServiceEnd => {
+                debug!("Přestávám hlídat konec bohoslužby");
+                presenter.service_end = None;
+                presenter.service_end_remaining = None;
+                presenter.service_end_reached = false;
+                Task::none()
+            }
+            Message::ServiceEndTick => {
+                let Some(end) = presenter.service_end else {
+                    return Task::none();
+                };
+                let remaining = end - Local::now().naive_local();
+                presenter.service_end_remaining = Some(remaining);
+                if remaining > TimeDelta::zero() || presenter.service_end_reached {
+                    return Task::none();
+                }
+
+                debug!("Nastal konec bohoslužby");
+                presenter.service_end_reached = true;
+                match presenter.service_end_message() {
+                    Some(msg) => Task::done(msg.into()),
+                    None => Task::none(),
+                }
+            }
+            Message::CountdownInputChanged(input) => {
+                presenter.countdown_input = input;
+                Task::none()
+            }
+            Message::StartCountdown => {
+                let now = Local::now().naive_local();
+                match time_today_from_input(&presenter.countdown_input, now) {
+                    Some(start) if start > now => {
+                        debug!("Spouštím odpočet do začátku bohoslužby v {start}");
+                        presenter.countdown_start = Some(start);
+                        presenter.countdown_remaining = start - now;
+                        presenter.countdown_msg.clear();
+                        Task::done(
+                            Message::PresentationModeChanged(PresentationMode::Countdown).into(),
+                        )
+                    }
+                    Some(start) => {
+                        presenter.countdown_msg =
+                            format!("Čas začátku {} již uplynul", start.format("%H:%M"));
+                        Task::none()
+                    }
+                    None => {
+                        presenter.countdown_msg = format!(
+                            "Neplatný čas \"{}\", zadej jej ve tvaru HH:MM",
+                            presenter.countdown_input.trim()
+                        );
+                        Task::none()
+                    }
+                }
+            }
+            Message::SkipCountdown => {
+                debug!("Ukončuji odpočet předčasně");
+                presenter.finish_countdown()
+            }
+            Message::CountdownTick => {
+                let Some(start) = presenter.countdown_start else {
+                    return Task::none();
+                };
+                presenter.countdown_remaining = start - Local::now().naive_local();
+                if presenter.countdown_remaining > TimeDelta::zero() {
+                    return Task::none();
+                }
+
+                debug!("Odpočet skončil, začíná bohoslužba");
+                presenter.finish_countdown()
+            }
+            Message::RunMacro(macro_index) => {
+                debug!("Spouštím makro {}", presenter.macros[macro_index].name);
+                Task::done(Message::RunMacroStep(macro_index, 0).into())
+            }
+            Message::RunMacroStep(macro_index, step_index) => {
+                let Some(&step) = presenter
+                    .macros
+                    .get(macro_index)
+                    .and_then(|presenter_macro| presenter_macro.steps.get(step_index))
+                else {
+                    debug!("Makro s indexem {macro_index} dokončeno");
+                    return Task::none();
+                };
+                trace!("Provádím krok makra {step}");
+                let next_step = Message::RunMacroStep(macro_index, step_index + 1);
+
+                // Další krok se naplánuje až po zprávách tohoto kroku, aby se prováděly popořadě
+                match (step, presenter.macro_step_to_message(step)) {
+                    (MacroStep::Wait(duration), _) => {
+                        Task::perform(tokio::time::sleep(duration), move |_| next_step.into())
+                    }
+                    (_, Some(msg)) => {
+                        Presenter::update(state, msg).chain(Task::done(next_step.into()))
+                    }
+                    (_, None) => {
+                        warn!("Krok makra {step} nelze provést, přeskakuji");
+                        Task::done(next_step.into())
+                    }
+                }
+            }
         }
     }
 }
 
-/// Normalizuje pomocí lineární transformace multiplikátor textu o hodnotě `value` tak,
-/// aby platilo:
-/// ```rust
-/// assert_eq!(normalize_text_multiplier(0), TEXT_SIZE_MULTIPLIER_MIN);
-/// assert_eq!(normalize_text_multiplier(255), TEXT_SIZE_MULTIPLIER_MAX);
-/// assert_eq!(normalize_text_multiplier(TEXT_SIZE_MULTIPLIER_DEFAULT_U8), TEXT_SIZE_MULTIPLIER_DEFAULT);
-/// ```
-fn normalize_text_multiplier(value: u8) -> f32 {
-    let value: f32 = value.into();
+/// Ke každému slajdu vrátí dvojici (pořadí položky v playlistu, pořadí slajdu v rámci
+/// položky), podle které se ukládají úpravy slajdů. Kopie zopakované části mají klíč
+/// původního slajdu a pořadí dalších slajdů položky neposouvají.
+fn slide_keys(slides: &[Slide]) -> Vec<(usize, usize)> {
+    let mut keys = Vec::with_capacity(slides.len());
+    let mut slide_index = 0;
 
-    let min: f32 = u8::MIN.into();
-    let max: f32 = u8::MAX.into();
+    for (index, slide) in slides.iter().enumerate() {
+        if index > 0 && slides[index - 1].item_index() != slide.item_index() {
+            slide_index = 0;
+        }
+        match slide {
+            Slide::Song(SongSlide {
+                repeat_of: Some(original),
+                ..
+            }) => keys.push((slide.item_index(), *original)),
+            _ => {
+                keys.push((slide.item_index(), slide_index));
+                slide_index += 1;
+            }
+        }
+    }
 
-    let zero_to_one = (value - min) / max;
+    keys
+}
 
-    zero_to_one * (TEXT_SIZE_MULTIPLIER_MAX - TEXT_SIZE_MULTIPLIER_MIN) + TEXT_SIZE_MULTIPLIER_MIN
+/// Zopakuje slajdy v rozsahu `range` (část písně) hned za ním i s jejich úpravami
+/// `overrides`, kopie si pamatují pořadí původních slajdů (viz [`slide_keys()`])
+fn repeat_slides(slides: &mut Vec<Slide>, overrides: &mut Vec<SlideOverride>, range: Range<usize>) {
+    let keys = slide_keys(slides);
+    let copies: Vec<Slide> = slides[range.clone()]
+        .iter()
+        .zip(&keys[range.clone()])
+        .map(|(slide, &(_, original))| match slide {
+            Slide::Song(song_slide) => Slide::Song(SongSlide {
+                repeat_of: Some(original),
+                ..song_slide.clone()
+            }),
+            slide => slide.clone(),
+        })
+        .collect();
+    let copied_overrides = overrides[range.clone()].to_vec();
+    slides.splice(range.end..range.end, copies);
+    overrides.splice(range.end..range.end, copied_overrides);
 }
 
-/// Vytvoří prázdný slide
-fn blank_slide() -> Element<'static, Message> {
-    container(Space::new(Length::Fill, Length::Fill))
-        .style(black_background)
-        .into()
+/// Převede index slajdu `index` podle klíčů slajdů před (`old_keys`) a po (`new_keys`)
+/// novém rozdělení pasáží (viz [`slide_keys()`]). Výsledný slajd je na stejné položce
+/// playlistu, pokud má položka nově méně slajdů, je to její poslední slajd.
+fn remap_slide_index(
+    old_keys: &[(usize, usize)],
+    new_keys: &[(usize, usize)],
+    index: usize,
+) -> usize {
+    let (item_index, slide_index) = old_keys[index];
+
+    new_keys
+        .iter()
+        .rposition(|&(new_item_index, new_slide_index)| {
+            new_item_index == item_index && new_slide_index <= slide_index
+        })
+        .unwrap_or(0)
+}
+
+/// Index slajdu před slajdem s indexem `current`, na prvním slajdu `None`
+fn prev_slide_index(current: usize) -> Option<usize> {
+    current.checked_sub(1)
+}
+
+/// Index slajdu za slajdem s indexem `current` mezi `num_slides` slajdy, na posledním
+/// slajdu `None`
+fn next_slide_index(current: usize, num_slides: usize) -> Option<usize> {
+    Some(current + 1).filter(|next| *next < num_slides)
+}
+
+/// Vrátí rozsahy indexů slajdů jednotlivých položek playlistu v pořadí, v jakém se
+/// promítají (slajdy jedné položky jdou vždy po sobě)
+fn item_ranges(slides: &[Slide]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+
+    for (index, slide) in slides.iter().enumerate() {
+        match ranges.last_mut() {
+            Some(range) if slides[range.start].item_index() == slide.item_index() => {
+                range.end = index + 1;
+            }
+            _ => ranges.push(index..index + 1),
+        }
+    }
+
+    ranges
+}
+
+/// Index prvního slajdu položky před položkou slajdu `current`, která nepatří mezi
+/// přeskočené položky `skipped`, pokud taková není, vrací `None`
+fn prev_item_start(slides: &[Slide], skipped: &HashSet<usize>, current: usize) -> Option<usize> {
+    let ranges = item_ranges(slides);
+    let current_start = ranges.iter().find(|range| range.contains(&current))?.start;
+
+    ranges
+        .iter()
+        .rev()
+        .map(|range| range.start)
+        .find(|&start| start < current_start && !skipped.contains(&slides[start].item_index()))
+}
+
+/// Index prvního slajdu položky za položkou slajdu `current`, která nepatří mezi
+/// přeskočené položky `skipped`, pokud taková není, vrací `None`
+fn next_item_start(slides: &[Slide], skipped: &HashSet<usize>, current: usize) -> Option<usize> {
+    item_ranges(slides)
+        .into_iter()
+        .map(|range| range.start)
+        .find(|&start| start > current && !skipped.contains(&slides[start].item_index()))
+}
+
+/// Popis položky, ze které je slajd `slide` (druh položky a její název)
+fn item_label(slide: &Slide) -> String {
+    match slide {
+        Slide::Passage(slide) => {
+            let (from, to) = slide.passage_indexes;
+            format!("Pasáž {} - {}", from, to)
+        }
+        Slide::Song(slide) => format!("Píseň {}", slide.title),
+        Slide::Text(slide) => format!("Text {}", slide.title),
+        Slide::Image(slide) => format!("Obrázek {}", slide.name),
+    }
+}
+
+/// Zda lze prohodit položku na pozici `item` s položkou za ní, obě musí existovat
+/// a ještě nebyly promítnuty (jsou až za položkou promítaného slajdu `current`)
+fn can_swap_with_next(ranges: &[Range<usize>], current: usize, item: usize) -> bool {
+    item + 1 < ranges.len() && ranges[item].start > current
+}
+
+/// Pořadí položek playlistu podle pořadí jejich slajdů, `order[i]` je původní index
+/// položky, která se promítá jako `i`-tá (viz [`PlaylistMetadata::reorder_items`])
+fn item_order(slides: &[Slide]) -> Vec<usize> {
+    item_ranges(slides)
+        .into_iter()
+        .map(|range| slides[range.start].item_index())
+        .collect()
+}
+
+/// Zda pořadí položek `order` odpovídá pořadí v playlistu
+fn is_identity(order: &[usize]) -> bool {
+    order.iter().copied().eq(0..order.len())
+}
+
+/// Vrátí rozsah indexů slajdů části písně, do které patří slajd na indexu `index`
+/// (rozdělená část má více kusů, viz [`crate::slide_render::split_song_part()`]).
+/// Pokud slajd není z písně, vrací `None`.
+fn song_part_range(slides: &[Slide], index: usize) -> Option<Range<usize>> {
+    let Some(Slide::Song(current)) = slides.get(index) else {
+        return None;
+    };
+    let (piece, pieces) = current.part_piece.unwrap_or((1, 1));
+    let start = index.checked_sub(piece.saturating_sub(1))?;
+    let range = start..start + pieces;
+
+    let same_part = |slide: &Slide| {
+        matches!(slide, Slide::Song(slide)
+            if slide.item_index == current.item_index && slide.part_name == current.part_name)
+    };
+    // Kusy části jdou vždy po sobě, pro jistotu se ale opakuje alespoň samotný slajd
+    Some(
+        slides
+            .get(range.clone())
+            .filter(|part| part.iter().all(same_part))
+            .map_or(index..index + 1, |_| range),
+    )
+}
+
+/// Index slajdu před slajdem `current`, který nepatří k žádné z přeskočených položek
+/// `skipped`, pokud takový není, vrací `None`
+fn prev_unskipped_index(
+    slides: &[Slide],
+    skipped: &HashSet<usize>,
+    current: usize,
+) -> Option<usize> {
+    let mut index = current;
+    loop {
+        index = prev_slide_index(index)?;
+        if !skipped.contains(&slides[index].item_index()) {
+            return Some(index);
+        }
+    }
+}
+
+/// Index slajdu za slajdem `current`, který nepatří k žádné z přeskočených položek
+/// `skipped`, pokud takový není, vrací `None`
+fn next_unskipped_index(
+    slides: &[Slide],
+    skipped: &HashSet<usize>,
+    current: usize,
+) -> Option<usize> {
+    let mut index = current;
+    loop {
+        index = next_slide_index(index, slides.len())?;
+        if !skipped.contains(&slides[index].item_index()) {
+            return Some(index);
+        }
+    }
+}
+
+/// Převede číslo slajdu (od 1) zadané uživatelem na index slajdu. Pokud vstup není
+/// číslo nebo slajd s takovým číslem mezi `num_slides` slajdy neexistuje, vrací `None`.
+fn slide_index_from_input(input: &str, num_slides: usize) -> Option<usize> {
+    input
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|number| (1..=num_slides).contains(number))
+        .map(|number| number - 1)
+}
+
+/// Vrátí indexy slajdů odpovídajících hledanému výrazu `query` v pořadí slajdů. Slajd písně
+/// odpovídá částí názvu písně nebo celým tagem části (`C`), slajd pasáže odkazem na verš,
+/// který je na slajdu (`Jan 3:16` i `Jan 3,16`, viz [`parse_verse_query()`]), nebo částí
+/// odkazu na celou pasáž, ostatní slajdy částí názvu. Velikost písmen se nerozlišuje,
+/// prázdnému výrazu neodpovídá nic. Názvy a odkazy se hledají až od dvou znaků, jeden znak
+/// (`C`, `B`) tak najde pouze části písní s tímto tagem.
+fn search_slides(slides: &[Slide], query: &str) -> Vec<usize> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let verse = parse_verse_query(&query);
+    let in_name = |name: &str| query.chars().count() > 1 && name.to_lowercase().contains(&query);
+
+    let matches = |slide: &Slide| match slide {
+        Slide::Song(slide) => in_name(&slide.title) || slide.part_name.to_lowercase() == query,
+        Slide::Passage(slide) => {
+            let (from, to) = slide.passage_indexes;
+            match verse {
+                Some(verse) => {
+                    (from..=to).contains(&verse)
+                        && slide
+                            .verses
+                            .iter()
+                            .any(|(number, _)| *number == verse.destructure().2)
+                }
+                None => in_name(&format!("{from} - {to}")),
+            }
+        }
+        Slide::Text(slide) => in_name(&slide.title),
+        Slide::Image(slide) => in_name(&slide.name),
+    };
+
+    slides
+        .iter()
+        .enumerate()
+        .filter(|(_, slide)| matches(slide))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Zparsuje odkaz na jeden verš ve formátu `KNIHA KAPITOLA:VERŠ` (nebo s čárkou místo
+/// dvojtečky), knihy podle [`Book::parse()`]. Pokud vstup odkazem na verš není, vrací `None`.
+fn parse_verse_query(query: &str) -> Option<VerseIndex> {
+    let (book, reference) = query.trim().rsplit_once(' ')?;
+    let (chapter, verse) = reference.split_once([':', ','])?;
+
+    VerseIndex::try_new(
+        Book::parse(book.trim())?,
+        chapter.trim().parse().ok()?,
+        verse.trim().parse().ok()?,
+    )
 }
 
-/// Stylovací funkce pro pozadí slajdu
-fn black_background(_theme: &Theme) -> container::Style {
-    container::Style {
-        text_color: Some(Color::WHITE),
-        background: Some(iced::Background::Color(Color::BLACK)),
-        ..Default::default()
+/// Pokud je klávesa `key` součástí číselného odkazu na pasáž (číslice, případně oddělovač
+/// za již rozepsaným odkazem `entry`), vrátí její znak, jinak vrací `None`.
+fn passage_entry_char(key: &str, entry: &str) -> Option<char> {
+    let mut chars = key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_digit() => Some(c),
+        (Some(c), None) if !entry.is_empty() && ".,/*-+".contains(c) => Some(' '),
+        _ => None,
+    }
+}
+
+/// Zjistí, zda je stisk klávesy s modifikátory `modifiers` klávesovou zkratkou prezentéru.
+/// Stisk, který již zpracoval widget s fokusem (`status` je [`event::Status::Captured`],
+/// např. psaní do textového vstupu), zkratkou není, stejně jako stisk s Ctrl, Alt nebo Super
+/// (zkratky systému a ostatních programů).
+fn is_hotkey(status: event::Status, modifiers: Modifiers) -> bool {
+    status == event::Status::Ignored
+        && !(modifiers.control() || modifiers.alt() || modifiers.logo())
+}
+
+/// Převede čas zadaný uživatelem (`HH:MM`, konec nebo začátek bohoslužby) na datum a čas,
+/// vždy v den `now`. Pokud vstup není platný čas, vrací `None`.
+fn time_today_from_input(input: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    NaiveTime::parse_from_str(input.trim(), "%H:%M")
+        .ok()
+        .map(|time| now.date().and_time(time))
+}
+
+/// Naléhavost varování před koncem bohoslužby
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ServiceEndUrgency {
+    /// Do konce zbývá víc než nastavený počet minut
+    Calm,
+    /// Konec se blíží (zbývá nejvýše nastavený počet minut)
+    Approaching,
+    /// Zbývá nejvýše [`SERVICE_END_IMMINENT`]
+    Imminent,
+    /// Konec již nastal
+    Overtime,
+}
+
+/// Určí naléhavost varování podle času `remaining` zbývajícího do konce bohoslužby,
+/// varovat se začíná `warning` před koncem
+fn service_end_urgency(remaining: TimeDelta, warning: TimeDelta) -> ServiceEndUrgency {
+    if remaining <= TimeDelta::zero() {
+        ServiceEndUrgency::Overtime
+    } else if remaining <= SERVICE_END_IMMINENT.min(warning) {
+        ServiceEndUrgency::Imminent
+    } else if remaining <= warning {
+        ServiceEndUrgency::Approaching
+    } else {
+        ServiceEndUrgency::Calm
+    }
+}
+
+/// Hodnota textového vstupu pro barvu pozadí podle úpravy slajdu
+fn background_input(slide_override: &SlideOverride) -> String {
+    slide_override
+        .background_color
+        .map(rgb_to_hex)
+        .unwrap_or_default()
+}
+
+/// Zapíše záznam průběhu prezentace `log` do databáze.
+pub async fn flush_log(
+    mut log: PresentationLog,
+    conn: impl Future<Output = Result<PoolConnection<Sqlite>, sqlx::Error>>,
+) -> Result<()> {
+    let mut conn = conn.await.context("Nelze získat připojení k databázi")?;
+    log.flush(&mut conn).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PresentationTheme;
+    use crate::slide_render::{PassageSlide, TextSlide};
+
+    #[test]
+    fn slide_number_input() {
+        assert_eq!(slide_index_from_input("1", 5), Some(0));
+        assert_eq!(slide_index_from_input(" 5 ", 5), Some(4));
+        assert_eq!(slide_index_from_input("0", 5), None);
+        assert_eq!(slide_index_from_input("6", 5), None);
+        assert_eq!(slide_index_from_input("-1", 5), None);
+        assert_eq!(slide_index_from_input("", 5), None);
+        assert_eq!(slide_index_from_input("abc", 5), None);
+    }
+
+    #[test]
+    fn keypad_keys_build_passage_entry() {
+        assert_eq!(passage_entry_char("4", ""), Some('4'));
+        assert_eq!(passage_entry_char(".", "43"), Some(' '));
+        assert_eq!(passage_entry_char("/", "43 3"), Some(' '));
+        // Samotný oddělovač odkaz nezačíná
+        assert_eq!(passage_entry_char(".", ""), None);
+        assert_eq!(passage_entry_char("a", "43"), None);
+        assert_eq!(passage_entry_char("42", ""), None);
+    }
+
+    #[test]
+    fn only_unhandled_keys_are_hotkeys() {
+        assert!(is_hotkey(event::Status::Ignored, Modifiers::empty()));
+        assert!(is_hotkey(event::Status::Ignored, Modifiers::SHIFT));
+        assert!(!is_hotkey(event::Status::Captured, Modifiers::empty()));
+        assert!(!is_hotkey(event::Status::Ignored, Modifiers::CTRL));
+        assert!(!is_hotkey(event::Status::Ignored, Modifiers::ALT));
+        assert!(!is_hotkey(event::Status::Ignored, Modifiers::LOGO));
+    }
+
+    #[test]
+    fn service_end_time_is_today() {
+        let now = NaiveDateTime::parse_from_str("2025-06-01 09:45", "%Y-%m-%d %H:%M").unwrap();
+
+        assert_eq!(
+            time_today_from_input(" 10:30 ", now),
+            NaiveDateTime::parse_from_str("2025-06-01 10:30", "%Y-%m-%d %H:%M").ok()
+        );
+        assert_eq!(
+            time_today_from_input("9:00", now),
+            NaiveDateTime::parse_from_str("2025-06-01 09:00", "%Y-%m-%d %H:%M").ok()
+        );
+        assert_eq!(time_today_from_input("25:00", now), None);
+        assert_eq!(time_today_from_input("půl jedenácté", now), None);
+    }
+
+    #[test]
+    fn service_end_warning_escalates() {
+        let warning = TimeDelta::minutes(10);
+
+        assert_eq!(
+            service_end_urgency(TimeDelta::minutes(25), warning),
+            ServiceEndUrgency::Calm
+        );
+        assert_eq!(
+            service_end_urgency(TimeDelta::minutes(10), warning),
+            ServiceEndUrgency::Approaching
+        );
+        assert_eq!(
+            service_end_urgency(TimeDelta::seconds(90), warning),
+            ServiceEndUrgency::Imminent
+        );
+        assert_eq!(
+            service_end_urgency(TimeDelta::zero(), warning),
+            ServiceEndUrgency::Overtime
+        );
+        assert_eq!(
+            service_end_urgency(TimeDelta::minutes(-5), warning),
+            ServiceEndUrgency::Overtime
+        );
+        // Kratší varování než SERVICE_END_IMMINENT je rovnou nejvýraznější
+        assert_eq!(
+            service_end_urgency(TimeDelta::seconds(50), TimeDelta::minutes(1)),
+            ServiceEndUrgency::Imminent
+        );
+    }
+
+    #[test]
+    fn navigation_stays_within_playlist() {
+        assert_eq!(prev_slide_index(0), None);
+        assert_eq!(prev_slide_index(3), Some(2));
+        assert_eq!(next_slide_index(3, 5), Some(4));
+        assert_eq!(next_slide_index(4, 5), None);
+        assert_eq!(next_slide_index(0, 1), None);
+        assert_eq!(next_slide_index(0, 0), None);
+    }
+
+    #[test]
+    fn repeated_song_part_includes_all_pieces() {
+        let song_slide = |item_index, part_name: &str, part_piece| {
+            Slide::Song(SongSlide {
+                title: String::from("Haleluja"),
+                key: None,
+                part_name: part_name.to_string(),
+                content: String::new(),
+                part_piece,
+                translation: None,
+                repeat_of: None,
+                item_index,
+                theme: PresentationTheme::default(),
+            })
+        };
+        let slides = [
+            song_slide(0, "V1", None),
+            song_slide(0, "C", Some((1, 2))),
+            song_slide(0, "C", Some((2, 2))),
+            song_slide(0, "V2", None),
+            Slide::Text(TextSlide {
+                title: String::from("Oznámení"),
+                content: String::new(),
+                item_index: 1,
+                theme: PresentationTheme::default(),
+            }),
+        ];
+
+        assert_eq!(song_part_range(&slides, 0), Some(0..1));
+        assert_eq!(song_part_range(&slides, 1), Some(1..3));
+        assert_eq!(song_part_range(&slides, 2), Some(1..3));
+        assert_eq!(song_part_range(&slides, 4), None);
+        assert_eq!(song_part_range(&slides, 5), None);
+    }
+
+    #[test]
+    fn repeated_song_part_keeps_slide_keys() {
+        let song_slide = |part_name: &str| {
+            Slide::Song(SongSlide {
+                title: String::from("Haleluja"),
+                key: None,
+                part_name: part_name.to_string(),
+                content: String::new(),
+                part_piece: None,
+                translation: None,
+                repeat_of: None,
+                item_index: 0,
+                theme: PresentationTheme::default(),
+            })
+        };
+        let mut slides = vec![song_slide("V1"), song_slide("C"), song_slide("V2")];
+        let bigger = SlideOverride {
+            text_scale: Some(150),
+            ..Default::default()
+        };
+        let mut overrides = vec![
+            SlideOverride::default(),
+            bigger.clone(),
+            SlideOverride::default(),
+        ];
+
+        repeat_slides(&mut slides, &mut overrides, 1..2);
+        repeat_slides(&mut slides, &mut overrides, 2..3);
+
+        // Kopie mají klíč původního slajdu, úprava slajdu za nimi se uloží pod jeho klíčem
+        assert_eq!(
+            slide_keys(&slides),
+            vec![(0, 0), (0, 1), (0, 1), (0, 1), (0, 2)]
+        );
+        assert_eq!(overrides[3], bigger);
+    }
+
+    #[test]
+    fn navigation_jumps_over_skipped_items() {
+        let text_slide = |item_index| {
+            Slide::Text(TextSlide {
+                title: format!("Text {item_index}"),
+                content: String::new(),
+                item_index,
+                theme: PresentationTheme::default(),
+            })
+        };
+        let slides = [text_slide(0), text_slide(1), text_slide(1), text_slide(2)];
+        let skipped = HashSet::from([1]);
+
+        assert_eq!(next_unskipped_index(&slides, &skipped, 0), Some(3));
+        assert_eq!(prev_unskipped_index(&slides, &skipped, 3), Some(0));
+        assert_eq!(
+            next_unskipped_index(&slides, &skipped, 1),
+            Some(3),
+            "Z ručně vybraného přeskočeného slajdu se pokračuje dál"
+        );
+        assert_eq!(next_unskipped_index(&slides, &HashSet::from([2]), 2), None);
+        assert_eq!(prev_unskipped_index(&slides, &HashSet::from([0]), 1), None);
+    }
+
+    #[test]
+    fn item_navigation_jumps_to_first_slide_of_item() {
+        let text_slide = |item_index| {
+            Slide::Text(TextSlide {
+                title: format!("Text {item_index}"),
+                content: String::new(),
+                item_index,
+                theme: PresentationTheme::default(),
+            })
+        };
+        let slides = [
+            text_slide(0),
+            text_slide(0),
+            text_slide(1),
+            text_slide(2),
+            text_slide(2),
+            text_slide(2),
+        ];
+        let none = HashSet::new();
+
+        assert_eq!(next_item_start(&slides, &none, 0), Some(2));
+        assert_eq!(next_item_start(&slides, &none, 1), Some(2));
+        assert_eq!(next_item_start(&slides, &none, 4), None);
+        assert_eq!(prev_item_start(&slides, &none, 4), Some(2));
+        assert_eq!(prev_item_start(&slides, &none, 1), None);
+        assert_eq!(
+            next_item_start(&slides, &HashSet::from([1]), 0),
+            Some(3),
+            "Přeskočená položka se vynechá"
+        );
+        assert_eq!(prev_item_start(&slides, &HashSet::from([1]), 3), Some(0));
+    }
+
+    #[test]
+    fn only_remaining_items_can_be_reordered() {
+        let text_slide = |item_index| {
+            Slide::Text(TextSlide {
+                title: format!("Text {item_index}"),
+                content: String::new(),
+                item_index,
+                theme: PresentationTheme::default(),
+            })
+        };
+        let slides = [
+            text_slide(0),
+            text_slide(0),
+            text_slide(2),
+            text_slide(1),
+            text_slide(1),
+        ];
+
+        let ranges = item_ranges(&slides);
+        assert_eq!(ranges, vec![0..2, 2..3, 3..5]);
+        assert_eq!(item_order(&slides), vec![0, 2, 1]);
+        assert!(!is_identity(&item_order(&slides)));
+
+        assert!(
+            !can_swap_with_next(&ranges, 1, 0),
+            "Promítaná položka se nepřesouvá"
+        );
+        assert!(can_swap_with_next(&ranges, 1, 1));
+        assert!(!can_swap_with_next(&ranges, 2, 1));
+        assert!(
+            !can_swap_with_next(&ranges, 1, 2),
+            "Za poslední položkou nic není"
+        );
+    }
+
+    #[test]
+    fn slides_are_found_by_title_part_and_verse() {
+        let song_slide = |part_name: &str| {
+            Slide::Song(SongSlide {
+                title: String::from("Chválím Tě, Pane"),
+                key: None,
+                part_name: part_name.to_string(),
+                content: String::new(),
+                part_piece: None,
+                translation: None,
+                repeat_of: None,
+                item_index: 0,
+                theme: PresentationTheme::default(),
+            })
+        };
+        let passage_slide = |verses: Vec<u8>| {
+            Slide::Passage(PassageSlide {
+                translation_name: String::from("ČEP"),
+                passage_indexes: (
+                    VerseIndex::try_new(Book::John, 3, 16).unwrap(),
+                    VerseIndex::try_new(Book::John, 3, 18).unwrap(),
+                ),
+                verses: verses
+                    .into_iter()
+                    .map(|number| (number, String::new()))
+                    .collect(),
+                heading: None,
+                item_index: 1,
+                theme: PresentationTheme::default(),
+            })
+        };
+        let slides = [
+            song_slide("V1"),
+            song_slide("C"),
+            passage_slide(vec![16, 17]),
+            passage_slide(vec![18]),
+        ];
+
+        assert_eq!(search_slides(&slides, "chválím"), vec![0, 1]);
+        assert_eq!(search_slides(&slides, " c "), vec![1]);
+        assert_eq!(search_slides(&slides, "Jan 3:18"), vec![3]);
+        assert_eq!(search_slides(&slides, "jan 3,17"), vec![2]);
+        assert_eq!(search_slides(&slides, "Jan 3:19"), Vec::<usize>::new());
+        assert_eq!(search_slides(&slides, "Jan 3"), vec![2, 3]);
+        assert_eq!(search_slides(&slides, ""), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn slide_index_stays_on_item_after_rechunk() {
+        let old_keys = [(0, 0), (0, 1), (0, 2), (1, 0)];
+        let new_keys = [(0, 0), (0, 1), (1, 0)];
+
+        assert_eq!(remap_slide_index(&old_keys, &new_keys, 1), 1);
+        assert_eq!(remap_slide_index(&old_keys, &new_keys, 2), 1);
+        assert_eq!(remap_slide_index(&old_keys, &new_keys, 3), 2);
     }
 }
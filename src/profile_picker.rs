@@ -0,0 +1,211 @@
+//! Obrazovka pro výběr a správu pojmenovaných profilů (např. pro jednotlivé sbory nebo
+//! místa), viz [`crate::config::Profiles`]. Zobrazí se při startu, pokud je profilů více,
+//! a lze se na ni dostat z nastavení.
+
+use ekkles_data::database;
+use iced::{
+    Alignment, Element, Length, Task,
+    widget::{button, column, container, row, text, text_input},
+};
+use log::{debug, info};
+use sqlx::SqlitePool;
+
+use crate::{
+    Ekkles, Screen,
+    pick_playlist::{self, PlaylistPicker},
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Přepne na daný profil a pokračuje na výběr playlistů
+    ProfilePicked(String),
+    /// Databáze vybraného profilu je otevřená, profil lze aktivovat
+    ProfileDatabaseOpened(String, SqlitePool),
+    NewProfileNameChanged(String),
+    /// Vytvoří nový profil jako kopii aktivního
+    CreateProfile,
+    DeleteProfile(String),
+    OperationFailed(String),
+}
+
+impl From<Message> for crate::Message {
+    fn from(value: Message) -> Self {
+        crate::Message::ProfilePicker(value)
+    }
+}
+
+impl Message {
+    /// Zda je zpráva povolená v režimu hosta, host může profil vybrat, ale ne vytvořit
+    /// nebo smazat
+    pub fn allowed_in_guest_mode(&self) -> bool {
+        match self {
+            Message::ProfilePicked(_)
+            | Message::ProfileDatabaseOpened(..)
+            | Message::OperationFailed(_) => true,
+            Message::NewProfileNameChanged(_)
+            | Message::CreateProfile
+            | Message::DeleteProfile(_) => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ProfilePicker {
+    profiles: Vec<String>,
+    active: String,
+    new_profile_name: String,
+    err_msg: String,
+}
+
+impl ProfilePicker {
+    pub fn new(config: &crate::config::Config) -> Self {
+        Self {
+            profiles: config.profiles.names(),
+            active: config.profiles.active.clone(),
+            new_profile_name: String::new(),
+            err_msg: String::new(),
+        }
+    }
+
+    /// V režimu hosta (`guest_mode`) lze profil pouze vybrat
+    pub fn view(&self, guest_mode: bool) -> Element<Message> {
+        let profiles = self.profiles.iter().map(|name| {
+            let is_active = *name == self.active;
+            let label = if is_active {
+                format!("{name} (aktivní)")
+            } else {
+                name.clone()
+            };
+
+            row![
+                button(text(label))
+                    .style(if is_active {
+                        button::success
+                    } else {
+                        button::primary
+                    })
+                    .on_press(Message::ProfilePicked(name.clone()))
+                    .width(Length::Fill),
+                button("Smazat").style(button::danger).on_press_maybe(
+                    (!is_active && !guest_mode).then(|| Message::DeleteProfile(name.clone()))
+                ),
+            ]
+            .spacing(10)
+            .into()
+        });
+
+        container(
+            column![
+                text("Vyber profil (sbor, místo), každý má vlastní databázi a nastavení"),
+                column(profiles).spacing(10),
+                row![
+                    text_input("Název nového profilu", &self.new_profile_name)
+                        .on_input(Message::NewProfileNameChanged)
+                        .on_submit(Message::CreateProfile),
+                    button("Vytvořit z aktivního")
+                        .on_press_maybe((!guest_mode).then_some(Message::CreateProfile)),
+                ]
+                .spacing(10),
+                text(&self.err_msg).style(text::danger),
+            ]
+            .spacing(20)
+            .align_x(Alignment::Center)
+            .max_width(600),
+        )
+        .padding(30)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .into()
+    }
+
+    /// Update funkce pro výběr profilu. Pokud je tato funkce zavolána nad jinou
+    /// obrazovkou než [`Screen::PickProfile`], zpanikaří.
+    pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
+        let picker = match &mut state.screen {
+            Screen::PickProfile(picker) => picker,
+            screen => panic!(
+                "Update pro výběr profilu zavolán nad jinou obrazovkou {:?}",
+                screen
+            ),
+        };
+
+        match msg {
+            Message::ProfilePicked(name) => {
+                let Some(path) = state.config.profile_db_path(&name) else {
+                    picker.err_msg = format!("Profil \"{name}\" neexistuje");
+                    return Task::none();
+                };
+
+                if path == state.config.db_path {
+                    debug!("Profil {name} používá otevřenou databázi, pouze přepínám nastavení");
+                    if let Err(e) = state.config.switch_profile(&name) {
+                        picker.err_msg = format!("{:#}", e);
+                        return Task::none();
+                    }
+                    state.screen = Screen::PickPlaylist(picker_after_switch(state));
+                    return Task::done(pick_playlist::Message::LoadPlaylists.into());
+                }
+
+                debug!("Otevírám databázi profilu {name}: {}", path.display());
+                Task::perform(
+                    database::open_or_create_database(path),
+                    move |res| match res {
+                        Ok(db) => Message::ProfileDatabaseOpened(name, db).into(),
+                        Err(e) => Message::OperationFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::ProfileDatabaseOpened(name, db) => {
+                if let Err(e) = state.config.switch_profile(&name) {
+                    // Databázi nepřepínáme, tu právě otevřenou zase uzavřeme
+                    let err_msg = format!("{:#}", e);
+                    return Task::perform(database::checkpoint_and_close(db), move |_| {
+                        Message::OperationFailed(err_msg).into()
+                    });
+                }
+
+                info!("Přepínám na profil {name}");
+                state.screen = Screen::PickPlaylist(picker_after_switch(state));
+                pick_playlist::replace_database(state, db)
+            }
+            Message::NewProfileNameChanged(input) => {
+                picker.new_profile_name = input;
+                Task::none()
+            }
+            Message::CreateProfile => {
+                debug!("Vytvářím profil {}", picker.new_profile_name);
+                match state.config.create_profile(&picker.new_profile_name) {
+                    Ok(_) => *picker = ProfilePicker::new(&state.config),
+                    Err(e) => picker.err_msg = format!("{:#}", e),
+                }
+                Task::none()
+            }
+            Message::DeleteProfile(name) => {
+                debug!("Mažu profil {name}");
+                match state.config.delete_profile(&name) {
+                    Ok(_) => *picker = ProfilePicker::new(&state.config),
+                    Err(e) => picker.err_msg = format!("{:#}", e),
+                }
+                Task::none()
+            }
+            Message::OperationFailed(err_msg) => {
+                debug!("Operace s profily selhala: {err_msg}");
+                picker.err_msg = err_msg;
+                Task::none()
+            }
+        }
+    }
+}
+
+/// Uloží volbu právě přepnutého profilu a vrátí výběr playlistů, na který se po přepnutí
+/// přejde, případně s chybovou hláškou o neuložení volby.
+fn picker_after_switch(state: &mut Ekkles) -> PlaylistPicker {
+    let mut picker = PlaylistPicker::new();
+    match state.config.save_settings() {
+        Ok(_) => {
+            picker.info_msg = Some(format!("Aktivní profil: {}", state.config.profiles.active))
+        }
+        Err(e) => picker.err_msg = Some(format!("Profil přepnut, ale volbu nelze uložit: {:#}", e)),
+    }
+    picker
+}
@@ -0,0 +1,473 @@
+//! Obrazovka plánů čtení Bible. Zobrazuje čtení pro vybraný den (výchozí je dnešek)
+//! a umožňuje je jedním kliknutím vložit do playlistu jako pasáže. Zároveň se zde
+//! plány importují ze souboru a mažou.
+
+use std::fs;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{Datelike, Days, Local, NaiveDate};
+use ekkles_data::{
+    bible::{
+        get_available_translations,
+        reading_plan::{self, Reading, ReadingPlan, ReadingPlanInfo},
+    },
+    playlist::PlaylistMetadata,
+};
+use iced::{
+    Alignment, Element, Length, Task,
+    widget::{self, button, column, container, pick_list, row, scrollable, text, text_input},
+};
+use log::{debug, trace};
+
+use crate::{Ekkles, Screen, bible_picker::TranslationPickerItem, playlist_editor::PlaylistEditor};
+
+/// Přípona souborů s plánem čtení
+const PLAN_EXTENSION: &str = "txt";
+/// Formát data pro zobrazení i zadávání začátku plánu
+const DATE_FORMAT: &str = "%d.%m.%Y";
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    LoadPlans,
+    PlansLoaded(Vec<ReadingPlanInfo>),
+    PlanPicked(ReadingPlanInfo),
+    LoadTranslations,
+    TranslationsLoaded(Vec<TranslationPickerItem>),
+    TranslationPicked(TranslationPickerItem),
+    PreviousDay,
+    NextDay,
+    Today,
+    LoadReadings,
+    ReadingsLoaded(Vec<Reading>),
+    /// Vloží čtení zobrazeného dne na konec playlistu a vrátí se do editoru
+    InsertReadings,
+    ImportPlan,
+    PlanImported(i64),
+    DeletePlan,
+    StartDateChanged(String),
+    SetStartDate,
+    DialogCancelled,
+    OperationFailed(String),
+    ReturnToEditor,
+}
+
+impl From<Message> for crate::Message {
+    fn from(value: Message) -> Self {
+        crate::Message::ReadingPlanPicker(value)
+    }
+}
+
+#[derive(Debug)]
+pub struct ReadingPlanPicker {
+    playlist: PlaylistMetadata,
+    plans: Option<Vec<ReadingPlanInfo>>,
+    picked_plan_id: Option<i64>,
+    translations: Option<Vec<TranslationPickerItem>>,
+    picked_translation: Option<TranslationPickerItem>,
+    /// Datum, pro které se zobrazují čtení
+    date: NaiveDate,
+    /// Čtení zobrazeného dne, `None` pokud se ještě načítají
+    readings: Option<Vec<Reading>>,
+    start_date_input: String,
+    err_msg: String,
+}
+
+impl ReadingPlanPicker {
+    pub fn new(playlist: PlaylistMetadata) -> Self {
+        Self {
+            playlist,
+            plans: None,
+            picked_plan_id: None,
+            translations: None,
+            picked_translation: None,
+            date: Local::now().date_naive(),
+            readings: None,
+            start_date_input: String::new(),
+            err_msg: String::new(),
+        }
+    }
+
+    /// Vrátí playlist, do kterého se vkládá
+    pub fn get_playlist(&self) -> &PlaylistMetadata {
+        &self.playlist
+    }
+
+    /// Vrátí vybraný plán, pokud nějaký je
+    fn picked_plan(&self) -> Option<&ReadingPlanInfo> {
+        self.plans
+            .as_ref()?
+            .iter()
+            .find(|plan| Some(plan.id) == self.picked_plan_id)
+    }
+
+    /// Den vybraného plánu připadající na zobrazené datum
+    fn picked_day(&self) -> Option<u32> {
+        self.picked_plan()?.day_for(self.date)
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let pickers = row![
+            pick_list(
+                self.plans.clone().unwrap_or_default(),
+                self.picked_plan().cloned(),
+                Message::PlanPicked,
+            )
+            .placeholder(match &self.plans {
+                Some(plans) if plans.is_empty() => "Žádné plány, importuj nějaký",
+                Some(_) => "Vyber plán čtení",
+                None => "Načítám plány...",
+            })
+            .width(Length::FillPortion(2)),
+            pick_list(
+                self.translations.clone().unwrap_or_default(),
+                self.picked_translation.clone(),
+                Message::TranslationPicked,
+            )
+            .placeholder(if self.translations.is_some() {
+                "Vyber překlad"
+            } else {
+                "Načítám překlady..."
+            })
+            .width(Length::FillPortion(1)),
+        ]
+        .spacing(10);
+
+        let day_label = match (self.picked_plan(), self.picked_day()) {
+            (Some(plan), Some(day)) => format!("{}. den z {}", day, plan.num_days),
+            (Some(_), None) => String::from("Plán v tento den ještě nezačal"),
+            (None, _) => String::new(),
+        };
+
+        let date_navigation = row![
+            button("<").on_press(Message::PreviousDay),
+            text(self.date.format(DATE_FORMAT).to_string()),
+            button(">").on_press(Message::NextDay),
+            button("Dnes").on_press(Message::Today),
+            text(day_label).width(Length::Fill).align_x(Alignment::End),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+        let readings: Element<Message> = match &self.readings {
+            Some(readings) if readings.is_empty() => text("Žádná čtení").into(),
+            Some(readings) => scrollable(column(
+                readings
+                    .iter()
+                    .map(|(from, to)| text(format!("{from} - {to}")).into()),
+            ))
+            .into(),
+            None => text("Načítám čtení...").into(),
+        };
+
+        let insert_button = button("Vložit do playlistu")
+            .style(widget::button::success)
+            .on_press_maybe(
+                (self.picked_translation.is_some()
+                    && self.readings.as_ref().is_some_and(|r| !r.is_empty()))
+                .then_some(Message::InsertReadings),
+            )
+            .width(Length::Fill);
+
+        let plan_management = column![
+            row![
+                text_input("Začátek plánu (dd.mm.rrrr)", &self.start_date_input)
+                    .on_input(Message::StartDateChanged)
+                    .on_submit(Message::SetStartDate),
+                button("Nastavit začátek")
+                    .on_press_maybe(self.picked_plan_id.map(|_| Message::SetStartDate)),
+            ]
+            .spacing(10),
+            row![
+                button("Importovat plán")
+                    .on_press(Message::ImportPlan)
+                    .width(Length::Fill),
+                button("Smazat plán")
+                    .style(widget::button::danger)
+                    .on_press_maybe(self.picked_plan_id.map(|_| Message::DeletePlan))
+                    .width(Length::Fill),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10);
+
+        let content = column![
+            pickers,
+            date_navigation,
+            container(readings).height(200),
+            insert_button,
+            text(&self.err_msg).style(widget::text::danger),
+            plan_management,
+        ]
+        .spacing(20)
+        .max_width(800);
+
+        row![
+            container(
+                button("Zpět")
+                    .on_press(Message::ReturnToEditor)
+                    .width(Length::Fill)
+            )
+            .align_bottom(Length::Fill)
+            .width(Length::FillPortion(1))
+            .padding(30),
+            container(content)
+                .center_x(Length::FillPortion(3))
+                .center_y(Length::Fill),
+            container("").width(Length::FillPortion(1)),
+        ]
+        .padding(10)
+        .into()
+    }
+
+    /// Update funkce pro obrazovku plánů čtení. Pokud je tato funkce zavolána nad jinou
+    /// obrazovkou než [`Screen::PickReadingPlan`], zpanikaří.
+    pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
+        let picker = match &mut state.screen {
+            Screen::PickReadingPlan(picker) => picker,
+            screen => panic!(
+                "Update pro plány čtení zavolán nad jinou obrazovkou {:?}",
+                screen
+            ),
+        };
+
+        match msg {
+            Message::LoadPlans => {
+                debug!("Načítám plány čtení");
+                let conn = state.db.acquire();
+                Task::perform(
+                    async {
+                        let mut conn = conn.await?;
+                        reading_plan::get_available(&mut conn).await
+                    },
+                    |res| match res {
+                        Ok(plans) => Message::PlansLoaded(plans).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::PlansLoaded(plans) => {
+                debug!("Plány čtení načteny {:?}", plans);
+                // Ponecháme vybraný plán, pokud pořád existuje, jinak vybereme první
+                if !plans
+                    .iter()
+                    .any(|plan| Some(plan.id) == picker.picked_plan_id)
+                {
+                    picker.picked_plan_id = plans.first().map(|plan| plan.id);
+                }
+                picker.plans = Some(plans);
+                picker.start_date_input = picker
+                    .picked_plan()
+                    .map(|plan| plan.start_date.format(DATE_FORMAT).to_string())
+                    .unwrap_or_default();
+                Task::done(Message::LoadReadings.into())
+            }
+            Message::PlanPicked(plan) => {
+                debug!("Vybrán plán čtení {}", plan.name);
+                picker.start_date_input = plan.start_date.format(DATE_FORMAT).to_string();
+                picker.picked_plan_id = Some(plan.id);
+                Task::done(Message::LoadReadings.into())
+            }
+            Message::LoadTranslations => {
+                debug!("Načítám seznam překladů pro plány čtení");
+                let conn = state.db.acquire();
+                Task::perform(
+                    async {
+                        let mut conn = conn.await?;
+                        get_available_translations(&mut conn).await
+                    },
+                    |res| match res {
+                        Ok(translations) => Message::TranslationsLoaded(
+                            translations
+                                .into_iter()
+                                .map(|(id, name)| TranslationPickerItem { id, name })
+                                .collect(),
+                        )
+                        .into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+                // Na obrazovku se vždy vstupuje přes načtení překladů, plány načteme s nimi
+                .chain(Task::done(Message::LoadPlans.into()))
+            }
+            Message::TranslationsLoaded(translations) => {
+                debug!("Překlady načteny {:?}", translations);
+                let default_translation = state.config.settings.default_translation.as_deref();
+                picker.picked_translation = translations
+                    .iter()
+                    .find(|item| Some(item.name.as_str()) == default_translation)
+                    .or(translations.first())
+                    .cloned();
+                picker.translations = Some(translations);
+                Task::none()
+            }
+            Message::TranslationPicked(item) => {
+                debug!("Vybrán překlad {}", item);
+                picker.picked_translation = Some(item);
+                Task::none()
+            }
+            Message::PreviousDay => {
+                picker.date = picker.date - Days::new(1);
+                Task::done(Message::LoadReadings.into())
+            }
+            Message::NextDay => {
+                picker.date = picker.date + Days::new(1);
+                Task::done(Message::LoadReadings.into())
+            }
+            Message::Today => {
+                picker.date = Local::now().date_naive();
+                Task::done(Message::LoadReadings.into())
+            }
+            Message::LoadReadings => {
+                let (Some(plan_id), Some(day)) = (picker.picked_plan_id, picker.picked_day())
+                else {
+                    trace!("Není vybrán plán nebo v daný den nezačal, žádná čtení");
+                    picker.readings = Some(Vec::new());
+                    return Task::none();
+                };
+
+                debug!("Načítám čtení {day}. dne plánu s id {plan_id}");
+                picker.readings = None;
+                let conn = state.db.acquire();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await?;
+                        reading_plan::load_readings(plan_id, day, &mut conn).await
+                    },
+                    |res| match res {
+                        Ok(readings) => Message::ReadingsLoaded(readings).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::ReadingsLoaded(readings) => {
+                debug!("Čtení načtena {:?}", readings);
+                picker.readings = Some(readings);
+                Task::none()
+            }
+            Message::InsertReadings => {
+                let (Some(translation), Some(readings)) =
+                    (&picker.picked_translation, &picker.readings)
+                else {
+                    return Task::none();
+                };
+
+                debug!(
+                    "Vkládám {} čtení na konec playlistu a vracím se do editoru",
+                    readings.len()
+                );
+                for (from, to) in readings {
+                    picker
+                        .playlist
+                        .push_bible_passage(translation.id, *from, *to);
+                }
+
+                Task::done(Message::ReturnToEditor.into())
+            }
+            Message::ImportPlan => {
+                debug!("Importuji plán čtení");
+                let db = state.db.clone();
+                Task::perform(
+                    async move {
+                        let Some(file) = rfd::AsyncFileDialog::new()
+                            .add_filter("Plán čtení", &[PLAN_EXTENSION])
+                            .pick_file()
+                            .await
+                        else {
+                            return Ok(None);
+                        };
+
+                        let path = file.path();
+                        let input = fs::read_to_string(path)
+                            .with_context(|| format!("Nelze přečíst {}", path.display()))?;
+                        let name = path
+                            .file_stem()
+                            .map(|stem| stem.to_string_lossy().to_string())
+                            .ok_or(anyhow!("Soubor s plánem nemá název"))?;
+                        let plan = ReadingPlan::parse(&name, &input)?;
+
+                        // Plány bývají roční, výchozím začátkem je tedy začátek roku
+                        let today = Local::now().date_naive();
+                        let start_date = NaiveDate::from_ymd_opt(today.year(), 1, 1)
+                            .expect("1. leden existuje v každém roce");
+                        plan.save_to_db(start_date, &db).await.map(Some)
+                    },
+                    |res: Result<Option<i64>>| match res {
+                        Ok(Some(id)) => Message::PlanImported(id).into(),
+                        Ok(None) => Message::DialogCancelled.into(),
+                        Err(e) => Message::OperationFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::PlanImported(id) => {
+                debug!("Plán čtení naimportován s id {id}");
+                picker.err_msg.clear();
+                picker.picked_plan_id = Some(id);
+                Task::done(Message::LoadPlans.into())
+            }
+            Message::DeletePlan => {
+                let Some(plan_id) = picker.picked_plan_id else {
+                    return Task::none();
+                };
+
+                debug!("Mažu plán čtení s id {plan_id}");
+                picker.picked_plan_id = None;
+                let db = state.db.clone();
+                Task::perform(
+                    async move { reading_plan::delete(plan_id, &db).await },
+                    |res| match res {
+                        Ok(_) => Message::LoadPlans.into(),
+                        Err(e) => Message::OperationFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::StartDateChanged(input) => {
+                picker.start_date_input = input;
+                Task::none()
+            }
+            Message::SetStartDate => {
+                let Some(plan_id) = picker.picked_plan_id else {
+                    return Task::none();
+                };
+
+                let input: String = picker
+                    .start_date_input
+                    .chars()
+                    .filter(|c| !c.is_whitespace())
+                    .collect();
+                let Ok(start_date) = NaiveDate::parse_from_str(&input, DATE_FORMAT) else {
+                    return Task::done(
+                        Message::OperationFailed(String::from(
+                            "Začátek plánu musí být datum ve tvaru dd.mm.rrrr",
+                        ))
+                        .into(),
+                    );
+                };
+
+                debug!("Nastavuji začátek plánu s id {plan_id} na {start_date}");
+                picker.err_msg.clear();
+                let db = state.db.clone();
+                Task::perform(
+                    async move { reading_plan::set_start_date(plan_id, start_date, &db).await },
+                    |res| match res {
+                        Ok(_) => Message::LoadPlans.into(),
+                        Err(e) => Message::OperationFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::DialogCancelled => {
+                debug!("Dialog pro výběr souboru zrušen");
+                Task::none()
+            }
+            Message::OperationFailed(err_msg) => {
+                debug!("Operace s plány čtení selhala: {err_msg}");
+                picker.err_msg = err_msg;
+                Task::none()
+            }
+            Message::ReturnToEditor => {
+                debug!("Vracím se do editoru playlistů");
+                state.screen = Screen::EditPlaylist(PlaylistEditor::new(picker.playlist.clone()));
+                Task::done(crate::playlist_editor::Message::LoadSongNameCache.into())
+            }
+        }
+    }
+}
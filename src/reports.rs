@@ -0,0 +1,192 @@
+//! Obrazovka s exportem přehledů o používání písní do CSV (knihovna písní, používání
+//! písní, záznam prezentací) a hlášení pro CCLI za zvolené období, typicky pro hlášení
+//! licenční organizaci. Přehledy sestavuje [`ekkles_data::reports`].
+
+use anyhow::{Context, Result};
+use chrono::{Local, Months, NaiveDate};
+use ekkles_data::reports::{Report, ReportPeriod};
+use iced::{
+    Alignment, Element, Length, Task,
+    widget::{button, column, container, row, text, text_input},
+};
+use log::debug;
+
+use crate::{Ekkles, Screen, settings::SettingsEditor};
+
+/// Formát data v polích období hlášení CCLI
+const DATE_FORMAT: &str = "%d.%m.%Y";
+/// Výchozí délka období hlášení CCLI (končí dnešním dnem)
+const DEFAULT_PERIOD_MONTHS: u32 = 6;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Export(Report),
+    PeriodFromChanged(String),
+    PeriodToChanged(String),
+    /// Exportuje hlášení CCLI za období zadané v polích od/do
+    ExportCcli,
+    /// Přehled byl uložen do souboru s danou cestou
+    Exported(String),
+    DialogCancelled,
+    ExportFailed(String),
+    ReturnToSettings,
+}
+
+impl From<Message> for crate::Message {
+    fn from(value: Message) -> Self {
+        crate::Message::ReportExporter(value)
+    }
+}
+
+#[derive(Debug)]
+pub struct ReportExporter {
+    /// Začátek období hlášení CCLI v textové podobě, validuje se až při exportu
+    period_from: String,
+    /// Konec období hlášení CCLI v textové podobě, validuje se až při exportu
+    period_to: String,
+    err_msg: String,
+    info_msg: String,
+}
+
+impl ReportExporter {
+    pub fn new() -> Self {
+        let today = Local::now().date_naive();
+        let from = today - Months::new(DEFAULT_PERIOD_MONTHS);
+
+        Self {
+            period_from: from.format(DATE_FORMAT).to_string(),
+            period_to: today.format(DATE_FORMAT).to_string(),
+            err_msg: String::new(),
+            info_msg: String::new(),
+        }
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let export_buttons = Report::ALL.into_iter().map(|report| {
+            button(text(format!("Exportovat: {report}")))
+                .on_press(Message::Export(report))
+                .width(Length::Fill)
+                .into()
+        });
+
+        container(
+            column![
+                text("Přehledy pro licenční hlášení (CSV)"),
+                column(export_buttons).spacing(10),
+                text("Hlášení CCLI (písně s CCLI číslem použité v období)"),
+                row![
+                    text("Od"),
+                    text_input("dd.mm.rrrr", &self.period_from)
+                        .on_input(Message::PeriodFromChanged),
+                    text("Do"),
+                    text_input("dd.mm.rrrr", &self.period_to).on_input(Message::PeriodToChanged),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+                button("Exportovat hlášení CCLI")
+                    .on_press(Message::ExportCcli)
+                    .width(Length::Fill),
+                text(&self.err_msg).style(text::danger),
+                text(&self.info_msg).style(text::success),
+                button("Zpět").on_press(Message::ReturnToSettings),
+            ]
+            .spacing(20)
+            .align_x(Alignment::Center)
+            .max_width(600),
+        )
+        .padding(30)
+        .center_x(Length::Fill)
+        .into()
+    }
+
+    /// Zvaliduje zadané období hlášení CCLI
+    fn period(&self) -> Result<ReportPeriod> {
+        let parse = |input: &str| {
+            let input: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+            NaiveDate::parse_from_str(&input, DATE_FORMAT)
+                .with_context(|| format!("Datum '{input}' musí být ve tvaru dd.mm.rrrr"))
+        };
+
+        ReportPeriod::new(parse(&self.period_from)?, parse(&self.period_to)?)
+    }
+
+    /// Update funkce pro obrazovku přehledů. Pokud je tato funkce zavolána nad jinou
+    /// obrazovkou než [`Screen::Reports`], zpanikaří.
+    pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
+        let exporter = match &mut state.screen {
+            Screen::Reports(exporter) => exporter,
+            screen => panic!(
+                "Update pro přehledy zavolán nad jinou obrazovkou {:?}",
+                screen
+            ),
+        };
+
+        match msg {
+            Message::Export(report) => {
+                debug!("Exportuji přehled {report}");
+                let db = state.db.clone();
+                Task::perform(
+                    async move {
+                        let Some(file) = rfd::AsyncFileDialog::new()
+                            .add_filter("CSV", &["csv"])
+                            .set_file_name(report.default_file_name())
+                            .save_file()
+                            .await
+                        else {
+                            return Ok(None);
+                        };
+
+                        let mut conn = db
+                            .acquire()
+                            .await
+                            .context("Nelze získat připojení k databázi")?;
+                        let csv = report.to_csv(&mut conn).await?;
+                        std::fs::write(file.path(), csv).with_context(|| {
+                            format!("Nelze zapsat přehled do {}", file.path().display())
+                        })?;
+
+                        Ok(Some(file.path().display().to_string()))
+                    },
+                    |res: Result<Option<String>>| match res {
+                        Ok(Some(path)) => Message::Exported(path).into(),
+                        Ok(None) => Message::DialogCancelled.into(),
+                        Err(e) => Message::ExportFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::PeriodFromChanged(input) => {
+                exporter.period_from = input;
+                Task::none()
+            }
+            Message::PeriodToChanged(input) => {
+                exporter.period_to = input;
+                Task::none()
+            }
+            Message::ExportCcli => match exporter.period() {
+                Ok(period) => Task::done(Message::Export(Report::Ccli(period)).into()),
+                Err(e) => Task::done(Message::ExportFailed(format!("{:#}", e)).into()),
+            },
+            Message::Exported(path) => {
+                debug!("Přehled exportován do {path}");
+                exporter.err_msg.clear();
+                exporter.info_msg = format!("Přehled uložen do {path}");
+                Task::none()
+            }
+            Message::DialogCancelled => {
+                debug!("Dialog pro uložení přehledu zrušen");
+                Task::none()
+            }
+            Message::ExportFailed(err_msg) => {
+                debug!("Export přehledu selhal: {err_msg}");
+                exporter.info_msg.clear();
+                exporter.err_msg = err_msg;
+                Task::none()
+            }
+            Message::ReturnToSettings => {
+                debug!("Vracím se do nastavení");
+                state.screen = Screen::Settings(SettingsEditor::new(&state.config.settings));
+                Task::done(crate::settings::Message::LoadTranslations.into())
+            }
+        }
+    }
+}
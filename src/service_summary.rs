@@ -0,0 +1,168 @@
+//! Obrazovka zobrazená po skončení prezentace. Nabídne uložení naměřených délek
+//! jednotlivých položek (ze záznamu průběhu prezentace) zpět do playlistu jako jejich
+//! očekávané délky, aby byl odhad délky bohoslužby příště přesnější.
+
+use anyhow::Context;
+use ekkles_data::playlist::PlaylistMetadata;
+use iced::{
+    Alignment, Element, Length, Task,
+    widget::{button, column, container, row, scrollable, text},
+};
+use log::debug;
+
+use crate::{
+    Ekkles, Screen,
+    components::format_duration,
+    pick_playlist::{self, PlaylistPicker},
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Záznam průběhu prezentace byl zapsán do databáze
+    LogSaved,
+    SaveDurations,
+    DurationsSaved,
+    SaveFailed(String),
+    ReturnToPlaylistPicker,
+}
+
+impl From<Message> for crate::Message {
+    fn from(value: Message) -> Self {
+        crate::Message::ServiceSummary(value)
+    }
+}
+
+impl Message {
+    /// Zda je zpráva povolená v režimu hosta, záznam prezentace se ukládá vždy,
+    /// očekávané délky v playlistu host měnit nemůže
+    pub fn allowed_in_guest_mode(&self) -> bool {
+        !matches!(self, Message::SaveDurations | Message::DurationsSaved)
+    }
+}
+
+/// Naměřená délka jedné položky playlistu
+#[derive(Debug, Clone)]
+pub struct MeasuredItem {
+    /// Index položky v playlistu
+    pub item_index: usize,
+    /// Popisek položky (název písně, rozsah pasáže)
+    pub label: String,
+    /// Jak dlouho byla položka promítána v sekundách
+    pub duration: u32,
+}
+
+#[derive(Debug)]
+pub struct ServiceSummary {
+    playlist_id: i64,
+    items: Vec<MeasuredItem>,
+    err_msg: String,
+}
+
+impl ServiceSummary {
+    pub fn new(playlist_id: i64, items: Vec<MeasuredItem>) -> Self {
+        Self {
+            playlist_id,
+            items,
+            err_msg: String::new(),
+        }
+    }
+
+    /// V režimu hosta (`guest_mode`) nelze naměřené délky uložit do playlistu
+    pub fn view(&self, guest_mode: bool) -> Element<Message> {
+        let items = self.items.iter().map(|item| {
+            row![
+                text(&item.label).width(Length::Fill),
+                text(format_duration(item.duration)),
+            ]
+            .spacing(10)
+            .into()
+        });
+
+        let total: u32 = self.items.iter().map(|item| item.duration).sum();
+
+        container(
+            column![
+                text("Prezentace skončila"),
+                text("Uložit naměřené délky položek jako očekávané délky pro příště?"),
+                scrollable(column(items).spacing(5)).height(Length::Fill),
+                text(format!("Celkem: {}", format_duration(total))),
+                row![
+                    button("Uložit délky")
+                        .style(button::success)
+                        .on_press_maybe((!guest_mode).then_some(Message::SaveDurations)),
+                    button("Neukládat").on_press(Message::ReturnToPlaylistPicker),
+                ]
+                .spacing(10),
+                text(&self.err_msg).style(text::danger),
+            ]
+            .spacing(20)
+            .align_x(Alignment::Center)
+            .max_width(600),
+        )
+        .padding(30)
+        .center_x(Length::Fill)
+        .into()
+    }
+
+    pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
+        let summary = match &mut state.screen {
+            Screen::ServiceSummary(summary) => summary,
+            screen => panic!(
+                "Update pro shrnutí prezentace zavolán nad jinou obrazovkou {:?}",
+                screen
+            ),
+        };
+
+        match msg {
+            Message::LogSaved => {
+                debug!("Záznam o prezentaci uložen");
+                Task::none()
+            }
+            Message::SaveDurations => {
+                debug!(
+                    "Ukládám naměřené délky do playlistu {}",
+                    summary.playlist_id
+                );
+                let playlist_id = summary.playlist_id;
+                let measured: Vec<(usize, u32)> = summary
+                    .items
+                    .iter()
+                    .map(|item| (item.item_index, item.duration))
+                    .collect();
+                let db = state.db.clone();
+                Task::perform(
+                    async move {
+                        let conn = db
+                            .acquire()
+                            .await
+                            .context("Nelze získat připojení k databázi")?;
+                        let mut playlist = PlaylistMetadata::load(playlist_id, conn).await?;
+
+                        for (index, duration) in measured {
+                            playlist.set_expected_duration(index, Some(duration))?;
+                        }
+
+                        let mut conn = db
+                            .acquire()
+                            .await
+                            .context("Nelze získat připojení k databázi")?;
+                        playlist.save(&mut conn).await
+                    },
+                    |res| match res {
+                        Ok(_) => Message::DurationsSaved.into(),
+                        Err(e) => Message::SaveFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::SaveFailed(err_msg) => {
+                summary.err_msg = err_msg;
+                Task::none()
+            }
+            Message::DurationsSaved | Message::ReturnToPlaylistPicker => {
+                debug!("Vracím se na výběr playlistů");
+                state.screen = Screen::PickPlaylist(PlaylistPicker::new());
+                Task::done(pick_playlist::Message::LoadPlaylists.into())
+            }
+        }
+    }
+}
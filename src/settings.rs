@@ -0,0 +1,1516 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{Local, NaiveTime};
+use ekkles_data::{
+    archive::{self, ArchiveSummary},
+    bible::{cross_reference, get_available_translations},
+    media::{self, MediaInfo, NewMedia},
+    theme::{self, BackgroundImage, MAX_BACKGROUND_DIMMING, SlideTheme, hex_to_rgb, rgb_to_hex},
+};
+use iced::{
+    Alignment, Element, Length, Task,
+    widget::{
+        button, checkbox, column, container, pick_list, row, slider, text, text_editor, text_input,
+    },
+};
+use log::{debug, trace};
+
+use crate::{
+    Ekkles, Screen,
+    components::{MediaOption, TopButtonsMessage, TopButtonsPickedSection, top_buttons},
+    config::{
+        CountdownSettings, DEFAULT_MQTT_TOPIC_PREFIX, DMX_CHANNELS, EventHooks, Keymap,
+        LightingSettings, MAX_ARTNET_UNIVERSE, MAX_SERVICE_END_WARNING_MINUTES, MAX_VERSE_CACHE_MB,
+        MonitorMapping, MqttSettings, PresentationTheme, QuickSlide, RecentFiles, ServiceEndAction,
+        ServiceEndSettings, Settings, parse_macros, parse_pin,
+    },
+    diagnostics::DiagnosticsPanel,
+    pick_playlist::{self, PlaylistPicker},
+    profile_picker::ProfilePicker,
+    reports::ReportExporter,
+    slide_render::{MAX_SONG_LINES, MAX_VERSES_PER_SLIDE, REFERENCE_SIZE},
+};
+
+/// Přípona souborů s exportovaným profilem
+const PROFILE_EXTENSION: &str = "toml";
+/// Výchozí název exportovaného profilu
+const PROFILE_DEFAULT_FILE_NAME: &str = "ekkles_profil.toml";
+/// Výchozí název souboru s obrázkem prezentace
+const COMPANION_IMAGE_DEFAULT_FILE_NAME: &str = "ekkles_prezentace.png";
+/// Prefix názvu složky s archivem celé databáze, doplní se o datum exportu
+const ARCHIVE_DIR_PREFIX: &str = "ekkles_archiv";
+/// Předvolené ztmavení obrázku na pozadí nového motivu (v procentech)
+const DEFAULT_BACKGROUND_DIMMING: u8 = 40;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    TopButtonSongs,
+    TopButtonPlaylists,
+    TopButtonSettings,
+    LoadTranslations,
+    TranslationsLoaded(Vec<String>),
+    DefaultTranslationPicked(String),
+    FreezeKeyChanged(String),
+    NormalKeyChanged(String),
+    BlankKeyChanged(String),
+    LanguageKeyChanged(String),
+    RepeatPartKeyChanged(String),
+    TextColorChanged(String),
+    BackgroundColorChanged(String),
+    PositionXChanged(String),
+    PositionYChanged(String),
+    LicensingModeToggled(bool),
+    OfflineModeToggled(bool),
+    /// Kontrola zápisu, viz [`ekkles_data::verify`]
+    VerifySavesToggled(bool),
+    /// Změna počtu veršů na slajdu při automatickém rozdělení pasáže
+    VersesPerSlideChanged(u8),
+    PassageHeadingsToggled(bool),
+    VerseCacheChanged(u16),
+    /// Změna nejvyššího počtu řádků části písně na slajdu, 0 = části se nedělí
+    MaxSongLinesChanged(u8),
+    MqttBrokerChanged(String),
+    MqttTopicPrefixChanged(String),
+    MqttControlToggled(bool),
+    LightingNodeChanged(String),
+    LightingUniverseChanged(String),
+    LightingChannelChanged(String),
+    /// Otevře dialog pro výběr cesty obrázku prezentace
+    PickCompanionImagePath,
+    CompanionImagePathPicked(PathBuf),
+    /// Vypne zápis obrázku prezentace
+    ClearCompanionImagePath,
+    PinChanged(String),
+    MacrosEdited(text_editor::Action),
+    NewQuickSlideNameChanged(String),
+    NewQuickSlideKeyChanged(String),
+    NewQuickSlideContentEdited(text_editor::Action),
+    /// Přidá rychlý slajd z formuláře, uloží se až s nastavením
+    AddQuickSlide,
+    DeleteQuickSlide(usize),
+    /// Změna počtu minut před koncem bohoslužby, kdy se začne varovat
+    ServiceEndWarningMinutesChanged(u8),
+    ServiceEndActionPicked(ServiceEndAction),
+    CountdownStartTimeChanged(String),
+    CountdownBackgroundChanged(String),
+    LoadThemes,
+    ThemesLoaded(Vec<(i64, String)>),
+    NewThemeNameChanged(String),
+    /// Byl vybrán obrázek na pozadí nového motivu
+    NewThemeBackgroundPicked(MediaOption),
+    ClearNewThemeBackground,
+    NewThemeDimmingChanged(u8),
+    /// Uloží aktuální barvy prezentace (a vybraný obrázek na pozadí) jako nový pojmenovaný motiv
+    AddTheme,
+    DeleteTheme(i64),
+    LoadMedia,
+    MediaLoaded(Vec<MediaInfo>),
+    /// Naimportuje obrázek na pozadí, zmenšený na rozlišení výstupu
+    ImportMedia,
+    MediaImported(String),
+    DeleteMedia(i64),
+    /// Nahradí křížové odkazy odkazy z datové sady OpenBible.info
+    ImportCrossReferences,
+    CrossReferencesImported(usize),
+    Save,
+    ExportProfile,
+    ProfileExported,
+    ImportProfile,
+    ProfileImported(Settings),
+    /// Vybere složku a exportuje do ní celou databázi v čitelné podobě, viz [`archive`]
+    ExportArchive,
+    ArchiveExported(PathBuf, ArchiveSummary),
+    /// Přejde na export přehledů o používání písní
+    OpenReports,
+    OpenDiagnostics,
+    /// Přejde na výběr a správu profilů
+    OpenProfiles,
+    /// Zapomene naposledy otevřené databáze a složky importu, viz [`RecentFiles`]
+    ClearRecentFiles,
+    DialogCancelled,
+    OperationFailed(String),
+    ReturnToPlaylistPicker,
+}
+
+impl From<Message> for crate::Message {
+    fn from(value: Message) -> Self {
+        crate::Message::Settings(value)
+    }
+}
+
+impl From<TopButtonsMessage> for Message {
+    fn from(value: TopButtonsMessage) -> Self {
+        match value {
+            TopButtonsMessage::Playlists => Message::TopButtonPlaylists,
+            TopButtonsMessage::Songs => Message::TopButtonSongs,
+            TopButtonsMessage::Settings => Message::TopButtonSettings,
+        }
+    }
+}
+
+/// Obrazovka s uživatelským nastavením. Všechny položky se editují v textové podobě
+/// a teprve při uložení/exportu se validují a převádí na [`Settings`].
+#[derive(Debug)]
+pub struct SettingsEditor {
+    translations: Option<Vec<String>>,
+    default_translation: Option<String>,
+    freeze_key: String,
+    normal_key: String,
+    blank_key: String,
+    language_key: String,
+    repeat_part_key: String,
+    text_color: String,
+    background_color: String,
+    position_x: String,
+    position_y: String,
+    /// Pojmenované motivy v databázi (id, název)
+    themes: Option<Vec<(i64, String)>>,
+    new_theme_name: String,
+    /// Obrázek na pozadí nového motivu, `None` = pouze barva pozadí
+    new_theme_background: Option<MediaOption>,
+    /// Ztmavení obrázku na pozadí nového motivu v procentech
+    new_theme_dimming: u8,
+    /// Obrázky na pozadí v databázi
+    media: Option<Vec<MediaInfo>>,
+    /// Schválené zdroje slov písní, v GUI se needitují, pouze se zachovají
+    lyrics_sources: Vec<String>,
+    /// Příkazy událostí prezentace, v GUI se needitují, pouze se zachovají
+    event_hooks: EventHooks,
+    licensing_mode: bool,
+    offline_mode: bool,
+    verify_saves: bool,
+    verses_per_slide: u8,
+    passage_headings: bool,
+    /// Velikost cache veršů Bible v MB, 0 = vypnutá
+    verse_cache_mb: u16,
+    /// Nejvyšší počet řádků části písně na slajdu, 0 = části se nedělí
+    max_song_lines: u8,
+    /// Připojení k MQTT brokeru, vstupy se upravují přímo
+    mqtt: MqttSettings,
+    /// Adresa Art-Net uzlu pro světelná cue, viz [`LightingSettings`]
+    lighting_node: String,
+    lighting_universe: String,
+    lighting_channel: String,
+    /// Kam se během prezentace zapisuje promítaný slajd, `None` = nezapisuje se
+    companion_image_path: Option<PathBuf>,
+    /// Interval zápisu obrázku prezentace, v GUI se needituje, pouze se zachová
+    companion_image_interval_ms: Option<u64>,
+    /// Zvolená databáze, mění se přepnutím databáze ve výběru playlistů, zde se pouze zachová
+    database_path: Option<PathBuf>,
+    /// PIN pro mazání a vstup do nastavení, prázdný = bez PINu
+    pin: String,
+    /// Makra prezentéru, jedno na řádek, viz [`crate::config::PresenterMacro`]
+    macros: text_editor::Content,
+    quick_slides: Vec<QuickSlide>,
+    new_quick_slide_name: String,
+    new_quick_slide_key: String,
+    new_quick_slide_content: text_editor::Content,
+    /// Kolik minut před koncem bohoslužby se začne varovat
+    service_end_warning_minutes: u8,
+    service_end_action: ServiceEndAction,
+    /// Předvyplněný čas začátku bohoslužby pro odpočet (`HH:MM`), prázdný = nic
+    countdown_start_time: String,
+    /// Barva pozadí slajdu s odpočtem, prázdná = pozadí z motivu prezentace
+    countdown_background: String,
+    /// Naposledy otevřené databáze a složky importu (pouze pro zobrazení), načtou se
+    /// spolu s překlady
+    recent_files: RecentFiles,
+    err_msg: String,
+    info_msg: String,
+}
+
+impl SettingsEditor {
+    pub fn new(settings: &Settings) -> Self {
+        let (position_x, position_y) = match settings.monitor.position {
+            Some((x, y)) => (x.to_string(), y.to_string()),
+            None => (String::new(), String::new()),
+        };
+
+        Self {
+            translations: None,
+            default_translation: settings.default_translation.clone(),
+            freeze_key: settings.keymap.freeze.clone(),
+            normal_key: settings.keymap.normal.clone(),
+            blank_key: settings.keymap.blank.clone(),
+            language_key: settings.keymap.language.clone(),
+            repeat_part_key: settings.keymap.repeat_part.clone(),
+            text_color: rgb_to_hex(settings.theme.text_color),
+            background_color: rgb_to_hex(settings.theme.background_color),
+            position_x,
+            position_y,
+            themes: None,
+            new_theme_name: String::new(),
+            new_theme_background: None,
+            new_theme_dimming: DEFAULT_BACKGROUND_DIMMING,
+            media: None,
+            lyrics_sources: settings.lyrics_sources.clone(),
+            event_hooks: settings.event_hooks.clone(),
+            licensing_mode: settings.licensing_mode,
+            offline_mode: settings.offline_mode,
+            verify_saves: settings.verify_saves,
+            verses_per_slide: settings.verses_per_slide(),
+            passage_headings: settings.passage_headings,
+            verse_cache_mb: settings.verse_cache_mb(),
+            max_song_lines: settings.max_song_lines.unwrap_or(0).min(MAX_SONG_LINES),
+            mqtt: settings.mqtt.clone(),
+            lighting_node: settings.lighting.node.clone(),
+            lighting_universe: settings.lighting.universe.to_string(),
+            lighting_channel: settings.lighting.channel().to_string(),
+            companion_image_path: settings.companion_image_path.clone(),
+            companion_image_interval_ms: settings.companion_image_interval_ms,
+            database_path: settings.database_path.clone(),
+            pin: settings.pin.clone().unwrap_or_default(),
+            macros: text_editor::Content::with_text(
+                &settings
+                    .macros
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            quick_slides: settings.quick_slides.clone(),
+            new_quick_slide_name: String::new(),
+            new_quick_slide_key: String::new(),
+            new_quick_slide_content: text_editor::Content::new(),
+            service_end_warning_minutes: settings.service_end.warning_minutes(),
+            service_end_action: settings.service_end.action.clone(),
+            countdown_start_time: settings.countdown.start_time.clone().unwrap_or_default(),
+            countdown_background: settings
+                .countdown
+                .background_color
+                .map(rgb_to_hex)
+                .unwrap_or_default(),
+            recent_files: RecentFiles::default(),
+            err_msg: String::new(),
+            info_msg: String::new(),
+        }
+    }
+
+    /// Zvaliduje zadané hodnoty a sestaví z nich [`Settings`]. Pokud je nějaká
+    /// hodnota nevalidní, vrací Error s popisem, co je špatně.
+    fn validate(&self) -> Result<Settings> {
+        let keymap = Keymap {
+            freeze: validate_key(&self.freeze_key).context("Neplatná klávesa pro zmrazení")?,
+            normal: validate_key(&self.normal_key).context("Neplatná klávesa pro normál")?,
+            blank: validate_key(&self.blank_key).context("Neplatná klávesa pro prázdný snímek")?,
+            language: validate_key(&self.language_key)
+                .context("Neplatná klávesa pro přepnutí jazyka")?,
+            repeat_part: validate_key(&self.repeat_part_key)
+                .context("Neplatná klávesa pro opakování části písně")?,
+        };
+
+        let theme = PresentationTheme {
+            text_color: hex_to_rgb(&self.text_color)
+                .ok_or(anyhow!("Barva textu musí být ve tvaru #rrggbb"))?,
+            background_color: hex_to_rgb(&self.background_color)
+                .ok_or(anyhow!("Barva pozadí musí být ve tvaru #rrggbb"))?,
+            background_image: None,
+        };
+
+        let position = match (self.position_x.trim(), self.position_y.trim()) {
+            ("", "") => None,
+            (x, y) => Some((
+                x.parse().context("Pozice x musí být číslo")?,
+                y.parse().context("Pozice y musí být číslo")?,
+            )),
+        };
+
+        keymap.validate()?;
+        let pin = parse_pin(&self.pin).context("Neplatný PIN")?;
+
+        let macros = parse_macros(&self.macros.text()).context("Neplatné makro")?;
+
+        self.mqtt.broker_address()?;
+
+        let lighting = LightingSettings {
+            node: self.lighting_node.trim().to_string(),
+            universe: match self.lighting_universe.trim() {
+                "" => 0,
+                universe => universe
+                    .parse()
+                    .ok()
+                    .filter(|universe| *universe <= MAX_ARTNET_UNIVERSE)
+                    .with_context(|| {
+                        format!("DMX universe musí být číslo 0 až {MAX_ARTNET_UNIVERSE}")
+                    })?,
+            },
+            channel: match self.lighting_channel.trim() {
+                "" => 1,
+                channel => channel
+                    .parse()
+                    .ok()
+                    .filter(|channel| (1..=DMX_CHANNELS).contains(channel))
+                    .with_context(|| format!("DMX kanál musí být číslo 1 až {DMX_CHANNELS}"))?,
+            },
+        };
+        lighting.node_address()?;
+
+        // Klávesy maker a rychlých slajdů se nesmí krýt s ostatními zkratkami ani navzájem
+        let mut used_keys: Vec<_> = keymap.keys().into_iter().map(|(key, _)| key).collect();
+        let hotkeys = macros
+            .iter()
+            .map(|presenter_macro| (&presenter_macro.key, &presenter_macro.name))
+            .chain(
+                self.quick_slides
+                    .iter()
+                    .map(|quick_slide| (&quick_slide.key, &quick_slide.name)),
+            );
+        for (key, name) in hotkeys {
+            if used_keys.contains(&key) {
+                bail!("Klávesa \"{key}\" pro \"{name}\" je již použita pro jinou zkratku");
+            }
+            used_keys.push(key);
+        }
+
+        if let ServiceEndAction::QuickSlide(name) = &self.service_end_action
+            && !self
+                .quick_slides
+                .iter()
+                .any(|quick_slide| &quick_slide.name == name)
+        {
+            bail!("Rychlý slajd \"{name}\" pro konec bohoslužby neexistuje");
+        }
+
+        let countdown = CountdownSettings {
+            start_time: match self.countdown_start_time.trim() {
+                "" => None,
+                time => Some(
+                    NaiveTime::parse_from_str(time, "%H:%M")
+                        .ok()
+                        .context("Čas začátku bohoslužby musí být ve tvaru HH:MM")?
+                        .format("%H:%M")
+                        .to_string(),
+                ),
+            },
+            background_color: match self.countdown_background.trim() {
+                "" => None,
+                color => Some(
+                    hex_to_rgb(color)
+                        .ok_or(anyhow!("Barva pozadí odpočtu musí být ve tvaru #rrggbb"))?,
+                ),
+            },
+        };
+
+        Ok(Settings {
+            theme,
+            keymap,
+            default_translation: self.default_translation.clone(),
+            monitor: MonitorMapping { position },
+            lyrics_sources: self.lyrics_sources.clone(),
+            licensing_mode: self.licensing_mode,
+            database_path: self.database_path.clone(),
+            pin,
+            macros,
+            quick_slides: self.quick_slides.clone(),
+            offline_mode: self.offline_mode,
+            verify_saves: self.verify_saves,
+            event_hooks: self.event_hooks.clone(),
+            verses_per_slide: Some(self.verses_per_slide),
+            passage_headings: self.passage_headings,
+            verse_cache_mb: Some(self.verse_cache_mb),
+            max_song_lines: (self.max_song_lines > 0).then_some(self.max_song_lines),
+            mqtt: self.mqtt.clone(),
+            lighting,
+            companion_image_path: self.companion_image_path.clone(),
+            companion_image_interval_ms: self.companion_image_interval_ms,
+            service_end: ServiceEndSettings {
+                warning_minutes: Some(self.service_end_warning_minutes),
+                action: self.service_end_action.clone(),
+            },
+            countdown,
+        })
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let translation_picker = pick_list(
+            self.translations.clone().unwrap_or_default(),
+            self.default_translation.clone(),
+            Message::DefaultTranslationPicked,
+        )
+        .placeholder(if self.translations.is_some() {
+            "Vyber výchozí překlad"
+        } else {
+            "Načítám překlady..."
+        })
+        .width(Length::FillPortion(2));
+
+        let themes: Element<Message> = match &self.themes {
+            Some(themes) if themes.is_empty() => text("Žádné motivy").into(),
+            Some(themes) => column(themes.iter().map(|(id, name)| {
+                row![
+                    text(name).width(Length::Fill),
+                    button("Smazat")
+                        .style(button::danger)
+                        .on_press(Message::DeleteTheme(*id)),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .into()
+            }))
+            .spacing(5)
+            .into(),
+            None => text("Načítám motivy...").into(),
+        };
+
+        let media: Element<Message> = match &self.media {
+            Some(media) if media.is_empty() => text("Žádné obrázky").into(),
+            Some(media) => column(media.iter().map(|info| {
+                row![
+                    text(&info.name).width(Length::Fill),
+                    text(format!("{}×{}", info.width, info.height)),
+                    button("Smazat")
+                        .style(button::danger)
+                        .on_press(Message::DeleteMedia(info.id)),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .into()
+            }))
+            .spacing(5)
+            .into(),
+            None => text("Načítám obrázky...").into(),
+        };
+
+        let quick_slides: Element<Message> = if self.quick_slides.is_empty() {
+            text("Žádné rychlé slajdy").into()
+        } else {
+            column(
+                self.quick_slides
+                    .iter()
+                    .enumerate()
+                    .map(|(index, quick_slide)| {
+                        row![
+                            text(format!("{} ({})", quick_slide.name, quick_slide.key))
+                                .width(Length::Fill),
+                            button("Smazat")
+                                .style(button::danger)
+                                .on_press(Message::DeleteQuickSlide(index)),
+                        ]
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .into()
+                    }),
+            )
+            .spacing(5)
+            .into()
+        };
+
+        let form = column![
+            text("Prezentace"),
+            labeled(
+                "Barva textu",
+                text_input("#ffffff", &self.text_color)
+                    .on_input(Message::TextColorChanged)
+                    .width(Length::FillPortion(2))
+            ),
+            labeled(
+                "Barva pozadí",
+                text_input("#000000", &self.background_color)
+                    .on_input(Message::BackgroundColorChanged)
+                    .width(Length::FillPortion(2))
+            ),
+            labeled(
+                "Pozice prezentačního okna (x, y)",
+                row![
+                    text_input("x", &self.position_x).on_input(Message::PositionXChanged),
+                    text_input("y", &self.position_y).on_input(Message::PositionYChanged),
+                ]
+                .spacing(5)
+                .width(Length::FillPortion(2))
+            ),
+            labeled(
+                "Nejvíce řádků písně na slajdu",
+                row![
+                    slider(
+                        0..=MAX_SONG_LINES,
+                        self.max_song_lines,
+                        Message::MaxSongLinesChanged
+                    ),
+                    text(match self.max_song_lines {
+                        0 => String::from("Nedělit"),
+                        lines => lines.to_string(),
+                    }),
+                ]
+                .spacing(10)
+                .width(Length::FillPortion(2))
+            ),
+            text("Klávesové zkratky"),
+            labeled(
+                "Zmrazit",
+                text_input("f", &self.freeze_key)
+                    .on_input(Message::FreezeKeyChanged)
+                    .width(Length::FillPortion(2))
+            ),
+            labeled(
+                "Normál",
+                text_input("n", &self.normal_key)
+                    .on_input(Message::NormalKeyChanged)
+                    .width(Length::FillPortion(2))
+            ),
+            labeled(
+                "Prázdný snímek",
+                text_input("b", &self.blank_key)
+                    .on_input(Message::BlankKeyChanged)
+                    .width(Length::FillPortion(2))
+            ),
+            labeled(
+                "Jazyk písní",
+                text_input("j", &self.language_key)
+                    .on_input(Message::LanguageKeyChanged)
+                    .width(Length::FillPortion(2))
+            ),
+            labeled(
+                "Opakovat část písně",
+                text_input("r", &self.repeat_part_key)
+                    .on_input(Message::RepeatPartKeyChanged)
+                    .width(Length::FillPortion(2))
+            ),
+            text("Makra (klávesa | název | blank, normal, freeze, next, prev, goto 1, wait 2)"),
+            text_editor(&self.macros)
+                .placeholder("u | Úvod | blank, wait 2, goto 1, normal")
+                .on_action(Message::MacrosEdited),
+            text("Rychlé slajdy"),
+            quick_slides,
+            row![
+                text_input("Název rychlého slajdu", &self.new_quick_slide_name)
+                    .on_input(Message::NewQuickSlideNameChanged),
+                text_input("Klávesa", &self.new_quick_slide_key)
+                    .on_input(Message::NewQuickSlideKeyChanged),
+            ]
+            .spacing(10),
+            text_editor(&self.new_quick_slide_content)
+                .placeholder("Promítaný text, např. Prosíme, ztlumte své telefony")
+                .on_action(Message::NewQuickSlideContentEdited),
+            button("Přidat rychlý slajd").on_press(Message::AddQuickSlide),
+            text("Konec bohoslužby"),
+            labeled(
+                "Varovat minut předem",
+                row![
+                    slider(
+                        1..=MAX_SERVICE_END_WARNING_MINUTES,
+                        self.service_end_warning_minutes,
+                        Message::ServiceEndWarningMinutesChanged
+                    ),
+                    text(self.service_end_warning_minutes.to_string()),
+                ]
+                .spacing(10)
+                .width(Length::FillPortion(2))
+            ),
+            labeled(
+                "V čase konce",
+                pick_list(
+                    [ServiceEndAction::Warn, ServiceEndAction::Blank]
+                        .into_iter()
+                        .chain(self.quick_slides.iter().map(|quick_slide| {
+                            ServiceEndAction::QuickSlide(quick_slide.name.clone())
+                        }))
+                        .collect::<Vec<_>>(),
+                    Some(self.service_end_action.clone()),
+                    Message::ServiceEndActionPicked,
+                )
+                .width(Length::FillPortion(2))
+            ),
+            text("Odpočet před začátkem bohoslužby"),
+            labeled(
+                "Předvyplněný čas začátku (prázdný = žádný)",
+                text_input("HH:MM", &self.countdown_start_time)
+                    .on_input(Message::CountdownStartTimeChanged)
+                    .width(Length::FillPortion(2))
+            ),
+            labeled(
+                "Barva pozadí odpočtu (prázdná = z motivu)",
+                text_input("#000000", &self.countdown_background)
+                    .on_input(Message::CountdownBackgroundChanged)
+                    .width(Length::FillPortion(2))
+            ),
+            text("Licence"),
+            checkbox(
+                "Licenční režim (varovat u písní bez CCLI čísla nebo licence)",
+                self.licensing_mode
+            )
+            .on_toggle(Message::LicensingModeToggled),
+            text("Zabezpečení"),
+            checkbox(
+                "Offline režim (žádný přístup k síti, např. stahování slov písní)",
+                self.offline_mode
+            )
+            .on_toggle(Message::OfflineModeToggled),
+            checkbox(
+                "Kontrolovat uložené písně a playlisty (načíst zpět a porovnat, pomalejší)",
+                self.verify_saves
+            )
+            .on_toggle(Message::VerifySavesToggled),
+            labeled(
+                "PIN pro mazání a nastavení (prázdný = bez PINu)",
+                text_input("1234", &self.pin)
+                    .on_input(Message::PinChanged)
+                    .secure(true)
+                    .width(Length::FillPortion(2))
+            ),
+            text("MQTT (domácí automatizace)"),
+            labeled(
+                "Broker (prázdný = vypnuto)",
+                text_input("host:1883", &self.mqtt.broker)
+                    .on_input(Message::MqttBrokerChanged)
+                    .width(Length::FillPortion(2))
+            ),
+            labeled(
+                "Prefix témat",
+                text_input(DEFAULT_MQTT_TOPIC_PREFIX, &self.mqtt.topic_prefix)
+                    .on_input(Message::MqttTopicPrefixChanged)
+                    .width(Length::FillPortion(2))
+            ),
+            checkbox(
+                "Přijímat ovládání prezentace (téma prefix/control)",
+                self.mqtt.control
+            )
+            .on_toggle(Message::MqttControlToggled),
+            text("Světla (Art-Net)"),
+            labeled(
+                "Uzel (prázdný = vypnuto)",
+                text_input("2.255.255.255", &self.lighting_node)
+                    .on_input(Message::LightingNodeChanged)
+                    .width(Length::FillPortion(2))
+            ),
+            labeled(
+                "Universe a kanál pro číslo cue",
+                row![
+                    text_input("0", &self.lighting_universe)
+                        .on_input(Message::LightingUniverseChanged),
+                    text_input("1", &self.lighting_channel)
+                        .on_input(Message::LightingChannelChanged),
+                ]
+                .spacing(5)
+                .width(Length::FillPortion(2))
+            ),
+            text("Obrázek prezentace (OBS, digital signage)"),
+            labeled(
+                "Soubor PNG",
+                row![
+                    text(match &self.companion_image_path {
+                        Some(path) => path.display().to_string(),
+                        None => String::from("Nezapisuje se"),
+                    })
+                    .width(Length::Fill),
+                    button("Vybrat").on_press(Message::PickCompanionImagePath),
+                    button("Vypnout").on_press_maybe(
+                        self.companion_image_path
+                            .is_some()
+                            .then_some(Message::ClearCompanionImagePath)
+                    ),
+                ]
+                .spacing(5)
+                .align_y(Alignment::Center)
+                .width(Length::FillPortion(2))
+            ),
+            text("Bible"),
+            labeled("Výchozí překlad", translation_picker),
+            labeled(
+                "Veršů na slajdu",
+                row![
+                    slider(
+                        1..=MAX_VERSES_PER_SLIDE,
+                        self.verses_per_slide,
+                        Message::VersesPerSlideChanged
+                    ),
+                    text(self.verses_per_slide.to_string()),
+                ]
+                .spacing(10)
+                .width(Length::FillPortion(2))
+            ),
+            checkbox(
+                "Zobrazovat nadpisy (např. žalmů) nad prvním veršem pasáže",
+                self.passage_headings
+            )
+            .on_toggle(Message::PassageHeadingsToggled),
+            labeled(
+                "Cache veršů (MB, 0 = vypnutá)",
+                row![
+                    slider(
+                        0..=MAX_VERSE_CACHE_MB,
+                        self.verse_cache_mb,
+                        Message::VerseCacheChanged
+                    )
+                    .step(4u16),
+                    text(self.verse_cache_mb.to_string()),
+                ]
+                .spacing(10)
+                .width(Length::FillPortion(2))
+            ),
+            button("Importovat křížové odkazy (OpenBible.info)")
+                .on_press(Message::ImportCrossReferences),
+            text("Motivy"),
+            themes,
+            labeled(
+                "Obrázek na pozadí motivu",
+                row![
+                    pick_list(
+                        self.media
+                            .iter()
+                            .flatten()
+                            .map(MediaOption::from)
+                            .collect::<Vec<_>>(),
+                        self.new_theme_background.clone(),
+                        Message::NewThemeBackgroundPicked,
+                    )
+                    .placeholder("Bez obrázku")
+                    .width(Length::Fill),
+                    button("Zrušit").on_press_maybe(
+                        self.new_theme_background
+                            .as_ref()
+                            .map(|_| Message::ClearNewThemeBackground)
+                    ),
+                ]
+                .spacing(10)
+                .width(Length::FillPortion(2))
+            ),
+            labeled(
+                "Ztmavení obrázku",
+                row![
+                    slider(
+                        0..=MAX_BACKGROUND_DIMMING,
+                        self.new_theme_dimming,
+                        Message::NewThemeDimmingChanged
+                    ),
+                    text(format!("{} %", self.new_theme_dimming)),
+                ]
+                .spacing(10)
+                .width(Length::FillPortion(2))
+            ),
+            row![
+                text_input("Název nového motivu", &self.new_theme_name)
+                    .on_input(Message::NewThemeNameChanged)
+                    .on_submit(Message::AddTheme),
+                button("Uložit barvy jako motiv").on_press(Message::AddTheme),
+            ]
+            .spacing(10),
+            text("Obrázky na pozadí"),
+            media,
+            button("Importovat obrázek").on_press(Message::ImportMedia),
+        ]
+        .spacing(10);
+
+        let controls = column![
+            button("Uložit")
+                .style(button::success)
+                .on_press(Message::Save)
+                .width(Length::Fill),
+            row![
+                button("Exportovat profil")
+                    .on_press(Message::ExportProfile)
+                    .width(Length::Fill),
+                button("Importovat profil")
+                    .on_press(Message::ImportProfile)
+                    .width(Length::Fill),
+            ]
+            .spacing(10),
+            button("Exportovat vše (písně, playlisty, odkazy na Bibli)…")
+                .on_press(Message::ExportArchive)
+                .width(Length::Fill),
+            button("Přehledy pro licenční hlášení (CSV)")
+                .on_press(Message::OpenReports)
+                .width(Length::Fill),
+            button("Diagnostika (SQL dotazy)")
+                .on_press(Message::OpenDiagnostics)
+                .width(Length::Fill),
+            button("Profily (sbory, místa)…")
+                .on_press(Message::OpenProfiles)
+                .width(Length::Fill),
+            self.view_recent_files(),
+            text(&self.err_msg).style(text::danger),
+            text(&self.info_msg).style(text::success),
+        ]
+        .spacing(10);
+
+        column![
+            top_buttons(TopButtonsPickedSection::Settings).map(|msg| msg.into()),
+            container(column![form, controls].spacing(30).max_width(800))
+                .padding(10)
+                .center_x(Length::Fill)
+                .center_y(Length::Fill),
+        ]
+        .into()
+    }
+
+    /// Naposledy otevřené databáze a složky importu písní s tlačítkem pro jejich zapomenutí
+    fn view_recent_files(&self) -> Element<Message> {
+        let recent = &self.recent_files;
+        if recent.databases.is_empty() && recent.import_dirs.is_empty() {
+            return column![].into();
+        }
+
+        let paths = |paths: &[PathBuf]| {
+            column(
+                paths
+                    .iter()
+                    .map(|path| text(path.display().to_string()).size(14).into()),
+            )
+            .spacing(2)
+        };
+
+        column![
+            text("Naposledy otevřené databáze (přepnout lze ve výběru playlistů)"),
+            paths(&recent.databases),
+            text("Složky, ze kterých se naposledy importovaly písně"),
+            paths(&recent.import_dirs),
+            button("Zapomenout naposledy otevřené")
+                .style(button::secondary)
+                .on_press(Message::ClearRecentFiles)
+                .width(Length::Fill),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    /// Update funkce pro obrazovku nastavení. Pokud je tato funkce zavolána nad jinou
+    /// obrazovkou než [`Screen::Settings`], zpanikaří.
+    pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
+        let editor = match &mut state.screen {
+            Screen::Settings(editor) => editor,
+            screen => panic!(
+                "Update pro nastavení zavolán nad jinou obrazovkou {:?}",
+                screen
+            ),
+        };
+
+        match msg {
+            Message::TopButtonSongs => {
+                debug!("Editor písní zatím neexistuje, zůstávám v nastavení");
+                Task::none()
+            }
+            Message::TopButtonSettings => {
+                debug!("Jsem v nastavení a klikám, abych se do něj znovu dostal, ignoruju");
+                Task::none()
+            }
+            Message::TopButtonPlaylists | Message::ReturnToPlaylistPicker => {
+                debug!("Vracím se na výběr playlistů");
+                state.screen = Screen::PickPlaylist(PlaylistPicker::new());
+                Task::done(pick_playlist::Message::LoadPlaylists.into())
+            }
+            Message::LoadTranslations => {
+                debug!("Načítám seznam překladů pro nastavení");
+                editor.recent_files = state.config.profiles.recent.clone();
+                let conn = state.db.acquire();
+                Task::perform(
+                    async {
+                        let mut conn = conn.await?;
+                        get_available_translations(&mut conn).await
+                    },
+                    |res| match res {
+                        Ok(translations) => Message::TranslationsLoaded(
+                            translations.into_iter().map(|(_, name)| name).collect(),
+                        )
+                        .into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+                // Do nastavení se vždy vstupuje přes načtení překladů, motivy načteme s nimi
+                .chain(Task::done(Message::LoadThemes.into()))
+            }
+            Message::LoadThemes => {
+                debug!("Načítám motivy slajdů pro nastavení");
+                let conn = state.db.acquire();
+                Task::perform(
+                    async {
+                        let mut conn = conn.await?;
+                        theme::get_available(&mut conn).await
+                    },
+                    |res| match res {
+                        Ok(themes) => Message::ThemesLoaded(themes).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+                .chain(Task::done(Message::LoadMedia.into()))
+            }
+            Message::ThemesLoaded(themes) => {
+                debug!("Motivy načteny {:?}", themes);
+                editor.themes = Some(themes);
+                Task::none()
+            }
+            Message::NewThemeNameChanged(input) => {
+                trace!("Změnil se název nového motivu: {input}");
+                editor.new_theme_name = input;
+                Task::none()
+            }
+            Message::NewThemeBackgroundPicked(background) => {
+                trace!("Vybrán obrázek na pozadí nového motivu: {background}");
+                editor.new_theme_background = Some(background);
+                Task::none()
+            }
+            Message::ClearNewThemeBackground => {
+                editor.new_theme_background = None;
+                Task::none()
+            }
+            Message::NewThemeDimmingChanged(dimming) => {
+                editor.new_theme_dimming = dimming;
+                Task::none()
+            }
+            Message::AddTheme => {
+                let name = editor.new_theme_name.trim().to_string();
+                if name.is_empty() {
+                    return Task::done(
+                        Message::OperationFailed(String::from("Motiv musí mít název")).into(),
+                    );
+                }
+
+                let (Some(text_color), Some(background_color)) = (
+                    hex_to_rgb(&editor.text_color),
+                    hex_to_rgb(&editor.background_color),
+                ) else {
+                    return Task::done(
+                        Message::OperationFailed(String::from(
+                            "Barvy motivu musí být ve tvaru #rrggbb",
+                        ))
+                        .into(),
+                    );
+                };
+
+                debug!("Ukládám nový motiv {name}");
+                let slide_theme = SlideTheme {
+                    name,
+                    text_color,
+                    background_color,
+                    background_image: editor.new_theme_background.as_ref().map(|background| {
+                        BackgroundImage {
+                            media_id: background.id,
+                            dimming: editor.new_theme_dimming,
+                        }
+                    }),
+                };
+                let db = state.db.clone();
+                editor.new_theme_name.clear();
+                editor.new_theme_background = None;
+                Task::perform(
+                    async move { slide_theme.save_to_db(&db).await },
+                    |res| match res {
+                        Ok(_) => Message::LoadThemes.into(),
+                        Err(e) => Message::OperationFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::DeleteTheme(id) => {
+                debug!("Mažu motiv s id {id}");
+                let db = state.db.clone();
+                Task::perform(
+                    async move { SlideTheme::delete_from_db(id, &db).await },
+                    |res| match res {
+                        Ok(_) => Message::LoadThemes.into(),
+                        Err(e) => Message::OperationFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::LoadMedia => {
+                debug!("Načítám obrázky na pozadí pro nastavení");
+                let conn = state.db.acquire();
+                Task::perform(
+                    async {
+                        let mut conn = conn.await?;
+                        media::get_available(&mut conn).await
+                    },
+                    |res| match res {
+                        Ok(media) => Message::MediaLoaded(media).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::MediaLoaded(media) => {
+                debug!("Obrázky načteny {:?}", media);
+                // Smazaný obrázek již nelze dát na pozadí motivu
+                if editor
+                    .new_theme_background
+                    .as_ref()
+                    .is_some_and(|background| !media.iter().any(|info| info.id == background.id))
+                {
+                    editor.new_theme_background = None;
+                }
+                editor.media = Some(media);
+                Task::none()
+            }
+            Message::ImportMedia => {
+                debug!("Importuji obrázek na pozadí");
+                let db = state.db.clone();
+                Task::perform(
+                    async move {
+                        let Some(file) = rfd::AsyncFileDialog::new()
+                            .add_filter("Obrázky", &media::SUPPORTED_EXTENSIONS)
+                            .pick_file()
+                            .await
+                        else {
+                            return Ok(None);
+                        };
+
+                        // Dekódování a zmenšení velké fotky trvá, nesmí blokovat GUI
+                        let path = file.path().to_path_buf();
+                        let new_media = tokio::task::spawn_blocking(move || {
+                            NewMedia::from_file(
+                                &path,
+                                REFERENCE_SIZE.width as u32,
+                                REFERENCE_SIZE.height as u32,
+                            )
+                        })
+                        .await
+                        .context("Úloha zpracování obrázku selhala")??;
+
+                        new_media.save_to_db(&db).await?;
+                        Ok(Some(new_media.name))
+                    },
+                    |res: Result<Option<String>>| match res {
+                        Ok(Some(name)) => Message::MediaImported(name).into(),
+                        Ok(None) => Message::DialogCancelled.into(),
+                        Err(e) => Message::OperationFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::MediaImported(name) => {
+                debug!("Obrázek {name} naimportován");
+                editor.err_msg.clear();
+                editor.info_msg = format!("Obrázek '{name}' naimportován");
+                Task::done(Message::LoadMedia.into())
+            }
+            Message::DeleteMedia(id) => {
+                debug!("Mažu obrázek s id {id}");
+                let db = state.db.clone();
+                Task::perform(
+                    async move { media::delete(id, &db).await },
+                    |res| match res {
+                        Ok(_) => Message::LoadMedia.into(),
+                        Err(e) => Message::OperationFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::ImportCrossReferences => {
+                debug!("Importuji křížové odkazy");
+                let db = state.db.clone();
+                Task::perform(
+                    async move {
+                        let Some(file) = rfd::AsyncFileDialog::new()
+                            .add_filter("Křížové odkazy", &["txt"])
+                            .pick_file()
+                            .await
+                        else {
+                            return Ok(None);
+                        };
+
+                        // Datová sada má stovky tisíc řádků, parsování nesmí blokovat GUI
+                        let path = file.path().to_path_buf();
+                        let cross_references = tokio::task::spawn_blocking(move || {
+                            let input = std::fs::read_to_string(&path)
+                                .with_context(|| format!("Nelze přečíst {}", path.display()))?;
+                            cross_reference::parse_openbible(&input)
+                        })
+                        .await
+                        .context("Úloha parsování křížových odkazů selhala")??;
+
+                        cross_reference::replace_all(&cross_references, &db).await?;
+                        Ok(Some(cross_references.len()))
+                    },
+                    |res: Result<Option<usize>>| match res {
+                        Ok(Some(count)) => Message::CrossReferencesImported(count).into(),
+                        Ok(None) => Message::DialogCancelled.into(),
+                        Err(e) => Message::OperationFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::CrossReferencesImported(count) => {
+                debug!("Naimportováno {count} křížových odkazů");
+                editor.err_msg.clear();
+                editor.info_msg = format!("Naimportováno {count} křížových odkazů");
+                Task::none()
+            }
+            Message::TranslationsLoaded(translations) => {
+                debug!("Překlady načteny {:?}", translations);
+                editor.translations = Some(translations);
+                Task::none()
+            }
+            Message::DefaultTranslationPicked(name) => {
+                debug!("Vybrán výchozí překlad {name}");
+                editor.default_translation = Some(name);
+                Task::none()
+            }
+            Message::FreezeKeyChanged(input) => {
+                editor.freeze_key = last_char_lowercase(&input);
+                Task::none()
+            }
+            Message::NormalKeyChanged(input) => {
+                editor.normal_key = last_char_lowercase(&input);
+                Task::none()
+            }
+            Message::BlankKeyChanged(input) => {
+                editor.blank_key = last_char_lowercase(&input);
+                Task::none()
+            }
+            Message::LanguageKeyChanged(input) => {
+                editor.language_key = last_char_lowercase(&input);
+                Task::none()
+            }
+            Message::RepeatPartKeyChanged(input) => {
+                editor.repeat_part_key = last_char_lowercase(&input);
+                Task::none()
+            }
+            Message::TextColorChanged(input) => {
+                trace!("Změnila se barva textu: {input}");
+                editor.text_color = input;
+                Task::none()
+            }
+            Message::BackgroundColorChanged(input) => {
+                trace!("Změnila se barva pozadí: {input}");
+                editor.background_color = input;
+                Task::none()
+            }
+            Message::PositionXChanged(input) => {
+                editor.position_x = input;
+                Task::none()
+            }
+            Message::PositionYChanged(input) => {
+                editor.position_y = input;
+                Task::none()
+            }
+            Message::LicensingModeToggled(enabled) => {
+                debug!("Licenční režim: {enabled}");
+                editor.licensing_mode = enabled;
+                Task::none()
+            }
+            Message::OfflineModeToggled(enabled) => {
+                debug!("Offline režim: {enabled}");
+                editor.offline_mode = enabled;
+                Task::none()
+            }
+            Message::VerifySavesToggled(enabled) => {
+                debug!("Kontrola zápisu: {enabled}");
+                editor.verify_saves = enabled;
+                Task::none()
+            }
+            Message::VersesPerSlideChanged(verses_per_slide) => {
+                trace!("Počet veršů na slajdu: {verses_per_slide}");
+                editor.verses_per_slide = verses_per_slide;
+                Task::none()
+            }
+            Message::PassageHeadingsToggled(enabled) => {
+                debug!("Nadpisy pasáží: {enabled}");
+                editor.passage_headings = enabled;
+                Task::none()
+            }
+            Message::VerseCacheChanged(verse_cache_mb) => {
+                trace!("Velikost cache veršů: {verse_cache_mb} MB");
+                editor.verse_cache_mb = verse_cache_mb;
+                Task::none()
+            }
+            Message::MaxSongLinesChanged(max_song_lines) => {
+                trace!("Nejvyšší počet řádků části písně na slajdu: {max_song_lines}");
+                editor.max_song_lines = max_song_lines;
+                Task::none()
+            }
+            Message::MqttBrokerChanged(input) => {
+                editor.mqtt.broker = input;
+                Task::none()
+            }
+            Message::MqttTopicPrefixChanged(input) => {
+                editor.mqtt.topic_prefix = input;
+                Task::none()
+            }
+            Message::MqttControlToggled(enabled) => {
+                debug!("Ovládání z MQTT: {enabled}");
+                editor.mqtt.control = enabled;
+                Task::none()
+            }
+            Message::LightingNodeChanged(input) => {
+                editor.lighting_node = input;
+                Task::none()
+            }
+            Message::LightingUniverseChanged(input) => {
+                editor.lighting_universe = input;
+                Task::none()
+            }
+            Message::LightingChannelChanged(input) => {
+                editor.lighting_channel = input;
+                Task::none()
+            }
+            Message::PickCompanionImagePath => Task::perform(
+                rfd::AsyncFileDialog::new()
+                    .add_filter("Obrázek PNG", &["png"])
+                    .set_file_name(COMPANION_IMAGE_DEFAULT_FILE_NAME)
+                    .save_file(),
+                |file| match file {
+                    Some(file) => {
+                        Message::CompanionImagePathPicked(file.path().to_path_buf()).into()
+                    }
+                    None => Message::DialogCancelled.into(),
+                },
+            ),
+            Message::CompanionImagePathPicked(path) => {
+                debug!("Obrázek prezentace se bude zapisovat do {}", path.display());
+                editor.companion_image_path = Some(path);
+                Task::none()
+            }
+            Message::ClearCompanionImagePath => {
+                editor.companion_image_path = None;
+                Task::none()
+            }
+            Message::PinChanged(input) => {
+                trace!("Změnil se PIN");
+                editor.pin = input;
+                Task::none()
+            }
+            Message::MacrosEdited(action) => {
+                editor.macros.perform(action);
+                Task::none()
+            }
+            Message::NewQuickSlideNameChanged(input) => {
+                editor.new_quick_slide_name = input;
+                Task::none()
+            }
+            Message::NewQuickSlideKeyChanged(input) => {
+                editor.new_quick_slide_key = last_char_lowercase(&input);
+                Task::none()
+            }
+            Message::NewQuickSlideContentEdited(action) => {
+                editor.new_quick_slide_content.perform(action);
+                Task::none()
+            }
+            Message::AddQuickSlide => {
+                let name = editor.new_quick_slide_name.trim().to_string();
+                let content = editor.new_quick_slide_content.text().trim().to_string();
+                if name.is_empty() || content.is_empty() {
+                    return Task::done(
+                        Message::OperationFailed(String::from(
+                            "Rychlý slajd musí mít název i text",
+                        ))
+                        .into(),
+                    );
+                }
+                let key = match validate_key(&editor.new_quick_slide_key) {
+                    Ok(key) => key,
+                    Err(e) => return Task::done(Message::OperationFailed(e.to_string()).into()),
+                };
+
+                debug!("Přidávám rychlý slajd {name}");
+                editor.quick_slides.push(QuickSlide { name, key, content });
+                editor.new_quick_slide_name.clear();
+                editor.new_quick_slide_key.clear();
+                editor.new_quick_slide_content = text_editor::Content::new();
+                Task::none()
+            }
+            Message::DeleteQuickSlide(index) => {
+                debug!("Mažu rychlý slajd s indexem {index}");
+                let quick_slide = editor.quick_slides.remove(index);
+                // Smazaný rychlý slajd již nelze promítnout na konci bohoslužby
+                if editor.service_end_action == ServiceEndAction::QuickSlide(quick_slide.name) {
+                    editor.service_end_action = ServiceEndAction::Warn;
+                }
+                Task::none()
+            }
+            Message::ServiceEndWarningMinutesChanged(minutes) => {
+                trace!("Varování před koncem bohoslužby {minutes} minut předem");
+                editor.service_end_warning_minutes = minutes;
+                Task::none()
+            }
+            Message::ServiceEndActionPicked(action) => {
+                debug!("Akce v čase konce bohoslužby: {action}");
+                editor.service_end_action = action;
+                Task::none()
+            }
+            Message::CountdownStartTimeChanged(input) => {
+                editor.countdown_start_time = input;
+                Task::none()
+            }
+            Message::CountdownBackgroundChanged(input) => {
+                trace!("Změnila se barva pozadí odpočtu: {input}");
+                editor.countdown_background = input;
+                Task::none()
+            }
+            Message::Save => match editor.validate() {
+                Ok(settings) => {
+                    debug!("Ukládám nastavení {:#?}", settings);
+                    state.config.settings = settings;
+                    match state.config.save_settings() {
+                        Ok(_) => {
+                            editor.err_msg.clear();
+                            editor.info_msg = String::from("Nastavení uloženo");
+                        }
+                        Err(e) => {
+                            editor.info_msg.clear();
+                            editor.err_msg = format!("{:#}", e);
+                        }
+                    }
+                    Task::none()
+                }
+                Err(e) => Task::done(Message::OperationFailed(e.to_string()).into()),
+            },
+            Message::ExportProfile => match editor.validate() {
+                Ok(settings) => {
+                    debug!("Exportuji profil");
+                    Task::perform(
+                        async move {
+                            let Some(file) = rfd::AsyncFileDialog::new()
+                                .add_filter("Profil Ekklesu", &[PROFILE_EXTENSION])
+                                .set_file_name(PROFILE_DEFAULT_FILE_NAME)
+                                .save_file()
+                                .await
+                            else {
+                                return Ok(None);
+                            };
+
+                            settings.export_profile(file.path()).map(Some)
+                        },
+                        |res| match res {
+                            Ok(Some(_)) => Message::ProfileExported.into(),
+                            Ok(None) => Message::DialogCancelled.into(),
+                            Err(e) => Message::OperationFailed(format!("{:#}", e)).into(),
+                        },
+                    )
+                }
+                Err(e) => Task::done(Message::OperationFailed(e.to_string()).into()),
+            },
+            Message::ProfileExported => {
+                debug!("Profil exportován");
+                editor.err_msg.clear();
+                editor.info_msg = String::from("Profil exportován");
+                Task::none()
+            }
+            Message::ExportArchive => {
+                debug!("Exportuji celou databázi do archivu");
+                let db = state.db.clone();
+                Task::perform(
+                    async move {
+                        let Some(folder) = rfd::AsyncFileDialog::new().pick_folder().await else {
+                            return Ok(None);
+                        };
+
+                        let dir = folder.path().join(format!(
+                            "{ARCHIVE_DIR_PREFIX}_{}",
+                            Local::now().format("%Y-%m-%d")
+                        ));
+                        let summary = archive::export_archive(&dir, &db).await?;
+                        Ok(Some((dir, summary)))
+                    },
+                    |res: Result<Option<(PathBuf, ArchiveSummary)>>| match res {
+                        Ok(Some((dir, summary))) => Message::ArchiveExported(dir, summary).into(),
+                        Ok(None) => Message::DialogCancelled.into(),
+                        Err(e) => Message::OperationFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::ArchiveExported(dir, summary) => {
+                debug!("Databáze exportována do {}", dir.display());
+                editor.err_msg.clear();
+                editor.info_msg = format!(
+                    "Exportováno do {}: písní {}, playlistů {}, překladů {}",
+                    dir.display(),
+                    summary.songs,
+                    summary.playlists,
+                    summary.translations
+                );
+                Task::none()
+            }
+            Message::ImportProfile => {
+                debug!("Importuji profil");
+                Task::perform(
+                    async {
+                        let Some(file) = rfd::AsyncFileDialog::new()
+                            .add_filter("Profil Ekklesu", &[PROFILE_EXTENSION])
+                            .pick_file()
+                            .await
+                        else {
+                            return Ok(None);
+                        };
+
+                        Settings::import_profile(file.path()).map(Some)
+                    },
+                    |res| match res {
+                        Ok(Some(settings)) => Message::ProfileImported(settings).into(),
+                        Ok(None) => Message::DialogCancelled.into(),
+                        Err(e) => Message::OperationFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::ProfileImported(settings) => {
+                debug!("Profil importován, aplikuji a ukládám {:#?}", settings);
+                // Profil nenese cestu k databázi, PIN, příkazy událostí ani cestu obrázku
+                // prezentace, zůstáváme u těch aktuálních
+                let settings = Settings {
+                    database_path: state.config.settings.database_path.clone(),
+                    pin: state.config.settings.pin.clone(),
+                    event_hooks: state.config.settings.event_hooks.clone(),
+                    companion_image_path: state.config.settings.companion_image_path.clone(),
+                    ..settings
+                };
+                let translations = editor.translations.take();
+                let themes = editor.themes.take();
+                let media = editor.media.take();
+                *editor = SettingsEditor::new(&settings);
+                editor.translations = translations;
+                editor.themes = themes;
+                editor.media = media;
+                state.config.settings = settings;
+                match state.config.save_settings() {
+                    Ok(_) => editor.info_msg = String::from("Profil importován a uložen"),
+                    Err(e) => editor.err_msg = format!("{:#}", e),
+                }
+                Task::none()
+            }
+            Message::OpenReports => {
+                debug!("Přecházím na export přehledů");
+                state.screen = Screen::Reports(ReportExporter::new());
+                Task::none()
+            }
+            Message::OpenDiagnostics => {
+                debug!("Přecházím na diagnostiku");
+                state.screen = Screen::Diagnostics(DiagnosticsPanel::new(state.config.diagnostics));
+                Task::none()
+            }
+            Message::OpenProfiles => {
+                debug!("Přecházím na výběr profilů");
+                state.screen = Screen::PickProfile(ProfilePicker::new(&state.config));
+                Task::none()
+            }
+            Message::ClearRecentFiles => {
+                debug!("Zapomínám naposledy otevřené databáze a složky importu");
+                state.config.profiles.recent.clear();
+                editor.recent_files.clear();
+                match state.config.save_settings() {
+                    Ok(_) => editor.info_msg = String::from("Naposledy otevřené zapomenuty"),
+                    Err(e) => editor.err_msg = format!("{:#}", e),
+                }
+                Task::none()
+            }
+            Message::DialogCancelled => {
+                debug!("Dialog pro výběr souboru zrušen");
+                Task::none()
+            }
+            Message::OperationFailed(err_msg) => {
+                debug!("Operace v nastavení selhala: {err_msg}");
+                editor.info_msg.clear();
+                editor.err_msg = err_msg;
+                Task::none()
+            }
+        }
+    }
+}
+
+/// Řádek formuláře s popiskem vlevo a vstupem vpravo
+fn labeled<'a>(label: &'a str, input: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+    row![text(label).width(Length::FillPortion(1)), input.into()]
+        .spacing(10)
+        .align_y(Alignment::Center)
+        .into()
+}
+
+/// Z textového vstupu vezme poslední znak (aby šlo klávesu přepsat bez mazání)
+/// a převede jej na malé písmeno.
+fn last_char_lowercase(input: &str) -> String {
+    input
+        .chars()
+        .last()
+        .map(|ch| ch.to_lowercase().collect())
+        .unwrap_or_default()
+}
+
+/// Zkontroluje, že klávesová zkratka je právě jeden znak.
+fn validate_key(key: &str) -> Result<String> {
+    if key.chars().count() == 1 {
+        Ok(key.to_string())
+    } else {
+        Err(anyhow!("Klávesová zkratka musí být právě jeden znak"))
+    }
+}
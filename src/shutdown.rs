@@ -0,0 +1,202 @@
+//! Řízené ukončení programu. Místo okamžitého `iced::exit()` se provede sekvence:
+//! 1. Pokud je otevřený neuložený playlist, zeptáme se uživatele, zda-li ho uložit
+//! 2. Zavře se prezentační okno (pokud je otevřené)
+//! 3. Zapíše se záznam průběhu prezentace do databáze
+//! 4. Provede se checkpoint databáze a uzavře se pool
+//! 5. Program se ukončí
+
+use ekkles_data::{
+    database::checkpoint_and_close,
+    playlist::{PlaylistMetadata, PlaylistMetadataStatus},
+};
+use iced::{
+    Element, Length, Task,
+    alignment::Horizontal,
+    widget::{button, column, container, row, text},
+};
+use log::{debug, warn};
+
+use crate::{Ekkles, Screen, presenter};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Bylo vyžádáno ukončení programu
+    Requested,
+    /// Uložit neuložený playlist a ukončit
+    SaveAndQuit,
+    /// Zahodit neuložené změny a ukončit
+    DiscardAndQuit,
+    /// Zrušit ukončení a vrátit se na předchozí obrazovku
+    Cancel,
+    /// Uložení playlistu selhalo
+    SaveFailed(String),
+    /// Zavřít prezentaci, zapsat log a uzavřít databázi
+    Finish,
+    /// Vše je uklizeno, můžeme skončit
+    Exit,
+}
+
+impl From<Message> for crate::Message {
+    fn from(value: Message) -> Self {
+        crate::Message::Shutdown(value)
+    }
+}
+
+/// Obrazovka s dotazem, co udělat s neuloženým playlistem před ukončením
+#[derive(Debug)]
+pub struct QuitPrompt {
+    /// Obrazovka, na kterou se vrátíme, pokud uživatel ukončení zruší
+    previous_screen: Box<Screen>,
+    /// Neuložený playlist
+    playlist: PlaylistMetadata,
+    err_msg: String,
+}
+
+impl QuitPrompt {
+    pub fn view(&self) -> Element<Message> {
+        container(
+            column![
+                text(format!(
+                    "Playlist \"{}\" obsahuje neuložené změny, uložit je před ukončením?",
+                    self.playlist.get_name()
+                )),
+                row![
+                    button("Uložit a ukončit")
+                        .style(button::success)
+                        .on_press(Message::SaveAndQuit),
+                    button("Zahodit a ukončit")
+                        .style(button::danger)
+                        .on_press(Message::DiscardAndQuit),
+                    button("Zrušit").on_press(Message::Cancel),
+                ]
+                .spacing(10),
+                text(&self.err_msg).style(text::danger),
+            ]
+            .spacing(20)
+            .align_x(Horizontal::Center),
+        )
+        .center(Length::Fill)
+        .into()
+    }
+}
+
+/// Vrátí neuložený playlist z aktuální obrazovky, pokud nějaký je
+fn unsaved_playlist(screen: &Screen) -> Option<PlaylistMetadata> {
+    let playlist = match screen {
+        Screen::EditPlaylist(editor) => editor.get_playlist(),
+        Screen::PickSong(picker) => picker.get_playlist().clone(),
+        Screen::ReviewImport(review) => review.get_playlist().clone(),
+        Screen::SongFromText(form) => form.get_playlist().clone(),
+        Screen::PickBible(picker) => picker.get_playlist().clone(),
+        Screen::ComparePassages(comparer) => comparer.get_playlist().clone(),
+        Screen::PickReadingPlan(picker) => picker.get_playlist().clone(),
+        Screen::EditSlideBreaks(editor) => editor.get_playlist().clone(),
+        Screen::ConfirmPin(prompt) => return unsaved_playlist(prompt.get_previous_screen()),
+        _ => return None,
+    };
+
+    match playlist.get_status() {
+        PlaylistMetadataStatus::Clean(_) => None,
+        PlaylistMetadataStatus::Transient | PlaylistMetadataStatus::Dirty(_) => Some(playlist),
+    }
+}
+
+pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
+    match msg {
+        Message::Requested => {
+            if let Screen::ConfirmQuit(_) = state.screen {
+                debug!("Dotaz na ukončení je již zobrazen");
+                return Task::none();
+            }
+
+            match unsaved_playlist(&state.screen) {
+                Some(playlist) => {
+                    debug!("Ukončuji, ale playlist není uložen, ptám se uživatele");
+                    let previous_screen =
+                        std::mem::replace(&mut state.screen, Screen::ErrorOccurred(String::new()));
+                    state.screen = Screen::ConfirmQuit(QuitPrompt {
+                        previous_screen: Box::new(previous_screen),
+                        playlist,
+                        err_msg: String::new(),
+                    });
+                    Task::none()
+                }
+                None => Task::done(Message::Finish.into()),
+            }
+        }
+        Message::SaveAndQuit => {
+            let Screen::ConfirmQuit(prompt) = &state.screen else {
+                panic!("Uložení před ukončením zavoláno mimo dotaz na ukončení");
+            };
+            debug!("Ukládám playlist před ukončením");
+            let mut playlist = prompt.playlist.clone();
+            let conn = state.db.acquire();
+            Task::perform(
+                async move {
+                    let mut conn = conn.await?;
+                    playlist.save(&mut conn).await
+                },
+                |res| match res {
+                    Ok(_) => Message::Finish.into(),
+                    Err(e) => Message::SaveFailed(format!("{:#}", e)).into(),
+                },
+            )
+        }
+        Message::SaveFailed(err_msg) => {
+            warn!("Nelze uložit playlist před ukončením: {err_msg}");
+            if let Screen::ConfirmQuit(prompt) = &mut state.screen {
+                prompt.err_msg = err_msg;
+            }
+            Task::none()
+        }
+        Message::DiscardAndQuit => {
+            debug!("Zahazuji neuložené změny playlistu");
+            Task::done(Message::Finish.into())
+        }
+        Message::Cancel => {
+            debug!("Ukončení zrušeno");
+            let screen = std::mem::replace(&mut state.screen, Screen::ErrorOccurred(String::new()));
+            state.screen = match screen {
+                Screen::ConfirmQuit(prompt) => *prompt.previous_screen,
+                screen => screen,
+            };
+            Task::none()
+        }
+        Message::Finish => {
+            debug!("Ukončuji prezentaci a zavírám databázi");
+            let (close_window, log) = match &mut state.screen {
+                Screen::Presenter(presenter) => {
+                    presenter.set_closing();
+                    let close_window = match presenter.get_presentation_window_id() {
+                        Some(id) => iced::window::close(id),
+                        None => Task::none(),
+                    };
+                    (close_window, Some(presenter.get_log()))
+                }
+                _ => (Task::none(), None),
+            };
+
+            let db = state.db.clone();
+            let cleanup = Task::perform(
+                async move {
+                    if let Some(log) = log
+                        && let Err(e) = presenter::flush_log(log, db.acquire()).await
+                    {
+                        warn!("Nelze uložit záznam o prezentaci: {:?}", e);
+                    }
+
+                    if let Err(e) = checkpoint_and_close(db).await {
+                        warn!("Nelze uzavřít databázi: {:?}", e);
+                    }
+                },
+                |_| Message::Exit.into(),
+            );
+
+            close_window.chain(cleanup)
+        }
+        Message::Exit => {
+            debug!("Ukončuji aplikaci");
+            iced::exit()
+        }
+    }
+}
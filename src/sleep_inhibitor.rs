@@ -0,0 +1,210 @@
+//! Zabránění spořiči obrazovky a uspání systému během prezentace, aby projektor
+//! nezčernal uprostřed dlouhého kázání.
+//!
+//! Každá platforma má vlastní API:
+//! - Linux: D-Bus rozhraní `org.freedesktop.ScreenSaver` (metody `Inhibit`/`UnInhibit`)
+//! - Windows: `SetThreadExecutionState`
+//! - macOS: `IOPMAssertionCreateWithName`/`IOPMAssertionRelease`
+//!
+//! Blokování je aktivní po dobu života [`SleepInhibitor`], při jeho dropnutí se uvolní.
+
+use anyhow::Result;
+use log::{debug, warn};
+
+use crate::PROGRAM_NAME;
+
+/// Důvod blokování, který se (na platformách, které to podporují) zobrazí uživateli
+const INHIBIT_REASON: &str = "Probíhá prezentace";
+
+/// RAII guard, který po dobu své existence brání spořiči obrazovky a uspání systému.
+#[derive(Debug)]
+pub struct SleepInhibitor {
+    inner: platform::Inhibitor,
+}
+
+impl SleepInhibitor {
+    /// Zapne blokování spořiče obrazovky. Pokud to na dané platformě není možné
+    /// (např. neběží D-Bus session), vrací Error.
+    pub fn try_new() -> Result<Self> {
+        let inner = platform::Inhibitor::inhibit(PROGRAM_NAME, INHIBIT_REASON)?;
+        debug!("Spořič obrazovky zablokován");
+        Ok(Self { inner })
+    }
+}
+
+impl Drop for SleepInhibitor {
+    fn drop(&mut self) {
+        match self.inner.release() {
+            Ok(_) => debug!("Blokování spořiče obrazovky uvolněno"),
+            Err(e) => warn!("Nelze uvolnit blokování spořiče obrazovky: {:?}", e),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use anyhow::{Context, Result};
+    use zbus::blocking::{Connection, Proxy};
+
+    const SERVICE: &str = "org.freedesktop.ScreenSaver";
+    const PATH: &str = "/org/freedesktop/ScreenSaver";
+    const INTERFACE: &str = "org.freedesktop.ScreenSaver";
+
+    /// Blokování je vázané na D-Bus spojení, proto si ho musíme držet až do uvolnění
+    #[derive(Debug)]
+    pub struct Inhibitor {
+        connection: Connection,
+        cookie: u32,
+    }
+
+    impl Inhibitor {
+        pub fn inhibit(app_name: &str, reason: &str) -> Result<Self> {
+            let connection = Connection::session().context("Nelze se připojit k D-Bus session")?;
+            let cookie: u32 = Proxy::new(&connection, SERVICE, PATH, INTERFACE)
+                .context("Nelze vytvořit proxy pro org.freedesktop.ScreenSaver")?
+                .call("Inhibit", &(app_name, reason))
+                .context("Volání org.freedesktop.ScreenSaver.Inhibit selhalo")?;
+
+            Ok(Self { connection, cookie })
+        }
+
+        pub fn release(&mut self) -> Result<()> {
+            Proxy::new(&self.connection, SERVICE, PATH, INTERFACE)
+                .context("Nelze vytvořit proxy pro org.freedesktop.ScreenSaver")?
+                .call::<_, _, ()>("UnInhibit", &(self.cookie,))
+                .context("Volání org.freedesktop.ScreenSaver.UnInhibit selhalo")
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use anyhow::{Result, anyhow};
+    use windows_sys::Win32::System::Power::{
+        ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED, SetThreadExecutionState,
+    };
+
+    /// `SetThreadExecutionState` platí pro vlákno, které ho zavolalo, volá se vždy
+    /// z update funkce, tedy z hlavního vlákna.
+    #[derive(Debug)]
+    pub struct Inhibitor;
+
+    impl Inhibitor {
+        pub fn inhibit(_app_name: &str, _reason: &str) -> Result<Self> {
+            // SAFETY: Funkce nemá žádné předpoklady, jen nastavuje stav volajícího vlákna
+            let previous = unsafe {
+                SetThreadExecutionState(ES_CONTINUOUS | ES_DISPLAY_REQUIRED | ES_SYSTEM_REQUIRED)
+            };
+
+            if previous == 0 {
+                Err(anyhow!("Volání SetThreadExecutionState selhalo"))
+            } else {
+                Ok(Self)
+            }
+        }
+
+        pub fn release(&mut self) -> Result<()> {
+            // SAFETY: Viz `inhibit`
+            let previous = unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+
+            if previous == 0 {
+                Err(anyhow!("Volání SetThreadExecutionState selhalo"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use anyhow::{Result, anyhow};
+    use core_foundation::base::TCFType;
+    use core_foundation::string::{CFString, CFStringRef};
+
+    type IOPMAssertionID = u32;
+    type IOPMAssertionLevel = u32;
+    type IOReturn = i32;
+
+    const K_IOPM_ASSERTION_LEVEL_ON: IOPMAssertionLevel = 255;
+    const K_IO_RETURN_SUCCESS: IOReturn = 0;
+    /// Brání uspání displeje (a tím i systému)
+    const ASSERTION_TYPE: &str = "PreventUserIdleDisplaySleep";
+
+    #[link(name = "IOKit", kind = "framework")]
+    unsafe extern "C" {
+        fn IOPMAssertionCreateWithName(
+            assertion_type: CFStringRef,
+            assertion_level: IOPMAssertionLevel,
+            assertion_name: CFStringRef,
+            assertion_id: *mut IOPMAssertionID,
+        ) -> IOReturn;
+
+        fn IOPMAssertionRelease(assertion_id: IOPMAssertionID) -> IOReturn;
+    }
+
+    #[derive(Debug)]
+    pub struct Inhibitor {
+        assertion_id: IOPMAssertionID,
+    }
+
+    impl Inhibitor {
+        pub fn inhibit(app_name: &str, reason: &str) -> Result<Self> {
+            let assertion_type = CFString::new(ASSERTION_TYPE);
+            let assertion_name = CFString::new(&format!("{app_name}: {reason}"));
+            let mut assertion_id: IOPMAssertionID = 0;
+
+            // SAFETY: Předáváme validní CFString (žijí do konce funkce) a ukazatel
+            // na inicializovanou proměnnou
+            let result = unsafe {
+                IOPMAssertionCreateWithName(
+                    assertion_type.as_concrete_TypeRef(),
+                    K_IOPM_ASSERTION_LEVEL_ON,
+                    assertion_name.as_concrete_TypeRef(),
+                    &mut assertion_id,
+                )
+            };
+
+            if result == K_IO_RETURN_SUCCESS {
+                Ok(Self { assertion_id })
+            } else {
+                Err(anyhow!(
+                    "Volání IOPMAssertionCreateWithName selhalo s kódem {result}"
+                ))
+            }
+        }
+
+        pub fn release(&mut self) -> Result<()> {
+            // SAFETY: `assertion_id` jsme dostali od `IOPMAssertionCreateWithName`
+            let result = unsafe { IOPMAssertionRelease(self.assertion_id) };
+
+            if result == K_IO_RETURN_SUCCESS {
+                Ok(())
+            } else {
+                Err(anyhow!(
+                    "Volání IOPMAssertionRelease selhalo s kódem {result}"
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+mod platform {
+    use anyhow::{Result, anyhow};
+
+    #[derive(Debug)]
+    pub struct Inhibitor;
+
+    impl Inhibitor {
+        pub fn inhibit(_app_name: &str, _reason: &str) -> Result<Self> {
+            Err(anyhow!(
+                "Blokování spořiče obrazovky není na této platformě podporováno"
+            ))
+        }
+
+        pub fn release(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Obrazovka pro rozdělení pasáže na slajdy. Zobrazí verše pasáže a mezi nimi značky
+//! zlomů slajdů, které lze přidávat a odebírat. Zlomy se uloží k položce playlistu
+//! a prezentace se podle nich řídí, viz [`crate::slide_render::playlist_to_slides()`].
+
+use ekkles_data::{
+    bible::indexing::Passage,
+    playlist::{PlaylistItemMetadata, PlaylistMetadata},
+};
+use iced::{
+    Element, Length, Task,
+    widget::{self, button, column, container, row, scrollable, text},
+};
+use log::{debug, trace};
+
+use crate::{Ekkles, Screen, playlist_editor::PlaylistEditor, slide_render::default_slide_breaks};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    LoadPassage,
+    PassageLoaded(Passage),
+    /// Přidá/odebere zlom slajdu před veršem s daným indexem (od 0)
+    ToggleBreak(usize),
+    /// Vrátí rozdělení na automatické (po pevném počtu veršů)
+    ResetBreaks,
+    /// Uloží zlomy k položce playlistu a vrátí se do editoru
+    ConfirmBreaks,
+    ReturnToEditor,
+}
+
+impl From<Message> for crate::Message {
+    fn from(value: Message) -> Self {
+        crate::Message::SlideBreakEditor(value)
+    }
+}
+
+#[derive(Debug)]
+pub struct SlideBreakEditor {
+    playlist: PlaylistMetadata,
+    /// Index upravované pasáže v playlistu
+    item_index: usize,
+    /// Upravovaná pasáž, `None` pokud se ještě načítá
+    passage: Option<Passage>,
+    /// Indexy veršů (od 0), kterými začíná nový slajd, `None` = automatické rozdělení
+    slide_breaks: Option<Vec<usize>>,
+    /// Počet veršů na slajdu automatického rozdělení (z nastavení)
+    verses_per_slide: usize,
+}
+
+impl SlideBreakEditor {
+    /// Vytvoří editor zlomů pasáže na indexu `item_index` playlistu `playlist`, automatické
+    /// rozdělení je po `verses_per_slide` verších. Pokud na daném indexu není pasáž, zpanikaří.
+    pub fn new(playlist: PlaylistMetadata, item_index: usize, verses_per_slide: usize) -> Self {
+        let slide_breaks = match playlist.get_item(item_index) {
+            Some(PlaylistItemMetadata::BiblePassage { slide_breaks, .. }) => slide_breaks.clone(),
+            _ => panic!("Zlomy slajdů lze upravovat pouze u pasáží"),
+        };
+
+        Self {
+            playlist,
+            item_index,
+            passage: None,
+            slide_breaks,
+            verses_per_slide,
+        }
+    }
+
+    /// Vrátí upravovaný playlist
+    pub fn get_playlist(&self) -> &PlaylistMetadata {
+        &self.playlist
+    }
+
+    /// Vrátí aktuální zlomy, u automatického rozdělení je dopočítá podle počtu veršů
+    fn effective_breaks(&self, num_verses: usize) -> Vec<usize> {
+        self.slide_breaks
+            .clone()
+            .unwrap_or_else(|| default_slide_breaks(num_verses, self.verses_per_slide))
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let verses: Element<Message> = match &self.passage {
+            None => text("Načítám pasáž...").into(),
+            Some(passage) => {
+                let verses = passage.get_verses();
+                let breaks = self.effective_breaks(verses.len());
+                let mut slide_number = 1;
+                let mut content = column![text(format!("Slajd {slide_number}")).size(20)];
+
+                for (index, (verse_number, verse)) in verses.iter().enumerate() {
+                    if index > 0 {
+                        content = content.push(if breaks.contains(&index) {
+                            slide_number += 1;
+                            column![
+                                button("Odebrat zlom slajdu")
+                                    .style(widget::button::danger)
+                                    .on_press(Message::ToggleBreak(index))
+                                    .width(Length::Fill),
+                                text(format!("Slajd {slide_number}")).size(20),
+                            ]
+                            .spacing(5)
+                        } else {
+                            column![
+                                button("+ zlom slajdu")
+                                    .style(widget::button::text)
+                                    .on_press(Message::ToggleBreak(index)),
+                            ]
+                        });
+                    }
+                    content = content.push(text(format!("{verse_number}: {verse}")));
+                }
+
+                scrollable(container(content.spacing(5)).padding(10))
+                    .height(Length::Fill)
+                    .into()
+            }
+        };
+
+        let mode = if self.slide_breaks.is_some() {
+            "Vlastní rozdělení na slajdy"
+        } else {
+            "Automatické rozdělení na slajdy"
+        };
+
+        column![
+            row![
+                button("Zpět").on_press(Message::ReturnToEditor),
+                text(mode).width(Length::Fill),
+                button("Automaticky")
+                    .on_press_maybe(self.slide_breaks.is_some().then_some(Message::ResetBreaks)),
+                button("Potvrdit")
+                    .style(widget::button::success)
+                    .on_press_maybe(self.passage.is_some().then_some(Message::ConfirmBreaks)),
+            ]
+            .spacing(20),
+            verses,
+        ]
+        .spacing(20)
+        .padding(20)
+        .into()
+    }
+
+    /// Update funkce pro editor zlomů slajdů. Pokud je tato funkce zavolána nad jinou
+    /// obrazovkou než [`Screen::EditSlideBreaks`], zpanikaří.
+    pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
+        let editor = match &mut state.screen {
+            Screen::EditSlideBreaks(editor) => editor,
+            screen => panic!(
+                "Update pro editor zlomů slajdů zavolán nad jinou obrazovkou {:?}",
+                screen
+            ),
+        };
+
+        match msg {
+            Message::LoadPassage => {
+                let Some(PlaylistItemMetadata::BiblePassage {
+                    translation_id,
+                    from,
+                    to,
+                    ..
+                }) = editor.playlist.get_item(editor.item_index).cloned()
+                else {
+                    unreachable!("Editor zlomů je vždy vytvořen nad pasáží");
+                };
+                debug!("Načítám pasáž {from} - {to} pro úpravu zlomů slajdů");
+
+                let conn = state.db.acquire();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await?;
+                        Passage::load(from, to, translation_id, &mut conn).await
+                    },
+                    |res| match res {
+                        Ok(passage) => Message::PassageLoaded(passage).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::PassageLoaded(passage) => {
+                debug!("Pasáž pro úpravu zlomů načtena");
+                editor.passage = Some(passage);
+                Task::none()
+            }
+            Message::ToggleBreak(index) => {
+                let num_verses = editor
+                    .passage
+                    .as_ref()
+                    .map(|passage| passage.get_verses().len())
+                    .unwrap_or_default();
+                let mut breaks = editor.effective_breaks(num_verses);
+
+                match breaks.binary_search(&index) {
+                    Ok(position) => {
+                        trace!("Odebírám zlom slajdu před veršem {index}");
+                        breaks.remove(position);
+                    }
+                    Err(position) => {
+                        trace!("Přidávám zlom slajdu před verš {index}");
+                        breaks.insert(position, index);
+                    }
+                }
+
+                editor.slide_breaks = Some(breaks);
+                Task::none()
+            }
+            Message::ResetBreaks => {
+                debug!("Vracím automatické rozdělení na slajdy");
+                editor.slide_breaks = None;
+                Task::none()
+            }
+            Message::ConfirmBreaks => {
+                debug!(
+                    "Ukládám zlomy slajdů {:?} k položce {} a vracím se do editoru",
+                    editor.slide_breaks, editor.item_index
+                );
+                let mut playlist = editor.playlist.clone();
+                playlist
+                    .set_slide_breaks(editor.item_index, editor.slide_breaks.clone())
+                    .expect("Editor zlomů je vždy vytvořen nad pasáží");
+                state.screen = Screen::EditPlaylist(PlaylistEditor::new(playlist));
+                Task::done(crate::playlist_editor::Message::LoadSongNameCache.into())
+            }
+            Message::ReturnToEditor => {
+                debug!("Zahazuji změny zlomů slajdů a vracím se do editoru");
+                state.screen = Screen::EditPlaylist(PlaylistEditor::new(editor.playlist.clone()));
+                Task::done(crate::playlist_editor::Message::LoadSongNameCache.into())
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Uložení promítaného slajdu jako obrázku (pro příspěvky na sociální sítě, archiv, ...).
+//!
+//! Vykreslení mimo okno iced neumí (viz [`crate::thumbnail_cache`]), slajd se proto
+//! vyfotí z prezentačního okna a přepočítá na zvolené rozlišení. Pokud má výstup jiný
+//! poměr stran než zvolené rozlišení, přesahující část se ořízne.
+
+use std::{fmt, fs, io::Cursor, path::PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use iced::window::Screenshot;
+use image::{DynamicImage, ImageFormat, RgbaImage, imageops::FilterType};
+use log::debug;
+
+/// Rozlišení exportovaného obrázku
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Resolution {
+    pub const fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+}
+
+impl fmt::Display for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} × {}", self.width, self.height)
+    }
+}
+
+/// Nabízená rozlišení exportu
+pub const RESOLUTIONS: [Resolution; 4] = [
+    Resolution::new(1280, 720),
+    Resolution::new(1920, 1080),
+    Resolution::new(2560, 1440),
+    Resolution::new(3840, 2160),
+];
+
+/// Výchozí rozlišení exportu
+pub const DEFAULT_RESOLUTION: Resolution = Resolution::new(1920, 1080);
+
+/// Výchozí název souboru s exportovaným slajdem
+pub const DEFAULT_FILE_NAME: &str = "slajd.png";
+
+/// Přepočítá snímek prezentačního okna `screenshot` na rozlišení `resolution`
+fn scale(screenshot: Screenshot, resolution: Resolution) -> Result<RgbaImage> {
+    let image = RgbaImage::from_raw(
+        screenshot.size.width,
+        screenshot.size.height,
+        screenshot.as_ref().to_vec(),
+    )
+    .ok_or(anyhow!("Snímek okna nemá očekávanou velikost"))?;
+
+    Ok(DynamicImage::ImageRgba8(image)
+        .resize_to_fill(resolution.width, resolution.height, FilterType::Lanczos3)
+        .into_rgba8())
+}
+
+/// Přepočítá snímek prezentačního okna `screenshot` na rozlišení `resolution`
+/// a uloží jej jako PNG do `path`. Pokud obrázek nelze zakódovat nebo uložit, vrací Error.
+pub async fn save(path: PathBuf, screenshot: Screenshot, resolution: Resolution) -> Result<()> {
+    tokio::task::spawn_blocking(move || {
+        let image = scale(screenshot, resolution)?;
+
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .context("Nelze zakódovat obrázek slajdu")?;
+        fs::write(&path, &png)
+            .with_context(|| format!("Nelze uložit obrázek slajdu do {}", path.display()))?;
+
+        debug!("Slajd uložen jako {} ({resolution})", path.display());
+        Ok(())
+    })
+    .await
+    .context("Úloha ukládání obrázku slajdu selhala")?
+}
@@ -0,0 +1,1053 @@
+//! Modul pro tvorbu a vykreslování slajdů.
+//!
+//! Rozvržení slajdu (aplikace motivu, zalamování textu, patička s názvem písně/rozsahem
+//! pasáže) je pouze zde, aby živý výstup, náhledy i případné exporty vypadaly stejně.
+//! Vykreslení je čistá funkce [`render()`], která ze slajdu, jeho stylu a velikosti
+//! výstupu vytvoří [`Element`], nezávisle na tom, kdo jej zobrazuje.
+
+use chrono::TimeDelta;
+use ekkles_data::bible::indexing::{Passage, VerseIndex};
+use ekkles_data::playlist::{Playlist, PlaylistItem};
+use ekkles_data::slide_override::{SlideAlignment, SlideOverride};
+use iced::widget::{Space, column, container, image, stack, text};
+use iced::{Alignment, ContentFit, Element, Length, Size};
+
+use std::collections::BTreeMap;
+
+use crate::config::{PresentationTheme, QuickSlide};
+
+pub const TEXT_SIZE_MULTIPLIER_MIN: f32 = 0.5;
+pub const TEXT_SIZE_MULTIPLIER_MAX: f32 = 3.0;
+pub const TEXT_SIZE_MULTIPLIER_DEFAULT: f32 = 1.0;
+/// Jelikož [`iced::widget::slider()`] potřebuje range a range přes f32 hodnoty se nechová dobře,
+/// používám pro range u8 (0..=255) a pomocí [`normalize_text_multiplier`] range poté
+/// normalizuji. Tato default hodnota by se měla promítnout do [`TEXT_SIZE_MULTIPLIER_DEFAULT`].
+pub const TEXT_SIZE_MULTIPLIER_DEFAULT_U8: u8 = ((TEXT_SIZE_MULTIPLIER_DEFAULT
+    - TEXT_SIZE_MULTIPLIER_MIN)
+    / (TEXT_SIZE_MULTIPLIER_MAX - TEXT_SIZE_MULTIPLIER_MIN)
+    * u8::MAX as f32) as u8;
+
+/// Výchozí počet veršů na jeden slajd při automatickém rozdělení pasáže, lze změnit
+/// v nastavení ([`crate::config::Settings::verses_per_slide`]) i během prezentace
+pub const VERSES_PER_SLIDE: u8 = 2;
+/// Nejvyšší nastavitelný počet veršů na jeden slajd
+pub const MAX_VERSES_PER_SLIDE: u8 = 10;
+/// Nejvyšší nastavitelný počet řádků části písně na jednom slajdu, viz
+/// [`crate::config::Settings::max_song_lines`]
+pub const MAX_SONG_LINES: u8 = 20;
+/// Značka na konci slajdu, jehož část písně pokračuje na dalším slajdu
+const CONTINUATION_MARK: &str = "(pokrač.)";
+
+/// Velikost textu pro hlavní obsah snímku
+const MAIN_TEXT_SIZE: f32 = 70.0;
+/// Velikost textu pro doplňující obsah snímku
+const ADDITIONAL_TEXT_SIZE: f32 = 30.0;
+
+/// Rozlišení výstupu, pro které jsou navrženy velikosti textu. Při vykreslení do jiné
+/// velikosti (náhledy) se text úměrně zmenší/zvětší.
+pub const REFERENCE_SIZE: Size = Size::new(1920.0, 1080.0);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Slide {
+    Passage(PassageSlide),
+    Song(SongSlide),
+    Text(TextSlide),
+    Image(ImageSlide),
+}
+
+impl Slide {
+    /// Index položky playlistu, ze které slajd vznikl
+    pub fn item_index(&self) -> usize {
+        match self {
+            Slide::Passage(passage_slide) => passage_slide.item_index,
+            Slide::Song(song_slide) => song_slide.item_index,
+            Slide::Text(text_slide) => text_slide.item_index,
+            Slide::Image(image_slide) => image_slide.item_index,
+        }
+    }
+
+    /// Nastaví index položky playlistu (po změně pořadí položek během prezentace)
+    pub fn set_item_index(&mut self, item_index: usize) {
+        match self {
+            Slide::Passage(passage_slide) => passage_slide.item_index = item_index,
+            Slide::Song(song_slide) => song_slide.item_index = item_index,
+            Slide::Text(text_slide) => text_slide.item_index = item_index,
+            Slide::Image(image_slide) => image_slide.item_index = item_index,
+        }
+    }
+
+    /// Motiv, kterým se slajd promítá
+    pub fn theme(&self) -> &PresentationTheme {
+        match self {
+            Slide::Passage(passage_slide) => &passage_slide.theme,
+            Slide::Song(song_slide) => &song_slide.theme,
+            Slide::Text(text_slide) => &text_slide.theme,
+            Slide::Image(image_slide) => &image_slide.theme,
+        }
+    }
+
+    /// Nadpis zobrazený malým písmem nad hlavním obsahem slajdu (nadpis žalmu na prvním
+    /// slajdu pasáže), ostatní slajdy nadpis nemají
+    pub fn heading(&self) -> Option<&str> {
+        match self {
+            Slide::Passage(slide) => slide.heading.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Vrátí texty, které se na slajdu zobrazí v jazyce `language`, jako dvojici
+    /// (hlavní obsah, patička). Pasáže a vlastní texty (bez patičky) se promítají vždy
+    /// stejně, u písní bez překladu se promítne originál. Obrázky žádný text nemají.
+    pub fn texts(&self, language: LanguageMode) -> (String, String) {
+        match self {
+            Slide::Passage(slide) => {
+                let verses_text: String = slide
+                    .verses
+                    .iter()
+                    .map(|(number, content)| format!("{}: {}", number, content))
+                    .collect();
+                let indexes_text =
+                    format!("{} - {}", slide.passage_indexes.0, slide.passage_indexes.1);
+
+                (verses_text, indexes_text)
+            }
+            Slide::Song(slide) => {
+                let (content, footer) = match (language, &slide.translation) {
+                    (LanguageMode::Original, _) | (_, None) => {
+                        (slide.content.clone(), slide.title.clone())
+                    }
+                    (LanguageMode::Translation, Some(translation)) => (
+                        translation
+                            .content
+                            .clone()
+                            .unwrap_or_else(|| slide.content.clone()),
+                        translation.title.clone(),
+                    ),
+                    (LanguageMode::Bilingual, Some(translation)) => (
+                        match &translation.content {
+                            Some(content) => format!("{}\n\n{}", slide.content, content),
+                            None => slide.content.clone(),
+                        },
+                        format!("{} / {}", slide.title, translation.title),
+                    ),
+                };
+
+                match slide.part_piece {
+                    Some((piece, pieces)) if piece < pieces => {
+                        (format!("{content}\n{CONTINUATION_MARK}"), footer)
+                    }
+                    _ => (content, footer),
+                }
+            }
+            Slide::Text(slide) => (slide.content.clone(), String::new()),
+            Slide::Image(_) => (String::new(), String::new()),
+        }
+    }
+}
+
+/// Jazyk, ve kterém se promítají písně s propojeným překladem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LanguageMode {
+    /// Pouze originál
+    #[default]
+    Original,
+    /// Pouze překlad
+    Translation,
+    /// Originál i překlad pod sebou
+    Bilingual,
+}
+
+impl LanguageMode {
+    /// Následující režim v pořadí originál → překlad → obojí → originál
+    pub fn next(self) -> Self {
+        match self {
+            LanguageMode::Original => LanguageMode::Translation,
+            LanguageMode::Translation => LanguageMode::Bilingual,
+            LanguageMode::Bilingual => LanguageMode::Original,
+        }
+    }
+}
+
+/// Jeden slajd při promítání pasáže
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassageSlide {
+    /// Název překladu, ze které je pasáž přebraná
+    pub translation_name: String,
+    /// Indexy celkové pasáže od-do
+    pub passage_indexes: (VerseIndex, VerseIndex),
+    /// Jednotlivé verše daného slajdu
+    pub verses: Vec<(u8, String)>,
+    /// Nadpis před prvním veršem pasáže, má jej pouze první slajd pasáže
+    pub heading: Option<String>,
+    /// Index položky playlistu, ze které slajd vznikl
+    pub item_index: usize,
+    /// Motiv slajdu
+    pub theme: PresentationTheme,
+}
+
+impl PassageSlide {
+    fn new(
+        translation_name: String,
+        from: VerseIndex,
+        to: VerseIndex,
+        verses: Vec<(u8, String)>,
+        item_index: usize,
+        theme: PresentationTheme,
+    ) -> Self {
+        Self {
+            translation_name,
+            passage_indexes: (from, to),
+            verses,
+            heading: None,
+            item_index,
+            theme,
+        }
+    }
+}
+
+/// Jeden slajd při promítání písně
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SongSlide {
+    /// Název písně
+    pub title: String,
+    /// Tónina, ve které se píseň na této bohoslužbě hraje (zobrazuje se pouze obsluze)
+    pub key: Option<String>,
+    /// Název části písně
+    pub part_name: String,
+    /// Obsah dané části písně (u rozdělené části pouze její kus)
+    pub content: String,
+    /// Pořadí kusu (od 1) a počet kusů, pokud se část písně nevešla na jeden slajd
+    /// a byla rozdělena, viz [`split_song_part()`]
+    pub part_piece: Option<(usize, usize)>,
+    /// Stejná část z propojeného překladu písně, pokud píseň překlad má
+    pub translation: Option<SongSlideTranslation>,
+    /// Pořadí původního slajdu v rámci položky, pokud je slajd kopií zopakované části
+    /// (viz [`crate::presenter::Message::RepeatSongPart`]), úpravy slajdu sdílí s ním
+    pub repeat_of: Option<usize>,
+    /// Index položky playlistu, ze které slajd vznikl
+    pub item_index: usize,
+    /// Motiv slajdu
+    pub theme: PresentationTheme,
+}
+
+/// Část překladu písně odpovídající slajdu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SongSlideTranslation {
+    /// Název překladu písně
+    pub title: String,
+    /// Obsah části se stejným tagem, `None` pokud ji překlad nemá
+    pub content: Option<String>,
+}
+
+impl SongSlide {
+    fn new(
+        title: String,
+        key: Option<String>,
+        part_name: String,
+        content: String,
+        translation: Option<SongSlideTranslation>,
+        item_index: usize,
+        theme: PresentationTheme,
+    ) -> Self {
+        Self {
+            title,
+            key,
+            part_name,
+            content,
+            part_piece: None,
+            translation,
+            repeat_of: None,
+            item_index,
+            theme,
+        }
+    }
+}
+
+/// Slajd s vlastním textem (oznámení, uvítání, ...)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextSlide {
+    /// Název textu, zobrazuje se pouze obsluze
+    pub title: String,
+    /// Promítaný text
+    pub content: String,
+    /// Index položky playlistu, ze které slajd vznikl
+    pub item_index: usize,
+    /// Motiv slajdu
+    pub theme: PresentationTheme,
+}
+
+/// Slajd s obrázkem (titulní grafika kázání, plakát oznámení)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageSlide {
+    /// Název obrázku, zobrazuje se pouze obsluze
+    pub name: String,
+    /// ID média, ze kterého obrázek pochází
+    pub media_id: i64,
+    /// Zmenšená verze obrázku
+    pub handle: image::Handle,
+    /// Index položky playlistu, ze které slajd vznikl
+    pub item_index: usize,
+    /// Motiv slajdu, obrázek se vykreslí na jeho pozadí
+    pub theme: PresentationTheme,
+}
+
+/// Výsledný styl slajdu, ve kterém jsou již započítané všechny úpravy
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlideStyle {
+    pub theme: PresentationTheme,
+    /// Multiplikátor velikosti textu (již normalizovaný)
+    pub text_size_multiplier: f32,
+    /// Zarovnání hlavního obsahu slajdu
+    pub alignment: Alignment,
+    /// Jazyk, ve kterém se promítají písně s překladem
+    pub language: LanguageMode,
+}
+
+impl SlideStyle {
+    /// Sestaví styl slajdu `slide` s globálním multiplikátorem velikosti textu
+    /// `text_size_multiplier`, úprava `slide_override` přebije jak jej, tak motiv slajdu
+    /// (barva pozadí z úpravy nahradí i obrázek na pozadí motivu).
+    pub fn new(slide: &Slide, text_size_multiplier: f32, slide_override: &SlideOverride) -> Self {
+        let mut theme = slide.theme().clone();
+        if let Some(background_color) = slide_override.background_color {
+            theme.background_color = background_color;
+            theme.background_image = None;
+        }
+
+        Self {
+            theme,
+            text_size_multiplier: slide_override
+                .text_scale
+                .map(normalize_text_multiplier)
+                .unwrap_or(text_size_multiplier),
+            alignment: match slide_override.alignment {
+                Some(SlideAlignment::Left) => Alignment::Start,
+                Some(SlideAlignment::Right) => Alignment::End,
+                Some(SlideAlignment::Center) | None => Alignment::Center,
+            },
+            language: LanguageMode::default(),
+        }
+    }
+}
+
+/// Vykreslí `slide` se stylem `style` pro výstup o velikosti `size` (podle ní se škáluje
+/// velikost textu, viz [`REFERENCE_SIZE`]).
+pub fn render<'a, M: 'a>(slide: &'a Slide, style: &SlideStyle, size: Size) -> Element<'a, M> {
+    if let Slide::Image(slide) = slide {
+        return render_image(slide, &style.theme);
+    }
+
+    let scale = style.text_size_multiplier * size.height / REFERENCE_SIZE.height;
+    let main_text_size = MAIN_TEXT_SIZE * scale;
+    let footer_text_size = ADDITIONAL_TEXT_SIZE * scale;
+
+    let (content, footer_text) = slide.texts(style.language);
+
+    // Nadpis (např. žalmu) je malým písmem nad hlavním obsahem
+    let mut main = column![].spacing(footer_text_size / 2.0);
+    if let Some(heading) = slide.heading() {
+        main = main.push(
+            text(heading.to_string())
+                .align_x(style.alignment)
+                .width(Length::Fill)
+                .size(footer_text_size),
+        );
+    }
+    let content = container(
+        main.push(
+            text(content)
+                .align_x(style.alignment)
+                .width(Length::Fill)
+                .size(main_text_size),
+        ),
+    )
+    .center(Length::Fill);
+
+    with_background(
+        column![content, footer(footer_text, footer_text_size)].into(),
+        &style.theme,
+    )
+}
+
+/// Vykreslí obrázek `slide` co největší při zachování poměru stran, zbytek výstupu
+/// vyplní pozadí motivu `theme`
+fn render_image<'a, M: 'a>(slide: &'a ImageSlide, theme: &PresentationTheme) -> Element<'a, M> {
+    with_background(
+        container(
+            image(slide.handle.clone())
+                .content_fit(ContentFit::Contain)
+                .width(Length::Fill)
+                .height(Length::Fill),
+        )
+        .center(Length::Fill)
+        .into(),
+        theme,
+    )
+}
+
+/// Vykreslí prázdný slajd s pozadím motivu `theme`
+pub fn render_blank<'a, M: 'a>(theme: &PresentationTheme) -> Element<'a, M> {
+    with_background(Space::new(Length::Fill, Length::Fill).into(), theme)
+}
+
+/// Vykreslí `content` na pozadí motivu `theme`. Obrázek na pozadí vyplní celý výstup
+/// (přesahující část se ořízne) a přes něj se vykreslí ztmavující vrstva.
+fn with_background<'a, M: 'a>(
+    content: Element<'a, M>,
+    theme: &PresentationTheme,
+) -> Element<'a, M> {
+    let theme = theme.clone();
+    let Some(background) = theme.background_image.clone() else {
+        return container(content)
+            .style(move |_| slide_background(&theme))
+            .into();
+    };
+
+    let dimming = background.dimming_color();
+    container(stack![
+        image(background.handle)
+            .content_fit(ContentFit::Cover)
+            .width(Length::Fill)
+            .height(Length::Fill),
+        container(Space::new(Length::Fill, Length::Fill)).style(move |_| container::Style {
+            background: Some(iced::Background::Color(dimming)),
+            ..Default::default()
+        }),
+        content,
+    ])
+    .style(move |_| slide_background(&theme))
+    .into()
+}
+
+/// Patička slajdu (název písně, rozsah pasáže), vždy zarovnaná na střed
+fn footer<'a, M: 'a>(content: String, size: f32) -> Element<'a, M> {
+    container(text(content).align_x(Alignment::Center).size(size))
+        .center_x(Length::Fill)
+        .align_bottom(Length::Shrink)
+        .into()
+}
+
+/// Stylovací funkce pro pozadí slajdu podle motivu
+fn slide_background(theme: &PresentationTheme) -> container::Style {
+    container::Style {
+        text_color: Some(theme.text_color()),
+        background: Some(iced::Background::Color(theme.background_color())),
+        ..Default::default()
+    }
+}
+
+/// Přetvoří `playlist` na vektor slajdů složený z položek vytvořených z jednotlivých
+/// položek playlistu ve stejném pořadí.
+///
+/// Každý slajd dostane motiv své položky, motiv playlistu má přednost před motivem
+/// písně, a pokud nemá motiv ani jeden, použije se `default_theme` (z nastavení).
+///
+/// Části písní delší než `max_song_lines` řádků se rozdělí na více slajdů (viz
+/// [`split_song_part()`]), pokud je `None`, promítne se každá část na jediném slajdu.
+/// Pokud je `passage_headings`, dostane první slajd pasáže nadpis (např. žalmu), má-li jej.
+///
+/// Vypnuté položky (viz [`Playlist::enabled`]) slajdy nemají, indexy položek slajdů
+/// (viz [`Slide::item_index()`]) ale zůstávají indexy v playlistu.
+pub fn playlist_to_slides(
+    playlist: Playlist,
+    verses_per_slide: usize,
+    max_song_lines: Option<usize>,
+    passage_headings: bool,
+    default_theme: &PresentationTheme,
+) -> Vec<Slide> {
+    // Každý obrázek na pozadí má jediný handle, aby jej iced dekódoval pouze jednou
+    let backgrounds: BTreeMap<i64, image::Handle> = playlist
+        .backgrounds
+        .iter()
+        .map(|(media_id, data)| (*media_id, image::Handle::from_bytes(data.clone())))
+        .collect();
+    let themes: Vec<PresentationTheme> = (0..playlist.items.len())
+        .map(|index| {
+            playlist
+                .theme_for_item(index)
+                .map(|theme| PresentationTheme::from_slide_theme(theme, &backgrounds))
+                .unwrap_or_else(|| default_theme.clone())
+        })
+        .collect();
+
+    let slide_breaks = playlist.slide_breaks.clone();
+    let song_keys = playlist.song_keys.clone();
+    let song_translations = playlist.song_translations.clone();
+    let enabled = playlist.enabled.clone();
+    let items = playlist.into_items();
+    let slides: Vec<Slide> = items
+        .into_iter()
+        .zip(themes)
+        .zip(slide_breaks)
+        .enumerate()
+        .filter(|(item_index, _)| enabled[*item_index])
+        .flat_map(|(item_index, ((item, theme), slide_breaks))| match item {
+            PlaylistItem::BiblePassage(passage) => {
+                let slide_breaks = slide_breaks.unwrap_or_else(|| {
+                    default_slide_breaks(passage.get_verses().len(), verses_per_slide)
+                });
+                passage_to_slides(
+                    &passage,
+                    &slide_breaks,
+                    passage_headings,
+                    item_index,
+                    &theme,
+                )
+            }
+            PlaylistItem::Song(song) => {
+                let title = song.title;
+                let key = &song_keys[item_index];
+                let translation = &song_translations[item_index];
+                song.order
+                    .into_iter()
+                    .flat_map(|part_name| {
+                        let part_content = song
+                            .parts
+                            .get(&part_name)
+                            .expect("Píseň musí obsahovat všechny svoje části");
+                        let pieces = split_song_part(part_content, max_song_lines);
+                        // Překlad se rozdělí na stejný počet kusů, aby k sobě kusy seděly
+                        let translation_pieces = translation.as_ref().map(|translation| {
+                            translation
+                                .parts
+                                .get(&part_name)
+                                .map(|content| split_into_pieces(content, pieces.len()))
+                        });
+                        let num_pieces = pieces.len();
+
+                        pieces
+                            .into_iter()
+                            .enumerate()
+                            .map(|(piece_index, piece)| {
+                                let part_translation =
+                                    translation
+                                        .as_ref()
+                                        .map(|translation| SongSlideTranslation {
+                                            title: translation.title.clone(),
+                                            content: translation_pieces
+                                                .as_ref()
+                                                .and_then(|pieces| pieces.as_ref())
+                                                .map(|pieces| pieces[piece_index].clone()),
+                                        });
+                                Slide::Song(SongSlide {
+                                    part_piece: (num_pieces > 1)
+                                        .then_some((piece_index + 1, num_pieces)),
+                                    ..SongSlide::new(
+                                        title.clone(),
+                                        key.clone(),
+                                        part_name.clone(),
+                                        piece,
+                                        part_translation,
+                                        item_index,
+                                        theme.clone(),
+                                    )
+                                })
+                            })
+                            .collect::<Vec<Slide>>()
+                    })
+                    .collect()
+            }
+            PlaylistItem::CustomText { title, body } => vec![Slide::Text(TextSlide {
+                title,
+                content: body,
+                item_index,
+                theme,
+            })],
+            PlaylistItem::Image {
+                media_id,
+                name,
+                data,
+            } => vec![Slide::Image(ImageSlide {
+                name,
+                media_id,
+                handle: image::Handle::from_bytes(data),
+                item_index,
+                theme,
+            })],
+        })
+        .collect();
+
+    slides
+}
+
+/// Rozdělí pasáž `passage` na slajdy podle zlomů `slide_breaks` (indexy veršů, kterými
+/// začíná nový slajd, viz [`split_at_breaks()`]). Pokud je `heading`, dostane první slajd
+/// nadpis pasáže (např. žalmu), má-li jej.
+pub fn passage_to_slides(
+    passage: &Passage,
+    slide_breaks: &[usize],
+    heading: bool,
+    item_index: usize,
+    theme: &PresentationTheme,
+) -> Vec<Slide> {
+    let name = passage.get_translation_name();
+    let (from, to) = passage.get_range();
+    let heading = passage
+        .get_heading()
+        .filter(|_| heading)
+        .map(str::to_string);
+
+    split_at_breaks(passage.get_verses(), slide_breaks)
+        .into_iter()
+        .enumerate()
+        .map(|(slide_index, verses)| {
+            Slide::Passage(PassageSlide {
+                heading: (slide_index == 0).then(|| heading.clone()).flatten(),
+                ..PassageSlide::new(
+                    name.to_string(),
+                    from,
+                    to,
+                    verses.to_vec(),
+                    item_index,
+                    theme.clone(),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Vytvoří textový slajd z rychlého slajdu `quick_slide` s motivem `theme` (z nastavení).
+/// Rychlý slajd nepatří do playlistu, index položky je proto vždy 0.
+pub fn quick_slide_to_slide(quick_slide: &QuickSlide, theme: &PresentationTheme) -> Slide {
+    Slide::Text(TextSlide {
+        title: quick_slide.name.clone(),
+        content: quick_slide.content.clone(),
+        item_index: 0,
+        theme: theme.clone(),
+    })
+}
+
+/// Vytvoří textový slajd odpočtu před začátkem bohoslužby s motivem `theme`, do začátku
+/// zbývá `remaining`. Zbývající čas se zaokrouhluje nahoru na celé sekundy, aby na slajdu
+/// bylo `00:00` až v čase začátku. Odpočet nepatří do playlistu, index položky je vždy 0.
+pub fn countdown_to_slide(remaining: TimeDelta, theme: &PresentationTheme) -> Slide {
+    Slide::Text(TextSlide {
+        title: String::from("Odpočet"),
+        content: format!("Bohoslužba začíná za {}", countdown_text(remaining)),
+        item_index: 0,
+        theme: theme.clone(),
+    })
+}
+
+/// Naformátuje čas zbývající do začátku bohoslužby do tvaru `mm:ss` (případně `h:mm:ss`)
+fn countdown_text(remaining: TimeDelta) -> String {
+    let seconds = (remaining.num_milliseconds().max(0) + 999) / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes:02}:{seconds:02}")
+    }
+}
+
+/// Vrátí zlomy slajdů automatického rozdělení pasáže o `num_verses` verších po
+/// `verses_per_slide` verších, tedy indexy veršů (od 0), kterými začíná nový slajd.
+pub fn default_slide_breaks(num_verses: usize, verses_per_slide: usize) -> Vec<usize> {
+    (verses_per_slide..num_verses)
+        .step_by(verses_per_slide.max(1))
+        .collect()
+}
+
+/// Rozdělí obsah části písně `content` na kusy po nejvýše `max_lines` řádcích, každý kus
+/// se promítne na vlastním slajdu. Řádky se rozdělí co nejrovnoměrněji (12 řádků po
+/// nejvýše 8 dá dva kusy po 6), aby nevznikl slajd s jediným osiřelým řádkem.
+/// Pokud je `max_lines` `None` (nebo 0), vrací celou část jako jediný kus.
+pub fn split_song_part(content: &str, max_lines: Option<usize>) -> Vec<String> {
+    let num_lines = content.lines().count();
+    let pieces = match max_lines {
+        Some(max_lines) if max_lines > 0 => num_lines.div_ceil(max_lines).max(1),
+        _ => 1,
+    };
+
+    split_into_pieces(content, pieces)
+}
+
+/// Rozdělí řádky `content` co nejrovnoměrněji na přesně `pieces` kusů (pokud má méně
+/// řádků než kusů, zbylé kusy jsou prázdné).
+fn split_into_pieces(content: &str, pieces: usize) -> Vec<String> {
+    if pieces <= 1 {
+        return vec![content.to_string()];
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    let lines_per_piece = lines.len().div_ceil(pieces).max(1);
+    let mut chunks: Vec<String> = lines
+        .chunks(lines_per_piece)
+        .map(|chunk| chunk.join("\n"))
+        .collect();
+    chunks.resize(pieces, String::new());
+
+    chunks
+}
+
+/// Znovu rozdělí pasáže ve slajdech `slides`, pasáže s ručními zlomy z `slide_breaks`
+/// (indexovanými podle položky playlistu) podle nich, ostatní po `verses_per_slide` verších.
+/// Ostatní slajdy ponechá beze změny. Umožňuje změnit počet veršů na slajdu (nebo promítat
+/// po jednom verši, s prázdnými `slide_breaks`) během prezentace bez opětovného načtení
+/// playlistu.
+pub fn rechunk_passages(
+    slides: Vec<Slide>,
+    slide_breaks: &[Option<Vec<usize>>],
+    verses_per_slide: usize,
+) -> Vec<Slide> {
+    let mut rechunked = Vec::with_capacity(slides.len());
+    let mut slides = slides.into_iter().peekable();
+
+    while let Some(slide) = slides.next() {
+        let first = match slide {
+            Slide::Passage(first) => first,
+            slide => {
+                rechunked.push(slide);
+                continue;
+            }
+        };
+
+        // Všechny slajdy jedné pasáže jdou po sobě, spojíme jejich verše
+        let mut verses = first.verses.clone();
+        while let Some(Slide::Passage(next)) =
+            slides.next_if(|slide| slide.item_index() == first.item_index)
+        {
+            verses.extend(next.verses);
+        }
+
+        let breaks = slide_breaks
+            .get(first.item_index)
+            .cloned()
+            .flatten()
+            .unwrap_or_else(|| default_slide_breaks(verses.len(), verses_per_slide));
+        rechunked.extend(
+            split_at_breaks(&verses, &breaks)
+                .into_iter()
+                .enumerate()
+                .map(|(slide_index, verses)| {
+                    Slide::Passage(PassageSlide {
+                        verses: verses.to_vec(),
+                        // Nadpis zůstává pouze na prvním slajdu pasáže
+                        heading: first.heading.clone().filter(|_| slide_index == 0),
+                        ..first.clone()
+                    })
+                }),
+        );
+    }
+
+    rechunked
+}
+
+/// Rozdělí verše `verses` na slajdy, každý zlom z `slide_breaks` začíná nový slajd.
+/// Zlomy mimo rozsah veršů nebo na jeho začátku se ignorují, takže nevznikne prázdný slajd.
+pub fn split_at_breaks<'a, T>(verses: &'a [T], slide_breaks: &[usize]) -> Vec<&'a [T]> {
+    let mut slides = Vec::new();
+    let mut start = 0;
+
+    for &slide_break in slide_breaks {
+        if slide_break > start && slide_break < verses.len() {
+            slides.push(&verses[start..slide_break]);
+            start = slide_break;
+        }
+    }
+
+    if start < verses.len() {
+        slides.push(&verses[start..]);
+    }
+
+    slides
+}
+
+/// Normalizuje pomocí lineární transformace multiplikátor textu o hodnotě `value` tak,
+/// aby platilo:
+/// ```rust
+/// assert_eq!(normalize_text_multiplier(0), TEXT_SIZE_MULTIPLIER_MIN);
+/// assert_eq!(normalize_text_multiplier(255), TEXT_SIZE_MULTIPLIER_MAX);
+/// assert_eq!(normalize_text_multiplier(TEXT_SIZE_MULTIPLIER_DEFAULT_U8), TEXT_SIZE_MULTIPLIER_DEFAULT);
+/// ```
+pub fn normalize_text_multiplier(value: u8) -> f32 {
+    let value: f32 = value.into();
+
+    let min: f32 = u8::MIN.into();
+    let max: f32 = u8::MAX.into();
+
+    let zero_to_one = (value - min) / max;
+
+    zero_to_one * (TEXT_SIZE_MULTIPLIER_MAX - TEXT_SIZE_MULTIPLIER_MIN) + TEXT_SIZE_MULTIPLIER_MIN
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::config::PresentationBackground;
+
+    #[test]
+    fn countdown_rounds_up() {
+        assert_eq!(countdown_text(TimeDelta::milliseconds(271_200)), "04:32");
+        assert_eq!(countdown_text(TimeDelta::seconds(272)), "04:32");
+        assert_eq!(countdown_text(TimeDelta::milliseconds(1)), "00:01");
+        assert_eq!(countdown_text(TimeDelta::seconds(-5)), "00:00");
+        assert_eq!(countdown_text(TimeDelta::seconds(3725)), "1:02:05");
+    }
+
+    #[test]
+    fn default_breaks_follow_verses_per_slide() {
+        assert_eq!(default_slide_breaks(5, 2), vec![2, 4]);
+        assert_eq!(default_slide_breaks(4, 2), vec![2]);
+        assert_eq!(default_slide_breaks(1, 2), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn split_at_custom_breaks() {
+        let verses = [1, 2, 3, 4, 5];
+
+        assert_eq!(
+            split_at_breaks(&verses, &[1, 4]),
+            vec![&[1][..], &[2, 3, 4][..], &[5][..]]
+        );
+        assert_eq!(split_at_breaks(&verses, &[]), vec![&verses[..]]);
+        assert_eq!(
+            split_at_breaks(&verses, &[0, 3, 3, 9]),
+            vec![&[1, 2, 3][..], &[4, 5][..]],
+            "Zlomy na začátku, duplicitní a mimo rozsah nesmí vytvořit prázdný slajd"
+        );
+    }
+
+    #[test]
+    fn rechunk_only_automatic_passages() {
+        use ekkles_data::bible::indexing::Book;
+
+        let passage = |item_index, verses: &[u8]| {
+            let index = VerseIndex::try_new(Book::Genesis, 1, 1).unwrap();
+            Slide::Passage(PassageSlide::new(
+                String::from("ČEP"),
+                index,
+                index,
+                verses
+                    .iter()
+                    .map(|&verse| (verse, verse.to_string()))
+                    .collect(),
+                item_index,
+                PresentationTheme::default(),
+            ))
+        };
+        let verse_counts = |slides: &[Slide]| -> Vec<usize> {
+            slides
+                .iter()
+                .map(|slide| match slide {
+                    Slide::Passage(slide) => slide.verses.len(),
+                    _ => 0,
+                })
+                .collect()
+        };
+        let slides = vec![
+            passage(0, &[1, 2]),
+            passage(0, &[3, 4]),
+            passage(0, &[5]),
+            passage(1, &[1]),
+            passage(1, &[2, 3]),
+        ];
+
+        let rechunked = rechunk_passages(slides, &[None, Some(vec![1])], 3);
+
+        assert_eq!(
+            verse_counts(&rechunked),
+            vec![3, 2, 1, 2],
+            "Pasáž s ručními zlomy se dělí podle nich, ne po počtu veršů na slajdu"
+        );
+    }
+
+    #[test]
+    fn verse_by_verse_and_back() {
+        use ekkles_data::bible::indexing::Book;
+
+        let index = VerseIndex::try_new(Book::Psalms, 136, 1).unwrap();
+        let passage = |verses: &[u8]| {
+            Slide::Passage(PassageSlide::new(
+                String::from("ČEP"),
+                index,
+                index,
+                verses
+                    .iter()
+                    .map(|&verse| (verse, verse.to_string()))
+                    .collect(),
+                0,
+                PresentationTheme::default(),
+            ))
+        };
+        let verse_counts = |slides: &[Slide]| -> Vec<usize> {
+            slides
+                .iter()
+                .map(|slide| match slide {
+                    Slide::Passage(slide) => slide.verses.len(),
+                    _ => 0,
+                })
+                .collect()
+        };
+        let slide_breaks = [Some(vec![3])];
+
+        let per_verse = rechunk_passages(vec![passage(&[1, 2, 3]), passage(&[4, 5])], &[], 1);
+        assert_eq!(verse_counts(&per_verse), vec![1, 1, 1, 1, 1]);
+
+        let restored = rechunk_passages(per_verse, &slide_breaks, 2);
+        assert_eq!(
+            verse_counts(&restored),
+            vec![3, 2],
+            "Po vypnutí promítání po verších se musí obnovit ruční zlomy"
+        );
+    }
+
+    #[test]
+    fn heading_stays_on_first_passage_slide() {
+        use ekkles_data::bible::indexing::Book;
+
+        let index = VerseIndex::try_new(Book::Psalms, 23, 1).unwrap();
+        let first = PassageSlide {
+            heading: Some(String::from("Žalm Davidův.")),
+            ..PassageSlide::new(
+                String::from("ČEP"),
+                index,
+                index,
+                (1..=6).map(|verse| (verse, verse.to_string())).collect(),
+                0,
+                PresentationTheme::default(),
+            )
+        };
+
+        let slides = rechunk_passages(vec![Slide::Passage(first)], &[None], 2);
+        let headings: Vec<Option<&str>> = slides.iter().map(Slide::heading).collect();
+
+        assert_eq!(headings, vec![Some("Žalm Davidův."), None, None]);
+    }
+
+    #[test]
+    fn long_song_parts_are_split_evenly() {
+        let content = (1..=12)
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let pieces = split_song_part(&content, Some(8));
+        assert_eq!(pieces.len(), 2);
+        assert_eq!(pieces[0].lines().count(), 6);
+        assert_eq!(pieces[1].lines().last(), Some("12"));
+
+        assert_eq!(split_song_part(&content, Some(12)), vec![content.clone()]);
+        assert_eq!(split_song_part(&content, None), vec![content.clone()]);
+        assert_eq!(
+            split_into_pieces("a\nb", 3),
+            vec![String::from("a"), String::from("b"), String::new()],
+            "Kratší překlad musí mít stejný počet kusů jako originál"
+        );
+    }
+
+    #[test]
+    fn continued_song_part_is_marked() {
+        let slide = |part_piece| {
+            Slide::Song(SongSlide {
+                part_piece,
+                ..SongSlide::new(
+                    String::from("Haleluja"),
+                    None,
+                    String::from("V1"),
+                    String::from("Haleluja"),
+                    None,
+                    0,
+                    PresentationTheme::default(),
+                )
+            })
+        };
+
+        assert_eq!(
+            slide(Some((1, 2))).texts(LanguageMode::Original).0,
+            format!("Haleluja\n{CONTINUATION_MARK}")
+        );
+        assert_eq!(
+            slide(Some((2, 2))).texts(LanguageMode::Original).0,
+            "Haleluja"
+        );
+        assert_eq!(slide(None).texts(LanguageMode::Original).0, "Haleluja");
+    }
+
+    #[test]
+    fn custom_text_has_no_footer() {
+        let slide = Slide::Text(TextSlide {
+            title: String::from("Oznámení"),
+            content: String::from("Po bohoslužbě je kafe"),
+            item_index: 0,
+            theme: PresentationTheme::default(),
+        });
+
+        assert_eq!(
+            slide.texts(LanguageMode::Bilingual),
+            (String::from("Po bohoslužbě je kafe"), String::new()),
+            "Název textu vidí pouze obsluha"
+        );
+    }
+
+    #[test]
+    fn background_override_replaces_background_image() {
+        let slide = Slide::Text(TextSlide {
+            title: String::from("Oznámení"),
+            content: String::from("Po bohoslužbě je kafe"),
+            item_index: 0,
+            theme: PresentationTheme {
+                background_image: Some(PresentationBackground {
+                    media_id: 1,
+                    handle: image::Handle::from_bytes(Vec::new()),
+                    dimming: 40,
+                }),
+                ..PresentationTheme::default()
+            },
+        });
+
+        let style = SlideStyle::new(&slide, 1.0, &SlideOverride::default());
+        assert!(style.theme.background_image.is_some());
+
+        let style = SlideStyle::new(
+            &slide,
+            1.0,
+            &SlideOverride {
+                background_color: Some([0x20, 0x40, 0x60]),
+                ..SlideOverride::default()
+            },
+        );
+        assert_eq!(style.theme.background_color, [0x20, 0x40, 0x60]);
+        assert_eq!(style.theme.background_image, None);
+    }
+
+    #[test]
+    fn song_texts_by_language() {
+        let slide = |translation| {
+            Slide::Song(SongSlide::new(
+                String::from("Amazing Grace"),
+                None,
+                String::from("V1"),
+                String::from("Amazing grace"),
+                translation,
+                0,
+                PresentationTheme::default(),
+            ))
+        };
+        let translated = slide(Some(SongSlideTranslation {
+            title: String::from("Úžasná milost"),
+            content: Some(String::from("Milost Boží")),
+        }));
+        let untranslated = slide(None);
+
+        assert_eq!(
+            translated.texts(LanguageMode::Translation),
+            (String::from("Milost Boží"), String::from("Úžasná milost"))
+        );
+        assert_eq!(
+            translated.texts(LanguageMode::Bilingual),
+            (
+                String::from("Amazing grace\n\nMilost Boží"),
+                String::from("Amazing Grace / Úžasná milost")
+            )
+        );
+        assert_eq!(
+            untranslated.texts(LanguageMode::Bilingual),
+            untranslated.texts(LanguageMode::Original),
+            "Píseň bez překladu se promítá vždy v originále"
+        );
+    }
+}
@@ -0,0 +1,630 @@
+//! Obrazovka pro vytvoření nové písně z vloženého prostého textu. Text se heuristicky
+//! rozdělí na části a navrhne se pořadí (viz [`ekkles_data::song_text`]), obojí lze
+//! před uložením upravit. Uložená píseň se rovnou vloží do playlistu.
+//!
+//! Text lze také stáhnout ze stránky na některém ze schválených webů
+//! (viz [`crate::config::Settings::lyrics_sources`]), takovou píseň lze uložit až poté,
+//! co uživatel potvrdí, že má licenci k jejímu promítání. V offline režimu
+//! (viz [`crate::network`]) se stahování nenabízí.
+//!
+//! Text lze také rozpoznat z naskenované stránky zpěvníku (obrázek nebo PDF) pomocí OCR
+//! (viz [`ekkles_data::song_ocr`]), rozpoznaný text se rozdělí na části stejně jako vložený.
+//!
+//! Vložený text i části písně se kontrolují na překlepy (viz [`ekkles_data::spellcheck`]),
+//! slovníky se načítají ze složek [`crate::config::Config::dictionaries_paths`]. Překlepy
+//! jsou v editorech zvýrazněné a lze je opravit podle návrhů nebo ignorovat.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context;
+use ekkles_data::{
+    playlist::PlaylistMetadata,
+    song_ocr::{self, OCR_EXTENSIONS},
+    song_text::{ProposedSong, lyrics_from_html},
+    spellcheck::{self, DEFAULT_LANGUAGES, SpellChecker},
+};
+use iced::{
+    Alignment, Element, Length, Task,
+    widget::{
+        self, button, checkbox, column, container, row, scrollable, text, text_editor, text_input,
+    },
+};
+use log::{debug, info, trace, warn};
+
+use crate::{
+    Ekkles, Screen,
+    components::spell_highlight::{SpellHighlighter, SpellSettings},
+    network,
+    song_picker::SongPicker,
+};
+
+/// Oddělovač tagů v textovém vstupu pořadí částí
+const ORDER_SEPARATOR: &str = " ";
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    TitleChanged(String),
+    AuthorChanged(String),
+    RawTextEdited(text_editor::Action),
+    UrlChanged(String),
+    /// Stáhne stránku se slovy z adresy v textovém vstupu
+    FetchLyrics,
+    /// Stránka na dané adrese (první String) byla stažena, obsahuje dané HTML (druhý String)
+    LyricsFetched(String, String),
+    FetchFailed(String),
+    /// Vybere naskenovanou stránku zpěvníku (obrázek nebo PDF) a rozpozná její text pomocí OCR
+    RecognizeScan,
+    /// Text skenu ze složky (PathBuf) byl rozpoznán (String)
+    ScanRecognized(PathBuf, String),
+    RecognitionFailed(String),
+    ScanDialogCancelled,
+    LicenseConfirmed(bool),
+    /// Rozdělí vložený text na části a navrhne pořadí (přepíše dosavadní návrh)
+    ProposeParts,
+    /// Změna tagu části na daném indexu
+    PartTagChanged(usize, String),
+    /// Úprava slov části na daném indexu
+    PartEdited(usize, text_editor::Action),
+    RemovePart(usize),
+    OrderChanged(String),
+    SaveSong,
+    /// Píseň byla uložena pod daným ID
+    SongSaved(i64),
+    SaveFailed(String),
+    ReturnToPicker,
+    /// Slovníky pro kontrolu pravopisu byly načteny
+    DictionariesLoaded(Arc<SpellChecker>),
+    DictionariesFailed(String),
+    /// Výběr překlepu k opravě, zobrazí se návrhy oprav
+    SelectMisspelling(String),
+    /// Nahradí vybraný překlep daným slovem ve vloženém textu i ve všech částech
+    ReplaceMisspelling(String),
+    /// Vybraný překlep se přestane zvýrazňovat (do opuštění obrazovky)
+    IgnoreMisspelling,
+}
+
+impl From<Message> for crate::Message {
+    fn from(value: Message) -> Self {
+        crate::Message::SongFromText(value)
+    }
+}
+
+#[derive(Debug)]
+pub struct SongFromText {
+    /// Výběr písně, do kterého se lze vrátit beze změny
+    picker: SongPicker,
+    title: String,
+    author: String,
+    /// Vložený text písně
+    raw_text: text_editor::Content,
+    /// Adresa stránky se slovy, kterou zadal uživatel
+    url: String,
+    /// Adresa, ze které byla stažena aktuální slova, `None` pokud byla vložena ručně
+    source_url: Option<String>,
+    /// Uživatel potvrdil, že má licenci k promítání stažené písně
+    license_confirmed: bool,
+    /// Právě se rozpoznává text skenu
+    recognizing: bool,
+    /// Navržené (a případně upravené) části písně jako dvojice (tag, slova)
+    parts: Vec<(String, text_editor::Content)>,
+    /// Pořadí částí, tagy oddělené mezerou
+    order: String,
+    err_msg: String,
+    /// Kontrola pravopisu (bez ní dokud se nenačtou slovníky) a ignorovaná slova
+    spelling: SpellSettings,
+    /// Chyba při načítání slovníků, kontrola pravopisu pak není k dispozici
+    spelling_error: Option<String>,
+    /// Překlepy ve vloženém textu a v částech (bez ignorovaných) v pořadí prvního výskytu
+    misspelled: Vec<String>,
+    /// Překlep vybraný k opravě spolu s návrhy oprav
+    selected_misspelling: Option<(String, Vec<String>)>,
+}
+
+impl SongFromText {
+    pub fn new(picker: SongPicker) -> Self {
+        Self {
+            picker,
+            title: String::new(),
+            author: String::new(),
+            raw_text: text_editor::Content::new(),
+            url: String::new(),
+            source_url: None,
+            license_confirmed: false,
+            recognizing: false,
+            parts: Vec::new(),
+            order: String::new(),
+            err_msg: String::new(),
+            spelling: SpellSettings::default(),
+            spelling_error: None,
+            misspelled: Vec::new(),
+            selected_misspelling: None,
+        }
+    }
+
+    /// Na pozadí načte slovníky pro kontrolu pravopisu ze složek `dirs`
+    pub fn load_dictionaries(dirs: Vec<PathBuf>) -> Task<crate::Message> {
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || SpellChecker::load(&dirs, &DEFAULT_LANGUAGES))
+                    .await
+                    .context("Načítání slovníků spadlo")?
+            },
+            |res| match res {
+                Ok(checker) => Message::DictionariesLoaded(Arc::new(checker)).into(),
+                Err(e) => Message::DictionariesFailed(format!("{:#}", e)).into(),
+            },
+        )
+    }
+
+    /// Vrátí playlist, do kterého se vybírá
+    pub fn get_playlist(&self) -> &PlaylistMetadata {
+        self.picker.get_playlist()
+    }
+
+    /// Sestaví návrh písně z aktuálního (upraveného) stavu formuláře
+    fn proposed_song(&self) -> ProposedSong {
+        ProposedSong {
+            parts: self
+                .parts
+                .iter()
+                .map(|(tag, lyrics)| (tag.trim().to_string(), lyrics.text().trim().to_string()))
+                .collect(),
+            order: self
+                .order
+                .split(ORDER_SEPARATOR)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+
+    /// Znovu vyhledá překlepy ve vloženém textu a ve všech částech
+    fn refresh_misspelled(&mut self) {
+        let Some(checker) = &self.spelling.checker else {
+            return;
+        };
+
+        let texts = std::iter::once(self.raw_text.text())
+            .chain(self.parts.iter().map(|(_, lyrics)| lyrics.text()));
+        let mut misspelled = Vec::new();
+        for text in texts {
+            for word in checker.misspelled_words(&text) {
+                if !misspelled.contains(&word) && !self.spelling.ignored.contains(&word) {
+                    misspelled.push(word);
+                }
+            }
+        }
+
+        self.misspelled = misspelled;
+    }
+
+    /// Píseň lze uložit, pokud má nějaké části a případně byla potvrzena licence ke stažené písni
+    fn can_save(&self) -> bool {
+        !self.parts.is_empty() && (self.source_url.is_none() || self.license_confirmed)
+    }
+
+    /// V offline režimu (`offline`) se nenabízí stažení slov ze stránky
+    pub fn view(&self, offline: bool) -> Element<Message> {
+        let fetch: Element<Message> = if offline {
+            text("Offline režim: slova nelze stáhnout, vlož je ručně").into()
+        } else {
+            row![
+                text_input("Adresa stránky se slovy (jen schválené weby)", &self.url)
+                    .on_input(Message::UrlChanged)
+                    .on_submit(Message::FetchLyrics),
+                button("Stáhnout")
+                    .on_press_maybe((!self.url.trim().is_empty()).then_some(Message::FetchLyrics)),
+            ]
+            .spacing(10)
+            .into()
+        };
+
+        let scan = if self.recognizing {
+            row![text("Rozpoznávám text skenu...")]
+        } else {
+            row![
+                button("Rozpoznat ze skenu (OCR)")
+                    .style(widget::button::secondary)
+                    .on_press(Message::RecognizeScan)
+            ]
+        };
+
+        let raw_text = column![
+            fetch,
+            scan,
+            text("Text písně"),
+            text_editor(&self.raw_text)
+                .placeholder("Sem vlož slova písně, části odděl prázdným řádkem")
+                .on_action(Message::RawTextEdited)
+                .highlight_with::<SpellHighlighter>(self.spelling.clone(), SpellHighlighter::format)
+                .height(Length::Fill),
+            button("Rozdělit na části")
+                .on_press(Message::ProposeParts)
+                .width(Length::Fill),
+            self.view_spelling(),
+        ]
+        .spacing(10)
+        .width(Length::FillPortion(1));
+
+        let parts = self.parts.iter().enumerate().map(|(index, (tag, lyrics))| {
+            column![
+                row![
+                    text_input("Tag", tag)
+                        .on_input(move |tag| Message::PartTagChanged(index, tag))
+                        .width(Length::Fixed(100.0)),
+                    button("Odebrat")
+                        .style(widget::button::danger)
+                        .on_press(Message::RemovePart(index)),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+                text_editor(lyrics)
+                    .on_action(move |action| Message::PartEdited(index, action))
+                    .highlight_with::<SpellHighlighter>(
+                        self.spelling.clone(),
+                        SpellHighlighter::format
+                    ),
+            ]
+            .spacing(5)
+            .into()
+        });
+
+        let mut proposal = column![
+            text("Části písně"),
+            scrollable(container(column(parts).spacing(15)).padding(10)).height(Length::Fill),
+            text_input("Pořadí částí (tagy oddělené mezerou)", &self.order)
+                .on_input(Message::OrderChanged),
+            text(&self.err_msg).style(text::danger),
+        ]
+        .spacing(10)
+        .width(Length::FillPortion(1));
+
+        if let Some(url) = &self.source_url {
+            proposal = proposal.push(
+                checkbox(
+                    format!("Mám licenci k promítání této písně (slova z {url})"),
+                    self.license_confirmed,
+                )
+                .on_toggle(Message::LicenseConfirmed),
+            );
+        }
+
+        let proposal = proposal.push(
+            button("Uložit píseň")
+                .style(widget::button::success)
+                .on_press_maybe(self.can_save().then_some(Message::SaveSong))
+                .width(Length::Fill),
+        );
+
+        column![
+            row![
+                button("Zpět").on_press(Message::ReturnToPicker),
+                text_input("Název písně", &self.title).on_input(Message::TitleChanged),
+                text_input("Autor (nepovinný)", &self.author).on_input(Message::AuthorChanged),
+            ]
+            .spacing(20),
+            row![raw_text, proposal].spacing(20),
+        ]
+        .spacing(20)
+        .padding(20)
+        .into()
+    }
+
+    /// Seznam překlepů a návrhy oprav vybraného překlepu
+    fn view_spelling(&self) -> Element<Message> {
+        if let Some(err) = &self.spelling_error {
+            return text(format!("Kontrola pravopisu není k dispozici: {err}")).into();
+        }
+        if self.spelling.checker.is_none() {
+            return text("Načítám slovníky pro kontrolu pravopisu...").into();
+        }
+        if self.misspelled.is_empty() {
+            return text("Žádné překlepy").into();
+        }
+
+        let misspelled = self.misspelled.iter().map(|word| {
+            button(text(word))
+                .style(widget::button::secondary)
+                .on_press(Message::SelectMisspelling(word.clone()))
+                .into()
+        });
+        let mut spelling =
+            column![text("Možné překlepy"), row(misspelled).spacing(5).wrap()].spacing(5);
+
+        if let Some((word, suggestions)) = &self.selected_misspelling {
+            let suggestions = suggestions
+                .iter()
+                .map(|suggestion| {
+                    button(text(suggestion))
+                        .on_press(Message::ReplaceMisspelling(suggestion.clone()))
+                        .into()
+                })
+                .chain(std::iter::once(
+                    button("Ignorovat")
+                        .style(widget::button::secondary)
+                        .on_press(Message::IgnoreMisspelling)
+                        .into(),
+                ));
+            spelling = spelling
+                .push(text(format!("Opravit {word} na:")))
+                .push(row(suggestions).spacing(5).wrap());
+        }
+
+        spelling.into()
+    }
+
+    /// Update funkce pro novou píseň z textu. Pokud je tato funkce zavolána nad jinou
+    /// obrazovkou než [`Screen::SongFromText`], zpanikaří.
+    pub fn update(state: &mut Ekkles, msg: Message) -> Task<crate::Message> {
+        let form = match &mut state.screen {
+            Screen::SongFromText(form) => form,
+            screen => panic!(
+                "Update pro novou píseň z textu zavolán nad jinou obrazovkou {:?}",
+                screen
+            ),
+        };
+
+        match msg {
+            Message::TitleChanged(title) => {
+                form.title = title;
+                Task::none()
+            }
+            Message::AuthorChanged(author) => {
+                form.author = author;
+                Task::none()
+            }
+            Message::RawTextEdited(action) => {
+                let is_edit = action.is_edit();
+                form.raw_text.perform(action);
+                if is_edit {
+                    form.refresh_misspelled();
+                }
+                Task::none()
+            }
+            Message::UrlChanged(url) => {
+                form.url = url;
+                Task::none()
+            }
+            Message::FetchLyrics => {
+                let offline = state.config.is_offline();
+                let url = match network::lyrics_url(&form.url, &state.config.settings, offline) {
+                    Ok(url) => url,
+                    Err(e) => {
+                        form.err_msg = format!("{:#}", e);
+                        return Task::none();
+                    }
+                };
+
+                debug!("Stahuji slova ze stránky {url}");
+                form.err_msg.clear();
+                let source = url.clone();
+                Task::perform(network::fetch_page(url, offline), move |res| match res {
+                    Ok(html) => Message::LyricsFetched(source, html).into(),
+                    Err(e) => Message::FetchFailed(format!("{:#}", e)).into(),
+                })
+            }
+            Message::LyricsFetched(url, html) => {
+                debug!("Stránka {url} stažena, vytahuji z ní slova");
+                form.raw_text = text_editor::Content::with_text(&lyrics_from_html(&html));
+                form.source_url = Some(url);
+                form.license_confirmed = false;
+                Task::done(Message::ProposeParts.into())
+            }
+            Message::FetchFailed(err) => {
+                debug!("Stránku se slovy se nepodařilo stáhnout: {err}");
+                form.err_msg = format!("Stránku nelze stáhnout: {err}");
+                Task::none()
+            }
+            Message::RecognizeScan => {
+                // Dialog se otevře ve složce, ze které se písně importovaly naposledy
+                let last_import_dir = state.config.profiles.recent.last_import_dir();
+                form.err_msg.clear();
+                form.recognizing = true;
+                Task::perform(
+                    async move {
+                        let mut dialog = rfd::AsyncFileDialog::new()
+                            .add_filter("Sken (obrázek nebo PDF)", &OCR_EXTENSIONS);
+                        if let Some(dir) = last_import_dir {
+                            dialog = dialog.set_directory(dir);
+                        }
+                        let Some(file) = dialog.pick_file().await else {
+                            return Ok(None);
+                        };
+
+                        let path = file.path().to_path_buf();
+                        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                        let text =
+                            tokio::task::spawn_blocking(move || song_ocr::recognize_file(&path))
+                                .await
+                                .context("Rozpoznávání textu spadlo")??;
+
+                        Ok(Some((dir, text)))
+                    },
+                    |res: anyhow::Result<Option<(PathBuf, String)>>| match res {
+                        Ok(Some((dir, text))) => Message::ScanRecognized(dir, text).into(),
+                        Ok(None) => Message::ScanDialogCancelled.into(),
+                        Err(e) => Message::RecognitionFailed(format!("{:#}", e)).into(),
+                    },
+                )
+            }
+            Message::ScanRecognized(dir, recognized) => {
+                debug!("Rozpoznán text skenu ({} znaků)", recognized.len());
+                form.recognizing = false;
+                state.config.profiles.recent.add_import_dir(&dir);
+                if let Err(e) = state.config.save_settings() {
+                    warn!("Nelze uložit naposledy použitou složku importu: {:?}", e);
+                }
+                if recognized.is_empty() {
+                    form.err_msg = String::from("Ve skenu nebyl rozpoznán žádný text");
+                    return Task::none();
+                }
+
+                form.raw_text = text_editor::Content::with_text(&recognized);
+                form.source_url = None;
+                Task::done(Message::ProposeParts.into())
+            }
+            Message::RecognitionFailed(err) => {
+                debug!("Text skenu se nepodařilo rozpoznat: {err}");
+                form.recognizing = false;
+                form.err_msg = format!("Text skenu nelze rozpoznat: {err}");
+                Task::none()
+            }
+            Message::ScanDialogCancelled => {
+                debug!("Dialog pro výběr skenu zrušen");
+                form.recognizing = false;
+                Task::none()
+            }
+            Message::LicenseConfirmed(confirmed) => {
+                form.license_confirmed = confirmed;
+                Task::none()
+            }
+            Message::ProposeParts => {
+                let proposed = ProposedSong::from_text(&form.raw_text.text());
+                debug!(
+                    "Navrženo {} částí s pořadím {:?}",
+                    proposed.parts.len(),
+                    proposed.order
+                );
+                form.parts = proposed
+                    .parts
+                    .into_iter()
+                    .map(|(tag, lyrics)| (tag, text_editor::Content::with_text(&lyrics)))
+                    .collect();
+                form.order = proposed.order.join(ORDER_SEPARATOR);
+                form.err_msg.clear();
+                form.refresh_misspelled();
+                Task::none()
+            }
+            Message::PartTagChanged(index, tag) => {
+                trace!("Měním tag části {index} na {tag}");
+                // Mezera je oddělovač tagů v pořadí, v tagu být nesmí
+                form.parts[index].0 = tag.replace(' ', "");
+                Task::none()
+            }
+            Message::PartEdited(index, action) => {
+                let is_edit = action.is_edit();
+                form.parts[index].1.perform(action);
+                if is_edit {
+                    form.refresh_misspelled();
+                }
+                Task::none()
+            }
+            Message::RemovePart(index) => {
+                debug!("Odebírám část {}", form.parts[index].0);
+                form.parts.remove(index);
+                form.refresh_misspelled();
+                Task::none()
+            }
+            Message::OrderChanged(order) => {
+                form.order = order;
+                Task::none()
+            }
+            Message::SaveSong => {
+                let author = Some(form.author.trim().to_string()).filter(|a| !a.is_empty());
+                let song = match form
+                    .proposed_song()
+                    .into_song(form.title.trim().to_string(), author)
+                {
+                    Ok(song) => song,
+                    Err(e) => {
+                        form.err_msg = format!("{:#}", e);
+                        return Task::none();
+                    }
+                };
+
+                if let Some(url) = &form.source_url {
+                    info!(
+                        "Ukládám píseň {} staženou z {url}, uživatel potvrdil licenci",
+                        song.title
+                    );
+                }
+                debug!("Ukládám novou píseň {}", song.title);
+                let db = state.db.clone();
+                Task::perform(async move { song.save_to_db(&db).await }, |res| match res {
+                    Ok(id) => Message::SongSaved(id).into(),
+                    Err(e) => Message::SaveFailed(format!("{:#}", e)).into(),
+                })
+            }
+            Message::SongSaved(id) => {
+                debug!("Nová píseň uložena s id {id}, vkládám ji do playlistu");
+                let screen =
+                    std::mem::replace(&mut state.screen, Screen::ErrorOccurred(String::new()));
+                state.screen = match screen {
+                    Screen::SongFromText(form) => Screen::PickSong(form.picker),
+                    screen => screen,
+                };
+                Task::done(crate::song_picker::Message::SongPicked(id).into())
+            }
+            Message::SaveFailed(err) => {
+                debug!("Píseň se nepodařilo uložit: {err}");
+                form.err_msg = format!("Píseň nelze uložit: {err}");
+                Task::none()
+            }
+            Message::ReturnToPicker => {
+                debug!("Vracím se na výběr písně");
+                let screen =
+                    std::mem::replace(&mut state.screen, Screen::ErrorOccurred(String::new()));
+                state.screen = match screen {
+                    Screen::SongFromText(form) => Screen::PickSong(form.picker),
+                    screen => screen,
+                };
+                Task::none()
+            }
+            Message::DictionariesLoaded(checker) => {
+                debug!("Načteny slovníky pro kontrolu pravopisu: {:?}", checker);
+                form.spelling.checker = Some(checker);
+                form.spelling_error = None;
+                form.refresh_misspelled();
+                Task::none()
+            }
+            Message::DictionariesFailed(err) => {
+                warn!("Nelze načíst slovníky pro kontrolu pravopisu: {err}");
+                form.spelling_error = Some(err);
+                Task::none()
+            }
+            Message::SelectMisspelling(word) => {
+                let suggestions = form
+                    .spelling
+                    .checker
+                    .as_ref()
+                    .map(|checker| checker.suggestions(&word))
+                    .unwrap_or_default();
+                trace!("Návrhy oprav slova {word}: {:?}", suggestions);
+                form.selected_misspelling = Some((word, suggestions));
+                Task::none()
+            }
+            Message::ReplaceMisspelling(replacement) => {
+                let Some((word, _)) = form.selected_misspelling.take() else {
+                    return Task::none();
+                };
+
+                debug!("Opravuji slovo {word} na {replacement}");
+                form.raw_text = text_editor::Content::with_text(&spellcheck::replace_word(
+                    &form.raw_text.text(),
+                    &word,
+                    &replacement,
+                ));
+                for (_, lyrics) in &mut form.parts {
+                    *lyrics = text_editor::Content::with_text(&spellcheck::replace_word(
+                        &lyrics.text(),
+                        &word,
+                        &replacement,
+                    ));
+                }
+                form.refresh_misspelled();
+                Task::none()
+            }
+            Message::IgnoreMisspelling => {
+                if let Some((word, _)) = form.selected_misspelling.take() {
+                    debug!("Ignoruji slovo {word} při kontrole pravopisu");
+                    form.spelling.ignored.push(word);
+                    form.refresh_misspelled();
+                }
+                Task::none()
+            }
+        }
+    }
+}
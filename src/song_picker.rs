@@ -1,16 +1,36 @@
-use std::fmt::Display;
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
-use anyhow::Result;
-use ekkles_data::{Song, playlist::PlaylistMetadata};
+use anyhow::{Context, Result};
+use ekkles_data::{
+    PartTag, Song,
+    author::get_authors_from_db,
+    license::append_license_badges,
+    playlist::{PlaylistItemMetadata, PlaylistMetadata},
+    song_arrangement::SongArrangement,
+    song_diff::{ImportCheck, ImportConflict, check_import},
+    songbook::append_songbook_numbers,
+};
 use iced::{
-    Alignment, Color, Element, Length, Task,
+    Alignment, Color, Element, Length, Padding, Task,
     task::Handle,
-    widget::{Container, Space, button, column, combo_box, container, row, text},
+    widget::{
+        Container, Space, button, column, combo_box, container, pick_list, row, scrollable, text,
+    },
 };
-use log::debug;
+use log::{debug, warn};
 use sqlx::{Sqlite, pool::PoolConnection};
 
-use crate::{Ekkles, Screen, playlist_editor::PlaylistEditor};
+use crate::{
+    Ekkles, Screen, import_review::ImportReview, playlist_editor::PlaylistEditor, plugins::Plugins,
+    song_from_text::SongFromText,
+};
+
+/// Počet písní v seznamech naposledy použitých a nejpoužívanějších písní
+const SHORTCUT_SONGS: usize = 8;
 
 #[derive(Debug, Clone)]
 pub struct SongPickerItem {
@@ -30,14 +50,103 @@ impl SongPickerItem {
     }
 }
 
+/// Autor z registru autorů, podle kterého lze filtrovat písně
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorPickerItem {
+    id: i64,
+    name: String,
+}
+
+impl Display for AuthorPickerItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Písně pro rychlý výběr, než uživatel začne psát (většina bohoslužeb používá stále
+/// stejný repertoár). Písně, které již v playlistu jsou, se nenabízejí.
+#[derive(Debug, Clone, Default)]
+pub struct SongShortcuts {
+    /// Naposledy použité nebo zařazené do playlistu, viz [`Song::recently_used()`]
+    recent: Vec<SongPickerItem>,
+    /// Nejčastěji použité, viz [`Song::most_used()`]
+    most_used: Vec<SongPickerItem>,
+}
+
+impl SongShortcuts {
+    /// Načte nejvýše [`SHORTCUT_SONGS`] písní do každého seznamu, vynechá písně z `playlist`
+    async fn load(playlist: &PlaylistMetadata, conn: &mut PoolConnection<Sqlite>) -> Result<Self> {
+        let in_playlist: HashSet<i64> = playlist
+            .get_items()
+            .filter_map(|item| match item {
+                PlaylistItemMetadata::Song(id) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        // Načteme více písní, aby jich po vynechání těch z playlistu zbylo dost
+        let limit = (SHORTCUT_SONGS + in_playlist.len()) as u32;
+        let pick = |songs: Vec<(i64, String)>| -> Vec<SongPickerItem> {
+            songs
+                .into_iter()
+                .filter(|(id, _)| !in_playlist.contains(id))
+                .take(SHORTCUT_SONGS)
+                .map(|(id, name)| SongPickerItem::new(id, name))
+                .collect()
+        };
+
+        Ok(Self {
+            recent: pick(Song::recently_used(limit, conn).await?),
+            most_used: pick(Song::most_used(limit, conn).await?),
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.recent.is_empty() && self.most_used.is_empty()
+    }
+}
+
+/// Píseň v náhledu spolu s jejími pojmenovanými pořadími, viz [`SongArrangement`]
+#[derive(Debug, Clone)]
+pub struct PreviewedSong {
+    id: i64,
+    song: Song,
+    arrangements: Vec<SongArrangement>,
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     LoadSongs,
     SongsLoaded(Vec<SongPickerItem>),
+    AuthorsLoaded(Vec<AuthorPickerItem>),
+    /// Naposledy použité a nejpoužívanější písně pro rychlý výběr, viz [`SongShortcuts`]
+    ShortcutsLoaded(SongShortcuts),
+    /// Změnil se text vyhledávání písně
+    SearchChanged(String),
+    /// Vyfiltruje písně podle daného autora, `None` filtr zruší
+    AuthorFilterChanged(Option<AuthorPickerItem>),
     ReturnToEditor,
     SongPicked(i64),
+    /// Píseň s daným ID byla vybrána v daném pojmenovaném pořadí, v playlistu bude
+    /// mít toto pořadí jako vlastní
+    SongPickedWithArrangement(i64, Vec<PartTag>),
+    /// Načte píseň s daným ID, kterou se nahradí nahrazovaná položka (případně s daným
+    /// pojmenovaným pořadím), viz [`PlaylistMetadata::replace_song`]
+    LoadReplacement(i64, Option<Vec<PartTag>>),
+    /// Píseň, kterou se nahradí nahrazovaná položka, byla načtena
+    ReplacementLoaded(i64, Song, Option<Vec<PartTag>>),
     LoadPreview(SongPickerItem),
-    PreviewLoaded(Song),
+    PreviewLoaded(PreviewedSong),
+    /// Přejde na vytvoření nové písně z vloženého textu
+    NewSongFromText,
+    /// Naimportuje písně importérem pluginu na daném indexu, viz [`crate::plugins`]
+    ImportWithPlugin(usize),
+    /// Písně byly naimportovány (složka importovaného souboru, počet nových, počet
+    /// přeskočených již existujících se stejnými slovy, konflikty k rozhodnutí)
+    SongsImported(PathBuf, usize, usize, Vec<ImportConflict>),
+    /// Konflikty importu byly rozhodnuty (viz [`ImportReview`]), zpráva pro uživatele
+    ConflictsResolved(String),
+    ImportFailed(String),
+    ImportDialogCancelled,
 }
 
 impl From<Message> for crate::Message {
@@ -51,7 +160,7 @@ impl From<Message> for crate::Message {
 enum Preview {
     Empty,
     Loading(Handle),
-    Loaded(Song),
+    Loaded(PreviewedSong),
 }
 
 impl Preview {
@@ -64,8 +173,8 @@ impl Preview {
     /// - Pokud se Preview již načítá, původní task je ukončen (abort) a začne se načítat nový
     pub fn load(
         &mut self,
-        fut: impl Future<Output = Result<Song>> + Send + 'static,
-    ) -> Task<Result<Song>> {
+        fut: impl Future<Output = Result<PreviewedSong>> + Send + 'static,
+    ) -> Task<Result<PreviewedSong>> {
         if let Preview::Loading(handle) = self {
             handle.abort();
         }
@@ -78,7 +187,7 @@ impl Preview {
     }
 
     /// Označí preview za načtené.
-    pub fn loaded(&mut self, song: Song) {
+    pub fn loaded(&mut self, song: PreviewedSong) {
         if let Preview::Loading(_) = self {
             *self = Preview::Loaded(song);
         } else {
@@ -97,6 +206,21 @@ pub struct SongPicker {
     songs: Option<combo_box::State<SongPickerItem>>,
     playlist: PlaylistMetadata,
     preview: Preview,
+    /// Autoři z registru, podle kterých lze filtrovat
+    authors: Vec<AuthorPickerItem>,
+    /// Vybraný autor, nabízejí se pouze jeho písně. `None` pokud se nefiltruje.
+    author_filter: Option<AuthorPickerItem>,
+    /// Index nahrazované písně v playlistu, vybraná píseň ji nahradí na místě.
+    /// `None` pokud se vybírá nová píseň.
+    replaced_item: Option<usize>,
+    /// Pozice v playlistu, na kterou se vloží nová píseň, `None` = na konec playlistu
+    insert_position: Option<usize>,
+    /// Výsledek posledního importu písní pluginem
+    import_msg: Result<String, String>,
+    /// Písně pro rychlý výběr, zobrazují se, dokud uživatel nezačne psát
+    shortcuts: SongShortcuts,
+    /// Text vyhledávání písně
+    search: String,
 }
 
 impl SongPicker {
@@ -105,13 +229,82 @@ impl SongPicker {
             songs: None,
             playlist,
             preview: Preview::Empty,
+            authors: Vec::new(),
+            author_filter: None,
+            replaced_item: None,
+            insert_position: None,
+            import_msg: Ok(String::new()),
+            shortcuts: SongShortcuts::default(),
+            search: String::new(),
         }
     }
 
-    pub async fn load_song_list(conn: &mut PoolConnection<Sqlite>) -> Result<Vec<SongPickerItem>> {
-        Song::get_available_from_db(conn).await.map(|vec| {
+    /// Vytvoří picker, jehož vybraná píseň se vloží za položku na indexu `selected_index`
+    /// playlistu `playlist`, pokud není žádná položka vybraná, vloží se na konec.
+    pub fn insert_after(playlist: PlaylistMetadata, selected_index: Option<usize>) -> Self {
+        Self {
+            insert_position: selected_index.map(|index| index + 1),
+            ..Self::new(playlist)
+        }
+    }
+
+    /// Vytvoří picker, jehož vybraná píseň nahradí píseň na indexu `item_index` playlistu `playlist`
+    pub fn replace_item(playlist: PlaylistMetadata, item_index: usize) -> Self {
+        Self {
+            replaced_item: Some(item_index),
+            ..Self::new(playlist)
+        }
+    }
+
+    /// Vrátí playlist, do kterého se vybírá
+    pub fn get_playlist(&self) -> &PlaylistMetadata {
+        &self.playlist
+    }
+
+    /// Vloží vybranou píseň `id` do playlistu, vrátí její pozici v playlistu
+    fn insert_song(&mut self, id: i64) -> usize {
+        match self.insert_position {
+            Some(position) => {
+                self.playlist.add_song(id, position);
+                position
+            }
+            None => {
+                self.playlist.push_song(id);
+                self.playlist.get_items().len() - 1
+            }
+        }
+    }
+
+    /// Načte seznam písní, pokud je dán `author_id`, pouze písní tohoto autora. K názvům písní
+    /// jsou připojena jejich čísla ve zpěvnících, aby šlo vyhledávat i podle nich (např. "EZ 452"),
+    /// a pokud je `licensing_mode`, také varování u písní bez licence.
+    pub async fn load_song_list(
+        conn: &mut PoolConnection<Sqlite>,
+        author_id: Option<i64>,
+        licensing_mode: bool,
+    ) -> Result<Vec<SongPickerItem>> {
+        let songs = match author_id {
+            Some(author_id) => Song::get_available_by_author_from_db(author_id, conn).await?,
+            None => Song::get_available_from_db(conn).await?,
+        };
+
+        let mut songs = append_songbook_numbers(songs, conn).await?;
+        if licensing_mode {
+            songs = append_license_badges(songs, conn).await?;
+        }
+
+        Ok(songs
+            .into_iter()
+            .map(|(id, name)| SongPickerItem::new(id, name))
+            .collect())
+    }
+
+    pub async fn load_author_list(
+        conn: &mut PoolConnection<Sqlite>,
+    ) -> Result<Vec<AuthorPickerItem>> {
+        get_authors_from_db(conn).await.map(|vec| {
             vec.into_iter()
-                .map(|(id, name)| SongPickerItem::new(id, name))
+                .map(|(id, name)| AuthorPickerItem { id, name })
                 .collect()
         })
     }
@@ -120,7 +313,7 @@ impl SongPicker {
         self.songs = Some(combo_box::State::new(song_list));
     }
 
-    pub fn view(&self) -> Element<Message> {
+    pub fn view(&self, plugins: &Plugins) -> Element<Message> {
         let picker = self
             .songs
             .as_ref()
@@ -129,28 +322,65 @@ impl SongPicker {
                     combo_box(combo_box_state, "Název písně", None, |item| {
                         Message::SongPicked(item.id)
                     })
+                    .on_input(Message::SearchChanged)
                     .on_option_hovered(Message::LoadPreview),
                 )
             })
             .unwrap_or(container(text("Načítám písně ...")));
 
+        let author_filter = row![
+            pick_list(
+                self.authors.as_slice(),
+                self.author_filter.as_ref(),
+                |author| Message::AuthorFilterChanged(Some(author)),
+            )
+            .placeholder("Všichni autoři")
+            .width(Length::Fill),
+            button("Zrušit").on_press_maybe(
+                self.author_filter
+                    .is_some()
+                    .then_some(Message::AuthorFilterChanged(None))
+            ),
+        ]
+        .spacing(10);
+
         let preview = match &self.preview {
             Preview::Empty => container(Space::new(Length::Shrink, Length::Shrink)),
             Preview::Loading(_) => container(text("Načítám náhled")),
             Preview::Loaded(song) => song_preview(song),
         };
 
+        let importers = plugins.importers().map(|(index, importer)| {
+            button(text(format!("Importovat: {}…", importer.label)))
+                .on_press(Message::ImportWithPlugin(index))
+                .width(Length::Fill)
+                .into()
+        });
+
         Into::<Element<Message>>::into(container(
             row![
                 container(
-                    button("Zpět")
-                        .on_press(Message::ReturnToEditor)
-                        .width(Length::Fill)
+                    column![
+                        column(importers).spacing(10),
+                        match &self.import_msg {
+                            Ok(info) => text(info).style(text::success),
+                            Err(err) => text(err).style(text::danger),
+                        },
+                        button("Nová píseň z textu…")
+                            .on_press(Message::NewSongFromText)
+                            .width(Length::Fill),
+                        button("Zpět")
+                            .on_press(Message::ReturnToEditor)
+                            .width(Length::Fill),
+                    ]
+                    .spacing(10)
                 )
                 .align_bottom(Length::Fill)
                 .width(Length::FillPortion(1))
                 .padding(30),
                 column![
+                    self.view_shortcuts(),
+                    author_filter,
                     picker.align_bottom(Length::FillPortion(6)),
                     preview.height(Length::FillPortion(4))
                 ]
@@ -166,6 +396,20 @@ impl SongPicker {
         .explain(Color::BLACK)
     }
 
+    /// Seznamy naposledy použitých a nejpoužívanějších písní, pouze dokud se nic nehledá
+    fn view_shortcuts(&self) -> Element<Message> {
+        if !self.search.is_empty() || self.shortcuts.is_empty() {
+            return column![].into();
+        }
+
+        row![
+            shortcut_list("Naposledy použité", &self.shortcuts.recent),
+            shortcut_list("Nejpoužívanější", &self.shortcuts.most_used),
+        ]
+        .spacing(20)
+        .into()
+    }
+
     pub fn update(state: &mut Ekkles, message: Message) -> Task<crate::Message> {
         let picker = match &mut state.screen {
             Screen::PickSong(picker) => picker,
@@ -177,24 +421,81 @@ impl SongPicker {
 
         match message {
             Message::LoadSongs => {
-                debug!("Načítám seznam písní");
-                let conn = state.db.acquire();
-                Task::perform(
-                    async {
-                        let mut conn = conn.await?;
-                        SongPicker::load_song_list(&mut conn).await
-                    },
-                    |res| match res {
-                        Ok(songs) => Message::SongsLoaded(songs).into(),
-                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
-                    },
-                )
+                debug!("Načítám seznam písní a autorů");
+                let author_id = picker.author_filter.as_ref().map(|author| author.id);
+                let licensing_mode = state.config.settings.licensing_mode;
+                let songs_conn = state.db.acquire();
+                let authors_conn = state.db.acquire();
+                let shortcuts_conn = state.db.acquire();
+                let playlist = picker.playlist.clone();
+                Task::batch([
+                    Task::perform(
+                        async move {
+                            let mut conn = songs_conn.await?;
+                            SongPicker::load_song_list(&mut conn, author_id, licensing_mode).await
+                        },
+                        |res| match res {
+                            Ok(songs) => Message::SongsLoaded(songs).into(),
+                            Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                        },
+                    ),
+                    Task::perform(
+                        async {
+                            let mut conn = authors_conn.await?;
+                            SongPicker::load_author_list(&mut conn).await
+                        },
+                        |res| match res {
+                            Ok(authors) => Message::AuthorsLoaded(authors).into(),
+                            Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                        },
+                    ),
+                    Task::perform(
+                        async move {
+                            let mut conn = shortcuts_conn.await?;
+                            SongShortcuts::load(&playlist, &mut conn).await
+                        },
+                        // Rychlý výběr není nezbytný, bez něj lze písně stále vyhledat
+                        |res| match res {
+                            Ok(shortcuts) => Message::ShortcutsLoaded(shortcuts).into(),
+                            Err(e) => {
+                                warn!("Nelze načíst písně pro rychlý výběr: {:?}", e);
+                                Message::ShortcutsLoaded(SongShortcuts::default()).into()
+                            }
+                        },
+                    ),
+                ])
             }
             Message::SongsLoaded(song_picker_items) => {
                 debug!("Písně načteny: {:#?}", &song_picker_items);
                 picker.set_song_list(song_picker_items);
+                // Nový seznam písní začíná s prázdným vyhledáváním
+                picker.search.clear();
+                Task::none()
+            }
+            Message::AuthorsLoaded(authors) => {
+                debug!("Načteno {} autorů", authors.len());
+                picker.authors = authors;
                 Task::none()
             }
+            Message::ShortcutsLoaded(shortcuts) => {
+                debug!(
+                    "Načteno {} naposledy použitých a {} nejpoužívanějších písní",
+                    shortcuts.recent.len(),
+                    shortcuts.most_used.len()
+                );
+                picker.shortcuts = shortcuts;
+                Task::none()
+            }
+            Message::SearchChanged(search) => {
+                picker.search = search;
+                Task::none()
+            }
+            Message::AuthorFilterChanged(author) => {
+                debug!("Filtruji písně podle autora {:?}", author);
+                picker.author_filter = author;
+                picker.songs = None;
+                Task::done(Message::LoadSongs.into())
+            }
             Message::ReturnToEditor => {
                 debug!("Vracím se do editoru");
                 state.screen = Screen::EditPlaylist(PlaylistEditor::new(picker.playlist.clone()));
@@ -202,7 +503,54 @@ impl SongPicker {
             }
             Message::SongPicked(id) => {
                 debug!("Byla vybrána píseň s id {id}");
-                picker.playlist.push_song(id);
+                if picker.replaced_item.is_some() {
+                    return Task::done(Message::LoadReplacement(id, None).into());
+                }
+                picker.insert_song(id);
+                Task::done(Message::ReturnToEditor.into())
+            }
+            Message::SongPickedWithArrangement(id, order) => {
+                debug!("Byla vybrána píseň s id {id} v pořadí {order:?}");
+                if picker.replaced_item.is_some() {
+                    return Task::done(Message::LoadReplacement(id, Some(order)).into());
+                }
+                let position = picker.insert_song(id);
+                picker
+                    .playlist
+                    .set_arrangement(position, Some(order))
+                    .expect("Uložené pořadí je neprázdné a položka je píseň");
+                Task::done(Message::ReturnToEditor.into())
+            }
+            Message::LoadReplacement(id, order) => {
+                // Z vlastního pořadí nahrazované položky se musí vypustit části, které
+                // nová píseň nemá, je tedy potřeba ji nejdřív načíst
+                debug!("Načítám píseň s id {id}, kterou se nahradí položka playlistu");
+                let conn = state.db.acquire();
+                Task::perform(
+                    async move {
+                        let mut conn = conn.await?;
+                        Song::load_from_db(id, &mut conn).await
+                    },
+                    move |res| match res {
+                        Ok(song) => Message::ReplacementLoaded(id, song, order).into(),
+                        Err(e) => crate::Message::FatalErrorOccured(format!("{:?}", e)),
+                    },
+                )
+            }
+            Message::ReplacementLoaded(id, song, order) => {
+                let index = picker
+                    .replaced_item
+                    .expect("Píseň se načítá pouze při nahrazování položky");
+                picker
+                    .playlist
+                    .replace_song(index, id, &song)
+                    .expect("Nahrazovaná položka musí být píseň");
+                if let Some(order) = order {
+                    picker
+                        .playlist
+                        .set_arrangement(index, Some(order))
+                        .expect("Uložené pořadí je neprázdné a položka je píseň");
+                }
                 Task::done(Message::ReturnToEditor.into())
             }
             Message::LoadPreview(item) => {
@@ -210,7 +558,11 @@ impl SongPicker {
                 let conn = state.db.acquire();
                 let fut = async move {
                     let mut conn = conn.await?;
-                    Song::load_from_db(item.id, &mut conn).await
+                    Ok(PreviewedSong {
+                        id: item.id,
+                        song: Song::load_from_db(item.id, &mut conn).await?,
+                        arrangements: SongArrangement::load_for_song(item.id, &mut conn).await?,
+                    })
                 };
                 picker.preview.load(fut).map(|res| match res {
                     Ok(song) => Message::PreviewLoaded(song).into(),
@@ -218,14 +570,207 @@ impl SongPicker {
                 })
             }
             Message::PreviewLoaded(song) => {
-                debug!("Načetlo se previw pro píseň {}", song.title);
+                debug!("Načetlo se previw pro píseň {}", song.song.title);
                 picker.preview.loaded(song);
                 Task::none()
             }
+            Message::NewSongFromText => {
+                debug!("Přecházím na vytvoření nové písně z textu");
+                picker.preview.reset();
+                let screen =
+                    std::mem::replace(&mut state.screen, Screen::ErrorOccurred(String::new()));
+                state.screen = match screen {
+                    Screen::PickSong(picker) => Screen::SongFromText(SongFromText::new(picker)),
+                    screen => screen,
+                };
+                SongFromText::load_dictionaries(state.config.dictionaries_paths.clone())
+            }
+            Message::ImportWithPlugin(index) => {
+                let Some(plugin) = state.plugins.get(index) else {
+                    warn!("Plugin s indexem {index} neexistuje");
+                    return Task::none();
+                };
+                let Some(importer) = plugin.manifest().importer.clone() else {
+                    warn!("Plugin {} nemá importér písní", plugin.manifest().name);
+                    return Task::none();
+                };
+                debug!("Importuji písně pluginem {}", plugin.manifest().name);
+                let db = state.db.clone();
+                // Dialog se otevře ve složce, ze které se písně importovaly naposledy
+                let last_import_dir = state.config.profiles.recent.last_import_dir();
+                Task::perform(
+                    async move {
+                        let mut dialog = rfd::AsyncFileDialog::new()
+                            .add_filter(&importer.label, &importer.extensions);
+                        if let Some(dir) = last_import_dir {
+                            dialog = dialog.set_directory(dir);
+                        }
+                        let Some(file) = dialog.pick_file().await else {
+                            return Ok(None);
+                        };
+
+                        let path = file.path().to_path_buf();
+                        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                        let songs = tokio::task::spawn_blocking(move || plugin.import_songs(&path))
+                            .await
+                            .context("Plugin při importu spadl")??;
+
+                        // Písně se stejným názvem, ale jinými slovy, rozhodne uživatel
+                        let (mut imported, mut skipped, mut conflicts) = (0, 0, Vec::new());
+                        for song in songs {
+                            match check_import(song, &db).await? {
+                                ImportCheck::New(song) => {
+                                    song.save_to_db(&db).await?;
+                                    imported += 1;
+                                }
+                                ImportCheck::Identical(_) => skipped += 1,
+                                ImportCheck::Conflict(conflict) => conflicts.push(*conflict),
+                            }
+                        }
+
+                        Ok(Some((dir, imported, skipped, conflicts)))
+                    },
+                    |res: anyhow::Result<Option<(PathBuf, usize, usize, Vec<ImportConflict>)>>| {
+                        match res {
+                            Ok(Some((dir, imported, skipped, conflicts))) => {
+                                Message::SongsImported(dir, imported, skipped, conflicts).into()
+                            }
+                            Ok(None) => Message::ImportDialogCancelled.into(),
+                            Err(e) => Message::ImportFailed(format!("{:#}", e)).into(),
+                        }
+                    },
+                )
+            }
+            Message::SongsImported(dir, imported, skipped, conflicts) => {
+                debug!(
+                    "Naimportováno {imported} písní, přeskočeno {skipped}, konfliktů {}",
+                    conflicts.len()
+                );
+                state.config.profiles.recent.add_import_dir(&dir);
+                if let Err(e) = state.config.save_settings() {
+                    warn!("Nelze uložit naposledy použitou složku importu: {:?}", e);
+                }
+                let summary = format!(
+                    "Naimportováno písní: {imported}, přeskočeno již existujících: {skipped}"
+                );
+                picker.songs = None;
+
+                if conflicts.is_empty() {
+                    picker.import_msg = Ok(summary);
+                    return Task::done(Message::LoadSongs.into());
+                }
+
+                let screen =
+                    std::mem::replace(&mut state.screen, Screen::ErrorOccurred(String::new()));
+                state.screen = match screen {
+                    Screen::PickSong(picker) => {
+                        Screen::ReviewImport(ImportReview::new(picker, conflicts, summary))
+                    }
+                    screen => screen,
+                };
+                Task::none()
+            }
+            Message::ConflictsResolved(msg) => {
+                debug!("Konflikty importu rozhodnuty, načítám znovu písně");
+                picker.import_msg = Ok(msg);
+                picker.songs = None;
+                Task::done(Message::LoadSongs.into())
+            }
+            Message::ImportFailed(err_msg) => {
+                debug!("Import písní pluginem selhal: {err_msg}");
+                picker.import_msg = Err(format!("Písně nelze naimportovat: {err_msg}"));
+                Task::none()
+            }
+            Message::ImportDialogCancelled => {
+                debug!("Dialog pro import písní zrušen");
+                Task::none()
+            }
         }
     }
 }
 
-fn song_preview(song: &Song) -> Container<'static, Message> {
-    todo!()
+/// Seznam písní rychlého výběru s nadpisem `label`, kliknutím se píseň vybere
+fn shortcut_list<'a>(label: &'a str, songs: &'a [SongPickerItem]) -> Element<'a, Message> {
+    let buttons = songs.iter().map(|song| {
+        button(text(&song.name))
+            .style(button::secondary)
+            .on_press(Message::SongPicked(song.id))
+            .width(Length::Fill)
+            .into()
+    });
+
+    column![text(label), column(buttons).spacing(5)]
+        .spacing(5)
+        .width(Length::FillPortion(1))
+        .into()
+}
+
+/// Náhled písně, části v pořadí `order` (včetně opakování) se zvýrazněným tagem,
+/// aby šlo před přidáním ověřit, že jde o správnou píseň. Pokud má píseň pojmenovaná
+/// pořadí, lze ji přidat v některém z nich.
+fn song_preview(previewed: &PreviewedSong) -> Container<'static, Message> {
+    let song = &previewed.song;
+    let parts = song.slide_sequence().into_iter().map(|(tag, content)| {
+        column![
+            text(tag.clone()).style(text::primary),
+            match content {
+                Some(content) => text(content.to_string()),
+                None => text("Část neexistuje!").style(text::danger),
+            },
+        ]
+        .spacing(2)
+        .into()
+    });
+
+    let header = match &song.author {
+        Some(author) => format!("{} ({})", song.title, author),
+        None => song.title.clone(),
+    };
+
+    let arrangements = previewed.arrangements.iter().map(|arrangement| {
+        button(text(format!("Přidat v pořadí {arrangement}")))
+            .on_press(Message::SongPickedWithArrangement(
+                previewed.id,
+                arrangement.order.clone(),
+            ))
+            .into()
+    });
+
+    container(
+        column![
+            text(header).size(20),
+            row(arrangements).spacing(10),
+            scrollable(
+                // Padding, aby scrollbar nepřekrýval text
+                container(column(parts).spacing(10)).padding(Padding::ZERO.right(10))
+            )
+            .height(Length::Fill),
+        ]
+        .spacing(10),
+    )
+    .padding(10)
+}
+
+#[cfg(test)]
+mod tests {
+    use ekkles_data::test_util;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn song_list_from_db() {
+        let pool = test_util::setup_db_with_test_data().await;
+        let mut conn = pool.acquire().await.unwrap();
+
+        let mut names: Vec<_> = SongPicker::load_song_list(&mut conn, None, false)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|item| item.name)
+            .collect();
+        names.sort();
+
+        assert_eq!(names, vec!["Píseň1", "Píseň2"]);
+    }
 }
@@ -0,0 +1,153 @@
+//! Cache náhledů slajdů.
+//!
+//! Náhled vzniká ze snímku prezentačního okna ve chvíli, kdy je na něm slajd poprvé
+//! vidět (vykreslení mimo okno iced neumí), zmenší se a uloží jako PNG do paměti
+//! i na disk. Klíčem je hash obsahu slajdu a jeho stylu, takže se stejný slajd
+//! (i v jiném playlistu) nemusí generovat znovu a po změně motivu/úprav se náhled
+//! vygeneruje nový.
+//!
+//! Klíč je spočítán pomocí [`DefaultHasher`], jehož výstup se může mezi verzemi Rustu
+//! změnit, v tom případě se náhledy na disku pouze přestanou používat a vygenerují se znovu.
+
+use std::{
+    collections::HashMap,
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow};
+use iced::{Alignment, widget::image::Handle, window::Screenshot};
+use image::{ImageFormat, RgbaImage, imageops};
+use log::{debug, trace};
+
+use crate::slide_render::{Slide, SlideStyle};
+
+/// Šířka náhledu v pixelech, výška se dopočítá podle poměru stran výstupu
+pub const THUMBNAIL_WIDTH: u32 = 240;
+
+/// Náhledy slajdů uložené v paměti, případně i na disku ve složce `dir`
+#[derive(Debug, Clone, Default)]
+pub struct ThumbnailCache {
+    /// Složka s náhledy na disku, pokud je `None`, náhledy se drží pouze v paměti
+    dir: Option<PathBuf>,
+    thumbnails: HashMap<u64, Handle>,
+}
+
+impl ThumbnailCache {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            thumbnails: HashMap::new(),
+        }
+    }
+
+    pub fn get_dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// Vrátí náhled s klíčem `key`, pokud již byl vygenerován/načten
+    pub fn get(&self, key: u64) -> Option<&Handle> {
+        self.thumbnails.get(&key)
+    }
+
+    pub fn contains(&self, key: u64) -> bool {
+        self.thumbnails.contains_key(&key)
+    }
+
+    /// Vloží do cache náhled s klíčem `key` zakódovaný jako PNG
+    pub fn insert(&mut self, key: u64, png: Vec<u8>) {
+        self.thumbnails.insert(key, Handle::from_bytes(png));
+    }
+}
+
+/// Spočítá klíč náhledu slajdu `slide` vykresleného se stylem `style`
+pub fn thumbnail_key(slide: &Slide, style: &SlideStyle) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    slide.texts(style.language).hash(&mut hasher);
+    slide.heading().hash(&mut hasher);
+    if let Slide::Image(image_slide) = slide {
+        image_slide.media_id.hash(&mut hasher);
+    }
+    style.theme.text_color.hash(&mut hasher);
+    style.theme.background_color.hash(&mut hasher);
+    style
+        .theme
+        .background_image
+        .as_ref()
+        .map(|background| (background.media_id, background.dimming))
+        .hash(&mut hasher);
+    style.text_size_multiplier.to_bits().hash(&mut hasher);
+    match style.alignment {
+        Alignment::Start => 0u8,
+        Alignment::Center => 1,
+        Alignment::End => 2,
+    }
+    .hash(&mut hasher);
+
+    hasher.finish()
+}
+
+/// Cesta k náhledu s klíčem `key` ve složce `dir`
+fn thumbnail_path(dir: &Path, key: u64) -> PathBuf {
+    dir.join(format!("{key:016x}.png"))
+}
+
+/// Načte z disku ze složky `dir` ty náhledy z `keys`, které tam jsou. Vrací dvojice
+/// (klíč, PNG), chybějící náhledy přeskočí.
+pub async fn load_from_disk(dir: PathBuf, keys: Vec<u64>) -> Vec<(u64, Vec<u8>)> {
+    tokio::task::spawn_blocking(move || {
+        keys.into_iter()
+            .filter_map(|key| {
+                fs::read(thumbnail_path(&dir, key))
+                    .ok()
+                    .map(|png| (key, png))
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Zmenší snímek prezentačního okna `screenshot` na náhled, zakóduje jej jako PNG,
+/// a pokud je zadaná složka `dir`, uloží jej tam pod klíčem `key`. Vrací PNG náhledu.
+pub async fn encode_and_store(
+    dir: Option<PathBuf>,
+    key: u64,
+    screenshot: Screenshot,
+) -> Result<Vec<u8>> {
+    tokio::task::spawn_blocking(move || {
+        let image = RgbaImage::from_raw(
+            screenshot.size.width,
+            screenshot.size.height,
+            screenshot.as_ref().to_vec(),
+        )
+        .ok_or(anyhow!("Snímek okna nemá očekávanou velikost"))?;
+
+        let height = (THUMBNAIL_WIDTH as u64 * screenshot.size.height as u64
+            / screenshot.size.width.max(1) as u64)
+            .max(1) as u32;
+        let thumbnail = imageops::thumbnail(&image, THUMBNAIL_WIDTH, height);
+
+        let mut png = Vec::new();
+        thumbnail
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .context("Nelze zakódovat náhled slajdu")?;
+
+        if let Some(dir) = dir {
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("Nelze vytvořit složku pro náhledy {}", dir.display()))?;
+            let path = thumbnail_path(&dir, key);
+            trace!("Ukládám náhled do {}", path.display());
+            fs::write(&path, &png)
+                .with_context(|| format!("Nelze uložit náhled do {}", path.display()))?;
+        }
+
+        debug!("Vygenerován náhled slajdu {key:016x}");
+        Ok(png)
+    })
+    .await
+    .context("Úloha generování náhledu selhala")?
+}
@@ -1,5 +1,9 @@
-use crate::{Screen, bible_picker, playlist_editor, presenter};
-use crate::{pick_playlist, song_picker};
+use crate::{
+    Screen, bible_picker, diagnostics, import_review, passage_comparer, pin_prompt,
+    playlist_editor, presenter, profile_picker, reading_plan_picker, reports, service_summary,
+    settings, shutdown, slide_break_editor, song_from_text,
+};
+use crate::{network::mqtt::MqttEvent, pick_playlist, song_picker};
 use iced::Task;
 use log::{debug, trace, warn};
 
@@ -9,6 +13,19 @@ impl Ekkles {
     pub fn update(&mut self, msg: Message) -> Task<Message> {
         trace!("Přišla zpráva: {:?}", msg);
 
+        if self.config.guest_mode && !msg.allowed_in_guest_mode() {
+            warn!("V režimu hosta nelze upravovat, ignoruji zprávu {:?}", msg);
+            return Task::none();
+        }
+
+        if msg.requires_pin()
+            && self.config.settings.pin.is_some()
+            && !std::mem::take(&mut self.pin_unlocked)
+        {
+            debug!("Zpráva {:?} vyžaduje PIN, ptám se uživatele", msg);
+            return pin_prompt::prompt(self, msg);
+        }
+
         match (msg, &mut self.screen) {
             (Message::WindowOpened(id), Screen::PickPlaylist(_icker)) => {
                 if id == self.main_window_id {
@@ -20,36 +37,89 @@ impl Ekkles {
                     todo!("Jiná okna nejsou implementována")
                 }
             }
-            (Message::WindowClosed(id), _) if id == self.main_window_id => {
-                debug!("Hlavní okno zavřeno, ukončuji aplikaci");
-                iced::exit()
+            (Message::WindowOpened(_), Screen::PickProfile(_)) => {
+                debug!("Hlavní okno otevřeno, čekám na výběr profilu");
+                Task::none()
+            }
+            (Message::WindowCloseRequested(id), _) if id == self.main_window_id => {
+                debug!("Požadavek na zavření hlavního okna, spouštím ukončení");
+                Task::done(shutdown::Message::Requested.into())
             }
             (Message::WindowClosed(id), Screen::Presenter(presenter))
-                if presenter
-                    .get_window_id()
-                    .is_some_and(|pres_id| id == pres_id) =>
+                if !presenter.is_closing()
+                    && presenter
+                        .get_window_id()
+                        .is_some_and(|pres_id| id == pres_id) =>
             {
-                debug!("Zavřeno okno prezentace");
-                Task::done(crate::presenter::Message::PresentationWindowClosed.into())
+                debug!("Okno prezentace zavřeno zvenčí");
+                Task::done(crate::presenter::Message::PresentationWindowLost.into())
             }
             (Message::PlaylistPicker(msg), Screen::PickPlaylist(_)) => {
                 pick_playlist::update(self, msg)
             }
+            (Message::ProfilePicker(msg), Screen::PickProfile(_)) => {
+                profile_picker::ProfilePicker::update(self, msg)
+            }
             (Message::PlaylistEditor(msg), Screen::EditPlaylist(_)) => {
                 playlist_editor::PlaylistEditor::update(self, msg)
             }
             (Message::SongPicker(msg), Screen::PickSong(_)) => {
                 song_picker::SongPicker::update(self, msg)
             }
+            (Message::ImportReview(msg), Screen::ReviewImport(_)) => {
+                import_review::ImportReview::update(self, msg)
+            }
+            (Message::SongFromText(msg), Screen::SongFromText(_)) => {
+                song_from_text::SongFromText::update(self, msg)
+            }
             (Message::BiblePicker(msg), Screen::PickBible(_)) => {
                 bible_picker::BiblePicker::update(self, msg)
             }
+            (Message::PassageComparer(msg), Screen::ComparePassages(_)) => {
+                passage_comparer::PassageComparer::update(self, msg)
+            }
+            (Message::SlideBreakEditor(msg), Screen::EditSlideBreaks(_)) => {
+                slide_break_editor::SlideBreakEditor::update(self, msg)
+            }
+            (Message::ReadingPlanPicker(msg), Screen::PickReadingPlan(_)) => {
+                reading_plan_picker::ReadingPlanPicker::update(self, msg)
+            }
             (Message::Presenter(msg), Screen::Presenter(_)) => {
                 presenter::Presenter::update(self, msg)
             }
+            (Message::Settings(msg), Screen::Settings(_)) => {
+                settings::SettingsEditor::update(self, msg)
+            }
+            (Message::ReportExporter(msg), Screen::Reports(_)) => {
+                reports::ReportExporter::update(self, msg)
+            }
+            (Message::Diagnostics(msg), Screen::Diagnostics(_)) => {
+                diagnostics::DiagnosticsPanel::update(self, msg)
+            }
+            (Message::ServiceSummary(msg), Screen::ServiceSummary(_)) => {
+                service_summary::ServiceSummary::update(self, msg)
+            }
+            (Message::PinPrompt(msg), Screen::ConfirmPin(_)) => pin_prompt::update(self, msg),
+            (Message::Shutdown(msg), _) => shutdown::update(self, msg),
+            (Message::Mqtt(MqttEvent::Connected(publisher)), _) => {
+                debug!("Spojení s MQTT brokerem navázáno");
+                self.mqtt = Some(publisher);
+                Task::none()
+            }
+            (Message::Mqtt(MqttEvent::Control(step)), Screen::Presenter(presenter)) => {
+                debug!("Ovládání prezentace z MQTT: {step}");
+                match presenter.macro_step_to_message(step) {
+                    Some(msg) => Task::done(msg.into()),
+                    None => Task::none(),
+                }
+            }
+            (Message::Mqtt(MqttEvent::Control(step)), _) => {
+                debug!("Neprobíhá prezentace, ignoruji ovládání z MQTT: {step}");
+                Task::none()
+            }
             (Message::ShouldQuit, _) => {
-                debug!("Ukončuji aplikaci");
-                iced::exit()
+                debug!("Vyžádáno ukončení aplikace");
+                Task::done(shutdown::Message::Requested.into())
             }
             (Message::FatalErrorOccured(e), _) => {
                 self.screen = Screen::ErrorOccurred(e);
@@ -65,3 +135,57 @@ impl Ekkles {
         }
     }
 }
+
+impl Message {
+    /// Zda je zpráva povolená v režimu hosta (viz [`crate::config::Config::guest_mode`]),
+    /// kdy lze playlisty pouze prezentovat. Obrazovky, na které se host nedostane
+    /// (úpravy playlistu, nastavení), nepovolují nic.
+    fn allowed_in_guest_mode(&self) -> bool {
+        match self {
+            Message::ShouldQuit
+            | Message::WindowOpened(_)
+            | Message::WindowCloseRequested(_)
+            | Message::WindowClosed(_)
+            | Message::Shutdown(_)
+            | Message::Mqtt(_)
+            | Message::FatalErrorOccured(_) => true,
+            Message::PlaylistPicker(msg) => msg.allowed_in_guest_mode(),
+            Message::Presenter(msg) => msg.allowed_in_guest_mode(),
+            Message::ProfilePicker(msg) => msg.allowed_in_guest_mode(),
+            Message::PlaylistEditor(msg) => msg.allowed_in_guest_mode(),
+            Message::ServiceSummary(msg) => msg.allowed_in_guest_mode(),
+            Message::SongPicker(_)
+            | Message::ImportReview(_)
+            | Message::SongFromText(_)
+            | Message::BiblePicker(_)
+            | Message::PassageComparer(_)
+            | Message::SlideBreakEditor(_)
+            | Message::ReadingPlanPicker(_)
+            | Message::Settings(_)
+            | Message::ReportExporter(_)
+            | Message::Diagnostics(_)
+            | Message::PinPrompt(_) => false,
+        }
+    }
+
+    /// Zda zpráva spouští chráněnou operaci (mazání playlistů a profilů, vstup do nastavení),
+    /// před kterou je potřeba zadat PIN, pokud je nastaven (viz [`crate::config::Settings::pin`]).
+    fn requires_pin(&self) -> bool {
+        match self {
+            Message::PlaylistPicker(msg) => matches!(
+                msg,
+                pick_playlist::Message::TopButtonSettings
+                    | pick_playlist::Message::DeletePlaylist(_)
+            ),
+            Message::PlaylistEditor(msg) => matches!(
+                msg,
+                playlist_editor::Message::TopButtonsSettings
+                    | playlist_editor::Message::DeletePlaylist
+            ),
+            Message::ProfilePicker(msg) => {
+                matches!(msg, profile_picker::Message::DeleteProfile(_))
+            }
+            _ => false,
+        }
+    }
+}